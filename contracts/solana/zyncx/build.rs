@@ -0,0 +1,21 @@
+use std::process::Command;
+
+// Exposes the git commit of the source tree to the program as ZYNCX_GIT_HASH.
+// Verifiable builds run without a .git directory, so the value can also be
+// supplied through the environment.
+fn main() {
+    println!("cargo:rerun-if-env-changed=ZYNCX_GIT_HASH");
+    println!("cargo:rerun-if-changed=../../../.git/HEAD");
+
+    let git_hash = std::env::var("ZYNCX_GIT_HASH").ok().unwrap_or_else(|| {
+        Command::new("git")
+            .args(["rev-parse", "HEAD"])
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+            .unwrap_or_default()
+    });
+
+    println!("cargo:rustc-env=ZYNCX_GIT_HASH={}", git_hash);
+}
@@ -6,6 +6,7 @@ use anchor_lang::solana_program::{
 use anchor_spl::token::{Token, TokenAccount};
 
 use crate::errors::ZyncxError;
+use crate::instructions::treasury::pay_from_vault_treasury;
 use super::types::{SwapRoute, SwapResult};
 
 /// Jupiter V6 Program ID (same on mainnet, devnet, and testnet)
@@ -36,6 +37,33 @@ pub fn execute_jupiter_swap<'info>(
     remaining_accounts: &[AccountInfo<'info>],
     vault_key: &Pubkey,
     treasury_bump: u8,
+) -> Result<SwapResult> {
+    // PDA signer seeds for vault treasury
+    let treasury_seeds = &[
+        b"vault_treasury",
+        vault_key.as_ref(),
+        &[treasury_bump],
+    ];
+
+    execute_jupiter_swap_signed(
+        vault_treasury,
+        destination,
+        jupiter_program,
+        swap_data,
+        remaining_accounts,
+        &[&treasury_seeds[..]],
+    )
+}
+
+/// Execute a Jupiter swap from any program PDA holding the source funds;
+/// `signer_seeds` are the source PDA's seeds
+pub fn execute_jupiter_swap_signed<'info>(
+    source: &AccountInfo<'info>,
+    destination: &AccountInfo<'info>,
+    jupiter_program: &AccountInfo<'info>,
+    swap_data: Vec<u8>,
+    remaining_accounts: &[AccountInfo<'info>],
+    signer_seeds: &[&[&[u8]]],
 ) -> Result<SwapResult> {
     // Verify Jupiter program ID
     require!(
@@ -46,9 +74,9 @@ pub fn execute_jupiter_swap<'info>(
     // Build account metas for Jupiter instruction
     let mut account_metas: Vec<AccountMeta> = Vec::with_capacity(remaining_accounts.len() + 2);
     
-    // Add vault treasury as source (signer via PDA)
+    // Add source (signer via PDA)
     account_metas.push(AccountMeta {
-        pubkey: vault_treasury.key(),
+        pubkey: source.key(),
         is_signer: true,
         is_writable: true,
     });
@@ -76,18 +104,10 @@ pub fn execute_jupiter_swap<'info>(
         data: swap_data,
     };
 
-    // PDA signer seeds for vault treasury
-    let treasury_seeds = &[
-        b"vault_treasury",
-        vault_key.as_ref(),
-        &[treasury_bump],
-    ];
-    let signer_seeds = &[&treasury_seeds[..]];
-
     // Collect all account infos for CPI
     let mut account_infos: Vec<AccountInfo> = Vec::with_capacity(remaining_accounts.len() + 3);
     account_infos.push(jupiter_program.clone());
-    account_infos.push(vault_treasury.clone());
+    account_infos.push(source.clone());
     account_infos.push(destination.clone());
     account_infos.extend(remaining_accounts.iter().cloned());
 
@@ -109,17 +129,24 @@ pub fn execute_jupiter_swap<'info>(
 pub fn transfer_sol_from_treasury<'info>(
     vault_treasury: &AccountInfo<'info>,
     recipient: &AccountInfo<'info>,
+    system_program: &Program<'info, System>,
     amount: u64,
-    _vault_key: &Pubkey,
-    _treasury_bump: u8,
+    vault_key: &Pubkey,
+    treasury_bump: u8,
 ) -> Result<()> {
     // Verify sufficient balance
     let treasury_balance = vault_treasury.lamports();
     require!(treasury_balance >= amount, ZyncxError::InsufficientFunds);
 
-    // Transfer SOL
-    **vault_treasury.try_borrow_mut_lamports()? -= amount;
-    **recipient.try_borrow_mut_lamports()? += amount;
+    // The treasury is system-owned: transfer with the PDA signing
+    pay_from_vault_treasury(
+        system_program,
+        vault_treasury,
+        recipient,
+        vault_key,
+        treasury_bump,
+        amount,
+    )?;
 
     msg!("Transferred {} lamports from treasury to recipient", amount);
     Ok(())
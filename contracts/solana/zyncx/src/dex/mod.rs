@@ -1,5 +1,7 @@
 pub mod jupiter;
 pub mod types;
+pub mod wsol;
 
 pub use jupiter::*;
 pub use types::*;
+pub use wsol::*;
@@ -56,3 +56,13 @@ pub const WSOL_MINT: Pubkey = Pubkey::new_from_array([
 pub fn is_native_sol(mint: &Pubkey) -> bool {
     *mint == NATIVE_SOL_MINT || *mint == WSOL_MINT
 }
+
+/// Mint a vault for `mint` is keyed by. WSOL shares the native SOL vault so
+/// SOL liquidity and commitments are not split across two vaults.
+pub fn canonical_asset_mint(mint: &Pubkey) -> Pubkey {
+    if is_native_sol(mint) {
+        NATIVE_SOL_MINT
+    } else {
+        *mint
+    }
+}
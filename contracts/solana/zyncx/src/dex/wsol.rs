@@ -0,0 +1,136 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token::{self, CloseAccount, InitializeAccount3, SyncNative, TokenAccount};
+
+/// Seed prefix of a native vault's temporary WSOL account:
+/// seeds = [b"treasury_wsol", vault]
+pub const TREASURY_WSOL_SEED: &[u8] = b"treasury_wsol";
+
+/// Create the treasury's temporary WSOL account at its PDA, owned by the
+/// treasury. The treasury pays the rent, which `close_treasury_wsol` returns.
+///
+/// # Arguments
+/// * `treasury_seeds` - Signer seeds of the vault treasury PDA
+/// * `wsol_seeds` - Signer seeds of the WSOL account PDA
+pub fn create_treasury_wsol<'info>(
+    vault_treasury: &AccountInfo<'info>,
+    wsol_account: &AccountInfo<'info>,
+    wsol_mint: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    treasury_seeds: &[&[u8]],
+    wsol_seeds: &[&[u8]],
+) -> Result<()> {
+    let space = TokenAccount::LEN;
+    let rent = Rent::get()?.minimum_balance(space);
+    let current = wsol_account.lamports();
+
+    // Tolerate lamports sent to the address ahead of time
+    if current == 0 {
+        system_program::create_account(
+            CpiContext::new_with_signer(
+                system_program.clone(),
+                system_program::CreateAccount {
+                    from: vault_treasury.clone(),
+                    to: wsol_account.clone(),
+                },
+                &[treasury_seeds, wsol_seeds],
+            ),
+            rent,
+            space as u64,
+            token_program.key,
+        )?;
+    } else {
+        if current < rent {
+            system_program::transfer(
+                CpiContext::new_with_signer(
+                    system_program.clone(),
+                    system_program::Transfer {
+                        from: vault_treasury.clone(),
+                        to: wsol_account.clone(),
+                    },
+                    &[treasury_seeds],
+                ),
+                rent - current,
+            )?;
+        }
+        system_program::allocate(
+            CpiContext::new_with_signer(
+                system_program.clone(),
+                system_program::Allocate {
+                    account_to_allocate: wsol_account.clone(),
+                },
+                &[wsol_seeds],
+            ),
+            space as u64,
+        )?;
+        system_program::assign(
+            CpiContext::new_with_signer(
+                system_program.clone(),
+                system_program::Assign {
+                    account_to_assign: wsol_account.clone(),
+                },
+                &[wsol_seeds],
+            ),
+            token_program.key,
+        )?;
+    }
+
+    token::initialize_account3(CpiContext::new(
+        token_program.clone(),
+        InitializeAccount3 {
+            account: wsol_account.clone(),
+            mint: wsol_mint.clone(),
+            authority: vault_treasury.clone(),
+        },
+    ))
+}
+
+/// Move `amount` lamports from the treasury into its WSOL account and sync
+/// the token balance
+pub fn wrap_treasury_sol<'info>(
+    vault_treasury: &AccountInfo<'info>,
+    wsol_account: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    amount: u64,
+    treasury_seeds: &[&[u8]],
+) -> Result<()> {
+    system_program::transfer(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            system_program::Transfer {
+                from: vault_treasury.clone(),
+                to: wsol_account.clone(),
+            },
+            &[treasury_seeds],
+        ),
+        amount,
+    )?;
+
+    token::sync_native(CpiContext::new(
+        token_program.clone(),
+        SyncNative {
+            account: wsol_account.clone(),
+        },
+    ))
+}
+
+/// Close the treasury's WSOL account, unwrapping whatever the route left in
+/// it back to the treasury along with the rent
+pub fn close_treasury_wsol<'info>(
+    vault_treasury: &AccountInfo<'info>,
+    wsol_account: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    treasury_seeds: &[&[u8]],
+) -> Result<()> {
+    token::close_account(CpiContext::new_with_signer(
+        token_program.clone(),
+        CloseAccount {
+            account: wsol_account.clone(),
+            destination: vault_treasury.clone(),
+            authority: vault_treasury.clone(),
+        },
+        &[treasury_seeds],
+    ))
+}
@@ -47,6 +47,18 @@ pub enum ZyncxError {
     #[msg("Root not found in history")]
     RootNotFound,
 
+    #[msg("Merkle tree is not the vault's active tree")]
+    InactiveMerkleTree,
+
+    #[msg("Merkle tree does not belong to this vault")]
+    MerkleTreeVaultMismatch,
+
+    #[msg("Active merkle tree has not reached the rotation threshold")]
+    TreeBelowRotationThreshold,
+
+    #[msg("Invalid merkle tree configuration")]
+    InvalidTreeConfig,
+
     #[msg("Poseidon hash computation failed")]
     PoseidonHashFailed,
 
@@ -74,6 +86,99 @@ pub enum ZyncxError {
     #[msg("DEX swap execution failed")]
     SwapExecutionFailed,
 
+    #[msg("Encrypted note exceeds maximum size")]
+    EncryptedNoteTooLarge,
+
+    #[msg("Viewing key ciphertext exceeds maximum size")]
+    ViewingCiphertextTooLarge,
+
+    #[msg("A registered viewing key requires a disclosure ciphertext")]
+    MissingViewingCiphertext,
+
+    #[msg("Amount is not one of the vault's fixed denominations")]
+    InvalidDenomination,
+
+    #[msg("Invalid denomination configuration")]
+    InvalidDenominationConfig,
+
+    #[msg("Exclusion root must be non-zero")]
+    InvalidExclusionRoot,
+
+    #[msg("Exclusion proof supplied without exclusion root or verifier")]
+    MissingExclusionRoot,
+
+    #[msg("Exclusion proof verification failed")]
+    InvalidExclusionProof,
+
+    #[msg("Recipient is on the blocklist")]
+    RecipientBlocked,
+
+    #[msg("Recipient is already on the blocklist")]
+    AlreadyBlocked,
+
+    #[msg("Recipient is not on the blocklist")]
+    NotBlocked,
+
+    #[msg("Blocklist is full")]
+    BlocklistFull,
+
+    #[msg("Invalid fee configuration")]
+    InvalidFeeConfig,
+
+    #[msg("Invalid config timelock")]
+    InvalidTimelock,
+
+    #[msg("Config change timelock has not elapsed")]
+    TimelockNotElapsed,
+
+    #[msg("Change requires the config timelock")]
+    TimelockRequired,
+
+    #[msg("Vault is paused")]
+    VaultPaused,
+
+    #[msg("Recipient account does not match the proof-bound recipient")]
+    RecipientMismatch,
+
+    #[msg("Deposit exceeds the vault's per-transaction limit")]
+    DepositTooLarge,
+
+    #[msg("Deposit would exceed the vault's total deposit cap")]
+    VaultDepositCapReached,
+
+    #[msg("Deposit exceeds the vault's per-epoch deposit limit")]
+    DepositRateLimited,
+
+    #[msg("Account is already fee exempt")]
+    AlreadyExempt,
+
+    #[msg("Account is not fee exempt")]
+    NotExempt,
+
+    #[msg("Fee exemption list is full")]
+    FeeExemptionsFull,
+
+    #[msg("Too many orders for one crank")]
+    CrankBatchTooLarge,
+
+    #[msg("Invalid DCA configuration")]
+    InvalidDcaConfig,
+
+    #[msg("DCA interval has not elapsed")]
+    DcaNotDue,
+
+    #[msg("Vault is being sunset and no longer accepts this operation")]
+    VaultSunset,
+
+    #[msg("Invalid sunset policy or vault is not eligible for this sunset step")]
+    InvalidSunsetState,
+
+    #[msg("Referral slot is out of range or not held by the signer")]
+    InvalidReferrer,
+
+    #[msg("Referral epoch is not open, not current or not yet revealed")]
+    InvalidReferralEpoch,
+
     // ========================================================================
     // Arcium / Confidential Computation Errors
     // ========================================================================
@@ -113,4 +218,193 @@ pub enum ZyncxError {
 
     #[msg("Price condition not met")]
     PriceConditionNotMet,
+
+    #[msg("Position is not active")]
+    InactivePosition,
+
+    #[msg("Withdrawal fee, relayer tip or disclosure differs from what the proof authorizes")]
+    ExtDataMismatch,
+
+    #[msg("A balance check for this threshold is already queued")]
+    BalanceCheckPending,
+
+    #[msg("Auction batch is empty, too large or mixes markets")]
+    InvalidAuctionBatch,
+
+    #[msg("Sealed order is not open")]
+    SealedOrderNotOpen,
+
+    #[msg("Orders are not on opposite sides of the same market")]
+    OrdersNotMatchable,
+
+    #[msg("Insurance claim needs 1 to 4 payouts, each non-zero")]
+    InvalidInsuranceClaim,
+
+    #[msg("This role already voted on the claim")]
+    AlreadyVoted,
+
+    #[msg("Insurance claim is no longer open")]
+    InsuranceClaimClosed,
+
+    #[msg("Insurance claim has not been approved by a quorum of roles")]
+    InsuranceClaimNotApproved,
+
+    #[msg("Invalid TWAP configuration")]
+    InvalidTwapConfig,
+
+    #[msg("TWAP interval has not elapsed")]
+    TwapNotDue,
+
+    #[msg("Treasury split needs a hot target of 1-10000 bps and a refill threshold at or below it")]
+    InvalidTreasurySplit,
+
+    #[msg("Treasury policy account required for this change")]
+    MissingTreasuryPolicy,
+
+    #[msg("Cold spend is no longer pending")]
+    ColdSpendClosed,
+
+    #[msg("Cold spend has not been approved by a quorum of role holders")]
+    ColdSpendNotApproved,
+
+    #[msg("Hot treasury is above its refill threshold or was refilled recently")]
+    RefillNotDue,
+
+    #[msg("Hot treasury is already at its target share")]
+    TreasuryBalanced,
+
+    #[msg("Price age must be 1s to 1h and confidence 1 to 10000 bps")]
+    InvalidPriceGuards,
+
+    #[msg("Allowance policy needs a non-zero limit and epoch and 1-16 non-zero viewing keys")]
+    InvalidAllowancePolicy,
+
+    #[msg("Withdrawal exceeds the allowance policy's limit for this epoch")]
+    AllowanceExceeded,
+
+    #[msg("Swap amount exceeds the price-impact limited child order size")]
+    PriceImpactExceeded,
+
+    #[msg("No price feed is registered for this token")]
+    PriceFeedNotRegistered,
+
+    #[msg("Price feed registry is full")]
+    PriceFeedRegistryFull,
+
+    #[msg("Note backup needs a non-empty encrypted note")]
+    MissingEncryptedNote,
+
+    #[msg("Market maker is not registered or not active")]
+    MarketMakerNotActive,
+
+    #[msg("Market maker has open RFQ quotes")]
+    MarketMakerHasOpenQuotes,
+
+    #[msg("RFQ size must be 1, 2 or 5 times a power of ten")]
+    InvalidRfqSize,
+
+    #[msg("RFQ quote window must be 1s to 1h")]
+    InvalidRfqQuoteWindow,
+
+    #[msg("RFQ is not open for quotes")]
+    RfqNotOpen,
+
+    #[msg("RFQ quote window has closed")]
+    RfqQuoteWindowClosed,
+
+    #[msg("RFQ already has the maximum number of quotes")]
+    RfqQuotesFull,
+
+    #[msg("RFQ has no selected quote to settle")]
+    RfqNotSelected,
+
+    #[msg("RFQ quotes passed do not match the request, or quoting is still open")]
+    InvalidRfqQuotes,
+
+    #[msg("Shielded transfer needs a non-zero recipient commitment")]
+    InvalidTransferCommitment,
+
+    #[msg("Arcium mempool is deeper than this computation type accepts; retry later")]
+    MempoolBackpressure,
+
+    #[msg("Arcium mempool account is too small to read its depth")]
+    InvalidMempoolAccount,
+
+    #[msg("Too many computation types have a mempool limit")]
+    MempoolLimitsFull,
+
+    #[msg("Stealth meta-address keys must be non-zero")]
+    InvalidStealthMetaAddress,
+
+    #[msg("Batch withdrawal needs 1 to 4 nullifiers, each with its nullifier account")]
+    InvalidBatchWithdrawal,
+
+    #[msg("Payout accounts for this vault type were not passed")]
+    MissingPayoutAccount,
+
+    #[msg("Vault verifies Groth16 proofs but the circuit's verification key was not passed")]
+    MissingVerificationKey,
+
+    #[msg("Verification key IC points do not match the public input count")]
+    VerificationKeyMismatch,
+
+    #[msg("Verification key cannot change while the vault verifies Groth16 proofs")]
+    VerificationKeyLocked,
+
+    #[msg("Verifier program does not match the protocol config")]
+    InvalidVerifierProgram,
+
+    #[msg("No verifier change is pending")]
+    NoPendingVerifierChange,
+
+    #[msg("Operation exceeds the protocol value ceiling without a risk signer attestation")]
+    OperationValueTooLarge,
+
+    #[msg("Commitment is not a BN254 field element, as Poseidon trees require")]
+    CommitmentNotInField,
+
+    #[msg("The tree hash of a tree with leaves cannot change")]
+    TreeNotEmpty,
+
+    #[msg("Relayer registration needs an endpoint, 1 to 8 vaults and a fee under 100%")]
+    InvalidRelayerRegistration,
+
+    #[msg("Relayer fee schedule differs from the one the withdrawal pins")]
+    RelayerScheduleMismatch,
+
+    #[msg("Relayer is not registered for this vault")]
+    RelayerVaultNotSupported,
+
+    #[msg("Relayer tip exceeds the relayer's posted fee schedule")]
+    RelayerFeeAboveSchedule,
+
+    #[msg("Parameter change queue is full")]
+    ParamChangeQueueFull,
+
+    #[msg("No queued parameter change has this id")]
+    ParamChangeNotFound,
+
+    #[msg("Tightening a fee or limit must be queued with propose_param_change")]
+    ParamChangeRequiresTimelock,
+
+    #[msg("Arcium config account required for this change")]
+    MissingArciumConfig,
+
+    #[msg("WSOL is held by the native SOL vault; use the zero mint")]
+    NonCanonicalAssetMint,
+
+    #[msg("Referral account required when the swap names a referrer")]
+    MissingReferralAccount,
+
+    #[msg("Remaining amount does not match the change commitment")]
+    RemainingAmountMismatch,
+
+    #[msg("Spent note is worth more than the vault has taken in")]
+    NoteValueExceedsDeposits,
+
+    #[msg("Account already has the current layout version")]
+    AccountUpToDate,
+
+    #[msg("This key already voted under another role")]
+    SignerAlreadyVoted,
 }
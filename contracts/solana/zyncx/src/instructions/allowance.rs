@@ -0,0 +1,145 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ZyncxError;
+use crate::state::{AllowanceParams, AllowancePolicy, VaultState, UNBOUND_POLICY_HASH};
+
+#[derive(Accounts)]
+#[instruction(policy_id: u64)]
+pub struct CreateAllowancePolicy<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"vault", vault.asset_mint.as_ref()],
+        bump = vault.bump,
+    )]
+    pub vault: Box<Account<'info, VaultState>>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = AllowancePolicy::INIT_SPACE,
+        seeds = [b"allowance_policy", vault.key().as_ref(), &policy_id.to_le_bytes()],
+        bump
+    )]
+    pub allowance_policy: Box<Account<'info, AllowancePolicy>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Create an allowance policy for an organisation's traders. The signer
+/// becomes the org admin; the first epoch starts now.
+pub fn handler_create_allowance_policy(
+    ctx: Context<CreateAllowancePolicy>,
+    policy_id: u64,
+    params: AllowanceParams,
+) -> Result<()> {
+    params.validate()?;
+    let now = Clock::get()?.unix_timestamp;
+
+    let policy = &mut ctx.accounts.allowance_policy;
+    policy.bump = ctx.bumps.allowance_policy;
+    policy.vault = ctx.accounts.vault.key();
+    policy.policy_id = policy_id;
+    policy.admin = ctx.accounts.admin.key();
+    policy.apply(params);
+    policy.epoch_start = now;
+    policy.epoch_spent = 0;
+    policy.total_withdrawn = 0;
+    policy.created_at = now;
+
+    emit!(AllowancePolicyUpdated {
+        policy: policy.key(),
+        vault: policy.vault,
+        policy_id,
+        epoch_limit: policy.epoch_limit,
+        epoch_seconds: policy.epoch_seconds,
+        policy_hash: policy.policy_hash(),
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdateAllowancePolicy<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = admin @ ZyncxError::Unauthorized,
+        seeds = [
+            b"allowance_policy",
+            allowance_policy.vault.as_ref(),
+            &allowance_policy.policy_id.to_le_bytes(),
+        ],
+        bump = allowance_policy.bump,
+    )]
+    pub allowance_policy: Box<Account<'info, AllowancePolicy>>,
+}
+
+/// Change a policy's limit, epoch or viewing keys (org admin only). The
+/// running epoch total is kept; a lower limit applies immediately. Notes
+/// already bound to the policy stay bound: its hash does not change.
+pub fn handler_update_allowance_policy(
+    ctx: Context<UpdateAllowancePolicy>,
+    params: AllowanceParams,
+) -> Result<()> {
+    params.validate()?;
+
+    let policy = &mut ctx.accounts.allowance_policy;
+    policy.apply(params);
+
+    emit!(AllowancePolicyUpdated {
+        policy: policy.key(),
+        vault: policy.vault,
+        policy_id: policy.policy_id,
+        epoch_limit: policy.epoch_limit,
+        epoch_seconds: policy.epoch_seconds,
+        policy_hash: policy.policy_hash(),
+    });
+
+    Ok(())
+}
+
+/// Charge a withdrawal against the allowance policy passed with it and
+/// return the policy hash public input: the policy's, or
+/// `UNBOUND_POLICY_HASH` without one. The circuit recomputes the spent
+/// note's commitment with it, so a bound note cannot be withdrawn without
+/// its policy being passed and charged.
+pub fn charge_allowance(
+    allowance_policy: &mut Option<Box<Account<AllowancePolicy>>>,
+    amount: u64,
+    now: i64,
+) -> Result<[u8; 32]> {
+    let Some(policy) = allowance_policy.as_mut() else {
+        return Ok(UNBOUND_POLICY_HASH);
+    };
+    policy.charge(amount, now)?;
+
+    emit!(AllowanceCharged {
+        policy: policy.key(),
+        amount,
+        epoch_start: policy.epoch_start,
+        epoch_spent: policy.epoch_spent,
+    });
+
+    Ok(policy.policy_hash())
+}
+
+#[event]
+pub struct AllowancePolicyUpdated {
+    pub policy: Pubkey,
+    pub vault: Pubkey,
+    pub policy_id: u64,
+    pub epoch_limit: u64,
+    pub epoch_seconds: i64,
+    pub policy_hash: [u8; 32],
+}
+
+#[event]
+pub struct AllowanceCharged {
+    pub policy: Pubkey,
+    pub amount: u64,
+    pub epoch_start: i64,
+    pub epoch_spent: u64,
+}
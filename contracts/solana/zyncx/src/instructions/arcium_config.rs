@@ -0,0 +1,146 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ZyncxError;
+use crate::state::{ArciumConfig, ComputationFeeEscrow, PriceGuards, Role, Roles, CURRENT_ACCOUNT_VERSION};
+
+#[derive(Accounts)]
+pub struct InitializeArciumConfig<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = ArciumConfig::INIT_SPACE,
+        seeds = [b"arcium_config"],
+        bump
+    )]
+    pub arcium_config: Box<Account<'info, ArciumConfig>>,
+
+    #[account(seeds = [b"roles"], bump = roles.bump)]
+    pub roles: Box<Account<'info, Roles>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Create the Arcium config with the default oracle price guards. Admin
+/// role only; the signer becomes the config authority.
+pub fn handler_initialize_arcium_config(
+    ctx: Context<InitializeArciumConfig>,
+    mxe_address: Pubkey,
+    computation_fee: u64,
+    timeout_seconds: i64,
+) -> Result<()> {
+    require!(
+        ctx.accounts.roles.has_role(Role::Admin, &ctx.accounts.authority.key()),
+        ZyncxError::Unauthorized
+    );
+
+    let config = &mut ctx.accounts.arcium_config;
+    config.bump = ctx.bumps.arcium_config;
+    config.authority = ctx.accounts.authority.key();
+    config.mxe_address = mxe_address;
+    config.computation_fee = computation_fee;
+    config.request_counter = 0;
+    config.timeout_seconds = timeout_seconds;
+    config.swaps_enabled = true;
+    config.limit_orders_enabled = true;
+    config.min_amount = 0;
+    config.max_amount = u64::MAX;
+    config.max_price_age_seconds = PriceGuards::DEFAULT.max_age_seconds;
+    config.max_price_confidence_bps = PriceGuards::DEFAULT.max_confidence_bps;
+    config.version = CURRENT_ACCOUNT_VERSION;
+
+    msg!("Arcium config initialized");
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetPriceGuards<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"arcium_config"],
+        bump = arcium_config.bump,
+        has_one = authority @ ZyncxError::Unauthorized,
+    )]
+    pub arcium_config: Box<Account<'info, ArciumConfig>>,
+}
+
+/// Set how fresh and how tight an oracle price must be before confidential
+/// swaps and limit orders are queued on it
+pub fn handler_set_price_guards(ctx: Context<SetPriceGuards>, guards: PriceGuards) -> Result<()> {
+    guards.validate()?;
+
+    let config = &mut ctx.accounts.arcium_config;
+    config.max_price_age_seconds = guards.max_age_seconds;
+    config.max_price_confidence_bps = guards.max_confidence_bps;
+
+    emit!(PriceGuardsUpdated {
+        max_age_seconds: guards.max_age_seconds,
+        max_confidence_bps: guards.max_confidence_bps,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct WithdrawComputationFees<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"arcium_config"],
+        bump = arcium_config.bump,
+        has_one = authority @ ZyncxError::Unauthorized,
+    )]
+    pub arcium_config: Box<Account<'info, ArciumConfig>>,
+
+    #[account(
+        mut,
+        seeds = [b"computation_fees"],
+        bump = computation_fee_escrow.bump,
+    )]
+    pub computation_fee_escrow: Box<Account<'info, ComputationFeeEscrow>>,
+
+    /// CHECK: Any account chosen by the authority to receive the fees
+    #[account(mut)]
+    pub recipient: UncheckedAccount<'info>,
+}
+
+/// Send every collected computation fee to `recipient`. Fees of computations
+/// still in flight stay in the escrow for their refund.
+pub fn handler_withdraw_computation_fees(ctx: Context<WithdrawComputationFees>) -> Result<()> {
+    let escrow = &mut ctx.accounts.computation_fee_escrow;
+    let amount = std::mem::take(&mut escrow.collected);
+    escrow.withdrawn = escrow
+        .withdrawn
+        .checked_add(amount)
+        .ok_or(ZyncxError::ArithmeticOverflow)?;
+
+    **escrow.to_account_info().try_borrow_mut_lamports()? -= amount;
+    **ctx.accounts.recipient.to_account_info().try_borrow_mut_lamports()? += amount;
+
+    emit!(ComputationFeesWithdrawn {
+        recipient: ctx.accounts.recipient.key(),
+        amount,
+        held: escrow.held,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct PriceGuardsUpdated {
+    pub max_age_seconds: i64,
+    pub max_confidence_bps: u16,
+}
+
+#[event]
+pub struct ComputationFeesWithdrawn {
+    pub recipient: Pubkey,
+    pub amount: u64,
+    /// Fees still held for computations in flight
+    pub held: u64,
+}
@@ -0,0 +1,132 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ZyncxError;
+use crate::state::{EncryptedSealedOrder, SealedOrderStatus, VaultState};
+
+#[derive(Accounts)]
+#[instruction(order_id: u64)]
+pub struct SubmitSealedOrder<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        seeds = [b"vault", base_vault.asset_mint.as_ref()],
+        bump = base_vault.bump,
+    )]
+    pub base_vault: Box<Account<'info, VaultState>>,
+
+    #[account(
+        seeds = [b"vault", quote_vault.asset_mint.as_ref()],
+        bump = quote_vault.bump,
+        constraint = quote_vault.key() != base_vault.key() @ ZyncxError::InvalidSwapRoute,
+    )]
+    pub quote_vault: Box<Account<'info, VaultState>>,
+
+    /// CHECK: Pyth price account; parsed when the auction runs
+    pub price_feed: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + EncryptedSealedOrder::INIT_SPACE,
+        seeds = [b"sealed_order", user.key().as_ref(), &order_id.to_le_bytes()],
+        bump,
+    )]
+    pub sealed_order: Box<Account<'info, EncryptedSealedOrder>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Submit a sealed order to the next batch auction of its market.
+/// `encrypted_params` are the client's Enc<Shared, SealedOrder> ciphertexts;
+/// only the market and price feed are public.
+pub fn handler_submit_sealed_order(
+    ctx: Context<SubmitSealedOrder>,
+    order_id: u64,
+    encrypted_params: [[u8; 32]; 3],
+    params_nonce: u128,
+    client_pubkey: [u8; 32],
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+
+    let order = &mut ctx.accounts.sealed_order;
+    order.bump = ctx.bumps.sealed_order;
+    order.user = ctx.accounts.user.key();
+    order.base_vault = ctx.accounts.base_vault.key();
+    order.quote_vault = ctx.accounts.quote_vault.key();
+    order.encrypted_params = encrypted_params;
+    order.params_nonce = params_nonce;
+    order.client_pubkey = client_pubkey;
+    order.price_feed = ctx.accounts.price_feed.key();
+    order.status = SealedOrderStatus::Open;
+    order.order_id = order_id;
+    order.created_at = now;
+    order.auction = Pubkey::default();
+    order.encrypted_fill = [0u8; 32];
+    order.fill_nonce = 0;
+
+    emit!(SealedOrderSubmitted {
+        order: order.key(),
+        user: order.user,
+        base_vault: order.base_vault,
+        quote_vault: order.quote_vault,
+        price_feed: order.price_feed,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CancelSealedOrder<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        close = user,
+        has_one = user @ ZyncxError::Unauthorized,
+        seeds = [b"sealed_order", user.key().as_ref(), &sealed_order.order_id.to_le_bytes()],
+        bump = sealed_order.bump,
+    )]
+    pub sealed_order: Box<Account<'info, EncryptedSealedOrder>>,
+}
+
+/// Withdraw an open order (or clean up a settled one) and refund its rent.
+/// Not allowed while its auction is in flight, so the callback always finds
+/// its account.
+pub fn handler_cancel_sealed_order(ctx: Context<CancelSealedOrder>) -> Result<()> {
+    let order = &ctx.accounts.sealed_order;
+    require!(
+        order.status != SealedOrderStatus::Auctioning,
+        ZyncxError::InvalidComputationStatus
+    );
+
+    emit!(SealedOrderCancelled {
+        order: order.key(),
+        user: order.user,
+        status: order.status,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct SealedOrderSubmitted {
+    pub order: Pubkey,
+    pub user: Pubkey,
+    pub base_vault: Pubkey,
+    pub quote_vault: Pubkey,
+    pub price_feed: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SealedOrderCancelled {
+    pub order: Pubkey,
+    pub user: Pubkey,
+    /// Status at the time the account was closed
+    pub status: SealedOrderStatus,
+    pub timestamp: i64,
+}
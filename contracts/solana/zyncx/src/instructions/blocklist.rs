@@ -0,0 +1,95 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ZyncxError;
+use crate::state::{Blocklist, Roles, MAX_BLOCKED_RECIPIENTS};
+
+#[derive(Accounts)]
+pub struct InitializeBlocklist<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = Blocklist::INIT_SPACE,
+        seeds = [b"blocklist"],
+        bump
+    )]
+    pub blocklist: Box<Account<'info, Blocklist>>,
+
+    #[account(
+        seeds = [b"roles"],
+        bump = roles.bump,
+        constraint = roles.compliance == authority.key() @ ZyncxError::Unauthorized,
+    )]
+    pub roles: Box<Account<'info, Roles>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Create the blocklist. Compliance role only.
+pub fn handler_initialize(ctx: Context<InitializeBlocklist>) -> Result<()> {
+    let blocklist = &mut ctx.accounts.blocklist;
+    blocklist.bump = ctx.bumps.blocklist;
+    blocklist.blocked = Vec::new();
+
+    msg!("Blocklist initialized");
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdateBlocklist<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"blocklist"],
+        bump = blocklist.bump,
+    )]
+    pub blocklist: Box<Account<'info, Blocklist>>,
+
+    #[account(
+        seeds = [b"roles"],
+        bump = roles.bump,
+        constraint = roles.compliance == authority.key() @ ZyncxError::Unauthorized,
+    )]
+    pub roles: Box<Account<'info, Roles>>,
+}
+
+pub fn handler_add(ctx: Context<UpdateBlocklist>, recipient: Pubkey) -> Result<()> {
+    let blocklist = &mut ctx.accounts.blocklist;
+
+    require!(!blocklist.is_blocked(&recipient), ZyncxError::AlreadyBlocked);
+    require!(
+        blocklist.blocked.len() < MAX_BLOCKED_RECIPIENTS,
+        ZyncxError::BlocklistFull
+    );
+
+    blocklist.blocked.push(recipient);
+
+    emit!(BlocklistUpdated { recipient, blocked: true });
+
+    Ok(())
+}
+
+pub fn handler_remove(ctx: Context<UpdateBlocklist>, recipient: Pubkey) -> Result<()> {
+    let blocklist = &mut ctx.accounts.blocklist;
+
+    let index = blocklist
+        .blocked
+        .iter()
+        .position(|blocked| *blocked == recipient)
+        .ok_or(ZyncxError::NotBlocked)?;
+    blocklist.blocked.swap_remove(index);
+
+    emit!(BlocklistUpdated { recipient, blocked: false });
+
+    Ok(())
+}
+
+#[event]
+pub struct BlocklistUpdated {
+    pub recipient: Pubkey,
+    pub blocked: bool,
+}
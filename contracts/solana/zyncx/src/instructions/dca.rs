@@ -0,0 +1,399 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+
+use crate::dex::{execute_jupiter_swap_signed, JUPITER_V6_PROGRAM_ID};
+use crate::errors::ZyncxError;
+use crate::state::{DCAStatus, EncryptedDCAConfig, VaultState, MIN_DCA_INTERVAL_SECONDS};
+
+#[derive(Accounts)]
+#[instruction(dca_id: u64)]
+pub struct CreateDca<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        seeds = [b"vault", source_vault.asset_mint.as_ref()],
+        bump = source_vault.bump,
+    )]
+    pub source_vault: Box<Account<'info, VaultState>>,
+
+    #[account(
+        seeds = [b"vault", dest_vault.asset_mint.as_ref()],
+        bump = dest_vault.bump,
+        constraint = dest_vault.key() != source_vault.key() @ ZyncxError::InvalidSwapRoute,
+    )]
+    pub dest_vault: Box<Account<'info, VaultState>>,
+
+    /// CHECK: Pyth price account for the source token; parsed on every run
+    pub price_feed: UncheckedAccount<'info>,
+
+    /// CHECK: Receives the swapped tokens on every settlement
+    pub recipient: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + EncryptedDCAConfig::INIT_SPACE,
+        seeds = [b"dca", user.key().as_ref(), &dca_id.to_le_bytes()],
+        bump,
+    )]
+    pub dca_config: Box<Account<'info, EncryptedDCAConfig>>,
+
+    /// CHECK: System-owned PDA escrowing the SOL budget
+    #[account(
+        mut,
+        seeds = [b"dca_escrow", dca_config.key().as_ref()],
+        bump,
+    )]
+    pub dca_escrow: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Create a DCA schedule. `encrypted_params` are the client's
+/// Enc<Shared, DCAParams> ciphertexts; `budget` lamports are escrowed and
+/// spent one MXE-approved swap per interval. The escrow can also be topped
+/// up by a shielded withdrawal to its address.
+pub fn handler_create_dca(
+    ctx: Context<CreateDca>,
+    dca_id: u64,
+    encrypted_params: [[u8; 32]; 3],
+    params_nonce: u128,
+    client_pubkey: [u8; 32],
+    interval_seconds: u64,
+    budget: u64,
+) -> Result<()> {
+    require!(
+        interval_seconds >= MIN_DCA_INTERVAL_SECONDS && interval_seconds <= i64::MAX as u64,
+        ZyncxError::InvalidDcaConfig
+    );
+    require!(budget > 0, ZyncxError::InvalidDcaConfig);
+
+    system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.user.to_account_info(),
+                to: ctx.accounts.dca_escrow.to_account_info(),
+            },
+        ),
+        budget,
+    )?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let dca = &mut ctx.accounts.dca_config;
+
+    dca.bump = ctx.bumps.dca_config;
+    dca.user = ctx.accounts.user.key();
+    dca.source_vault = ctx.accounts.source_vault.key();
+    dca.dest_vault = ctx.accounts.dest_vault.key();
+    dca.encrypted_params = encrypted_params;
+    dca.params_nonce = params_nonce;
+    dca.client_pubkey = client_pubkey;
+    dca.interval_seconds = interval_seconds;
+    dca.next_execution_at = now;
+    dca.status = DCAStatus::Active;
+    dca.created_at = now;
+    dca.swaps_executed = 0;
+    dca.dca_id = dca_id;
+    dca.price_feed = ctx.accounts.price_feed.key();
+    dca.recipient = ctx.accounts.recipient.key();
+    dca.pending_amount = 0;
+    dca.computation_pending = false;
+    dca.netted_total = 0;
+
+    emit!(DcaCreated {
+        dca: dca.key(),
+        user: dca.user,
+        source_vault: dca.source_vault,
+        dest_vault: dca.dest_vault,
+        interval_seconds,
+        budget,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SettleDcaSwap<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"dca", dca_config.user.as_ref(), &dca_config.dca_id.to_le_bytes()],
+        bump = dca_config.bump,
+    )]
+    pub dca_config: Box<Account<'info, EncryptedDCAConfig>>,
+
+    /// CHECK: System-owned PDA escrowing the SOL budget
+    #[account(
+        mut,
+        seeds = [b"dca_escrow", dca_config.key().as_ref()],
+        bump,
+    )]
+    pub dca_escrow: UncheckedAccount<'info>,
+
+    /// CHECK: Must be the recipient fixed at creation
+    #[account(
+        mut,
+        address = dca_config.recipient @ ZyncxError::RecipientMismatch,
+    )]
+    pub recipient: UncheckedAccount<'info>,
+
+    /// CHECK: Jupiter program
+    #[account(address = JUPITER_V6_PROGRAM_ID)]
+    pub jupiter_program: AccountInfo<'info>,
+    // Remaining accounts: All accounts required by Jupiter swap route
+}
+
+/// Execute the swap approved by the last `process_dca` run through Jupiter.
+/// Crankable; the route may not spend more than the approved amount.
+pub fn handler_settle_dca_swap<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SettleDcaSwap<'info>>,
+    swap_data: Vec<u8>,
+) -> Result<()> {
+    let amount = ctx.accounts.dca_config.pending_amount;
+    require!(amount > 0, ZyncxError::InvalidSwapAmount);
+
+    let escrow = &ctx.accounts.dca_escrow;
+    let before = escrow.lamports();
+    let reserve = Rent::get()?.minimum_balance(0);
+    require!(
+        before >= amount.saturating_add(reserve),
+        ZyncxError::InsufficientFunds
+    );
+
+    let dca_key = ctx.accounts.dca_config.key();
+    let bump = [ctx.bumps.dca_escrow];
+    let seeds: &[&[u8]] = &[b"dca_escrow", dca_key.as_ref(), &bump];
+
+    execute_jupiter_swap_signed(
+        &escrow.to_account_info(),
+        &ctx.accounts.recipient.to_account_info(),
+        &ctx.accounts.jupiter_program,
+        swap_data,
+        ctx.remaining_accounts,
+        &[seeds],
+    )?;
+
+    let spent = before.saturating_sub(escrow.lamports());
+    require!(spent <= amount, ZyncxError::InvalidSwapAmount);
+
+    let dca = &mut ctx.accounts.dca_config;
+    dca.pending_amount = 0;
+    dca.swaps_executed = dca.swaps_executed.saturating_add(1);
+
+    emit!(DcaSwapSettled {
+        dca: dca_key,
+        amount_in: spent,
+        swaps_executed: dca.swaps_executed,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdateDca<'info> {
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = user @ ZyncxError::Unauthorized,
+        seeds = [b"dca", user.key().as_ref(), &dca_config.dca_id.to_le_bytes()],
+        bump = dca_config.bump,
+    )]
+    pub dca_config: Box<Account<'info, EncryptedDCAConfig>>,
+}
+
+/// Pause an active DCA schedule. Cranks skip it until resumed; an already
+/// approved swap can still be settled.
+pub fn handler_pause_dca(ctx: Context<UpdateDca>) -> Result<()> {
+    let dca = &mut ctx.accounts.dca_config;
+    require!(
+        dca.status == DCAStatus::Active && !dca.computation_pending,
+        ZyncxError::InvalidComputationStatus
+    );
+
+    dca.status = DCAStatus::Paused;
+
+    emit!(DcaPaused {
+        dca: dca.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Resume a paused DCA schedule. Intervals missed while paused are not
+/// caught up; the next run is due immediately at the earliest.
+pub fn handler_resume_dca(ctx: Context<UpdateDca>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let dca = &mut ctx.accounts.dca_config;
+    require!(
+        dca.status == DCAStatus::Paused,
+        ZyncxError::InvalidComputationStatus
+    );
+
+    dca.status = DCAStatus::Active;
+    dca.next_execution_at = dca.next_execution_at.max(now);
+
+    emit!(DcaResumed {
+        dca: dca.key(),
+        next_execution_at: dca.next_execution_at,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+/// Replace the encrypted DCAParams mid-stream. The client re-encrypts the
+/// full params under a fresh nonce. A completed schedule becomes active
+/// again; a paused one stays paused. Not allowed while `process_dca` is in
+/// flight, whose callback would overwrite the new ciphertexts.
+pub fn handler_update_dca_params(
+    ctx: Context<UpdateDca>,
+    encrypted_params: [[u8; 32]; 3],
+    params_nonce: u128,
+    client_pubkey: [u8; 32],
+) -> Result<()> {
+    let dca = &mut ctx.accounts.dca_config;
+    require!(
+        !dca.computation_pending,
+        ZyncxError::InvalidComputationStatus
+    );
+    require!(
+        params_nonce != dca.params_nonce || client_pubkey != dca.client_pubkey,
+        ZyncxError::InvalidDcaConfig
+    );
+
+    dca.encrypted_params = encrypted_params;
+    dca.params_nonce = params_nonce;
+    dca.client_pubkey = client_pubkey;
+    if dca.status == DCAStatus::Completed {
+        dca.status = DCAStatus::Active;
+    }
+
+    emit!(DcaParamsUpdated {
+        dca: dca.key(),
+        status: dca.status,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CancelDca<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        close = user,
+        has_one = user @ ZyncxError::Unauthorized,
+        seeds = [b"dca", user.key().as_ref(), &dca_config.dca_id.to_le_bytes()],
+        bump = dca_config.bump,
+    )]
+    pub dca_config: Box<Account<'info, EncryptedDCAConfig>>,
+
+    /// CHECK: System-owned PDA escrowing the SOL budget
+    #[account(
+        mut,
+        seeds = [b"dca_escrow", dca_config.key().as_ref()],
+        bump,
+    )]
+    pub dca_escrow: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Cancel a DCA schedule, refunding the unspent escrow and the config rent.
+/// Not allowed while a `process_dca` computation is in flight.
+pub fn handler_cancel_dca(ctx: Context<CancelDca>) -> Result<()> {
+    require!(
+        !ctx.accounts.dca_config.computation_pending,
+        ZyncxError::InvalidComputationStatus
+    );
+
+    let dca_key = ctx.accounts.dca_config.key();
+    let refund = ctx.accounts.dca_escrow.lamports();
+
+    if refund > 0 {
+        let bump = [ctx.bumps.dca_escrow];
+        let seeds: &[&[u8]] = &[b"dca_escrow", dca_key.as_ref(), &bump];
+
+        system_program::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.dca_escrow.to_account_info(),
+                    to: ctx.accounts.user.to_account_info(),
+                },
+                &[seeds],
+            ),
+            refund,
+        )?;
+    }
+
+    emit!(DcaCancelled {
+        dca: dca_key,
+        user: ctx.accounts.user.key(),
+        swaps_executed: ctx.accounts.dca_config.swaps_executed,
+        refunded: refund,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct DcaCreated {
+    pub dca: Pubkey,
+    pub user: Pubkey,
+    pub source_vault: Pubkey,
+    pub dest_vault: Pubkey,
+    pub interval_seconds: u64,
+    pub budget: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DcaSwapSettled {
+    pub dca: Pubkey,
+    pub amount_in: u64,
+    pub swaps_executed: u16,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DcaCancelled {
+    pub dca: Pubkey,
+    pub user: Pubkey,
+    pub swaps_executed: u16,
+    pub refunded: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DcaPaused {
+    pub dca: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DcaResumed {
+    pub dca: Pubkey,
+    pub next_execution_at: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DcaParamsUpdated {
+    pub dca: Pubkey,
+    /// Status after the update
+    pub status: DCAStatus,
+    pub timestamp: i64,
+}
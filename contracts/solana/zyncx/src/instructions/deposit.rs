@@ -2,10 +2,18 @@ use anchor_lang::prelude::*;
 use anchor_lang::system_program;
 use anchor_spl::token::{self, Token, TokenAccount, Transfer};
 
-use crate::state::{MerkleTreeState, VaultState, VaultType, poseidon_hash_commitment};
+use crate::state::{
+    AllowancePolicy, DepositReceipt, GlobalStats, MerkleTreeState, NoteBackup, TreeInsertion,
+    VaultLifecycle, VaultState, VaultStats, VaultType, ViewingKey, UNBOUND_POLICY_HASH,
+};
 use crate::errors::ZyncxError;
+use crate::instructions::tree::TreeNearCapacity;
+
+/// Upper bound for the note ciphertext attached to a deposit
+pub const MAX_ENCRYPTED_NOTE_LEN: usize = 256;
 
 #[derive(Accounts)]
+#[instruction(amount: u64, precommitment: [u8; 32])]
 pub struct DepositNative<'info> {
     #[account(mut)]
     pub depositor: Signer<'info>,
@@ -19,8 +27,7 @@ pub struct DepositNative<'info> {
 
     #[account(
         mut,
-        seeds = [b"merkle_tree", vault.key().as_ref()],
-        bump = merkle_tree.bump,
+        address = vault.merkle_tree @ ZyncxError::InactiveMerkleTree,
     )]
     pub merkle_tree: Box<Account<'info, MerkleTreeState>>,
 
@@ -32,6 +39,55 @@ pub struct DepositNative<'info> {
     )]
     pub vault_treasury: AccountInfo<'info>,
 
+    #[account(
+        init_if_needed,
+        payer = depositor,
+        space = VaultStats::INIT_SPACE,
+        seeds = [b"vault_stats", vault.key().as_ref()],
+        bump
+    )]
+    pub vault_stats: Box<Account<'info, VaultStats>>,
+
+    #[account(
+        init_if_needed,
+        payer = depositor,
+        space = GlobalStats::INIT_SPACE,
+        seeds = [b"global_stats"],
+        bump
+    )]
+    pub global_stats: Box<Account<'info, GlobalStats>>,
+
+    /// Depositor's registered viewing key; when present the deposit must
+    /// carry a disclosure encrypted to it
+    #[account(
+        seeds = [b"viewing_key", depositor.key().as_ref()],
+        bump = viewing_key.bump,
+    )]
+    pub viewing_key: Option<Account<'info, ViewingKey>>,
+
+    /// When passed, the encrypted note is also kept in account data so the
+    /// depositor can recover the note from chain state alone
+    #[account(
+        init,
+        payer = depositor,
+        space = NoteBackup::INIT_SPACE,
+        seeds = [b"note_backup", depositor.key().as_ref(), precommitment.as_ref()],
+        bump
+    )]
+    pub note_backup: Option<Box<Account<'info, NoteBackup>>>,
+
+    /// Org allowance policy to bind the note to; a bound note can only be
+    /// withdrawn through the policy (see `AllowancePolicy`)
+    #[account(
+        seeds = [
+            b"allowance_policy",
+            vault.key().as_ref(),
+            &allowance_policy.policy_id.to_le_bytes(),
+        ],
+        bump = allowance_policy.bump,
+    )]
+    pub allowance_policy: Option<Box<Account<'info, AllowancePolicy>>>,
+
     pub system_program: Program<'info, System>,
 }
 
@@ -39,13 +95,25 @@ pub fn handler_native(
     ctx: Context<DepositNative>,
     amount: u64,
     precommitment: [u8; 32],
-) -> Result<[u8; 32]> {
+    encrypted_note: Vec<u8>,
+    viewing_ciphertext: Vec<u8>,
+    include_path: bool,
+) -> Result<DepositReceipt> {
     require!(amount > 0, ZyncxError::InvalidDepositAmount);
+    require!(
+        encrypted_note.len() <= MAX_ENCRYPTED_NOTE_LEN,
+        ZyncxError::EncryptedNoteTooLarge
+    );
+    let viewing_pubkey = viewing_disclosure_key(&ctx.accounts.viewing_key, &viewing_ciphertext)?;
 
     let vault = &mut ctx.accounts.vault;
     let merkle_tree = &mut ctx.accounts.merkle_tree;
 
     require!(vault.vault_type == VaultType::Native, ZyncxError::VaultNotFound);
+    require!(!vault.paused, ZyncxError::VaultPaused);
+    require!(vault.lifecycle == VaultLifecycle::Active, ZyncxError::VaultSunset);
+    require!(vault.is_valid_denomination(amount), ZyncxError::InvalidDenomination);
+    vault.apply_deposit_limits(amount, Clock::get()?.unix_timestamp)?;
 
     // Transfer SOL from depositor to vault treasury
     system_program::transfer(
@@ -59,11 +127,35 @@ pub fn handler_native(
         amount,
     )?;
 
-    // Generate commitment = hash(amount, precommitment)
-    let commitment = poseidon_hash_commitment(amount, precommitment)?;
+    // Generate commitment = hash(amount, precommitment[, policy_hash])
+    let policy_hash = ctx
+        .accounts
+        .allowance_policy
+        .as_ref()
+        .map_or(UNBOUND_POLICY_HASH, |policy| policy.policy_hash());
+    let commitment = merkle_tree.bound_commitment(amount, precommitment, &policy_hash)?;
 
     // Insert commitment into merkle tree
-    merkle_tree.insert(commitment)?;
+    let insertion = merkle_tree.insert(commitment)?;
+    emit_if_near_capacity(vault, merkle_tree);
+
+    if let Some(note_backup) = ctx.accounts.note_backup.as_mut() {
+        note_backup.bump = ctx.bumps.note_backup.unwrap_or_default();
+        store_note_backup(
+            note_backup,
+            ctx.accounts.depositor.key(),
+            vault.key(),
+            commitment,
+            &insertion,
+            &encrypted_note,
+        )?;
+    }
+
+    let auth_path = if include_path {
+        merkle_tree.auth_path(insertion.leaf_index)?
+    } else {
+        Vec::new()
+    };
 
     // Update vault state
     vault.nonce += 1;
@@ -71,21 +163,38 @@ pub fn handler_native(
         .checked_add(amount)
         .ok_or(ZyncxError::ArithmeticOverflow)?;
 
+    let vault_stats = &mut ctx.accounts.vault_stats;
+    vault_stats.ensure_initialized(vault.key(), ctx.bumps.vault_stats);
+    vault_stats.record_deposit(Clock::get()?.unix_timestamp);
+
+    let global_stats = &mut ctx.accounts.global_stats;
+    global_stats.ensure_initialized(ctx.bumps.global_stats);
+    global_stats.record_deposit(amount, Clock::get()?.unix_timestamp);
+
     // Emit event
     emit!(DepositedEvent {
         depositor: ctx.accounts.depositor.key(),
         amount,
         commitment,
         precommitment,
+        encrypted_note,
+        viewing_pubkey,
+        viewing_ciphertext,
+        leaf_index: insertion.leaf_index,
+        new_root: insertion.new_root,
     });
 
     msg!("Deposited {} lamports", amount);
     msg!("Commitment: {:?}", commitment);
 
-    Ok(commitment)
+    Ok(DepositReceipt {
+        insertion,
+        auth_path,
+    })
 }
 
 #[derive(Accounts)]
+#[instruction(amount: u64, precommitment: [u8; 32])]
 pub struct DepositToken<'info> {
     #[account(mut)]
     pub depositor: Signer<'info>,
@@ -99,8 +208,7 @@ pub struct DepositToken<'info> {
 
     #[account(
         mut,
-        seeds = [b"merkle_tree", vault.key().as_ref()],
-        bump = merkle_tree.bump,
+        address = vault.merkle_tree @ ZyncxError::InactiveMerkleTree,
     )]
     pub merkle_tree: Box<Account<'info, MerkleTreeState>>,
 
@@ -114,20 +222,82 @@ pub struct DepositToken<'info> {
     )]
     pub vault_token_account: Box<Account<'info, TokenAccount>>,
 
+    #[account(
+        init_if_needed,
+        payer = depositor,
+        space = VaultStats::INIT_SPACE,
+        seeds = [b"vault_stats", vault.key().as_ref()],
+        bump
+    )]
+    pub vault_stats: Box<Account<'info, VaultStats>>,
+
+    #[account(
+        init_if_needed,
+        payer = depositor,
+        space = GlobalStats::INIT_SPACE,
+        seeds = [b"global_stats"],
+        bump
+    )]
+    pub global_stats: Box<Account<'info, GlobalStats>>,
+
+    /// Depositor's registered viewing key; when present the deposit must
+    /// carry a disclosure encrypted to it
+    #[account(
+        seeds = [b"viewing_key", depositor.key().as_ref()],
+        bump = viewing_key.bump,
+    )]
+    pub viewing_key: Option<Account<'info, ViewingKey>>,
+
+    /// When passed, the encrypted note is also kept in account data so the
+    /// depositor can recover the note from chain state alone
+    #[account(
+        init,
+        payer = depositor,
+        space = NoteBackup::INIT_SPACE,
+        seeds = [b"note_backup", depositor.key().as_ref(), precommitment.as_ref()],
+        bump
+    )]
+    pub note_backup: Option<Box<Account<'info, NoteBackup>>>,
+
+    /// Org allowance policy to bind the note to; a bound note can only be
+    /// withdrawn through the policy (see `AllowancePolicy`)
+    #[account(
+        seeds = [
+            b"allowance_policy",
+            vault.key().as_ref(),
+            &allowance_policy.policy_id.to_le_bytes(),
+        ],
+        bump = allowance_policy.bump,
+    )]
+    pub allowance_policy: Option<Box<Account<'info, AllowancePolicy>>>,
+
     pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
 }
 
 pub fn handler_token(
     ctx: Context<DepositToken>,
     amount: u64,
     precommitment: [u8; 32],
-) -> Result<[u8; 32]> {
+    encrypted_note: Vec<u8>,
+    viewing_ciphertext: Vec<u8>,
+    include_path: bool,
+) -> Result<DepositReceipt> {
     require!(amount > 0, ZyncxError::InvalidDepositAmount);
+    require!(
+        encrypted_note.len() <= MAX_ENCRYPTED_NOTE_LEN,
+        ZyncxError::EncryptedNoteTooLarge
+    );
+    let viewing_pubkey = viewing_disclosure_key(&ctx.accounts.viewing_key, &viewing_ciphertext)?;
 
     let vault = &mut ctx.accounts.vault;
     let merkle_tree = &mut ctx.accounts.merkle_tree;
 
     require!(vault.vault_type == VaultType::Alternative, ZyncxError::VaultNotFound);
+    require!(!vault.paused, ZyncxError::VaultPaused);
+    require!(vault.lifecycle == VaultLifecycle::Active, ZyncxError::VaultSunset);
+    require!(vault.is_valid_denomination(amount), ZyncxError::InvalidDenomination);
+    vault.apply_deposit_limits(amount, Clock::get()?.unix_timestamp)?;
 
     // Transfer tokens from depositor to vault
     token::transfer(
@@ -142,11 +312,35 @@ pub fn handler_token(
         amount,
     )?;
 
-    // Generate commitment = hash(amount, precommitment)
-    let commitment = poseidon_hash_commitment(amount, precommitment)?;
+    // Generate commitment = hash(amount, precommitment[, policy_hash])
+    let policy_hash = ctx
+        .accounts
+        .allowance_policy
+        .as_ref()
+        .map_or(UNBOUND_POLICY_HASH, |policy| policy.policy_hash());
+    let commitment = merkle_tree.bound_commitment(amount, precommitment, &policy_hash)?;
 
     // Insert commitment into merkle tree
-    merkle_tree.insert(commitment)?;
+    let insertion = merkle_tree.insert(commitment)?;
+    emit_if_near_capacity(vault, merkle_tree);
+
+    if let Some(note_backup) = ctx.accounts.note_backup.as_mut() {
+        note_backup.bump = ctx.bumps.note_backup.unwrap_or_default();
+        store_note_backup(
+            note_backup,
+            ctx.accounts.depositor.key(),
+            vault.key(),
+            commitment,
+            &insertion,
+            &encrypted_note,
+        )?;
+    }
+
+    let auth_path = if include_path {
+        merkle_tree.auth_path(insertion.leaf_index)?
+    } else {
+        Vec::new()
+    };
 
     // Update vault state
     vault.nonce += 1;
@@ -154,18 +348,92 @@ pub fn handler_token(
         .checked_add(amount)
         .ok_or(ZyncxError::ArithmeticOverflow)?;
 
+    let vault_stats = &mut ctx.accounts.vault_stats;
+    vault_stats.ensure_initialized(vault.key(), ctx.bumps.vault_stats);
+    vault_stats.record_deposit(Clock::get()?.unix_timestamp);
+
+    let global_stats = &mut ctx.accounts.global_stats;
+    global_stats.ensure_initialized(ctx.bumps.global_stats);
+    global_stats.record_deposit(amount, Clock::get()?.unix_timestamp);
+
     // Emit event
     emit!(DepositedEvent {
         depositor: ctx.accounts.depositor.key(),
         amount,
         commitment,
         precommitment,
+        encrypted_note,
+        viewing_pubkey,
+        viewing_ciphertext,
+        leaf_index: insertion.leaf_index,
+        new_root: insertion.new_root,
     });
 
     msg!("Deposited {} tokens", amount);
     msg!("Commitment: {:?}", commitment);
 
-    Ok(commitment)
+    Ok(DepositReceipt {
+        insertion,
+        auth_path,
+    })
+}
+
+/// Viewing key a deposit disclosure is encrypted to (zero if none registered)
+fn viewing_disclosure_key(
+    viewing_key: &Option<Account<ViewingKey>>,
+    viewing_ciphertext: &[u8],
+) -> Result<[u8; 32]> {
+    ViewingKey::check_ciphertext_len(viewing_ciphertext)?;
+
+    match viewing_key {
+        Some(viewing_key) => {
+            require!(!viewing_ciphertext.is_empty(), ZyncxError::MissingViewingCiphertext);
+            Ok(viewing_key.viewing_pubkey)
+        }
+        None => Ok([0u8; 32]),
+    }
+}
+
+/// Keep the deposit's note ciphertext in its `NoteBackup` account
+fn store_note_backup(
+    note_backup: &mut NoteBackup,
+    owner: Pubkey,
+    vault: Pubkey,
+    commitment: [u8; 32],
+    insertion: &TreeInsertion,
+    encrypted_note: &[u8],
+) -> Result<()> {
+    require!(!encrypted_note.is_empty(), ZyncxError::MissingEncryptedNote);
+
+    note_backup.owner = owner;
+    note_backup.vault = vault;
+    note_backup.commitment = commitment;
+    note_backup.leaf_index = insertion.leaf_index;
+    note_backup.tree_epoch = insertion.tree_epoch;
+    note_backup.ciphertext = encrypted_note.to_vec();
+    note_backup.created_at = Clock::get()?.unix_timestamp;
+
+    emit!(NoteBackedUp {
+        owner,
+        vault,
+        commitment,
+        leaf_index: insertion.leaf_index,
+    });
+
+    Ok(())
+}
+
+/// Signal keepers that the active tree should be rotated via `pre_rotate_tree`
+fn emit_if_near_capacity(vault: &Account<VaultState>, merkle_tree: &Account<MerkleTreeState>) {
+    if merkle_tree.is_near_capacity(vault.rotation_threshold_bps) {
+        emit!(TreeNearCapacity {
+            vault: vault.key(),
+            merkle_tree: merkle_tree.key(),
+            epoch: merkle_tree.epoch,
+            size: merkle_tree.size,
+            max_leaves: merkle_tree.max_leaves,
+        });
+    }
 }
 
 #[event]
@@ -174,4 +442,23 @@ pub struct DepositedEvent {
     pub amount: u64,
     pub commitment: [u8; 32],
     pub precommitment: [u8; 32],
+    /// Commitment opening encrypted to the depositor's key (empty if none),
+    /// lets a wallet recover its notes from chain data alone
+    pub encrypted_note: Vec<u8>,
+    /// Viewing key the disclosure below is encrypted to (zero if none)
+    pub viewing_pubkey: [u8; 32],
+    /// Deposit details encrypted to the depositor's viewing key
+    pub viewing_ciphertext: Vec<u8>,
+    /// Leaf index of `commitment` in the active tree
+    pub leaf_index: u64,
+    /// Tree root once `commitment` was inserted
+    pub new_root: [u8; 32],
+}
+
+#[event]
+pub struct NoteBackedUp {
+    pub owner: Pubkey,
+    pub vault: Pubkey,
+    pub commitment: [u8; 32],
+    pub leaf_index: u64,
 }
@@ -0,0 +1,117 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{instruction::Instruction, program::invoke};
+
+use crate::errors::ZyncxError;
+use crate::state::{ExclusionRoot, VaultState};
+
+#[derive(Accounts)]
+pub struct SetExclusionRoot<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"vault", vault.asset_mint.as_ref()],
+        bump = vault.bump,
+        has_one = authority @ ZyncxError::Unauthorized,
+    )]
+    pub vault: Box<Account<'info, VaultState>>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = ExclusionRoot::INIT_SPACE,
+        seeds = [b"exclusion_root", vault.key().as_ref()],
+        bump
+    )]
+    pub exclusion_root: Box<Account<'info, ExclusionRoot>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Publish (or replace) the vault's exclusion set root
+pub fn handler_set(
+    ctx: Context<SetExclusionRoot>,
+    root: [u8; 32],
+    verifier_program: Pubkey,
+) -> Result<()> {
+    require!(root != [0u8; 32], ZyncxError::InvalidExclusionRoot);
+    require!(verifier_program != Pubkey::default(), ZyncxError::ZeroAddress);
+
+    let exclusion_root = &mut ctx.accounts.exclusion_root;
+
+    // The root tracks the published list and updates freely; swapping the
+    // verifier after first publication goes through the config timelock
+    let initialized = exclusion_root.vault != Pubkey::default();
+    require!(
+        !initialized || exclusion_root.verifier_program == verifier_program,
+        ZyncxError::TimelockRequired
+    );
+
+    exclusion_root.bump = ctx.bumps.exclusion_root;
+    exclusion_root.vault = ctx.accounts.vault.key();
+    exclusion_root.root = root;
+    exclusion_root.verifier_program = verifier_program;
+    exclusion_root.updated_at = Clock::get()?.unix_timestamp;
+
+    emit!(ExclusionRootUpdated {
+        vault: exclusion_root.vault,
+        root,
+        verifier_program,
+    });
+
+    Ok(())
+}
+
+/// Verify an optional proof that the spent note is not in the exclusion set.
+/// Returns the exclusion root proven against, or zero when no proof is given.
+///
+/// Public inputs, in circuit order:
+/// 1. exclusion_root (32 bytes)
+/// 2. nullifier_hash (32 bytes) - binds the proof to the note being spent
+pub fn verify_exclusion_proof<'info>(
+    exclusion_root: &Option<Account<'info, ExclusionRoot>>,
+    exclusion_verifier: &Option<UncheckedAccount<'info>>,
+    nullifier: &[u8; 32],
+    proof: &[u8],
+) -> Result<[u8; 32]> {
+    if proof.is_empty() {
+        return Ok([0u8; 32]);
+    }
+
+    let exclusion_root = exclusion_root
+        .as_ref()
+        .ok_or(ZyncxError::MissingExclusionRoot)?;
+    let verifier = exclusion_verifier
+        .as_ref()
+        .ok_or(ZyncxError::MissingExclusionRoot)?;
+    require_keys_eq!(
+        verifier.key(),
+        exclusion_root.verifier_program,
+        ZyncxError::InvalidExclusionProof
+    );
+
+    let mut verifier_input = Vec::with_capacity(proof.len() + 64);
+    verifier_input.extend_from_slice(proof);
+    verifier_input.extend_from_slice(&exclusion_root.root);
+    verifier_input.extend_from_slice(nullifier);
+
+    let instruction = Instruction {
+        program_id: verifier.key(),
+        accounts: vec![],
+        data: verifier_input,
+    };
+
+    invoke(&instruction, &[verifier.to_account_info()])
+        .map_err(|_| ZyncxError::InvalidExclusionProof)?;
+
+    msg!("Exclusion proof verified");
+
+    Ok(exclusion_root.root)
+}
+
+#[event]
+pub struct ExclusionRootUpdated {
+    pub vault: Pubkey,
+    pub root: [u8; 32],
+    pub verifier_program: Pubkey,
+}
@@ -0,0 +1,265 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{self, Mint, MintTo, Token, TokenAccount};
+
+use crate::errors::ZyncxError;
+use crate::state::{
+    Faucet, MerkleTreeState, TreeInsertion, VaultState, VaultType,
+    FAUCET_DECIMALS, MAX_FAUCET_AIRDROP, MAX_FAUCET_NOTES,
+};
+
+#[derive(Accounts)]
+pub struct InitializeFaucet<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = Faucet::INIT_SPACE,
+        seeds = [b"faucet"],
+        bump
+    )]
+    pub faucet: Box<Account<'info, Faucet>>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"faucet_mint"],
+        bump,
+        mint::decimals = FAUCET_DECIMALS,
+        mint::authority = faucet,
+    )]
+    pub faucet_mint: Box<Account<'info, Mint>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Create the test token mint. Permissionless: the faucet only exists in
+/// devnet builds. An admin then creates the faucet vault with
+/// `initialize_vault(faucet_mint)` and its token account.
+pub fn handler_initialize_faucet(ctx: Context<InitializeFaucet>) -> Result<()> {
+    let faucet = &mut ctx.accounts.faucet;
+    faucet.bump = ctx.bumps.faucet;
+    faucet.mint = ctx.accounts.faucet_mint.key();
+    faucet.total_airdropped = 0;
+    faucet.notes_seeded = 0;
+
+    msg!("Faucet mint: {}", faucet.mint);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct FaucetAirdrop<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"faucet"],
+        bump = faucet.bump,
+    )]
+    pub faucet: Box<Account<'info, Faucet>>,
+
+    #[account(
+        mut,
+        address = faucet.mint @ ZyncxError::InvalidMint,
+    )]
+    pub faucet_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = faucet_mint,
+        associated_token::authority = user,
+    )]
+    pub user_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Mint test tokens to the caller's associated token account
+pub fn handler_faucet_airdrop(ctx: Context<FaucetAirdrop>, amount: u64) -> Result<()> {
+    require!(
+        amount > 0 && amount <= MAX_FAUCET_AIRDROP,
+        ZyncxError::InvalidDepositAmount
+    );
+
+    mint_test_tokens(
+        &ctx.accounts.faucet,
+        &ctx.accounts.faucet_mint,
+        &ctx.accounts.user_token_account,
+        &ctx.accounts.token_program,
+        amount,
+    )?;
+
+    let faucet = &mut ctx.accounts.faucet;
+    faucet.total_airdropped = faucet.total_airdropped.saturating_add(amount);
+
+    emit!(FaucetAirdropped {
+        recipient: ctx.accounts.user.key(),
+        amount,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(count: u8)]
+pub struct SeedFaucetNotes<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"faucet"],
+        bump = faucet.bump,
+    )]
+    pub faucet: Box<Account<'info, Faucet>>,
+
+    #[account(
+        mut,
+        address = faucet.mint @ ZyncxError::InvalidMint,
+    )]
+    pub faucet_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", faucet_mint.key().as_ref()],
+        bump = vault.bump,
+    )]
+    pub vault: Box<Account<'info, VaultState>>,
+
+    #[account(
+        mut,
+        address = vault.merkle_tree @ ZyncxError::InactiveMerkleTree,
+    )]
+    pub merkle_tree: Box<Account<'info, MerkleTreeState>>,
+
+    #[account(
+        mut,
+        seeds = [b"vault_token_account", vault.key().as_ref()],
+        bump,
+    )]
+    pub vault_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Mint `count * amount` test tokens into the faucet vault and insert one
+/// note of `amount` per count. Each note's secrets are derived from its
+/// faucet index and published in `FaucetNoteSeeded`, so integrators can
+/// withdraw or swap it straight away without depositing first. Notes are
+/// inserted in faucet index order; returns their insertions in that order.
+pub fn handler_seed_faucet_notes(
+    ctx: Context<SeedFaucetNotes>,
+    count: u8,
+    amount: u64,
+) -> Result<Vec<TreeInsertion>> {
+    require!(
+        count > 0 && count <= MAX_FAUCET_NOTES,
+        ZyncxError::InvalidDepositAmount
+    );
+    let vault = &ctx.accounts.vault;
+    require!(vault.vault_type == VaultType::Alternative, ZyncxError::VaultNotFound);
+    require!(!vault.paused, ZyncxError::VaultPaused);
+    require!(vault.is_valid_denomination(amount), ZyncxError::InvalidDenomination);
+
+    let total = amount
+        .checked_mul(count as u64)
+        .ok_or(ZyncxError::ArithmeticOverflow)?;
+    mint_test_tokens(
+        &ctx.accounts.faucet,
+        &ctx.accounts.faucet_mint,
+        &ctx.accounts.vault_token_account,
+        &ctx.accounts.token_program,
+        total,
+    )?;
+
+    let first_note = ctx.accounts.faucet.notes_seeded;
+    let tree_hash = ctx.accounts.merkle_tree.tree_hash;
+    let notes = (first_note..first_note + count as u64)
+        .map(|note_index| {
+            let (secret, nullifier_secret) = Faucet::note_secrets(note_index);
+            let precommitment = Faucet::precommitment(tree_hash, &secret, &nullifier_secret);
+            let commitment = tree_hash.commitment(amount, precommitment)?;
+            Ok((note_index, secret, nullifier_secret, commitment))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let commitments: Vec<[u8; 32]> = notes.iter().map(|note| note.3).collect();
+    let insertions = ctx.accounts.merkle_tree.insert_many(&commitments)?;
+    ctx.accounts.faucet.notes_seeded += count as u64;
+
+    let vault_key = ctx.accounts.vault.key();
+    for ((note_index, secret, nullifier_secret, commitment), insertion) in
+        notes.into_iter().zip(insertions.iter())
+    {
+        emit!(FaucetNoteSeeded {
+            vault: vault_key,
+            note_index,
+            amount,
+            secret,
+            nullifier_secret,
+            commitment,
+            leaf_index: insertion.leaf_index,
+            tree_epoch: insertion.tree_epoch,
+        });
+    }
+
+    let vault = &mut ctx.accounts.vault;
+    vault.nonce += count as u64;
+    vault.total_deposited = vault
+        .total_deposited
+        .checked_add(total)
+        .ok_or(ZyncxError::ArithmeticOverflow)?;
+
+    Ok(insertions)
+}
+
+fn mint_test_tokens<'info>(
+    faucet: &Account<'info, Faucet>,
+    faucet_mint: &Account<'info, Mint>,
+    to: &Account<'info, TokenAccount>,
+    token_program: &Program<'info, Token>,
+    amount: u64,
+) -> Result<()> {
+    let bump = [faucet.bump];
+    let seeds: &[&[u8]] = &[b"faucet", &bump];
+
+    token::mint_to(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            MintTo {
+                mint: faucet_mint.to_account_info(),
+                to: to.to_account_info(),
+                authority: faucet.to_account_info(),
+            },
+            &[seeds],
+        ),
+        amount,
+    )
+}
+
+#[event]
+pub struct FaucetAirdropped {
+    pub recipient: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct FaucetNoteSeeded {
+    pub vault: Pubkey,
+    pub note_index: u64,
+    pub amount: u64,
+    /// Published note secrets; anyone can spend a faucet note
+    pub secret: [u8; 32],
+    pub nullifier_secret: [u8; 32],
+    pub commitment: [u8; 32],
+    pub leaf_index: u64,
+    pub tree_epoch: u64,
+}
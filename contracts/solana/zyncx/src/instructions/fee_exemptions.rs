@@ -0,0 +1,95 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ZyncxError;
+use crate::state::{FeeExemptions, Roles, MAX_FEE_EXEMPTIONS};
+
+#[derive(Accounts)]
+pub struct InitializeFeeExemptions<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = FeeExemptions::INIT_SPACE,
+        seeds = [b"fee_exemptions"],
+        bump
+    )]
+    pub fee_exemptions: Box<Account<'info, FeeExemptions>>,
+
+    #[account(
+        seeds = [b"roles"],
+        bump = roles.bump,
+        constraint = roles.fee_manager == authority.key() @ ZyncxError::Unauthorized,
+    )]
+    pub roles: Box<Account<'info, Roles>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Create the fee exemption list. Fee manager role only.
+pub fn handler_initialize_fee_exemptions(ctx: Context<InitializeFeeExemptions>) -> Result<()> {
+    let fee_exemptions = &mut ctx.accounts.fee_exemptions;
+    fee_exemptions.bump = ctx.bumps.fee_exemptions;
+    fee_exemptions.exempt = Vec::new();
+
+    msg!("Fee exemption list initialized");
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdateFeeExemptions<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"fee_exemptions"],
+        bump = fee_exemptions.bump,
+    )]
+    pub fee_exemptions: Box<Account<'info, FeeExemptions>>,
+
+    #[account(
+        seeds = [b"roles"],
+        bump = roles.bump,
+        constraint = roles.fee_manager == authority.key() @ ZyncxError::Unauthorized,
+    )]
+    pub roles: Box<Account<'info, Roles>>,
+}
+
+pub fn handler_add_exemption(ctx: Context<UpdateFeeExemptions>, account: Pubkey) -> Result<()> {
+    let fee_exemptions = &mut ctx.accounts.fee_exemptions;
+
+    require!(!fee_exemptions.contains(&account), ZyncxError::AlreadyExempt);
+    require!(
+        fee_exemptions.exempt.len() < MAX_FEE_EXEMPTIONS,
+        ZyncxError::FeeExemptionsFull
+    );
+
+    fee_exemptions.exempt.push(account);
+
+    emit!(FeeExemptionUpdated { account, exempt: true });
+
+    Ok(())
+}
+
+pub fn handler_remove_exemption(ctx: Context<UpdateFeeExemptions>, account: Pubkey) -> Result<()> {
+    let fee_exemptions = &mut ctx.accounts.fee_exemptions;
+
+    let index = fee_exemptions
+        .exempt
+        .iter()
+        .position(|exempt| *exempt == account)
+        .ok_or(ZyncxError::NotExempt)?;
+    fee_exemptions.exempt.swap_remove(index);
+
+    emit!(FeeExemptionUpdated { account, exempt: false });
+
+    Ok(())
+}
+
+#[event]
+pub struct FeeExemptionUpdated {
+    pub account: Pubkey,
+    pub exempt: bool,
+}
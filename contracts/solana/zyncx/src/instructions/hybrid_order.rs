@@ -0,0 +1,127 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ZyncxError;
+use crate::state::{EncryptedHybridOrder, HybridOrderStatus, VaultState};
+
+#[derive(Accounts)]
+#[instruction(order_id: u64)]
+pub struct PlaceHybridOrder<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        seeds = [b"vault", source_vault.asset_mint.as_ref()],
+        bump = source_vault.bump,
+    )]
+    pub source_vault: Box<Account<'info, VaultState>>,
+
+    #[account(
+        seeds = [b"vault", dest_vault.asset_mint.as_ref()],
+        bump = dest_vault.bump,
+        constraint = dest_vault.key() != source_vault.key() @ ZyncxError::InvalidSwapRoute,
+    )]
+    pub dest_vault: Box<Account<'info, VaultState>>,
+
+    /// CHECK: Pyth price account; parsed on every evaluation
+    pub price_feed: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + EncryptedHybridOrder::INIT_SPACE,
+        seeds = [b"hybrid_order", user.key().as_ref(), &order_id.to_le_bytes()],
+        bump,
+    )]
+    pub hybrid_order: Box<Account<'info, EncryptedHybridOrder>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Place a hybrid TWAP/limit order. `encrypted_params` are the client's
+/// Enc<Shared, HybridOrderParams> ciphertexts; nothing about the limit price,
+/// duration or side is visible on-chain.
+pub fn handler_place_hybrid_order(
+    ctx: Context<PlaceHybridOrder>,
+    order_id: u64,
+    encrypted_params: [[u8; 32]; 3],
+    params_nonce: u128,
+    client_pubkey: [u8; 32],
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let order = &mut ctx.accounts.hybrid_order;
+
+    order.bump = ctx.bumps.hybrid_order;
+    order.user = ctx.accounts.user.key();
+    order.source_vault = ctx.accounts.source_vault.key();
+    order.dest_vault = ctx.accounts.dest_vault.key();
+    order.order_id = order_id;
+    order.encrypted_params = encrypted_params;
+    order.params_nonce = params_nonce;
+    order.client_pubkey = client_pubkey;
+    order.price_feed = ctx.accounts.price_feed.key();
+    order.status = HybridOrderStatus::Active;
+    order.created_at = now;
+    order.last_evaluated_at = 0;
+    order.evaluations = 0;
+
+    emit!(HybridOrderPlaced {
+        order: order.key(),
+        user: order.user,
+        source_vault: order.source_vault,
+        dest_vault: order.dest_vault,
+        price_feed: order.price_feed,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CancelHybridOrder<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        close = user,
+        has_one = user @ ZyncxError::Unauthorized,
+        seeds = [b"hybrid_order", user.key().as_ref(), &hybrid_order.order_id.to_le_bytes()],
+        bump = hybrid_order.bump,
+    )]
+    pub hybrid_order: Box<Account<'info, EncryptedHybridOrder>>,
+}
+
+/// Cancel a hybrid order and reclaim its rent. Not allowed while an
+/// evaluation is in flight, so the callback always finds its account.
+pub fn handler_cancel_hybrid_order(ctx: Context<CancelHybridOrder>) -> Result<()> {
+    let order = &ctx.accounts.hybrid_order;
+    require!(
+        order.status != HybridOrderStatus::Evaluating,
+        ZyncxError::InvalidComputationStatus
+    );
+
+    emit!(HybridOrderCancelled {
+        order: order.key(),
+        user: order.user,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct HybridOrderPlaced {
+    pub order: Pubkey,
+    pub user: Pubkey,
+    pub source_vault: Pubkey,
+    pub dest_vault: Pubkey,
+    pub price_feed: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct HybridOrderCancelled {
+    pub order: Pubkey,
+    pub user: Pubkey,
+    pub timestamp: i64,
+}
@@ -1,6 +1,11 @@
 use anchor_lang::prelude::*;
 
-use crate::state::{MerkleTreeState, VaultState, VaultType};
+use crate::dex::canonical_asset_mint;
+use crate::errors::ZyncxError;
+use crate::state::{
+    GlobalStats, MerkleTreeState, CURRENT_ACCOUNT_VERSION, ProofSystem, Roles, TreeHash, VaultLifecycle, VaultState, VaultType, DEFAULT_ROTATION_THRESHOLD_BPS, MAX_DENOMINATIONS,
+    FRONTIER_SIZE, MAX_TREE_LEAVES,
+};
 
 pub const NATIVE_MINT: Pubkey = Pubkey::new_from_array([0u8; 32]); // Represents SOL
 
@@ -10,6 +15,13 @@ pub struct InitializeVault<'info> {
     #[account(mut)]
     pub authority: Signer<'info>,
 
+    #[account(
+        seeds = [b"roles"],
+        bump = roles.bump,
+        constraint = roles.admin == authority.key() @ crate::errors::ZyncxError::Unauthorized,
+    )]
+    pub roles: Box<Account<'info, Roles>>,
+
     #[account(
         init,
         payer = authority,
@@ -28,10 +40,25 @@ pub struct InitializeVault<'info> {
     )]
     pub merkle_tree: Box<Account<'info, MerkleTreeState>>,
 
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = GlobalStats::INIT_SPACE,
+        seeds = [b"global_stats"],
+        bump
+    )]
+    pub global_stats: Box<Account<'info, GlobalStats>>,
+
     pub system_program: Program<'info, System>,
 }
 
 pub fn handler(ctx: Context<InitializeVault>, asset_mint: Pubkey) -> Result<()> {
+    // A WSOL vault would fragment SOL liquidity away from the native vault
+    require!(
+        asset_mint == canonical_asset_mint(&asset_mint),
+        ZyncxError::NonCanonicalAssetMint
+    );
+
     let vault = &mut ctx.accounts.vault;
     let merkle_tree = &mut ctx.accounts.merkle_tree;
 
@@ -50,6 +77,27 @@ pub fn handler(ctx: Context<InitializeVault>, asset_mint: Pubkey) -> Result<()>
     vault.nonce = 0;
     vault.authority = ctx.accounts.authority.key();
     vault.total_deposited = 0;
+    vault.tree_epoch = 0;
+    vault.max_tree_leaves = MAX_TREE_LEAVES as u32;
+    vault.rotation_threshold_bps = DEFAULT_ROTATION_THRESHOLD_BPS;
+    vault.fixed_denominations = false;
+    vault.denomination_count = 0;
+    vault.denominations = [0u64; MAX_DENOMINATIONS];
+    vault.config_timelock_seconds = 0;
+    vault.paused = false;
+    vault.max_total_deposited = 0;
+    vault.max_deposit_per_tx = 0;
+    vault.max_deposit_per_epoch = 0;
+    vault.deposit_epoch = 0;
+    vault.epoch_deposited = 0;
+    vault.min_note_age_slots = 0;
+    vault.sunset_inactivity_epochs = 0;
+    vault.sunset_grace_seconds = 0;
+    vault.lifecycle = VaultLifecycle::Active;
+    vault.archive_at = 0;
+    vault.proof_system = ProofSystem::UltraHonk;
+    vault.tree_hash = TreeHash::Keccak;
+    vault.version = CURRENT_ACCOUNT_VERSION;
 
     // Initialize merkle tree state
     merkle_tree.bump = ctx.bumps.merkle_tree;
@@ -58,7 +106,16 @@ pub fn handler(ctx: Context<InitializeVault>, asset_mint: Pubkey) -> Result<()>
     merkle_tree.current_root_index = 0;
     merkle_tree.root = [0u8; 32];
     merkle_tree.roots = [[0u8; 32]; crate::state::merkle_tree::ROOT_HISTORY_SIZE];
-    merkle_tree.leaves = Vec::new();
+    merkle_tree.vault = vault.key();
+    merkle_tree.epoch = 0;
+    merkle_tree.max_leaves = MAX_TREE_LEAVES as u32;
+    merkle_tree.tree_hash = vault.tree_hash;
+    merkle_tree.frontier = [[0u8; 32]; FRONTIER_SIZE];
+    merkle_tree.version = CURRENT_ACCOUNT_VERSION;
+
+    let global_stats = &mut ctx.accounts.global_stats;
+    global_stats.ensure_initialized(ctx.bumps.global_stats);
+    global_stats.record_vault_activated(Clock::get()?.unix_timestamp);
 
     msg!("Vault initialized for asset: {:?}", asset_mint);
     msg!("Vault type: {:?}", vault_type as u8);
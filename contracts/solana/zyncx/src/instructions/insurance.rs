@@ -0,0 +1,247 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ZyncxError;
+use crate::state::{
+    ClaimPayout, InsuranceClaim, InsuranceClaimStatus, MerkleTreeState,
+    Role, Roles, TreeInsertion, VaultFeeState, VaultState,
+};
+
+#[derive(Accounts)]
+#[instruction(claim_id: u64)]
+pub struct ProposeInsuranceClaim<'info> {
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    #[account(
+        seeds = [b"vault", vault.asset_mint.as_ref()],
+        bump = vault.bump,
+    )]
+    pub vault: Box<Account<'info, VaultState>>,
+
+    #[account(
+        init,
+        payer = proposer,
+        space = InsuranceClaim::INIT_SPACE,
+        seeds = [b"insurance_claim", vault.key().as_ref(), &claim_id.to_le_bytes()],
+        bump
+    )]
+    pub claim: Box<Account<'info, InsuranceClaim>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Propose paying affected users out of the vault's insurance reserve.
+/// Anyone may propose; nothing moves until a quorum of roles approves.
+pub fn handler_propose_insurance_claim(
+    ctx: Context<ProposeInsuranceClaim>,
+    claim_id: u64,
+    evidence_hash: [u8; 32],
+    payouts: Vec<ClaimPayout>,
+) -> Result<()> {
+    let total = InsuranceClaim::payout_total(&payouts)?;
+    let now = Clock::get()?.unix_timestamp;
+
+    let claim = &mut ctx.accounts.claim;
+    claim.bump = ctx.bumps.claim;
+    claim.vault = ctx.accounts.vault.key();
+    claim.claim_id = claim_id;
+    claim.proposer = ctx.accounts.proposer.key();
+    claim.evidence_hash = evidence_hash;
+    claim.payouts = payouts;
+    claim.total = total;
+    claim.approvals = 0;
+    claim.rejections = 0;
+    claim.status = InsuranceClaimStatus::Voting;
+    claim.proposed_at = now;
+    claim.resolved_at = 0;
+
+    emit!(InsuranceClaimProposed {
+        claim: claim.key(),
+        vault: claim.vault,
+        claim_id,
+        proposer: claim.proposer,
+        evidence_hash,
+        payout_count: claim.payouts.len() as u8,
+        total,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct VoteInsuranceClaim<'info> {
+    pub voter: Signer<'info>,
+
+    #[account(seeds = [b"roles"], bump = roles.bump)]
+    pub roles: Box<Account<'info, Roles>>,
+
+    #[account(
+        mut,
+        seeds = [b"insurance_claim", claim.vault.as_ref(), &claim.claim_id.to_le_bytes()],
+        bump = claim.bump,
+    )]
+    pub claim: Box<Account<'info, InsuranceClaim>>,
+}
+
+/// Approve or reject a claim on behalf of a protocol role the voter holds
+pub fn handler_vote_insurance_claim(
+    ctx: Context<VoteInsuranceClaim>,
+    role: Role,
+    approve: bool,
+) -> Result<()> {
+    require!(
+        ctx.accounts.roles.has_role(role, &ctx.accounts.voter.key()),
+        ZyncxError::Unauthorized
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    let claim = &mut ctx.accounts.claim;
+    claim.record_vote(role, approve)?;
+    if claim.status == InsuranceClaimStatus::Rejected {
+        claim.resolved_at = now;
+    }
+
+    emit!(InsuranceClaimVoted {
+        claim: claim.key(),
+        voter: ctx.accounts.voter.key(),
+        role,
+        approve,
+        approvals: claim.approvals,
+        rejections: claim.rejections,
+        status: claim.status,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ExecuteInsuranceClaim<'info> {
+    #[account(mut)]
+    pub executor: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault.asset_mint.as_ref()],
+        bump = vault.bump,
+    )]
+    pub vault: Box<Account<'info, VaultState>>,
+
+    #[account(
+        mut,
+        address = vault.merkle_tree @ ZyncxError::InactiveMerkleTree,
+    )]
+    pub merkle_tree: Box<Account<'info, MerkleTreeState>>,
+
+    #[account(
+        mut,
+        seeds = [b"vault_fees", vault.key().as_ref()],
+        bump = vault_fees.bump,
+    )]
+    pub vault_fees: Box<Account<'info, VaultFeeState>>,
+
+    #[account(
+        mut,
+        has_one = vault,
+        seeds = [b"insurance_claim", vault.key().as_ref(), &claim.claim_id.to_le_bytes()],
+        bump = claim.bump,
+    )]
+    pub claim: Box<Account<'info, InsuranceClaim>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Pay an approved claim: debit the insurance reserve and insert one new
+/// commitment per payout. The funds never leave the vault; they move from
+/// retained fees to notes the affected users can withdraw. Permissionless
+/// once approved. Payout notes are inserted in `payouts` order; returns
+/// their insertions in that order.
+pub fn handler_execute_insurance_claim(
+    ctx: Context<ExecuteInsuranceClaim>,
+) -> Result<Vec<TreeInsertion>> {
+    let claim = &mut ctx.accounts.claim;
+    require!(claim.is_approved(), ZyncxError::InsuranceClaimNotApproved);
+
+    ctx.accounts.vault_fees.pay_insurance(claim.total)?;
+
+    let claim_key = claim.key();
+    let commitments = claim
+        .payouts
+        .iter()
+        .map(|payout| ctx.accounts.merkle_tree.commitment(payout.amount, payout.precommitment))
+        .collect::<Result<Vec<_>>>()?;
+    let insertions = ctx.accounts.merkle_tree.insert_many(&commitments)?;
+    ctx.accounts.vault.nonce += insertions.len() as u64;
+
+    for ((payout, commitment), insertion) in
+        claim.payouts.iter().zip(commitments).zip(insertions.iter())
+    {
+        emit!(InsurancePayout {
+            claim: claim_key,
+            commitment,
+            amount: payout.amount,
+            leaf_index: insertion.leaf_index,
+            tree_epoch: insertion.tree_epoch,
+        });
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    claim.status = InsuranceClaimStatus::Executed;
+    claim.resolved_at = now;
+
+    emit!(InsuranceClaimExecuted {
+        claim: claim_key,
+        vault: claim.vault,
+        total: claim.total,
+        reserve_remaining: ctx.accounts.vault_fees.insurance_reserve,
+        total_paid: ctx.accounts.vault_fees.insurance_paid,
+        timestamp: now,
+    });
+
+    Ok(insertions)
+}
+
+#[event]
+pub struct InsuranceClaimProposed {
+    pub claim: Pubkey,
+    pub vault: Pubkey,
+    pub claim_id: u64,
+    pub proposer: Pubkey,
+    pub evidence_hash: [u8; 32],
+    pub payout_count: u8,
+    pub total: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct InsuranceClaimVoted {
+    pub claim: Pubkey,
+    pub voter: Pubkey,
+    pub role: Role,
+    pub approve: bool,
+    pub approvals: u8,
+    pub rejections: u8,
+    pub status: InsuranceClaimStatus,
+    pub timestamp: i64,
+}
+
+/// One new note minted by an executed claim
+#[event]
+pub struct InsurancePayout {
+    pub claim: Pubkey,
+    pub commitment: [u8; 32],
+    pub amount: u64,
+    pub leaf_index: u64,
+    pub tree_epoch: u64,
+}
+
+#[event]
+pub struct InsuranceClaimExecuted {
+    pub claim: Pubkey,
+    pub vault: Pubkey,
+    pub total: u64,
+    pub reserve_remaining: u64,
+    pub total_paid: u64,
+    pub timestamp: i64,
+}
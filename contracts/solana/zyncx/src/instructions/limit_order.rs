@@ -0,0 +1,288 @@
+use anchor_lang::prelude::*;
+use arcium_anchor::prelude::*;
+use arcium_client::idl::arcium::types::CallbackAccount;
+
+use crate::errors::ZyncxError;
+use crate::instructions::mempool::check_mempool_depth;
+use crate::instructions::treasury::pay_from_vault_treasury;
+use crate::state::{
+    fallback_oracle, load_order_price, EncryptedLimitOrder, LimitOrderStatus, VaultState,
+    CRANK_TIP_LAMPORTS, LIMIT_ORDER_CRANK_BATCH,
+};
+use crate::{
+    CrankLimitOrders, EvaluateLimitOrderBatchCallback, LimitOrderExpired,
+    COMP_DEF_OFFSET_EVALUATE_LIMIT_ORDER_BATCH,
+};
+
+#[derive(Accounts)]
+#[instruction(order_id: u64)]
+pub struct PlaceLimitOrder<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        seeds = [b"vault", source_vault.asset_mint.as_ref()],
+        bump = source_vault.bump,
+    )]
+    pub source_vault: Box<Account<'info, VaultState>>,
+
+    #[account(
+        seeds = [b"vault", dest_vault.asset_mint.as_ref()],
+        bump = dest_vault.bump,
+        constraint = dest_vault.key() != source_vault.key() @ ZyncxError::InvalidSwapRoute,
+    )]
+    pub dest_vault: Box<Account<'info, VaultState>>,
+
+    /// CHECK: Pyth price account; parsed on every evaluation
+    pub price_feed: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + EncryptedLimitOrder::INIT_SPACE,
+        seeds = [b"limit_order", user.key().as_ref(), &order_id.to_le_bytes()],
+        bump,
+    )]
+    pub limit_order: Box<Account<'info, EncryptedLimitOrder>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Place a limit order. `encrypted_params` are the client's
+/// Enc<Shared, LimitOrderParams> ciphertexts; only the expiry is public.
+pub fn handler_place_limit_order(
+    ctx: Context<PlaceLimitOrder>,
+    order_id: u64,
+    encrypted_params: [[u8; 32]; 3],
+    params_nonce: u128,
+    client_pubkey: [u8; 32],
+    expires_at: i64,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    require!(expires_at > now, ZyncxError::ComputationExpired);
+
+    let order = &mut ctx.accounts.limit_order;
+    order.bump = ctx.bumps.limit_order;
+    order.user = ctx.accounts.user.key();
+    order.source_vault = ctx.accounts.source_vault.key();
+    order.dest_vault = ctx.accounts.dest_vault.key();
+    order.encrypted_params = encrypted_params;
+    order.params_nonce = params_nonce;
+    order.client_pubkey = client_pubkey;
+    order.expires_at = expires_at;
+    order.status = LimitOrderStatus::Active;
+    order.created_at = now;
+    order.order_id = order_id;
+    order.price_feed = ctx.accounts.price_feed.key();
+    order.last_evaluated_at = 0;
+    order.matched_with = Pubkey::default();
+    order.encrypted_fill = [0u8; 32];
+    order.fill_nonce = 0;
+
+    emit!(LimitOrderPlaced {
+        order: order.key(),
+        user: order.user,
+        source_vault: order.source_vault,
+        dest_vault: order.dest_vault,
+        price_feed: order.price_feed,
+        expires_at,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CancelLimitOrder<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        close = user,
+        has_one = user @ ZyncxError::Unauthorized,
+        seeds = [b"limit_order", user.key().as_ref(), &limit_order.order_id.to_le_bytes()],
+        bump = limit_order.bump,
+    )]
+    pub limit_order: Box<Account<'info, EncryptedLimitOrder>>,
+}
+
+/// Cancel (or clean up an executed/matched/expired) limit order and refund its
+/// rent. Not allowed while an evaluation or match is in flight, so the
+/// callback always finds its account.
+pub fn handler_cancel_limit_order(ctx: Context<CancelLimitOrder>) -> Result<()> {
+    let order = &ctx.accounts.limit_order;
+    require!(
+        order.status != LimitOrderStatus::Evaluating && order.status != LimitOrderStatus::Matching,
+        ZyncxError::InvalidComputationStatus
+    );
+
+    emit!(LimitOrderCancelled {
+        order: order.key(),
+        user: order.user,
+        status: order.status,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Mark expired orders among the remaining accounts and queue one MXE
+/// evaluation of the due ones, tipping the cranker out of retained SOL fees
+pub fn handler_crank_limit_orders(
+    ctx: Context<CrankLimitOrders>,
+    computation_offset: u64,
+) -> Result<()> {
+    require!(
+        ctx.remaining_accounts.len() <= LIMIT_ORDER_CRANK_BATCH,
+        ZyncxError::CrankBatchTooLarge
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    let price_feed = ctx.accounts.price_feed.key();
+    let source_vault = ctx.accounts.source_vault.key();
+
+    let mut due: Vec<(Pubkey, EncryptedLimitOrder)> = Vec::new();
+    let mut expired: u32 = 0;
+
+    for info in ctx.remaining_accounts.iter() {
+        if info.owner != &crate::ID || !info.is_writable {
+            continue;
+        }
+        let mut order = {
+            let data = info.try_borrow_data()?;
+            match EncryptedLimitOrder::try_deserialize(&mut &data[..]) {
+                Ok(order) => order,
+                Err(_) => continue,
+            }
+        };
+
+        if order.status == LimitOrderStatus::Active && now >= order.expires_at {
+            order.status = LimitOrderStatus::Expired;
+            order.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+            expired += 1;
+
+            emit!(LimitOrderExpired {
+                order: info.key(),
+                timestamp: now,
+            });
+        } else if order.is_due(now)
+            && order.price_feed == price_feed
+            && order.source_vault == source_vault
+        {
+            order.status = LimitOrderStatus::Evaluating;
+            order.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+            due.push((info.key(), order));
+        }
+    }
+
+    let mut tip = 0;
+    if !due.is_empty() {
+        let current_price = load_order_price(
+            &ctx.accounts.price_feed,
+            &ctx.accounts.price_feed_registry,
+            fallback_oracle(&ctx.accounts.oracle_fallback, &ctx.accounts.fallback_price_feed),
+            &ctx.accounts.source_vault.asset_mint,
+            &ctx.accounts.arcium_config.price_guards(),
+        )?;
+
+        // Short batches repeat the last due order; the callback only
+        // applies bits for the accounts it is handed.
+        let mut args = ArgBuilder::new();
+        for slot in 0..LIMIT_ORDER_CRANK_BATCH {
+            let (_, order) = &due[slot.min(due.len() - 1)];
+            args = args
+                .x25519_pubkey(order.client_pubkey)
+                .plaintext_u128(order.params_nonce)
+                .encrypted_u64(order.encrypted_params[0])
+                .encrypted_u64(order.encrypted_params[1])
+                .encrypted_u64(order.encrypted_params[2]);
+        }
+        let args = args.plaintext_u64(current_price).build();
+
+        let callback_accounts: Vec<CallbackAccount> = due
+            .iter()
+            .map(|(key, _)| CallbackAccount {
+                pubkey: *key,
+                is_writable: true,
+            })
+            .collect();
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        check_mempool_depth(
+            &ctx.accounts.mempool_account,
+            &ctx.accounts.mempool_limits,
+            COMP_DEF_OFFSET_EVALUATE_LIMIT_ORDER_BATCH,
+        )?;
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![EvaluateLimitOrderBatchCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &callback_accounts,
+            )?],
+            1,
+            0,
+        )?;
+
+        // Tip out of fees retained in the native vault treasury
+        tip = ctx
+            .accounts
+            .vault_fees
+            .draw_fees(CRANK_TIP_LAMPORTS * due.len() as u64);
+        pay_from_vault_treasury(
+            &ctx.accounts.system_program,
+            &ctx.accounts.vault_treasury,
+            &ctx.accounts.payer.to_account_info(),
+            &ctx.accounts.fee_vault.key(),
+            ctx.bumps.vault_treasury,
+            tip,
+        )?;
+    }
+
+    emit!(LimitOrdersCranked {
+        cranker: ctx.accounts.payer.key(),
+        computation_offset,
+        evaluated: due.len() as u32,
+        expired,
+        tip,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct LimitOrderPlaced {
+    pub order: Pubkey,
+    pub user: Pubkey,
+    pub source_vault: Pubkey,
+    pub dest_vault: Pubkey,
+    pub price_feed: Pubkey,
+    pub expires_at: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LimitOrderCancelled {
+    pub order: Pubkey,
+    pub user: Pubkey,
+    /// Status at the time the account was closed
+    pub status: LimitOrderStatus,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LimitOrdersCranked {
+    pub cranker: Pubkey,
+    pub computation_offset: u64,
+    pub evaluated: u32,
+    pub expired: u32,
+    pub tip: u64,
+    pub timestamp: i64,
+}
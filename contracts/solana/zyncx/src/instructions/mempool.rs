@@ -0,0 +1,125 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ZyncxError;
+use crate::state::{
+    mempool_depth, MempoolDepthLimit, MempoolLimits, Role, Roles, MAX_MEMPOOL_LIMITS,
+};
+
+#[derive(Accounts)]
+pub struct SetMempoolLimit<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(seeds = [b"roles"], bump = roles.bump)]
+    pub roles: Box<Account<'info, Roles>>,
+
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = MempoolLimits::INIT_SPACE,
+        seeds = [b"mempool_limits"],
+        bump
+    )]
+    pub mempool_limits: Box<Account<'info, MempoolLimits>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Set (or replace) the deepest mempool computations of `comp_def_offset`
+/// are queued into. A `max_depth` of zero removes the limit. Admin role only.
+pub fn handler_set_mempool_limit(
+    ctx: Context<SetMempoolLimit>,
+    comp_def_offset: u32,
+    max_depth: u32,
+    reject: bool,
+) -> Result<()> {
+    require!(
+        ctx.accounts.roles.has_role(Role::Admin, &ctx.accounts.admin.key()),
+        ZyncxError::Unauthorized
+    );
+
+    let mempool_limits = &mut ctx.accounts.mempool_limits;
+    mempool_limits.bump = ctx.bumps.mempool_limits;
+
+    if max_depth == 0 {
+        mempool_limits
+            .limits
+            .retain(|l| l.comp_def_offset != comp_def_offset);
+    } else {
+        let entry = MempoolDepthLimit {
+            comp_def_offset,
+            max_depth,
+            reject,
+        };
+        match mempool_limits
+            .limits
+            .iter_mut()
+            .find(|l| l.comp_def_offset == comp_def_offset)
+        {
+            Some(existing) => *existing = entry,
+            None => {
+                require!(
+                    mempool_limits.limits.len() < MAX_MEMPOOL_LIMITS,
+                    ZyncxError::MempoolLimitsFull
+                );
+                mempool_limits.limits.push(entry);
+            }
+        }
+    }
+
+    emit!(MempoolLimitSet {
+        comp_def_offset,
+        max_depth,
+        reject,
+    });
+
+    Ok(())
+}
+
+/// Read the cluster mempool depth ahead of queueing a `comp_def_offset`
+/// computation and emit it. Over a rejecting limit the queue instruction
+/// fails here rather than timing out in the mempool.
+pub fn check_mempool_depth(
+    mempool: &AccountInfo,
+    mempool_limits: &AccountInfo,
+    comp_def_offset: u32,
+) -> Result<()> {
+    let depth = mempool_depth(mempool)?;
+    let limit = MempoolLimits::load_limit(mempool_limits, comp_def_offset)?;
+    let over_limit = limit.is_some_and(|l| depth > l.max_depth);
+
+    emit!(MempoolDepthObserved {
+        comp_def_offset,
+        depth,
+        max_depth: limit.map_or(0, |l| l.max_depth),
+        over_limit,
+    });
+
+    if over_limit {
+        require!(
+            !limit.is_some_and(|l| l.reject),
+            ZyncxError::MempoolBackpressure
+        );
+        msg!("Mempool depth {} over limit; queueing anyway", depth);
+    }
+
+    Ok(())
+}
+
+#[event]
+pub struct MempoolLimitSet {
+    pub comp_def_offset: u32,
+    /// Zero when the limit was removed
+    pub max_depth: u32,
+    pub reject: bool,
+}
+
+#[event]
+pub struct MempoolDepthObserved {
+    pub comp_def_offset: u32,
+    /// Computations waiting in the cluster mempool before this one
+    pub depth: u32,
+    /// Configured limit (zero if none)
+    pub max_depth: u32,
+    pub over_limit: bool,
+}
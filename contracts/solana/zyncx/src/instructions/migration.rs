@@ -0,0 +1,97 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+
+use crate::errors::ZyncxError;
+use crate::state::{
+    ArciumConfig, EncryptedUserPosition, EncryptedVaultAccount, MerkleTreeState, MigratableAccount, VaultState,
+    Versioned, CURRENT_ACCOUNT_VERSION,
+};
+
+#[derive(Accounts)]
+pub struct MigrateAccount<'info> {
+    /// CHECK: Program account of the given kind; owner and discriminator are
+    /// checked in the handler, as an old layout does not deserialize
+    #[account(mut, owner = crate::ID @ ErrorCode::AccountOwnedByWrongProgram)]
+    pub account: UncheckedAccount<'info>,
+
+    /// Covers the rent of the grown account
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Upgrade an account to the current layout. Permissionless: the upgrade is
+/// the same whoever runs it, and the payer only funds the extra rent.
+pub fn handler_migrate_account(ctx: Context<MigrateAccount>, kind: MigratableAccount) -> Result<()> {
+    let (from_version, to_version) = match kind {
+        MigratableAccount::Vault => migrate::<VaultState>(&ctx)?,
+        MigratableAccount::MerkleTree => migrate::<MerkleTreeState>(&ctx)?,
+        MigratableAccount::EncryptedVault => migrate::<EncryptedVaultAccount>(&ctx)?,
+        MigratableAccount::EncryptedPosition => migrate::<EncryptedUserPosition>(&ctx)?,
+        MigratableAccount::ArciumConfig => migrate::<ArciumConfig>(&ctx)?,
+    };
+
+    emit!(AccountMigrated {
+        account: ctx.accounts.account.key(),
+        kind,
+        from_version,
+        to_version,
+    });
+
+    msg!("Migrated account from version {} to {}", from_version, to_version);
+
+    Ok(())
+}
+
+/// Grow the account to `T::SPACE`, upgrade it one version at a time and
+/// stamp the current version
+fn migrate<T: Versioned>(ctx: &Context<MigrateAccount>) -> Result<(u8, u8)> {
+    let account = ctx.accounts.account.to_account_info();
+    {
+        let data = account.try_borrow_data()?;
+        require!(
+            data.len() >= 8 && data[..8] == *T::DISCRIMINATOR,
+            ErrorCode::AccountDiscriminatorMismatch
+        );
+    }
+
+    if account.data_len() < T::SPACE {
+        let rent = Rent::get()?.minimum_balance(T::SPACE);
+        let shortfall = rent.saturating_sub(account.lamports());
+        if shortfall > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.payer.to_account_info(),
+                        to: account.clone(),
+                    },
+                ),
+                shortfall,
+            )?;
+        }
+        // Fields appended since the account's layout read as zero
+        account.resize(T::SPACE)?;
+    }
+
+    let mut state = T::try_deserialize(&mut &account.try_borrow_data()?[..])?;
+    let from_version = state.version();
+    require!(from_version < CURRENT_ACCOUNT_VERSION, ZyncxError::AccountUpToDate);
+
+    for version in from_version..CURRENT_ACCOUNT_VERSION {
+        state.upgrade_from(version);
+    }
+    state.set_version(CURRENT_ACCOUNT_VERSION);
+    state.try_serialize(&mut &mut account.try_borrow_mut_data()?[..])?;
+
+    Ok((from_version, CURRENT_ACCOUNT_VERSION))
+}
+
+#[event]
+pub struct AccountMigrated {
+    pub account: Pubkey,
+    pub kind: MigratableAccount,
+    pub from_version: u8,
+    pub to_version: u8,
+}
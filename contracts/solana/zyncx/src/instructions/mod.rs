@@ -3,9 +3,85 @@ pub mod deposit;
 pub mod withdraw;
 pub mod swap;
 pub mod verify;
+pub mod program_info;
+pub mod swap_request;
+pub mod tree;
+pub mod viewing_key;
+pub mod vault_config;
+pub mod exclusion;
+pub mod plan;
+pub mod blocklist;
+pub mod roles;
+pub mod fee_exemptions;
+pub mod vault_token;
+pub mod hybrid_order;
+pub mod limit_order;
+pub mod dca;
+pub mod sunset;
+pub mod referral;
+pub mod proof_failures;
+pub mod auction;
+pub mod insurance;
+pub mod twap;
+pub mod stop_order;
+pub mod treasury;
+pub mod arcium_config;
+pub mod allowance;
+pub mod oracle;
+pub mod note_backup;
+pub mod rfq;
+pub mod private_transfer;
+pub mod mempool;
+pub mod stealth;
+pub mod withdraw_batch;
+pub mod verification_key;
+pub mod protocol_config;
+pub mod relayer;
+pub mod simulate;
+pub mod migration;
+#[cfg(feature = "faucet")]
+pub mod faucet;
 
 pub use initialize::*;
 pub use deposit::*;
 pub use withdraw::*;
 pub use swap::*;
 pub use verify::*;
+pub use program_info::*;
+pub use swap_request::*;
+pub use tree::*;
+pub use viewing_key::*;
+pub use vault_config::*;
+pub use exclusion::*;
+pub use plan::*;
+pub use blocklist::*;
+pub use roles::*;
+pub use fee_exemptions::*;
+pub use vault_token::*;
+pub use hybrid_order::*;
+pub use limit_order::*;
+pub use dca::*;
+pub use sunset::*;
+pub use referral::*;
+pub use proof_failures::*;
+pub use auction::*;
+pub use insurance::*;
+pub use twap::*;
+pub use stop_order::*;
+pub use treasury::*;
+pub use arcium_config::*;
+pub use allowance::*;
+pub use oracle::*;
+pub use note_backup::*;
+pub use rfq::*;
+pub use private_transfer::*;
+pub use mempool::*;
+pub use stealth::*;
+pub use withdraw_batch::*;
+pub use verification_key::*;
+pub use protocol_config::*;
+pub use relayer::*;
+pub use simulate::*;
+pub use migration::*;
+#[cfg(feature = "faucet")]
+pub use faucet::*;
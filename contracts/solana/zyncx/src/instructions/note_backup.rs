@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ZyncxError;
+use crate::state::NoteBackup;
+
+#[derive(Accounts)]
+pub struct CloseNoteBackup<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        close = owner,
+        has_one = owner @ ZyncxError::Unauthorized,
+    )]
+    pub note_backup: Box<Account<'info, NoteBackup>>,
+}
+
+/// Delete one of the caller's note backups and reclaim its rent, typically
+/// once the note is spent. The ciphertext stays in the deposit's event log.
+pub fn handler_close_backup(ctx: Context<CloseNoteBackup>) -> Result<()> {
+    emit!(NoteBackupClosed {
+        owner: ctx.accounts.owner.key(),
+        commitment: ctx.accounts.note_backup.commitment,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct NoteBackupClosed {
+    pub owner: Pubkey,
+    pub commitment: [u8; 32],
+}
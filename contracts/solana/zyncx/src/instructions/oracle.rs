@@ -0,0 +1,206 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ZyncxError;
+use crate::state::{
+    load_pyth_price, CachedPriceFeed, OracleFallback, OracleSource, PriceFeedRegistry,
+    RegisteredPriceFeed, Role, Roles, MAX_PRICE_AGE_LIMIT, MAX_REGISTERED_PRICE_FEEDS,
+};
+
+#[derive(Accounts)]
+#[instruction(token_mint: Pubkey)]
+pub struct SetOracleFallback<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(seeds = [b"roles"], bump = roles.bump)]
+    pub roles: Box<Account<'info, Roles>>,
+
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = OracleFallback::INIT_SPACE,
+        seeds = [b"oracle_fallback", token_mint.as_ref()],
+        bump
+    )]
+    pub oracle_fallback: Box<Account<'info, OracleFallback>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Set (or replace) the oracle read for `token_mint` when its Pyth price is
+/// stale. Admin role only.
+pub fn handler_set_oracle_fallback(
+    ctx: Context<SetOracleFallback>,
+    token_mint: Pubkey,
+    source: OracleSource,
+) -> Result<()> {
+    require!(
+        ctx.accounts.roles.has_role(Role::Admin, &ctx.accounts.admin.key()),
+        ZyncxError::Unauthorized
+    );
+
+    let oracle_fallback = &mut ctx.accounts.oracle_fallback;
+    oracle_fallback.bump = ctx.bumps.oracle_fallback;
+    oracle_fallback.token_mint = token_mint;
+    oracle_fallback.source = source;
+    oracle_fallback.updated_at = Clock::get()?.unix_timestamp;
+
+    emit!(OracleFallbackSet { token_mint, source });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RegisterPriceFeed<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(seeds = [b"roles"], bump = roles.bump)]
+    pub roles: Box<Account<'info, Roles>>,
+
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = PriceFeedRegistry::INIT_SPACE,
+        seeds = [b"price_feed_registry"],
+        bump
+    )]
+    pub price_feed_registry: Box<Account<'info, PriceFeedRegistry>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Register the Pyth feed ID pricing `token_mint`, or replace its feed and
+/// age limit. Native SOL is priced by the wrapped SOL entry. Admin role only.
+pub fn handler_register_price_feed(
+    ctx: Context<RegisterPriceFeed>,
+    token_mint: Pubkey,
+    feed: [u8; 32],
+    max_age: i64,
+) -> Result<()> {
+    require!(
+        ctx.accounts.roles.has_role(Role::Admin, &ctx.accounts.admin.key()),
+        ZyncxError::Unauthorized
+    );
+    require!(feed != [0u8; 32], ZyncxError::InvalidPriceFeed);
+    require!(
+        max_age > 0 && max_age <= MAX_PRICE_AGE_LIMIT,
+        ZyncxError::InvalidPriceGuards
+    );
+
+    let registry = &mut ctx.accounts.price_feed_registry;
+    registry.bump = ctx.bumps.price_feed_registry;
+
+    let entry = RegisteredPriceFeed {
+        token_mint,
+        feed_id: feed,
+        max_age_seconds: max_age,
+    };
+    match registry.feeds.iter_mut().find(|f| f.token_mint == token_mint) {
+        Some(existing) => *existing = entry,
+        None => {
+            require!(
+                registry.feeds.len() < MAX_REGISTERED_PRICE_FEEDS,
+                ZyncxError::PriceFeedRegistryFull
+            );
+            registry.feeds.push(entry);
+        }
+    }
+
+    emit!(PriceFeedRegistered {
+        token_mint,
+        feed_id: feed,
+        max_age_seconds: max_age,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(token_mint: Pubkey)]
+pub struct UpdateCachedPrice<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(seeds = [b"price_feed_registry"], bump = price_feed_registry.bump)]
+    pub price_feed_registry: Box<Account<'info, PriceFeedRegistry>>,
+
+    /// CHECK: Pyth price update account; owner and feed ID are checked
+    /// against the token's registry entry
+    pub price_feed: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = CachedPriceFeed::INIT_SPACE,
+        seeds = [b"cached_price", token_mint.as_ref()],
+        bump
+    )]
+    pub cached_price_feed: Box<Account<'info, CachedPriceFeed>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Refresh the cached price of `token_mint` from its registered Pyth feed.
+/// Permissionless: the price must be within the feed's max age and newer
+/// than the one already cached.
+pub fn handler_update_cached_price(
+    ctx: Context<UpdateCachedPrice>,
+    token_mint: Pubkey,
+) -> Result<()> {
+    let registered = ctx
+        .accounts
+        .price_feed_registry
+        .feed_for(&token_mint)
+        .ok_or(ZyncxError::PriceFeedNotRegistered)?;
+    let price_data = load_pyth_price(&ctx.accounts.price_feed, &registered.feed_id)?;
+    require!(
+        !price_data.is_stale(registered.max_age_seconds),
+        ZyncxError::StalePriceFeed
+    );
+
+    let cached = &mut ctx.accounts.cached_price_feed;
+    require!(
+        price_data.publish_time > cached.price_data.publish_time,
+        ZyncxError::StalePriceFeed
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    cached.bump = ctx.bumps.cached_price_feed;
+    cached.token_mint = token_mint;
+    cached.pyth_feed = ctx.accounts.price_feed.key();
+    cached.price_data = price_data;
+    cached.last_updated = now;
+
+    emit!(CachedPriceUpdated {
+        token_mint,
+        price: price_data.price,
+        confidence: price_data.confidence,
+        exponent: price_data.exponent,
+        publish_time: price_data.publish_time,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct OracleFallbackSet {
+    pub token_mint: Pubkey,
+    pub source: OracleSource,
+}
+
+#[event]
+pub struct PriceFeedRegistered {
+    pub token_mint: Pubkey,
+    pub feed_id: [u8; 32],
+    pub max_age_seconds: i64,
+}
+
+#[event]
+pub struct CachedPriceUpdated {
+    pub token_mint: Pubkey,
+    pub price: i64,
+    pub confidence: u64,
+    pub exponent: i32,
+    pub publish_time: i64,
+}
@@ -0,0 +1,274 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::{get_associated_token_address, ID as ASSOCIATED_TOKEN_PROGRAM_ID};
+use anchor_spl::token::{spl_token::native_mint, ID as TOKEN_PROGRAM_ID};
+
+use crate::dex::{jupiter::JUPITER_V6_PROGRAM_ID, wsol::TREASURY_WSOL_SEED};
+use crate::state::{
+    AllowancePolicy, ExclusionRoot, ProofCircuit, ProofSystem, ProtocolConfig, TreasuryPolicy,
+    VaultState, VaultType,
+};
+
+#[derive(Accounts)]
+pub struct PlanOperation<'info> {
+    #[account(
+        seeds = [b"vault", vault.asset_mint.as_ref()],
+        bump = vault.bump,
+    )]
+    pub vault: Box<Account<'info, VaultState>>,
+
+    #[account(seeds = [b"protocol_config"], bump = protocol_config.bump)]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    /// Pass to plan a withdrawal that carries an exclusion proof
+    #[account(
+        seeds = [b"exclusion_root", vault.key().as_ref()],
+        bump = exclusion_root.bump,
+    )]
+    pub exclusion_root: Option<Account<'info, ExclusionRoot>>,
+
+    /// Pass for a native vault with a hot/cold treasury split
+    #[account(
+        seeds = [b"treasury_policy", vault.key().as_ref()],
+        bump = treasury_policy.bump,
+    )]
+    pub treasury_policy: Option<Account<'info, TreasuryPolicy>>,
+
+    /// Pass to plan a withdrawal of a note bound to an allowance policy
+    #[account(
+        seeds = [
+            b"allowance_policy",
+            vault.key().as_ref(),
+            &allowance_policy.policy_id.to_le_bytes(),
+        ],
+        bump = allowance_policy.bump,
+    )]
+    pub allowance_policy: Option<Account<'info, AllowancePolicy>>,
+}
+
+/// One entry of an instruction's account list, in instruction order
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PlannedAccount {
+    pub pubkey: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+impl PlannedAccount {
+    fn readonly(pubkey: Pubkey) -> Self {
+        Self { pubkey, is_signer: false, is_writable: false }
+    }
+
+    fn writable(pubkey: Pubkey) -> Self {
+        Self { pubkey, is_signer: false, is_writable: true }
+    }
+
+    fn signer(pubkey: Pubkey) -> Self {
+        Self { pubkey, is_signer: true, is_writable: true }
+    }
+}
+
+/// Accounts for `withdraw_native` / `withdraw_token`, picked by vault type.
+/// Absent optional accounts are filled with the program ID, as Anchor expects.
+pub fn handler_plan_withdrawal(
+    ctx: Context<PlanOperation>,
+    nullifier: [u8; 32],
+    recipient: Pubkey,
+    payer: Pubkey,
+) -> Result<Vec<PlannedAccount>> {
+    let vault = &ctx.accounts.vault;
+    let vault_key = vault.key();
+
+    let (exclusion_root, exclusion_verifier) = match &ctx.accounts.exclusion_root {
+        Some(exclusion_root) => (exclusion_root.key(), exclusion_root.verifier_program),
+        None => (crate::ID, crate::ID),
+    };
+
+    let mut accounts = vec![
+        PlannedAccount::writable(recipient),
+        PlannedAccount::writable(vault_key),
+        PlannedAccount::writable(vault.merkle_tree),
+    ];
+
+    match vault.vault_type {
+        VaultType::Native => {
+            accounts.push(PlannedAccount::writable(treasury_address(&vault_key)));
+        }
+        VaultType::Alternative => {
+            accounts.push(PlannedAccount::readonly(vault.asset_mint));
+            accounts.push(PlannedAccount::writable(get_associated_token_address(
+                &recipient,
+                &vault.asset_mint,
+            )));
+            accounts.push(PlannedAccount::writable(vault_token_address(&vault_key)));
+        }
+    }
+
+    accounts.extend([
+        PlannedAccount::writable(nullifier_address(&vault_key, &nullifier)),
+        PlannedAccount::writable(vault_stats_address(&vault_key)),
+        PlannedAccount::writable(vault_fees_address(&vault_key)),
+        PlannedAccount::writable(global_stats_address()),
+        PlannedAccount::readonly(exclusion_root),
+        PlannedAccount::readonly(exclusion_verifier),
+    ]);
+
+    // withdraw_native's optional hot/cold treasury accounts
+    if vault.vault_type == VaultType::Native {
+        match &ctx.accounts.treasury_policy {
+            Some(treasury_policy) => accounts.extend([
+                PlannedAccount::writable(treasury_policy.key()),
+                PlannedAccount::writable(cold_treasury_address(&vault_key)),
+            ]),
+            None => accounts.extend([
+                PlannedAccount::readonly(crate::ID),
+                PlannedAccount::readonly(crate::ID),
+            ]),
+        }
+    }
+
+    accounts.push(match &ctx.accounts.allowance_policy {
+        Some(allowance_policy) => PlannedAccount::writable(allowance_policy.key()),
+        None => PlannedAccount::readonly(crate::ID),
+    });
+
+    accounts.extend([
+        PlannedAccount::readonly(blocklist_address()),
+        PlannedAccount::readonly(fee_exemptions_address()),
+        PlannedAccount::readonly(protocol_config_address()),
+        PlannedAccount::readonly(ctx.accounts.protocol_config.mixer_verifier),
+        PlannedAccount::readonly(verification_key_address(vault, &vault_key)),
+        // risk_signer; substitute the signer above the protocol value ceiling
+        PlannedAccount::readonly(crate::ID),
+        PlannedAccount::signer(payer),
+        // relayer_registration; substitute the payer's when ext_data pins
+        // its fee schedule
+        PlannedAccount::readonly(crate::ID),
+    ]);
+    // withdraw_token's optional relayer_token_account
+    if vault.vault_type == VaultType::Alternative {
+        accounts.push(PlannedAccount::readonly(crate::ID));
+    }
+    push_programs(&mut accounts, vault.vault_type);
+
+    Ok(accounts)
+}
+
+/// Accounts for `swap_native` / `swap_token`, picked by vault type.
+/// `recipient` is the swap output account (`SwapParam::output_account`).
+/// The Jupiter route accounts follow as remaining accounts and are not listed.
+pub fn handler_plan_swap(
+    ctx: Context<PlanOperation>,
+    nullifier: [u8; 32],
+    recipient: Pubkey,
+    payer: Pubkey,
+) -> Result<Vec<PlannedAccount>> {
+    let vault = &ctx.accounts.vault;
+    let vault_key = vault.key();
+
+    let mut accounts = vec![
+        PlannedAccount::writable(recipient),
+        PlannedAccount::writable(vault_key),
+        PlannedAccount::writable(vault.merkle_tree),
+    ];
+
+    match vault.vault_type {
+        VaultType::Native => accounts.push(PlannedAccount::writable(treasury_address(&vault_key))),
+        VaultType::Alternative => {
+            accounts.push(PlannedAccount::writable(vault_token_address(&vault_key)))
+        }
+    }
+
+    accounts.extend([
+        PlannedAccount::writable(nullifier_address(&vault_key, &nullifier)),
+        PlannedAccount::writable(global_stats_address()),
+        PlannedAccount::readonly(blocklist_address()),
+        PlannedAccount::readonly(protocol_config_address()),
+        PlannedAccount::readonly(ctx.accounts.protocol_config.mixer_verifier),
+        PlannedAccount::readonly(verification_key_address(vault, &vault_key)),
+        // risk_signer; substitute the signer above the protocol value ceiling
+        PlannedAccount::readonly(crate::ID),
+        PlannedAccount::writable(vault_fees_address(&vault_key)),
+        // referral_account; substitute the referrer's PDA when the swap names one
+        PlannedAccount::readonly(crate::ID),
+        PlannedAccount::readonly(JUPITER_V6_PROGRAM_ID),
+    ]);
+    if vault.vault_type == VaultType::Native {
+        accounts.extend([
+            PlannedAccount::writable(treasury_wsol_address(&vault_key)),
+            PlannedAccount::readonly(native_mint::ID),
+        ]);
+    }
+    accounts.extend([
+        PlannedAccount::signer(payer),
+        // Both swaps take the token program; swap_native wraps through WSOL
+        PlannedAccount::readonly(TOKEN_PROGRAM_ID),
+        PlannedAccount::readonly(anchor_lang::system_program::ID),
+    ]);
+
+    Ok(accounts)
+}
+
+fn push_programs(accounts: &mut Vec<PlannedAccount>, vault_type: VaultType) {
+    if vault_type == VaultType::Alternative {
+        accounts.push(PlannedAccount::readonly(TOKEN_PROGRAM_ID));
+        accounts.push(PlannedAccount::readonly(ASSOCIATED_TOKEN_PROGRAM_ID));
+    }
+    accounts.push(PlannedAccount::readonly(anchor_lang::system_program::ID));
+}
+
+fn treasury_address(vault: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"vault_treasury", vault.as_ref()], &crate::ID).0
+}
+
+fn treasury_wsol_address(vault: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[TREASURY_WSOL_SEED, vault.as_ref()], &crate::ID).0
+}
+
+fn cold_treasury_address(vault: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"cold_treasury", vault.as_ref()], &crate::ID).0
+}
+
+fn vault_token_address(vault: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"vault_token_account", vault.as_ref()], &crate::ID).0
+}
+
+fn nullifier_address(vault: &Pubkey, nullifier: &[u8; 32]) -> Pubkey {
+    Pubkey::find_program_address(&[b"nullifier", vault.as_ref(), nullifier.as_ref()], &crate::ID).0
+}
+
+fn blocklist_address() -> Pubkey {
+    Pubkey::find_program_address(&[b"blocklist"], &crate::ID).0
+}
+
+fn vault_fees_address(vault: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"vault_fees", vault.as_ref()], &crate::ID).0
+}
+
+fn vault_stats_address(vault: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"vault_stats", vault.as_ref()], &crate::ID).0
+}
+
+fn global_stats_address() -> Pubkey {
+    Pubkey::find_program_address(&[b"global_stats"], &crate::ID).0
+}
+
+fn fee_exemptions_address() -> Pubkey {
+    Pubkey::find_program_address(&[b"fee_exemptions"], &crate::ID).0
+}
+
+fn protocol_config_address() -> Pubkey {
+    Pubkey::find_program_address(&[b"protocol_config"], &crate::ID).0
+}
+
+/// The vault's mixer verification key when it verifies Groth16 proofs,
+/// otherwise the program ID for the absent optional account
+fn verification_key_address(vault: &VaultState, vault_key: &Pubkey) -> Pubkey {
+    match vault.proof_system {
+        ProofSystem::Groth16 => Pubkey::find_program_address(
+            &[b"verification_key", vault_key.as_ref(), &[ProofCircuit::Mixer as u8]],
+            &crate::ID,
+        )
+        .0,
+        ProofSystem::UltraHonk => crate::ID,
+    }
+}
@@ -0,0 +1,162 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ZyncxError;
+use crate::instructions::deposit::MAX_ENCRYPTED_NOTE_LEN;
+use crate::state::{
+    u64_public_input, MerkleTreeState, NullifierState, ProofCircuit, ProofVerifier, ProtocolConfig, TreeInsertion, VaultLifecycle, VaultState, VaultStats,
+    VerificationKey,
+};
+
+#[derive(Accounts)]
+#[instruction(nullifier: [u8; 32])]
+pub struct PrivateTransfer<'info> {
+    #[account(
+        seeds = [b"vault", vault.asset_mint.as_ref()],
+        bump = vault.bump,
+    )]
+    pub vault: Box<Account<'info, VaultState>>,
+
+    #[account(
+        mut,
+        constraint = merkle_tree.vault == vault.key() @ ZyncxError::MerkleTreeVaultMismatch,
+    )]
+    pub merkle_tree: Box<Account<'info, MerkleTreeState>>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = NullifierState::INIT_SPACE,
+        seeds = [b"nullifier", vault.key().as_ref(), nullifier.as_ref()],
+        bump
+    )]
+    pub nullifier_account: Account<'info, NullifierState>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = VaultStats::INIT_SPACE,
+        seeds = [b"vault_stats", vault.key().as_ref()],
+        bump
+    )]
+    pub vault_stats: Box<Account<'info, VaultStats>>,
+
+    #[account(seeds = [b"protocol_config"], bump = protocol_config.bump)]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    /// CHECK: Noir verifier of the transfer circuit (address pinned by the protocol config)
+    #[account(
+        executable,
+        address = protocol_config.transfer_verifier @ ZyncxError::InvalidVerifierProgram
+    )]
+    pub verifier_program: AccountInfo<'info>,
+
+    /// Vault's Groth16 key of the transfer circuit; required when the vault
+    /// verifies Groth16 proofs
+    #[account(
+        seeds = [b"verification_key", vault.key().as_ref(), &[ProofCircuit::Transfer as u8]],
+        bump = verification_key.bump,
+    )]
+    pub verification_key: Option<Box<Account<'info, VerificationKey>>>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Pay someone inside the pool: spend `nullifier` and insert the recipient's
+/// note plus an optional change note (`change_commitment` zero for none).
+/// No funds leave the vault, so the amount never appears on-chain; the
+/// transfer circuit proves both notes add up to the spent one. When paying a
+/// stealth meta-address, `ephemeral_pubkey` (zero otherwise) is emitted so
+/// the recipient can derive the note. Returns one insertion per inserted
+/// note in insertion order: the recipient's, then the change note's. Notes
+/// bound to an allowance policy cannot be transferred: the transfer circuit
+/// only recomputes unbound commitments.
+pub fn handler_private_transfer(
+    ctx: Context<PrivateTransfer>,
+    nullifier: [u8; 32],
+    recipient_commitment: [u8; 32],
+    change_commitment: [u8; 32],
+    proof: Vec<u8>,
+    encrypted_note: Vec<u8>,
+    ephemeral_pubkey: [u8; 32],
+) -> Result<Vec<TreeInsertion>> {
+    require!(recipient_commitment != [0u8; 32], ZyncxError::InvalidTransferCommitment);
+    require!(
+        encrypted_note.len() <= MAX_ENCRYPTED_NOTE_LEN,
+        ZyncxError::EncryptedNoteTooLarge
+    );
+
+    let vault = &ctx.accounts.vault;
+    require!(!vault.paused, ZyncxError::VaultPaused);
+    require!(vault.lifecycle != VaultLifecycle::Archived, ZyncxError::VaultSunset);
+
+    let merkle_tree = &mut ctx.accounts.merkle_tree;
+    let root = merkle_tree.get_root();
+
+    // Circuit expects public inputs: [root, nullifier_hash, recipient_commitment, change_commitment, note_age_cutoff]
+    let note_age_cutoff = u64_public_input(vault.note_age_cutoff(Clock::get()?.slot));
+    ProofVerifier::for_vault(
+        vault,
+        &ctx.accounts.verifier_program,
+        &ctx.accounts.verification_key,
+    )
+    .verify(&proof, &[root, nullifier, recipient_commitment, change_commitment, note_age_cutoff])?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let nullifier_account = &mut ctx.accounts.nullifier_account;
+    nullifier_account.bump = ctx.bumps.nullifier_account;
+    nullifier_account.nullifier = nullifier;
+    nullifier_account.spent = true;
+    nullifier_account.spent_at = now;
+    nullifier_account.vault = vault.key();
+
+    let has_change = change_commitment != [0u8; 32];
+    let insertions = if has_change {
+        merkle_tree.insert_many(&[recipient_commitment, change_commitment])?
+    } else {
+        merkle_tree.insert_many(&[recipient_commitment])?
+    };
+
+    let vault_stats = &mut ctx.accounts.vault_stats;
+    vault_stats.ensure_initialized(vault.key(), ctx.bumps.vault_stats);
+    let anonymity_set = vault_stats.anonymity_set_size();
+    vault_stats.record_transfer(has_change, now);
+    let anonymity_set_seconds = vault_stats.anonymity_set_seconds;
+
+    emit!(PrivateTransferEvent {
+        vault: vault.key(),
+        nullifier,
+        recipient_commitment,
+        change_commitment,
+        recipient_leaf_index: insertions[0].leaf_index,
+        tree_epoch: insertions[0].tree_epoch,
+        encrypted_note,
+        ephemeral_pubkey,
+        anonymity_set,
+        anonymity_set_seconds,
+    });
+
+    Ok(insertions)
+}
+
+#[event]
+pub struct PrivateTransferEvent {
+    pub vault: Pubkey,
+    pub nullifier: [u8; 32],
+    pub recipient_commitment: [u8; 32],
+    /// Zero when the whole note was transferred
+    pub change_commitment: [u8; 32],
+    pub recipient_leaf_index: u64,
+    pub tree_epoch: u64,
+    /// Recipient note opening encrypted to the recipient's key (empty if
+    /// shared out of band)
+    pub encrypted_note: Vec<u8>,
+    /// Stealth payment ephemeral pubkey (zero if none)
+    pub ephemeral_pubkey: [u8; 32],
+    /// Unspent notes in the vault when the note was spent
+    pub anonymity_set: u64,
+    /// `VaultStats::anonymity_set_seconds` at spend time
+    pub anonymity_set_seconds: u128,
+}
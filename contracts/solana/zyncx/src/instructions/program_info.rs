@@ -0,0 +1,114 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ZyncxError;
+use crate::program::Zyncx;
+use crate::state::{ProgramInfo, GIT_HASH_LEN};
+
+#[derive(Accounts)]
+pub struct SyncProgramInfo<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = ProgramInfo::INIT_SPACE,
+        seeds = [b"program_info"],
+        bump
+    )]
+    pub program_info: Box<Account<'info, ProgramInfo>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Write the version, git hash and feature bitmap of the running binary.
+/// Permissionless: the values are compiled in, so any caller writes the same
+/// data. Run once after every deploy or upgrade.
+pub fn handler_program_info(ctx: Context<SyncProgramInfo>) -> Result<()> {
+    let program_info = &mut ctx.accounts.program_info;
+
+    program_info.bump = ctx.bumps.program_info;
+    program_info.sync_from_build(Clock::get()?.unix_timestamp);
+
+    emit!(ProgramInfoSynced {
+        version_major: program_info.version_major,
+        version_minor: program_info.version_minor,
+        version_patch: program_info.version_patch,
+        git_hash: program_info.git_hash,
+        features: program_info.features,
+    });
+
+    msg!(
+        "Program info synced: v{}.{}.{} features {:#x}",
+        program_info.version_major,
+        program_info.version_minor,
+        program_info.version_patch,
+        program_info.features
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AttestBuild<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"program_info"],
+        bump = program_info.bump,
+    )]
+    pub program_info: Box<Account<'info, ProgramInfo>>,
+
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, Zyncx>,
+
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(authority.key())
+            @ ZyncxError::Unauthorized
+    )]
+    pub program_data: Account<'info, ProgramData>,
+}
+
+/// Record the verifiable-build hash (`solana-verify get-executable-hash`) of
+/// the deployed binary. Upgrade authority only. The deploy slot is stored
+/// with it, so anyone can compare the hash against `solana-verify
+/// get-program-hash` and detect an attestation left over from a previous
+/// upgrade.
+pub fn handler_attest_build(ctx: Context<AttestBuild>, build_hash: [u8; 32]) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let deploy_slot = ctx.accounts.program_data.slot;
+    let program_info = &mut ctx.accounts.program_info;
+
+    program_info.build_hash = build_hash;
+    program_info.attested_slot = deploy_slot;
+    program_info.attested_at = now;
+
+    emit!(BuildAttested {
+        authority: ctx.accounts.authority.key(),
+        build_hash,
+        deploy_slot,
+        git_hash: program_info.git_hash,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct ProgramInfoSynced {
+    pub version_major: u16,
+    pub version_minor: u16,
+    pub version_patch: u16,
+    pub git_hash: [u8; GIT_HASH_LEN],
+    pub features: u64,
+}
+
+#[event]
+pub struct BuildAttested {
+    pub authority: Pubkey,
+    pub build_hash: [u8; 32],
+    pub deploy_slot: u64,
+    pub git_hash: [u8; GIT_HASH_LEN],
+    pub timestamp: i64,
+}
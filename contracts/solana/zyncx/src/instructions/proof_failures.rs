@@ -0,0 +1,73 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ZyncxError;
+use crate::state::{ProofFailureStats, Roles, VaultState, PROOF_FAILURE_SPIKE_THRESHOLD};
+
+#[derive(Accounts)]
+pub struct ReportProofFailures<'info> {
+    #[account(mut)]
+    pub reporter: Signer<'info>,
+
+    #[account(
+        seeds = [b"roles"],
+        bump = roles.bump,
+    )]
+    pub roles: Box<Account<'info, Roles>>,
+
+    #[account(
+        seeds = [b"vault", vault.asset_mint.as_ref()],
+        bump = vault.bump,
+        constraint = roles.pauser == reporter.key() || vault.authority == reporter.key()
+            @ ZyncxError::Unauthorized,
+    )]
+    pub vault: Box<Account<'info, VaultState>>,
+
+    #[account(
+        init_if_needed,
+        payer = reporter,
+        space = ProofFailureStats::INIT_SPACE,
+        seeds = [b"proof_failures", vault.key().as_ref()],
+        bump
+    )]
+    pub proof_failures: Box<Account<'info, ProofFailureStats>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Record `count` InvalidZKProof rejections observed for a vault. Pauser
+/// role or vault authority only, so the counter cannot be inflated to
+/// trigger false alarms.
+pub fn handler_report_proof_failures(
+    ctx: Context<ReportProofFailures>,
+    count: u32,
+) -> Result<()> {
+    require!(count > 0, ZyncxError::AmountTooSmall);
+
+    let now = Clock::get()?.unix_timestamp;
+    let vault_key = ctx.accounts.vault.key();
+    let stats = &mut ctx.accounts.proof_failures;
+    stats.ensure_initialized(vault_key, ctx.bumps.proof_failures);
+
+    if stats.record(count, now) {
+        emit!(ProofFailureSpike {
+            vault: vault_key,
+            epoch: stats.epoch,
+            failures: stats.epoch_failures,
+            threshold: PROOF_FAILURE_SPIKE_THRESHOLD,
+            timestamp: now,
+        });
+    }
+
+    Ok(())
+}
+
+/// A vault's proof failures crossed the per-epoch threshold; the pause
+/// guardian should check for a wallet bug or an attack
+#[event]
+pub struct ProofFailureSpike {
+    pub vault: Pubkey,
+    pub epoch: i64,
+    pub failures: u32,
+    pub threshold: u32,
+    pub timestamp: i64,
+}
@@ -0,0 +1,356 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ZyncxError;
+use crate::state::{
+    ArciumConfig, ParamChange, ProofCircuit, ProtocolConfig, Role, Roles,
+    MAX_CONFIG_TIMELOCK_SECONDS,
+};
+
+#[derive(Accounts)]
+pub struct InitializeProtocolConfig<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(seeds = [b"roles"], bump = roles.bump)]
+    pub roles: Box<Account<'info, Roles>>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = ProtocolConfig::INIT_SPACE,
+        seeds = [b"protocol_config"],
+        bump
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Create the protocol config, pinning the verifier program of each circuit.
+/// Proof-verifying instructions fail until it exists. Admin role only.
+pub fn handler_initialize_protocol_config(
+    ctx: Context<InitializeProtocolConfig>,
+    mixer_verifier: Pubkey,
+    transfer_verifier: Pubkey,
+    batch_withdraw_verifier: Pubkey,
+    timelock_seconds: i64,
+) -> Result<()> {
+    require!(
+        ctx.accounts.roles.has_role(Role::Admin, &ctx.accounts.admin.key()),
+        ZyncxError::Unauthorized
+    );
+    for verifier in [mixer_verifier, transfer_verifier, batch_withdraw_verifier] {
+        require!(verifier != Pubkey::default(), ZyncxError::ZeroAddress);
+    }
+    require!(
+        (0..=MAX_CONFIG_TIMELOCK_SECONDS).contains(&timelock_seconds),
+        ZyncxError::InvalidTimelock
+    );
+
+    let protocol_config = &mut ctx.accounts.protocol_config;
+    protocol_config.bump = ctx.bumps.protocol_config;
+    protocol_config.mixer_verifier = mixer_verifier;
+    protocol_config.transfer_verifier = transfer_verifier;
+    protocol_config.batch_withdraw_verifier = batch_withdraw_verifier;
+    protocol_config.timelock_seconds = timelock_seconds;
+    protocol_config.clear_pending_change();
+    protocol_config.max_operation_value = 0;
+    protocol_config.risk_signer = Pubkey::default();
+    protocol_config.next_param_change_id = 0;
+    protocol_config.pending_params = Vec::new();
+    protocol_config.swap_fee_bps = 0;
+    protocol_config.referral_share_bps = 0;
+
+    emit!(ProtocolConfigInitialized {
+        mixer_verifier,
+        transfer_verifier,
+        batch_withdraw_verifier,
+        timelock_seconds,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdateProtocolConfig<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(seeds = [b"roles"], bump = roles.bump)]
+    pub roles: Box<Account<'info, Roles>>,
+
+    #[account(
+        mut,
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+}
+
+impl UpdateProtocolConfig<'_> {
+    fn require_admin(&self) -> Result<()> {
+        require!(
+            self.roles.has_role(Role::Admin, &self.admin.key()),
+            ZyncxError::Unauthorized
+        );
+        Ok(())
+    }
+}
+
+/// Propose pointing `circuit` at a new verifier program. Executable once the
+/// protocol timelock has elapsed; a new proposal replaces a pending one and
+/// restarts the timelock. Admin role only.
+pub fn handler_propose_verifier_change(
+    ctx: Context<UpdateProtocolConfig>,
+    circuit: ProofCircuit,
+    verifier: Pubkey,
+) -> Result<()> {
+    ctx.accounts.require_admin()?;
+    require!(verifier != Pubkey::default(), ZyncxError::ZeroAddress);
+
+    let now = Clock::get()?.unix_timestamp;
+    let protocol_config = &mut ctx.accounts.protocol_config;
+    let eta = now
+        .checked_add(protocol_config.timelock_seconds)
+        .ok_or(ZyncxError::ArithmeticOverflow)?;
+
+    protocol_config.pending_circuit = circuit;
+    protocol_config.pending_verifier = verifier;
+    protocol_config.pending_eta = eta;
+
+    emit!(VerifierChangeProposed {
+        circuit,
+        current: protocol_config.verifier(circuit),
+        proposed: verifier,
+        eta,
+    });
+
+    Ok(())
+}
+
+/// Apply the pending verifier change after its timelock. Admin role only.
+pub fn handler_execute_verifier_change(ctx: Context<UpdateProtocolConfig>) -> Result<()> {
+    ctx.accounts.require_admin()?;
+
+    let protocol_config = &mut ctx.accounts.protocol_config;
+    require!(
+        protocol_config.has_pending_change(),
+        ZyncxError::NoPendingVerifierChange
+    );
+    require!(
+        Clock::get()?.unix_timestamp >= protocol_config.pending_eta,
+        ZyncxError::TimelockNotElapsed
+    );
+
+    let circuit = protocol_config.pending_circuit;
+    let previous = protocol_config.verifier(circuit);
+    let verifier = protocol_config.pending_verifier;
+    protocol_config.set_verifier(circuit, verifier);
+    protocol_config.clear_pending_change();
+
+    emit!(VerifierChanged {
+        circuit,
+        previous,
+        verifier,
+    });
+
+    Ok(())
+}
+
+/// Drop the pending verifier change. Admin role only.
+pub fn handler_cancel_verifier_change(ctx: Context<UpdateProtocolConfig>) -> Result<()> {
+    ctx.accounts.require_admin()?;
+
+    let protocol_config = &mut ctx.accounts.protocol_config;
+    require!(
+        protocol_config.has_pending_change(),
+        ZyncxError::NoPendingVerifierChange
+    );
+
+    let circuit = protocol_config.pending_circuit;
+    let proposed = protocol_config.pending_verifier;
+    protocol_config.clear_pending_change();
+
+    emit!(VerifierChangeCancelled { circuit, proposed });
+
+    Ok(())
+}
+
+/// Set the per-operation value ceiling and the risk signer allowed to lift it.
+/// `max_operation_value` of 0 removes the ceiling; a default `risk_signer`
+/// disables the bypass. Only raises or removes the ceiling: tightening it is
+/// a queued parameter change. Admin role only.
+pub fn handler_set_operation_value_limit(
+    ctx: Context<UpdateProtocolConfig>,
+    max_operation_value: u64,
+    risk_signer: Pubkey,
+) -> Result<()> {
+    ctx.accounts.require_admin()?;
+
+    let protocol_config = &mut ctx.accounts.protocol_config;
+    require!(
+        !protocol_config.tightens_operation_value(max_operation_value),
+        ZyncxError::ParamChangeRequiresTimelock
+    );
+    protocol_config.max_operation_value = max_operation_value;
+    protocol_config.risk_signer = risk_signer;
+
+    emit!(OperationValueLimitSet {
+        max_operation_value,
+        risk_signer,
+    });
+
+    Ok(())
+}
+
+/// Queue a fee or limit change. It is announced now and executable once the
+/// protocol timelock has elapsed, so users can withdraw under the old terms
+/// first. Admin role only.
+pub fn handler_propose_param_change(
+    ctx: Context<UpdateProtocolConfig>,
+    change: ParamChange,
+) -> Result<()> {
+    ctx.accounts.require_admin()?;
+    change.validate()?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let pending = ctx.accounts.protocol_config.queue_param_change(change, now)?;
+
+    emit!(ParamChangeProposed {
+        id: pending.id,
+        change,
+        proposed_at: now,
+        eta: pending.eta,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ExecuteParamChange<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(seeds = [b"roles"], bump = roles.bump)]
+    pub roles: Box<Account<'info, Roles>>,
+
+    #[account(
+        mut,
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    /// Required for `ComputationFee` changes
+    #[account(
+        mut,
+        seeds = [b"arcium_config"],
+        bump = arcium_config.bump,
+    )]
+    pub arcium_config: Option<Box<Account<'info, ArciumConfig>>>,
+}
+
+/// Apply queued parameter change `id` after its timelock. Admin role only.
+pub fn handler_execute_param_change(ctx: Context<ExecuteParamChange>, id: u64) -> Result<()> {
+    require!(
+        ctx.accounts.roles.has_role(Role::Admin, &ctx.accounts.admin.key()),
+        ZyncxError::Unauthorized
+    );
+
+    let protocol_config = &mut ctx.accounts.protocol_config;
+    let pending = protocol_config.take_param_change(id)?;
+    require!(
+        Clock::get()?.unix_timestamp >= pending.eta,
+        ZyncxError::TimelockNotElapsed
+    );
+
+    match pending.change {
+        ParamChange::OperationValueLimit(max_operation_value) => {
+            protocol_config.max_operation_value = max_operation_value;
+        }
+        ParamChange::ComputationFee(computation_fee) => {
+            let arcium_config = ctx
+                .accounts
+                .arcium_config
+                .as_mut()
+                .ok_or(ZyncxError::MissingArciumConfig)?;
+            arcium_config.computation_fee = computation_fee;
+        }
+        ParamChange::SwapFee(bps) => protocol_config.swap_fee_bps = bps,
+        ParamChange::ReferralShare(bps) => protocol_config.referral_share_bps = bps,
+    }
+
+    emit!(ParamChangeExecuted {
+        id,
+        change: pending.change,
+    });
+
+    Ok(())
+}
+
+/// Drop queued parameter change `id`. Admin role only.
+pub fn handler_cancel_param_change(ctx: Context<UpdateProtocolConfig>, id: u64) -> Result<()> {
+    ctx.accounts.require_admin()?;
+
+    let pending = ctx.accounts.protocol_config.take_param_change(id)?;
+
+    emit!(ParamChangeCancelled {
+        id,
+        change: pending.change,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct ProtocolConfigInitialized {
+    pub mixer_verifier: Pubkey,
+    pub transfer_verifier: Pubkey,
+    pub batch_withdraw_verifier: Pubkey,
+    pub timelock_seconds: i64,
+}
+
+#[event]
+pub struct VerifierChangeProposed {
+    pub circuit: ProofCircuit,
+    pub current: Pubkey,
+    pub proposed: Pubkey,
+    pub eta: i64,
+}
+
+#[event]
+pub struct VerifierChanged {
+    pub circuit: ProofCircuit,
+    pub previous: Pubkey,
+    pub verifier: Pubkey,
+}
+
+#[event]
+pub struct VerifierChangeCancelled {
+    pub circuit: ProofCircuit,
+    pub proposed: Pubkey,
+}
+
+#[event]
+pub struct OperationValueLimitSet {
+    pub max_operation_value: u64,
+    pub risk_signer: Pubkey,
+}
+
+#[event]
+pub struct ParamChangeProposed {
+    pub id: u64,
+    pub change: ParamChange,
+    pub proposed_at: i64,
+    pub eta: i64,
+}
+
+#[event]
+pub struct ParamChangeExecuted {
+    pub id: u64,
+    pub change: ParamChange,
+}
+
+#[event]
+pub struct ParamChangeCancelled {
+    pub id: u64,
+    pub change: ParamChange,
+}
@@ -0,0 +1,308 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+
+use crate::dex::jupiter::transfer_tokens_from_vault;
+use crate::errors::ZyncxError;
+use crate::instructions::initialize::NATIVE_MINT;
+use crate::instructions::treasury::pay_from_vault_treasury;
+use crate::state::{
+    ReferralAccount, ReferralEpoch, ReferralRegistry, Roles, VaultFeeState, VaultState, VaultType,
+    REFERRAL_SLOTS,
+};
+
+#[derive(Accounts)]
+pub struct InitializeReferralRegistry<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = ReferralRegistry::INIT_SPACE,
+        seeds = [b"referral_registry"],
+        bump
+    )]
+    pub referral_registry: Box<Account<'info, ReferralRegistry>>,
+
+    #[account(
+        seeds = [b"roles"],
+        bump = roles.bump,
+        constraint = roles.fee_manager == authority.key() @ ZyncxError::Unauthorized,
+    )]
+    pub roles: Box<Account<'info, Roles>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Create the referral registry. Fee manager role only.
+pub fn handler_initialize_referral_registry(
+    ctx: Context<InitializeReferralRegistry>,
+    fee_per_referral: u64,
+) -> Result<()> {
+    let registry = &mut ctx.accounts.referral_registry;
+    registry.bump = ctx.bumps.referral_registry;
+    registry.referrers = [Pubkey::default(); REFERRAL_SLOTS];
+    registry.fee_per_referral = fee_per_referral;
+
+    msg!("Referral registry initialized");
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdateReferralRegistry<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"referral_registry"],
+        bump = referral_registry.bump,
+    )]
+    pub referral_registry: Box<Account<'info, ReferralRegistry>>,
+
+    #[account(
+        seeds = [b"roles"],
+        bump = roles.bump,
+        constraint = roles.fee_manager == authority.key() @ ZyncxError::Unauthorized,
+    )]
+    pub roles: Box<Account<'info, Roles>>,
+}
+
+/// Assign (or clear, with the default key) the payout wallet of a slot.
+/// Fees already accrued to the slot are claimable by the new holder.
+pub fn handler_set_referrer(
+    ctx: Context<UpdateReferralRegistry>,
+    slot: u8,
+    referrer: Pubkey,
+) -> Result<()> {
+    require!((slot as usize) < REFERRAL_SLOTS, ZyncxError::InvalidReferrer);
+
+    ctx.accounts.referral_registry.referrers[slot as usize] = referrer;
+
+    emit!(ReferrerUpdated { slot, referrer });
+
+    Ok(())
+}
+
+/// Change the per-swap referral fee; applies to swaps queued afterwards
+pub fn handler_set_referral_fee(
+    ctx: Context<UpdateReferralRegistry>,
+    fee_per_referral: u64,
+) -> Result<()> {
+    ctx.accounts.referral_registry.fee_per_referral = fee_per_referral;
+
+    msg!("Referral fee set to {} lamports", fee_per_referral);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ClaimReferralFees<'info> {
+    #[account(mut)]
+    pub referrer: Signer<'info>,
+
+    #[account(
+        seeds = [b"referral_registry"],
+        bump = referral_registry.bump,
+    )]
+    pub referral_registry: Box<Account<'info, ReferralRegistry>>,
+
+    #[account(
+        mut,
+        seeds = [b"referral_epoch".as_ref(), &referral_epoch.epoch.to_le_bytes()],
+        bump = referral_epoch.bump,
+    )]
+    pub referral_epoch: Box<Account<'info, ReferralEpoch>>,
+
+    /// Native SOL vault whose retained fees fund referral payouts
+    #[account(
+        seeds = [b"vault", NATIVE_MINT.as_ref()],
+        bump = fee_vault.bump,
+    )]
+    pub fee_vault: Box<Account<'info, VaultState>>,
+
+    /// CHECK: Native vault treasury PDA holding the fees
+    #[account(
+        mut,
+        seeds = [b"vault_treasury", fee_vault.key().as_ref()],
+        bump,
+    )]
+    pub vault_treasury: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault_fees", fee_vault.key().as_ref()],
+        bump = vault_fees.bump,
+    )]
+    pub vault_fees: Box<Account<'info, VaultFeeState>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Pay a referrer its revealed total for an ended epoch out of the native
+/// vault's retained fees. Only the per-epoch aggregate is ever public.
+pub fn handler_claim_referral_fees(ctx: Context<ClaimReferralFees>, slot: u8) -> Result<()> {
+    let index = slot as usize;
+    require!(index < REFERRAL_SLOTS, ZyncxError::InvalidReferrer);
+    require_keys_eq!(
+        ctx.accounts.referral_registry.referrers[index],
+        ctx.accounts.referrer.key(),
+        ZyncxError::InvalidReferrer
+    );
+
+    let referral_epoch = &mut ctx.accounts.referral_epoch;
+    require!(referral_epoch.revealed, ZyncxError::InvalidReferralEpoch);
+    require!(!referral_epoch.claimed[index], ZyncxError::InvalidReferralEpoch);
+
+    let amount = referral_epoch.totals[index];
+    require!(
+        ctx.accounts.vault_fees.accrued_fees >= amount,
+        ZyncxError::InsufficientFunds
+    );
+
+    referral_epoch.claimed[index] = true;
+    let paid = ctx.accounts.vault_fees.draw_fees(amount);
+    pay_from_vault_treasury(
+        &ctx.accounts.system_program,
+        &ctx.accounts.vault_treasury,
+        &ctx.accounts.referrer.to_account_info(),
+        &ctx.accounts.fee_vault.key(),
+        ctx.bumps.vault_treasury,
+        paid,
+    )?;
+
+    emit!(ReferralFeesClaimed {
+        epoch: referral_epoch.epoch,
+        slot,
+        referrer: ctx.accounts.referrer.key(),
+        amount: paid,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ClaimSwapReferralFees<'info> {
+    #[account(mut)]
+    pub referrer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"swap_referral", referrer.key().as_ref(), vault.key().as_ref()],
+        bump = referral_account.bump,
+        has_one = referrer @ ZyncxError::InvalidReferrer,
+        has_one = vault @ ZyncxError::InvalidReferrer,
+    )]
+    pub referral_account: Box<Account<'info, ReferralAccount>>,
+
+    #[account(
+        seeds = [b"vault", vault.asset_mint.as_ref()],
+        bump = vault.bump,
+    )]
+    pub vault: Box<Account<'info, VaultState>>,
+
+    /// CHECK: Vault treasury PDA; required for native vaults
+    #[account(
+        mut,
+        seeds = [b"vault_treasury", vault.key().as_ref()],
+        bump,
+    )]
+    pub vault_treasury: Option<AccountInfo<'info>>,
+
+    /// Vault token account; required for token vaults
+    #[account(
+        mut,
+        seeds = [b"vault_token_account", vault.key().as_ref()],
+        bump,
+    )]
+    pub vault_token_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    /// Referrer's token account for the vault asset; required for token vaults
+    #[account(
+        mut,
+        token::mint = vault.asset_mint,
+    )]
+    pub referrer_token_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    pub token_program: Option<Program<'info, Token>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Pay a referrer its accrued swap referral rewards out of the vault, in the
+/// vault's asset
+pub fn handler_claim_swap_referral_fees(ctx: Context<ClaimSwapReferralFees>) -> Result<()> {
+    let amount = ctx.accounts.referral_account.accrued;
+    require!(amount > 0, ZyncxError::InsufficientFunds);
+
+    let vault_key = ctx.accounts.vault.key();
+    match ctx.accounts.vault.vault_type {
+        VaultType::Native => {
+            let vault_treasury = ctx
+                .accounts
+                .vault_treasury
+                .as_ref()
+                .ok_or(ZyncxError::MissingPayoutAccount)?;
+            let treasury_bump = ctx.bumps.vault_treasury.ok_or(ZyncxError::MissingPayoutAccount)?;
+            require!(vault_treasury.lamports() >= amount, ZyncxError::InsufficientFunds);
+            pay_from_vault_treasury(
+                &ctx.accounts.system_program,
+                vault_treasury,
+                &ctx.accounts.referrer.to_account_info(),
+                &vault_key,
+                treasury_bump,
+                amount,
+            )?;
+        }
+        VaultType::Alternative => {
+            let (Some(vault_token_account), Some(referrer_token_account), Some(token_program)) = (
+                ctx.accounts.vault_token_account.as_ref(),
+                ctx.accounts.referrer_token_account.as_ref(),
+                ctx.accounts.token_program.as_ref(),
+            ) else {
+                return err!(ZyncxError::MissingPayoutAccount);
+            };
+            transfer_tokens_from_vault(
+                vault_token_account,
+                &referrer_token_account.to_account_info(),
+                token_program,
+                amount,
+                &vault_key,
+                ctx.bumps.vault_token_account.unwrap_or_default(),
+            )?;
+        }
+    }
+
+    let referral_account = &mut ctx.accounts.referral_account;
+    referral_account.accrued = 0;
+    referral_account.claimed = referral_account.claimed.saturating_add(amount);
+
+    emit!(SwapReferralFeesClaimed {
+        referrer: ctx.accounts.referrer.key(),
+        vault: vault_key,
+        amount,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct ReferrerUpdated {
+    pub slot: u8,
+    pub referrer: Pubkey,
+}
+
+#[event]
+pub struct ReferralFeesClaimed {
+    pub epoch: u64,
+    pub slot: u8,
+    pub referrer: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct SwapReferralFeesClaimed {
+    pub referrer: Pubkey,
+    pub vault: Pubkey,
+    pub amount: u64,
+}
@@ -0,0 +1,101 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ZyncxError;
+use crate::state::{RelayerRegistration, MAX_RELAYER_VAULTS};
+
+#[derive(Accounts)]
+pub struct RegisterRelayer<'info> {
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = relayer,
+        space = RelayerRegistration::INIT_SPACE,
+        seeds = [b"relayer", relayer.key().as_ref()],
+        bump
+    )]
+    pub relayer_registration: Box<Account<'info, RelayerRegistration>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Register the caller as a relayer, or update its endpoint, vaults and fee
+/// schedule. Withdrawals that pinned the previous schedule stop going
+/// through this relayer once it changes.
+pub fn handler_register_relayer(
+    ctx: Context<RegisterRelayer>,
+    endpoint_hash: [u8; 32],
+    vaults: Vec<Pubkey>,
+    base_fee: u64,
+    fee_bps: u16,
+) -> Result<()> {
+    require!(
+        endpoint_hash != [0u8; 32]
+            && !vaults.is_empty()
+            && vaults.len() <= MAX_RELAYER_VAULTS
+            && fee_bps < 10_000,
+        ZyncxError::InvalidRelayerRegistration
+    );
+
+    let registration = &mut ctx.accounts.relayer_registration;
+    registration.bump = ctx.bumps.relayer_registration;
+    registration.relayer = ctx.accounts.relayer.key();
+    registration.endpoint_hash = endpoint_hash;
+    registration.vaults = [Pubkey::default(); MAX_RELAYER_VAULTS];
+    registration.vaults[..vaults.len()].copy_from_slice(&vaults);
+    registration.vault_count = vaults.len() as u8;
+    registration.base_fee = base_fee;
+    registration.fee_bps = fee_bps;
+    registration.updated_at = Clock::get()?.unix_timestamp;
+
+    emit!(RelayerRegistered {
+        relayer: registration.relayer,
+        endpoint_hash,
+        vaults,
+        base_fee,
+        fee_bps,
+        fee_schedule_hash: registration.fee_schedule_hash(),
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CloseRelayerRegistration<'info> {
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+
+    #[account(
+        mut,
+        close = relayer,
+        seeds = [b"relayer", relayer.key().as_ref()],
+        bump = relayer_registration.bump,
+    )]
+    pub relayer_registration: Box<Account<'info, RelayerRegistration>>,
+}
+
+/// Leave the relayer registry. Withdrawals pinning this relayer's schedule
+/// can no longer be relayed by it.
+pub fn handler_close_relayer_registration(ctx: Context<CloseRelayerRegistration>) -> Result<()> {
+    emit!(RelayerDeregistered {
+        relayer: ctx.accounts.relayer.key(),
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct RelayerRegistered {
+    pub relayer: Pubkey,
+    pub endpoint_hash: [u8; 32],
+    pub vaults: Vec<Pubkey>,
+    pub base_fee: u64,
+    pub fee_bps: u16,
+    pub fee_schedule_hash: [u8; 32],
+}
+
+#[event]
+pub struct RelayerDeregistered {
+    pub relayer: Pubkey,
+}
@@ -0,0 +1,674 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+
+use crate::errors::ZyncxError;
+use crate::state::{
+    is_rfq_size_bucket, MarketMaker, MerkleTreeState, RfqQuote,
+    RfqRequest, RfqStatus, Role, Roles, VaultLifecycle, VaultState, VaultType,
+    MAX_RFQ_QUOTE_WINDOW, RFQ_MAX_QUOTES,
+};
+
+/// How long after the quote deadline a selected quote has to settle before
+/// the requester may cancel and take the escrow back
+pub const RFQ_SETTLEMENT_WINDOW: i64 = 600;
+
+/// Public and encrypted terms of a new RFQ
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RfqParams {
+    /// Bucketed input amount, escrowed at creation
+    pub size: u64,
+    /// Minimum output, Enc<Shared, u64> ciphertext
+    pub encrypted_min_out: [u8; 32],
+    pub params_nonce: u128,
+    pub client_pubkey: [u8; 32],
+    /// Precommitment of the output note
+    pub precommitment: [u8; 32],
+    /// Seconds market makers have to quote
+    pub quote_window: i64,
+}
+
+#[derive(Accounts)]
+#[instruction(maker: Pubkey)]
+pub struct RegisterMarketMaker<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(seeds = [b"roles"], bump = roles.bump)]
+    pub roles: Box<Account<'info, Roles>>,
+
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = MarketMaker::INIT_SPACE,
+        seeds = [b"market_maker", maker.as_ref()],
+        bump
+    )]
+    pub market_maker: Box<Account<'info, MarketMaker>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Allow `maker` to quote RFQs (`active`), or stop it from quoting new ones.
+/// Admin role only.
+pub fn handler_register_market_maker(
+    ctx: Context<RegisterMarketMaker>,
+    maker: Pubkey,
+    active: bool,
+) -> Result<()> {
+    require!(
+        ctx.accounts.roles.has_role(Role::Admin, &ctx.accounts.admin.key()),
+        ZyncxError::Unauthorized
+    );
+    require!(maker != Pubkey::default(), ZyncxError::ZeroAddress);
+
+    let market_maker = &mut ctx.accounts.market_maker;
+    if market_maker.maker == Pubkey::default() {
+        market_maker.bump = ctx.bumps.market_maker;
+        market_maker.maker = maker;
+        market_maker.open_quotes = 0;
+        market_maker.registered_at = Clock::get()?.unix_timestamp;
+    }
+    market_maker.active = active;
+
+    emit!(MarketMakerRegistered { maker, active });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct DepositInventory<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    #[account(
+        has_one = maker @ ZyncxError::Unauthorized,
+        seeds = [b"market_maker", maker.key().as_ref()],
+        bump = market_maker.bump,
+    )]
+    pub market_maker: Box<Account<'info, MarketMaker>>,
+
+    pub mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = maker,
+    )]
+    pub maker_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = maker,
+        seeds = [b"mm_inventory", maker.key().as_ref(), mint.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = market_maker,
+    )]
+    pub inventory: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Escrow `amount` of `mint` as inventory RFQ quotes settle against
+pub fn handler_deposit_inventory(ctx: Context<DepositInventory>, amount: u64) -> Result<()> {
+    require!(amount > 0, ZyncxError::InvalidDepositAmount);
+
+    token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.maker_token_account.to_account_info(),
+                to: ctx.accounts.inventory.to_account_info(),
+                authority: ctx.accounts.maker.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+
+    emit!(InventoryDeposited {
+        maker: ctx.accounts.maker.key(),
+        mint: ctx.accounts.mint.key(),
+        amount,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct WithdrawInventory<'info> {
+    pub maker: Signer<'info>,
+
+    #[account(
+        has_one = maker @ ZyncxError::Unauthorized,
+        seeds = [b"market_maker", maker.key().as_ref()],
+        bump = market_maker.bump,
+    )]
+    pub market_maker: Box<Account<'info, MarketMaker>>,
+
+    #[account(
+        mut,
+        seeds = [b"mm_inventory", maker.key().as_ref(), inventory.mint.as_ref()],
+        bump,
+    )]
+    pub inventory: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = inventory.mint,
+        token::authority = maker,
+    )]
+    pub maker_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Take inventory back out of escrow. Only while the maker has no open
+/// quotes, so a selected quote is always backed.
+pub fn handler_withdraw_inventory(ctx: Context<WithdrawInventory>, amount: u64) -> Result<()> {
+    let market_maker = &ctx.accounts.market_maker;
+    require!(market_maker.open_quotes == 0, ZyncxError::MarketMakerHasOpenQuotes);
+
+    let bump = [market_maker.bump];
+    let seeds: &[&[u8]] = &[b"market_maker", market_maker.maker.as_ref(), &bump];
+
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.inventory.to_account_info(),
+                to: ctx.accounts.maker_token_account.to_account_info(),
+                authority: market_maker.to_account_info(),
+            },
+            &[seeds],
+        ),
+        amount,
+    )?;
+
+    emit!(InventoryWithdrawn {
+        maker: market_maker.maker,
+        mint: ctx.accounts.inventory.mint,
+        amount,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(rfq_id: u64)]
+pub struct CreateRfq<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        seeds = [b"vault", source_vault.asset_mint.as_ref()],
+        bump = source_vault.bump,
+    )]
+    pub source_vault: Box<Account<'info, VaultState>>,
+
+    #[account(
+        seeds = [b"vault", dest_vault.asset_mint.as_ref()],
+        bump = dest_vault.bump,
+        constraint = dest_vault.key() != source_vault.key() @ ZyncxError::InvalidSwapRoute,
+    )]
+    pub dest_vault: Box<Account<'info, VaultState>>,
+
+    #[account(address = source_vault.asset_mint @ ZyncxError::InvalidMint)]
+    pub source_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = source_mint,
+        token::authority = user,
+    )]
+    pub user_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        payer = user,
+        space = RfqRequest::INIT_SPACE,
+        seeds = [b"rfq_request", user.key().as_ref(), &rfq_id.to_le_bytes()],
+        bump,
+    )]
+    pub rfq_request: Box<Account<'info, RfqRequest>>,
+
+    #[account(
+        init,
+        payer = user,
+        seeds = [b"rfq_escrow", rfq_request.key().as_ref()],
+        bump,
+        token::mint = source_mint,
+        token::authority = rfq_request,
+    )]
+    pub rfq_escrow: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Post an RFQ and escrow its bucketed size. Registered market makers quote
+/// until `now + quote_window`; the output lands as a new note in the
+/// destination vault.
+pub fn handler_create_rfq(ctx: Context<CreateRfq>, rfq_id: u64, params: RfqParams) -> Result<()> {
+    require!(
+        ctx.accounts.source_vault.vault_type == VaultType::Alternative
+            && ctx.accounts.dest_vault.vault_type == VaultType::Alternative,
+        ZyncxError::VaultNotFound
+    );
+    require!(is_rfq_size_bucket(params.size), ZyncxError::InvalidRfqSize);
+    require!(
+        params.quote_window > 0 && params.quote_window <= MAX_RFQ_QUOTE_WINDOW,
+        ZyncxError::InvalidRfqQuoteWindow
+    );
+
+    token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.user_token_account.to_account_info(),
+                to: ctx.accounts.rfq_escrow.to_account_info(),
+                authority: ctx.accounts.user.to_account_info(),
+            },
+        ),
+        params.size,
+    )?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let request = &mut ctx.accounts.rfq_request;
+    request.bump = ctx.bumps.rfq_request;
+    request.user = ctx.accounts.user.key();
+    request.rfq_id = rfq_id;
+    request.source_vault = ctx.accounts.source_vault.key();
+    request.dest_vault = ctx.accounts.dest_vault.key();
+    request.size = params.size;
+    request.encrypted_min_out = params.encrypted_min_out;
+    request.params_nonce = params.params_nonce;
+    request.client_pubkey = params.client_pubkey;
+    request.precommitment = params.precommitment;
+    request.quote_deadline = now + params.quote_window;
+    request.status = RfqStatus::Open;
+    request.quotes = Vec::new();
+    request.winner = Pubkey::default();
+    request.winning_amount = 0;
+    request.created_at = now;
+    request.last_computation = Pubkey::default();
+
+    emit!(RfqCreated {
+        rfq_request: request.key(),
+        user: request.user,
+        source_vault: request.source_vault,
+        dest_vault: request.dest_vault,
+        size: request.size,
+        quote_deadline: request.quote_deadline,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SubmitRfqQuote<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = maker @ ZyncxError::Unauthorized,
+        seeds = [b"market_maker", maker.key().as_ref()],
+        bump = market_maker.bump,
+        constraint = market_maker.active @ ZyncxError::MarketMakerNotActive,
+    )]
+    pub market_maker: Box<Account<'info, MarketMaker>>,
+
+    #[account(mut)]
+    pub rfq_request: Box<Account<'info, RfqRequest>>,
+
+    #[account(
+        init,
+        payer = maker,
+        space = RfqQuote::INIT_SPACE,
+        seeds = [b"rfq_quote", rfq_request.key().as_ref(), maker.key().as_ref()],
+        bump,
+    )]
+    pub rfq_quote: Box<Account<'info, RfqQuote>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Quote an open RFQ. `encrypted_amount` is the output the maker pays for
+/// the request's size, as an Enc<Shared, u64> ciphertext under
+/// `maker_pubkey`; losing quotes are never revealed.
+pub fn handler_submit_rfq_quote(
+    ctx: Context<SubmitRfqQuote>,
+    encrypted_amount: [u8; 32],
+    quote_nonce: u128,
+    maker_pubkey: [u8; 32],
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let request = &mut ctx.accounts.rfq_request;
+    require!(request.status == RfqStatus::Open, ZyncxError::RfqNotOpen);
+    require!(now < request.quote_deadline, ZyncxError::RfqQuoteWindowClosed);
+    require!(request.quotes.len() < RFQ_MAX_QUOTES, ZyncxError::RfqQuotesFull);
+
+    let maker = ctx.accounts.maker.key();
+    request.quotes.push(maker);
+
+    let quote = &mut ctx.accounts.rfq_quote;
+    quote.bump = ctx.bumps.rfq_quote;
+    quote.rfq_request = request.key();
+    quote.maker = maker;
+    quote.encrypted_amount = encrypted_amount;
+    quote.quote_nonce = quote_nonce;
+    quote.maker_pubkey = maker_pubkey;
+    quote.submitted_at = now;
+
+    let market_maker = &mut ctx.accounts.market_maker;
+    market_maker.open_quotes = market_maker
+        .open_quotes
+        .checked_add(1)
+        .ok_or(ZyncxError::ArithmeticOverflow)?;
+
+    emit!(RfqQuoteSubmitted {
+        rfq_request: request.key(),
+        maker,
+        slot: (request.quotes.len() - 1) as u8,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SettleRfq<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(mut)]
+    pub rfq_request: Box<Account<'info, RfqRequest>>,
+
+    #[account(
+        mut,
+        seeds = [b"rfq_escrow", rfq_request.key().as_ref()],
+        bump,
+    )]
+    pub rfq_escrow: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [b"market_maker", rfq_request.winner.as_ref()],
+        bump = market_maker.bump,
+    )]
+    pub market_maker: Box<Account<'info, MarketMaker>>,
+
+    #[account(
+        mut,
+        seeds = [b"mm_inventory", rfq_request.winner.as_ref(), dest_vault.asset_mint.as_ref()],
+        bump,
+    )]
+    pub maker_inventory: Box<Account<'info, TokenAccount>>,
+
+    /// Winner's account receiving the escrowed input
+    #[account(
+        mut,
+        token::mint = rfq_escrow.mint,
+        token::authority = rfq_request.winner,
+    )]
+    pub maker_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        address = rfq_request.dest_vault @ ZyncxError::InvalidSwapRoute,
+    )]
+    pub dest_vault: Box<Account<'info, VaultState>>,
+
+    #[account(
+        mut,
+        address = dest_vault.merkle_tree @ ZyncxError::InactiveMerkleTree,
+    )]
+    pub merkle_tree: Box<Account<'info, MerkleTreeState>>,
+
+    #[account(
+        mut,
+        seeds = [b"vault_token_account", dest_vault.key().as_ref()],
+        bump,
+    )]
+    pub vault_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Settle a selected RFQ. Permissionless: the winner's inventory pays the
+/// winning amount into the destination vault as the requester's note, and
+/// the escrowed input goes to the winner.
+pub fn handler_settle_rfq(ctx: Context<SettleRfq>) -> Result<()> {
+    let request = &ctx.accounts.rfq_request;
+    require!(request.status == RfqStatus::Selected, ZyncxError::RfqNotSelected);
+
+    let dest_vault = &ctx.accounts.dest_vault;
+    require!(!dest_vault.paused, ZyncxError::VaultPaused);
+    require!(dest_vault.lifecycle == VaultLifecycle::Active, ZyncxError::VaultSunset);
+
+    let amount = request.winning_amount;
+    let market_maker = &ctx.accounts.market_maker;
+    let maker_bump = [market_maker.bump];
+    let maker_seeds: &[&[u8]] = &[b"market_maker", market_maker.maker.as_ref(), &maker_bump];
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.maker_inventory.to_account_info(),
+                to: ctx.accounts.vault_token_account.to_account_info(),
+                authority: market_maker.to_account_info(),
+            },
+            &[maker_seeds],
+        ),
+        amount,
+    )?;
+
+    let rfq_id = request.rfq_id.to_le_bytes();
+    let request_bump = [request.bump];
+    let request_seeds: &[&[u8]] = &[b"rfq_request", request.user.as_ref(), &rfq_id, &request_bump];
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.rfq_escrow.to_account_info(),
+                to: ctx.accounts.maker_token_account.to_account_info(),
+                authority: request.to_account_info(),
+            },
+            &[request_seeds],
+        ),
+        request.size,
+    )?;
+
+    let commitment = ctx.accounts.merkle_tree.commitment(amount, request.precommitment)?;
+    let insertion = ctx.accounts.merkle_tree.insert(commitment)?;
+
+    let dest_vault = &mut ctx.accounts.dest_vault;
+    dest_vault.nonce += 1;
+    dest_vault.total_deposited = dest_vault
+        .total_deposited
+        .checked_add(amount)
+        .ok_or(ZyncxError::ArithmeticOverflow)?;
+
+    let request = &mut ctx.accounts.rfq_request;
+    request.status = RfqStatus::Filled;
+
+    emit!(RfqFilled {
+        rfq_request: request.key(),
+        winner: request.winner,
+        size: request.size,
+        amount,
+        commitment,
+        leaf_index: insertion.leaf_index,
+        tree_epoch: insertion.tree_epoch,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CancelRfq<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = user @ ZyncxError::Unauthorized,
+        seeds = [b"rfq_request", user.key().as_ref(), &rfq_request.rfq_id.to_le_bytes()],
+        bump = rfq_request.bump,
+    )]
+    pub rfq_request: Box<Account<'info, RfqRequest>>,
+
+    #[account(
+        mut,
+        seeds = [b"rfq_escrow", rfq_request.key().as_ref()],
+        bump,
+    )]
+    pub rfq_escrow: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = rfq_escrow.mint,
+        token::authority = user,
+    )]
+    pub user_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Refund the escrowed input. Allowed while quoting, when no quote met the
+/// limit, or when a selected quote was not settled within
+/// `RFQ_SETTLEMENT_WINDOW` of the deadline.
+pub fn handler_cancel_rfq(ctx: Context<CancelRfq>) -> Result<()> {
+    let request = &ctx.accounts.rfq_request;
+    let now = Clock::get()?.unix_timestamp;
+    let cancellable = match request.status {
+        RfqStatus::Open | RfqStatus::NoFill => true,
+        RfqStatus::Selected => now >= request.quote_deadline + RFQ_SETTLEMENT_WINDOW,
+        RfqStatus::Selecting | RfqStatus::Filled | RfqStatus::Cancelled => false,
+    };
+    require!(cancellable, ZyncxError::InvalidComputationStatus);
+
+    let rfq_id = request.rfq_id.to_le_bytes();
+    let bump = [request.bump];
+    let seeds: &[&[u8]] = &[b"rfq_request", request.user.as_ref(), &rfq_id, &bump];
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.rfq_escrow.to_account_info(),
+                to: ctx.accounts.user_token_account.to_account_info(),
+                authority: request.to_account_info(),
+            },
+            &[seeds],
+        ),
+        request.size,
+    )?;
+
+    let request = &mut ctx.accounts.rfq_request;
+    request.status = RfqStatus::Cancelled;
+
+    emit!(RfqCancelled {
+        rfq_request: request.key(),
+        user: request.user,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CloseRfqQuote<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = maker @ ZyncxError::Unauthorized,
+        seeds = [b"market_maker", maker.key().as_ref()],
+        bump = market_maker.bump,
+    )]
+    pub market_maker: Box<Account<'info, MarketMaker>>,
+
+    #[account(address = rfq_quote.rfq_request)]
+    pub rfq_request: Box<Account<'info, RfqRequest>>,
+
+    #[account(
+        mut,
+        close = maker,
+        has_one = maker @ ZyncxError::Unauthorized,
+        seeds = [b"rfq_quote", rfq_request.key().as_ref(), maker.key().as_ref()],
+        bump = rfq_quote.bump,
+    )]
+    pub rfq_quote: Box<Account<'info, RfqQuote>>,
+}
+
+/// Close a quote once its RFQ is filled, unfilled or cancelled, releasing
+/// the maker's inventory for withdrawal and refunding the rent
+pub fn handler_close_rfq_quote(ctx: Context<CloseRfqQuote>) -> Result<()> {
+    require!(
+        matches!(
+            ctx.accounts.rfq_request.status,
+            RfqStatus::Filled | RfqStatus::NoFill | RfqStatus::Cancelled
+        ),
+        ZyncxError::InvalidComputationStatus
+    );
+
+    let market_maker = &mut ctx.accounts.market_maker;
+    market_maker.open_quotes = market_maker.open_quotes.saturating_sub(1);
+
+    Ok(())
+}
+
+#[event]
+pub struct MarketMakerRegistered {
+    pub maker: Pubkey,
+    pub active: bool,
+}
+
+#[event]
+pub struct InventoryDeposited {
+    pub maker: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct InventoryWithdrawn {
+    pub maker: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct RfqCreated {
+    pub rfq_request: Pubkey,
+    pub user: Pubkey,
+    pub source_vault: Pubkey,
+    pub dest_vault: Pubkey,
+    pub size: u64,
+    pub quote_deadline: i64,
+}
+
+#[event]
+pub struct RfqQuoteSubmitted {
+    pub rfq_request: Pubkey,
+    pub maker: Pubkey,
+    /// Circuit slot of the quote
+    pub slot: u8,
+}
+
+#[event]
+pub struct RfqFilled {
+    pub rfq_request: Pubkey,
+    pub winner: Pubkey,
+    pub size: u64,
+    pub amount: u64,
+    pub commitment: [u8; 32],
+    pub leaf_index: u64,
+    pub tree_epoch: u64,
+}
+
+#[event]
+pub struct RfqCancelled {
+    pub rfq_request: Pubkey,
+    pub user: Pubkey,
+}
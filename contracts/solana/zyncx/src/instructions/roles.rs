@@ -0,0 +1,258 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ZyncxError;
+use crate::program::Zyncx;
+use crate::state::{
+    GlobalStats, PendingRoleChange, Role, Roles, VaultState, ROLE_CHANGE_TIMELOCK_SECONDS,
+};
+
+#[derive(Accounts)]
+pub struct InitializeRoles<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = Roles::INIT_SPACE,
+        seeds = [b"roles"],
+        bump
+    )]
+    pub roles: Box<Account<'info, Roles>>,
+
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, Zyncx>,
+
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(authority.key())
+            @ ZyncxError::Unauthorized
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Create the role registry. Only the program upgrade authority may do this.
+pub fn handler_initialize_roles(
+    ctx: Context<InitializeRoles>,
+    admin: Pubkey,
+    pauser: Pubkey,
+    fee_manager: Pubkey,
+    compliance: Pubkey,
+) -> Result<()> {
+    for holder in [admin, pauser, fee_manager, compliance] {
+        require!(holder != Pubkey::default(), ZyncxError::ZeroAddress);
+    }
+
+    let roles = &mut ctx.accounts.roles;
+    roles.bump = ctx.bumps.roles;
+    roles.admin = admin;
+    roles.pauser = pauser;
+    roles.fee_manager = fee_manager;
+    roles.compliance = compliance;
+
+    msg!("Roles initialized, admin: {}", admin);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ProposeRoleChange<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"roles"],
+        bump = roles.bump,
+        has_one = admin @ ZyncxError::Unauthorized,
+    )]
+    pub roles: Box<Account<'info, Roles>>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = PendingRoleChange::INIT_SPACE,
+        seeds = [b"pending_role"],
+        bump
+    )]
+    pub pending_change: Box<Account<'info, PendingRoleChange>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Propose reassigning a role, executable after the role change timelock.
+/// Admin only; reassigning Admin hands over the registry.
+pub fn handler_propose_role_change(
+    ctx: Context<ProposeRoleChange>,
+    role: Role,
+    holder: Pubkey,
+) -> Result<()> {
+    require!(holder != Pubkey::default(), ZyncxError::ZeroAddress);
+
+    let now = Clock::get()?.unix_timestamp;
+    let eta = now
+        .checked_add(ROLE_CHANGE_TIMELOCK_SECONDS)
+        .ok_or(ZyncxError::ArithmeticOverflow)?;
+
+    let pending_change = &mut ctx.accounts.pending_change;
+    pending_change.bump = ctx.bumps.pending_change;
+    pending_change.role = role;
+    pending_change.holder = holder;
+    pending_change.proposed_at = now;
+    pending_change.eta = eta;
+
+    emit!(RoleChangeProposed {
+        role,
+        current: ctx.accounts.roles.holder(role),
+        proposed: holder,
+        eta,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ExecuteRoleChange<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"roles"],
+        bump = roles.bump,
+        has_one = admin @ ZyncxError::Unauthorized,
+    )]
+    pub roles: Box<Account<'info, Roles>>,
+
+    #[account(
+        mut,
+        close = admin,
+        seeds = [b"pending_role"],
+        bump = pending_change.bump,
+    )]
+    pub pending_change: Box<Account<'info, PendingRoleChange>>,
+}
+
+/// Apply the pending role change once its timelock has elapsed. Admin only.
+pub fn handler_execute_role_change(ctx: Context<ExecuteRoleChange>) -> Result<()> {
+    let pending_change = &ctx.accounts.pending_change;
+    require!(
+        Clock::get()?.unix_timestamp >= pending_change.eta,
+        ZyncxError::TimelockNotElapsed
+    );
+
+    let role = pending_change.role;
+    let holder = pending_change.holder;
+    let roles = &mut ctx.accounts.roles;
+    let previous = roles.holder(role);
+    roles.set_holder(role, holder);
+
+    emit!(RoleUpdated { role, previous, holder });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CancelRoleChange<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"roles"],
+        bump = roles.bump,
+        has_one = admin @ ZyncxError::Unauthorized,
+    )]
+    pub roles: Box<Account<'info, Roles>>,
+
+    #[account(
+        mut,
+        close = admin,
+        seeds = [b"pending_role"],
+        bump = pending_change.bump,
+    )]
+    pub pending_change: Box<Account<'info, PendingRoleChange>>,
+}
+
+/// Drop the pending role change. Admin only.
+pub fn handler_cancel_role_change(ctx: Context<CancelRoleChange>) -> Result<()> {
+    emit!(RoleChangeCancelled {
+        role: ctx.accounts.pending_change.role,
+        holder: ctx.accounts.pending_change.holder,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetVaultPaused<'info> {
+    pub pauser: Signer<'info>,
+
+    #[account(
+        seeds = [b"roles"],
+        bump = roles.bump,
+        has_one = pauser @ ZyncxError::Unauthorized,
+    )]
+    pub roles: Box<Account<'info, Roles>>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault.asset_mint.as_ref()],
+        bump = vault.bump,
+    )]
+    pub vault: Box<Account<'info, VaultState>>,
+
+    #[account(
+        mut,
+        seeds = [b"global_stats"],
+        bump = global_stats.bump,
+    )]
+    pub global_stats: Box<Account<'info, GlobalStats>>,
+}
+
+/// Halt (or resume) deposits, withdrawals and swaps on a vault
+pub fn handler_set_paused(ctx: Context<SetVaultPaused>, paused: bool) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    if vault.paused != paused {
+        let now = Clock::get()?.unix_timestamp;
+        if paused {
+            ctx.accounts.global_stats.record_vault_deactivated(now);
+        } else {
+            ctx.accounts.global_stats.record_vault_activated(now);
+        }
+    }
+    vault.paused = paused;
+
+    emit!(VaultPauseUpdated {
+        vault: vault.key(),
+        paused,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct RoleChangeProposed {
+    pub role: Role,
+    pub current: Pubkey,
+    pub proposed: Pubkey,
+    pub eta: i64,
+}
+
+#[event]
+pub struct RoleChangeCancelled {
+    pub role: Role,
+    pub holder: Pubkey,
+}
+
+#[event]
+pub struct RoleUpdated {
+    pub role: Role,
+    pub previous: Pubkey,
+    pub holder: Pubkey,
+}
+
+#[event]
+pub struct VaultPauseUpdated {
+    pub vault: Pubkey,
+    pub paused: bool,
+}
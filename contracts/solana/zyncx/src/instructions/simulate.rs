@@ -0,0 +1,279 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+
+use crate::state::{
+    u64_public_input, AllowancePolicy, Blocklist, FeeExemptions, MerkleTreeState, ProofCircuit, ProofVerifier,
+    ProtocolConfig, RelayerRegistration, VaultFeeState, VaultState, VaultType, VerificationKey, ViewingKey,
+    WithdrawExtData, UNBOUND_POLICY_HASH,
+};
+use crate::errors::ZyncxError;
+
+/// Read-only counterpart of `WithdrawNative` / `WithdrawToken`: nothing is
+/// marked mutable, so nothing the handler computes is written back
+#[derive(Accounts)]
+#[instruction(nullifier: [u8; 32])]
+pub struct SimulateWithdraw<'info> {
+    /// CHECK: Withdrawal recipient, bound by the proof and ext_data
+    pub recipient: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"vault", vault.asset_mint.as_ref()],
+        bump = vault.bump,
+    )]
+    pub vault: Box<Account<'info, VaultState>>,
+
+    #[account(
+        constraint = merkle_tree.vault == vault.key() @ ZyncxError::MerkleTreeVaultMismatch,
+    )]
+    pub merkle_tree: Box<Account<'info, MerkleTreeState>>,
+
+    /// CHECK: Vault treasury PDA; required for native vaults
+    #[account(
+        seeds = [b"vault_treasury", vault.key().as_ref()],
+        bump,
+    )]
+    pub vault_treasury: Option<AccountInfo<'info>>,
+
+    /// Vault token account; required for token vaults
+    #[account(
+        seeds = [b"vault_token_account", vault.key().as_ref()],
+        bump,
+    )]
+    pub vault_token_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    /// CHECK: Nullifier PDA of the spent note; the note is unspent while it
+    /// holds no data
+    #[account(
+        seeds = [b"nullifier", vault.key().as_ref(), nullifier.as_ref()],
+        bump,
+    )]
+    pub nullifier_account: UncheckedAccount<'info>,
+
+    /// Vault's fee state; omit while the vault has none (no fee is charged)
+    #[account(
+        seeds = [b"vault_fees", vault.key().as_ref()],
+        bump = vault_fees.bump,
+    )]
+    pub vault_fees: Option<Box<Account<'info, VaultFeeState>>>,
+
+    /// Org allowance policy the spent note is bound to
+    #[account(
+        seeds = [
+            b"allowance_policy",
+            vault.key().as_ref(),
+            &allowance_policy.policy_id.to_le_bytes(),
+        ],
+        bump = allowance_policy.bump,
+    )]
+    pub allowance_policy: Option<Box<Account<'info, AllowancePolicy>>>,
+
+    /// CHECK: Blocklist PDA; may be uninitialized, parsed in the handler
+    #[account(seeds = [b"blocklist"], bump)]
+    pub blocklist: UncheckedAccount<'info>,
+
+    /// CHECK: Fee exemption PDA; may be uninitialized, parsed in the handler
+    #[account(seeds = [b"fee_exemptions"], bump)]
+    pub fee_exemptions: UncheckedAccount<'info>,
+
+    #[account(seeds = [b"protocol_config"], bump = protocol_config.bump)]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    /// CHECK: Noir ZK verifier program (address pinned by the protocol config)
+    #[account(
+        executable,
+        address = protocol_config.mixer_verifier @ ZyncxError::InvalidVerifierProgram
+    )]
+    pub verifier_program: AccountInfo<'info>,
+
+    /// Vault's Groth16 key of the mixer circuit; required when the vault
+    /// verifies Groth16 proofs
+    #[account(
+        seeds = [b"verification_key", vault.key().as_ref(), &[ProofCircuit::Mixer as u8]],
+        bump = verification_key.bump,
+    )]
+    pub verification_key: Option<Box<Account<'info, VerificationKey>>>,
+
+    /// CHECK: Relayer that would land the withdrawal (its payer); bound by
+    /// ext_data, need not sign
+    pub payer: UncheckedAccount<'info>,
+
+    /// Payer's relayer registration; required when `ext_data` pins a fee
+    /// schedule
+    #[account(
+        seeds = [b"relayer", payer.key().as_ref()],
+        bump = relayer_registration.bump,
+    )]
+    pub relayer_registration: Option<Box<Account<'info, RelayerRegistration>>>,
+}
+
+/// Outcome of a simulated withdrawal, returned as return data
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct WithdrawSimulation {
+    /// The proof's root is the tree's current root, the one the withdrawal
+    /// verifies against
+    pub root_current: bool,
+    /// The proof's root is still in the root history; known but not current
+    /// means the user has to prove again against the current root
+    pub root_known: bool,
+    /// No nullifier account exists for the note yet
+    pub nullifier_unspent: bool,
+    /// Above the protocol value ceiling; the risk signer has to co-sign
+    pub needs_risk_signer: bool,
+    /// The note's allowance policy has room for the amount this epoch
+    /// (always true for notes without one)
+    pub within_allowance: bool,
+    /// Treasury lamports (native) or token account balance (token)
+    pub vault_balance: u64,
+    pub sufficient_balance: bool,
+    /// Protocol fee the withdrawal would charge right now
+    pub fee: u64,
+    /// The fee is within `ext_data.max_fee` and the amount covers fee and tip
+    pub fee_accepted: bool,
+    pub payout: u64,
+    pub relayer_tip: u64,
+    /// Proof verified against the current root. Only attempted once every
+    /// other check passes.
+    pub proof_valid: bool,
+}
+
+impl WithdrawSimulation {
+    /// Whether `withdraw_native` / `withdraw_token` would go through with the
+    /// same arguments (given the risk signer when it is needed)
+    pub fn passes(&self) -> bool {
+        self.root_current
+            && self.nullifier_unspent
+            && self.within_allowance
+            && self.sufficient_balance
+            && self.fee_accepted
+            && self.proof_valid
+    }
+}
+
+/// Run a withdrawal's checks without spending the note, so a relayer can
+/// vet a user's withdrawal before paying to land it. `root` is the root the
+/// user proved against. Malformed arguments fail with the error the
+/// withdrawal would raise; state checks are reported in the result.
+/// Exclusion proofs are not checked, and a native vault's cold treasury is
+/// not counted. A Noir verifier rejecting the proof fails the simulation
+/// outright, as a failed CPI cannot be caught.
+#[allow(clippy::too_many_arguments)]
+pub fn handler_simulate_withdraw(
+    ctx: Context<SimulateWithdraw>,
+    nullifier: [u8; 32],
+    root: [u8; 32],
+    amount: u64,
+    new_commitment: [u8; 32],
+    remaining_amount: u64,
+    proof: Vec<u8>,
+    ext_data: WithdrawExtData,
+) -> Result<WithdrawSimulation> {
+    require!(amount > 0, ZyncxError::InvalidWithdrawalAmount);
+    ViewingKey::check_ciphertext_len(&ext_data.viewing_ciphertext)?;
+    Blocklist::check_recipient(&ctx.accounts.blocklist, &ctx.accounts.recipient.key())?;
+    let ext_data_hash = ext_data.verify(&ctx.accounts.recipient.key(), &ctx.accounts.payer.key())?;
+    RelayerRegistration::check_ext_data(
+        &ctx.accounts.relayer_registration,
+        &ext_data,
+        &ctx.accounts.vault.key(),
+        amount,
+    )?;
+
+    let vault = &ctx.accounts.vault;
+    require!(!vault.paused, ZyncxError::VaultPaused);
+    require!(vault.is_valid_denomination(amount), ZyncxError::InvalidDenomination);
+    vault.check_spent_note(amount, remaining_amount, &new_commitment)?;
+
+    let clock = Clock::get()?;
+    let merkle_tree = &ctx.accounts.merkle_tree;
+    let current_root = merkle_tree.get_root();
+
+    let mut simulation = WithdrawSimulation {
+        root_current: root == current_root,
+        root_known: merkle_tree.root_exists(&root),
+        nullifier_unspent: ctx.accounts.nullifier_account.data_is_empty(),
+        needs_risk_signer: ctx.accounts.protocol_config.check_operation_value(amount, None).is_err(),
+        ..WithdrawSimulation::default()
+    };
+
+    // Charge a copy of the policy; the account itself is not writable
+    let policy_hash = match ctx.accounts.allowance_policy.as_deref() {
+        Some(policy) => {
+            let mut policy = AllowancePolicy::clone(policy);
+            simulation.within_allowance = policy.charge(amount, clock.unix_timestamp).is_ok();
+            policy.policy_hash()
+        }
+        None => {
+            simulation.within_allowance = true;
+            UNBOUND_POLICY_HASH
+        }
+    };
+
+    simulation.vault_balance = match vault.vault_type {
+        VaultType::Native => ctx
+            .accounts
+            .vault_treasury
+            .as_ref()
+            .ok_or(ZyncxError::MissingPayoutAccount)?
+            .lamports(),
+        VaultType::Alternative => {
+            ctx.accounts
+                .vault_token_account
+                .as_ref()
+                .ok_or(ZyncxError::MissingPayoutAccount)?
+                .amount
+        }
+    };
+    simulation.sufficient_balance = simulation.vault_balance >= amount;
+
+    if let Some(vault_fees) = ctx.accounts.vault_fees.as_deref() {
+        let exempt = FeeExemptions::is_exempt(
+            &ctx.accounts.fee_exemptions,
+            &[ctx.accounts.payer.key(), ctx.accounts.recipient.key()],
+        )?;
+        simulation.fee = VaultFeeState::clone(vault_fees).apply_withdrawal(
+            amount,
+            simulation.vault_balance,
+            clock.unix_timestamp,
+            exempt,
+        );
+    }
+    if let Ok((payout, relayer_tip)) = ext_data.split(amount, simulation.fee) {
+        simulation.fee_accepted = true;
+        simulation.payout = payout;
+        simulation.relayer_tip = relayer_tip;
+    }
+
+    let checks_passed = simulation.root_current
+        && simulation.nullifier_unspent
+        && simulation.within_allowance
+        && simulation.sufficient_balance
+        && simulation.fee_accepted;
+    if !checks_passed {
+        msg!("Withdrawal checks failed; proof not verified");
+        return Ok(simulation);
+    }
+
+    // Same public inputs, in the same order, as the withdrawal
+    let public_inputs = vec![
+        current_root,
+        nullifier,
+        ctx.accounts.recipient.key().to_bytes(),
+        u64_public_input(amount),
+        new_commitment,
+        ext_data_hash,
+        u64_public_input(remaining_amount),
+        u64_public_input(vault.note_age_cutoff(clock.slot)),
+        policy_hash,
+    ];
+
+    simulation.proof_valid = ProofVerifier::for_vault(
+        vault,
+        &ctx.accounts.verifier_program,
+        &ctx.accounts.verification_key,
+    )
+    .verify(&proof, &public_inputs)
+    .is_ok();
+
+    msg!("Withdrawal simulation passes: {}", simulation.passes());
+    Ok(simulation)
+}
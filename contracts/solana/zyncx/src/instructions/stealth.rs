@@ -0,0 +1,84 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ZyncxError;
+use crate::state::StealthMetaAddress;
+
+#[derive(Accounts)]
+pub struct RegisterStealthMetaAddress<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = StealthMetaAddress::INIT_SPACE,
+        seeds = [b"stealth_meta", owner.key().as_ref()],
+        bump
+    )]
+    pub stealth_meta_address: Box<Account<'info, StealthMetaAddress>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Publish (or rotate) the caller's stealth meta-address
+pub fn handler_register_stealth_meta_address(
+    ctx: Context<RegisterStealthMetaAddress>,
+    spend_pubkey: [u8; 32],
+    view_pubkey: [u8; 32],
+) -> Result<()> {
+    require!(
+        spend_pubkey != [0u8; 32] && view_pubkey != [0u8; 32],
+        ZyncxError::InvalidStealthMetaAddress
+    );
+
+    let meta = &mut ctx.accounts.stealth_meta_address;
+    meta.bump = ctx.bumps.stealth_meta_address;
+    meta.owner = ctx.accounts.owner.key();
+    meta.spend_pubkey = spend_pubkey;
+    meta.view_pubkey = view_pubkey;
+    meta.updated_at = Clock::get()?.unix_timestamp;
+
+    emit!(StealthMetaAddressRegistered {
+        owner: meta.owner,
+        spend_pubkey,
+        view_pubkey,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CloseStealthMetaAddress<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        close = owner,
+        seeds = [b"stealth_meta", owner.key().as_ref()],
+        bump = stealth_meta_address.bump,
+    )]
+    pub stealth_meta_address: Box<Account<'info, StealthMetaAddress>>,
+}
+
+/// Unpublish the caller's stealth meta-address. Payments already sent to it
+/// stay discoverable from their ephemeral pubkeys.
+pub fn handler_close_stealth_meta_address(ctx: Context<CloseStealthMetaAddress>) -> Result<()> {
+    emit!(StealthMetaAddressClosed {
+        owner: ctx.accounts.owner.key(),
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct StealthMetaAddressRegistered {
+    pub owner: Pubkey,
+    pub spend_pubkey: [u8; 32],
+    pub view_pubkey: [u8; 32],
+}
+
+#[event]
+pub struct StealthMetaAddressClosed {
+    pub owner: Pubkey,
+}
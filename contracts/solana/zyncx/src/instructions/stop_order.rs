@@ -0,0 +1,291 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+
+use crate::dex::{execute_jupiter_swap_signed, JUPITER_V6_PROGRAM_ID};
+use crate::errors::ZyncxError;
+use crate::state::{EncryptedStopOrder, StopOrderStatus, VaultState};
+
+#[derive(Accounts)]
+#[instruction(order_id: u64)]
+pub struct PlaceStopOrder<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        seeds = [b"vault", source_vault.asset_mint.as_ref()],
+        bump = source_vault.bump,
+    )]
+    pub source_vault: Box<Account<'info, VaultState>>,
+
+    #[account(
+        seeds = [b"vault", dest_vault.asset_mint.as_ref()],
+        bump = dest_vault.bump,
+        constraint = dest_vault.key() != source_vault.key() @ ZyncxError::InvalidSwapRoute,
+    )]
+    pub dest_vault: Box<Account<'info, VaultState>>,
+
+    /// CHECK: Pyth price account; parsed on every evaluation
+    pub price_feed: UncheckedAccount<'info>,
+
+    /// CHECK: Receives the swapped tokens once the stop fires
+    pub recipient: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + EncryptedStopOrder::INIT_SPACE,
+        seeds = [b"stop_order", user.key().as_ref(), &order_id.to_le_bytes()],
+        bump,
+    )]
+    pub stop_order: Box<Account<'info, EncryptedStopOrder>>,
+
+    /// CHECK: System-owned PDA escrowing the SOL to sell
+    #[account(
+        mut,
+        seeds = [b"stop_escrow", stop_order.key().as_ref()],
+        bump,
+    )]
+    pub stop_escrow: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Place a stop order. `encrypted_params` are the client's
+/// Enc<Shared, StopOrderParams> ciphertexts; only the expiry is public.
+/// `budget` lamports are escrowed and only need to cover the hidden amount,
+/// so over-funding keeps the size private until the stop fires.
+pub fn handler_place_stop_order(
+    ctx: Context<PlaceStopOrder>,
+    order_id: u64,
+    encrypted_params: [[u8; 32]; 3],
+    params_nonce: u128,
+    client_pubkey: [u8; 32],
+    expires_at: i64,
+    budget: u64,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    require!(expires_at > now, ZyncxError::ComputationExpired);
+    require!(budget > 0, ZyncxError::InvalidSwapAmount);
+
+    system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.user.to_account_info(),
+                to: ctx.accounts.stop_escrow.to_account_info(),
+            },
+        ),
+        budget,
+    )?;
+
+    let order = &mut ctx.accounts.stop_order;
+    order.bump = ctx.bumps.stop_order;
+    order.user = ctx.accounts.user.key();
+    order.source_vault = ctx.accounts.source_vault.key();
+    order.dest_vault = ctx.accounts.dest_vault.key();
+    order.encrypted_params = encrypted_params;
+    order.params_nonce = params_nonce;
+    order.client_pubkey = client_pubkey;
+    order.expires_at = expires_at;
+    order.status = StopOrderStatus::Active;
+    order.created_at = now;
+    order.order_id = order_id;
+    order.price_feed = ctx.accounts.price_feed.key();
+    order.recipient = ctx.accounts.recipient.key();
+    order.last_evaluated_at = 0;
+    order.trigger_amount = 0;
+    order.trigger_price = 0;
+    order.evaluation_price = 0;
+
+    emit!(StopOrderPlaced {
+        order: order.key(),
+        user: order.user,
+        source_vault: order.source_vault,
+        dest_vault: order.dest_vault,
+        price_feed: order.price_feed,
+        expires_at,
+        budget,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ExecuteStopOrder<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"stop_order", stop_order.user.as_ref(), &stop_order.order_id.to_le_bytes()],
+        bump = stop_order.bump,
+    )]
+    pub stop_order: Box<Account<'info, EncryptedStopOrder>>,
+
+    /// CHECK: System-owned PDA escrowing the SOL to sell
+    #[account(
+        mut,
+        seeds = [b"stop_escrow", stop_order.key().as_ref()],
+        bump,
+    )]
+    pub stop_escrow: UncheckedAccount<'info>,
+
+    /// CHECK: Must be the recipient fixed at placement
+    #[account(
+        mut,
+        address = stop_order.recipient @ ZyncxError::RecipientMismatch,
+    )]
+    pub recipient: UncheckedAccount<'info>,
+
+    /// CHECK: Jupiter program
+    #[account(address = JUPITER_V6_PROGRAM_ID)]
+    pub jupiter_program: AccountInfo<'info>,
+    // Remaining accounts: All accounts required by Jupiter swap route
+}
+
+/// Sell the amount revealed when the stop fired through Jupiter.
+/// Crankable so a stop executes without its owner online; the route may not
+/// spend more than the triggered amount.
+pub fn handler_execute_stop_order<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ExecuteStopOrder<'info>>,
+    swap_data: Vec<u8>,
+) -> Result<()> {
+    require!(
+        ctx.accounts.stop_order.status == StopOrderStatus::Triggered,
+        ZyncxError::InvalidComputationStatus
+    );
+    let amount = ctx.accounts.stop_order.trigger_amount;
+    require!(amount > 0, ZyncxError::InvalidSwapAmount);
+
+    let escrow = &ctx.accounts.stop_escrow;
+    let before = escrow.lamports();
+    let reserve = Rent::get()?.minimum_balance(0);
+    require!(
+        before >= amount.saturating_add(reserve),
+        ZyncxError::InsufficientFunds
+    );
+
+    let order_key = ctx.accounts.stop_order.key();
+    let bump = [ctx.bumps.stop_escrow];
+    let seeds: &[&[u8]] = &[b"stop_escrow", order_key.as_ref(), &bump];
+
+    execute_jupiter_swap_signed(
+        &escrow.to_account_info(),
+        &ctx.accounts.recipient.to_account_info(),
+        &ctx.accounts.jupiter_program,
+        swap_data,
+        ctx.remaining_accounts,
+        &[seeds],
+    )?;
+
+    let spent = before.saturating_sub(escrow.lamports());
+    require!(spent <= amount, ZyncxError::InvalidSwapAmount);
+
+    let order = &mut ctx.accounts.stop_order;
+    order.status = StopOrderStatus::Executed;
+    order.trigger_amount = 0;
+
+    emit!(StopOrderExecuted {
+        order: order_key,
+        amount_in: spent,
+        trigger_price: order.trigger_price,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CancelStopOrder<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        close = user,
+        has_one = user @ ZyncxError::Unauthorized,
+        seeds = [b"stop_order", user.key().as_ref(), &stop_order.order_id.to_le_bytes()],
+        bump = stop_order.bump,
+    )]
+    pub stop_order: Box<Account<'info, EncryptedStopOrder>>,
+
+    /// CHECK: System-owned PDA escrowing the SOL to sell
+    #[account(
+        mut,
+        seeds = [b"stop_escrow", stop_order.key().as_ref()],
+        bump,
+    )]
+    pub stop_escrow: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Cancel (or clean up an executed/expired) stop order, refunding the
+/// escrow and rent. Not allowed while an evaluation is in flight.
+pub fn handler_cancel_stop_order(ctx: Context<CancelStopOrder>) -> Result<()> {
+    require!(
+        ctx.accounts.stop_order.status != StopOrderStatus::Evaluating,
+        ZyncxError::InvalidComputationStatus
+    );
+
+    let order_key = ctx.accounts.stop_order.key();
+    let refund = ctx.accounts.stop_escrow.lamports();
+
+    if refund > 0 {
+        let bump = [ctx.bumps.stop_escrow];
+        let seeds: &[&[u8]] = &[b"stop_escrow", order_key.as_ref(), &bump];
+
+        system_program::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.stop_escrow.to_account_info(),
+                    to: ctx.accounts.user.to_account_info(),
+                },
+                &[seeds],
+            ),
+            refund,
+        )?;
+    }
+
+    emit!(StopOrderCancelled {
+        order: order_key,
+        user: ctx.accounts.user.key(),
+        status: ctx.accounts.stop_order.status,
+        refunded: refund,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct StopOrderPlaced {
+    pub order: Pubkey,
+    pub user: Pubkey,
+    pub source_vault: Pubkey,
+    pub dest_vault: Pubkey,
+    pub price_feed: Pubkey,
+    pub expires_at: i64,
+    pub budget: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct StopOrderExecuted {
+    pub order: Pubkey,
+    pub amount_in: u64,
+    pub trigger_price: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct StopOrderCancelled {
+    pub order: Pubkey,
+    pub user: Pubkey,
+    /// Status at the time the account was closed
+    pub status: StopOrderStatus,
+    pub refunded: u64,
+    pub timestamp: i64,
+}
@@ -0,0 +1,175 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ZyncxError;
+use crate::instructions::vault_config::ConfigureVault;
+use crate::state::{Roles, VaultLifecycle, VaultState, VaultStats, MIN_SUNSET_GRACE_SECONDS};
+
+/// Set how long a vault must sit idle before it can be flagged for sunset,
+/// and how long note holders then have before it is archived.
+/// `inactivity_epochs` of 0 opts the vault out of sunsetting.
+pub fn handler_set_sunset_policy(
+    ctx: Context<ConfigureVault>,
+    inactivity_epochs: u64,
+    grace_seconds: i64,
+) -> Result<()> {
+    require!(
+        inactivity_epochs == 0 || grace_seconds >= MIN_SUNSET_GRACE_SECONDS,
+        ZyncxError::InvalidSunsetState
+    );
+
+    let vault = &mut ctx.accounts.vault;
+    vault.sunset_inactivity_epochs = inactivity_epochs;
+    vault.sunset_grace_seconds = grace_seconds;
+
+    emit!(SunsetPolicyUpdated {
+        vault: vault.key(),
+        inactivity_epochs,
+        grace_seconds,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct FlagVaultSunset<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"roles"],
+        bump = roles.bump,
+        constraint = roles.admin == admin.key() @ ZyncxError::Unauthorized,
+    )]
+    pub roles: Box<Account<'info, Roles>>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault.asset_mint.as_ref()],
+        bump = vault.bump,
+    )]
+    pub vault: Box<Account<'info, VaultState>>,
+
+    /// Last deposit or withdrawal is the proof of inactivity
+    #[account(
+        seeds = [b"vault_stats", vault.key().as_ref()],
+        bump = vault_stats.bump,
+    )]
+    pub vault_stats: Box<Account<'info, VaultStats>>,
+}
+
+/// Governance flags an abandoned vault WithdrawalsOnly. Only possible once
+/// `vault_stats` shows no deposits or withdrawals for the vault's
+/// inactivity window; starts the grace period before archiving.
+pub fn handler_flag_sunset(ctx: Context<FlagVaultSunset>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let last_activity_at = ctx.accounts.vault_stats.updated_at;
+    let vault = &mut ctx.accounts.vault;
+
+    require!(
+        vault.lifecycle == VaultLifecycle::Active && vault.is_inactive(last_activity_at, now),
+        ZyncxError::InvalidSunsetState
+    );
+
+    vault.lifecycle = VaultLifecycle::WithdrawalsOnly;
+    vault.archive_at = now.saturating_add(vault.sunset_grace_seconds);
+
+    emit!(VaultSunsetFlagged {
+        vault: vault.key(),
+        last_activity_at,
+        archive_at: vault.archive_at,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ArchiveVault<'info> {
+    #[account(
+        mut,
+        seeds = [b"vault", vault.asset_mint.as_ref()],
+        bump = vault.bump,
+    )]
+    pub vault: Box<Account<'info, VaultState>>,
+}
+
+/// Archive a WithdrawalsOnly vault once its grace period is over.
+/// Permissionless; withdrawals remain open so late note holders are never
+/// locked out.
+pub fn handler_archive(ctx: Context<ArchiveVault>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let vault = &mut ctx.accounts.vault;
+
+    require!(
+        vault.lifecycle == VaultLifecycle::WithdrawalsOnly && now >= vault.archive_at,
+        ZyncxError::InvalidSunsetState
+    );
+
+    vault.lifecycle = VaultLifecycle::Archived;
+
+    emit!(VaultArchived {
+        vault: vault.key(),
+        archived_at: now,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ReactivateVault<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"roles"],
+        bump = roles.bump,
+        constraint = roles.admin == admin.key() @ ZyncxError::Unauthorized,
+    )]
+    pub roles: Box<Account<'info, Roles>>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault.asset_mint.as_ref()],
+        bump = vault.bump,
+    )]
+    pub vault: Box<Account<'info, VaultState>>,
+}
+
+/// Governance returns a sunset or archived vault to normal operation
+pub fn handler_reactivate(ctx: Context<ReactivateVault>) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    require!(
+        vault.lifecycle != VaultLifecycle::Active,
+        ZyncxError::InvalidSunsetState
+    );
+
+    vault.lifecycle = VaultLifecycle::Active;
+    vault.archive_at = 0;
+
+    emit!(VaultReactivated { vault: vault.key() });
+
+    Ok(())
+}
+
+#[event]
+pub struct SunsetPolicyUpdated {
+    pub vault: Pubkey,
+    pub inactivity_epochs: u64,
+    pub grace_seconds: i64,
+}
+
+/// Wallets should warn holders of notes in `vault` to withdraw before `archive_at`
+#[event]
+pub struct VaultSunsetFlagged {
+    pub vault: Pubkey,
+    pub last_activity_at: i64,
+    pub archive_at: i64,
+}
+
+#[event]
+pub struct VaultArchived {
+    pub vault: Pubkey,
+    pub archived_at: i64,
+}
+
+#[event]
+pub struct VaultReactivated {
+    pub vault: Pubkey,
+}
@@ -1,18 +1,28 @@
 use anchor_lang::prelude::*;
-use anchor_lang::solana_program::{instruction::Instruction, program::invoke};
-use anchor_spl::token::{Token, TokenAccount};
+use anchor_spl::token::{spl_token::native_mint, Mint, Token, TokenAccount};
 
 use crate::{
-    dex::jupiter::{execute_jupiter_swap, transfer_sol_from_treasury, JUPITER_V6_PROGRAM_ID},
+    dex::{
+        jupiter::{execute_jupiter_swap, transfer_sol_from_treasury, JUPITER_V6_PROGRAM_ID},
+        wsol::{close_treasury_wsol, create_treasury_wsol, wrap_treasury_sol, TREASURY_WSOL_SEED},
+    },
     errors::ZyncxError,
-    state::{MerkleTreeState, NullifierState, VaultState, SwapParam, VaultType},
+    state::{
+        execution_price, slippage_bps, u64_public_input, Blocklist, EncryptedSwapRequest, EncryptedVaultAccount,
+        GlobalStats, MerkleTreeState, NullifierState, ProofCircuit, ProofVerifier, ProtocolConfig, ReferralAccount, SwapParam, SwapRequestStatus,
+        TreeInsertion, UserRequestIndex, VaultFeeState, VaultLifecycle, VaultState, VaultType,
+        VerificationKey, UNBOUND_POLICY_HASH,
+    },
 };
 
 #[derive(Accounts)]
-#[instruction(nullifier: [u8; 32])]
+#[instruction(swap_param: SwapParam, nullifier: [u8; 32])]
 pub struct SwapNative<'info> {
-    /// CHECK: Recipient of swapped tokens (or intermediate token account)
-    #[account(mut)]
+    /// CHECK: Output account bound to the proof's recipient (its ATA for token output)
+    #[account(
+        mut,
+        address = swap_param.output_account() @ ZyncxError::RecipientMismatch,
+    )]
     pub recipient: AccountInfo<'info>,
 
     #[account(
@@ -24,8 +34,7 @@ pub struct SwapNative<'info> {
 
     #[account(
         mut,
-        seeds = [b"merkle_tree", vault.key().as_ref()],
-        bump = merkle_tree.bump,
+        constraint = merkle_tree.vault == vault.key() @ ZyncxError::MerkleTreeVaultMismatch,
     )]
     pub merkle_tree: Box<Account<'info, MerkleTreeState>>,
 
@@ -46,20 +55,80 @@ pub struct SwapNative<'info> {
     )]
     pub nullifier_account: Account<'info, NullifierState>,
 
-    /// CHECK: Noir ZK verifier program (address verified via constraint)
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = GlobalStats::INIT_SPACE,
+        seeds = [b"global_stats"],
+        bump
+    )]
+    pub global_stats: Box<Account<'info, GlobalStats>>,
+
+    /// CHECK: Blocklist PDA; may be uninitialized, parsed in the handler
+    #[account(seeds = [b"blocklist"], bump)]
+    pub blocklist: UncheckedAccount<'info>,
+
+    #[account(seeds = [b"protocol_config"], bump = protocol_config.bump)]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    /// CHECK: Noir ZK verifier program (address pinned by the protocol config)
     #[account(
         executable,
-        address = crate::NOIR_VERIFIER_PROGRAM_ID
+        address = protocol_config.mixer_verifier @ ZyncxError::InvalidVerifierProgram
     )]
     pub verifier_program: AccountInfo<'info>,
 
+    /// Vault's Groth16 key of the mixer circuit; required when the vault
+    /// verifies Groth16 proofs
+    #[account(
+        seeds = [b"verification_key", vault.key().as_ref(), &[ProofCircuit::Mixer as u8]],
+        bump = verification_key.bump,
+    )]
+    pub verification_key: Option<Box<Account<'info, VerificationKey>>>,
+
+    /// Risk signer of the protocol config; co-signs operations above its
+    /// value ceiling
+    pub risk_signer: Option<Signer<'info>>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = VaultFeeState::INIT_SPACE,
+        seeds = [b"vault_fees", vault.key().as_ref()],
+        bump
+    )]
+    pub vault_fees: Box<Account<'info, VaultFeeState>>,
+
+    /// Rewards account of `swap_param.referrer`; required when it is set
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = ReferralAccount::INIT_SPACE,
+        seeds = [b"swap_referral", swap_param.referrer.as_ref(), vault.key().as_ref()],
+        bump
+    )]
+    pub referral_account: Option<Box<Account<'info, ReferralAccount>>>,
+
     /// CHECK: Jupiter V6 program for DEX aggregation
     #[account(address = JUPITER_V6_PROGRAM_ID)]
     pub jupiter_program: AccountInfo<'info>,
 
+    /// CHECK: Temporary WSOL account of the treasury; created, funded and
+    /// closed within a routed swap. Pass it as the route's source account.
+    #[account(
+        mut,
+        seeds = [TREASURY_WSOL_SEED, vault.key().as_ref()],
+        bump,
+    )]
+    pub treasury_wsol: UncheckedAccount<'info>,
+
+    #[account(address = native_mint::ID @ ZyncxError::InvalidMint)]
+    pub wsol_mint: Box<Account<'info, Mint>>,
+
     #[account(mut)]
     pub payer: Signer<'info>,
 
+    pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
     // Remaining accounts: All accounts required by Jupiter swap route
 }
@@ -71,27 +140,39 @@ pub fn handler_native<'info>(
     new_commitment: [u8; 32],
     proof: Vec<u8>,
     swap_data: Vec<u8>,
-) -> Result<()> {
+) -> Result<Option<TreeInsertion>> {
     require!(swap_param.amount_in > 0, ZyncxError::InvalidSwapAmount);
+    ctx.accounts.protocol_config.check_operation_value(
+        swap_param.amount_in,
+        ctx.accounts.risk_signer.as_ref().map(|signer| signer.key()),
+    )?;
+    Blocklist::check_recipient(&ctx.accounts.blocklist, &swap_param.recipient)?;
 
     let vault = &ctx.accounts.vault;
     let merkle_tree = &mut ctx.accounts.merkle_tree;
     let nullifier_account = &mut ctx.accounts.nullifier_account;
 
     require!(vault.vault_type == VaultType::Native, ZyncxError::VaultNotFound);
+    require!(!vault.paused, ZyncxError::VaultPaused);
+    require!(vault.lifecycle != VaultLifecycle::Archived, ZyncxError::VaultSunset);
+    vault.check_spent_note(swap_param.amount_in, swap_param.remaining_amount, &new_commitment)?;
 
     // Get current merkle root
     let root = merkle_tree.get_root();
 
     // Verify ZK proof via CPI to Noir verifier
     verify_noir_proof_cpi(
-        &ctx.accounts.verifier_program,
+        &ProofVerifier::for_vault(
+            vault,
+            &ctx.accounts.verifier_program,
+            &ctx.accounts.verification_key,
+        ),
         &proof,
         &root,
         &nullifier,
-        &swap_param.recipient,
-        swap_param.amount_in,
+        &swap_param,
         &new_commitment,
+        vault.note_age_cutoff(Clock::get()?.slot),
     )?;
     
     msg!("ZK Proof verified successfully!");
@@ -105,10 +186,30 @@ pub fn handler_native<'info>(
 
     // For partial swaps, insert new commitment for remaining balance
     let is_partial = new_commitment != [0u8; 32];
-    if is_partial {
-        merkle_tree.insert(new_commitment)?;
+    let insertion = if is_partial {
+        let insertion = merkle_tree.insert(new_commitment)?;
         msg!("Partial swap: inserted change commitment into merkle tree");
-    }
+        Some(insertion)
+    } else {
+        None
+    };
+    let (leaf_index, new_root) = merkle_tree.event_position(insertion.as_ref());
+
+    let global_stats = &mut ctx.accounts.global_stats;
+    global_stats.ensure_initialized(ctx.bumps.global_stats);
+    global_stats.record_swap(swap_param.amount_in, Clock::get()?.unix_timestamp);
+
+    // The protocol fee stays in the treasury; only the rest is swapped
+    let (fee, referral_reward) = charge_swap_fee(
+        &ctx.accounts.protocol_config,
+        &mut ctx.accounts.vault_fees,
+        ctx.bumps.vault_fees,
+        &mut ctx.accounts.referral_account,
+        ctx.bumps.referral_account,
+        &swap_param,
+        vault.key(),
+    )?;
+    let swap_amount = swap_param.amount_in - fee;
 
     // Check if this is a direct transfer (same token) or a swap
     let is_direct_transfer = swap_param.src_token == swap_param.dst_token;
@@ -118,21 +219,55 @@ pub fn handler_native<'info>(
         transfer_sol_from_treasury(
             &ctx.accounts.vault_treasury,
             &ctx.accounts.recipient,
-            swap_param.amount_in,
+            &ctx.accounts.system_program,
+            swap_amount,
             &vault.key(),
             ctx.bumps.vault_treasury,
         )?;
     } else {
+        // Jupiter routes spend WSOL: wrap the input into the treasury's
+        // temporary WSOL account, swap, then unwrap any leftover
+        let vault_key = vault.key();
+        let treasury_bump = [ctx.bumps.vault_treasury];
+        let treasury_seeds: &[&[u8]] = &[b"vault_treasury", vault_key.as_ref(), &treasury_bump];
+        let wsol_bump = [ctx.bumps.treasury_wsol];
+        let wsol_seeds: &[&[u8]] = &[TREASURY_WSOL_SEED, vault_key.as_ref(), &wsol_bump];
+
+        let vault_treasury = ctx.accounts.vault_treasury.to_account_info();
+        let treasury_wsol = ctx.accounts.treasury_wsol.to_account_info();
+        let token_program = ctx.accounts.token_program.to_account_info();
+        let system_program = ctx.accounts.system_program.to_account_info();
+
+        create_treasury_wsol(
+            &vault_treasury,
+            &treasury_wsol,
+            &ctx.accounts.wsol_mint.to_account_info(),
+            &token_program,
+            &system_program,
+            treasury_seeds,
+            wsol_seeds,
+        )?;
+        wrap_treasury_sol(
+            &vault_treasury,
+            &treasury_wsol,
+            &token_program,
+            &system_program,
+            swap_amount,
+            treasury_seeds,
+        )?;
+
         // Execute swap via Jupiter
         execute_jupiter_swap(
-            &ctx.accounts.vault_treasury,
+            &vault_treasury,
             &ctx.accounts.recipient,
             &ctx.accounts.jupiter_program,
             swap_data,
             ctx.remaining_accounts,
-            &vault.key(),
+            &vault_key,
             ctx.bumps.vault_treasury,
         )?;
+
+        close_treasury_wsol(&vault_treasury, &treasury_wsol, &token_program, treasury_seeds)?;
     }
 
     // Emit event
@@ -144,18 +279,26 @@ pub fn handler_native<'info>(
         min_amount_out: swap_param.min_amount_out,
         nullifier,
         new_commitment,
+        leaf_index,
+        new_root,
+        fee,
+        referrer: swap_param.referrer,
+        referral_reward,
     });
 
-    msg!("Swapped {} lamports via Jupiter", swap_param.amount_in);
+    msg!("Swapped {} lamports via Jupiter", swap_amount);
 
-    Ok(())
+    Ok(insertion)
 }
 
 #[derive(Accounts)]
-#[instruction(nullifier: [u8; 32])]
+#[instruction(swap_param: SwapParam, nullifier: [u8; 32])]
 pub struct SwapToken<'info> {
-    /// CHECK: Recipient of swapped tokens
-    #[account(mut)]
+    /// CHECK: Output account bound to the proof's recipient (its ATA for token output)
+    #[account(
+        mut,
+        address = swap_param.output_account() @ ZyncxError::RecipientMismatch,
+    )]
     pub recipient: AccountInfo<'info>,
 
     #[account(
@@ -167,8 +310,7 @@ pub struct SwapToken<'info> {
 
     #[account(
         mut,
-        seeds = [b"merkle_tree", vault.key().as_ref()],
-        bump = merkle_tree.bump,
+        constraint = merkle_tree.vault == vault.key() @ ZyncxError::MerkleTreeVaultMismatch,
     )]
     pub merkle_tree: Box<Account<'info, MerkleTreeState>>,
 
@@ -188,13 +330,60 @@ pub struct SwapToken<'info> {
     )]
     pub nullifier_account: Account<'info, NullifierState>,
 
-    /// CHECK: Noir ZK verifier program (address verified via constraint)
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = GlobalStats::INIT_SPACE,
+        seeds = [b"global_stats"],
+        bump
+    )]
+    pub global_stats: Box<Account<'info, GlobalStats>>,
+
+    /// CHECK: Blocklist PDA; may be uninitialized, parsed in the handler
+    #[account(seeds = [b"blocklist"], bump)]
+    pub blocklist: UncheckedAccount<'info>,
+
+    #[account(seeds = [b"protocol_config"], bump = protocol_config.bump)]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    /// CHECK: Noir ZK verifier program (address pinned by the protocol config)
     #[account(
         executable,
-        address = crate::NOIR_VERIFIER_PROGRAM_ID
+        address = protocol_config.mixer_verifier @ ZyncxError::InvalidVerifierProgram
     )]
     pub verifier_program: AccountInfo<'info>,
 
+    /// Vault's Groth16 key of the mixer circuit; required when the vault
+    /// verifies Groth16 proofs
+    #[account(
+        seeds = [b"verification_key", vault.key().as_ref(), &[ProofCircuit::Mixer as u8]],
+        bump = verification_key.bump,
+    )]
+    pub verification_key: Option<Box<Account<'info, VerificationKey>>>,
+
+    /// Risk signer of the protocol config; co-signs operations above its
+    /// value ceiling
+    pub risk_signer: Option<Signer<'info>>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = VaultFeeState::INIT_SPACE,
+        seeds = [b"vault_fees", vault.key().as_ref()],
+        bump
+    )]
+    pub vault_fees: Box<Account<'info, VaultFeeState>>,
+
+    /// Rewards account of `swap_param.referrer`; required when it is set
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = ReferralAccount::INIT_SPACE,
+        seeds = [b"swap_referral", swap_param.referrer.as_ref(), vault.key().as_ref()],
+        bump
+    )]
+    pub referral_account: Option<Box<Account<'info, ReferralAccount>>>,
+
     /// CHECK: Jupiter V6 program for DEX aggregation
     #[account(address = JUPITER_V6_PROGRAM_ID)]
     pub jupiter_program: AccountInfo<'info>,
@@ -214,27 +403,39 @@ pub fn handler_token<'info>(
     new_commitment: [u8; 32],
     proof: Vec<u8>,
     swap_data: Vec<u8>,
-) -> Result<()> {
+) -> Result<Option<TreeInsertion>> {
     require!(swap_param.amount_in > 0, ZyncxError::InvalidSwapAmount);
+    ctx.accounts.protocol_config.check_operation_value(
+        swap_param.amount_in,
+        ctx.accounts.risk_signer.as_ref().map(|signer| signer.key()),
+    )?;
+    Blocklist::check_recipient(&ctx.accounts.blocklist, &swap_param.recipient)?;
 
     let vault = &ctx.accounts.vault;
     let merkle_tree = &mut ctx.accounts.merkle_tree;
     let nullifier_account = &mut ctx.accounts.nullifier_account;
 
     require!(vault.vault_type == VaultType::Alternative, ZyncxError::VaultNotFound);
+    require!(!vault.paused, ZyncxError::VaultPaused);
+    require!(vault.lifecycle != VaultLifecycle::Archived, ZyncxError::VaultSunset);
+    vault.check_spent_note(swap_param.amount_in, swap_param.remaining_amount, &new_commitment)?;
 
     // Get current merkle root
     let root = merkle_tree.get_root();
 
     // Verify ZK proof via CPI to Noir verifier
     verify_noir_proof_cpi(
-        &ctx.accounts.verifier_program,
+        &ProofVerifier::for_vault(
+            vault,
+            &ctx.accounts.verifier_program,
+            &ctx.accounts.verification_key,
+        ),
         &proof,
         &root,
         &nullifier,
-        &swap_param.recipient,
-        swap_param.amount_in,
+        &swap_param,
         &new_commitment,
+        vault.note_age_cutoff(Clock::get()?.slot),
     )?;
     
     msg!("ZK Proof verified successfully!");
@@ -248,10 +449,31 @@ pub fn handler_token<'info>(
 
     // For partial swaps, insert new commitment for remaining balance
     let is_partial = new_commitment != [0u8; 32];
-    if is_partial {
-        merkle_tree.insert(new_commitment)?;
+    let insertion = if is_partial {
+        let insertion = merkle_tree.insert(new_commitment)?;
         msg!("Partial swap: inserted change commitment into merkle tree");
-    }
+        Some(insertion)
+    } else {
+        None
+    };
+    let (leaf_index, new_root) = merkle_tree.event_position(insertion.as_ref());
+
+    let global_stats = &mut ctx.accounts.global_stats;
+    global_stats.ensure_initialized(ctx.bumps.global_stats);
+    global_stats.record_swap(swap_param.amount_in, Clock::get()?.unix_timestamp);
+
+    // The protocol fee stays in the vault token account; the route must
+    // only spend the rest
+    let (fee, referral_reward) = charge_swap_fee(
+        &ctx.accounts.protocol_config,
+        &mut ctx.accounts.vault_fees,
+        ctx.bumps.vault_fees,
+        &mut ctx.accounts.referral_account,
+        ctx.bumps.referral_account,
+        &swap_param,
+        vault.key(),
+    )?;
+    let swap_amount = swap_param.amount_in - fee;
 
     // Check if this is a direct transfer (same token) or a swap
     let is_direct_transfer = swap_param.src_token == swap_param.dst_token;
@@ -263,7 +485,7 @@ pub fn handler_token<'info>(
             &ctx.accounts.vault_token_account,
             &ctx.accounts.recipient,
             &ctx.accounts.token_program,
-            swap_param.amount_in,
+            swap_amount,
             &vault.key(),
             ctx.bumps.vault_token_account,
         )?;
@@ -289,14 +511,358 @@ pub fn handler_token<'info>(
         min_amount_out: swap_param.min_amount_out,
         nullifier,
         new_commitment,
+        leaf_index,
+        new_root,
+        fee,
+        referrer: swap_param.referrer,
+        referral_reward,
     });
 
-    msg!("Swapped {} tokens via Jupiter", swap_param.amount_in);
+    msg!("Swapped {} tokens via Jupiter", swap_amount);
 
-    Ok(())
+    Ok(insertion)
 }
 
-/// Verify Noir ZK proof via CPI to the deployed verifier program
+#[derive(Accounts)]
+#[instruction(swap_param: SwapParam, nullifier: [u8; 32])]
+pub struct ExecuteApprovedSwap<'info> {
+    /// Owner of the request; signs because the destination precommitment is
+    /// not bound by the proof
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = user @ ZyncxError::Unauthorized,
+        seeds = [
+            b"swap_request",
+            user.key().as_ref(),
+            &swap_request.computation_offset.to_le_bytes(),
+        ],
+        bump = swap_request.bump,
+    )]
+    pub swap_request: Box<Account<'info, EncryptedSwapRequest>>,
+
+    #[account(
+        mut,
+        seeds = [b"user_request_index", user.key().as_ref()],
+        bump = user_request_index.bump,
+    )]
+    pub user_request_index: Box<Account<'info, UserRequestIndex>>,
+
+    #[account(
+        seeds = [b"vault", source_vault.asset_mint.as_ref()],
+        bump = source_vault.bump,
+    )]
+    pub source_vault: Box<Account<'info, VaultState>>,
+
+    #[account(
+        mut,
+        constraint = source_merkle_tree.vault == source_vault.key() @ ZyncxError::MerkleTreeVaultMismatch,
+    )]
+    pub source_merkle_tree: Box<Account<'info, MerkleTreeState>>,
+
+    /// CHECK: Source vault PDA that holds SOL
+    #[account(
+        mut,
+        seeds = [b"vault_treasury", source_vault.key().as_ref()],
+        bump,
+    )]
+    pub vault_treasury: AccountInfo<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = NullifierState::INIT_SPACE,
+        seeds = [b"nullifier", source_vault.key().as_ref(), nullifier.as_ref()],
+        bump
+    )]
+    pub nullifier_account: Account<'info, NullifierState>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", dest_vault.asset_mint.as_ref()],
+        bump = dest_vault.bump,
+        constraint = dest_vault.key() != source_vault.key() @ ZyncxError::InvalidSwapRoute,
+    )]
+    pub dest_vault: Box<Account<'info, VaultState>>,
+
+    #[account(
+        mut,
+        address = dest_vault.merkle_tree @ ZyncxError::InactiveMerkleTree,
+    )]
+    pub dest_merkle_tree: Box<Account<'info, MerkleTreeState>>,
+
+    #[account(
+        mut,
+        seeds = [b"vault_token_account", dest_vault.key().as_ref()],
+        bump,
+    )]
+    pub dest_vault_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// Destination mint; its decimals scale the realized execution price
+    #[account(address = dest_vault.asset_mint @ ZyncxError::InvalidSwapRoute)]
+    pub dest_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = GlobalStats::INIT_SPACE,
+        seeds = [b"global_stats"],
+        bump
+    )]
+    pub global_stats: Box<Account<'info, GlobalStats>>,
+
+    #[account(seeds = [b"protocol_config"], bump = protocol_config.bump)]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    /// CHECK: Noir ZK verifier program (address pinned by the protocol config)
+    #[account(
+        executable,
+        address = protocol_config.mixer_verifier @ ZyncxError::InvalidVerifierProgram
+    )]
+    pub verifier_program: AccountInfo<'info>,
+
+    /// Vault's Groth16 key of the mixer circuit; required when the vault
+    /// verifies Groth16 proofs
+    #[account(
+        seeds = [b"verification_key", source_vault.key().as_ref(), &[ProofCircuit::Mixer as u8]],
+        bump = verification_key.bump,
+    )]
+    pub verification_key: Option<Box<Account<'info, VerificationKey>>>,
+
+    /// Risk signer of the protocol config; co-signs operations above its
+    /// value ceiling
+    pub risk_signer: Option<Signer<'info>>,
+
+    /// CHECK: Jupiter V6 program for DEX aggregation
+    #[account(address = JUPITER_V6_PROGRAM_ID)]
+    pub jupiter_program: AccountInfo<'info>,
+
+    /// Keeper submitting the transaction; receives the request's keeper escrow
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    // Remaining accounts: All accounts required by Jupiter swap route
+}
+
+/// Settle a confidential swap the MXE approved. Spends the user's note in
+/// the native source vault, swaps through Jupiter from the vault treasury
+/// into the destination vault, and inserts a note for the amount actually
+/// received (`poseidon(received, precommitment)`) into the destination tree.
+///
+/// The proof's recipient must be the destination vault, so the spent note
+/// can only ever be swapped into it.
+pub fn handler_execute_approved<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ExecuteApprovedSwap<'info>>,
+    swap_param: SwapParam,
+    nullifier: [u8; 32],
+    new_commitment: [u8; 32],
+    precommitment: [u8; 32],
+    proof: Vec<u8>,
+    swap_data: Vec<u8>,
+) -> Result<TreeInsertion> {
+    let swap_request = &ctx.accounts.swap_request;
+    require!(
+        swap_request.status == SwapRequestStatus::Completed && swap_request.should_execute,
+        ZyncxError::InvalidComputationStatus
+    );
+    require!(swap_param.amount_in > 0, ZyncxError::InvalidSwapAmount);
+    require!(
+        swap_param.amount_in <= swap_request.max_child_amount,
+        ZyncxError::PriceImpactExceeded
+    );
+    ctx.accounts.protocol_config.check_operation_value(
+        swap_param.amount_in,
+        ctx.accounts.risk_signer.as_ref().map(|signer| signer.key()),
+    )?;
+
+    let source_vault = &ctx.accounts.source_vault;
+    let dest_vault = &ctx.accounts.dest_vault;
+
+    // The request names the encrypted vaults the MXE evaluated; settle only
+    // between the plain vaults of the same two tokens
+    require_keys_eq!(
+        swap_request.source_vault,
+        EncryptedVaultAccount::address(&source_vault.asset_mint),
+        ZyncxError::InvalidSwapRoute
+    );
+    require_keys_eq!(
+        swap_request.dest_vault,
+        EncryptedVaultAccount::address(&dest_vault.asset_mint),
+        ZyncxError::InvalidSwapRoute
+    );
+
+    require!(source_vault.vault_type == VaultType::Native, ZyncxError::VaultNotFound);
+    require!(!source_vault.paused, ZyncxError::VaultPaused);
+    require!(source_vault.lifecycle != VaultLifecycle::Archived, ZyncxError::VaultSunset);
+    source_vault.check_spent_note(
+        swap_param.amount_in,
+        swap_param.remaining_amount,
+        &new_commitment,
+    )?;
+    require!(dest_vault.vault_type == VaultType::Alternative, ZyncxError::VaultNotFound);
+    require!(!dest_vault.paused, ZyncxError::VaultPaused);
+    require!(dest_vault.lifecycle == VaultLifecycle::Active, ZyncxError::VaultSunset);
+    require!(
+        swap_param.recipient == dest_vault.key()
+            && swap_param.src_token == source_vault.asset_mint
+            && swap_param.dst_token == dest_vault.asset_mint,
+        ZyncxError::InvalidSwapRoute
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    let root = ctx.accounts.source_merkle_tree.get_root();
+
+    verify_noir_proof_cpi(
+        &ProofVerifier::for_vault(
+            source_vault,
+            &ctx.accounts.verifier_program,
+            &ctx.accounts.verification_key,
+        ),
+        &proof,
+        &root,
+        &nullifier,
+        &swap_param,
+        &new_commitment,
+        source_vault.note_age_cutoff(Clock::get()?.slot),
+    )?;
+
+    let nullifier_account = &mut ctx.accounts.nullifier_account;
+    nullifier_account.bump = ctx.bumps.nullifier_account;
+    nullifier_account.nullifier = nullifier;
+    nullifier_account.spent = true;
+    nullifier_account.spent_at = now;
+    nullifier_account.vault = source_vault.key();
+
+    if new_commitment != [0u8; 32] {
+        ctx.accounts.source_merkle_tree.insert(new_commitment)?;
+        msg!("Partial swap: inserted change commitment into merkle tree");
+    }
+
+    let treasury_before = ctx.accounts.vault_treasury.lamports();
+    let balance_before = ctx.accounts.dest_vault_token_account.amount;
+
+    execute_jupiter_swap(
+        &ctx.accounts.vault_treasury,
+        &ctx.accounts.dest_vault_token_account.to_account_info(),
+        &ctx.accounts.jupiter_program,
+        swap_data,
+        ctx.remaining_accounts,
+        &source_vault.key(),
+        ctx.bumps.vault_treasury,
+    )?;
+
+    let spent = treasury_before.saturating_sub(ctx.accounts.vault_treasury.lamports());
+    require!(spent <= swap_param.amount_in, ZyncxError::InvalidSwapAmount);
+
+    ctx.accounts.dest_vault_token_account.reload()?;
+    let received = ctx
+        .accounts
+        .dest_vault_token_account
+        .amount
+        .saturating_sub(balance_before);
+    require!(
+        received > 0 && received >= swap_param.min_amount_out,
+        ZyncxError::SlippageExceeded
+    );
+
+    // Realized price from the balance diffs, on the oracle's scale
+    let execution_price = execution_price(
+        spent,
+        native_mint::DECIMALS,
+        received,
+        ctx.accounts.dest_mint.decimals,
+    )
+    .unwrap_or(0);
+
+    // Credit the output to the user as a fresh note in the destination vault
+    let dest_commitment = ctx.accounts.dest_merkle_tree.commitment(received, precommitment)?;
+    let insertion = ctx.accounts.dest_merkle_tree.insert(dest_commitment)?;
+
+    let dest_vault = &mut ctx.accounts.dest_vault;
+    dest_vault.nonce += 1;
+    dest_vault.total_deposited = dest_vault
+        .total_deposited
+        .checked_add(received)
+        .ok_or(ZyncxError::ArithmeticOverflow)?;
+
+    let global_stats = &mut ctx.accounts.global_stats;
+    global_stats.ensure_initialized(ctx.bumps.global_stats);
+    global_stats.record_swap(swap_param.amount_in, now);
+
+    let swap_request = &mut ctx.accounts.swap_request;
+    swap_request.status = SwapRequestStatus::Executed;
+    ctx.accounts
+        .user_request_index
+        .update(swap_request.key(), SwapRequestStatus::Executed);
+    swap_request.dest_vault = dest_vault.key();
+    swap_request.amount = swap_param.amount_in;
+    swap_request.nullifier = nullifier;
+    swap_request.new_commitment = new_commitment;
+    swap_request.execution_price = execution_price;
+
+    // Pay the keeper out of the escrow held in the request PDA
+    let keeper_fee = swap_request.keeper_escrow;
+    swap_request.keeper_escrow = 0;
+    if keeper_fee > 0 {
+        **swap_request.to_account_info().try_borrow_mut_lamports()? -= keeper_fee;
+        **ctx.accounts.payer.to_account_info().try_borrow_mut_lamports()? += keeper_fee;
+    }
+
+    emit!(ConfidentialSwapExecuted {
+        swap_request: swap_request.key(),
+        source_vault: swap_request.source_vault,
+        dest_vault: swap_request.dest_vault,
+        amount_in: spent,
+        amount_out: received,
+        oracle_price: swap_request.oracle_price,
+        execution_price,
+        slippage_bps: slippage_bps(swap_request.oracle_price, execution_price),
+        nullifier,
+        new_commitment,
+        dest_commitment,
+        leaf_index: insertion.leaf_index,
+        new_root: insertion.new_root,
+        timestamp: now,
+    });
+
+    Ok(insertion)
+}
+
+/// Charge the protocol swap fee on `swap_param.amount_in`: the referrer's
+/// share accrues to its referral account, the rest to the vault's fees.
+/// Returns the fee and the referral reward.
+fn charge_swap_fee(
+    protocol_config: &ProtocolConfig,
+    vault_fees: &mut VaultFeeState,
+    vault_fees_bump: u8,
+    referral_account: &mut Option<Box<Account<ReferralAccount>>>,
+    referral_bump: Option<u8>,
+    swap_param: &SwapParam,
+    vault: Pubkey,
+) -> Result<(u64, u64)> {
+    let referred = swap_param.referrer != Pubkey::default();
+    let (fee, reward) = protocol_config.swap_fee(swap_param.amount_in, referred);
+
+    if referred {
+        let account = referral_account
+            .as_mut()
+            .ok_or(ZyncxError::MissingReferralAccount)?;
+        account.ensure_initialized(swap_param.referrer, vault, referral_bump.unwrap_or_default());
+        account.accrued = account
+            .accrued
+            .checked_add(reward)
+            .ok_or(ZyncxError::ArithmeticOverflow)?;
+    }
+
+    vault_fees.ensure_initialized(vault, vault_fees_bump);
+    vault_fees.record_swap_fee(fee - reward);
+
+    Ok((fee, reward))
+}
+
+/// Verify the swap's spend proof with the vault's proof system
 /// 
 /// Public inputs order (matching Noir circuit):
 /// 1. root - Merkle tree root
@@ -304,53 +870,36 @@ pub fn handler_token<'info>(
 /// 3. recipient - Bound to proof to prevent front-running
 /// 4. withdraw_amount - Amount being swapped
 /// 5. new_commitment - Change commitment for partial swaps
+/// 6. ext_data_hash - Binds the referrer (`SwapParam::ext_data_hash`); swaps
+///    carry no relayer terms
+/// 7. remaining_amount - Value of the change note
+/// 8. note_age_cutoff - Latest allowed note insertion slot (`u64::MAX` when
+///    the vault sets no minimum note age)
+/// 9. policy_hash - Zero; notes bound to an allowance policy cannot be
+///    swapped out of its limit
 fn verify_noir_proof_cpi(
-    verifier_program: &AccountInfo,
+    verifier: &ProofVerifier,
     proof: &[u8],
     root: &[u8; 32],
     nullifier: &[u8; 32],
-    recipient: &Pubkey,
-    amount: u64,
+    swap_param: &SwapParam,
     new_commitment: &[u8; 32],
+    note_age_cutoff: u64,
 ) -> Result<()> {
-    if proof.is_empty() {
-        return Err(ZyncxError::InvalidZKProof.into());
-    }
+    let public_inputs = [
+        *root,
+        *nullifier,
+        swap_param.recipient.to_bytes(),
+        u64_public_input(swap_param.amount_in),
+        *new_commitment,
+        swap_param.ext_data_hash(),
+        u64_public_input(swap_param.remaining_amount),
+        u64_public_input(note_age_cutoff),
+        UNBOUND_POLICY_HASH,
+    ];
 
-    // Build verifier instruction data
-    let mut verifier_input = Vec::with_capacity(proof.len() + 160);
-    
-    // Proof bytes
-    verifier_input.extend_from_slice(proof);
-    
-    // Public inputs (must match circuit order)
-    verifier_input.extend_from_slice(root);
-    verifier_input.extend_from_slice(nullifier);
-    verifier_input.extend_from_slice(&recipient.to_bytes());
-    
-    let mut amount_bytes = [0u8; 32];
-    amount_bytes[24..32].copy_from_slice(&amount.to_be_bytes());
-    verifier_input.extend_from_slice(&amount_bytes);
-    
-    verifier_input.extend_from_slice(new_commitment);
-    
-    let instruction = Instruction {
-        program_id: *verifier_program.key,
-        accounts: vec![],
-        data: verifier_input,
-    };
-    
     msg!("Verifying ZK proof ({} bytes)", proof.len());
-    
-    invoke(
-        &instruction,
-        &[verifier_program.clone()],
-    ).map_err(|e| {
-        msg!("ZK proof verification failed: {:?}", e);
-        ZyncxError::InvalidZKProof
-    })?;
-    
-    Ok(())
+    verifier.verify(proof, &public_inputs)
 }
 
 #[event]
@@ -362,4 +911,40 @@ pub struct SwappedEvent {
     pub min_amount_out: u64,
     pub nullifier: [u8; 32],
     pub new_commitment: [u8; 32],
+    /// Leaf index of `new_commitment` (0 for a full swap)
+    pub leaf_index: u64,
+    /// Tree root after the swap, unchanged for a full one
+    pub new_root: [u8; 32],
+    /// Protocol fee kept out of `amount_in`
+    pub fee: u64,
+    /// Referrer named by the swap (default = none)
+    pub referrer: Pubkey,
+    /// Part of `fee` credited to the referrer
+    pub referral_reward: u64,
+}
+
+#[event]
+pub struct ConfidentialSwapExecuted {
+    pub swap_request: Pubkey,
+    pub source_vault: Pubkey,
+    pub dest_vault: Pubkey,
+    /// Lamports that left the source treasury
+    pub amount_in: u64,
+    pub amount_out: u64,
+    /// Source token oracle price when the swap was queued
+    pub oracle_price: u64,
+    /// Realized `amount_out` per `amount_in`, on the oracle's scale
+    pub execution_price: u64,
+    /// Shortfall of `execution_price` against `oracle_price`
+    pub slippage_bps: i64,
+    pub nullifier: [u8; 32],
+    /// Change note left in the source vault (zero for a full swap)
+    pub new_commitment: [u8; 32],
+    /// Note for `amount_out` inserted into the destination vault
+    pub dest_commitment: [u8; 32],
+    /// Leaf index of `dest_commitment` in the destination tree
+    pub leaf_index: u64,
+    /// Destination tree root once `dest_commitment` was inserted
+    pub new_root: [u8; 32],
+    pub timestamp: i64,
 }
@@ -0,0 +1,254 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ZyncxError;
+use crate::state::{
+    ArciumConfig, ComputationFailure, ComputationFeeEscrow, EncryptedSwapRequest, SwapRequestStatus,
+    UserRequestIndex,
+};
+
+#[derive(Accounts)]
+pub struct CancelSwapRequest<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = user @ ZyncxError::Unauthorized,
+        seeds = [
+            b"swap_request",
+            user.key().as_ref(),
+            &swap_request.computation_offset.to_le_bytes(),
+        ],
+        bump = swap_request.bump,
+    )]
+    pub swap_request: Box<Account<'info, EncryptedSwapRequest>>,
+
+    #[account(
+        mut,
+        seeds = [b"computation_fees"],
+        bump = computation_fee_escrow.bump,
+    )]
+    pub computation_fee_escrow: Box<Account<'info, ComputationFeeEscrow>>,
+
+    #[account(
+        mut,
+        seeds = [b"user_request_index", swap_request.user.as_ref()],
+        bump = user_request_index.bump,
+    )]
+    pub user_request_index: Box<Account<'info, UserRequestIndex>>,
+}
+
+/// Cancel a confidential swap that the MXE has not evaluated yet.
+/// The keeper escrow and computation fee go back to the user; a callback
+/// arriving later only records completion and never triggers trade
+/// side-effects.
+pub fn handler_cancel(ctx: Context<CancelSwapRequest>) -> Result<()> {
+    let swap_request = &mut ctx.accounts.swap_request;
+
+    require!(
+        swap_request.status == SwapRequestStatus::Pending,
+        ZyncxError::InvalidComputationStatus
+    );
+
+    swap_request.status = SwapRequestStatus::Cancelled;
+    swap_request.completed_at = Clock::get()?.unix_timestamp;
+    ctx.accounts
+        .user_request_index
+        .update(swap_request.key(), SwapRequestStatus::Cancelled);
+
+    // Refund the keeper escrow held in the request PDA
+    let refund = swap_request.keeper_escrow;
+    swap_request.keeper_escrow = 0;
+    if refund > 0 {
+        **swap_request.to_account_info().try_borrow_mut_lamports()? -= refund;
+        **ctx.accounts.user.to_account_info().try_borrow_mut_lamports()? += refund;
+    }
+    let computation_fee = refund_computation_fee(
+        swap_request,
+        &mut ctx.accounts.computation_fee_escrow,
+        &ctx.accounts.user.to_account_info(),
+    )?;
+
+    emit!(SwapRequestCancelled {
+        swap_request: swap_request.key(),
+        user: ctx.accounts.user.key(),
+        computation_offset: swap_request.computation_offset,
+        refunded: refund,
+        computation_fee_refunded: computation_fee,
+    });
+
+    msg!("Swap request cancelled, refunded {} lamports", refund);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ExpireSwapRequest<'info> {
+    /// CHECK: Original requester; receives the keeper escrow refund
+    #[account(mut, address = swap_request.user @ ZyncxError::Unauthorized)]
+    pub user: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"swap_request",
+            swap_request.user.as_ref(),
+            &swap_request.computation_offset.to_le_bytes(),
+        ],
+        bump = swap_request.bump,
+    )]
+    pub swap_request: Box<Account<'info, EncryptedSwapRequest>>,
+
+    #[account(seeds = [b"arcium_config"], bump = arcium_config.bump)]
+    pub arcium_config: Box<Account<'info, ArciumConfig>>,
+
+    #[account(
+        mut,
+        seeds = [b"computation_fees"],
+        bump = computation_fee_escrow.bump,
+    )]
+    pub computation_fee_escrow: Box<Account<'info, ComputationFeeEscrow>>,
+
+    #[account(
+        mut,
+        seeds = [b"user_request_index", swap_request.user.as_ref()],
+        bump = user_request_index.bump,
+    )]
+    pub user_request_index: Box<Account<'info, UserRequestIndex>>,
+}
+
+/// Mark a request the MXE never answered as Expired and refund its keeper
+/// escrow and computation fee to the user. Permissionless once `expires_at` has passed. The
+/// failure is `InsufficientFee` when the escrow never covered the configured
+/// computation fee, `ClusterTimeout` otherwise.
+pub fn handler_expire(ctx: Context<ExpireSwapRequest>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let swap_request = &mut ctx.accounts.swap_request;
+
+    require!(
+        swap_request.status == SwapRequestStatus::Pending,
+        ZyncxError::InvalidComputationStatus
+    );
+    require!(now >= swap_request.expires_at, ZyncxError::ComputationNotExpired);
+
+    swap_request.status = SwapRequestStatus::Expired;
+    swap_request.completed_at = now;
+    ctx.accounts
+        .user_request_index
+        .update(swap_request.key(), SwapRequestStatus::Expired);
+    let failure = if swap_request.keeper_escrow < ctx.accounts.arcium_config.computation_fee {
+        ComputationFailure::InsufficientFee
+    } else {
+        ComputationFailure::ClusterTimeout
+    };
+    swap_request.failure = Some(failure);
+
+    let refund = swap_request.keeper_escrow;
+    swap_request.keeper_escrow = 0;
+    if refund > 0 {
+        **swap_request.to_account_info().try_borrow_mut_lamports()? -= refund;
+        **ctx.accounts.user.to_account_info().try_borrow_mut_lamports()? += refund;
+    }
+    let computation_fee = refund_computation_fee(
+        swap_request,
+        &mut ctx.accounts.computation_fee_escrow,
+        &ctx.accounts.user.to_account_info(),
+    )?;
+
+    emit!(SwapRequestExpired {
+        swap_request: swap_request.key(),
+        user: swap_request.user,
+        computation_offset: swap_request.computation_offset,
+        refunded: refund,
+        computation_fee_refunded: computation_fee,
+        failure,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CloseSwapRequest<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        close = user,
+        has_one = user @ ZyncxError::Unauthorized,
+        seeds = [
+            b"swap_request",
+            user.key().as_ref(),
+            &swap_request.computation_offset.to_le_bytes(),
+        ],
+        bump = swap_request.bump,
+    )]
+    pub swap_request: Box<Account<'info, EncryptedSwapRequest>>,
+
+    #[account(
+        mut,
+        seeds = [b"user_request_index", swap_request.user.as_ref()],
+        bump = user_request_index.bump,
+    )]
+    pub user_request_index: Box<Account<'info, UserRequestIndex>>,
+}
+
+/// Close a terminal swap request, returning its rent (and any unspent
+/// keeper escrow) to the user. A late callback for a cancelled or expired
+/// request fails on the missing account, which discards nothing of value.
+pub fn handler_close(ctx: Context<CloseSwapRequest>) -> Result<()> {
+    let swap_request = &ctx.accounts.swap_request;
+    require!(swap_request.is_terminal(), ZyncxError::InvalidComputationStatus);
+    ctx.accounts.user_request_index.remove(swap_request.key());
+
+    emit!(SwapRequestClosed {
+        swap_request: swap_request.key(),
+        user: swap_request.user,
+        status: swap_request.status,
+    });
+
+    Ok(())
+}
+
+/// Return the computation fee the request still holds in the escrow to
+/// `user`
+fn refund_computation_fee(
+    swap_request: &mut EncryptedSwapRequest,
+    escrow: &mut Account<ComputationFeeEscrow>,
+    user: &AccountInfo,
+) -> Result<u64> {
+    let fee = std::mem::take(&mut swap_request.computation_fee);
+    if fee > 0 {
+        escrow.release(fee)?;
+        **escrow.to_account_info().try_borrow_mut_lamports()? -= fee;
+        **user.try_borrow_mut_lamports()? += fee;
+    }
+    Ok(fee)
+}
+
+#[event]
+pub struct SwapRequestCancelled {
+    pub swap_request: Pubkey,
+    pub user: Pubkey,
+    pub computation_offset: u64,
+    pub refunded: u64,
+    pub computation_fee_refunded: u64,
+}
+
+#[event]
+pub struct SwapRequestExpired {
+    pub swap_request: Pubkey,
+    pub user: Pubkey,
+    pub computation_offset: u64,
+    pub refunded: u64,
+    pub computation_fee_refunded: u64,
+    pub failure: ComputationFailure,
+}
+
+#[event]
+pub struct SwapRequestClosed {
+    pub swap_request: Pubkey,
+    pub user: Pubkey,
+    /// Status at the time the account was closed
+    pub status: SwapRequestStatus,
+}
@@ -0,0 +1,516 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+
+use crate::errors::ZyncxError;
+use crate::state::{
+    ColdSpend, ColdSpendStatus, Role, Roles, TreasuryPolicy, TreasurySplitParams, VaultState,
+    VaultType, ROLE_COUNT,
+};
+
+#[derive(Accounts)]
+pub struct InitializeTreasuryPolicy<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(seeds = [b"roles"], bump = roles.bump)]
+    pub roles: Box<Account<'info, Roles>>,
+
+    #[account(
+        seeds = [b"vault", vault.asset_mint.as_ref()],
+        bump = vault.bump,
+    )]
+    pub vault: Box<Account<'info, VaultState>>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = TreasuryPolicy::INIT_SPACE,
+        seeds = [b"treasury_policy", vault.key().as_ref()],
+        bump
+    )]
+    pub treasury_policy: Box<Account<'info, TreasuryPolicy>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Split a native vault's SOL into hot and cold treasuries. Later changes
+/// to the split go through the config timelock.
+pub fn handler_initialize_treasury_policy(
+    ctx: Context<InitializeTreasuryPolicy>,
+    params: TreasurySplitParams,
+) -> Result<()> {
+    require!(
+        ctx.accounts.roles.has_role(Role::Admin, &ctx.accounts.admin.key()),
+        ZyncxError::Unauthorized
+    );
+    require!(
+        ctx.accounts.vault.vault_type == VaultType::Native,
+        ZyncxError::VaultNotFound
+    );
+    params.validate()?;
+
+    let vault_key = ctx.accounts.vault.key();
+    let policy = &mut ctx.accounts.treasury_policy;
+    policy.bump = ctx.bumps.treasury_policy;
+    policy.vault = vault_key;
+    policy.cold_bump =
+        Pubkey::find_program_address(&[b"cold_treasury", vault_key.as_ref()], ctx.program_id).1;
+    policy.apply_split(&params);
+    policy.last_refill_at = 0;
+    policy.total_swept = 0;
+    policy.total_refilled = 0;
+    policy.total_cold_spent = 0;
+
+    emit!(TreasuryPolicyInitialized {
+        vault: policy.vault,
+        hot_target_bps: params.hot_target_bps,
+        refill_threshold_bps: params.refill_threshold_bps,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RebalanceTreasury<'info> {
+    pub cranker: Signer<'info>,
+
+    #[account(
+        seeds = [b"vault", vault.asset_mint.as_ref()],
+        bump = vault.bump,
+    )]
+    pub vault: Box<Account<'info, VaultState>>,
+
+    #[account(
+        mut,
+        has_one = vault,
+        seeds = [b"treasury_policy", vault.key().as_ref()],
+        bump = treasury_policy.bump,
+    )]
+    pub treasury_policy: Box<Account<'info, TreasuryPolicy>>,
+
+    /// CHECK: Vault PDA that holds SOL
+    #[account(
+        mut,
+        seeds = [b"vault_treasury", vault.key().as_ref()],
+        bump,
+    )]
+    pub vault_treasury: AccountInfo<'info>,
+
+    /// CHECK: Vault PDA holding the cold share of the SOL
+    #[account(
+        mut,
+        seeds = [b"cold_treasury", vault.key().as_ref()],
+        bump,
+    )]
+    pub cold_treasury: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Sweep whatever the hot treasury holds above its target share into the
+/// cold treasury (permissionless)
+pub fn handler_sweep_to_cold(ctx: Context<RebalanceTreasury>) -> Result<()> {
+    let hot = &ctx.accounts.vault_treasury;
+    let cold = &ctx.accounts.cold_treasury;
+    let reserve = Rent::get()?.minimum_balance(0);
+
+    let (hot_balance, cold_balance) = treasury_balances(hot, cold, reserve);
+    let policy = &mut ctx.accounts.treasury_policy;
+    let amount = policy.sweep_amount(hot_balance, cold_balance);
+    // The first sweep also has to fund the cold treasury's rent reserve
+    require!(
+        amount > 0 && cold.lamports().saturating_add(amount) >= reserve,
+        ZyncxError::TreasuryBalanced
+    );
+
+    pay_from_vault_treasury(
+        &ctx.accounts.system_program,
+        hot,
+        cold,
+        &policy.vault,
+        ctx.bumps.vault_treasury,
+        amount,
+    )?;
+    policy.total_swept = policy.total_swept.saturating_add(amount);
+
+    emit!(TreasurySwept {
+        vault: policy.vault,
+        amount,
+        hot_balance: hot_balance - amount,
+        cold_balance: cold_balance.saturating_add(amount),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Top the hot treasury back up to its target share once it has dropped
+/// below the refill threshold (permissionless, rate-limited). Withdrawals
+/// that pass the policy accounts do the same on their own.
+pub fn handler_refill_hot_treasury(ctx: Context<RebalanceTreasury>) -> Result<()> {
+    let refilled = refill_from_cold(
+        &mut ctx.accounts.treasury_policy,
+        &ctx.accounts.vault_treasury,
+        &ctx.accounts.cold_treasury,
+        &ctx.accounts.system_program,
+        Clock::get()?.unix_timestamp,
+    )?;
+    require!(refilled > 0, ZyncxError::RefillNotDue);
+    Ok(())
+}
+
+/// Refill the hot treasury from cold if the policy says it is due and
+/// return the lamports moved (0 when not due)
+pub fn refill_from_cold<'info>(
+    policy: &mut TreasuryPolicy,
+    hot: &AccountInfo<'info>,
+    cold: &AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+    now: i64,
+) -> Result<u64> {
+    let reserve = Rent::get()?.minimum_balance(0);
+    let (hot_balance, cold_balance) = treasury_balances(hot, cold, reserve);
+    let amount = policy.refill_amount(hot_balance, cold_balance, now);
+    if amount == 0 {
+        return Ok(0);
+    }
+
+    transfer_lamports(system_program, cold, hot, &policy.cold_seeds(), amount)?;
+    policy.last_refill_at = now;
+    policy.total_refilled = policy.total_refilled.saturating_add(amount);
+
+    emit!(HotTreasuryRefilled {
+        vault: policy.vault,
+        amount,
+        hot_balance: hot_balance.saturating_add(amount),
+        cold_balance: cold_balance - amount,
+        timestamp: now,
+    });
+
+    Ok(amount)
+}
+
+/// Spendable balances of the hot and cold treasuries, above rent
+fn treasury_balances(hot: &AccountInfo, cold: &AccountInfo, reserve: u64) -> (u64, u64) {
+    (
+        hot.lamports().saturating_sub(reserve),
+        cold.lamports().saturating_sub(reserve),
+    )
+}
+
+/// Pay SOL out of a vault's hot treasury. Every lamport payout from
+/// `vault_treasury` (single and batch withdrawals, direct swaps, referral
+/// claims, crank tips, sweeps to cold) goes through here; only the WSOL
+/// wrapping of Jupiter routes signs its own system CPIs (`dex::wsol`).
+pub fn pay_from_vault_treasury<'info>(
+    system_program: &Program<'info, System>,
+    vault_treasury: &AccountInfo<'info>,
+    to: &AccountInfo<'info>,
+    vault: &Pubkey,
+    treasury_bump: u8,
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+    let bump = [treasury_bump];
+    let seeds: &[&[u8]] = &[b"vault_treasury", vault.as_ref(), &bump];
+    transfer_lamports(system_program, vault_treasury, to, seeds, amount)
+}
+
+/// Move SOL out of a treasury PDA. Both treasuries are system-owned, so
+/// only the system program can debit them, with the PDA signing.
+fn transfer_lamports<'info>(
+    system_program: &Program<'info, System>,
+    from: &AccountInfo<'info>,
+    to: &AccountInfo<'info>,
+    seeds: &[&[u8]],
+    amount: u64,
+) -> Result<()> {
+    system_program::transfer(
+        CpiContext::new_with_signer(
+            system_program.to_account_info(),
+            system_program::Transfer {
+                from: from.clone(),
+                to: to.clone(),
+            },
+            &[seeds],
+        ),
+        amount,
+    )
+}
+
+#[derive(Accounts)]
+#[instruction(spend_id: u64)]
+pub struct ProposeColdSpend<'info> {
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    #[account(
+        seeds = [b"vault", vault.asset_mint.as_ref()],
+        bump = vault.bump,
+    )]
+    pub vault: Box<Account<'info, VaultState>>,
+
+    #[account(
+        has_one = vault,
+        seeds = [b"treasury_policy", vault.key().as_ref()],
+        bump = treasury_policy.bump,
+    )]
+    pub treasury_policy: Box<Account<'info, TreasuryPolicy>>,
+
+    /// CHECK: Receives the SOL once the spend executes
+    pub recipient: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = proposer,
+        space = ColdSpend::INIT_SPACE,
+        seeds = [b"cold_spend", vault.key().as_ref(), &spend_id.to_le_bytes()],
+        bump
+    )]
+    pub cold_spend: Box<Account<'info, ColdSpend>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Propose paying SOL out of the cold treasury. Anyone may propose; nothing
+/// moves until a quorum of role holders approves and the vault's config timelock
+/// has passed since the proposal.
+pub fn handler_propose_cold_spend(
+    ctx: Context<ProposeColdSpend>,
+    spend_id: u64,
+    amount: u64,
+    memo_hash: [u8; 32],
+) -> Result<()> {
+    require!(amount > 0, ZyncxError::InvalidWithdrawalAmount);
+
+    let now = Clock::get()?.unix_timestamp;
+    let eta = now
+        .checked_add(ctx.accounts.vault.config_timelock_seconds)
+        .ok_or(ZyncxError::ArithmeticOverflow)?;
+
+    let spend = &mut ctx.accounts.cold_spend;
+    spend.bump = ctx.bumps.cold_spend;
+    spend.vault = ctx.accounts.vault.key();
+    spend.spend_id = spend_id;
+    spend.proposer = ctx.accounts.proposer.key();
+    spend.recipient = ctx.accounts.recipient.key();
+    spend.amount = amount;
+    spend.memo_hash = memo_hash;
+    spend.approvals = 0;
+    spend.rejections = 0;
+    spend.voters = [Pubkey::default(); ROLE_COUNT];
+    spend.status = ColdSpendStatus::Pending;
+    spend.proposed_at = now;
+    spend.eta = eta;
+    spend.resolved_at = 0;
+
+    emit!(ColdSpendProposed {
+        spend: spend.key(),
+        vault: spend.vault,
+        spend_id,
+        proposer: spend.proposer,
+        recipient: spend.recipient,
+        amount,
+        memo_hash,
+        eta,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct VoteColdSpend<'info> {
+    pub voter: Signer<'info>,
+
+    #[account(seeds = [b"roles"], bump = roles.bump)]
+    pub roles: Box<Account<'info, Roles>>,
+
+    #[account(
+        mut,
+        seeds = [b"cold_spend", cold_spend.vault.as_ref(), &cold_spend.spend_id.to_le_bytes()],
+        bump = cold_spend.bump,
+    )]
+    pub cold_spend: Box<Account<'info, ColdSpend>>,
+}
+
+/// Approve or reject a cold spend on behalf of a protocol role the voter
+/// holds. A key holding several roles still casts a single vote.
+pub fn handler_vote_cold_spend(ctx: Context<VoteColdSpend>, role: Role, approve: bool) -> Result<()> {
+    let voter = ctx.accounts.voter.key();
+    require!(
+        ctx.accounts.roles.has_role(role, &voter),
+        ZyncxError::Unauthorized
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    let spend = &mut ctx.accounts.cold_spend;
+    spend.record_vote(role, voter, approve)?;
+    if spend.status == ColdSpendStatus::Rejected {
+        spend.resolved_at = now;
+    }
+
+    emit!(ColdSpendVoted {
+        spend: spend.key(),
+        voter,
+        role,
+        approve,
+        approvals: spend.approvals,
+        rejections: spend.rejections,
+        status: spend.status,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ExecuteColdSpend<'info> {
+    pub executor: Signer<'info>,
+
+    #[account(seeds = [b"roles"], bump = roles.bump)]
+    pub roles: Box<Account<'info, Roles>>,
+
+    #[account(
+        seeds = [b"vault", vault.asset_mint.as_ref()],
+        bump = vault.bump,
+    )]
+    pub vault: Box<Account<'info, VaultState>>,
+
+    #[account(
+        mut,
+        has_one = vault,
+        seeds = [b"treasury_policy", vault.key().as_ref()],
+        bump = treasury_policy.bump,
+    )]
+    pub treasury_policy: Box<Account<'info, TreasuryPolicy>>,
+
+    /// CHECK: Vault PDA holding the cold share of the SOL
+    #[account(
+        mut,
+        seeds = [b"cold_treasury", vault.key().as_ref()],
+        bump,
+    )]
+    pub cold_treasury: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        has_one = vault,
+        seeds = [b"cold_spend", vault.key().as_ref(), &cold_spend.spend_id.to_le_bytes()],
+        bump = cold_spend.bump,
+    )]
+    pub cold_spend: Box<Account<'info, ColdSpend>>,
+
+    /// CHECK: Must be the recipient fixed at proposal
+    #[account(
+        mut,
+        address = cold_spend.recipient @ ZyncxError::RecipientMismatch,
+    )]
+    pub recipient: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Pay an approved cold spend once its timelock has elapsed (permissionless).
+/// Approvals only count while the voter still holds the role it voted for.
+pub fn handler_execute_cold_spend(ctx: Context<ExecuteColdSpend>) -> Result<()> {
+    let spend = &mut ctx.accounts.cold_spend;
+    require!(
+        spend.is_approved(&ctx.accounts.roles),
+        ZyncxError::ColdSpendNotApproved
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    require!(now >= spend.eta, ZyncxError::TimelockNotElapsed);
+
+    let cold = &ctx.accounts.cold_treasury;
+    let reserve = Rent::get()?.minimum_balance(0);
+    require!(
+        cold.lamports().saturating_sub(reserve) >= spend.amount,
+        ZyncxError::InsufficientFunds
+    );
+
+    transfer_lamports(
+        &ctx.accounts.system_program,
+        cold,
+        &ctx.accounts.recipient.to_account_info(),
+        &ctx.accounts.treasury_policy.cold_seeds(),
+        spend.amount,
+    )?;
+
+    spend.status = ColdSpendStatus::Executed;
+    spend.resolved_at = now;
+    let policy = &mut ctx.accounts.treasury_policy;
+    policy.total_cold_spent = policy.total_cold_spent.saturating_add(spend.amount);
+
+    emit!(ColdSpendExecuted {
+        spend: spend.key(),
+        vault: spend.vault,
+        recipient: spend.recipient,
+        amount: spend.amount,
+        cold_remaining: cold.lamports().saturating_sub(reserve),
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct TreasuryPolicyInitialized {
+    pub vault: Pubkey,
+    pub hot_target_bps: u16,
+    pub refill_threshold_bps: u16,
+}
+
+#[event]
+pub struct TreasurySwept {
+    pub vault: Pubkey,
+    pub amount: u64,
+    pub hot_balance: u64,
+    pub cold_balance: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct HotTreasuryRefilled {
+    pub vault: Pubkey,
+    pub amount: u64,
+    pub hot_balance: u64,
+    pub cold_balance: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ColdSpendProposed {
+    pub spend: Pubkey,
+    pub vault: Pubkey,
+    pub spend_id: u64,
+    pub proposer: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub memo_hash: [u8; 32],
+    pub eta: i64,
+}
+
+#[event]
+pub struct ColdSpendVoted {
+    pub spend: Pubkey,
+    pub voter: Pubkey,
+    pub role: Role,
+    pub approve: bool,
+    pub approvals: u8,
+    pub rejections: u8,
+    pub status: ColdSpendStatus,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ColdSpendExecuted {
+    pub spend: Pubkey,
+    pub vault: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub cold_remaining: u64,
+    pub timestamp: i64,
+}
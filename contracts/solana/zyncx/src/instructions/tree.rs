@@ -0,0 +1,156 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ZyncxError;
+use crate::state::{MerkleTreeState, VaultState, CURRENT_ACCOUNT_VERSION, FRONTIER_SIZE, MAX_TREE_LEAVES, ROOT_HISTORY_SIZE};
+
+#[derive(Accounts)]
+pub struct PreRotateTree<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault.asset_mint.as_ref()],
+        bump = vault.bump,
+    )]
+    pub vault: Box<Account<'info, VaultState>>,
+
+    #[account(
+        address = vault.merkle_tree @ ZyncxError::InactiveMerkleTree,
+    )]
+    pub current_tree: Box<Account<'info, MerkleTreeState>>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = MerkleTreeState::INIT_SPACE,
+        seeds = [
+            b"merkle_tree",
+            vault.key().as_ref(),
+            &(vault.tree_epoch + 1).to_le_bytes(),
+        ],
+        bump
+    )]
+    pub new_tree: Box<Account<'info, MerkleTreeState>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Switch deposits to a fresh tree once the active one crosses the rotation
+/// threshold. Permissionless so keepers can react to `TreeNearCapacity`.
+///
+/// The previous tree stays spendable: withdrawals and swaps against it keep
+/// inserting change commitments into the headroom above the threshold.
+pub fn handler_pre_rotate(ctx: Context<PreRotateTree>) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let current_tree = &ctx.accounts.current_tree;
+    let new_tree = &mut ctx.accounts.new_tree;
+
+    require!(
+        current_tree.is_near_capacity(vault.rotation_threshold_bps),
+        ZyncxError::TreeBelowRotationThreshold
+    );
+
+    let epoch = vault.tree_epoch + 1;
+
+    new_tree.bump = ctx.bumps.new_tree;
+    new_tree.depth = 0;
+    new_tree.size = 0;
+    new_tree.current_root_index = 0;
+    new_tree.root = vault.tree_hash.empty_root();
+    new_tree.roots = [[0u8; 32]; ROOT_HISTORY_SIZE];
+    new_tree.vault = vault.key();
+    new_tree.epoch = epoch;
+    new_tree.max_leaves = vault.max_tree_leaves;
+    new_tree.tree_hash = vault.tree_hash;
+    new_tree.frontier = [[0u8; 32]; FRONTIER_SIZE];
+    new_tree.version = CURRENT_ACCOUNT_VERSION;
+
+    vault.tree_epoch = epoch;
+    vault.merkle_tree = new_tree.key();
+
+    emit!(TreeRotated {
+        vault: vault.key(),
+        previous_tree: current_tree.key(),
+        new_tree: new_tree.key(),
+        epoch,
+    });
+
+    msg!("Rotated merkle tree to epoch {}", epoch);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ConfigureTree<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault.asset_mint.as_ref()],
+        bump = vault.bump,
+        has_one = authority @ ZyncxError::Unauthorized,
+    )]
+    pub vault: Box<Account<'info, VaultState>>,
+
+    #[account(
+        mut,
+        address = vault.merkle_tree @ ZyncxError::InactiveMerkleTree,
+    )]
+    pub merkle_tree: Box<Account<'info, MerkleTreeState>>,
+}
+
+/// Set the leaf capacity and rotation threshold for the vault. The capacity
+/// also applies to the active tree as long as it still fits its leaves.
+/// Leaves are not stored, so capacity only bounds when the tree rotates.
+pub fn handler_configure(
+    ctx: Context<ConfigureTree>,
+    max_leaves: u32,
+    rotation_threshold_bps: u16,
+) -> Result<()> {
+    require!(
+        max_leaves > 0 && max_leaves as usize <= MAX_TREE_LEAVES,
+        ZyncxError::InvalidTreeConfig
+    );
+    require!(
+        rotation_threshold_bps > 0 && rotation_threshold_bps <= 10_000,
+        ZyncxError::InvalidTreeConfig
+    );
+
+    let vault = &mut ctx.accounts.vault;
+    let merkle_tree = &mut ctx.accounts.merkle_tree;
+
+    require!(
+        merkle_tree.size <= max_leaves as u64,
+        ZyncxError::InvalidTreeConfig
+    );
+
+    vault.max_tree_leaves = max_leaves;
+    vault.rotation_threshold_bps = rotation_threshold_bps;
+    merkle_tree.max_leaves = max_leaves;
+
+    msg!(
+        "Tree config updated: max_leaves={}, rotation_threshold_bps={}",
+        max_leaves,
+        rotation_threshold_bps
+    );
+
+    Ok(())
+}
+
+#[event]
+pub struct TreeNearCapacity {
+    pub vault: Pubkey,
+    pub merkle_tree: Pubkey,
+    pub epoch: u64,
+    pub size: u64,
+    pub max_leaves: u32,
+}
+
+#[event]
+pub struct TreeRotated {
+    pub vault: Pubkey,
+    pub previous_tree: Pubkey,
+    pub new_tree: Pubkey,
+    pub epoch: u64,
+}
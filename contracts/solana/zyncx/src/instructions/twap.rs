@@ -0,0 +1,283 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+
+use crate::dex::{execute_jupiter_swap_signed, JUPITER_V6_PROGRAM_ID};
+use crate::errors::ZyncxError;
+use crate::state::{EncryptedTWAPConfig, TWAPStatus, VaultState, MIN_TWAP_INTERVAL_SECONDS};
+
+#[derive(Accounts)]
+#[instruction(twap_id: u64)]
+pub struct CreateTwap<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        seeds = [b"vault", source_vault.asset_mint.as_ref()],
+        bump = source_vault.bump,
+    )]
+    pub source_vault: Box<Account<'info, VaultState>>,
+
+    #[account(
+        seeds = [b"vault", dest_vault.asset_mint.as_ref()],
+        bump = dest_vault.bump,
+        constraint = dest_vault.key() != source_vault.key() @ ZyncxError::InvalidSwapRoute,
+    )]
+    pub dest_vault: Box<Account<'info, VaultState>>,
+
+    /// CHECK: Receives the swapped tokens on every slice
+    pub recipient: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + EncryptedTWAPConfig::INIT_SPACE,
+        seeds = [b"twap", user.key().as_ref(), &twap_id.to_le_bytes()],
+        bump,
+    )]
+    pub twap_config: Box<Account<'info, EncryptedTWAPConfig>>,
+
+    /// CHECK: System-owned PDA escrowing the SOL budget
+    #[account(
+        mut,
+        seeds = [b"twap_escrow", twap_config.key().as_ref()],
+        bump,
+    )]
+    pub twap_escrow: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Create a TWAP execution. `encrypted_params` are the client's
+/// Enc<Shared, TWAPParams> ciphertexts. `budget` lamports are escrowed; it
+/// only needs to cover the hidden total, so over-funding it keeps the order
+/// size private. The unspent remainder is refunded on cancel.
+pub fn handler_create_twap(
+    ctx: Context<CreateTwap>,
+    twap_id: u64,
+    encrypted_params: [[u8; 32]; 3],
+    params_nonce: u128,
+    client_pubkey: [u8; 32],
+    interval_seconds: u64,
+    budget: u64,
+) -> Result<()> {
+    require!(
+        interval_seconds >= MIN_TWAP_INTERVAL_SECONDS && interval_seconds <= i64::MAX as u64,
+        ZyncxError::InvalidTwapConfig
+    );
+    require!(budget > 0, ZyncxError::InvalidTwapConfig);
+
+    system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.user.to_account_info(),
+                to: ctx.accounts.twap_escrow.to_account_info(),
+            },
+        ),
+        budget,
+    )?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let twap = &mut ctx.accounts.twap_config;
+
+    twap.bump = ctx.bumps.twap_config;
+    twap.user = ctx.accounts.user.key();
+    twap.source_vault = ctx.accounts.source_vault.key();
+    twap.dest_vault = ctx.accounts.dest_vault.key();
+    twap.encrypted_params = encrypted_params;
+    twap.params_nonce = params_nonce;
+    twap.client_pubkey = client_pubkey;
+    twap.interval_seconds = interval_seconds;
+    twap.next_execution_at = now;
+    twap.status = TWAPStatus::Active;
+    twap.created_at = now;
+    twap.slices_executed = 0;
+    twap.twap_id = twap_id;
+    twap.recipient = ctx.accounts.recipient.key();
+    twap.pending_amount = 0;
+    twap.computation_pending = false;
+
+    emit!(TwapCreated {
+        twap: twap.key(),
+        user: twap.user,
+        source_vault: twap.source_vault,
+        dest_vault: twap.dest_vault,
+        interval_seconds,
+        budget,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SettleTwapSlice<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"twap", twap_config.user.as_ref(), &twap_config.twap_id.to_le_bytes()],
+        bump = twap_config.bump,
+    )]
+    pub twap_config: Box<Account<'info, EncryptedTWAPConfig>>,
+
+    /// CHECK: System-owned PDA escrowing the SOL budget
+    #[account(
+        mut,
+        seeds = [b"twap_escrow", twap_config.key().as_ref()],
+        bump,
+    )]
+    pub twap_escrow: UncheckedAccount<'info>,
+
+    /// CHECK: Must be the recipient fixed at creation
+    #[account(
+        mut,
+        address = twap_config.recipient @ ZyncxError::RecipientMismatch,
+    )]
+    pub recipient: UncheckedAccount<'info>,
+
+    /// CHECK: Jupiter program
+    #[account(address = JUPITER_V6_PROGRAM_ID)]
+    pub jupiter_program: AccountInfo<'info>,
+    // Remaining accounts: All accounts required by Jupiter swap route
+}
+
+/// Execute the slice approved by the last `process_twap` run through
+/// Jupiter. Crankable; the route may not spend more than the slice.
+pub fn handler_settle_twap_slice<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SettleTwapSlice<'info>>,
+    swap_data: Vec<u8>,
+) -> Result<()> {
+    let amount = ctx.accounts.twap_config.pending_amount;
+    require!(amount > 0, ZyncxError::InvalidSwapAmount);
+
+    let escrow = &ctx.accounts.twap_escrow;
+    let before = escrow.lamports();
+    let reserve = Rent::get()?.minimum_balance(0);
+    require!(
+        before >= amount.saturating_add(reserve),
+        ZyncxError::InsufficientFunds
+    );
+
+    let twap_key = ctx.accounts.twap_config.key();
+    let bump = [ctx.bumps.twap_escrow];
+    let seeds: &[&[u8]] = &[b"twap_escrow", twap_key.as_ref(), &bump];
+
+    execute_jupiter_swap_signed(
+        &escrow.to_account_info(),
+        &ctx.accounts.recipient.to_account_info(),
+        &ctx.accounts.jupiter_program,
+        swap_data,
+        ctx.remaining_accounts,
+        &[seeds],
+    )?;
+
+    let spent = before.saturating_sub(escrow.lamports());
+    require!(spent <= amount, ZyncxError::InvalidSwapAmount);
+
+    let twap = &mut ctx.accounts.twap_config;
+    twap.pending_amount = 0;
+    twap.slices_executed = twap.slices_executed.saturating_add(1);
+
+    emit!(TwapSliceSettled {
+        twap: twap_key,
+        amount_in: spent,
+        slices_executed: twap.slices_executed,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CancelTwap<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        close = user,
+        has_one = user @ ZyncxError::Unauthorized,
+        seeds = [b"twap", user.key().as_ref(), &twap_config.twap_id.to_le_bytes()],
+        bump = twap_config.bump,
+    )]
+    pub twap_config: Box<Account<'info, EncryptedTWAPConfig>>,
+
+    /// CHECK: System-owned PDA escrowing the SOL budget
+    #[account(
+        mut,
+        seeds = [b"twap_escrow", twap_config.key().as_ref()],
+        bump,
+    )]
+    pub twap_escrow: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Cancel (or close a completed) TWAP, refunding the unspent escrow and the
+/// config rent. Not allowed while a `process_twap` computation is in flight.
+pub fn handler_cancel_twap(ctx: Context<CancelTwap>) -> Result<()> {
+    require!(
+        !ctx.accounts.twap_config.computation_pending,
+        ZyncxError::InvalidComputationStatus
+    );
+
+    let twap_key = ctx.accounts.twap_config.key();
+    let refund = ctx.accounts.twap_escrow.lamports();
+
+    if refund > 0 {
+        let bump = [ctx.bumps.twap_escrow];
+        let seeds: &[&[u8]] = &[b"twap_escrow", twap_key.as_ref(), &bump];
+
+        system_program::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.twap_escrow.to_account_info(),
+                    to: ctx.accounts.user.to_account_info(),
+                },
+                &[seeds],
+            ),
+            refund,
+        )?;
+    }
+
+    emit!(TwapCancelled {
+        twap: twap_key,
+        user: ctx.accounts.user.key(),
+        slices_executed: ctx.accounts.twap_config.slices_executed,
+        refunded: refund,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct TwapCreated {
+    pub twap: Pubkey,
+    pub user: Pubkey,
+    pub source_vault: Pubkey,
+    pub dest_vault: Pubkey,
+    pub interval_seconds: u64,
+    pub budget: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TwapSliceSettled {
+    pub twap: Pubkey,
+    pub amount_in: u64,
+    pub slices_executed: u16,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TwapCancelled {
+    pub twap: Pubkey,
+    pub user: Pubkey,
+    pub slices_executed: u16,
+    pub refunded: u64,
+    pub timestamp: i64,
+}
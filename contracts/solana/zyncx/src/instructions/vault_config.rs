@@ -0,0 +1,418 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ZyncxError;
+use crate::state::{
+    ConfigChange, ExclusionRoot, MerkleTreeState, PendingConfigChange, Role, Roles, TreasuryPolicy,
+    VaultFeeState, VaultState, MAX_CONFIG_TIMELOCK_SECONDS, MAX_DENOMINATIONS, MAX_INSURANCE_SHARE_BPS,
+};
+
+#[derive(Accounts)]
+pub struct ConfigureVault<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault.asset_mint.as_ref()],
+        bump = vault.bump,
+        has_one = authority @ ZyncxError::Unauthorized,
+    )]
+    pub vault: Box<Account<'info, VaultState>>,
+}
+
+/// Restrict deposits and withdrawals to a fixed set of amounts.
+/// Uniform amounts keep notes indistinguishable by value, which is what
+/// gives the anonymity set its size.
+pub fn handler_set_denominations(
+    ctx: Context<ConfigureVault>,
+    fixed_denominations: bool,
+    denominations: Vec<u64>,
+) -> Result<()> {
+    require!(
+        denominations.len() <= MAX_DENOMINATIONS,
+        ZyncxError::InvalidDenominationConfig
+    );
+    require!(
+        !fixed_denominations || !denominations.is_empty(),
+        ZyncxError::InvalidDenominationConfig
+    );
+    require!(
+        denominations.iter().all(|d| *d > 0),
+        ZyncxError::InvalidDenominationConfig
+    );
+
+    let vault = &mut ctx.accounts.vault;
+
+    let mut stored = [0u64; MAX_DENOMINATIONS];
+    stored[..denominations.len()].copy_from_slice(&denominations);
+
+    vault.fixed_denominations = fixed_denominations;
+    vault.denomination_count = denominations.len() as u8;
+    vault.denominations = stored;
+
+    emit!(DenominationsUpdated {
+        vault: vault.key(),
+        fixed_denominations,
+        denominations,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct DenominationsUpdated {
+    pub vault: Pubkey,
+    pub fixed_denominations: bool,
+    pub denominations: Vec<u64>,
+}
+
+/// Set deposit caps and the per-epoch rate limit (0 disables each).
+/// Lets a new vault launch conservatively and open up as it matures.
+pub fn handler_set_deposit_limits(
+    ctx: Context<ConfigureVault>,
+    max_total_deposited: u64,
+    max_deposit_per_tx: u64,
+    max_deposit_per_epoch: u64,
+) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    vault.max_total_deposited = max_total_deposited;
+    vault.max_deposit_per_tx = max_deposit_per_tx;
+    vault.max_deposit_per_epoch = max_deposit_per_epoch;
+
+    emit!(DepositLimitsUpdated {
+        vault: vault.key(),
+        max_total_deposited,
+        max_deposit_per_tx,
+        max_deposit_per_epoch,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct DepositLimitsUpdated {
+    pub vault: Pubkey,
+    pub max_total_deposited: u64,
+    pub max_deposit_per_tx: u64,
+    pub max_deposit_per_epoch: u64,
+}
+
+/// Require notes to age `min_note_age_slots` before they can be spent
+/// (0 disables). Proofs must then commit to the vault's note-age cutoff.
+pub fn handler_set_min_note_age(ctx: Context<ConfigureVault>, min_note_age_slots: u64) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    vault.min_note_age_slots = min_note_age_slots;
+
+    emit!(MinNoteAgeUpdated {
+        vault: vault.key(),
+        min_note_age_slots,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct MinNoteAgeUpdated {
+    pub vault: Pubkey,
+    pub min_note_age_slots: u64,
+}
+
+/// Lengthen the config timelock. Takes effect immediately since it only
+/// makes future changes slower; shortening goes through the timelock.
+pub fn handler_increase_timelock(ctx: Context<ConfigureVault>, timelock_seconds: i64) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+
+    require!(
+        timelock_seconds >= vault.config_timelock_seconds
+            && timelock_seconds <= MAX_CONFIG_TIMELOCK_SECONDS,
+        ZyncxError::InvalidTimelock
+    );
+
+    vault.config_timelock_seconds = timelock_seconds;
+
+    msg!("Config timelock set to {}s", timelock_seconds);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ProposeConfigChange<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"vault", vault.asset_mint.as_ref()],
+        bump = vault.bump,
+    )]
+    pub vault: Box<Account<'info, VaultState>>,
+
+    #[account(seeds = [b"roles"], bump = roles.bump)]
+    pub roles: Box<Account<'info, Roles>>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = PendingConfigChange::INIT_SPACE,
+        seeds = [b"pending_config", vault.key().as_ref()],
+        bump
+    )]
+    pub pending_change: Box<Account<'info, PendingConfigChange>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler_propose_config_change(
+    ctx: Context<ProposeConfigChange>,
+    change: ConfigChange,
+) -> Result<()> {
+    require_change_authority(
+        &change,
+        &ctx.accounts.vault,
+        &ctx.accounts.roles,
+        &ctx.accounts.authority.key(),
+    )?;
+
+    match &change {
+        ConfigChange::FeeCurve(params) => params.validate()?,
+        ConfigChange::ExclusionVerifier(verifier) => {
+            require!(*verifier != Pubkey::default(), ZyncxError::ZeroAddress)
+        }
+        ConfigChange::Timelock(seconds) => require!(
+            *seconds >= 0 && *seconds < ctx.accounts.vault.config_timelock_seconds,
+            ZyncxError::InvalidTimelock
+        ),
+        ConfigChange::InsuranceShare(bps) => {
+            require!(*bps <= MAX_INSURANCE_SHARE_BPS, ZyncxError::InvalidFeeConfig)
+        }
+        ConfigChange::TreasurySplit(params) => params.validate()?,
+        ConfigChange::ProofSystem(_) | ConfigChange::TreeHash(_) => {}
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    let eta = now
+        .checked_add(ctx.accounts.vault.config_timelock_seconds)
+        .ok_or(ZyncxError::ArithmeticOverflow)?;
+
+    let pending_change = &mut ctx.accounts.pending_change;
+    pending_change.bump = ctx.bumps.pending_change;
+    pending_change.vault = ctx.accounts.vault.key();
+    pending_change.change = change.clone();
+    pending_change.proposed_at = now;
+    pending_change.eta = eta;
+
+    emit!(ConfigChangeProposed {
+        vault: pending_change.vault,
+        change,
+        eta,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ExecuteConfigChange<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault.asset_mint.as_ref()],
+        bump = vault.bump,
+    )]
+    pub vault: Box<Account<'info, VaultState>>,
+
+    #[account(seeds = [b"roles"], bump = roles.bump)]
+    pub roles: Box<Account<'info, Roles>>,
+
+    #[account(
+        mut,
+        close = authority,
+        has_one = vault,
+        seeds = [b"pending_config", vault.key().as_ref()],
+        bump = pending_change.bump,
+    )]
+    pub pending_change: Box<Account<'info, PendingConfigChange>>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = VaultFeeState::INIT_SPACE,
+        seeds = [b"vault_fees", vault.key().as_ref()],
+        bump
+    )]
+    pub vault_fees: Box<Account<'info, VaultFeeState>>,
+
+    /// Required for `ExclusionVerifier` changes
+    #[account(
+        mut,
+        seeds = [b"exclusion_root", vault.key().as_ref()],
+        bump = exclusion_root.bump,
+    )]
+    pub exclusion_root: Option<Account<'info, ExclusionRoot>>,
+
+    /// Required for `TreasurySplit` changes
+    #[account(
+        mut,
+        seeds = [b"treasury_policy", vault.key().as_ref()],
+        bump = treasury_policy.bump,
+    )]
+    pub treasury_policy: Option<Account<'info, TreasuryPolicy>>,
+
+    /// The vault's active tree; pass with `TreeHash` changes to switch it
+    /// too while it has no leaves
+    #[account(
+        mut,
+        address = vault.merkle_tree @ ZyncxError::InactiveMerkleTree,
+    )]
+    pub merkle_tree: Option<Box<Account<'info, MerkleTreeState>>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Apply a proposed change once its timelock has elapsed
+pub fn handler_execute_config_change(ctx: Context<ExecuteConfigChange>) -> Result<()> {
+    let pending_change = &ctx.accounts.pending_change;
+    require_change_authority(
+        &pending_change.change,
+        &ctx.accounts.vault,
+        &ctx.accounts.roles,
+        &ctx.accounts.authority.key(),
+    )?;
+    require!(
+        Clock::get()?.unix_timestamp >= pending_change.eta,
+        ZyncxError::TimelockNotElapsed
+    );
+
+    let vault_key = ctx.accounts.vault.key();
+    let change = pending_change.change.clone();
+
+    match &change {
+        ConfigChange::FeeCurve(params) => {
+            let vault_fees = &mut ctx.accounts.vault_fees;
+            vault_fees.ensure_initialized(vault_key, ctx.bumps.vault_fees);
+            vault_fees.apply_curve(params);
+        }
+        ConfigChange::ExclusionVerifier(verifier) => {
+            let exclusion_root = ctx
+                .accounts
+                .exclusion_root
+                .as_mut()
+                .ok_or(ZyncxError::MissingExclusionRoot)?;
+            exclusion_root.verifier_program = *verifier;
+        }
+        ConfigChange::Timelock(seconds) => {
+            ctx.accounts.vault.config_timelock_seconds = *seconds;
+        }
+        ConfigChange::InsuranceShare(bps) => {
+            let vault_fees = &mut ctx.accounts.vault_fees;
+            vault_fees.ensure_initialized(vault_key, ctx.bumps.vault_fees);
+            vault_fees.insurance_share_bps = *bps;
+        }
+        ConfigChange::TreasurySplit(params) => {
+            let treasury_policy = ctx
+                .accounts
+                .treasury_policy
+                .as_mut()
+                .ok_or(ZyncxError::MissingTreasuryPolicy)?;
+            treasury_policy.apply_split(params);
+        }
+        ConfigChange::ProofSystem(proof_system) => {
+            ctx.accounts.vault.proof_system = *proof_system;
+        }
+        ConfigChange::TreeHash(tree_hash) => {
+            ctx.accounts.vault.tree_hash = *tree_hash;
+            if let Some(merkle_tree) = ctx.accounts.merkle_tree.as_mut() {
+                require!(merkle_tree.size == 0, ZyncxError::TreeNotEmpty);
+                merkle_tree.tree_hash = *tree_hash;
+                merkle_tree.root = tree_hash.empty_root();
+            }
+        }
+    }
+
+    emit!(ConfigChangeExecuted {
+        vault: vault_key,
+        change,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CancelConfigChange<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"vault", vault.asset_mint.as_ref()],
+        bump = vault.bump,
+    )]
+    pub vault: Box<Account<'info, VaultState>>,
+
+    #[account(seeds = [b"roles"], bump = roles.bump)]
+    pub roles: Box<Account<'info, Roles>>,
+
+    #[account(
+        mut,
+        close = authority,
+        has_one = vault,
+        seeds = [b"pending_config", vault.key().as_ref()],
+        bump = pending_change.bump,
+    )]
+    pub pending_change: Box<Account<'info, PendingConfigChange>>,
+}
+
+pub fn handler_cancel_config_change(ctx: Context<CancelConfigChange>) -> Result<()> {
+    require_change_authority(
+        &ctx.accounts.pending_change.change,
+        &ctx.accounts.vault,
+        &ctx.accounts.roles,
+        &ctx.accounts.authority.key(),
+    )?;
+
+    emit!(ConfigChangeCancelled {
+        vault: ctx.accounts.vault.key(),
+        change: ctx.accounts.pending_change.change.clone(),
+    });
+
+    Ok(())
+}
+
+/// Fee changes belong to the fee manager role; everything else to the
+/// vault authority
+fn require_change_authority(
+    change: &ConfigChange,
+    vault: &VaultState,
+    roles: &Roles,
+    signer: &Pubkey,
+) -> Result<()> {
+    let allowed = match change {
+        ConfigChange::FeeCurve(_) | ConfigChange::InsuranceShare(_) => {
+            roles.has_role(Role::FeeManager, signer)
+        }
+        ConfigChange::ExclusionVerifier(_)
+        | ConfigChange::Timelock(_)
+        | ConfigChange::TreasurySplit(_)
+        | ConfigChange::ProofSystem(_)
+        | ConfigChange::TreeHash(_) => vault.authority == *signer,
+    };
+    require!(allowed, ZyncxError::Unauthorized);
+    Ok(())
+}
+
+#[event]
+pub struct ConfigChangeProposed {
+    pub vault: Pubkey,
+    pub change: ConfigChange,
+    pub eta: i64,
+}
+
+#[event]
+pub struct ConfigChangeExecuted {
+    pub vault: Pubkey,
+    pub change: ConfigChange,
+}
+
+#[event]
+pub struct ConfigChangeCancelled {
+    pub vault: Pubkey,
+    pub change: ConfigChange,
+}
@@ -0,0 +1,132 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token::{self, InitializeAccount3, Mint, Token, TokenAccount};
+
+use crate::errors::ZyncxError;
+use crate::state::{VaultState, VaultType};
+
+#[derive(Accounts)]
+pub struct PrepareVaultTokenAccount<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        seeds = [b"vault", vault.asset_mint.as_ref()],
+        bump = vault.bump,
+    )]
+    pub vault: Box<Account<'info, VaultState>>,
+
+    #[account(address = vault.asset_mint @ ZyncxError::InvalidMint)]
+    pub mint: Box<Account<'info, Mint>>,
+
+    /// CHECK: Vault escrow token account PDA; created here when missing
+    #[account(
+        mut,
+        seeds = [b"vault_token_account", vault.key().as_ref()],
+        bump,
+    )]
+    pub vault_token_account: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Idempotently create a token vault's escrow account (self-owned PDA token
+/// account). Run before the first deposit into, or cross-token swap towards,
+/// a vault whose asset has never been held. A no-op once the account exists.
+pub fn handler_prepare_token_account(ctx: Context<PrepareVaultTokenAccount>) -> Result<()> {
+    let vault = &ctx.accounts.vault;
+    require!(vault.vault_type == VaultType::Alternative, ZyncxError::VaultNotFound);
+
+    let vault_token_account = &ctx.accounts.vault_token_account;
+    if !vault_token_account.data_is_empty() {
+        let data = vault_token_account.try_borrow_data()?;
+        let existing = TokenAccount::try_deserialize(&mut &data[..])?;
+        require_keys_eq!(existing.mint, ctx.accounts.mint.key(), ZyncxError::InvalidMint);
+        require_keys_eq!(existing.owner, vault_token_account.key(), ZyncxError::InvalidMint);
+        return Ok(());
+    }
+
+    let vault_key = vault.key();
+    let bump = [ctx.bumps.vault_token_account];
+    let seeds: &[&[u8]] = &[b"vault_token_account", vault_key.as_ref(), &bump];
+    let signer_seeds = &[seeds];
+
+    let space = TokenAccount::LEN;
+    let rent = Rent::get()?.minimum_balance(space);
+    let current = vault_token_account.lamports();
+
+    // Tolerate lamports sent to the address ahead of time
+    if current == 0 {
+        system_program::create_account(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::CreateAccount {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: vault_token_account.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            rent,
+            space as u64,
+            &ctx.accounts.token_program.key(),
+        )?;
+    } else {
+        if current < rent {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.payer.to_account_info(),
+                        to: vault_token_account.to_account_info(),
+                    },
+                ),
+                rent - current,
+            )?;
+        }
+        system_program::allocate(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Allocate {
+                    account_to_allocate: vault_token_account.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            space as u64,
+        )?;
+        system_program::assign(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Assign {
+                    account_to_assign: vault_token_account.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            &ctx.accounts.token_program.key(),
+        )?;
+    }
+
+    token::initialize_account3(CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        InitializeAccount3 {
+            account: vault_token_account.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            authority: vault_token_account.to_account_info(),
+        },
+    ))?;
+
+    emit!(VaultTokenAccountCreated {
+        vault: vault_key,
+        mint: ctx.accounts.mint.key(),
+        token_account: vault_token_account.key(),
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct VaultTokenAccountCreated {
+    pub vault: Pubkey,
+    pub mint: Pubkey,
+    pub token_account: Pubkey,
+}
@@ -0,0 +1,77 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ZyncxError;
+use crate::state::{ProofCircuit, ProofSystem, VaultState, VerificationKey, MAX_VK_PUBLIC_INPUTS};
+
+#[derive(Accounts)]
+#[instruction(circuit: ProofCircuit)]
+pub struct SetVerificationKey<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"vault", vault.asset_mint.as_ref()],
+        bump = vault.bump,
+        has_one = authority @ ZyncxError::Unauthorized,
+    )]
+    pub vault: Box<Account<'info, VaultState>>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = VerificationKey::space_with_inputs(MAX_VK_PUBLIC_INPUTS),
+        seeds = [b"verification_key", vault.key().as_ref(), &[circuit as u8]],
+        bump
+    )]
+    pub verification_key: Box<Account<'info, VerificationKey>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Upload (or replace) the vault's Groth16 verification key for `circuit`.
+/// `ic` holds one point per public input plus the constant term. Keys are
+/// frozen while the vault verifies Groth16 proofs; switching proof systems
+/// goes through the config timelock.
+pub fn handler_set_verification_key(
+    ctx: Context<SetVerificationKey>,
+    circuit: ProofCircuit,
+    alpha_g1: [u8; 64],
+    beta_g2: [u8; 128],
+    gamma_g2: [u8; 128],
+    delta_g2: [u8; 128],
+    ic: Vec<[u8; 64]>,
+) -> Result<()> {
+    require!(
+        ctx.accounts.vault.proof_system != ProofSystem::Groth16,
+        ZyncxError::VerificationKeyLocked
+    );
+    require!(
+        ic.len() >= 2 && ic.len() <= MAX_VK_PUBLIC_INPUTS + 1,
+        ZyncxError::VerificationKeyMismatch
+    );
+
+    let verification_key = &mut ctx.accounts.verification_key;
+    verification_key.bump = ctx.bumps.verification_key;
+    verification_key.vault = ctx.accounts.vault.key();
+    verification_key.circuit = circuit;
+    verification_key.alpha_g1 = alpha_g1;
+    verification_key.beta_g2 = beta_g2;
+    verification_key.gamma_g2 = gamma_g2;
+    verification_key.delta_g2 = delta_g2;
+    verification_key.ic = ic;
+
+    emit!(VerificationKeySet {
+        vault: verification_key.vault,
+        circuit,
+        public_inputs: (verification_key.ic.len() - 1) as u8,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct VerificationKeySet {
+    pub vault: Pubkey,
+    pub circuit: ProofCircuit,
+    pub public_inputs: u8,
+}
@@ -1,7 +1,9 @@
 use anchor_lang::prelude::*;
-use anchor_lang::solana_program::{instruction::Instruction, program::invoke};
 
-use crate::state::{MerkleTreeState, VaultState};
+use crate::state::{
+    u64_public_input, MerkleTreeState, ProofCircuit, ProofVerifier, ProtocolConfig, VaultState, VerificationKey,
+    UNBOUND_POLICY_HASH,
+};
 use crate::errors::ZyncxError;
 
 #[derive(Accounts)]
@@ -13,24 +15,37 @@ pub struct VerifyProof<'info> {
     pub vault: Account<'info, VaultState>,
 
     #[account(
-        seeds = [b"merkle_tree", vault.key().as_ref()],
-        bump = merkle_tree.bump,
+        constraint = merkle_tree.vault == vault.key() @ ZyncxError::MerkleTreeVaultMismatch,
     )]
     pub merkle_tree: Account<'info, MerkleTreeState>,
 
-    /// CHECK: Noir ZK verifier program (address verified via constraint)
+    #[account(seeds = [b"protocol_config"], bump = protocol_config.bump)]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    /// CHECK: Noir ZK verifier program (address pinned by the protocol config)
     #[account(
         executable,
-        address = crate::NOIR_VERIFIER_PROGRAM_ID
+        address = protocol_config.mixer_verifier @ ZyncxError::InvalidVerifierProgram
     )]
     pub verifier_program: AccountInfo<'info>,
+
+    /// Vault's Groth16 key of the mixer circuit; required when the vault
+    /// verifies Groth16 proofs
+    #[account(
+        seeds = [b"verification_key", vault.key().as_ref(), &[ProofCircuit::Mixer as u8]],
+        bump = verification_key.bump,
+    )]
+    pub verification_key: Option<Box<Account<'info, VerificationKey>>>,
 }
 
 pub fn handler(
     ctx: Context<VerifyProof>,
     amount: u64,
     nullifier: [u8; 32],
+    recipient: Pubkey,
     new_commitment: [u8; 32],
+    ext_data_hash: [u8; 32],
+    remaining_amount: u64,
     proof: Vec<u8>,
 ) -> Result<bool> {
     let merkle_tree = &ctx.accounts.merkle_tree;
@@ -40,12 +55,20 @@ pub fn handler(
 
     // Verify the ZK proof via CPI to Noir verifier
     match verify_noir_proof(
-        &ctx.accounts.verifier_program,
+        &ProofVerifier::for_vault(
+            &ctx.accounts.vault,
+            &ctx.accounts.verifier_program,
+            &ctx.accounts.verification_key,
+        ),
         &proof,
         &root,
         &nullifier,
+        &recipient,
         amount,
         &new_commitment,
+        &ext_data_hash,
+        remaining_amount,
+        ctx.accounts.vault.note_age_cutoff(Clock::get()?.slot),
     ) {
         Ok(_) => {
             msg!("Proof verification successful");
@@ -58,68 +81,46 @@ pub fn handler(
     }
 }
 
-/// Verify a Noir ZK proof via CPI to the deployed verifier program (mixer.so)
+/// Verify a mixer circuit proof with the vault's proof system
 /// 
 /// The Noir circuit (mixer/src/main.nr) expects public inputs in order:
 /// 1. root (32 bytes) - Merkle tree root
 /// 2. nullifier_hash (32 bytes) - Prevents double-spending  
-/// 3. recipient (32 bytes) - Withdrawal recipient (bound to proof)
+/// 3. recipient (32 bytes) - Withdrawal recipient (bound to proof, so a
+///    relayer cannot redirect the funds)
 /// 4. withdraw_amount (32 bytes) - Amount being withdrawn
 /// 5. new_commitment (32 bytes) - Change commitment (0 for full withdrawal)
+/// 6. ext_data_hash (32 bytes) - Withdrawal terms the proof authorizes
+/// 7. remaining_amount (32 bytes) - Change note value
+/// 8. note_age_cutoff (32 bytes) - Latest allowed note insertion slot
+/// 9. policy_hash (32 bytes) - Zero: only notes bound to no allowance policy
+///    are checked here
 pub fn verify_noir_proof(
-    verifier_program: &AccountInfo,
+    verifier: &ProofVerifier,
     proof: &[u8],
     root: &[u8; 32],
     nullifier: &[u8; 32],
+    recipient: &Pubkey,
     amount: u64,
     new_commitment: &[u8; 32],
+    ext_data_hash: &[u8; 32],
+    remaining_amount: u64,
+    note_age_cutoff: u64,
 ) -> Result<()> {
-    if proof.is_empty() {
-        return Err(ZyncxError::InvalidZKProof.into());
-    }
-
-    // Build verifier instruction data: [proof][public_inputs...]
-    let mut verifier_input = Vec::with_capacity(proof.len() + 160);
-    
-    // Proof bytes (variable length)
-    verifier_input.extend_from_slice(proof);
-    
-    // Public inputs (must match Noir circuit order)
-    // 1. root
-    verifier_input.extend_from_slice(root);
-    
-    // 2. nullifier_hash
-    verifier_input.extend_from_slice(nullifier);
-    
-    // 3. recipient (zero for now - actual binding happens in withdraw/swap)
-    verifier_input.extend_from_slice(&[0u8; 32]);
-    
-    // 4. withdraw_amount as 32-byte big-endian
-    let mut amount_bytes = [0u8; 32];
-    amount_bytes[24..32].copy_from_slice(&amount.to_be_bytes());
-    verifier_input.extend_from_slice(&amount_bytes);
-    
-    // 5. new_commitment
-    verifier_input.extend_from_slice(new_commitment);
-    
-    // Create CPI instruction to verifier
-    let instruction = Instruction {
-        program_id: *verifier_program.key,
-        accounts: vec![],
-        data: verifier_input,
-    };
-    
-    msg!("Invoking Noir verifier with {} byte proof", proof.len());
-    
-    invoke(
-        &instruction,
-        &[verifier_program.clone()],
-    ).map_err(|e| {
-        msg!("Noir proof verification failed: {:?}", e);
-        ZyncxError::InvalidZKProof
-    })?;
-    
-    Ok(())
+    let public_inputs = [
+        *root,
+        *nullifier,
+        recipient.to_bytes(),
+        u64_public_input(amount),
+        *new_commitment,
+        *ext_data_hash,
+        u64_public_input(remaining_amount),
+        u64_public_input(note_age_cutoff),
+        UNBOUND_POLICY_HASH,
+    ];
+
+    msg!("Verifying {} byte proof", proof.len());
+    verifier.verify(proof, &public_inputs)
 }
 
 #[derive(Accounts)]
@@ -153,8 +154,7 @@ pub fn check_nullifier_spent(
 #[derive(Accounts)]
 pub struct CheckRoot<'info> {
     #[account(
-        seeds = [b"merkle_tree", vault.key().as_ref()],
-        bump = merkle_tree.bump,
+        constraint = merkle_tree.vault == vault.key() @ ZyncxError::MerkleTreeVaultMismatch,
     )]
     pub merkle_tree: Account<'info, MerkleTreeState>,
 
@@ -172,3 +172,15 @@ pub fn check_root_exists(
     let merkle_tree = &ctx.accounts.merkle_tree;
     Ok(merkle_tree.root_exists(&root))
 }
+
+/// Check a client-supplied auth path: true when `leaf` hashes up `path` to
+/// one of the tree's recent roots. `leaf` is the tree node of the note
+/// (`leaf_node(commitment, slot)`), as returned in `DepositReceipt`'s path.
+pub fn handler_verify_merkle_inclusion(
+    ctx: Context<CheckRoot>,
+    leaf: [u8; 32],
+    path: Vec<[u8; 32]>,
+    indices: Vec<u8>,
+) -> Result<bool> {
+    ctx.accounts.merkle_tree.verify_inclusion(&leaf, &path, &indices)
+}
@@ -0,0 +1,77 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ZyncxError;
+use crate::state::ViewingKey;
+
+#[derive(Accounts)]
+pub struct RegisterViewingKey<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = ViewingKey::INIT_SPACE,
+        seeds = [b"viewing_key", owner.key().as_ref()],
+        bump
+    )]
+    pub viewing_key: Account<'info, ViewingKey>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Register (or rotate) the caller's viewing key
+pub fn handler_register(ctx: Context<RegisterViewingKey>, viewing_pubkey: [u8; 32]) -> Result<()> {
+    require!(viewing_pubkey != [0u8; 32], ZyncxError::ZeroAddress);
+
+    let viewing_key = &mut ctx.accounts.viewing_key;
+    viewing_key.bump = ctx.bumps.viewing_key;
+    viewing_key.owner = ctx.accounts.owner.key();
+    viewing_key.viewing_pubkey = viewing_pubkey;
+    viewing_key.registered_at = Clock::get()?.unix_timestamp;
+
+    emit!(ViewingKeyRegistered {
+        owner: viewing_key.owner,
+        viewing_pubkey,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RevokeViewingKey<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        close = owner,
+        has_one = owner @ ZyncxError::Unauthorized,
+        seeds = [b"viewing_key", owner.key().as_ref()],
+        bump = viewing_key.bump,
+    )]
+    pub viewing_key: Account<'info, ViewingKey>,
+}
+
+/// Remove the caller's viewing key. Disclosures already emitted stay
+/// readable by the previous key holder.
+pub fn handler_revoke(ctx: Context<RevokeViewingKey>) -> Result<()> {
+    emit!(ViewingKeyRevoked {
+        owner: ctx.accounts.owner.key(),
+        viewing_pubkey: ctx.accounts.viewing_key.viewing_pubkey,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct ViewingKeyRegistered {
+    pub owner: Pubkey,
+    pub viewing_pubkey: [u8; 32],
+}
+
+#[event]
+pub struct ViewingKeyRevoked {
+    pub owner: Pubkey,
+    pub viewing_pubkey: [u8; 32],
+}
@@ -1,8 +1,15 @@
 use anchor_lang::prelude::*;
-use anchor_lang::solana_program::{instruction::Instruction, program::invoke};
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
-
-use crate::state::{MerkleTreeState, VaultState, VaultType, NullifierState};
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+
+use crate::state::{
+    u64_public_input, AllowancePolicy, Blocklist, ExclusionRoot, FeeExemptions, GlobalStats, MerkleTreeState, NullifierState, ProofCircuit,
+    ProofVerifier, ProtocolConfig, RelayerRegistration, TreasuryPolicy, TreeInsertion, VaultFeeState, VaultState, VaultStats, VaultType,
+    VerificationKey, ViewingKey, WithdrawExtData,
+};
+use crate::instructions::allowance::charge_allowance;
+use crate::instructions::exclusion::verify_exclusion_proof;
+use crate::instructions::treasury::{pay_from_vault_treasury, refill_from_cold};
 use crate::errors::ZyncxError;
 
 #[derive(Accounts)]
@@ -20,8 +27,7 @@ pub struct WithdrawNative<'info> {
 
     #[account(
         mut,
-        seeds = [b"merkle_tree", vault.key().as_ref()],
-        bump = merkle_tree.bump,
+        constraint = merkle_tree.vault == vault.key() @ ZyncxError::MerkleTreeVaultMismatch,
     )]
     pub merkle_tree: Box<Account<'info, MerkleTreeState>>,
 
@@ -42,73 +48,199 @@ pub struct WithdrawNative<'info> {
     )]
     pub nullifier_account: Account<'info, NullifierState>,
 
-    /// CHECK: Noir ZK verifier program (address verified via constraint)
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = VaultStats::INIT_SPACE,
+        seeds = [b"vault_stats", vault.key().as_ref()],
+        bump
+    )]
+    pub vault_stats: Box<Account<'info, VaultStats>>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = VaultFeeState::INIT_SPACE,
+        seeds = [b"vault_fees", vault.key().as_ref()],
+        bump
+    )]
+    pub vault_fees: Box<Account<'info, VaultFeeState>>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = GlobalStats::INIT_SPACE,
+        seeds = [b"global_stats"],
+        bump
+    )]
+    pub global_stats: Box<Account<'info, GlobalStats>>,
+
+    /// Vault's published exclusion root; required with an exclusion proof
+    #[account(
+        seeds = [b"exclusion_root", vault.key().as_ref()],
+        bump = exclusion_root.bump,
+    )]
+    pub exclusion_root: Option<Account<'info, ExclusionRoot>>,
+
+    /// CHECK: Non-membership verifier, checked against exclusion_root
+    #[account(executable)]
+    pub exclusion_verifier: Option<UncheckedAccount<'info>>,
+
+    /// Vault's hot/cold split; pass with cold_treasury to refill the hot
+    /// treasury when it has run low
+    #[account(
+        mut,
+        seeds = [b"treasury_policy", vault.key().as_ref()],
+        bump = treasury_policy.bump,
+    )]
+    pub treasury_policy: Option<Account<'info, TreasuryPolicy>>,
+
+    /// CHECK: Vault PDA holding the cold share of the SOL
+    #[account(
+        mut,
+        seeds = [b"cold_treasury", vault.key().as_ref()],
+        bump,
+    )]
+    pub cold_treasury: Option<UncheckedAccount<'info>>,
+
+    /// Org allowance policy the spent note is bound to; its epoch limit is
+    /// enforced and its hash is the proof's `policy_hash`. Required for
+    /// bound notes: without it the proof is checked against an unbound note
+    #[account(
+        mut,
+        seeds = [
+            b"allowance_policy",
+            vault.key().as_ref(),
+            &allowance_policy.policy_id.to_le_bytes(),
+        ],
+        bump = allowance_policy.bump,
+    )]
+    pub allowance_policy: Option<Box<Account<'info, AllowancePolicy>>>,
+
+    /// CHECK: Blocklist PDA; may be uninitialized, parsed in the handler
+    #[account(seeds = [b"blocklist"], bump)]
+    pub blocklist: UncheckedAccount<'info>,
+
+    /// CHECK: Fee exemption PDA; may be uninitialized, parsed in the handler
+    #[account(seeds = [b"fee_exemptions"], bump)]
+    pub fee_exemptions: UncheckedAccount<'info>,
+
+    #[account(seeds = [b"protocol_config"], bump = protocol_config.bump)]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    /// CHECK: Noir ZK verifier program (address pinned by the protocol config)
     #[account(
         executable,
-        address = crate::NOIR_VERIFIER_PROGRAM_ID
+        address = protocol_config.mixer_verifier @ ZyncxError::InvalidVerifierProgram
     )]
     pub verifier_program: AccountInfo<'info>,
 
+    /// Vault's Groth16 key of the mixer circuit; required when the vault
+    /// verifies Groth16 proofs
+    #[account(
+        seeds = [b"verification_key", vault.key().as_ref(), &[ProofCircuit::Mixer as u8]],
+        bump = verification_key.bump,
+    )]
+    pub verification_key: Option<Box<Account<'info, VerificationKey>>>,
+
+    /// Risk signer of the protocol config; co-signs operations above its
+    /// value ceiling
+    pub risk_signer: Option<Signer<'info>>,
+
     #[account(mut)]
     pub payer: Signer<'info>,
 
+    /// Payer's relayer registration; required when `ext_data` pins a fee
+    /// schedule
+    #[account(
+        seeds = [b"relayer", payer.key().as_ref()],
+        bump = relayer_registration.bump,
+    )]
+    pub relayer_registration: Option<Box<Account<'info, RelayerRegistration>>>,
+
     pub system_program: Program<'info, System>,
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn handler_native(
     ctx: Context<WithdrawNative>,
     amount: u64,
     nullifier: [u8; 32],
     new_commitment: [u8; 32],
+    remaining_amount: u64,
     proof: Vec<u8>,
-) -> Result<()> {
+    ext_data: WithdrawExtData,
+    exclusion_proof: Vec<u8>,
+) -> Result<Option<TreeInsertion>> {
     require!(amount > 0, ZyncxError::InvalidWithdrawalAmount);
+    ctx.accounts.protocol_config.check_operation_value(
+        amount,
+        ctx.accounts.risk_signer.as_ref().map(|signer| signer.key()),
+    )?;
+    ViewingKey::check_ciphertext_len(&ext_data.viewing_ciphertext)?;
+    Blocklist::check_recipient(&ctx.accounts.blocklist, &ctx.accounts.recipient.key())?;
+    let ext_data_hash = ext_data.verify(&ctx.accounts.recipient.key(), &ctx.accounts.payer.key())?;
+    RelayerRegistration::check_ext_data(
+        &ctx.accounts.relayer_registration,
+        &ext_data,
+        &ctx.accounts.vault.key(),
+        amount,
+    )?;
 
     let vault = &ctx.accounts.vault;
     let merkle_tree = &mut ctx.accounts.merkle_tree;
     let nullifier_account = &mut ctx.accounts.nullifier_account;
 
     require!(vault.vault_type == VaultType::Native, ZyncxError::VaultNotFound);
+    require!(!vault.paused, ZyncxError::VaultPaused);
+    require!(vault.is_valid_denomination(amount), ZyncxError::InvalidDenomination);
+    vault.check_spent_note(amount, remaining_amount, &new_commitment)?;
 
     // Get current merkle root
     let root = merkle_tree.get_root();
 
-    // Verify ZK proof via CPI to verifier program
-    // Circuit expects public inputs: [root, nullifier_hash, recipient, amount]
-    let mut verifier_input = Vec::new();
-    
-    // 1. Append proof bytes
-    verifier_input.extend_from_slice(&proof);
-    
-    // 2. Public Input: Root (32 bytes)
-    verifier_input.extend_from_slice(&root);
-    
-    // 3. Public Input: Nullifier Hash (32 bytes)
-    verifier_input.extend_from_slice(&nullifier);
-    
-    // 4. Public Input: Recipient (32 bytes)
-    verifier_input.extend_from_slice(&ctx.accounts.recipient.key().to_bytes());
-    
-    // 5. Public Input: Amount (32 bytes, Big Endian)
-    let mut amount_bytes = [0u8; 32];
-    amount_bytes[24..32].copy_from_slice(&amount.to_be_bytes());
-    verifier_input.extend_from_slice(&amount_bytes);
-    
-    // Invoke verifier program
-    let instruction = Instruction {
-        program_id: *ctx.accounts.verifier_program.key,
-        accounts: vec![],
-        data: verifier_input,
-    };
-    
+    // Charge the note's allowance policy, if it has one
+    let policy_hash = charge_allowance(
+        &mut ctx.accounts.allowance_policy,
+        amount,
+        Clock::get()?.unix_timestamp,
+    )?;
+
+    // Public inputs: [root, nullifier_hash, recipient, amount, new_commitment, ext_data_hash,
+    // remaining_amount, note_age_cutoff, policy_hash]
+    let public_inputs = vec![
+        root,
+        nullifier,
+        ctx.accounts.recipient.key().to_bytes(),
+        u64_public_input(amount),
+        new_commitment,
+        // Binds max fee, relayer tip and disclosure
+        ext_data_hash,
+        // Change note value; the circuit proves amount + remaining = note value
+        u64_public_input(remaining_amount),
+        // Latest insertion slot of a spendable note
+        u64_public_input(vault.note_age_cutoff(Clock::get()?.slot)),
+        // Allowance policy the note (and its change note) is bound to
+        policy_hash,
+    ];
+
     msg!("Invoking ZK Verifier...");
-    invoke(
-        &instruction,
-        &[ctx.accounts.verifier_program.clone()],
-    ).map_err(|_| ZyncxError::InvalidZKProof)?;
-    
+    ProofVerifier::for_vault(
+        vault,
+        &ctx.accounts.verifier_program,
+        &ctx.accounts.verification_key,
+    )
+    .verify(&proof, &public_inputs)?;
+
     msg!("ZK Proof Verified Successfully!");
 
+    let exclusion_root = verify_exclusion_proof(
+        &ctx.accounts.exclusion_root,
+        &ctx.accounts.exclusion_verifier,
+        &nullifier,
+        &exclusion_proof,
+    )?;
+
     // Mark nullifier as spent
     nullifier_account.bump = ctx.bumps.nullifier_account;
     nullifier_account.nullifier = nullifier;
@@ -119,32 +251,97 @@ pub fn handler_native(
     // For partial withdrawals, insert new commitment for remaining balance
     // If new_commitment is all zeros, it's a full withdrawal - no change to insert
     let is_partial_withdrawal = new_commitment != [0u8; 32];
-    if is_partial_withdrawal {
-        merkle_tree.insert(new_commitment)?;
+    let insertion = if is_partial_withdrawal {
+        let insertion = merkle_tree.insert(new_commitment)?;
         msg!("Partial withdrawal: inserted change commitment into merkle tree");
+        Some(insertion)
     } else {
         msg!("Full withdrawal: no change commitment needed");
+        None
+    };
+    let (leaf_index, new_root) = merkle_tree.event_position(insertion.as_ref());
+
+    let vault_stats = &mut ctx.accounts.vault_stats;
+    vault_stats.ensure_initialized(vault.key(), ctx.bumps.vault_stats);
+    let anonymity_set = vault_stats.anonymity_set_size();
+    vault_stats.record_spend(is_partial_withdrawal, Clock::get()?.unix_timestamp);
+    let anonymity_set_seconds = vault_stats.anonymity_set_seconds;
+
+    let global_stats = &mut ctx.accounts.global_stats;
+    global_stats.ensure_initialized(ctx.bumps.global_stats);
+    global_stats.record_withdrawal(amount, Clock::get()?.unix_timestamp);
+
+    // Top the hot treasury up from cold first if it has dropped below its
+    // refill threshold; the fee curve sees the vault's whole balance
+    let mut cold_lamports = 0;
+    if let (Some(treasury_policy), Some(cold_treasury)) = (
+        ctx.accounts.treasury_policy.as_mut(),
+        ctx.accounts.cold_treasury.as_ref(),
+    ) {
+        refill_from_cold(
+            treasury_policy,
+            &ctx.accounts.vault_treasury,
+            cold_treasury,
+            &ctx.accounts.system_program,
+            Clock::get()?.unix_timestamp,
+        )?;
+        cold_lamports = cold_treasury.lamports();
     }
 
     // Transfer SOL from vault treasury to recipient
     let treasury_lamports = ctx.accounts.vault_treasury.lamports();
     require!(treasury_lamports >= amount, ZyncxError::InvalidWithdrawalAmount);
 
-    **ctx.accounts.vault_treasury.try_borrow_mut_lamports()? -= amount;
-    **ctx.accounts.recipient.try_borrow_mut_lamports()? += amount;
+    // Volume-based fee stays in the treasury
+    let vault_fees = &mut ctx.accounts.vault_fees;
+    vault_fees.ensure_initialized(vault.key(), ctx.bumps.vault_fees);
+    let exempt = FeeExemptions::is_exempt(
+        &ctx.accounts.fee_exemptions,
+        &[ctx.accounts.payer.key(), ctx.accounts.recipient.key()],
+    )?;
+    let fee = vault_fees.apply_withdrawal(
+        amount,
+        treasury_lamports.saturating_add(cold_lamports),
+        Clock::get()?.unix_timestamp,
+        exempt,
+    );
+    let (payout, relayer_tip) = ext_data.split(amount, fee)?;
+
+    for (to, lamports) in [
+        (ctx.accounts.recipient.to_account_info(), payout),
+        (ctx.accounts.payer.to_account_info(), relayer_tip),
+    ] {
+        pay_from_vault_treasury(
+            &ctx.accounts.system_program,
+            &ctx.accounts.vault_treasury,
+            &to,
+            &vault.key(),
+            ctx.bumps.vault_treasury,
+            lamports,
+        )?;
+    }
 
     // Emit event
     emit!(WithdrawnEvent {
         recipient: ctx.accounts.recipient.key(),
         amount,
+        fee,
+        relayer_tip,
         nullifier,
         new_commitment,
         is_partial: is_partial_withdrawal,
+        viewing_ciphertext: ext_data.viewing_ciphertext,
+        exclusion_root,
+        ephemeral_pubkey: ext_data.ephemeral_pubkey,
+        anonymity_set,
+        anonymity_set_seconds,
+        leaf_index,
+        new_root,
     });
 
     msg!("Withdrawn {} lamports (partial: {})", amount, is_partial_withdrawal);
 
-    Ok(())
+    Ok(insertion)
 }
 
 #[derive(Accounts)]
@@ -163,12 +360,21 @@ pub struct WithdrawToken<'info> {
 
     #[account(
         mut,
-        seeds = [b"merkle_tree", vault.key().as_ref()],
-        bump = merkle_tree.bump,
+        constraint = merkle_tree.vault == vault.key() @ ZyncxError::MerkleTreeVaultMismatch,
     )]
     pub merkle_tree: Box<Account<'info, MerkleTreeState>>,
 
-    #[account(mut)]
+    #[account(address = vault.asset_mint @ ZyncxError::InvalidMint)]
+    pub asset_mint: Box<Account<'info, Mint>>,
+
+    /// Recipient's associated token account, created at the payer's expense
+    /// for recipients that have none yet (e.g. fresh stealth addresses)
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = asset_mint,
+        associated_token::authority = recipient,
+    )]
     pub recipient_token_account: Box<Account<'info, TokenAccount>>,
 
     #[account(
@@ -187,73 +393,192 @@ pub struct WithdrawToken<'info> {
     )]
     pub nullifier_account: Account<'info, NullifierState>,
 
-    /// CHECK: Noir ZK verifier program (address verified via constraint)
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = VaultStats::INIT_SPACE,
+        seeds = [b"vault_stats", vault.key().as_ref()],
+        bump
+    )]
+    pub vault_stats: Box<Account<'info, VaultStats>>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = VaultFeeState::INIT_SPACE,
+        seeds = [b"vault_fees", vault.key().as_ref()],
+        bump
+    )]
+    pub vault_fees: Box<Account<'info, VaultFeeState>>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = GlobalStats::INIT_SPACE,
+        seeds = [b"global_stats"],
+        bump
+    )]
+    pub global_stats: Box<Account<'info, GlobalStats>>,
+
+    /// Vault's published exclusion root; required with an exclusion proof
+    #[account(
+        seeds = [b"exclusion_root", vault.key().as_ref()],
+        bump = exclusion_root.bump,
+    )]
+    pub exclusion_root: Option<Account<'info, ExclusionRoot>>,
+
+    /// CHECK: Non-membership verifier, checked against exclusion_root
+    #[account(executable)]
+    pub exclusion_verifier: Option<UncheckedAccount<'info>>,
+
+    /// Org allowance policy the spent note is bound to; its epoch limit is
+    /// enforced and its hash is the proof's `policy_hash`. Required for
+    /// bound notes: without it the proof is checked against an unbound note
+    #[account(
+        mut,
+        seeds = [
+            b"allowance_policy",
+            vault.key().as_ref(),
+            &allowance_policy.policy_id.to_le_bytes(),
+        ],
+        bump = allowance_policy.bump,
+    )]
+    pub allowance_policy: Option<Box<Account<'info, AllowancePolicy>>>,
+
+    /// CHECK: Blocklist PDA; may be uninitialized, parsed in the handler
+    #[account(seeds = [b"blocklist"], bump)]
+    pub blocklist: UncheckedAccount<'info>,
+
+    /// CHECK: Fee exemption PDA; may be uninitialized, parsed in the handler
+    #[account(seeds = [b"fee_exemptions"], bump)]
+    pub fee_exemptions: UncheckedAccount<'info>,
+
+    #[account(seeds = [b"protocol_config"], bump = protocol_config.bump)]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    /// CHECK: Noir ZK verifier program (address pinned by the protocol config)
     #[account(
         executable,
-        address = crate::NOIR_VERIFIER_PROGRAM_ID
+        address = protocol_config.mixer_verifier @ ZyncxError::InvalidVerifierProgram
     )]
     pub verifier_program: AccountInfo<'info>,
 
+    /// Vault's Groth16 key of the mixer circuit; required when the vault
+    /// verifies Groth16 proofs
+    #[account(
+        seeds = [b"verification_key", vault.key().as_ref(), &[ProofCircuit::Mixer as u8]],
+        bump = verification_key.bump,
+    )]
+    pub verification_key: Option<Box<Account<'info, VerificationKey>>>,
+
+    /// Risk signer of the protocol config; co-signs operations above its
+    /// value ceiling
+    pub risk_signer: Option<Signer<'info>>,
+
     #[account(mut)]
     pub payer: Signer<'info>,
 
+    /// Payer's relayer registration; required when `ext_data` pins a fee
+    /// schedule
+    #[account(
+        seeds = [b"relayer", payer.key().as_ref()],
+        bump = relayer_registration.bump,
+    )]
+    pub relayer_registration: Option<Box<Account<'info, RelayerRegistration>>>,
+
+    /// Payer's token account for the relayer tip; required when the tip is non-zero
+    #[account(
+        mut,
+        constraint = relayer_token_account.owner == payer.key() @ ZyncxError::ExtDataMismatch,
+        constraint = relayer_token_account.mint == vault_token_account.mint @ ZyncxError::ExtDataMismatch,
+    )]
+    pub relayer_token_account: Option<Box<Account<'info, TokenAccount>>>,
+
     pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn handler_token(
     ctx: Context<WithdrawToken>,
     amount: u64,
     nullifier: [u8; 32],
     new_commitment: [u8; 32],
+    remaining_amount: u64,
     proof: Vec<u8>,
-) -> Result<()> {
+    ext_data: WithdrawExtData,
+    exclusion_proof: Vec<u8>,
+) -> Result<Option<TreeInsertion>> {
     require!(amount > 0, ZyncxError::InvalidWithdrawalAmount);
+    ctx.accounts.protocol_config.check_operation_value(
+        amount,
+        ctx.accounts.risk_signer.as_ref().map(|signer| signer.key()),
+    )?;
+    ViewingKey::check_ciphertext_len(&ext_data.viewing_ciphertext)?;
+    Blocklist::check_recipient(&ctx.accounts.blocklist, &ctx.accounts.recipient.key())?;
+    let ext_data_hash = ext_data.verify(&ctx.accounts.recipient.key(), &ctx.accounts.payer.key())?;
+    RelayerRegistration::check_ext_data(
+        &ctx.accounts.relayer_registration,
+        &ext_data,
+        &ctx.accounts.vault.key(),
+        amount,
+    )?;
 
     let vault = &ctx.accounts.vault;
     let merkle_tree = &mut ctx.accounts.merkle_tree;
     let nullifier_account = &mut ctx.accounts.nullifier_account;
 
     require!(vault.vault_type == VaultType::Alternative, ZyncxError::VaultNotFound);
+    require!(!vault.paused, ZyncxError::VaultPaused);
+    require!(vault.is_valid_denomination(amount), ZyncxError::InvalidDenomination);
+    vault.check_spent_note(amount, remaining_amount, &new_commitment)?;
 
     // Get current merkle root
     let root = merkle_tree.get_root();
 
-    // Verify ZK proof via CPI to verifier program
-    let mut verifier_input = Vec::new();
-    
-    // 1. Append proof bytes
-    verifier_input.extend_from_slice(&proof);
-    
-    // 2. Public Input: Root (32 bytes)
-    verifier_input.extend_from_slice(&root);
-    
-    // 3. Public Input: Nullifier Hash (32 bytes)
-    verifier_input.extend_from_slice(&nullifier);
-    
-    // 4. Public Input: Recipient (32 bytes)
-    verifier_input.extend_from_slice(&ctx.accounts.recipient.key().to_bytes());
-    
-    // 5. Public Input: Amount (32 bytes, Big Endian)
-    let mut amount_bytes = [0u8; 32];
-    amount_bytes[24..32].copy_from_slice(&amount.to_be_bytes());
-    verifier_input.extend_from_slice(&amount_bytes);
-    
-    // Invoke verifier program
-    let instruction = Instruction {
-        program_id: *ctx.accounts.verifier_program.key,
-        accounts: vec![],
-        data: verifier_input,
-    };
-    
+    // Charge the note's allowance policy, if it has one
+    let policy_hash = charge_allowance(
+        &mut ctx.accounts.allowance_policy,
+        amount,
+        Clock::get()?.unix_timestamp,
+    )?;
+
+    // Public inputs: [root, nullifier_hash, recipient, amount, new_commitment, ext_data_hash,
+    // remaining_amount, note_age_cutoff, policy_hash]
+    let public_inputs = vec![
+        root,
+        nullifier,
+        ctx.accounts.recipient.key().to_bytes(),
+        u64_public_input(amount),
+        new_commitment,
+        // Binds max fee, relayer tip and disclosure
+        ext_data_hash,
+        // Change note value; the circuit proves amount + remaining = note value
+        u64_public_input(remaining_amount),
+        // Latest insertion slot of a spendable note
+        u64_public_input(vault.note_age_cutoff(Clock::get()?.slot)),
+        // Allowance policy the note (and its change note) is bound to
+        policy_hash,
+    ];
+
     msg!("Invoking ZK Verifier...");
-    invoke(
-        &instruction,
-        &[ctx.accounts.verifier_program.clone()],
-    ).map_err(|_| ZyncxError::InvalidZKProof)?;
-    
+    ProofVerifier::for_vault(
+        vault,
+        &ctx.accounts.verifier_program,
+        &ctx.accounts.verification_key,
+    )
+    .verify(&proof, &public_inputs)?;
+
     msg!("ZK Proof Verified Successfully!");
 
+    let exclusion_root = verify_exclusion_proof(
+        &ctx.accounts.exclusion_root,
+        &ctx.accounts.exclusion_verifier,
+        &nullifier,
+        &exclusion_proof,
+    )?;
+
     // Mark nullifier as spent
     nullifier_account.bump = ctx.bumps.nullifier_account;
     nullifier_account.nullifier = nullifier;
@@ -263,12 +588,40 @@ pub fn handler_token(
 
     // For partial withdrawals, insert new commitment for remaining balance
     let is_partial_withdrawal = new_commitment != [0u8; 32];
-    if is_partial_withdrawal {
-        merkle_tree.insert(new_commitment)?;
+    let insertion = if is_partial_withdrawal {
+        let insertion = merkle_tree.insert(new_commitment)?;
         msg!("Partial withdrawal: inserted change commitment into merkle tree");
+        Some(insertion)
     } else {
         msg!("Full withdrawal: no change commitment needed");
-    }
+        None
+    };
+    let (leaf_index, new_root) = merkle_tree.event_position(insertion.as_ref());
+
+    let vault_stats = &mut ctx.accounts.vault_stats;
+    vault_stats.ensure_initialized(vault.key(), ctx.bumps.vault_stats);
+    let anonymity_set = vault_stats.anonymity_set_size();
+    vault_stats.record_spend(is_partial_withdrawal, Clock::get()?.unix_timestamp);
+    let anonymity_set_seconds = vault_stats.anonymity_set_seconds;
+
+    let global_stats = &mut ctx.accounts.global_stats;
+    global_stats.ensure_initialized(ctx.bumps.global_stats);
+    global_stats.record_withdrawal(amount, Clock::get()?.unix_timestamp);
+
+    // Volume-based fee stays in the vault token account
+    let vault_fees = &mut ctx.accounts.vault_fees;
+    vault_fees.ensure_initialized(vault.key(), ctx.bumps.vault_fees);
+    let exempt = FeeExemptions::is_exempt(
+        &ctx.accounts.fee_exemptions,
+        &[ctx.accounts.payer.key(), ctx.accounts.recipient.key()],
+    )?;
+    let fee = vault_fees.apply_withdrawal(
+        amount,
+        ctx.accounts.vault_token_account.amount,
+        Clock::get()?.unix_timestamp,
+        exempt,
+    );
+    let (payout, relayer_tip) = ext_data.split(amount, fee)?;
 
     // Transfer tokens from vault to recipient
     let vault_key = vault.key();
@@ -290,21 +643,50 @@ pub fn handler_token(
             },
             signer_seeds,
         ),
-        amount,
+        payout,
     )?;
 
+    if relayer_tip > 0 {
+        let relayer_token_account = ctx
+            .accounts
+            .relayer_token_account
+            .as_ref()
+            .ok_or(ZyncxError::ExtDataMismatch)?;
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.vault_token_account.to_account_info(),
+                    to: relayer_token_account.to_account_info(),
+                    authority: ctx.accounts.vault_token_account.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            relayer_tip,
+        )?;
+    }
+
     // Emit event
     emit!(WithdrawnEvent {
         recipient: ctx.accounts.recipient.key(),
         amount,
+        fee,
+        relayer_tip,
         nullifier,
         new_commitment,
         is_partial: is_partial_withdrawal,
+        viewing_ciphertext: ext_data.viewing_ciphertext,
+        exclusion_root,
+        ephemeral_pubkey: ext_data.ephemeral_pubkey,
+        anonymity_set,
+        anonymity_set_seconds,
+        leaf_index,
+        new_root,
     });
 
     msg!("Withdrawn {} tokens (partial: {})", amount, is_partial_withdrawal);
 
-    Ok(())
+    Ok(insertion)
 }
 
 
@@ -313,7 +695,29 @@ pub fn handler_token(
 pub struct WithdrawnEvent {
     pub recipient: Pubkey,
     pub amount: u64,
+    /// Protocol fee retained by the vault out of `amount`
+    pub fee: u64,
+    /// Paid to the relayer out of `amount`, as bound by the proof
+    pub relayer_tip: u64,
     pub nullifier: [u8; 32],
     pub new_commitment: [u8; 32],
     pub is_partial: bool,
+    /// Withdrawal details encrypted to the note owner's viewing key. No key
+    /// identifier is emitted so the spend stays unlinkable; auditors
+    /// trial-decrypt.
+    pub viewing_ciphertext: Vec<u8>,
+    /// Exclusion root the note was proven absent from (zero if not proven)
+    pub exclusion_root: [u8; 32],
+    /// Stealth payment ephemeral pubkey, as bound by the proof (zero if none)
+    pub ephemeral_pubkey: [u8; 32],
+    /// Unspent notes in the vault when this note was spent
+    pub anonymity_set: u64,
+    /// `VaultStats::anonymity_set_seconds` at spend time; the difference
+    /// between two events over the time between them is the time-weighted
+    /// average set size
+    pub anonymity_set_seconds: u128,
+    /// Leaf index of `new_commitment` (0 for a full withdrawal)
+    pub leaf_index: u64,
+    /// Tree root after the withdrawal, unchanged for a full one
+    pub new_root: [u8; 32],
 }
@@ -0,0 +1,541 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+use crate::errors::ZyncxError;
+use crate::instructions::treasury::{pay_from_vault_treasury, refill_from_cold};
+use crate::state::{
+    u64_public_input, Blocklist, FeeExemptions, GlobalStats, MerkleTreeState, NullifierState,
+    ProofCircuit, ProofVerifier, ProtocolConfig, RelayerRegistration, TreasuryPolicy, TreeInsertion, VaultFeeState,
+    VaultState, VaultStats, VaultType, VerificationKey, ViewingKey, WithdrawExtData,
+};
+
+/// Notes one batch withdrawal can spend (slots of the withdraw_batch circuit)
+pub const BATCH_WITHDRAW_MAX_NOTES: usize = 4;
+
+#[derive(Accounts)]
+pub struct WithdrawBatch<'info> {
+    /// CHECK: Receives the lamports of a SOL vault; owner of
+    /// recipient_token_account for a token vault
+    #[account(mut)]
+    pub recipient: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault.asset_mint.as_ref()],
+        bump = vault.bump,
+    )]
+    pub vault: Box<Account<'info, VaultState>>,
+
+    #[account(
+        mut,
+        constraint = merkle_tree.vault == vault.key() @ ZyncxError::MerkleTreeVaultMismatch,
+    )]
+    pub merkle_tree: Box<Account<'info, MerkleTreeState>>,
+
+    /// CHECK: Vault PDA that holds SOL; required for SOL vaults
+    #[account(
+        mut,
+        seeds = [b"vault_treasury", vault.key().as_ref()],
+        bump,
+    )]
+    pub vault_treasury: Option<UncheckedAccount<'info>>,
+
+    /// Vault's hot/cold split; pass with cold_treasury to refill the hot
+    /// treasury when it has run low (SOL vaults)
+    #[account(
+        mut,
+        seeds = [b"treasury_policy", vault.key().as_ref()],
+        bump = treasury_policy.bump,
+    )]
+    pub treasury_policy: Option<Account<'info, TreasuryPolicy>>,
+
+    /// CHECK: Vault PDA holding the cold share of the SOL
+    #[account(
+        mut,
+        seeds = [b"cold_treasury", vault.key().as_ref()],
+        bump,
+    )]
+    pub cold_treasury: Option<UncheckedAccount<'info>>,
+
+    /// Vault escrow; required for token vaults
+    #[account(
+        mut,
+        seeds = [b"vault_token_account", vault.key().as_ref()],
+        bump,
+    )]
+    pub vault_token_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    /// Recipient's token account; required for token vaults
+    #[account(
+        mut,
+        constraint = recipient_token_account.owner == recipient.key() @ ZyncxError::ExtDataMismatch,
+        constraint = recipient_token_account.mint == vault.asset_mint @ ZyncxError::InvalidMint,
+    )]
+    pub recipient_token_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    /// Payer's token account for the relayer tip; required when a token
+    /// vault's tip is non-zero
+    #[account(
+        mut,
+        constraint = relayer_token_account.owner == payer.key() @ ZyncxError::ExtDataMismatch,
+        constraint = relayer_token_account.mint == vault.asset_mint @ ZyncxError::ExtDataMismatch,
+    )]
+    pub relayer_token_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = VaultStats::INIT_SPACE,
+        seeds = [b"vault_stats", vault.key().as_ref()],
+        bump
+    )]
+    pub vault_stats: Box<Account<'info, VaultStats>>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = VaultFeeState::INIT_SPACE,
+        seeds = [b"vault_fees", vault.key().as_ref()],
+        bump
+    )]
+    pub vault_fees: Box<Account<'info, VaultFeeState>>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = GlobalStats::INIT_SPACE,
+        seeds = [b"global_stats"],
+        bump
+    )]
+    pub global_stats: Box<Account<'info, GlobalStats>>,
+
+    /// CHECK: Blocklist PDA; may be uninitialized, parsed in the handler
+    #[account(seeds = [b"blocklist"], bump)]
+    pub blocklist: UncheckedAccount<'info>,
+
+    /// CHECK: Fee exemption PDA; may be uninitialized, parsed in the handler
+    #[account(seeds = [b"fee_exemptions"], bump)]
+    pub fee_exemptions: UncheckedAccount<'info>,
+
+    #[account(seeds = [b"protocol_config"], bump = protocol_config.bump)]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    /// CHECK: Noir verifier of the withdraw_batch circuit (address pinned by the protocol config)
+    #[account(
+        executable,
+        address = protocol_config.batch_withdraw_verifier @ ZyncxError::InvalidVerifierProgram
+    )]
+    pub verifier_program: AccountInfo<'info>,
+
+    /// Vault's Groth16 key of the withdraw_batch circuit; required when the
+    /// vault verifies Groth16 proofs
+    #[account(
+        seeds = [b"verification_key", vault.key().as_ref(), &[ProofCircuit::BatchWithdraw as u8]],
+        bump = verification_key.bump,
+    )]
+    pub verification_key: Option<Box<Account<'info, VerificationKey>>>,
+
+    /// Risk signer of the protocol config; co-signs operations above its
+    /// value ceiling
+    pub risk_signer: Option<Signer<'info>>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Payer's relayer registration; required when `ext_data` pins a fee
+    /// schedule
+    #[account(
+        seeds = [b"relayer", payer.key().as_ref()],
+        bump = relayer_registration.bump,
+    )]
+    pub relayer_registration: Option<Box<Account<'info, RelayerRegistration>>>,
+
+    pub token_program: Option<Program<'info, Token>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Withdraw `amount` funded by several notes with one proof. Remaining
+/// accounts are the nullifier PDAs ([b"nullifier", vault, nullifier]) in
+/// `nullifiers` order; they are created here like a single withdrawal's.
+/// At most one change note is inserted. Notes needing an exclusion proof are
+/// withdrawn one at a time, as are notes bound to an allowance policy: the
+/// batch circuit only recomputes unbound commitments, so their proofs fail.
+pub fn handler_withdraw_batch<'info>(
+    ctx: Context<'_, '_, 'info, 'info, WithdrawBatch<'info>>,
+    amount: u64,
+    nullifiers: Vec<[u8; 32]>,
+    new_commitment: [u8; 32],
+    proof: Vec<u8>,
+    ext_data: WithdrawExtData,
+) -> Result<Option<TreeInsertion>> {
+    require!(amount > 0, ZyncxError::InvalidWithdrawalAmount);
+    require!(
+        !nullifiers.is_empty() && nullifiers.len() <= BATCH_WITHDRAW_MAX_NOTES,
+        ZyncxError::InvalidBatchWithdrawal
+    );
+    require!(
+        ctx.remaining_accounts.len() == nullifiers.len(),
+        ZyncxError::InvalidBatchWithdrawal
+    );
+    ctx.accounts.protocol_config.check_operation_value(
+        amount,
+        ctx.accounts.risk_signer.as_ref().map(|signer| signer.key()),
+    )?;
+    ViewingKey::check_ciphertext_len(&ext_data.viewing_ciphertext)?;
+    Blocklist::check_recipient(&ctx.accounts.blocklist, &ctx.accounts.recipient.key())?;
+    let ext_data_hash = ext_data.verify(&ctx.accounts.recipient.key(), &ctx.accounts.payer.key())?;
+    RelayerRegistration::check_ext_data(
+        &ctx.accounts.relayer_registration,
+        &ext_data,
+        &ctx.accounts.vault.key(),
+        amount,
+    )?;
+
+    let vault = &ctx.accounts.vault;
+    let vault_key = vault.key();
+    let vault_type = vault.vault_type;
+    require!(!vault.paused, ZyncxError::VaultPaused);
+    require!(vault.is_valid_denomination(amount), ZyncxError::InvalidDenomination);
+
+    let merkle_tree = &mut ctx.accounts.merkle_tree;
+    let root = merkle_tree.get_root();
+
+    // Circuit expects public inputs: [root, nullifier_hashes[4], recipient, amount, new_commitment, ext_data_hash, note_age_cutoff]
+    let mut public_inputs = Vec::with_capacity(BATCH_WITHDRAW_MAX_NOTES + 6);
+    public_inputs.push(root);
+
+    // Unused slots are zero
+    for slot in 0..BATCH_WITHDRAW_MAX_NOTES {
+        public_inputs.push(nullifiers.get(slot).copied().unwrap_or([0u8; 32]));
+    }
+
+    public_inputs.push(ctx.accounts.recipient.key().to_bytes());
+    public_inputs.push(u64_public_input(amount));
+    public_inputs.push(new_commitment);
+    public_inputs.push(ext_data_hash);
+    public_inputs.push(u64_public_input(vault.note_age_cutoff(Clock::get()?.slot)));
+
+    ProofVerifier::for_vault(
+        vault,
+        &ctx.accounts.verifier_program,
+        &ctx.accounts.verification_key,
+    )
+    .verify(&proof, &public_inputs)?;
+
+    // Mark every nullifier spent; a repeated nullifier finds its account
+    // already created and fails
+    let now = Clock::get()?.unix_timestamp;
+    for (nullifier, nullifier_account) in nullifiers.iter().zip(ctx.remaining_accounts.iter()) {
+        spend_nullifier(
+            nullifier_account,
+            &vault_key,
+            nullifier,
+            &ctx.accounts.payer,
+            &ctx.accounts.system_program,
+            now,
+        )?;
+    }
+
+    let is_partial_withdrawal = new_commitment != [0u8; 32];
+    let insertion = if is_partial_withdrawal {
+        Some(merkle_tree.insert(new_commitment)?)
+    } else {
+        None
+    };
+    let (leaf_index, new_root) = merkle_tree.event_position(insertion.as_ref());
+
+    let vault_stats = &mut ctx.accounts.vault_stats;
+    vault_stats.ensure_initialized(vault_key, ctx.bumps.vault_stats);
+    let anonymity_set = vault_stats.anonymity_set_size();
+    for i in 0..nullifiers.len() {
+        vault_stats.record_spend(is_partial_withdrawal && i == 0, now);
+    }
+    let anonymity_set_seconds = vault_stats.anonymity_set_seconds;
+
+    let global_stats = &mut ctx.accounts.global_stats;
+    global_stats.ensure_initialized(ctx.bumps.global_stats);
+    global_stats.record_withdrawal(amount, now);
+
+    let exempt = FeeExemptions::is_exempt(
+        &ctx.accounts.fee_exemptions,
+        &[ctx.accounts.payer.key(), ctx.accounts.recipient.key()],
+    )?;
+    ctx.accounts
+        .vault_fees
+        .ensure_initialized(vault_key, ctx.bumps.vault_fees);
+
+    let (fee, relayer_tip) = match vault_type {
+        VaultType::Native => {
+            let bump = ctx
+                .bumps
+                .vault_treasury
+                .ok_or(ZyncxError::MissingPayoutAccount)?;
+            pay_out_native(ctx.accounts, amount, &ext_data, exempt, now, bump)?
+        }
+        VaultType::Alternative => {
+            let bump = ctx
+                .bumps
+                .vault_token_account
+                .ok_or(ZyncxError::MissingPayoutAccount)?;
+            pay_out_token(ctx.accounts, amount, &ext_data, exempt, now, bump)?
+        }
+    };
+
+    emit!(BatchWithdrawnEvent {
+        recipient: ctx.accounts.recipient.key(),
+        amount,
+        fee,
+        relayer_tip,
+        nullifiers,
+        new_commitment,
+        is_partial: is_partial_withdrawal,
+        viewing_ciphertext: ext_data.viewing_ciphertext,
+        ephemeral_pubkey: ext_data.ephemeral_pubkey,
+        anonymity_set,
+        anonymity_set_seconds,
+        leaf_index,
+        new_root,
+    });
+
+    Ok(insertion)
+}
+
+/// Create and fill the nullifier PDA for `nullifier`. Tolerates lamports
+/// sent to the address ahead of time, as the vault escrow creation does.
+fn spend_nullifier<'info>(
+    nullifier_account: &AccountInfo<'info>,
+    vault: &Pubkey,
+    nullifier: &[u8; 32],
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    now: i64,
+) -> Result<()> {
+    let (expected, bump) =
+        Pubkey::find_program_address(&[b"nullifier", vault.as_ref(), nullifier], &crate::ID);
+    require_keys_eq!(nullifier_account.key(), expected, ZyncxError::InvalidBatchWithdrawal);
+    require!(
+        nullifier_account.data_is_empty(),
+        ZyncxError::NullifierAlreadySpent
+    );
+
+    let bump_seed = [bump];
+    let seeds: &[&[u8]] = &[b"nullifier", vault.as_ref(), nullifier, &bump_seed];
+    let signer_seeds = &[seeds];
+
+    let space = NullifierState::INIT_SPACE;
+    let rent = Rent::get()?.minimum_balance(space);
+    let current = nullifier_account.lamports();
+
+    if current == 0 {
+        system_program::create_account(
+            CpiContext::new_with_signer(
+                system_program.to_account_info(),
+                system_program::CreateAccount {
+                    from: payer.to_account_info(),
+                    to: nullifier_account.clone(),
+                },
+                signer_seeds,
+            ),
+            rent,
+            space as u64,
+            &crate::ID,
+        )?;
+    } else {
+        if current < rent {
+            system_program::transfer(
+                CpiContext::new(
+                    system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: payer.to_account_info(),
+                        to: nullifier_account.clone(),
+                    },
+                ),
+                rent - current,
+            )?;
+        }
+        system_program::allocate(
+            CpiContext::new_with_signer(
+                system_program.to_account_info(),
+                system_program::Allocate {
+                    account_to_allocate: nullifier_account.clone(),
+                },
+                signer_seeds,
+            ),
+            space as u64,
+        )?;
+        system_program::assign(
+            CpiContext::new_with_signer(
+                system_program.to_account_info(),
+                system_program::Assign {
+                    account_to_assign: nullifier_account.clone(),
+                },
+                signer_seeds,
+            ),
+            &crate::ID,
+        )?;
+    }
+
+    let state = NullifierState {
+        bump,
+        nullifier: *nullifier,
+        spent: true,
+        spent_at: now,
+        vault: *vault,
+    };
+    let mut data = nullifier_account.try_borrow_mut_data()?;
+    state.try_serialize(&mut &mut data[..])?;
+
+    Ok(())
+}
+
+/// Pay a SOL vault's batch withdrawal out of the hot treasury, refilling it
+/// from cold first when a treasury policy is passed. Returns (fee, tip).
+fn pay_out_native(
+    accounts: &mut WithdrawBatch,
+    amount: u64,
+    ext_data: &WithdrawExtData,
+    exempt: bool,
+    now: i64,
+    treasury_bump: u8,
+) -> Result<(u64, u64)> {
+    let vault_treasury = accounts
+        .vault_treasury
+        .as_ref()
+        .ok_or(ZyncxError::MissingPayoutAccount)?;
+
+    let mut cold_lamports = 0;
+    if let (Some(treasury_policy), Some(cold_treasury)) = (
+        accounts.treasury_policy.as_mut(),
+        accounts.cold_treasury.as_ref(),
+    ) {
+        refill_from_cold(
+            treasury_policy,
+            vault_treasury,
+            cold_treasury,
+            &accounts.system_program,
+            now,
+        )?;
+        cold_lamports = cold_treasury.lamports();
+    }
+
+    let treasury_lamports = vault_treasury.lamports();
+    require!(treasury_lamports >= amount, ZyncxError::InvalidWithdrawalAmount);
+
+    let fee = accounts.vault_fees.apply_withdrawal(
+        amount,
+        treasury_lamports.saturating_add(cold_lamports),
+        now,
+        exempt,
+    );
+    let (payout, relayer_tip) = ext_data.split(amount, fee)?;
+
+    for (to, lamports) in [
+        (accounts.recipient.to_account_info(), payout),
+        (accounts.payer.to_account_info(), relayer_tip),
+    ] {
+        pay_from_vault_treasury(
+            &accounts.system_program,
+            vault_treasury,
+            &to,
+            &accounts.vault.key(),
+            treasury_bump,
+            lamports,
+        )?;
+    }
+
+    Ok((fee, relayer_tip))
+}
+
+/// Pay a token vault's batch withdrawal out of its escrow. Returns (fee, tip).
+fn pay_out_token(
+    accounts: &mut WithdrawBatch,
+    amount: u64,
+    ext_data: &WithdrawExtData,
+    exempt: bool,
+    now: i64,
+    vault_token_bump: u8,
+) -> Result<(u64, u64)> {
+    let vault_token_account = accounts
+        .vault_token_account
+        .as_ref()
+        .ok_or(ZyncxError::MissingPayoutAccount)?;
+    let recipient_token_account = accounts
+        .recipient_token_account
+        .as_ref()
+        .ok_or(ZyncxError::MissingPayoutAccount)?;
+    let token_program = accounts
+        .token_program
+        .as_ref()
+        .ok_or(ZyncxError::MissingPayoutAccount)?;
+
+    let fee = accounts
+        .vault_fees
+        .apply_withdrawal(amount, vault_token_account.amount, now, exempt);
+    let (payout, relayer_tip) = ext_data.split(amount, fee)?;
+
+    let vault_key = accounts.vault.key();
+    let bump = [vault_token_bump];
+    let seeds: &[&[u8]] = &[b"vault_token_account", vault_key.as_ref(), &bump];
+    let signer_seeds = &[seeds];
+
+    token::transfer(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            Transfer {
+                from: vault_token_account.to_account_info(),
+                to: recipient_token_account.to_account_info(),
+                authority: vault_token_account.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        payout,
+    )?;
+
+    if relayer_tip > 0 {
+        let relayer_token_account = accounts
+            .relayer_token_account
+            .as_ref()
+            .ok_or(ZyncxError::ExtDataMismatch)?;
+        token::transfer(
+            CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                Transfer {
+                    from: vault_token_account.to_account_info(),
+                    to: relayer_token_account.to_account_info(),
+                    authority: vault_token_account.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            relayer_tip,
+        )?;
+    }
+
+    Ok((fee, relayer_tip))
+}
+
+#[event]
+pub struct BatchWithdrawnEvent {
+    pub recipient: Pubkey,
+    pub amount: u64,
+    /// Protocol fee retained by the vault out of `amount`
+    pub fee: u64,
+    /// Paid to the relayer out of `amount`, as bound by the proof
+    pub relayer_tip: u64,
+    pub nullifiers: Vec<[u8; 32]>,
+    pub new_commitment: [u8; 32],
+    pub is_partial: bool,
+    /// Withdrawal details encrypted to the note owner's viewing key
+    pub viewing_ciphertext: Vec<u8>,
+    /// Stealth payment ephemeral pubkey, as bound by the proof (zero if none)
+    pub ephemeral_pubkey: [u8; 32],
+    /// Unspent notes in the vault when these notes were spent
+    pub anonymity_set: u64,
+    /// `VaultStats::anonymity_set_seconds` at spend time
+    pub anonymity_set_seconds: u128,
+    /// Leaf index of `new_commitment` (0 for a full withdrawal)
+    pub leaf_index: u64,
+    /// Tree root after the withdrawal, unchanged for a full one
+    pub new_root: [u8; 32],
+}
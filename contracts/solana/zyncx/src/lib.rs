@@ -8,19 +8,54 @@ pub mod instructions;
 pub mod state;
 
 use instructions::*;
-use state::{SwapParam, EncryptedVaultAccount};
+use state::{
+    ConfigChange, Role, SwapParam, EncryptedVaultAccount, EncryptedSwapRequest, SwapRequestStatus,
+    EncryptedHybridOrder, HybridOrderStatus, EncryptedLimitOrder, LimitOrderStatus,
+    EncryptedDCAConfig, DCAStatus, ReferralEpoch, TreeInsertion, DepositReceipt,
+    EncryptedUserPosition, BalanceAttestation, WithdrawExtData, EncryptedSealedOrder,
+    SealedOrderStatus, BatchAuction, ClaimPayout, EncryptedTWAPConfig, TWAPStatus,
+    EncryptedStopOrder, StopOrderStatus, TreasurySplitParams, PriceGuards, AllowanceParams,
+    OracleSource, SwapQuote, RfqQuote, RfqRequest, RfqStatus, ProofCircuit, ParamChange,
+    MigratableAccount, CURRENT_ACCOUNT_VERSION,
+};
 
 // Computation definition offsets for Arcium MXE circuits
 const COMP_DEF_OFFSET_INIT_VAULT: u32 = comp_def_offset("init_vault");
 const COMP_DEF_OFFSET_PROCESS_DEPOSIT: u32 = comp_def_offset("process_deposit");
 const COMP_DEF_OFFSET_CONFIDENTIAL_SWAP: u32 = comp_def_offset("confidential_swap");
+const COMP_DEF_OFFSET_EVALUATE_HYBRID_ORDER: u32 = comp_def_offset("evaluate_hybrid_order");
+const COMP_DEF_OFFSET_EVALUATE_LIMIT_ORDER: u32 = comp_def_offset("evaluate_limit_order");
+const COMP_DEF_OFFSET_EVALUATE_LIMIT_ORDER_BATCH: u32 = comp_def_offset("evaluate_limit_order_batch");
+const COMP_DEF_OFFSET_PROCESS_DCA: u32 = comp_def_offset("process_dca");
+const COMP_DEF_OFFSET_INIT_REFERRAL_EPOCH: u32 = comp_def_offset("init_referral_epoch");
+const COMP_DEF_OFFSET_REVEAL_REFERRAL_EPOCH: u32 = comp_def_offset("reveal_referral_epoch");
+const COMP_DEF_OFFSET_COMPUTE_WITHDRAWAL: u32 = comp_def_offset("compute_withdrawal");
+const COMP_DEF_OFFSET_CLEAR_POSITION: u32 = comp_def_offset("clear_position");
+const COMP_DEF_OFFSET_INIT_POSITION: u32 = comp_def_offset("init_position");
+const COMP_DEF_OFFSET_VERIFY_SUFFICIENT_BALANCE: u32 = comp_def_offset("verify_sufficient_balance");
+const COMP_DEF_OFFSET_RUN_BATCH_AUCTION: u32 = comp_def_offset("run_batch_auction");
+const COMP_DEF_OFFSET_MATCH_ORDERS: u32 = comp_def_offset("match_orders");
+const COMP_DEF_OFFSET_PROCESS_TWAP: u32 = comp_def_offset("process_twap");
+const COMP_DEF_OFFSET_EVALUATE_STOP_ORDER: u32 = comp_def_offset("evaluate_stop_order");
+const COMP_DEF_OFFSET_NET_ORDERS: u32 = comp_def_offset("net_orders");
+const COMP_DEF_OFFSET_SELECT_RFQ_QUOTE: u32 = comp_def_offset("select_rfq_quote");
 
 declare_id!("5TGQEPDL2K6RoxKLbfjD2KMypbvKewDUsfuaNAvCAUMU");
 
-// Noir ZK Verifier Program (deployed via Sunspot)
+// Noir ZK Verifier Program (deployed via Sunspot). The verifiers proofs are
+// checked against are pinned in `ProtocolConfig`; these are the deployments
+// it is initialized with.
 pub const NOIR_VERIFIER_PROGRAM_ID: Pubkey = 
     pubkey!("AWUEQfGnU2nVYAA3dfKpckDhqjoW6HELT5wvkg9Sve1y");
 
+// Noir verifier for the shielded transfer circuit (transfer/, deployed via Sunspot)
+pub const TRANSFER_VERIFIER_PROGRAM_ID: Pubkey =
+    pubkey!("4fxK8aGf8D4k4qLRhSfkGARMx6WWJoHP9JaiGX4qKeke");
+
+// Noir verifier for the batch withdrawal circuit (withdraw_batch/, deployed via Sunspot)
+pub const BATCH_WITHDRAW_VERIFIER_PROGRAM_ID: Pubkey =
+    pubkey!("2Xxnd8BJg3x5p7xgSX2gxGWveaJvSnmPpm4mXP2tdwF7");
+
 #[arcium_program]
 pub mod zyncx {
     use super::*;
@@ -33,40 +68,161 @@ pub mod zyncx {
         instructions::initialize::handler(ctx, asset_mint)
     }
 
+    /// Deposit into the vault's active tree. With `include_path` the return
+    /// data also carries the new leaf's Merkle authentication path.
     pub fn deposit_native(
         ctx: Context<DepositNative>,
         amount: u64,
         precommitment: [u8; 32],
-    ) -> Result<[u8; 32]> {
-        instructions::deposit::handler_native(ctx, amount, precommitment)
+        encrypted_note: Vec<u8>,
+        viewing_ciphertext: Vec<u8>,
+        include_path: bool,
+    ) -> Result<DepositReceipt> {
+        instructions::deposit::handler_native(
+            ctx,
+            amount,
+            precommitment,
+            encrypted_note,
+            viewing_ciphertext,
+            include_path,
+        )
     }
 
+    /// Deposit into the vault's active tree. With `include_path` the return
+    /// data also carries the new leaf's Merkle authentication path.
     pub fn deposit_token(
         ctx: Context<DepositToken>,
         amount: u64,
         precommitment: [u8; 32],
-    ) -> Result<[u8; 32]> {
-        instructions::deposit::handler_token(ctx, amount, precommitment)
+        encrypted_note: Vec<u8>,
+        viewing_ciphertext: Vec<u8>,
+        include_path: bool,
+    ) -> Result<DepositReceipt> {
+        instructions::deposit::handler_token(
+            ctx,
+            amount,
+            precommitment,
+            encrypted_note,
+            viewing_ciphertext,
+            include_path,
+        )
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn withdraw_native(
         ctx: Context<WithdrawNative>,
         amount: u64,
         nullifier: [u8; 32],
         new_commitment: [u8; 32],
+        remaining_amount: u64,
         proof: Vec<u8>,
-    ) -> Result<()> {
-        instructions::withdraw::handler_native(ctx, amount, nullifier, new_commitment, proof)
+        ext_data: WithdrawExtData,
+        exclusion_proof: Vec<u8>,
+    ) -> Result<Option<TreeInsertion>> {
+        instructions::withdraw::handler_native(
+            ctx,
+            amount,
+            nullifier,
+            new_commitment,
+            remaining_amount,
+            proof,
+            ext_data,
+            exclusion_proof,
+        )
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn withdraw_token(
         ctx: Context<WithdrawToken>,
         amount: u64,
         nullifier: [u8; 32],
         new_commitment: [u8; 32],
+        remaining_amount: u64,
+        proof: Vec<u8>,
+        ext_data: WithdrawExtData,
+        exclusion_proof: Vec<u8>,
+    ) -> Result<Option<TreeInsertion>> {
+        instructions::withdraw::handler_token(
+            ctx,
+            amount,
+            nullifier,
+            new_commitment,
+            remaining_amount,
+            proof,
+            ext_data,
+            exclusion_proof,
+        )
+    }
+
+    /// Withdraw from several notes with one proof and one change note
+    pub fn withdraw_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, WithdrawBatch<'info>>,
+        amount: u64,
+        nullifiers: Vec<[u8; 32]>,
+        new_commitment: [u8; 32],
         proof: Vec<u8>,
+        ext_data: WithdrawExtData,
+    ) -> Result<Option<TreeInsertion>> {
+        instructions::withdraw_batch::handler_withdraw_batch(
+            ctx,
+            amount,
+            nullifiers,
+            new_commitment,
+            proof,
+            ext_data,
+        )
+    }
+
+    /// Spend a note into a recipient note and an optional change note
+    /// without moving funds out of the vault
+    pub fn private_transfer(
+        ctx: Context<PrivateTransfer>,
+        nullifier: [u8; 32],
+        recipient_commitment: [u8; 32],
+        change_commitment: [u8; 32],
+        proof: Vec<u8>,
+        encrypted_note: Vec<u8>,
+        ephemeral_pubkey: [u8; 32],
+    ) -> Result<Vec<TreeInsertion>> {
+        instructions::private_transfer::handler_private_transfer(
+            ctx,
+            nullifier,
+            recipient_commitment,
+            change_commitment,
+            proof,
+            encrypted_note,
+            ephemeral_pubkey,
+        )
+    }
+
+    /// Publish or rotate the caller's stealth meta-address
+    pub fn register_stealth_meta_address(
+        ctx: Context<RegisterStealthMetaAddress>,
+        spend_pubkey: [u8; 32],
+        view_pubkey: [u8; 32],
+    ) -> Result<()> {
+        instructions::stealth::handler_register_stealth_meta_address(ctx, spend_pubkey, view_pubkey)
+    }
+
+    /// Close the caller's stealth meta-address
+    pub fn close_stealth_meta_address(ctx: Context<CloseStealthMetaAddress>) -> Result<()> {
+        instructions::stealth::handler_close_stealth_meta_address(ctx)
+    }
+
+    /// Register the caller as a relayer or update its posted fee schedule
+    pub fn register_relayer(
+        ctx: Context<RegisterRelayer>,
+        endpoint_hash: [u8; 32],
+        vaults: Vec<Pubkey>,
+        base_fee: u64,
+        fee_bps: u16,
     ) -> Result<()> {
-        instructions::withdraw::handler_token(ctx, amount, nullifier, new_commitment, proof)
+        instructions::relayer::handler_register_relayer(ctx, endpoint_hash, vaults, base_fee, fee_bps)
+    }
+
+    /// Remove the caller from the relayer registry
+    pub fn close_relayer_registration(ctx: Context<CloseRelayerRegistration>) -> Result<()> {
+        instructions::relayer::handler_close_relayer_registration(ctx)
     }
 
     pub fn swap_native<'info>(
@@ -76,7 +232,7 @@ pub mod zyncx {
         new_commitment: [u8; 32],
         proof: Vec<u8>,
         swap_data: Vec<u8>,
-    ) -> Result<()> {
+    ) -> Result<Option<TreeInsertion>> {
         instructions::swap::handler_native(ctx, swap_param, nullifier, new_commitment, proof, swap_data)
     }
 
@@ -87,303 +243,4945 @@ pub mod zyncx {
         new_commitment: [u8; 32],
         proof: Vec<u8>,
         swap_data: Vec<u8>,
-    ) -> Result<()> {
+    ) -> Result<Option<TreeInsertion>> {
         instructions::swap::handler_token(ctx, swap_param, nullifier, new_commitment, proof, swap_data)
     }
 
+    /// Ordered account list (with signer/writable flags) for a withdrawal
+    pub fn plan_withdrawal(
+        ctx: Context<PlanOperation>,
+        nullifier: [u8; 32],
+        recipient: Pubkey,
+        payer: Pubkey,
+    ) -> Result<Vec<PlannedAccount>> {
+        instructions::plan::handler_plan_withdrawal(ctx, nullifier, recipient, payer)
+    }
+
+    /// Ordered account list (with signer/writable flags) for a swap
+    pub fn plan_swap(
+        ctx: Context<PlanOperation>,
+        nullifier: [u8; 32],
+        recipient: Pubkey,
+        payer: Pubkey,
+    ) -> Result<Vec<PlannedAccount>> {
+        instructions::plan::handler_plan_swap(ctx, nullifier, recipient, payer)
+    }
+
+    /// Dry run of a withdrawal: every check, no writes; the outcome is
+    /// returned as return data
+    #[allow(clippy::too_many_arguments)]
+    pub fn simulate_withdraw(
+        ctx: Context<SimulateWithdraw>,
+        nullifier: [u8; 32],
+        root: [u8; 32],
+        amount: u64,
+        new_commitment: [u8; 32],
+        remaining_amount: u64,
+        proof: Vec<u8>,
+        ext_data: WithdrawExtData,
+    ) -> Result<WithdrawSimulation> {
+        instructions::simulate::handler_simulate_withdraw(
+            ctx,
+            nullifier,
+            root,
+            amount,
+            new_commitment,
+            remaining_amount,
+            proof,
+            ext_data,
+        )
+    }
+
     pub fn verify_proof(
         ctx: Context<VerifyProof>,
         amount: u64,
         nullifier: [u8; 32],
+        recipient: Pubkey,
         new_commitment: [u8; 32],
+        ext_data_hash: [u8; 32],
+        remaining_amount: u64,
         proof: Vec<u8>,
     ) -> Result<bool> {
-        instructions::verify::handler(ctx, amount, nullifier, new_commitment, proof)
+        instructions::verify::handler(
+            ctx,
+            amount,
+            nullifier,
+            recipient,
+            new_commitment,
+            ext_data_hash,
+            remaining_amount,
+            proof,
+        )
     }
 
     pub fn check_root(ctx: Context<CheckRoot>, root: [u8; 32]) -> Result<bool> {
         instructions::verify::check_root_exists(ctx, root)
     }
 
-    // ========================================================================
-    // PHASE 2: ARCIUM MXE CONFIDENTIAL COMPUTATION
-    // ========================================================================
+    /// Check a merkle auth path against the tree's root history
+    pub fn verify_merkle_inclusion(
+        ctx: Context<CheckRoot>,
+        leaf: [u8; 32],
+        path: Vec<[u8; 32]>,
+        indices: Vec<u8>,
+    ) -> Result<bool> {
+        instructions::verify::handler_verify_merkle_inclusion(ctx, leaf, path, indices)
+    }
 
-    /// Initialize the init_vault computation definition
-    pub fn init_vault_comp_def(ctx: Context<InitVaultCompDef>) -> Result<()> {
-        init_comp_def(ctx.accounts, None, None)?;
-        Ok(())
+    /// Rotate deposits to a fresh merkle tree once the active one is near capacity
+    pub fn pre_rotate_tree(ctx: Context<PreRotateTree>) -> Result<()> {
+        instructions::tree::handler_pre_rotate(ctx)
     }
 
-    /// Initialize the process_deposit computation definition
-    pub fn init_process_deposit_comp_def(ctx: Context<InitProcessDepositCompDef>) -> Result<()> {
-        init_comp_def(ctx.accounts, None, None)?;
-        Ok(())
+    /// Set leaf capacity and rotation threshold for a vault's trees
+    pub fn configure_tree(
+        ctx: Context<ConfigureTree>,
+        max_leaves: u32,
+        rotation_threshold_bps: u16,
+    ) -> Result<()> {
+        instructions::tree::handler_configure(ctx, max_leaves, rotation_threshold_bps)
     }
 
-    /// Initialize the confidential_swap computation definition
-    pub fn init_confidential_swap_comp_def(ctx: Context<InitConfidentialSwapCompDef>) -> Result<()> {
-        init_comp_def(ctx.accounts, None, None)?;
-        Ok(())
+    /// Publish the exclusion set root used for proof-of-innocence withdrawals
+    pub fn set_exclusion_root(
+        ctx: Context<SetExclusionRoot>,
+        root: [u8; 32],
+        verifier_program: Pubkey,
+    ) -> Result<()> {
+        instructions::exclusion::handler_set(ctx, root, verifier_program)
     }
 
-    /// Create a new encrypted vault with Arcium MXE
-    pub fn create_encrypted_vault(
-        ctx: Context<CreateEncryptedVault>,
-        computation_offset: u64,
-        nonce: u128,
+    /// Upload a circuit's Groth16 verification key for the vault
+    pub fn set_verification_key(
+        ctx: Context<SetVerificationKey>,
+        circuit: ProofCircuit,
+        alpha_g1: [u8; 64],
+        beta_g2: [u8; 128],
+        gamma_g2: [u8; 128],
+        delta_g2: [u8; 128],
+        ic: Vec<[u8; 64]>,
     ) -> Result<()> {
-        msg!("Creating encrypted vault");
+        instructions::verification_key::handler_set_verification_key(
+            ctx, circuit, alpha_g1, beta_g2, gamma_g2, delta_g2, ic,
+        )
+    }
 
-        ctx.accounts.vault.bump = ctx.bumps.vault;
-        ctx.accounts.vault.token_mint = ctx.accounts.token_mint.key();
-        ctx.accounts.vault.authority = ctx.accounts.payer.key();
-        ctx.accounts.vault.nonce = nonce;
-        ctx.accounts.vault.encrypted_state = [[0u8; 32]; 3];
+    /// Create the role registry (upgrade authority only)
+    pub fn initialize_roles(
+        ctx: Context<InitializeRoles>,
+        admin: Pubkey,
+        pauser: Pubkey,
+        fee_manager: Pubkey,
+        compliance: Pubkey,
+    ) -> Result<()> {
+        instructions::roles::handler_initialize_roles(ctx, admin, pauser, fee_manager, compliance)
+    }
 
-        let args = ArgBuilder::new().plaintext_u128(nonce).build();
+    /// Propose reassigning a protocol role (admin only, timelocked)
+    pub fn propose_role_change(
+        ctx: Context<ProposeRoleChange>,
+        role: Role,
+        holder: Pubkey,
+    ) -> Result<()> {
+        instructions::roles::handler_propose_role_change(ctx, role, holder)
+    }
 
-        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+    /// Apply a proposed role change after its timelock (admin only)
+    pub fn execute_role_change(ctx: Context<ExecuteRoleChange>) -> Result<()> {
+        instructions::roles::handler_execute_role_change(ctx)
+    }
 
-        queue_computation(
-            ctx.accounts,
-            computation_offset,
-            args,
-            None,
-            vec![InitVaultCallback::callback_ix(
-                computation_offset,
-                &ctx.accounts.mxe_account,
-                &[CallbackAccount {
-                    pubkey: ctx.accounts.vault.key(),
-                    is_writable: true,
-                }],
-            )?],
-            1,
-            0,
-        )?;
+    /// Cancel a proposed role change (admin only)
+    pub fn cancel_role_change(ctx: Context<CancelRoleChange>) -> Result<()> {
+        instructions::roles::handler_cancel_role_change(ctx)
+    }
 
-        Ok(())
+    /// Create the protocol config pinning each circuit's verifier (admin only)
+    pub fn initialize_protocol_config(
+        ctx: Context<InitializeProtocolConfig>,
+        mixer_verifier: Pubkey,
+        transfer_verifier: Pubkey,
+        batch_withdraw_verifier: Pubkey,
+        timelock_seconds: i64,
+    ) -> Result<()> {
+        instructions::protocol_config::handler_initialize_protocol_config(
+            ctx,
+            mixer_verifier,
+            transfer_verifier,
+            batch_withdraw_verifier,
+            timelock_seconds,
+        )
     }
 
-    /// Callback for init_vault computation
-    #[arcium_callback(encrypted_ix = "init_vault")]
-    pub fn init_vault_callback(
-        ctx: Context<InitVaultCallback>,
-        output: SignedComputationOutputs<InitVaultOutput>,
+    /// Propose a new verifier program for a circuit (admin only, timelocked)
+    pub fn propose_verifier_change(
+        ctx: Context<UpdateProtocolConfig>,
+        circuit: ProofCircuit,
+        verifier: Pubkey,
     ) -> Result<()> {
-        let o = match output.verify_output(
-            &ctx.accounts.cluster_account,
-            &ctx.accounts.computation_account,
-        ) {
-            Ok(InitVaultOutput { field_0 }) => field_0,
-            Err(_) => return Err(ErrorCode::AbortedComputation.into()),
-        };
+        instructions::protocol_config::handler_propose_verifier_change(ctx, circuit, verifier)
+    }
 
-        ctx.accounts.vault.encrypted_state = o.ciphertexts;
-        ctx.accounts.vault.nonce = o.nonce;
+    /// Apply the pending verifier change once its timelock has elapsed (admin only)
+    pub fn execute_verifier_change(ctx: Context<UpdateProtocolConfig>) -> Result<()> {
+        instructions::protocol_config::handler_execute_verifier_change(ctx)
+    }
 
-        emit!(VaultInitialized {
-            vault: ctx.accounts.vault.key(),
-            timestamp: Clock::get()?.unix_timestamp,
-        });
+    /// Drop the pending verifier change (admin only)
+    pub fn cancel_verifier_change(ctx: Context<UpdateProtocolConfig>) -> Result<()> {
+        instructions::protocol_config::handler_cancel_verifier_change(ctx)
+    }
 
-        Ok(())
+    /// Cap the value of a single withdrawal or swap; larger ones need the
+    /// risk signer's co-signature (admin only)
+    pub fn set_operation_value_limit(
+        ctx: Context<UpdateProtocolConfig>,
+        max_operation_value: u64,
+        risk_signer: Pubkey,
+    ) -> Result<()> {
+        instructions::protocol_config::handler_set_operation_value_limit(
+            ctx,
+            max_operation_value,
+            risk_signer,
+        )
     }
 
-    /// Queue an encrypted deposit via Arcium MXE
-    pub fn queue_encrypted_deposit(
-        ctx: Context<QueueEncryptedDeposit>,
-        computation_offset: u64,
-        deposit_amount: u64,
+    /// Queue a fee or limit change, announced a full timelock ahead so users
+    /// can exit first (admin only)
+    pub fn propose_param_change(
+        ctx: Context<UpdateProtocolConfig>,
+        change: ParamChange,
     ) -> Result<()> {
-        msg!("Queueing encrypted deposit");
+        instructions::protocol_config::handler_propose_param_change(ctx, change)
+    }
 
-        let args = ArgBuilder::new()
-            .plaintext_u64(deposit_amount)
-            .plaintext_u128(ctx.accounts.vault.nonce)
-            .account(
-                ctx.accounts.vault.key(),
-                8 + 1 + 32 + 32 + 16,
-                32 * 3,
-            )
-            .build();
+    /// Apply a queued parameter change once its timelock has elapsed (admin only)
+    pub fn execute_param_change(ctx: Context<ExecuteParamChange>, id: u64) -> Result<()> {
+        instructions::protocol_config::handler_execute_param_change(ctx, id)
+    }
 
-        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+    /// Drop a queued parameter change (admin only)
+    pub fn cancel_param_change(ctx: Context<UpdateProtocolConfig>, id: u64) -> Result<()> {
+        instructions::protocol_config::handler_cancel_param_change(ctx, id)
+    }
 
-        queue_computation(
-            ctx.accounts,
-            computation_offset,
-            args,
-            None,
-            vec![ProcessDepositCallback::callback_ix(
-                computation_offset,
-                &ctx.accounts.mxe_account,
-                &[CallbackAccount {
-                    pubkey: ctx.accounts.vault.key(),
-                    is_writable: true,
-                }],
-            )?],
-            1,
-            0,
-        )?;
+    /// Pause or resume a vault (pauser role)
+    pub fn set_vault_paused(ctx: Context<SetVaultPaused>, paused: bool) -> Result<()> {
+        instructions::roles::handler_set_paused(ctx, paused)
+    }
 
-        emit!(EncryptedDepositQueued {
-            user: ctx.accounts.payer.key(),
-            vault: ctx.accounts.vault.key(),
-            computation_offset,
-            timestamp: Clock::get()?.unix_timestamp,
-        });
+    /// Report rejected ZK proofs for a vault (pauser or vault authority)
+    pub fn report_proof_failures(ctx: Context<ReportProofFailures>, count: u32) -> Result<()> {
+        instructions::proof_failures::handler_report_proof_failures(ctx, count)
+    }
 
-        Ok(())
+    /// Create the recipient blocklist (compliance role)
+    pub fn initialize_blocklist(ctx: Context<InitializeBlocklist>) -> Result<()> {
+        instructions::blocklist::handler_initialize(ctx)
     }
 
-    /// Callback for process_deposit computation
-    #[arcium_callback(encrypted_ix = "process_deposit")]
-    pub fn process_deposit_callback(
-        ctx: Context<ProcessDepositCallback>,
-        output: SignedComputationOutputs<ProcessDepositOutput>,
+    /// Block withdrawals and swaps to `recipient`
+    pub fn add_blocked(ctx: Context<UpdateBlocklist>, recipient: Pubkey) -> Result<()> {
+        instructions::blocklist::handler_add(ctx, recipient)
+    }
+
+    /// Lift a block on `recipient`
+    pub fn remove_blocked(ctx: Context<UpdateBlocklist>, recipient: Pubkey) -> Result<()> {
+        instructions::blocklist::handler_remove(ctx, recipient)
+    }
+
+    /// Create the withdrawal fee exemption list (fee manager role)
+    pub fn initialize_fee_exemptions(ctx: Context<InitializeFeeExemptions>) -> Result<()> {
+        instructions::fee_exemptions::handler_initialize_fee_exemptions(ctx)
+    }
+
+    /// Exempt an integration's withdrawals from protocol fees
+    pub fn add_fee_exemption(ctx: Context<UpdateFeeExemptions>, account: Pubkey) -> Result<()> {
+        instructions::fee_exemptions::handler_add_exemption(ctx, account)
+    }
+
+    /// Remove an integration's fee exemption
+    pub fn remove_fee_exemption(ctx: Context<UpdateFeeExemptions>, account: Pubkey) -> Result<()> {
+        instructions::fee_exemptions::handler_remove_exemption(ctx, account)
+    }
+
+    /// Queue a sensitive vault config change behind the vault's timelock
+    pub fn propose_config_change(
+        ctx: Context<ProposeConfigChange>,
+        change: ConfigChange,
     ) -> Result<()> {
-        let o = match output.verify_output(
-            &ctx.accounts.cluster_account,
-            &ctx.accounts.computation_account,
-        ) {
-            Ok(ProcessDepositOutput { field_0 }) => field_0,
-            Err(_) => return Err(ErrorCode::AbortedComputation.into()),
-        };
+        instructions::vault_config::handler_propose_config_change(ctx, change)
+    }
 
-        ctx.accounts.vault.encrypted_state = o.ciphertexts;
-        ctx.accounts.vault.nonce = o.nonce;
+    /// Apply a queued config change after its timelock has elapsed
+    pub fn execute_config_change(ctx: Context<ExecuteConfigChange>) -> Result<()> {
+        instructions::vault_config::handler_execute_config_change(ctx)
+    }
 
-        emit!(DepositProcessed {
-            vault: ctx.accounts.vault.key(),
-            timestamp: Clock::get()?.unix_timestamp,
-        });
+    /// Drop a queued config change
+    pub fn cancel_config_change(ctx: Context<CancelConfigChange>) -> Result<()> {
+        instructions::vault_config::handler_cancel_config_change(ctx)
+    }
 
-        Ok(())
+    /// Lengthen the vault's config timelock (shortening is itself timelocked)
+    pub fn increase_config_timelock(
+        ctx: Context<ConfigureVault>,
+        timelock_seconds: i64,
+    ) -> Result<()> {
+        instructions::vault_config::handler_increase_timelock(ctx, timelock_seconds)
     }
 
-    /// Queue a confidential swap via Arcium MXE
-    pub fn queue_confidential_swap(
-        ctx: Context<QueueConfidentialSwap>,
-        computation_offset: u64,
-        encrypted_min_out: [u8; 32],
-        encryption_pubkey: [u8; 32],
-        nonce: u128,
-        current_output: u64,
+    /// Set the minimum note age (in slots) enforced on withdrawals and swaps
+    pub fn set_min_note_age(ctx: Context<ConfigureVault>, min_note_age_slots: u64) -> Result<()> {
+        instructions::vault_config::handler_set_min_note_age(ctx, min_note_age_slots)
+    }
+
+    /// Set the inactivity window and grace period for vault sunsetting
+    pub fn set_sunset_policy(
+        ctx: Context<ConfigureVault>,
+        inactivity_epochs: u64,
+        grace_seconds: i64,
     ) -> Result<()> {
-        msg!("Queueing confidential swap");
+        instructions::sunset::handler_set_sunset_policy(ctx, inactivity_epochs, grace_seconds)
+    }
 
-        let args = ArgBuilder::new()
-            .x25519_pubkey(encryption_pubkey)
-            .plaintext_u128(nonce)
-            .encrypted_u64(encrypted_min_out)
-            .plaintext_u64(current_output)
-            .build();
+    /// Flag an inactive vault WithdrawalsOnly (admin)
+    pub fn flag_vault_sunset(ctx: Context<FlagVaultSunset>) -> Result<()> {
+        instructions::sunset::handler_flag_sunset(ctx)
+    }
 
-        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+    /// Archive a sunset vault after its grace period (permissionless)
+    pub fn archive_vault(ctx: Context<ArchiveVault>) -> Result<()> {
+        instructions::sunset::handler_archive(ctx)
+    }
 
-        queue_computation(
-            ctx.accounts,
-            computation_offset,
-            args,
-            None,
-            vec![ConfidentialSwapCallback::callback_ix(
-                computation_offset,
-                &ctx.accounts.mxe_account,
-                &[],
-            )?],
-            1,
-            0,
-        )?;
+    /// Return a sunset or archived vault to normal operation (admin)
+    pub fn reactivate_vault(ctx: Context<ReactivateVault>) -> Result<()> {
+        instructions::sunset::handler_reactivate(ctx)
+    }
 
-        emit!(ConfidentialSwapQueued {
-            user: ctx.accounts.payer.key(),
-            vault: ctx.accounts.vault.key(),
-            computation_offset,
-            current_output,
-            timestamp: Clock::get()?.unix_timestamp,
-        });
+    /// Create a token vault's escrow account if it does not exist yet
+    pub fn prepare_vault_token_account(ctx: Context<PrepareVaultTokenAccount>) -> Result<()> {
+        instructions::vault_token::handler_prepare_token_account(ctx)
+    }
 
-        Ok(())
+    /// Enable or disable fixed-denomination mode for a vault
+    pub fn set_denominations(
+        ctx: Context<ConfigureVault>,
+        fixed_denominations: bool,
+        denominations: Vec<u64>,
+    ) -> Result<()> {
+        instructions::vault_config::handler_set_denominations(
+            ctx,
+            fixed_denominations,
+            denominations,
+        )
     }
 
-    /// Callback for confidential_swap computation
-    #[arcium_callback(encrypted_ix = "confidential_swap")]
-    pub fn confidential_swap_callback(
-        ctx: Context<ConfidentialSwapCallback>,
-        output: SignedComputationOutputs<ConfidentialSwapOutput>,
+    /// Set a vault's deposit caps and per-epoch rate limit (0 = unlimited)
+    pub fn set_deposit_limits(
+        ctx: Context<ConfigureVault>,
+        max_total_deposited: u64,
+        max_deposit_per_tx: u64,
+        max_deposit_per_epoch: u64,
     ) -> Result<()> {
-        let should_execute = match output.verify_output(
-            &ctx.accounts.cluster_account,
-            &ctx.accounts.computation_account,
-        ) {
-            Ok(ConfidentialSwapOutput { field_0 }) => field_0,
-            Err(_) => return Err(ErrorCode::AbortedComputation.into()),
-        };
+        instructions::vault_config::handler_set_deposit_limits(
+            ctx,
+            max_total_deposited,
+            max_deposit_per_tx,
+            max_deposit_per_epoch,
+        )
+    }
 
-        emit!(ConfidentialSwapResult {
-            should_execute,
-            timestamp: Clock::get()?.unix_timestamp,
-        });
+    /// Register or rotate the caller's viewing key for selective disclosure
+    pub fn register_viewing_key(
+        ctx: Context<RegisterViewingKey>,
+        viewing_pubkey: [u8; 32],
+    ) -> Result<()> {
+        instructions::viewing_key::handler_register(ctx, viewing_pubkey)
+    }
 
-        Ok(())
+    /// Remove the caller's viewing key
+    pub fn revoke_viewing_key(ctx: Context<RevokeViewingKey>) -> Result<()> {
+        instructions::viewing_key::handler_revoke(ctx)
     }
-}
 
-// ============================================================================
-// ARCIUM COMPUTATION DEFINITION ACCOUNTS
-// ============================================================================
+    /// Close one of the caller's deposit-time note backups
+    pub fn close_note_backup(ctx: Context<CloseNoteBackup>) -> Result<()> {
+        instructions::note_backup::handler_close_backup(ctx)
+    }
 
-#[init_computation_definition_accounts("init_vault", payer)]
-#[derive(Accounts)]
-pub struct InitVaultCompDef<'info> {
-    #[account(mut)]
-    pub payer: Signer<'info>,
-    #[account(mut, address = derive_mxe_pda!())]
+    /// Record the deployed version, git hash and feature bitmap in ProgramInfo
+    pub fn sync_program_info(ctx: Context<SyncProgramInfo>) -> Result<()> {
+        instructions::program_info::handler_program_info(ctx)
+    }
+
+    /// Attest the verifiable-build hash of the deployed binary (upgrade authority)
+    pub fn attest_build(ctx: Context<AttestBuild>, build_hash: [u8; 32]) -> Result<()> {
+        instructions::program_info::handler_attest_build(ctx, build_hash)
+    }
+
+    /// Grow an account written under an older layout and upgrade it to the
+    /// current version
+    pub fn migrate_account(ctx: Context<MigrateAccount>, kind: MigratableAccount) -> Result<()> {
+        instructions::migration::handler_migrate_account(ctx, kind)
+    }
+
+    /// Propose an insurance claim paying affected users new commitments
+    /// out of the vault's insurance reserve
+    pub fn propose_insurance_claim(
+        ctx: Context<ProposeInsuranceClaim>,
+        claim_id: u64,
+        evidence_hash: [u8; 32],
+        payouts: Vec<ClaimPayout>,
+    ) -> Result<()> {
+        instructions::insurance::handler_propose_insurance_claim(
+            ctx,
+            claim_id,
+            evidence_hash,
+            payouts,
+        )
+    }
+
+    /// Approve or reject an insurance claim as one of the protocol roles
+    pub fn vote_insurance_claim(
+        ctx: Context<VoteInsuranceClaim>,
+        role: Role,
+        approve: bool,
+    ) -> Result<()> {
+        instructions::insurance::handler_vote_insurance_claim(ctx, role, approve)
+    }
+
+    /// Execute an approved insurance claim (permissionless)
+    pub fn execute_insurance_claim(ctx: Context<ExecuteInsuranceClaim>) -> Result<Vec<TreeInsertion>> {
+        instructions::insurance::handler_execute_insurance_claim(ctx)
+    }
+
+    /// Split a native vault's SOL into a hot treasury for withdrawals and
+    /// a cold treasury behind the role quorum and timelock
+    pub fn initialize_treasury_policy(
+        ctx: Context<InitializeTreasuryPolicy>,
+        params: TreasurySplitParams,
+    ) -> Result<()> {
+        instructions::treasury::handler_initialize_treasury_policy(ctx, params)
+    }
+
+    /// Sweep the hot treasury's excess over its target into cold (permissionless)
+    pub fn sweep_to_cold(ctx: Context<RebalanceTreasury>) -> Result<()> {
+        instructions::treasury::handler_sweep_to_cold(ctx)
+    }
+
+    /// Refill the hot treasury from cold once it is below its threshold (permissionless)
+    pub fn refill_hot_treasury(ctx: Context<RebalanceTreasury>) -> Result<()> {
+        instructions::treasury::handler_refill_hot_treasury(ctx)
+    }
+
+    /// Propose paying SOL out of a vault's cold treasury
+    pub fn propose_cold_spend(
+        ctx: Context<ProposeColdSpend>,
+        spend_id: u64,
+        amount: u64,
+        memo_hash: [u8; 32],
+    ) -> Result<()> {
+        instructions::treasury::handler_propose_cold_spend(ctx, spend_id, amount, memo_hash)
+    }
+
+    /// Approve or reject a cold spend as one of the protocol roles
+    pub fn vote_cold_spend(ctx: Context<VoteColdSpend>, role: Role, approve: bool) -> Result<()> {
+        instructions::treasury::handler_vote_cold_spend(ctx, role, approve)
+    }
+
+    /// Execute an approved cold spend after its timelock (permissionless)
+    pub fn execute_cold_spend(ctx: Context<ExecuteColdSpend>) -> Result<()> {
+        instructions::treasury::handler_execute_cold_spend(ctx)
+    }
+
+    /// Create a per-epoch withdrawal allowance for an org's note-viewing keys
+    pub fn create_allowance_policy(
+        ctx: Context<CreateAllowancePolicy>,
+        policy_id: u64,
+        params: AllowanceParams,
+    ) -> Result<()> {
+        instructions::allowance::handler_create_allowance_policy(ctx, policy_id, params)
+    }
+
+    /// Change an allowance policy's limit, epoch or viewing keys (org admin)
+    pub fn update_allowance_policy(
+        ctx: Context<UpdateAllowancePolicy>,
+        params: AllowanceParams,
+    ) -> Result<()> {
+        instructions::allowance::handler_update_allowance_policy(ctx, params)
+    }
+
+    /// Create the devnet faucet's test token mint (`faucet` builds only)
+    #[cfg(feature = "faucet")]
+    pub fn initialize_faucet(ctx: Context<InitializeFaucet>) -> Result<()> {
+        instructions::faucet::handler_initialize_faucet(ctx)
+    }
+
+    /// Mint test tokens to the caller (`faucet` builds only)
+    #[cfg(feature = "faucet")]
+    pub fn faucet_airdrop(ctx: Context<FaucetAirdrop>, amount: u64) -> Result<()> {
+        instructions::faucet::handler_faucet_airdrop(ctx, amount)
+    }
+
+    /// Fund the faucet vault with notes whose secrets are published (`faucet` builds only)
+    #[cfg(feature = "faucet")]
+    pub fn seed_faucet_notes(
+        ctx: Context<SeedFaucetNotes>,
+        count: u8,
+        amount: u64,
+    ) -> Result<Vec<TreeInsertion>> {
+        instructions::faucet::handler_seed_faucet_notes(ctx, count, amount)
+    }
+
+    // ========================================================================
+    // PHASE 2: ARCIUM MXE CONFIDENTIAL COMPUTATION
+    // ========================================================================
+
+    /// Initialize Arcium MXE configuration (admin role)
+    pub fn initialize_arcium_config(
+        ctx: Context<InitializeArciumConfig>,
+        mxe_address: Pubkey,
+        computation_fee: u64,
+        timeout_seconds: i64,
+    ) -> Result<()> {
+        instructions::arcium_config::handler_initialize_arcium_config(
+            ctx,
+            mxe_address,
+            computation_fee,
+            timeout_seconds,
+        )
+    }
+
+    /// Set the oracle staleness and confidence limits for swaps and limit orders
+    pub fn set_price_guards(ctx: Context<SetPriceGuards>, guards: PriceGuards) -> Result<()> {
+        instructions::arcium_config::handler_set_price_guards(ctx, guards)
+    }
+
+    /// Withdraw collected Arcium computation fees (config authority)
+    pub fn withdraw_computation_fees(ctx: Context<WithdrawComputationFees>) -> Result<()> {
+        instructions::arcium_config::handler_withdraw_computation_fees(ctx)
+    }
+
+    /// Set the deepest Arcium mempool a computation type is queued into
+    /// (admin role; zero max_depth removes the limit)
+    pub fn set_mempool_limit(
+        ctx: Context<SetMempoolLimit>,
+        comp_def_offset: u32,
+        max_depth: u32,
+        reject: bool,
+    ) -> Result<()> {
+        instructions::mempool::handler_set_mempool_limit(ctx, comp_def_offset, max_depth, reject)
+    }
+
+    /// Set the oracle read for a token when its Pyth price is stale (admin role)
+    pub fn set_oracle_fallback(
+        ctx: Context<SetOracleFallback>,
+        token_mint: Pubkey,
+        source: OracleSource,
+    ) -> Result<()> {
+        instructions::oracle::handler_set_oracle_fallback(ctx, token_mint, source)
+    }
+
+    /// Register (or replace) the Pyth feed pricing a token (admin role)
+    pub fn register_price_feed(
+        ctx: Context<RegisterPriceFeed>,
+        token_mint: Pubkey,
+        feed: [u8; 32],
+        max_age: i64,
+    ) -> Result<()> {
+        instructions::oracle::handler_register_price_feed(ctx, token_mint, feed, max_age)
+    }
+
+    /// Refresh a token's cached price from its registered Pyth feed (permissionless)
+    pub fn update_cached_price(ctx: Context<UpdateCachedPrice>, token_mint: Pubkey) -> Result<()> {
+        instructions::oracle::handler_update_cached_price(ctx, token_mint)
+    }
+
+    /// Initialize the init_vault computation definition
+    pub fn init_vault_comp_def(ctx: Context<InitVaultCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    /// Initialize the process_deposit computation definition
+    pub fn init_process_deposit_comp_def(ctx: Context<InitProcessDepositCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    /// Initialize the confidential_swap computation definition
+    pub fn init_confidential_swap_comp_def(ctx: Context<InitConfidentialSwapCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    /// Initialize the evaluate_hybrid_order computation definition
+    pub fn init_evaluate_hybrid_order_comp_def(
+        ctx: Context<InitEvaluateHybridOrderCompDef>,
+    ) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    /// Initialize the evaluate_limit_order computation definition
+    pub fn init_evaluate_limit_order_comp_def(
+        ctx: Context<InitEvaluateLimitOrderCompDef>,
+    ) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    /// Initialize the evaluate_limit_order_batch computation definition
+    pub fn init_evaluate_limit_order_batch_comp_def(
+        ctx: Context<InitEvaluateLimitOrderBatchCompDef>,
+    ) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    /// Initialize the process_dca computation definition
+    pub fn init_process_dca_comp_def(ctx: Context<InitProcessDcaCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    /// Initialize the init_referral_epoch computation definition
+    pub fn init_referral_epoch_comp_def(ctx: Context<InitReferralEpochCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    /// Initialize the reveal_referral_epoch computation definition
+    pub fn init_reveal_referral_epoch_comp_def(
+        ctx: Context<InitRevealReferralEpochCompDef>,
+    ) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    /// Initialize the compute_withdrawal computation definition
+    pub fn init_compute_withdrawal_comp_def(
+        ctx: Context<InitComputeWithdrawalCompDef>,
+    ) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    /// Initialize the clear_position computation definition
+    pub fn init_clear_position_comp_def(ctx: Context<InitClearPositionCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    /// Initialize the init_position computation definition
+    pub fn init_position_comp_def(ctx: Context<InitPositionCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    /// Initialize the verify_sufficient_balance computation definition
+    pub fn init_verify_sufficient_balance_comp_def(
+        ctx: Context<InitVerifySufficientBalanceCompDef>,
+    ) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    /// Initialize the run_batch_auction computation definition
+    pub fn init_run_batch_auction_comp_def(
+        ctx: Context<InitRunBatchAuctionCompDef>,
+    ) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    /// Initialize the match_orders computation definition
+    pub fn init_match_orders_comp_def(ctx: Context<InitMatchOrdersCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    /// Initialize the process_twap computation definition
+    pub fn init_process_twap_comp_def(ctx: Context<InitProcessTwapCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    /// Initialize the evaluate_stop_order computation definition
+    pub fn init_evaluate_stop_order_comp_def(
+        ctx: Context<InitEvaluateStopOrderCompDef>,
+    ) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    /// Initialize the net_orders computation definition
+    pub fn init_net_orders_comp_def(ctx: Context<InitNetOrdersCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    /// Initialize the select_rfq_quote computation definition
+    pub fn init_select_rfq_quote_comp_def(
+        ctx: Context<InitSelectRfqQuoteCompDef>,
+    ) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    /// Create a new encrypted vault with Arcium MXE
+    pub fn create_encrypted_vault(
+        ctx: Context<CreateEncryptedVault>,
+        computation_offset: u64,
+        nonce: u128,
+    ) -> Result<()> {
+        msg!("Creating encrypted vault");
+
+        ctx.accounts.vault.bump = ctx.bumps.vault;
+        ctx.accounts.vault.token_mint = ctx.accounts.token_mint.key();
+        ctx.accounts.vault.authority = ctx.accounts.payer.key();
+        ctx.accounts.vault.nonce = nonce;
+        ctx.accounts.vault.encrypted_state = [[0u8; 32]; 3];
+        ctx.accounts.vault.version = CURRENT_ACCOUNT_VERSION;
+
+        let args = ArgBuilder::new().plaintext_u128(nonce).build();
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        instructions::mempool::check_mempool_depth(
+            &ctx.accounts.mempool_account,
+            &ctx.accounts.mempool_limits,
+            COMP_DEF_OFFSET_INIT_VAULT,
+        )?;
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![InitVaultCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[CallbackAccount {
+                    pubkey: ctx.accounts.vault.key(),
+                    is_writable: true,
+                }],
+            )?],
+            1,
+            0,
+        )?;
+
+        Ok(())
+    }
+
+    /// Callback for init_vault computation
+    #[arcium_callback(encrypted_ix = "init_vault")]
+    pub fn init_vault_callback(
+        ctx: Context<InitVaultCallback>,
+        output: SignedComputationOutputs<InitVaultOutput>,
+    ) -> Result<()> {
+        let target = ctx.accounts.vault.key();
+        let computation = ctx.accounts.computation_account.key();
+        if !first_application(&mut ctx.accounts.vault.last_computation, target, computation) {
+            return Ok(());
+        }
+
+        let o = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(InitVaultOutput { field_0 }) => field_0,
+            Err(_) => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        ctx.accounts.vault.encrypted_state = o.ciphertexts;
+        ctx.accounts.vault.nonce = o.nonce;
+
+        emit!(VaultInitialized {
+            vault: ctx.accounts.vault.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Queue an encrypted deposit via Arcium MXE
+    pub fn queue_encrypted_deposit(
+        ctx: Context<QueueEncryptedDeposit>,
+        computation_offset: u64,
+        deposit_amount: u64,
+    ) -> Result<()> {
+        msg!("Queueing encrypted deposit");
+
+        let args = ArgBuilder::new()
+            .plaintext_u64(deposit_amount)
+            .plaintext_u128(ctx.accounts.vault.nonce)
+            .account(
+                ctx.accounts.vault.key(),
+                EncryptedVaultAccount::ENCRYPTED_STATE_OFFSET as u32,
+                EncryptedVaultAccount::ENCRYPTED_STATE_SIZE as u32,
+            )
+            .build();
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        instructions::mempool::check_mempool_depth(
+            &ctx.accounts.mempool_account,
+            &ctx.accounts.mempool_limits,
+            COMP_DEF_OFFSET_PROCESS_DEPOSIT,
+        )?;
+
+        // A deposit computation cannot be cancelled or expired, so its fee is
+        // collected as soon as it is queued
+        let computation_fee = ctx.accounts.arcium_config.computation_fee;
+        charge_computation_fee(
+            ctx.accounts.payer.to_account_info(),
+            ctx.accounts.computation_fee_escrow.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            computation_fee,
+        )?;
+        let escrow = &mut ctx.accounts.computation_fee_escrow;
+        escrow.bump = ctx.bumps.computation_fee_escrow;
+        escrow.collect(computation_fee)?;
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![ProcessDepositCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[CallbackAccount {
+                    pubkey: ctx.accounts.vault.key(),
+                    is_writable: true,
+                }],
+            )?],
+            1,
+            0,
+        )?;
+
+        emit!(EncryptedDepositQueued {
+            user: ctx.accounts.payer.key(),
+            vault: ctx.accounts.vault.key(),
+            computation_offset,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Callback for process_deposit computation
+    #[arcium_callback(encrypted_ix = "process_deposit")]
+    pub fn process_deposit_callback(
+        ctx: Context<ProcessDepositCallback>,
+        output: SignedComputationOutputs<ProcessDepositOutput>,
+    ) -> Result<()> {
+        let target = ctx.accounts.vault.key();
+        let computation = ctx.accounts.computation_account.key();
+        if !first_application(&mut ctx.accounts.vault.last_computation, target, computation) {
+            return Ok(());
+        }
+
+        let o = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(ProcessDepositOutput { field_0 }) => field_0,
+            Err(_) => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        ctx.accounts.vault.encrypted_state = o.ciphertexts;
+        ctx.accounts.vault.nonce = o.nonce;
+
+        emit!(DepositProcessed {
+            vault: ctx.accounts.vault.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Queue a confidential swap via Arcium MXE
+    ///
+    /// Creates an `EncryptedSwapRequest` tracking the computation. `keeper_fee`
+    /// lamports are escrowed in the request and refunded if it is cancelled.
+    /// `encrypted_params` is Enc<Shared, SwapParams>: [min_out, referrer code,
+    /// max_impact_bps]. The referral is credited inside the MXE to the current
+    /// epoch's totals, and the MXE reveals the largest child order the
+    /// route's `quote.liquidity_depth` can absorb within the impact threshold.
+    /// `dest_vault` is the encrypted vault of the token bought; both vault
+    /// states are carried through the circuit.
+    pub fn queue_confidential_swap(
+        ctx: Context<QueueConfidentialSwap>,
+        computation_offset: u64,
+        encrypted_params: [[u8; 32]; 3],
+        encryption_pubkey: [u8; 32],
+        nonce: u128,
+        quote: SwapQuote,
+        keeper_fee: u64,
+    ) -> Result<()> {
+        msg!("Queueing confidential swap");
+
+        let now = Clock::get()?.unix_timestamp;
+        let vault_key = ctx.accounts.vault.key();
+        let dest_vault_key = ctx.accounts.dest_vault.key();
+
+        let referral_epoch = &ctx.accounts.referral_epoch;
+        require!(
+            referral_epoch.initialized && referral_epoch.epoch == state::referral_epoch_at(now),
+            errors::ZyncxError::InvalidReferralEpoch
+        );
+        let referral_fee = ctx.accounts.referral_registry.fee_per_referral;
+        let oracle_price = state::load_order_price(
+            &ctx.accounts.price_feed,
+            &ctx.accounts.price_feed_registry,
+            state::fallback_oracle(&ctx.accounts.oracle_fallback, &ctx.accounts.fallback_price_feed),
+            &ctx.accounts.vault.token_mint,
+            &ctx.accounts.arcium_config.price_guards(),
+        )?;
+
+        let swap_request = &mut ctx.accounts.swap_request;
+        swap_request.bump = ctx.bumps.swap_request;
+        swap_request.user = ctx.accounts.payer.key();
+        swap_request.source_vault = vault_key;
+        swap_request.dest_vault = dest_vault_key;
+        swap_request.computation_offset = computation_offset;
+        swap_request.encrypted_bounds = encrypted_params;
+        swap_request.bounds_nonce = nonce;
+        swap_request.client_pubkey = encryption_pubkey;
+        swap_request.amount = 0;
+        swap_request.nullifier = [0u8; 32];
+        swap_request.new_commitment = [0u8; 32];
+        swap_request.status = SwapRequestStatus::Pending;
+        swap_request.queued_at = now;
+        swap_request.completed_at = 0;
+        swap_request.encrypted_result = [[0u8; 32]; 2];
+        swap_request.result_nonce = 0;
+        swap_request.keeper_escrow = keeper_fee;
+        swap_request.should_execute = false;
+        swap_request.expires_at = now.saturating_add(state::SWAP_REQUEST_TTL_SECONDS);
+        swap_request.latest_computation_offset = computation_offset;
+        swap_request.retries = 0;
+        swap_request.price_feed = ctx.accounts.price_feed.key();
+        swap_request.oracle_price = oracle_price;
+        swap_request.execution_price = 0;
+        swap_request.liquidity_depth = quote.liquidity_depth;
+        swap_request.max_child_amount = 0;
+        swap_request.failure = None;
+        let computation_fee = ctx.accounts.arcium_config.computation_fee;
+        swap_request.computation_fee = computation_fee;
+
+        charge_computation_fee(
+            ctx.accounts.payer.to_account_info(),
+            ctx.accounts.computation_fee_escrow.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            computation_fee,
+        )?;
+        let escrow = &mut ctx.accounts.computation_fee_escrow;
+        escrow.bump = ctx.bumps.computation_fee_escrow;
+        escrow.hold(computation_fee)?;
+
+        let user_request_index = &mut ctx.accounts.user_request_index;
+        user_request_index
+            .ensure_initialized(ctx.accounts.payer.key(), ctx.bumps.user_request_index);
+        user_request_index.record(ctx.accounts.swap_request.key(), SwapRequestStatus::Pending);
+
+        if keeper_fee > 0 {
+            anchor_lang::system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    anchor_lang::system_program::Transfer {
+                        from: ctx.accounts.payer.to_account_info(),
+                        to: ctx.accounts.swap_request.to_account_info(),
+                    },
+                ),
+                keeper_fee,
+            )?;
+        }
+
+        let args = confidential_swap_args(
+            &ctx.accounts.swap_request,
+            &quote,
+            referral_fee,
+            &ctx.accounts.referral_epoch,
+            &ctx.accounts.vault,
+            &ctx.accounts.dest_vault,
+        );
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        instructions::mempool::check_mempool_depth(
+            &ctx.accounts.mempool_account,
+            &ctx.accounts.mempool_limits,
+            COMP_DEF_OFFSET_CONFIDENTIAL_SWAP,
+        )?;
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![ConfidentialSwapCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[
+                    CallbackAccount {
+                        pubkey: ctx.accounts.swap_request.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.referral_epoch.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: vault_key,
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: dest_vault_key,
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.computation_fee_escrow.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.user_request_index.key(),
+                        is_writable: true,
+                    },
+                ],
+            )?],
+            1,
+            0,
+        )?;
+
+        emit!(ConfidentialSwapQueued {
+            user: ctx.accounts.payer.key(),
+            vault: vault_key,
+            dest_vault: dest_vault_key,
+            swap_request: ctx.accounts.swap_request.key(),
+            computation_offset,
+            current_output: quote.current_output,
+            liquidity_depth: quote.liquidity_depth,
+            oracle_price,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    /// Callback for confidential_swap computation
+    #[arcium_callback(encrypted_ix = "confidential_swap")]
+    pub fn confidential_swap_callback(
+        ctx: Context<ConfidentialSwapCallback>,
+        output: SignedComputationOutputs<ConfidentialSwapOutput>,
+    ) -> Result<()> {
+        let target = ctx.accounts.swap_request.key();
+        let computation = ctx.accounts.computation_account.key();
+        if !first_application(&mut ctx.accounts.swap_request.last_computation, target, computation) {
+            return Ok(());
+        }
+
+        let now = Clock::get()?.unix_timestamp;
+        let swap_request = &mut ctx.accounts.swap_request;
+
+        // The computation ran, so its fee is earned whatever the outcome.
+        // A request cancelled or expired in flight was already refunded.
+        let computation_fee = std::mem::take(&mut swap_request.computation_fee);
+        ctx.accounts.computation_fee_escrow.forward(computation_fee)?;
+
+        let failure = output_failure(&output);
+        let (should_execute, max_child_amount, referrals, source_state, dest_state) = match output
+            .verify_output(&ctx.accounts.cluster_account, &ctx.accounts.computation_account)
+        {
+            Ok(ConfidentialSwapOutput {
+                field_0:
+                    ConfidentialSwapOutputStruct0 {
+                        field_0,
+                        field_1,
+                        field_2,
+                        field_3,
+                        field_4,
+                    },
+            }) => (field_0, field_1, field_2, field_3, field_4),
+            Err(_) => {
+                // Record the abort so the user can retry_computation
+                if swap_request.status == SwapRequestStatus::Pending {
+                    swap_request.status = SwapRequestStatus::Failed;
+                    swap_request.failure = Some(failure);
+                }
+                swap_request.completed_at = now;
+                ctx.accounts.user_request_index.update(target, swap_request.status);
+
+                emit!(ConfidentialSwapFailed {
+                    swap_request: swap_request.key(),
+                    computation_offset: swap_request.latest_computation_offset,
+                    failure,
+                    timestamp: now,
+                });
+
+                return Ok(());
+            }
+        };
+
+        // A request cancelled or expired while the computation was in flight
+        // only records completion; the decision and its referral accrual are
+        // discarded.
+        if matches!(
+            swap_request.status,
+            SwapRequestStatus::Cancelled | SwapRequestStatus::Expired
+        ) {
+            swap_request.completed_at = now;
+
+            emit!(ConfidentialSwapSkipped {
+                swap_request: swap_request.key(),
+                timestamp: now,
+            });
+
+            return Ok(());
+        }
+
+        swap_request.status = SwapRequestStatus::Completed;
+        swap_request.should_execute = should_execute;
+        swap_request.max_child_amount = max_child_amount;
+        swap_request.completed_at = now;
+        ctx.accounts.user_request_index.update(target, SwapRequestStatus::Completed);
+
+        // Totals are frozen once revealed; a swap landing after the reveal
+        // forfeits its referral accrual.
+        let referral_epoch = &mut ctx.accounts.referral_epoch;
+        if !referral_epoch.revealed {
+            referral_epoch.encrypted_totals = referrals.ciphertexts;
+            referral_epoch.nonce = referrals.nonce;
+        }
+
+        let source_vault = &mut ctx.accounts.source_vault;
+        source_vault.encrypted_state = source_state.ciphertexts;
+        source_vault.nonce = source_state.nonce;
+        source_vault.last_computation = computation;
+        let dest_vault = &mut ctx.accounts.dest_vault;
+        dest_vault.encrypted_state = dest_state.ciphertexts;
+        dest_vault.nonce = dest_state.nonce;
+        dest_vault.last_computation = computation;
+
+        emit!(ConfidentialSwapResult {
+            swap_request: swap_request.key(),
+            should_execute,
+            max_child_amount,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    /// Re-queue an aborted confidential swap under a new computation offset,
+    /// reusing the ciphertexts, nonce and client pubkey stored in the request
+    pub fn retry_computation(
+        ctx: Context<RetryComputation>,
+        computation_offset: u64,
+        quote: SwapQuote,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+
+        let referral_epoch = &ctx.accounts.referral_epoch;
+        require!(
+            referral_epoch.initialized && referral_epoch.epoch == state::referral_epoch_at(now),
+            errors::ZyncxError::InvalidReferralEpoch
+        );
+        let referral_fee = ctx.accounts.referral_registry.fee_per_referral;
+        let oracle_price = state::load_order_price(
+            &ctx.accounts.price_feed,
+            &ctx.accounts.price_feed_registry,
+            state::fallback_oracle(&ctx.accounts.oracle_fallback, &ctx.accounts.fallback_price_feed),
+            &ctx.accounts.source_vault.token_mint,
+            &ctx.accounts.arcium_config.price_guards(),
+        )?;
+
+        let swap_request = &mut ctx.accounts.swap_request;
+        require!(
+            swap_request.status == SwapRequestStatus::Failed,
+            errors::ZyncxError::InvalidComputationStatus
+        );
+        require!(
+            swap_request.retries < state::MAX_SWAP_REQUEST_RETRIES,
+            errors::ZyncxError::InvalidComputationStatus
+        );
+
+        swap_request.status = SwapRequestStatus::Pending;
+        swap_request.queued_at = now;
+        swap_request.completed_at = 0;
+        swap_request.expires_at = now.saturating_add(state::SWAP_REQUEST_TTL_SECONDS);
+        swap_request.latest_computation_offset = computation_offset;
+        swap_request.retries += 1;
+        swap_request.price_feed = ctx.accounts.price_feed.key();
+        swap_request.oracle_price = oracle_price;
+        swap_request.liquidity_depth = quote.liquidity_depth;
+        swap_request.failure = None;
+        // Each run of the computation pays the fee; the aborted one's was
+        // forwarded by its callback
+        let computation_fee = ctx.accounts.arcium_config.computation_fee;
+        swap_request.computation_fee = computation_fee;
+
+        charge_computation_fee(
+            ctx.accounts.payer.to_account_info(),
+            ctx.accounts.computation_fee_escrow.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            computation_fee,
+        )?;
+        ctx.accounts.computation_fee_escrow.hold(computation_fee)?;
+        ctx.accounts
+            .user_request_index
+            .record(ctx.accounts.swap_request.key(), SwapRequestStatus::Pending);
+
+        let args = confidential_swap_args(
+            &ctx.accounts.swap_request,
+            &quote,
+            referral_fee,
+            &ctx.accounts.referral_epoch,
+            &ctx.accounts.source_vault,
+            &ctx.accounts.dest_vault,
+        );
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        instructions::mempool::check_mempool_depth(
+            &ctx.accounts.mempool_account,
+            &ctx.accounts.mempool_limits,
+            COMP_DEF_OFFSET_CONFIDENTIAL_SWAP,
+        )?;
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![ConfidentialSwapCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[
+                    CallbackAccount {
+                        pubkey: ctx.accounts.swap_request.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.referral_epoch.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.source_vault.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.dest_vault.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.computation_fee_escrow.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.user_request_index.key(),
+                        is_writable: true,
+                    },
+                ],
+            )?],
+            1,
+            0,
+        )?;
+
+        emit!(ConfidentialSwapRetried {
+            swap_request: ctx.accounts.swap_request.key(),
+            computation_offset,
+            retries: ctx.accounts.swap_request.retries,
+            current_output: quote.current_output,
+            liquidity_depth: quote.liquidity_depth,
+            oracle_price,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    /// Settle a confidential swap the MXE approved: spend the note, swap via
+    /// Jupiter and credit the output as a note in the destination vault
+    pub fn execute_approved_swap<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ExecuteApprovedSwap<'info>>,
+        swap_param: SwapParam,
+        nullifier: [u8; 32],
+        new_commitment: [u8; 32],
+        precommitment: [u8; 32],
+        proof: Vec<u8>,
+        swap_data: Vec<u8>,
+    ) -> Result<TreeInsertion> {
+        instructions::swap::handler_execute_approved(
+            ctx,
+            swap_param,
+            nullifier,
+            new_commitment,
+            precommitment,
+            proof,
+            swap_data,
+        )
+    }
+
+    /// Cancel a pending confidential swap and refund its keeper escrow
+    pub fn cancel_swap_request(ctx: Context<CancelSwapRequest>) -> Result<()> {
+        instructions::swap_request::handler_cancel(ctx)
+    }
+
+    /// Expire a confidential swap still pending past its deadline (permissionless)
+    pub fn expire_swap_request(ctx: Context<ExpireSwapRequest>) -> Result<()> {
+        instructions::swap_request::handler_expire(ctx)
+    }
+
+    /// Close a finished swap request and return its rent to the user
+    pub fn close_swap_request(ctx: Context<CloseSwapRequest>) -> Result<()> {
+        instructions::swap_request::handler_close(ctx)
+    }
+
+    /// Place a hybrid TWAP/limit order with encrypted limit price and duration
+    pub fn place_hybrid_order(
+        ctx: Context<PlaceHybridOrder>,
+        order_id: u64,
+        encrypted_params: [[u8; 32]; 3],
+        params_nonce: u128,
+        client_pubkey: [u8; 32],
+    ) -> Result<()> {
+        instructions::hybrid_order::handler_place_hybrid_order(
+            ctx,
+            order_id,
+            encrypted_params,
+            params_nonce,
+            client_pubkey,
+        )
+    }
+
+    /// Crank a hybrid order: queue an MXE evaluation against the order's
+    /// oracle price. Permissionless; the cranker pays the computation.
+    pub fn queue_hybrid_order_evaluation(
+        ctx: Context<QueueHybridOrderEvaluation>,
+        computation_offset: u64,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+
+        let current_price = state::load_order_price(
+            &ctx.accounts.price_feed,
+            &ctx.accounts.price_feed_registry,
+            state::fallback_oracle(&ctx.accounts.oracle_fallback, &ctx.accounts.fallback_price_feed),
+            &ctx.accounts.source_vault.asset_mint,
+            &PriceGuards::DEFAULT,
+        )?;
+
+        let order = &mut ctx.accounts.hybrid_order;
+        require!(
+            order.status == HybridOrderStatus::Active,
+            errors::ZyncxError::InvalidComputationStatus
+        );
+        order.status = HybridOrderStatus::Evaluating;
+        let elapsed_seconds = now.saturating_sub(order.created_at).max(0) as u64;
+
+        let args = ArgBuilder::new()
+            .x25519_pubkey(order.client_pubkey)
+            .plaintext_u128(order.params_nonce)
+            .encrypted_u64(order.encrypted_params[0])
+            .encrypted_u64(order.encrypted_params[1])
+            .encrypted_u64(order.encrypted_params[2])
+            .plaintext_u64(current_price)
+            .plaintext_u64(elapsed_seconds)
+            .build();
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        instructions::mempool::check_mempool_depth(
+            &ctx.accounts.mempool_account,
+            &ctx.accounts.mempool_limits,
+            COMP_DEF_OFFSET_EVALUATE_HYBRID_ORDER,
+        )?;
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![EvaluateHybridOrderCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[CallbackAccount {
+                    pubkey: ctx.accounts.hybrid_order.key(),
+                    is_writable: true,
+                }],
+            )?],
+            1,
+            0,
+        )?;
+
+        emit!(HybridOrderEvaluationQueued {
+            order: ctx.accounts.hybrid_order.key(),
+            cranker: ctx.accounts.payer.key(),
+            computation_offset,
+            current_price,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    /// Callback for evaluate_hybrid_order computation
+    #[arcium_callback(encrypted_ix = "evaluate_hybrid_order")]
+    pub fn evaluate_hybrid_order_callback(
+        ctx: Context<EvaluateHybridOrderCallback>,
+        output: SignedComputationOutputs<EvaluateHybridOrderOutput>,
+    ) -> Result<()> {
+        let target = ctx.accounts.hybrid_order.key();
+        let computation = ctx.accounts.computation_account.key();
+        if !first_application(&mut ctx.accounts.hybrid_order.last_computation, target, computation) {
+            return Ok(());
+        }
+
+        let triggered = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(EvaluateHybridOrderOutput { field_0 }) => field_0,
+            Err(_) => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        let now = Clock::get()?.unix_timestamp;
+        let order = &mut ctx.accounts.hybrid_order;
+
+        order.status = if triggered {
+            HybridOrderStatus::Triggered
+        } else {
+            HybridOrderStatus::Active
+        };
+        order.last_evaluated_at = now;
+        order.evaluations = order.evaluations.saturating_add(1);
+
+        emit!(HybridOrderEvaluated {
+            order: order.key(),
+            triggered,
+            evaluations: order.evaluations,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    /// Cancel a hybrid order that is not mid-evaluation and reclaim its rent
+    pub fn cancel_hybrid_order(ctx: Context<CancelHybridOrder>) -> Result<()> {
+        instructions::hybrid_order::handler_cancel_hybrid_order(ctx)
+    }
+
+    /// Place a limit order with an encrypted target price, amount and side
+    pub fn place_limit_order(
+        ctx: Context<PlaceLimitOrder>,
+        order_id: u64,
+        encrypted_params: [[u8; 32]; 3],
+        params_nonce: u128,
+        client_pubkey: [u8; 32],
+        expires_at: i64,
+    ) -> Result<()> {
+        instructions::limit_order::handler_place_limit_order(
+            ctx,
+            order_id,
+            encrypted_params,
+            params_nonce,
+            client_pubkey,
+            expires_at,
+        )
+    }
+
+    /// Queue an MXE evaluation of a limit order against its oracle price.
+    /// Permissionless; an order past its expiry is marked Expired instead.
+    pub fn queue_limit_order_evaluation(
+        ctx: Context<QueueLimitOrderEvaluation>,
+        computation_offset: u64,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+
+        let order = &mut ctx.accounts.limit_order;
+        require!(
+            order.status == LimitOrderStatus::Active,
+            errors::ZyncxError::InvalidComputationStatus
+        );
+
+        if now >= order.expires_at {
+            order.status = LimitOrderStatus::Expired;
+
+            emit!(LimitOrderExpired {
+                order: order.key(),
+                timestamp: now,
+            });
+
+            return Ok(());
+        }
+
+        let current_price = state::load_order_price(
+            &ctx.accounts.price_feed,
+            &ctx.accounts.price_feed_registry,
+            state::fallback_oracle(&ctx.accounts.oracle_fallback, &ctx.accounts.fallback_price_feed),
+            &ctx.accounts.source_vault.asset_mint,
+            &ctx.accounts.arcium_config.price_guards(),
+        )?;
+        order.status = LimitOrderStatus::Evaluating;
+
+        let args = ArgBuilder::new()
+            .x25519_pubkey(order.client_pubkey)
+            .plaintext_u128(order.params_nonce)
+            .encrypted_u64(order.encrypted_params[0])
+            .encrypted_u64(order.encrypted_params[1])
+            .encrypted_u64(order.encrypted_params[2])
+            .plaintext_u64(current_price)
+            .build();
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        instructions::mempool::check_mempool_depth(
+            &ctx.accounts.mempool_account,
+            &ctx.accounts.mempool_limits,
+            COMP_DEF_OFFSET_EVALUATE_LIMIT_ORDER,
+        )?;
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![EvaluateLimitOrderCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[CallbackAccount {
+                    pubkey: ctx.accounts.limit_order.key(),
+                    is_writable: true,
+                }],
+            )?],
+            1,
+            0,
+        )?;
+
+        emit!(LimitOrderEvaluationQueued {
+            order: ctx.accounts.limit_order.key(),
+            cranker: ctx.accounts.payer.key(),
+            computation_offset,
+            current_price,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    /// Callback for evaluate_limit_order computation
+    #[arcium_callback(encrypted_ix = "evaluate_limit_order")]
+    pub fn evaluate_limit_order_callback(
+        ctx: Context<EvaluateLimitOrderCallback>,
+        output: SignedComputationOutputs<EvaluateLimitOrderOutput>,
+    ) -> Result<()> {
+        let target = ctx.accounts.limit_order.key();
+        let computation = ctx.accounts.computation_account.key();
+        if !first_application(&mut ctx.accounts.limit_order.last_computation, target, computation) {
+            return Ok(());
+        }
+
+        let triggered = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(EvaluateLimitOrderOutput { field_0 }) => field_0,
+            Err(_) => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        let now = Clock::get()?.unix_timestamp;
+        let order = &mut ctx.accounts.limit_order;
+
+        order.status = if triggered {
+            LimitOrderStatus::Executed
+        } else {
+            LimitOrderStatus::Active
+        };
+        order.last_evaluated_at = now;
+
+        emit!(LimitOrderEvaluated {
+            order: order.key(),
+            triggered,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    /// Close a limit order that is not mid-evaluation and refund its rent
+    pub fn cancel_limit_order(ctx: Context<CancelLimitOrder>) -> Result<()> {
+        instructions::limit_order::handler_cancel_limit_order(ctx)
+    }
+
+    /// Permissionless keeper crank over limit orders passed as remaining
+    /// accounts (writable, up to `LIMIT_ORDER_CRANK_BATCH`, all on
+    /// `price_feed`). Expired orders are marked Expired; due orders are
+    /// evaluated together in one MXE computation. The cranker earns
+    /// `CRANK_TIP_LAMPORTS` per evaluated order out of accrued SOL fees.
+    pub fn crank_limit_orders(
+        ctx: Context<CrankLimitOrders>,
+        computation_offset: u64,
+    ) -> Result<()> {
+        instructions::limit_order::handler_crank_limit_orders(ctx, computation_offset)
+    }
+
+    /// Callback for evaluate_limit_order_batch computation
+    #[arcium_callback(encrypted_ix = "evaluate_limit_order_batch")]
+    pub fn evaluate_limit_order_batch_callback(
+        ctx: Context<EvaluateLimitOrderBatchCallback>,
+        output: SignedComputationOutputs<EvaluateLimitOrderBatchOutput>,
+    ) -> Result<()> {
+        let triggered_mask = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(EvaluateLimitOrderBatchOutput { field_0 }) => field_0,
+            Err(_) => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        let now = Clock::get()?.unix_timestamp;
+        let computation = ctx.accounts.computation_account.key();
+
+        for (i, info) in ctx.remaining_accounts.iter().enumerate() {
+            require_keys_eq!(*info.owner, crate::ID, errors::ZyncxError::Unauthorized);
+            let mut order = {
+                let data = info.try_borrow_data()?;
+                EncryptedLimitOrder::try_deserialize(&mut &data[..])?
+            };
+            if !first_application(&mut order.last_computation, info.key(), computation) {
+                continue;
+            }
+            if order.status != LimitOrderStatus::Evaluating {
+                continue;
+            }
+
+            let triggered = triggered_mask & (1 << i) != 0;
+            order.status = if triggered {
+                LimitOrderStatus::Executed
+            } else {
+                LimitOrderStatus::Active
+            };
+            order.last_evaluated_at = now;
+            order.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+
+            emit!(LimitOrderEvaluated {
+                order: info.key(),
+                triggered,
+                timestamp: now,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Permissionless: propose that two active limit orders on opposite sides
+    /// of the same market cross each other. The MXE compares their hidden
+    /// limits and, on a match, settles them against each other internally
+    /// without routing through a DEX. Only whether they crossed is public.
+    pub fn propose_match(ctx: Context<ProposeMatch>, computation_offset: u64) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+
+        let order_a = &ctx.accounts.order_a;
+        let order_b = &ctx.accounts.order_b;
+        require!(
+            order_a.status == LimitOrderStatus::Active && order_b.status == LimitOrderStatus::Active,
+            errors::ZyncxError::InvalidComputationStatus
+        );
+        require!(
+            now < order_a.expires_at && now < order_b.expires_at,
+            errors::ZyncxError::ComputationExpired
+        );
+        require!(
+            order_a.source_vault == order_b.dest_vault
+                && order_a.dest_vault == order_b.source_vault
+                && order_a.price_feed == order_b.price_feed,
+            errors::ZyncxError::OrdersNotMatchable
+        );
+
+        let args = ArgBuilder::new()
+            .x25519_pubkey(order_a.client_pubkey)
+            .plaintext_u128(order_a.params_nonce)
+            .encrypted_u64(order_a.encrypted_params[0])
+            .encrypted_u64(order_a.encrypted_params[1])
+            .encrypted_u64(order_a.encrypted_params[2])
+            .x25519_pubkey(order_b.client_pubkey)
+            .plaintext_u128(order_b.params_nonce)
+            .encrypted_u64(order_b.encrypted_params[0])
+            .encrypted_u64(order_b.encrypted_params[1])
+            .encrypted_u64(order_b.encrypted_params[2])
+            .build();
+
+        ctx.accounts.order_a.status = LimitOrderStatus::Matching;
+        ctx.accounts.order_b.status = LimitOrderStatus::Matching;
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        instructions::mempool::check_mempool_depth(
+            &ctx.accounts.mempool_account,
+            &ctx.accounts.mempool_limits,
+            COMP_DEF_OFFSET_MATCH_ORDERS,
+        )?;
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![MatchOrdersCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[
+                    CallbackAccount {
+                        pubkey: ctx.accounts.order_a.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.order_b.key(),
+                        is_writable: true,
+                    },
+                ],
+            )?],
+            1,
+            0,
+        )?;
+
+        emit!(MatchProposed {
+            order_a: ctx.accounts.order_a.key(),
+            order_b: ctx.accounts.order_b.key(),
+            proposer: ctx.accounts.payer.key(),
+            computation_offset,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    /// Callback for match_orders computation. Crossed orders are settled
+    /// against each other with their encrypted fills; otherwise (or if the
+    /// MXE aborted) both return to Active.
+    #[arcium_callback(encrypted_ix = "match_orders")]
+    pub fn match_orders_callback(
+        ctx: Context<MatchOrdersCallback>,
+        output: SignedComputationOutputs<MatchOrdersOutput>,
+    ) -> Result<()> {
+        let target = ctx.accounts.order_a.key();
+        let computation = ctx.accounts.computation_account.key();
+        if !first_application(&mut ctx.accounts.order_a.last_computation, target, computation) {
+            return Ok(());
+        }
+        ctx.accounts.order_b.last_computation = computation;
+
+        let result = output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        let key_a = ctx.accounts.order_a.key();
+        let key_b = ctx.accounts.order_b.key();
+        let order_a = &mut ctx.accounts.order_a;
+        let order_b = &mut ctx.accounts.order_b;
+        if order_a.status != LimitOrderStatus::Matching || order_b.status != LimitOrderStatus::Matching {
+            return Ok(());
+        }
+
+        let aborted = result.is_err();
+        match result {
+            Ok(MatchOrdersOutput { field_0 }) if field_0.field_0 => {
+                order_a.status = LimitOrderStatus::Matched;
+                order_a.matched_with = key_b;
+                order_a.encrypted_fill = field_0.field_1.ciphertexts[0];
+                order_a.fill_nonce = field_0.field_1.nonce;
+
+                order_b.status = LimitOrderStatus::Matched;
+                order_b.matched_with = key_a;
+                order_b.encrypted_fill = field_0.field_2.ciphertexts[0];
+                order_b.fill_nonce = field_0.field_2.nonce;
+
+                emit!(LimitOrdersMatched {
+                    order_a: key_a,
+                    order_b: key_b,
+                    encrypted_fill_a: order_a.encrypted_fill,
+                    fill_nonce_a: order_a.fill_nonce,
+                    encrypted_fill_b: order_b.encrypted_fill,
+                    fill_nonce_b: order_b.fill_nonce,
+                    timestamp: now,
+                });
+            }
+            _ => {
+                order_a.status = LimitOrderStatus::Active;
+                order_b.status = LimitOrderStatus::Active;
+
+                emit!(LimitOrderMatchRejected {
+                    order_a: key_a,
+                    order_b: key_b,
+                    aborted,
+                    timestamp: now,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Submit a sealed order (encrypted amount, limit price and side) to the
+    /// batch auction of its market
+    pub fn submit_sealed_order(
+        ctx: Context<SubmitSealedOrder>,
+        order_id: u64,
+        encrypted_params: [[u8; 32]; 3],
+        params_nonce: u128,
+        client_pubkey: [u8; 32],
+    ) -> Result<()> {
+        instructions::auction::handler_submit_sealed_order(
+            ctx,
+            order_id,
+            encrypted_params,
+            params_nonce,
+            client_pubkey,
+        )
+    }
+
+    /// Close a sealed order that is not in a running auction and refund its rent
+    pub fn cancel_sealed_order(ctx: Context<CancelSealedOrder>) -> Result<()> {
+        instructions::auction::handler_cancel_sealed_order(ctx)
+    }
+
+    /// Permissionless: clear up to `AUCTION_BATCH_SIZE` open sealed orders,
+    /// passed as writable remaining accounts, at the oracle price of their
+    /// shared market. The MXE matches crossing buys against sells and returns
+    /// each owner's fill encrypted to them; only matched volume is public.
+    pub fn run_auction(ctx: Context<RunAuction>, computation_offset: u64) -> Result<()> {
+        let order_count = ctx.remaining_accounts.len();
+        require!(
+            order_count > 0 && order_count <= state::AUCTION_BATCH_SIZE,
+            errors::ZyncxError::InvalidAuctionBatch
+        );
+
+        let auction_key = ctx.accounts.auction.key();
+        let price_feed = ctx.accounts.price_feed.key();
+        let base_vault = ctx.accounts.base_vault.key();
+        let mut orders: Vec<(Pubkey, EncryptedSealedOrder)> = Vec::with_capacity(order_count);
+
+        for info in ctx.remaining_accounts.iter() {
+            require_keys_eq!(*info.owner, crate::ID, errors::ZyncxError::Unauthorized);
+            require!(info.is_writable, errors::ZyncxError::InvalidAuctionBatch);
+            let mut order = {
+                let data = info.try_borrow_data()?;
+                EncryptedSealedOrder::try_deserialize(&mut &data[..])?
+            };
+            require!(
+                order.status == SealedOrderStatus::Open,
+                errors::ZyncxError::SealedOrderNotOpen
+            );
+            require!(
+                order.price_feed == price_feed
+                    && order.base_vault == base_vault
+                    && orders.iter().all(|(key, other)| {
+                        *key != info.key()
+                            && other.base_vault == order.base_vault
+                            && other.quote_vault == order.quote_vault
+                    }),
+                errors::ZyncxError::InvalidAuctionBatch
+            );
+
+            order.status = SealedOrderStatus::Auctioning;
+            order.auction = auction_key;
+            order.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+            orders.push((info.key(), order));
+        }
+
+        let clearing_price = state::load_order_price(
+            &ctx.accounts.price_feed,
+            &ctx.accounts.price_feed_registry,
+            state::fallback_oracle(&ctx.accounts.oracle_fallback, &ctx.accounts.fallback_price_feed),
+            &ctx.accounts.base_vault.asset_mint,
+            &PriceGuards::DEFAULT,
+        )?;
+        let now = Clock::get()?.unix_timestamp;
+
+        let auction = &mut ctx.accounts.auction;
+        auction.bump = ctx.bumps.auction;
+        auction.computation_offset = computation_offset;
+        auction.base_vault = orders[0].1.base_vault;
+        auction.quote_vault = orders[0].1.quote_vault;
+        auction.clearing_price = clearing_price;
+        auction.order_count = order_count as u8;
+        auction.matched_volume = 0;
+        auction.settled = false;
+        auction.created_at = now;
+        auction.settled_at = 0;
+
+        // Short batches repeat the last order; the circuit ignores slots
+        // at or past order_count
+        let mut args = ArgBuilder::new();
+        for slot in 0..state::AUCTION_BATCH_SIZE {
+            let (_, order) = &orders[slot.min(order_count - 1)];
+            args = args
+                .x25519_pubkey(order.client_pubkey)
+                .plaintext_u128(order.params_nonce)
+                .encrypted_u64(order.encrypted_params[0])
+                .encrypted_u64(order.encrypted_params[1])
+                .encrypted_u64(order.encrypted_params[2]);
+        }
+        let args = args
+            .plaintext_u64(clearing_price)
+            .plaintext_u64(order_count as u64)
+            .build();
+
+        let mut callback_accounts = vec![CallbackAccount {
+            pubkey: auction_key,
+            is_writable: true,
+        }];
+        callback_accounts.extend(orders.iter().map(|(key, _)| CallbackAccount {
+            pubkey: *key,
+            is_writable: true,
+        }));
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        instructions::mempool::check_mempool_depth(
+            &ctx.accounts.mempool_account,
+            &ctx.accounts.mempool_limits,
+            COMP_DEF_OFFSET_RUN_BATCH_AUCTION,
+        )?;
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![RunBatchAuctionCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &callback_accounts,
+            )?],
+            1,
+            0,
+        )?;
+
+        emit!(AuctionQueued {
+            auction: auction_key,
+            base_vault: ctx.accounts.auction.base_vault,
+            quote_vault: ctx.accounts.auction.quote_vault,
+            clearing_price,
+            order_count: order_count as u8,
+            computation_offset,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    /// Callback for run_batch_auction computation. Writes each order's
+    /// encrypted fill; an aborted auction returns its orders to Open.
+    #[arcium_callback(encrypted_ix = "run_batch_auction")]
+    pub fn run_batch_auction_callback(
+        ctx: Context<RunBatchAuctionCallback>,
+        output: SignedComputationOutputs<RunBatchAuctionOutput>,
+    ) -> Result<()> {
+        let result = output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        let auction = &mut ctx.accounts.auction;
+        if auction.settled {
+            emit!(CallbackReplayed {
+                target: auction.key(),
+                computation: ctx.accounts.computation_account.key(),
+            });
+            return Ok(());
+        }
+        auction.settled = true;
+        auction.settled_at = now;
+
+        let fills = match result {
+            Ok(RunBatchAuctionOutput { field_0 }) => {
+                auction.matched_volume = field_0.field_0;
+                Some([field_0.field_1, field_0.field_2, field_0.field_3, field_0.field_4])
+            }
+            Err(_) => None,
+        };
+
+        for (i, info) in ctx.remaining_accounts.iter().enumerate() {
+            require_keys_eq!(*info.owner, crate::ID, errors::ZyncxError::Unauthorized);
+            let mut order = {
+                let data = info.try_borrow_data()?;
+                EncryptedSealedOrder::try_deserialize(&mut &data[..])?
+            };
+            if order.status != SealedOrderStatus::Auctioning || order.auction != auction.key() {
+                continue;
+            }
+
+            match &fills {
+                Some(fills) if i < state::AUCTION_BATCH_SIZE => {
+                    order.status = SealedOrderStatus::Settled;
+                    order.encrypted_fill = fills[i].ciphertexts[0];
+                    order.fill_nonce = fills[i].nonce;
+
+                    emit!(SealedOrderSettled {
+                        order: info.key(),
+                        auction: auction.key(),
+                        encrypted_fill: order.encrypted_fill,
+                        fill_nonce: order.fill_nonce,
+                    });
+                }
+                _ => {
+                    order.status = SealedOrderStatus::Open;
+                    order.auction = Pubkey::default();
+                }
+            }
+            order.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+        }
+
+        emit!(AuctionSettled {
+            auction: auction.key(),
+            clearing_price: auction.clearing_price,
+            matched_volume: auction.matched_volume,
+            aborted: fills.is_none(),
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    /// Register a market maker for RFQs, or toggle whether it may quote
+    pub fn register_market_maker(
+        ctx: Context<RegisterMarketMaker>,
+        maker: Pubkey,
+        active: bool,
+    ) -> Result<()> {
+        instructions::rfq::handler_register_market_maker(ctx, maker, active)
+    }
+
+    /// Escrow market maker inventory that RFQ quotes settle against
+    pub fn deposit_inventory(ctx: Context<DepositInventory>, amount: u64) -> Result<()> {
+        instructions::rfq::handler_deposit_inventory(ctx, amount)
+    }
+
+    /// Withdraw market maker inventory while no quotes are open
+    pub fn withdraw_inventory(ctx: Context<WithdrawInventory>, amount: u64) -> Result<()> {
+        instructions::rfq::handler_withdraw_inventory(ctx, amount)
+    }
+
+    /// Post an RFQ with a bucketed size and an encrypted minimum output
+    pub fn create_rfq(ctx: Context<CreateRfq>, rfq_id: u64, params: RfqParams) -> Result<()> {
+        instructions::rfq::handler_create_rfq(ctx, rfq_id, params)
+    }
+
+    /// Submit a registered market maker's encrypted quote for an open RFQ
+    pub fn submit_rfq_quote(
+        ctx: Context<SubmitRfqQuote>,
+        encrypted_amount: [u8; 32],
+        quote_nonce: u128,
+        maker_pubkey: [u8; 32],
+    ) -> Result<()> {
+        instructions::rfq::handler_submit_rfq_quote(
+            ctx,
+            encrypted_amount,
+            quote_nonce,
+            maker_pubkey,
+        )
+    }
+
+    /// Permissionless: once quoting has closed (or every slot is taken),
+    /// have the MXE pick the best quote. The request's quotes are passed as
+    /// remaining accounts in slot order.
+    pub fn select_rfq_quote(ctx: Context<SelectRfqQuote>, computation_offset: u64) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let request = &ctx.accounts.rfq_request;
+        require!(request.status == RfqStatus::Open, errors::ZyncxError::RfqNotOpen);
+        let quote_count = request.quotes.len();
+        require!(
+            quote_count > 0
+                && ctx.remaining_accounts.len() == quote_count
+                && (now >= request.quote_deadline || quote_count == state::RFQ_MAX_QUOTES),
+            errors::ZyncxError::InvalidRfqQuotes
+        );
+
+        let request_key = request.key();
+        let mut quotes: Vec<RfqQuote> = Vec::with_capacity(quote_count);
+        for (info, maker) in ctx.remaining_accounts.iter().zip(request.quotes.iter()) {
+            require_keys_eq!(*info.owner, crate::ID, errors::ZyncxError::Unauthorized);
+            let quote = {
+                let data = info.try_borrow_data()?;
+                RfqQuote::try_deserialize(&mut &data[..])?
+            };
+            require!(
+                quote.rfq_request == request_key && quote.maker == *maker,
+                errors::ZyncxError::InvalidRfqQuotes
+            );
+            quotes.push(quote);
+        }
+
+        // Short lists repeat the last quote; the circuit ignores slots at or
+        // past quote_count
+        let mut args = ArgBuilder::new()
+            .x25519_pubkey(request.client_pubkey)
+            .plaintext_u128(request.params_nonce)
+            .encrypted_u64(request.encrypted_min_out);
+        for slot in 0..state::RFQ_MAX_QUOTES {
+            let quote = &quotes[slot.min(quote_count - 1)];
+            args = args
+                .x25519_pubkey(quote.maker_pubkey)
+                .plaintext_u128(quote.quote_nonce)
+                .encrypted_u64(quote.encrypted_amount);
+        }
+        let args = args.plaintext_u64(quote_count as u64).build();
+
+        ctx.accounts.rfq_request.status = RfqStatus::Selecting;
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        instructions::mempool::check_mempool_depth(
+            &ctx.accounts.mempool_account,
+            &ctx.accounts.mempool_limits,
+            COMP_DEF_OFFSET_SELECT_RFQ_QUOTE,
+        )?;
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![SelectRfqQuoteCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[CallbackAccount {
+                    pubkey: request_key,
+                    is_writable: true,
+                }],
+            )?],
+            1,
+            0,
+        )?;
+
+        emit!(RfqSelectionQueued {
+            rfq_request: request_key,
+            quote_count: quote_count as u8,
+            computation_offset,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    /// Callback for select_rfq_quote computation. Records the winner and
+    /// its revealed amount; an aborted selection reopens the request so it
+    /// can be retried.
+    #[arcium_callback(encrypted_ix = "select_rfq_quote")]
+    pub fn select_rfq_quote_callback(
+        ctx: Context<SelectRfqQuoteCallback>,
+        output: SignedComputationOutputs<SelectRfqQuoteOutput>,
+    ) -> Result<()> {
+        let target = ctx.accounts.rfq_request.key();
+        let computation = ctx.accounts.computation_account.key();
+        if !first_application(&mut ctx.accounts.rfq_request.last_computation, target, computation) {
+            return Ok(());
+        }
+
+        let request = &mut ctx.accounts.rfq_request;
+        if request.status != RfqStatus::Selecting {
+            return Ok(());
+        }
+
+        let (filled, slot, amount) = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(SelectRfqQuoteOutput {
+                field_0:
+                    SelectRfqQuoteOutputStruct0 {
+                        field_0,
+                        field_1,
+                        field_2,
+                    },
+            }) => (field_0, field_1, field_2),
+            Err(_) => {
+                request.status = RfqStatus::Open;
+                return Ok(());
+            }
+        };
+
+        match request.quotes.get(slot as usize) {
+            Some(winner) if filled => {
+                request.winner = *winner;
+                request.winning_amount = amount;
+                request.status = RfqStatus::Selected;
+            }
+            _ => request.status = RfqStatus::NoFill,
+        }
+
+        emit!(RfqQuoteSelected {
+            rfq_request: target,
+            winner: request.winner,
+            winning_amount: request.winning_amount,
+            filled: request.status == RfqStatus::Selected,
+        });
+
+        Ok(())
+    }
+
+    /// Settle a selected RFQ into the destination vault
+    pub fn settle_rfq(ctx: Context<SettleRfq>) -> Result<()> {
+        instructions::rfq::handler_settle_rfq(ctx)
+    }
+
+    /// Cancel an RFQ and refund its escrow
+    pub fn cancel_rfq(ctx: Context<CancelRfq>) -> Result<()> {
+        instructions::rfq::handler_cancel_rfq(ctx)
+    }
+
+    /// Close a market maker's quote once its RFQ is finished
+    pub fn close_rfq_quote(ctx: Context<CloseRfqQuote>) -> Result<()> {
+        instructions::rfq::handler_close_rfq_quote(ctx)
+    }
+
+    /// Create a DCA schedule with encrypted amount, swap count and min price
+    pub fn create_dca(
+        ctx: Context<CreateDca>,
+        dca_id: u64,
+        encrypted_params: [[u8; 32]; 3],
+        params_nonce: u128,
+        client_pubkey: [u8; 32],
+        interval_seconds: u64,
+        budget: u64,
+    ) -> Result<()> {
+        instructions::dca::handler_create_dca(
+            ctx,
+            dca_id,
+            encrypted_params,
+            params_nonce,
+            client_pubkey,
+            interval_seconds,
+            budget,
+        )
+    }
+
+    /// Crank a due DCA interval: queue `process_dca` against the source
+    /// price. The approved amount is swapped by `settle_dca_swap`.
+    pub fn execute_dca(ctx: Context<ExecuteDca>, computation_offset: u64) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+
+        let dca = &mut ctx.accounts.dca_config;
+        require!(
+            dca.status == DCAStatus::Active && !dca.computation_pending,
+            errors::ZyncxError::InvalidComputationStatus
+        );
+        require!(dca.pending_amount == 0, errors::ZyncxError::InvalidComputationStatus);
+        require!(now >= dca.next_execution_at, errors::ZyncxError::DcaNotDue);
+
+        let current_price = state::load_order_price(
+            &ctx.accounts.price_feed,
+            &ctx.accounts.price_feed_registry,
+            state::fallback_oracle(&ctx.accounts.oracle_fallback, &ctx.accounts.fallback_price_feed),
+            &ctx.accounts.source_vault.asset_mint,
+            &PriceGuards::DEFAULT,
+        )?;
+
+        dca.computation_pending = true;
+        dca.next_execution_at = now.saturating_add(dca.interval_seconds as i64);
+
+        let args = ArgBuilder::new()
+            .x25519_pubkey(dca.client_pubkey)
+            .plaintext_u128(dca.params_nonce)
+            .encrypted_u64(dca.encrypted_params[0])
+            .encrypted_u64(dca.encrypted_params[1])
+            .encrypted_u64(dca.encrypted_params[2])
+            .plaintext_u64(current_price)
+            .build();
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        instructions::mempool::check_mempool_depth(
+            &ctx.accounts.mempool_account,
+            &ctx.accounts.mempool_limits,
+            COMP_DEF_OFFSET_PROCESS_DCA,
+        )?;
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![ProcessDcaCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[CallbackAccount {
+                    pubkey: ctx.accounts.dca_config.key(),
+                    is_writable: true,
+                }],
+            )?],
+            1,
+            0,
+        )?;
+
+        emit!(DcaExecutionQueued {
+            dca: ctx.accounts.dca_config.key(),
+            cranker: ctx.accounts.payer.key(),
+            computation_offset,
+            current_price,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    /// Callback for process_dca computation
+    #[arcium_callback(encrypted_ix = "process_dca")]
+    pub fn process_dca_callback(
+        ctx: Context<ProcessDcaCallback>,
+        output: SignedComputationOutputs<ProcessDcaOutput>,
+    ) -> Result<()> {
+        let target = ctx.accounts.dca_config.key();
+        let computation = ctx.accounts.computation_account.key();
+        if !first_application(&mut ctx.accounts.dca_config.last_computation, target, computation) {
+            return Ok(());
+        }
+
+        let (params, amount, completed) = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(ProcessDcaOutput {
+                field_0: ProcessDcaOutputStruct0 { field_0, field_1, field_2 },
+            }) => (field_0, field_1, field_2),
+            Err(_) => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        let dca = &mut ctx.accounts.dca_config;
+        dca.encrypted_params = params.ciphertexts;
+        dca.params_nonce = params.nonce;
+        dca.pending_amount = amount;
+        dca.computation_pending = false;
+        if completed {
+            dca.status = DCAStatus::Completed;
+        }
+
+        emit!(DcaProcessed {
+            dca: dca.key(),
+            amount,
+            completed,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Swap the amount approved by the last DCA run via Jupiter
+    pub fn settle_dca_swap<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SettleDcaSwap<'info>>,
+        swap_data: Vec<u8>,
+    ) -> Result<()> {
+        instructions::dca::handler_settle_dca_swap(ctx, swap_data)
+    }
+
+    /// Permissionless: run a due DCA interval against the same user's
+    /// opposing limit order. The MXE fills the limit order from the
+    /// interval's amount internally, with no DEX trade, and only the
+    /// residual is left for `settle_dca_swap`. Used instead of `execute_dca`
+    /// for that interval.
+    pub fn net_dca_with_limit_order(
+        ctx: Context<NetDcaWithLimitOrder>,
+        computation_offset: u64,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+
+        let dca = &ctx.accounts.dca_config;
+        let order = &ctx.accounts.limit_order;
+        require!(
+            dca.status == DCAStatus::Active && !dca.computation_pending,
+            errors::ZyncxError::InvalidComputationStatus
+        );
+        require!(dca.pending_amount == 0, errors::ZyncxError::InvalidComputationStatus);
+        require!(now >= dca.next_execution_at, errors::ZyncxError::DcaNotDue);
+        require!(
+            order.status == LimitOrderStatus::Active,
+            errors::ZyncxError::InvalidComputationStatus
+        );
+        require!(now < order.expires_at, errors::ZyncxError::ComputationExpired);
+        require!(
+            order.user == dca.user
+                && order.source_vault == dca.dest_vault
+                && order.dest_vault == dca.source_vault
+                && order.price_feed == dca.price_feed,
+            errors::ZyncxError::OrdersNotMatchable
+        );
+
+        let current_price = state::load_order_price(
+            &ctx.accounts.price_feed,
+            &ctx.accounts.price_feed_registry,
+            state::fallback_oracle(&ctx.accounts.oracle_fallback, &ctx.accounts.fallback_price_feed),
+            &ctx.accounts.source_vault.asset_mint,
+            &PriceGuards::DEFAULT,
+        )?;
+
+        let args = ArgBuilder::new()
+            .x25519_pubkey(dca.client_pubkey)
+            .plaintext_u128(dca.params_nonce)
+            .encrypted_u64(dca.encrypted_params[0])
+            .encrypted_u64(dca.encrypted_params[1])
+            .encrypted_u64(dca.encrypted_params[2])
+            .x25519_pubkey(order.client_pubkey)
+            .plaintext_u128(order.params_nonce)
+            .encrypted_u64(order.encrypted_params[0])
+            .encrypted_u64(order.encrypted_params[1])
+            .encrypted_u64(order.encrypted_params[2])
+            .plaintext_u64(current_price)
+            .build();
+
+        let dca = &mut ctx.accounts.dca_config;
+        dca.computation_pending = true;
+        dca.next_execution_at = now.saturating_add(dca.interval_seconds as i64);
+        ctx.accounts.limit_order.status = LimitOrderStatus::Matching;
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        instructions::mempool::check_mempool_depth(
+            &ctx.accounts.mempool_account,
+            &ctx.accounts.mempool_limits,
+            COMP_DEF_OFFSET_NET_ORDERS,
+        )?;
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![NetOrdersCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[
+                    CallbackAccount {
+                        pubkey: ctx.accounts.dca_config.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.limit_order.key(),
+                        is_writable: true,
+                    },
+                ],
+            )?],
+            1,
+            0,
+        )?;
+
+        emit!(DcaNettingQueued {
+            dca: ctx.accounts.dca_config.key(),
+            limit_order: ctx.accounts.limit_order.key(),
+            cranker: ctx.accounts.payer.key(),
+            computation_offset,
+            current_price,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    /// Callback for net_orders computation. The DCA keeps only the residual
+    /// as its pending swap and the limit order returns to Active with its
+    /// reduced amount; on abort both are released unchanged.
+    #[arcium_callback(encrypted_ix = "net_orders")]
+    pub fn net_orders_callback(
+        ctx: Context<NetOrdersCallback>,
+        output: SignedComputationOutputs<NetOrdersOutput>,
+    ) -> Result<()> {
+        let target = ctx.accounts.dca_config.key();
+        let computation = ctx.accounts.computation_account.key();
+        if !first_application(&mut ctx.accounts.dca_config.last_computation, target, computation) {
+            return Ok(());
+        }
+        ctx.accounts.limit_order.last_computation = computation;
+
+        let now = Clock::get()?.unix_timestamp;
+        let dca_key = ctx.accounts.dca_config.key();
+        let order_key = ctx.accounts.limit_order.key();
+        let dca = &mut ctx.accounts.dca_config;
+        let order = &mut ctx.accounts.limit_order;
+        dca.computation_pending = false;
+        if order.status == LimitOrderStatus::Matching {
+            order.status = LimitOrderStatus::Active;
+        }
+
+        let (dca_params, order_params, netted, residual, completed) = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(NetOrdersOutput {
+                field_0:
+                    NetOrdersOutputStruct0 {
+                        field_0,
+                        field_1,
+                        field_2,
+                        field_3,
+                        field_4,
+                    },
+            }) => (field_0, field_1, field_2, field_3, field_4),
+            Err(_) => return Ok(()),
+        };
+
+        dca.encrypted_params = dca_params.ciphertexts;
+        dca.params_nonce = dca_params.nonce;
+        dca.pending_amount = residual;
+        dca.netted_total = dca.netted_total.saturating_add(netted);
+        if completed {
+            dca.status = DCAStatus::Completed;
+        }
+
+        order.encrypted_params = order_params.ciphertexts;
+        order.params_nonce = order_params.nonce;
+        order.last_evaluated_at = now;
+
+        emit!(DcaNetted {
+            dca: dca_key,
+            limit_order: order_key,
+            netted,
+            residual,
+            completed,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    /// Pause an active DCA schedule
+    pub fn pause_dca(ctx: Context<UpdateDca>) -> Result<()> {
+        instructions::dca::handler_pause_dca(ctx)
+    }
+
+    /// Resume a paused DCA schedule
+    pub fn resume_dca(ctx: Context<UpdateDca>) -> Result<()> {
+        instructions::dca::handler_resume_dca(ctx)
+    }
+
+    /// Replace a DCA schedule's encrypted params with a fresh encryption
+    pub fn update_dca_params(
+        ctx: Context<UpdateDca>,
+        encrypted_params: [[u8; 32]; 3],
+        params_nonce: u128,
+        client_pubkey: [u8; 32],
+    ) -> Result<()> {
+        instructions::dca::handler_update_dca_params(
+            ctx,
+            encrypted_params,
+            params_nonce,
+            client_pubkey,
+        )
+    }
+
+    /// Cancel a DCA schedule and refund its escrow and rent
+    pub fn cancel_dca(ctx: Context<CancelDca>) -> Result<()> {
+        instructions::dca::handler_cancel_dca(ctx)
+    }
+
+    /// Create a confidential TWAP execution with encrypted total amount and
+    /// max participation rate
+    pub fn create_twap(
+        ctx: Context<CreateTwap>,
+        twap_id: u64,
+        encrypted_params: [[u8; 32]; 3],
+        params_nonce: u128,
+        client_pubkey: [u8; 32],
+        interval_seconds: u64,
+        budget: u64,
+    ) -> Result<()> {
+        instructions::twap::handler_create_twap(
+            ctx,
+            twap_id,
+            encrypted_params,
+            params_nonce,
+            client_pubkey,
+            interval_seconds,
+            budget,
+        )
+    }
+
+    /// Crank a due TWAP interval: queue `process_twap` to size the next
+    /// slice. The approved slice is swapped by `settle_twap_slice`.
+    pub fn execute_twap(ctx: Context<ExecuteTwap>, computation_offset: u64) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+
+        let twap = &mut ctx.accounts.twap_config;
+        require!(
+            twap.status == TWAPStatus::Active && !twap.computation_pending,
+            errors::ZyncxError::InvalidComputationStatus
+        );
+        require!(twap.pending_amount == 0, errors::ZyncxError::InvalidComputationStatus);
+        require!(now >= twap.next_execution_at, errors::ZyncxError::TwapNotDue);
+
+        twap.computation_pending = true;
+        twap.next_execution_at = now.saturating_add(twap.interval_seconds as i64);
+
+        let args = ArgBuilder::new()
+            .x25519_pubkey(twap.client_pubkey)
+            .plaintext_u128(twap.params_nonce)
+            .encrypted_u64(twap.encrypted_params[0])
+            .encrypted_u64(twap.encrypted_params[1])
+            .encrypted_u64(twap.encrypted_params[2])
+            .build();
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        instructions::mempool::check_mempool_depth(
+            &ctx.accounts.mempool_account,
+            &ctx.accounts.mempool_limits,
+            COMP_DEF_OFFSET_PROCESS_TWAP,
+        )?;
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![ProcessTwapCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[CallbackAccount {
+                    pubkey: ctx.accounts.twap_config.key(),
+                    is_writable: true,
+                }],
+            )?],
+            1,
+            0,
+        )?;
+
+        emit!(TwapExecutionQueued {
+            twap: ctx.accounts.twap_config.key(),
+            cranker: ctx.accounts.payer.key(),
+            computation_offset,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    /// Callback for process_twap computation. An aborted run skips the
+    /// interval and leaves the encrypted params untouched.
+    #[arcium_callback(encrypted_ix = "process_twap")]
+    pub fn process_twap_callback(
+        ctx: Context<ProcessTwapCallback>,
+        output: SignedComputationOutputs<ProcessTwapOutput>,
+    ) -> Result<()> {
+        let target = ctx.accounts.twap_config.key();
+        let computation = ctx.accounts.computation_account.key();
+        if !first_application(&mut ctx.accounts.twap_config.last_computation, target, computation) {
+            return Ok(());
+        }
+
+        let result = output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        );
+
+        let twap = &mut ctx.accounts.twap_config;
+        twap.computation_pending = false;
+
+        let (amount, completed, aborted) = match result {
+            Ok(ProcessTwapOutput {
+                field_0: ProcessTwapOutputStruct0 { field_0, field_1, field_2 },
+            }) => {
+                twap.encrypted_params = field_0.ciphertexts;
+                twap.params_nonce = field_0.nonce;
+                twap.pending_amount = field_1;
+                if field_2 {
+                    twap.status = TWAPStatus::Completed;
+                }
+                (field_1, field_2, false)
+            }
+            Err(_) => (0, false, true),
+        };
+
+        emit!(TwapProcessed {
+            twap: twap.key(),
+            amount,
+            completed,
+            aborted,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Swap the slice approved by the last TWAP run via Jupiter
+    pub fn settle_twap_slice<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SettleTwapSlice<'info>>,
+        swap_data: Vec<u8>,
+    ) -> Result<()> {
+        instructions::twap::handler_settle_twap_slice(ctx, swap_data)
+    }
+
+    /// Cancel a TWAP execution and refund its escrow and rent
+    pub fn cancel_twap(ctx: Context<CancelTwap>) -> Result<()> {
+        instructions::twap::handler_cancel_twap(ctx)
+    }
+
+    /// Place a stop order with an encrypted stop price, amount and side,
+    /// escrowing `budget` lamports to sell once it fires
+    pub fn place_stop_order(
+        ctx: Context<PlaceStopOrder>,
+        order_id: u64,
+        encrypted_params: [[u8; 32]; 3],
+        params_nonce: u128,
+        client_pubkey: [u8; 32],
+        expires_at: i64,
+        budget: u64,
+    ) -> Result<()> {
+        instructions::stop_order::handler_place_stop_order(
+            ctx,
+            order_id,
+            encrypted_params,
+            params_nonce,
+            client_pubkey,
+            expires_at,
+            budget,
+        )
+    }
+
+    /// Queue an MXE evaluation of a stop order against its oracle price.
+    /// Permissionless; an order past its expiry is marked Expired instead.
+    pub fn queue_stop_order_evaluation(
+        ctx: Context<QueueStopOrderEvaluation>,
+        computation_offset: u64,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+
+        let order = &mut ctx.accounts.stop_order;
+        require!(
+            order.status == StopOrderStatus::Active,
+            errors::ZyncxError::InvalidComputationStatus
+        );
+
+        if now >= order.expires_at {
+            order.status = StopOrderStatus::Expired;
+
+            emit!(StopOrderExpired {
+                order: order.key(),
+                timestamp: now,
+            });
+
+            return Ok(());
+        }
+
+        let current_price = state::load_order_price(
+            &ctx.accounts.price_feed,
+            &ctx.accounts.price_feed_registry,
+            state::fallback_oracle(&ctx.accounts.oracle_fallback, &ctx.accounts.fallback_price_feed),
+            &ctx.accounts.source_vault.asset_mint,
+            &PriceGuards::DEFAULT,
+        )?;
+        order.status = StopOrderStatus::Evaluating;
+        order.evaluation_price = current_price;
+
+        let args = ArgBuilder::new()
+            .x25519_pubkey(order.client_pubkey)
+            .plaintext_u128(order.params_nonce)
+            .encrypted_u64(order.encrypted_params[0])
+            .encrypted_u64(order.encrypted_params[1])
+            .encrypted_u64(order.encrypted_params[2])
+            .plaintext_u64(current_price)
+            .build();
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        instructions::mempool::check_mempool_depth(
+            &ctx.accounts.mempool_account,
+            &ctx.accounts.mempool_limits,
+            COMP_DEF_OFFSET_EVALUATE_STOP_ORDER,
+        )?;
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![EvaluateStopOrderCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[CallbackAccount {
+                    pubkey: ctx.accounts.stop_order.key(),
+                    is_writable: true,
+                }],
+            )?],
+            1,
+            0,
+        )?;
+
+        emit!(StopOrderEvaluationQueued {
+            order: ctx.accounts.stop_order.key(),
+            cranker: ctx.accounts.payer.key(),
+            computation_offset,
+            current_price,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    /// Callback for evaluate_stop_order computation. An aborted run returns
+    /// the order to Active so the next evaluation can retry.
+    #[arcium_callback(encrypted_ix = "evaluate_stop_order")]
+    pub fn evaluate_stop_order_callback(
+        ctx: Context<EvaluateStopOrderCallback>,
+        output: SignedComputationOutputs<EvaluateStopOrderOutput>,
+    ) -> Result<()> {
+        let target = ctx.accounts.stop_order.key();
+        let computation = ctx.accounts.computation_account.key();
+        if !first_application(&mut ctx.accounts.stop_order.last_computation, target, computation) {
+            return Ok(());
+        }
+
+        let result = output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        let order = &mut ctx.accounts.stop_order;
+
+        let (triggered, aborted) = match result {
+            Ok(EvaluateStopOrderOutput {
+                field_0: EvaluateStopOrderOutputStruct0 { field_0, field_1 },
+            }) => {
+                if field_0 {
+                    order.status = StopOrderStatus::Triggered;
+                    order.trigger_amount = field_1;
+                    order.trigger_price = order.evaluation_price;
+                } else {
+                    order.status = StopOrderStatus::Active;
+                }
+                order.last_evaluated_at = now;
+                (field_0, false)
+            }
+            Err(_) => {
+                order.status = StopOrderStatus::Active;
+                (false, true)
+            }
+        };
+
+        emit!(StopOrderEvaluated {
+            order: order.key(),
+            triggered,
+            trigger_amount: order.trigger_amount,
+            aborted,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    /// Sell a triggered stop order's amount via Jupiter
+    pub fn execute_stop_order<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ExecuteStopOrder<'info>>,
+        swap_data: Vec<u8>,
+    ) -> Result<()> {
+        instructions::stop_order::handler_execute_stop_order(ctx, swap_data)
+    }
+
+    /// Cancel a stop order and refund its escrow and rent
+    pub fn cancel_stop_order(ctx: Context<CancelStopOrder>) -> Result<()> {
+        instructions::stop_order::handler_cancel_stop_order(ctx)
+    }
+
+    /// Create the referral registry (fee manager)
+    pub fn initialize_referral_registry(
+        ctx: Context<InitializeReferralRegistry>,
+        fee_per_referral: u64,
+    ) -> Result<()> {
+        instructions::referral::handler_initialize_referral_registry(ctx, fee_per_referral)
+    }
+
+    /// Assign a referral slot's payout wallet (fee manager)
+    pub fn set_referrer(
+        ctx: Context<UpdateReferralRegistry>,
+        slot: u8,
+        referrer: Pubkey,
+    ) -> Result<()> {
+        instructions::referral::handler_set_referrer(ctx, slot, referrer)
+    }
+
+    /// Set the lamports accrued per executed referred swap (fee manager)
+    pub fn set_referral_fee(
+        ctx: Context<UpdateReferralRegistry>,
+        fee_per_referral: u64,
+    ) -> Result<()> {
+        instructions::referral::handler_set_referral_fee(ctx, fee_per_referral)
+    }
+
+    /// Open a referral epoch with MXE-encrypted zero totals (permissionless)
+    pub fn open_referral_epoch(
+        ctx: Context<OpenReferralEpoch>,
+        computation_offset: u64,
+        epoch: u64,
+        nonce: u128,
+    ) -> Result<()> {
+        let current = state::referral_epoch_at(Clock::get()?.unix_timestamp);
+        require!(
+            epoch == current || epoch == current + 1,
+            errors::ZyncxError::InvalidReferralEpoch
+        );
+
+        let referral_epoch = &mut ctx.accounts.referral_epoch;
+        referral_epoch.bump = ctx.bumps.referral_epoch;
+        referral_epoch.epoch = epoch;
+        referral_epoch.nonce = nonce;
+        referral_epoch.encrypted_totals = [[0u8; 32]; state::REFERRAL_SLOTS];
+        referral_epoch.initialized = false;
+        referral_epoch.revealed = false;
+        referral_epoch.totals = [0; state::REFERRAL_SLOTS];
+        referral_epoch.claimed = [false; state::REFERRAL_SLOTS];
+
+        let args = ArgBuilder::new().plaintext_u128(nonce).build();
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        instructions::mempool::check_mempool_depth(
+            &ctx.accounts.mempool_account,
+            &ctx.accounts.mempool_limits,
+            COMP_DEF_OFFSET_INIT_REFERRAL_EPOCH,
+        )?;
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![InitReferralEpochCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[CallbackAccount {
+                    pubkey: ctx.accounts.referral_epoch.key(),
+                    is_writable: true,
+                }],
+            )?],
+            1,
+            0,
+        )?;
+
+        Ok(())
+    }
+
+    /// Callback for init_referral_epoch computation
+    #[arcium_callback(encrypted_ix = "init_referral_epoch")]
+    pub fn init_referral_epoch_callback(
+        ctx: Context<InitReferralEpochCallback>,
+        output: SignedComputationOutputs<InitReferralEpochOutput>,
+    ) -> Result<()> {
+        let target = ctx.accounts.referral_epoch.key();
+        let computation = ctx.accounts.computation_account.key();
+        if !first_application(&mut ctx.accounts.referral_epoch.last_computation, target, computation) {
+            return Ok(());
+        }
+
+        let o = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(InitReferralEpochOutput { field_0 }) => field_0,
+            Err(_) => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        let referral_epoch = &mut ctx.accounts.referral_epoch;
+        referral_epoch.encrypted_totals = o.ciphertexts;
+        referral_epoch.nonce = o.nonce;
+        referral_epoch.initialized = true;
+
+        emit!(ReferralEpochOpened {
+            epoch: referral_epoch.epoch,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Reveal an ended referral epoch's aggregate totals (permissionless)
+    pub fn reveal_referral_epoch(
+        ctx: Context<RevealReferralEpoch>,
+        computation_offset: u64,
+    ) -> Result<()> {
+        let current = state::referral_epoch_at(Clock::get()?.unix_timestamp);
+        let referral_epoch = &ctx.accounts.referral_epoch;
+        require!(
+            referral_epoch.initialized
+                && !referral_epoch.revealed
+                && referral_epoch.epoch < current,
+            errors::ZyncxError::InvalidReferralEpoch
+        );
+
+        let args = ArgBuilder::new()
+            .plaintext_u128(referral_epoch.nonce)
+            .account(
+                referral_epoch.key(),
+                ReferralEpoch::ENCRYPTED_TOTALS_OFFSET as u32,
+                ReferralEpoch::ENCRYPTED_TOTALS_SIZE as u32,
+            )
+            .build();
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        instructions::mempool::check_mempool_depth(
+            &ctx.accounts.mempool_account,
+            &ctx.accounts.mempool_limits,
+            COMP_DEF_OFFSET_REVEAL_REFERRAL_EPOCH,
+        )?;
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![RevealReferralEpochCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[CallbackAccount {
+                    pubkey: ctx.accounts.referral_epoch.key(),
+                    is_writable: true,
+                }],
+            )?],
+            1,
+            0,
+        )?;
+
+        Ok(())
+    }
+
+    /// Callback for reveal_referral_epoch computation
+    #[arcium_callback(encrypted_ix = "reveal_referral_epoch")]
+    pub fn reveal_referral_epoch_callback(
+        ctx: Context<RevealReferralEpochCallback>,
+        output: SignedComputationOutputs<RevealReferralEpochOutput>,
+    ) -> Result<()> {
+        let target = ctx.accounts.referral_epoch.key();
+        let computation = ctx.accounts.computation_account.key();
+        if !first_application(&mut ctx.accounts.referral_epoch.last_computation, target, computation) {
+            return Ok(());
+        }
+
+        let totals = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(RevealReferralEpochOutput { field_0 }) => field_0,
+            Err(_) => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        let referral_epoch = &mut ctx.accounts.referral_epoch;
+        referral_epoch.totals = totals;
+        referral_epoch.revealed = true;
+
+        emit!(ReferralEpochRevealed {
+            epoch: referral_epoch.epoch,
+            totals,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Claim a referrer's revealed fees for an ended epoch
+    pub fn claim_referral_fees(ctx: Context<ClaimReferralFees>, slot: u8) -> Result<()> {
+        instructions::referral::handler_claim_referral_fees(ctx, slot)
+    }
+
+    /// Claim a referrer's accrued swap referral rewards in a vault
+    pub fn claim_swap_referral_fees(ctx: Context<ClaimSwapReferralFees>) -> Result<()> {
+        instructions::referral::handler_claim_swap_referral_fees(ctx)
+    }
+
+    /// Open the caller's encrypted position in a vault. The position stays
+    /// inactive until the MXE writes its encrypted zero state in the callback.
+    pub fn create_encrypted_position(
+        ctx: Context<CreateEncryptedPosition>,
+        computation_offset: u64,
+        nonce: u128,
+    ) -> Result<()> {
+        msg!("Creating encrypted position");
+
+        let position = &mut ctx.accounts.position;
+        position.bump = ctx.bumps.position;
+        position.owner = ctx.accounts.payer.key();
+        position.vault = ctx.accounts.vault.key();
+        position.position_state = [[0u8; 32]; 2];
+        position.nonce = nonce;
+        position.created_at = Clock::get()?.unix_timestamp;
+        position.is_active = false;
+        position.version = CURRENT_ACCOUNT_VERSION;
+
+        let args = ArgBuilder::new().plaintext_u128(nonce).build();
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        instructions::mempool::check_mempool_depth(
+            &ctx.accounts.mempool_account,
+            &ctx.accounts.mempool_limits,
+            COMP_DEF_OFFSET_INIT_POSITION,
+        )?;
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![InitPositionCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[CallbackAccount {
+                    pubkey: ctx.accounts.position.key(),
+                    is_writable: true,
+                }],
+            )?],
+            1,
+            0,
+        )?;
+
+        Ok(())
+    }
+
+    /// Callback for init_position computation
+    #[arcium_callback(encrypted_ix = "init_position")]
+    pub fn init_position_callback(
+        ctx: Context<InitPositionCallback>,
+        output: SignedComputationOutputs<InitPositionOutput>,
+    ) -> Result<()> {
+        let target = ctx.accounts.position.key();
+        let computation = ctx.accounts.computation_account.key();
+        if !first_application(&mut ctx.accounts.position.last_computation, target, computation) {
+            return Ok(());
+        }
+
+        let o = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(InitPositionOutput { field_0 }) => field_0,
+            Err(_) => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        let position = &mut ctx.accounts.position;
+        position.position_state = o.ciphertexts;
+        position.nonce = o.nonce;
+        position.is_active = true;
+
+        emit!(PositionInitialized {
+            position: position.key(),
+            owner: position.owner,
+            vault: position.vault,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Privately learn a position's withdrawable balance. The MXE re-encrypts
+    /// it to `client_pubkey`; only the owner can decrypt the stored quote.
+    pub fn queue_compute_withdrawal(
+        ctx: Context<QueueComputeWithdrawal>,
+        computation_offset: u64,
+        client_pubkey: [u8; 32],
+        client_nonce: u128,
+    ) -> Result<()> {
+        let position = &ctx.accounts.position;
+        require!(position.is_active, errors::ZyncxError::InactivePosition);
+
+        let args = ArgBuilder::new()
+            .x25519_pubkey(client_pubkey)
+            .plaintext_u128(client_nonce)
+            .plaintext_u128(position.nonce)
+            .account(
+                position.key(),
+                EncryptedUserPosition::ENCRYPTED_STATE_OFFSET as u32,
+                EncryptedUserPosition::ENCRYPTED_STATE_SIZE as u32,
+            )
+            .build();
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        instructions::mempool::check_mempool_depth(
+            &ctx.accounts.mempool_account,
+            &ctx.accounts.mempool_limits,
+            COMP_DEF_OFFSET_COMPUTE_WITHDRAWAL,
+        )?;
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![ComputeWithdrawalCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[CallbackAccount {
+                    pubkey: ctx.accounts.position.key(),
+                    is_writable: true,
+                }],
+            )?],
+            1,
+            0,
+        )?;
+
+        Ok(())
+    }
+
+    /// Callback for compute_withdrawal computation
+    #[arcium_callback(encrypted_ix = "compute_withdrawal")]
+    pub fn compute_withdrawal_callback(
+        ctx: Context<ComputeWithdrawalCallback>,
+        output: SignedComputationOutputs<ComputeWithdrawalOutput>,
+    ) -> Result<()> {
+        let target = ctx.accounts.position.key();
+        let computation = ctx.accounts.computation_account.key();
+        if !first_application(&mut ctx.accounts.position.last_computation, target, computation) {
+            return Ok(());
+        }
+
+        let quote = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(ComputeWithdrawalOutput { field_0 }) => field_0,
+            Err(_) => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        let position = &mut ctx.accounts.position;
+        position.withdrawal_quote = quote.ciphertexts[0];
+        position.quote_pubkey = quote.encryption_key;
+        position.quote_nonce = quote.nonce;
+
+        emit!(WithdrawalComputed {
+            position: position.key(),
+            owner: position.owner,
+            withdrawal_quote: quote.ciphertexts[0],
+            quote_nonce: quote.nonce,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Attest publicly whether the caller's position holds at least
+    /// `threshold`, without revealing the balance. The result lands in a
+    /// `BalanceAttestation` PDA per (position, threshold); re-queueing
+    /// refreshes it.
+    pub fn queue_balance_check(
+        ctx: Context<QueueBalanceCheck>,
+        computation_offset: u64,
+        threshold: u64,
+    ) -> Result<()> {
+        let position = &ctx.accounts.position;
+        require!(position.is_active, errors::ZyncxError::InactivePosition);
+
+        let attestation = &mut ctx.accounts.attestation;
+        require!(!attestation.pending, errors::ZyncxError::BalanceCheckPending);
+        attestation.bump = ctx.bumps.attestation;
+        attestation.position = position.key();
+        attestation.owner = position.owner;
+        attestation.vault = position.vault;
+        attestation.threshold = threshold;
+        attestation.pending = true;
+        attestation.computation_offset = computation_offset;
+
+        let args = ArgBuilder::new()
+            .plaintext_u64(threshold)
+            .plaintext_u128(position.nonce)
+            .account(
+                position.key(),
+                EncryptedUserPosition::ENCRYPTED_STATE_OFFSET as u32,
+                EncryptedUserPosition::ENCRYPTED_STATE_SIZE as u32,
+            )
+            .build();
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        instructions::mempool::check_mempool_depth(
+            &ctx.accounts.mempool_account,
+            &ctx.accounts.mempool_limits,
+            COMP_DEF_OFFSET_VERIFY_SUFFICIENT_BALANCE,
+        )?;
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![VerifySufficientBalanceCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[CallbackAccount {
+                    pubkey: ctx.accounts.attestation.key(),
+                    is_writable: true,
+                }],
+            )?],
+            1,
+            0,
+        )?;
+
+        Ok(())
+    }
+
+    /// Callback for verify_sufficient_balance computation
+    #[arcium_callback(encrypted_ix = "verify_sufficient_balance")]
+    pub fn verify_sufficient_balance_callback(
+        ctx: Context<VerifySufficientBalanceCallback>,
+        output: SignedComputationOutputs<VerifySufficientBalanceOutput>,
+    ) -> Result<()> {
+        let target = ctx.accounts.attestation.key();
+        let computation = ctx.accounts.computation_account.key();
+        if !first_application(&mut ctx.accounts.attestation.last_computation, target, computation) {
+            return Ok(());
+        }
+
+        let result = output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        );
+
+        // Clear the pending flag even on abort so the owner can re-queue;
+        // the previous result, if any, is left untouched
+        let attestation = &mut ctx.accounts.attestation;
+        attestation.pending = false;
+        let sufficient = match result {
+            Ok(VerifySufficientBalanceOutput { field_0 }) => field_0,
+            Err(_) => return Ok(()),
+        };
+
+        let now = Clock::get()?.unix_timestamp;
+        attestation.sufficient = sufficient;
+        attestation.attested_at = now;
+
+        emit!(BalanceAttested {
+            attestation: attestation.key(),
+            position: attestation.position,
+            threshold: attestation.threshold,
+            sufficient,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    /// Redeem a position: the MXE reveals its balance, releases it from the
+    /// vault's encrypted totals and empties the position
+    pub fn queue_clear_position(
+        ctx: Context<QueueClearPosition>,
+        computation_offset: u64,
+    ) -> Result<()> {
+        let position = &ctx.accounts.position;
+        require!(position.is_active, errors::ZyncxError::InactivePosition);
+
+        let args = ArgBuilder::new()
+            .plaintext_u128(position.nonce)
+            .account(
+                position.key(),
+                EncryptedUserPosition::ENCRYPTED_STATE_OFFSET as u32,
+                EncryptedUserPosition::ENCRYPTED_STATE_SIZE as u32,
+            )
+            .plaintext_u128(ctx.accounts.vault.nonce)
+            .account(
+                ctx.accounts.vault.key(),
+                EncryptedVaultAccount::ENCRYPTED_STATE_OFFSET as u32,
+                EncryptedVaultAccount::ENCRYPTED_STATE_SIZE as u32,
+            )
+            .build();
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        instructions::mempool::check_mempool_depth(
+            &ctx.accounts.mempool_account,
+            &ctx.accounts.mempool_limits,
+            COMP_DEF_OFFSET_CLEAR_POSITION,
+        )?;
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![ClearPositionCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[
+                    CallbackAccount {
+                        pubkey: ctx.accounts.position.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.vault.key(),
+                        is_writable: true,
+                    },
+                ],
+            )?],
+            1,
+            0,
+        )?;
+
+        Ok(())
+    }
+
+    /// Callback for clear_position computation
+    #[arcium_callback(encrypted_ix = "clear_position")]
+    pub fn clear_position_callback(
+        ctx: Context<ClearPositionCallback>,
+        output: SignedComputationOutputs<ClearPositionOutput>,
+    ) -> Result<()> {
+        let target = ctx.accounts.position.key();
+        let computation = ctx.accounts.computation_account.key();
+        if !first_application(&mut ctx.accounts.position.last_computation, target, computation) {
+            return Ok(());
+        }
+
+        let (amount, cleared, vault_state) = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(ClearPositionOutput {
+                field_0: ClearPositionOutputStruct0 { field_0, field_1, field_2 },
+            }) => (field_0, field_1, field_2),
+            Err(_) => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        let position = &mut ctx.accounts.position;
+        position.position_state = cleared.ciphertexts;
+        position.nonce = cleared.nonce;
+        position.is_active = false;
+        position.redeemable_amount = position.redeemable_amount.saturating_add(amount);
+
+        ctx.accounts.vault.encrypted_state = vault_state.ciphertexts;
+        ctx.accounts.vault.nonce = vault_state.nonce;
+
+        emit!(PositionCleared {
+            position: position.key(),
+            owner: position.owner,
+            vault: position.vault,
+            amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+}
+
+/// Record `computation` as applied to `target`. Returns false (and emits
+/// CallbackReplayed) when its callback already ran, so a redelivered callback
+/// leaves nonces, ciphertexts and timestamps untouched.
+fn first_application(last_computation: &mut Pubkey, target: Pubkey, computation: Pubkey) -> bool {
+    if *last_computation == computation {
+        emit!(CallbackReplayed { target, computation });
+        return false;
+    }
+    *last_computation = computation;
+    true
+}
+
+/// Why a callback's output is unusable: the cluster aborted the circuit, or
+/// it returned an output whose signature did not verify
+fn output_failure<T: HasSize + AnchorDeserialize + AnchorSerialize>(
+    output: &SignedComputationOutputs<T>,
+) -> state::ComputationFailure {
+    match output {
+        SignedComputationOutputs::Failure => state::ComputationFailure::CircuitAssertion,
+        _ => state::ComputationFailure::OutputVerificationFailed,
+    }
+}
+
+/// Pay `fee` lamports from `payer` into the computation fee escrow
+fn charge_computation_fee<'info>(
+    payer: AccountInfo<'info>,
+    escrow: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+    fee: u64,
+) -> Result<()> {
+    if fee > 0 {
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                system_program,
+                anchor_lang::system_program::Transfer { from: payer, to: escrow },
+            ),
+            fee,
+        )?;
+    }
+    Ok(())
+}
+
+/// Arguments for the confidential_swap circuit, in ArgBuilder order:
+/// Enc<Shared, SwapParams>, current_output, liquidity_depth, referral_fee,
+/// Enc<Mxe, ReferralTotals>, Enc<Mxe, VaultState> (source), Enc<Mxe, VaultState> (dest)
+fn confidential_swap_args(
+    swap_request: &EncryptedSwapRequest,
+    quote: &SwapQuote,
+    referral_fee: u64,
+    referral_epoch: &Account<ReferralEpoch>,
+    source_vault: &Account<EncryptedVaultAccount>,
+    dest_vault: &Account<EncryptedVaultAccount>,
+) -> ArgumentList {
+    ArgBuilder::new()
+        .x25519_pubkey(swap_request.client_pubkey)
+        .plaintext_u128(swap_request.bounds_nonce)
+        .encrypted_u64(swap_request.encrypted_bounds[0])
+        .encrypted_u64(swap_request.encrypted_bounds[1])
+        .encrypted_u64(swap_request.encrypted_bounds[2])
+        .plaintext_u64(quote.current_output)
+        .plaintext_u64(quote.liquidity_depth)
+        .plaintext_u64(referral_fee)
+        .plaintext_u128(referral_epoch.nonce)
+        .account(
+            referral_epoch.key(),
+            ReferralEpoch::ENCRYPTED_TOTALS_OFFSET as u32,
+            ReferralEpoch::ENCRYPTED_TOTALS_SIZE as u32,
+        )
+        .plaintext_u128(source_vault.nonce)
+        .account(
+            source_vault.key(),
+            EncryptedVaultAccount::ENCRYPTED_STATE_OFFSET as u32,
+            EncryptedVaultAccount::ENCRYPTED_STATE_SIZE as u32,
+        )
+        .plaintext_u128(dest_vault.nonce)
+        .account(
+            dest_vault.key(),
+            EncryptedVaultAccount::ENCRYPTED_STATE_OFFSET as u32,
+            EncryptedVaultAccount::ENCRYPTED_STATE_SIZE as u32,
+        )
+        .build()
+}
+
+// ============================================================================
+// ARCIUM COMPUTATION DEFINITION ACCOUNTS
+// ============================================================================
+
+#[init_computation_definition_accounts("init_vault", payer)]
+#[derive(Accounts)]
+pub struct InitVaultCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("process_deposit", payer)]
+#[derive(Accounts)]
+pub struct InitProcessDepositCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("confidential_swap", payer)]
+#[derive(Accounts)]
+pub struct InitConfidentialSwapCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("evaluate_hybrid_order", payer)]
+#[derive(Accounts)]
+pub struct InitEvaluateHybridOrderCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("evaluate_limit_order", payer)]
+#[derive(Accounts)]
+pub struct InitEvaluateLimitOrderCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("evaluate_limit_order_batch", payer)]
+#[derive(Accounts)]
+pub struct InitEvaluateLimitOrderBatchCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("match_orders", payer)]
+#[derive(Accounts)]
+pub struct InitMatchOrdersCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("process_dca", payer)]
+#[derive(Accounts)]
+pub struct InitProcessDcaCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("process_twap", payer)]
+#[derive(Accounts)]
+pub struct InitProcessTwapCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("evaluate_stop_order", payer)]
+#[derive(Accounts)]
+pub struct InitEvaluateStopOrderCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("net_orders", payer)]
+#[derive(Accounts)]
+pub struct InitNetOrdersCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("select_rfq_quote", payer)]
+#[derive(Accounts)]
+pub struct InitSelectRfqQuoteCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("init_referral_epoch", payer)]
+#[derive(Accounts)]
+pub struct InitReferralEpochCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("reveal_referral_epoch", payer)]
+#[derive(Accounts)]
+pub struct InitRevealReferralEpochCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("compute_withdrawal", payer)]
+#[derive(Accounts)]
+pub struct InitComputeWithdrawalCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("clear_position", payer)]
+#[derive(Accounts)]
+pub struct InitClearPositionCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("init_position", payer)]
+#[derive(Accounts)]
+pub struct InitPositionCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("verify_sufficient_balance", payer)]
+#[derive(Accounts)]
+pub struct InitVerifySufficientBalanceCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("run_batch_auction", payer)]
+#[derive(Accounts)]
+pub struct InitRunBatchAuctionCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
     pub mxe_account: Box<Account<'info, MXEAccount>>,
     #[account(mut)]
-    /// CHECK: comp_def_account, checked by arcium program.
-    pub comp_def_account: UncheckedAccount<'info>,
+    /// CHECK: comp_def_account, checked by arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+// ============================================================================
+// QUEUE COMPUTATION ACCOUNTS
+// ============================================================================
+
+#[queue_computation_accounts("init_vault", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct CreateEncryptedVault<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    /// CHECK: Mempool limits PDA; may be uninitialized, parsed in the handler
+    #[account(seeds = [b"mempool_limits"], bump)]
+    pub mempool_limits: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_VAULT))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    /// CHECK: Token mint for the vault
+    pub token_mint: AccountInfo<'info>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + EncryptedVaultAccount::INIT_SPACE,
+        seeds = [b"enc_vault", token_mint.key().as_ref()],
+        bump,
+    )]
+    pub vault: Account<'info, EncryptedVaultAccount>,
+}
+
+#[queue_computation_accounts("process_deposit", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct QueueEncryptedDeposit<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    /// CHECK: Mempool limits PDA; may be uninitialized, parsed in the handler
+    #[account(seeds = [b"mempool_limits"], bump)]
+    pub mempool_limits: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROCESS_DEPOSIT))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(mut)]
+    pub vault: Account<'info, EncryptedVaultAccount>,
+    #[account(seeds = [b"arcium_config"], bump = arcium_config.bump)]
+    pub arcium_config: Box<Account<'info, state::ArciumConfig>>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = state::ComputationFeeEscrow::INIT_SPACE,
+        seeds = [b"computation_fees"],
+        bump,
+    )]
+    pub computation_fee_escrow: Box<Account<'info, state::ComputationFeeEscrow>>,
+}
+
+#[queue_computation_accounts("confidential_swap", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct QueueConfidentialSwap<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    /// CHECK: Mempool limits PDA; may be uninitialized, parsed in the handler
+    #[account(seeds = [b"mempool_limits"], bump)]
+    pub mempool_limits: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CONFIDENTIAL_SWAP))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(mut)]
+    pub vault: Account<'info, EncryptedVaultAccount>,
+    /// Encrypted vault of the token bought
+    #[account(
+        mut,
+        constraint = dest_vault.key() != vault.key() @ errors::ZyncxError::InvalidSwapRoute,
+    )]
+    pub dest_vault: Box<Account<'info, EncryptedVaultAccount>>,
+    #[account(seeds = [b"arcium_config"], bump = arcium_config.bump)]
+    pub arcium_config: Box<Account<'info, state::ArciumConfig>>,
+    /// CHECK: Pyth price account for the source token; its price is recorded
+    /// as the decision-time oracle price
+    pub price_feed: UncheckedAccount<'info>,
+    #[account(seeds = [b"price_feed_registry"], bump = price_feed_registry.bump)]
+    pub price_feed_registry: Box<Account<'info, state::PriceFeedRegistry>>,
+    /// Admin-set fallback oracle for the priced token; read together with
+    /// fallback_price_feed when the Pyth price is stale
+    pub oracle_fallback: Option<Box<Account<'info, state::OracleFallback>>>,
+    /// CHECK: Fallback oracle's price account, checked against oracle_fallback
+    pub fallback_price_feed: Option<UncheckedAccount<'info>>,
+    #[account(
+        seeds = [b"referral_registry"],
+        bump = referral_registry.bump,
+    )]
+    pub referral_registry: Box<Account<'info, state::ReferralRegistry>>,
+    #[account(
+        mut,
+        seeds = [b"referral_epoch".as_ref(), &referral_epoch.epoch.to_le_bytes()],
+        bump = referral_epoch.bump,
+    )]
+    pub referral_epoch: Box<Account<'info, ReferralEpoch>>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + EncryptedSwapRequest::INIT_SPACE,
+        seeds = [b"swap_request", payer.key().as_ref(), &computation_offset.to_le_bytes()],
+        bump,
+    )]
+    pub swap_request: Box<Account<'info, EncryptedSwapRequest>>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = state::ComputationFeeEscrow::INIT_SPACE,
+        seeds = [b"computation_fees"],
+        bump,
+    )]
+    pub computation_fee_escrow: Box<Account<'info, state::ComputationFeeEscrow>>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = state::UserRequestIndex::INIT_SPACE,
+        seeds = [b"user_request_index", payer.key().as_ref()],
+        bump,
+    )]
+    pub user_request_index: Box<Account<'info, state::UserRequestIndex>>,
+}
+
+#[queue_computation_accounts("confidential_swap", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct RetryComputation<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    /// CHECK: Mempool limits PDA; may be uninitialized, parsed in the handler
+    #[account(seeds = [b"mempool_limits"], bump)]
+    pub mempool_limits: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CONFIDENTIAL_SWAP))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    /// Source vault of the request; picks the expected price feed
+    #[account(mut, address = swap_request.source_vault @ errors::ZyncxError::VaultNotFound)]
+    pub source_vault: Box<Account<'info, EncryptedVaultAccount>>,
+    #[account(mut, address = swap_request.dest_vault @ errors::ZyncxError::VaultNotFound)]
+    pub dest_vault: Box<Account<'info, EncryptedVaultAccount>>,
+    #[account(seeds = [b"arcium_config"], bump = arcium_config.bump)]
+    pub arcium_config: Box<Account<'info, state::ArciumConfig>>,
+    /// CHECK: Pyth price account for the source token; its price is recorded
+    /// as the decision-time oracle price
+    pub price_feed: UncheckedAccount<'info>,
+    #[account(seeds = [b"price_feed_registry"], bump = price_feed_registry.bump)]
+    pub price_feed_registry: Box<Account<'info, state::PriceFeedRegistry>>,
+    /// Admin-set fallback oracle for the priced token; read together with
+    /// fallback_price_feed when the Pyth price is stale
+    pub oracle_fallback: Option<Box<Account<'info, state::OracleFallback>>>,
+    /// CHECK: Fallback oracle's price account, checked against oracle_fallback
+    pub fallback_price_feed: Option<UncheckedAccount<'info>>,
+    #[account(
+        seeds = [b"referral_registry"],
+        bump = referral_registry.bump,
+    )]
+    pub referral_registry: Box<Account<'info, state::ReferralRegistry>>,
+    #[account(
+        mut,
+        seeds = [b"referral_epoch".as_ref(), &referral_epoch.epoch.to_le_bytes()],
+        bump = referral_epoch.bump,
+    )]
+    pub referral_epoch: Box<Account<'info, ReferralEpoch>>,
+    #[account(
+        mut,
+        constraint = swap_request.user == payer.key() @ errors::ZyncxError::Unauthorized,
+        seeds = [
+            b"swap_request",
+            swap_request.user.as_ref(),
+            &swap_request.computation_offset.to_le_bytes(),
+        ],
+        bump = swap_request.bump,
+    )]
+    pub swap_request: Box<Account<'info, EncryptedSwapRequest>>,
+    #[account(
+        mut,
+        seeds = [b"computation_fees"],
+        bump = computation_fee_escrow.bump,
+    )]
+    pub computation_fee_escrow: Box<Account<'info, state::ComputationFeeEscrow>>,
+    #[account(
+        mut,
+        seeds = [b"user_request_index", swap_request.user.as_ref()],
+        bump = user_request_index.bump,
+    )]
+    pub user_request_index: Box<Account<'info, state::UserRequestIndex>>,
+}
+
+#[queue_computation_accounts("evaluate_hybrid_order", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct QueueHybridOrderEvaluation<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    /// CHECK: Mempool limits PDA; may be uninitialized, parsed in the handler
+    #[account(seeds = [b"mempool_limits"], bump)]
+    pub mempool_limits: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_EVALUATE_HYBRID_ORDER))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        mut,
+        seeds = [
+            b"hybrid_order",
+            hybrid_order.user.as_ref(),
+            &hybrid_order.order_id.to_le_bytes(),
+        ],
+        bump = hybrid_order.bump,
+    )]
+    pub hybrid_order: Box<Account<'info, EncryptedHybridOrder>>,
+    /// Vault of the token being priced; picks the expected price feed
+    #[account(address = hybrid_order.source_vault @ errors::ZyncxError::VaultNotFound)]
+    pub source_vault: Box<Account<'info, state::VaultState>>,
+    /// CHECK: Pyth price account bound to the order at placement
+    #[account(address = hybrid_order.price_feed @ errors::ZyncxError::InvalidPriceFeed)]
+    pub price_feed: UncheckedAccount<'info>,
+    #[account(seeds = [b"price_feed_registry"], bump = price_feed_registry.bump)]
+    pub price_feed_registry: Box<Account<'info, state::PriceFeedRegistry>>,
+    /// Admin-set fallback oracle for the priced token; read together with
+    /// fallback_price_feed when the Pyth price is stale
+    pub oracle_fallback: Option<Box<Account<'info, state::OracleFallback>>>,
+    /// CHECK: Fallback oracle's price account, checked against oracle_fallback
+    pub fallback_price_feed: Option<UncheckedAccount<'info>>,
+}
+
+#[queue_computation_accounts("evaluate_limit_order", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct QueueLimitOrderEvaluation<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    /// CHECK: Mempool limits PDA; may be uninitialized, parsed in the handler
+    #[account(seeds = [b"mempool_limits"], bump)]
+    pub mempool_limits: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_EVALUATE_LIMIT_ORDER))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        mut,
+        seeds = [
+            b"limit_order",
+            limit_order.user.as_ref(),
+            &limit_order.order_id.to_le_bytes(),
+        ],
+        bump = limit_order.bump,
+    )]
+    pub limit_order: Box<Account<'info, EncryptedLimitOrder>>,
+    /// Vault of the token being priced; picks the expected price feed
+    #[account(address = limit_order.source_vault @ errors::ZyncxError::VaultNotFound)]
+    pub source_vault: Box<Account<'info, state::VaultState>>,
+    #[account(seeds = [b"arcium_config"], bump = arcium_config.bump)]
+    pub arcium_config: Box<Account<'info, state::ArciumConfig>>,
+    /// CHECK: Pyth price account bound to the order at placement
+    #[account(address = limit_order.price_feed @ errors::ZyncxError::InvalidPriceFeed)]
+    pub price_feed: UncheckedAccount<'info>,
+    #[account(seeds = [b"price_feed_registry"], bump = price_feed_registry.bump)]
+    pub price_feed_registry: Box<Account<'info, state::PriceFeedRegistry>>,
+    /// Admin-set fallback oracle for the priced token; read together with
+    /// fallback_price_feed when the Pyth price is stale
+    pub oracle_fallback: Option<Box<Account<'info, state::OracleFallback>>>,
+    /// CHECK: Fallback oracle's price account, checked against oracle_fallback
+    pub fallback_price_feed: Option<UncheckedAccount<'info>>,
+}
+
+#[queue_computation_accounts("evaluate_limit_order_batch", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct CrankLimitOrders<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    /// CHECK: Mempool limits PDA; may be uninitialized, parsed in the handler
+    #[account(seeds = [b"mempool_limits"], bump)]
+    pub mempool_limits: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_EVALUATE_LIMIT_ORDER_BATCH))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    /// Source vault shared by every order in the batch; picks the expected
+    /// price feed
+    pub source_vault: Box<Account<'info, state::VaultState>>,
+    #[account(seeds = [b"arcium_config"], bump = arcium_config.bump)]
+    pub arcium_config: Box<Account<'info, state::ArciumConfig>>,
+    /// CHECK: Pyth price account shared by every order in the batch
+    pub price_feed: UncheckedAccount<'info>,
+    #[account(seeds = [b"price_feed_registry"], bump = price_feed_registry.bump)]
+    pub price_feed_registry: Box<Account<'info, state::PriceFeedRegistry>>,
+    /// Admin-set fallback oracle for the priced token; read together with
+    /// fallback_price_feed when the Pyth price is stale
+    pub oracle_fallback: Option<Box<Account<'info, state::OracleFallback>>>,
+    /// CHECK: Fallback oracle's price account, checked against oracle_fallback
+    pub fallback_price_feed: Option<UncheckedAccount<'info>>,
+    /// Native SOL vault whose retained fees fund crank tips
+    #[account(
+        seeds = [b"vault", instructions::initialize::NATIVE_MINT.as_ref()],
+        bump = fee_vault.bump,
+    )]
+    pub fee_vault: Box<Account<'info, state::VaultState>>,
+    /// CHECK: Native vault treasury PDA holding the fees
+    #[account(
+        mut,
+        seeds = [b"vault_treasury", fee_vault.key().as_ref()],
+        bump,
+    )]
+    pub vault_treasury: AccountInfo<'info>,
+    #[account(
+        mut,
+        seeds = [b"vault_fees", fee_vault.key().as_ref()],
+        bump = vault_fees.bump,
+    )]
+    pub vault_fees: Box<Account<'info, state::VaultFeeState>>,
+}
+
+#[queue_computation_accounts("match_orders", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct ProposeMatch<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    /// CHECK: Mempool limits PDA; may be uninitialized, parsed in the handler
+    #[account(seeds = [b"mempool_limits"], bump)]
+    pub mempool_limits: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_MATCH_ORDERS))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        mut,
+        seeds = [
+            b"limit_order",
+            order_a.user.as_ref(),
+            &order_a.order_id.to_le_bytes(),
+        ],
+        bump = order_a.bump,
+    )]
+    pub order_a: Box<Account<'info, EncryptedLimitOrder>>,
+    #[account(
+        mut,
+        seeds = [
+            b"limit_order",
+            order_b.user.as_ref(),
+            &order_b.order_id.to_le_bytes(),
+        ],
+        bump = order_b.bump,
+    )]
+    pub order_b: Box<Account<'info, EncryptedLimitOrder>>,
+}
+
+#[queue_computation_accounts("run_batch_auction", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct RunAuction<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    /// CHECK: Mempool limits PDA; may be uninitialized, parsed in the handler
+    #[account(seeds = [b"mempool_limits"], bump)]
+    pub mempool_limits: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_RUN_BATCH_AUCTION))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    /// Base vault shared by every order in the batch; picks the expected
+    /// price feed
+    pub base_vault: Box<Account<'info, state::VaultState>>,
+    /// CHECK: Pyth price account shared by every order in the batch
+    pub price_feed: UncheckedAccount<'info>,
+    #[account(seeds = [b"price_feed_registry"], bump = price_feed_registry.bump)]
+    pub price_feed_registry: Box<Account<'info, state::PriceFeedRegistry>>,
+    /// Admin-set fallback oracle for the priced token; read together with
+    /// fallback_price_feed when the Pyth price is stale
+    pub oracle_fallback: Option<Box<Account<'info, state::OracleFallback>>>,
+    /// CHECK: Fallback oracle's price account, checked against oracle_fallback
+    pub fallback_price_feed: Option<UncheckedAccount<'info>>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + BatchAuction::INIT_SPACE,
+        seeds = [b"batch_auction".as_ref(), &computation_offset.to_le_bytes()],
+        bump,
+    )]
+    pub auction: Box<Account<'info, BatchAuction>>,
+}
+
+#[queue_computation_accounts("process_dca", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct ExecuteDca<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    /// CHECK: Mempool limits PDA; may be uninitialized, parsed in the handler
+    #[account(seeds = [b"mempool_limits"], bump)]
+    pub mempool_limits: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROCESS_DCA))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        mut,
+        seeds = [b"dca", dca_config.user.as_ref(), &dca_config.dca_id.to_le_bytes()],
+        bump = dca_config.bump,
+    )]
+    pub dca_config: Box<Account<'info, EncryptedDCAConfig>>,
+    /// Vault of the token being priced; picks the expected price feed
+    #[account(address = dca_config.source_vault @ errors::ZyncxError::VaultNotFound)]
+    pub source_vault: Box<Account<'info, state::VaultState>>,
+    /// CHECK: Pyth price account bound to the DCA at creation
+    #[account(address = dca_config.price_feed @ errors::ZyncxError::InvalidPriceFeed)]
+    pub price_feed: UncheckedAccount<'info>,
+    #[account(seeds = [b"price_feed_registry"], bump = price_feed_registry.bump)]
+    pub price_feed_registry: Box<Account<'info, state::PriceFeedRegistry>>,
+    /// Admin-set fallback oracle for the priced token; read together with
+    /// fallback_price_feed when the Pyth price is stale
+    pub oracle_fallback: Option<Box<Account<'info, state::OracleFallback>>>,
+    /// CHECK: Fallback oracle's price account, checked against oracle_fallback
+    pub fallback_price_feed: Option<UncheckedAccount<'info>>,
+}
+
+#[queue_computation_accounts("net_orders", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct NetDcaWithLimitOrder<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    /// CHECK: Mempool limits PDA; may be uninitialized, parsed in the handler
+    #[account(seeds = [b"mempool_limits"], bump)]
+    pub mempool_limits: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_NET_ORDERS))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        mut,
+        seeds = [b"dca", dca_config.user.as_ref(), &dca_config.dca_id.to_le_bytes()],
+        bump = dca_config.bump,
+    )]
+    pub dca_config: Box<Account<'info, EncryptedDCAConfig>>,
+    #[account(
+        mut,
+        seeds = [
+            b"limit_order",
+            limit_order.user.as_ref(),
+            &limit_order.order_id.to_le_bytes(),
+        ],
+        bump = limit_order.bump,
+    )]
+    pub limit_order: Box<Account<'info, EncryptedLimitOrder>>,
+    /// Vault of the token being priced; picks the expected price feed
+    #[account(address = dca_config.source_vault @ errors::ZyncxError::VaultNotFound)]
+    pub source_vault: Box<Account<'info, state::VaultState>>,
+    /// CHECK: Pyth price account bound to both orders at creation
+    #[account(address = dca_config.price_feed @ errors::ZyncxError::InvalidPriceFeed)]
+    pub price_feed: UncheckedAccount<'info>,
+    #[account(seeds = [b"price_feed_registry"], bump = price_feed_registry.bump)]
+    pub price_feed_registry: Box<Account<'info, state::PriceFeedRegistry>>,
+    /// Admin-set fallback oracle for the priced token; read together with
+    /// fallback_price_feed when the Pyth price is stale
+    pub oracle_fallback: Option<Box<Account<'info, state::OracleFallback>>>,
+    /// CHECK: Fallback oracle's price account, checked against oracle_fallback
+    pub fallback_price_feed: Option<UncheckedAccount<'info>>,
+}
+
+#[queue_computation_accounts("select_rfq_quote", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct SelectRfqQuote<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    /// CHECK: Mempool limits PDA; may be uninitialized, parsed in the handler
+    #[account(seeds = [b"mempool_limits"], bump)]
+    pub mempool_limits: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_SELECT_RFQ_QUOTE))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
     pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        mut,
+        seeds = [b"rfq_request", rfq_request.user.as_ref(), &rfq_request.rfq_id.to_le_bytes()],
+        bump = rfq_request.bump,
+    )]
+    pub rfq_request: Box<Account<'info, RfqRequest>>,
+}
+
+#[queue_computation_accounts("process_twap", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct ExecuteTwap<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    /// CHECK: Mempool limits PDA; may be uninitialized, parsed in the handler
+    #[account(seeds = [b"mempool_limits"], bump)]
+    pub mempool_limits: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROCESS_TWAP))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
     pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        mut,
+        seeds = [b"twap", twap_config.user.as_ref(), &twap_config.twap_id.to_le_bytes()],
+        bump = twap_config.bump,
+    )]
+    pub twap_config: Box<Account<'info, EncryptedTWAPConfig>>,
 }
 
-#[init_computation_definition_accounts("process_deposit", payer)]
+#[queue_computation_accounts("evaluate_stop_order", payer)]
 #[derive(Accounts)]
-pub struct InitProcessDepositCompDef<'info> {
+#[instruction(computation_offset: u64)]
+pub struct QueueStopOrderEvaluation<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
-    #[account(mut, address = derive_mxe_pda!())]
-    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    /// CHECK: Mempool limits PDA; may be uninitialized, parsed in the handler
+    #[account(seeds = [b"mempool_limits"], bump)]
+    pub mempool_limits: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_EVALUATE_STOP_ORDER))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        mut,
+        seeds = [
+            b"stop_order",
+            stop_order.user.as_ref(),
+            &stop_order.order_id.to_le_bytes(),
+        ],
+        bump = stop_order.bump,
+    )]
+    pub stop_order: Box<Account<'info, EncryptedStopOrder>>,
+    /// Vault of the token being priced; picks the expected price feed
+    #[account(address = stop_order.source_vault @ errors::ZyncxError::VaultNotFound)]
+    pub source_vault: Box<Account<'info, state::VaultState>>,
+    /// CHECK: Pyth price account bound to the order at placement
+    #[account(address = stop_order.price_feed @ errors::ZyncxError::InvalidPriceFeed)]
+    pub price_feed: UncheckedAccount<'info>,
+    #[account(seeds = [b"price_feed_registry"], bump = price_feed_registry.bump)]
+    pub price_feed_registry: Box<Account<'info, state::PriceFeedRegistry>>,
+    /// Admin-set fallback oracle for the priced token; read together with
+    /// fallback_price_feed when the Pyth price is stale
+    pub oracle_fallback: Option<Box<Account<'info, state::OracleFallback>>>,
+    /// CHECK: Fallback oracle's price account, checked against oracle_fallback
+    pub fallback_price_feed: Option<UncheckedAccount<'info>>,
+}
+
+#[queue_computation_accounts("init_referral_epoch", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, epoch: u64)]
+pub struct OpenReferralEpoch<'info> {
     #[account(mut)]
-    /// CHECK: comp_def_account, checked by arcium program.
-    pub comp_def_account: UncheckedAccount<'info>,
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    /// CHECK: Mempool limits PDA; may be uninitialized, parsed in the handler
+    #[account(seeds = [b"mempool_limits"], bump)]
+    pub mempool_limits: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_REFERRAL_EPOCH))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
     pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        init,
+        payer = payer,
+        space = ReferralEpoch::INIT_SPACE,
+        seeds = [b"referral_epoch".as_ref(), &epoch.to_le_bytes()],
+        bump,
+    )]
+    pub referral_epoch: Box<Account<'info, ReferralEpoch>>,
+}
+
+#[queue_computation_accounts("reveal_referral_epoch", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct RevealReferralEpoch<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    /// CHECK: Mempool limits PDA; may be uninitialized, parsed in the handler
+    #[account(seeds = [b"mempool_limits"], bump)]
+    pub mempool_limits: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_REFERRAL_EPOCH))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
     pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        mut,
+        seeds = [b"referral_epoch".as_ref(), &referral_epoch.epoch.to_le_bytes()],
+        bump = referral_epoch.bump,
+    )]
+    pub referral_epoch: Box<Account<'info, ReferralEpoch>>,
 }
 
-#[init_computation_definition_accounts("confidential_swap", payer)]
+#[queue_computation_accounts("init_position", payer)]
 #[derive(Accounts)]
-pub struct InitConfidentialSwapCompDef<'info> {
+#[instruction(computation_offset: u64)]
+pub struct CreateEncryptedPosition<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
-    #[account(mut, address = derive_mxe_pda!())]
-    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    /// CHECK: Mempool limits PDA; may be uninitialized, parsed in the handler
+    #[account(seeds = [b"mempool_limits"], bump)]
+    pub mempool_limits: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_POSITION))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub vault: Box<Account<'info, EncryptedVaultAccount>>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + EncryptedUserPosition::INIT_SPACE,
+        seeds = [b"enc_position", vault.key().as_ref(), payer.key().as_ref()],
+        bump,
+    )]
+    pub position: Box<Account<'info, EncryptedUserPosition>>,
+}
+
+#[queue_computation_accounts("compute_withdrawal", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct QueueComputeWithdrawal<'info> {
     #[account(mut)]
-    /// CHECK: comp_def_account, checked by arcium program.
-    pub comp_def_account: UncheckedAccount<'info>,
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    /// CHECK: Mempool limits PDA; may be uninitialized, parsed in the handler
+    #[account(seeds = [b"mempool_limits"], bump)]
+    pub mempool_limits: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_COMPUTE_WITHDRAWAL))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
     pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        constraint = position.owner == payer.key() @ errors::ZyncxError::Unauthorized,
+        seeds = [b"enc_position", position.vault.as_ref(), position.owner.as_ref()],
+        bump = position.bump,
+    )]
+    pub position: Box<Account<'info, EncryptedUserPosition>>,
+}
+
+#[queue_computation_accounts("verify_sufficient_balance", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, threshold: u64)]
+pub struct QueueBalanceCheck<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    /// CHECK: Mempool limits PDA; may be uninitialized, parsed in the handler
+    #[account(seeds = [b"mempool_limits"], bump)]
+    pub mempool_limits: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_VERIFY_SUFFICIENT_BALANCE))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
     pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    /// Only the owner may disclose a bit about their balance
+    #[account(
+        constraint = position.owner == payer.key() @ errors::ZyncxError::Unauthorized,
+        seeds = [b"enc_position", position.vault.as_ref(), position.owner.as_ref()],
+        bump = position.bump,
+    )]
+    pub position: Box<Account<'info, EncryptedUserPosition>>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + BalanceAttestation::INIT_SPACE,
+        seeds = [
+            b"balance_attestation".as_ref(),
+            position.key().as_ref(),
+            &threshold.to_le_bytes(),
+        ],
+        bump,
+    )]
+    pub attestation: Box<Account<'info, BalanceAttestation>>,
 }
 
-// ============================================================================
-// QUEUE COMPUTATION ACCOUNTS
-// ============================================================================
-
-#[queue_computation_accounts("init_vault", payer)]
+#[queue_computation_accounts("clear_position", payer)]
 #[derive(Accounts)]
 #[instruction(computation_offset: u64)]
-pub struct CreateEncryptedVault<'info> {
+pub struct QueueClearPosition<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
     #[account(
@@ -400,123 +5198,369 @@ pub struct CreateEncryptedVault<'info> {
     #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
     /// CHECK: mempool_account
     pub mempool_account: UncheckedAccount<'info>,
+    /// CHECK: Mempool limits PDA; may be uninitialized, parsed in the handler
+    #[account(seeds = [b"mempool_limits"], bump)]
+    pub mempool_limits: UncheckedAccount<'info>,
     #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
     /// CHECK: executing_pool
     pub executing_pool: UncheckedAccount<'info>,
     #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
     /// CHECK: computation_account
     pub computation_account: UncheckedAccount<'info>,
-    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_VAULT))]
-    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
-    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CLEAR_POSITION))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        mut,
+        constraint = position.owner == payer.key() @ errors::ZyncxError::Unauthorized,
+        seeds = [b"enc_position", position.vault.as_ref(), position.owner.as_ref()],
+        bump = position.bump,
+    )]
+    pub position: Box<Account<'info, EncryptedUserPosition>>,
+    #[account(mut, address = position.vault @ errors::ZyncxError::VaultNotFound)]
+    pub vault: Box<Account<'info, EncryptedVaultAccount>>,
+}
+
+// ============================================================================
+// CALLBACK ACCOUNTS
+// ============================================================================
+
+#[callback_accounts("init_vault")]
+#[derive(Accounts)]
+pub struct InitVaultCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_VAULT))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub vault: Account<'info, EncryptedVaultAccount>,
+}
+
+#[callback_accounts("process_deposit")]
+#[derive(Accounts)]
+pub struct ProcessDepositCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROCESS_DEPOSIT))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub vault: Account<'info, EncryptedVaultAccount>,
+}
+
+#[callback_accounts("confidential_swap")]
+#[derive(Accounts)]
+pub struct ConfidentialSwapCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CONFIDENTIAL_SWAP))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub swap_request: Account<'info, EncryptedSwapRequest>,
+    #[account(mut)]
+    pub referral_epoch: Account<'info, ReferralEpoch>,
+    #[account(mut)]
+    pub source_vault: Account<'info, EncryptedVaultAccount>,
+    #[account(mut)]
+    pub dest_vault: Account<'info, EncryptedVaultAccount>,
+    #[account(
+        mut,
+        seeds = [b"computation_fees"],
+        bump = computation_fee_escrow.bump,
+    )]
+    pub computation_fee_escrow: Account<'info, state::ComputationFeeEscrow>,
+    #[account(
+        mut,
+        seeds = [b"user_request_index", swap_request.user.as_ref()],
+        bump = user_request_index.bump,
+    )]
+    pub user_request_index: Account<'info, state::UserRequestIndex>,
+}
+
+#[callback_accounts("evaluate_hybrid_order")]
+#[derive(Accounts)]
+pub struct EvaluateHybridOrderCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_EVALUATE_HYBRID_ORDER))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub hybrid_order: Account<'info, EncryptedHybridOrder>,
+}
+
+#[callback_accounts("evaluate_limit_order")]
+#[derive(Accounts)]
+pub struct EvaluateLimitOrderCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_EVALUATE_LIMIT_ORDER))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub limit_order: Account<'info, EncryptedLimitOrder>,
+}
+
+#[callback_accounts("evaluate_limit_order_batch")]
+#[derive(Accounts)]
+pub struct EvaluateLimitOrderBatchCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_EVALUATE_LIMIT_ORDER_BATCH))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+}
+
+#[callback_accounts("match_orders")]
+#[derive(Accounts)]
+pub struct MatchOrdersCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_MATCH_ORDERS))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub order_a: Account<'info, EncryptedLimitOrder>,
+    #[account(mut)]
+    pub order_b: Account<'info, EncryptedLimitOrder>,
+}
+
+#[callback_accounts("run_batch_auction")]
+#[derive(Accounts)]
+pub struct RunBatchAuctionCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_RUN_BATCH_AUCTION))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub auction: Account<'info, BatchAuction>,
+}
+
+#[callback_accounts("process_dca")]
+#[derive(Accounts)]
+pub struct ProcessDcaCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROCESS_DCA))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub dca_config: Account<'info, EncryptedDCAConfig>,
+}
+
+#[callback_accounts("net_orders")]
+#[derive(Accounts)]
+pub struct NetOrdersCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_NET_ORDERS))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub dca_config: Account<'info, EncryptedDCAConfig>,
+    #[account(mut)]
+    pub limit_order: Account<'info, EncryptedLimitOrder>,
+}
+
+#[callback_accounts("select_rfq_quote")]
+#[derive(Accounts)]
+pub struct SelectRfqQuoteCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_SELECT_RFQ_QUOTE))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub rfq_request: Account<'info, RfqRequest>,
+}
+
+#[callback_accounts("process_twap")]
+#[derive(Accounts)]
+pub struct ProcessTwapCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROCESS_TWAP))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub twap_config: Account<'info, EncryptedTWAPConfig>,
+}
+
+#[callback_accounts("evaluate_stop_order")]
+#[derive(Accounts)]
+pub struct EvaluateStopOrderCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_EVALUATE_STOP_ORDER))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub stop_order: Account<'info, EncryptedStopOrder>,
+}
+
+#[callback_accounts("init_referral_epoch")]
+#[derive(Accounts)]
+pub struct InitReferralEpochCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_REFERRAL_EPOCH))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
     pub cluster_account: Account<'info, Cluster>,
-    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
-    pub pool_account: Account<'info, FeePool>,
-    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
-    pub clock_account: Account<'info, ClockAccount>,
-    pub system_program: Program<'info, System>,
-    pub arcium_program: Program<'info, Arcium>,
-    /// CHECK: Token mint for the vault
-    pub token_mint: AccountInfo<'info>,
-    #[account(
-        init,
-        payer = payer,
-        space = 8 + EncryptedVaultAccount::INIT_SPACE,
-        seeds = [b"enc_vault", token_mint.key().as_ref()],
-        bump,
-    )]
-    pub vault: Account<'info, EncryptedVaultAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub referral_epoch: Account<'info, ReferralEpoch>,
 }
 
-#[queue_computation_accounts("process_deposit", payer)]
+#[callback_accounts("reveal_referral_epoch")]
 #[derive(Accounts)]
-#[instruction(computation_offset: u64)]
-pub struct QueueEncryptedDeposit<'info> {
-    #[account(mut)]
-    pub payer: Signer<'info>,
-    #[account(
-        init_if_needed,
-        space = 9,
-        payer = payer,
-        seeds = [&SIGN_PDA_SEED],
-        bump,
-        address = derive_sign_pda!(),
-    )]
-    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+pub struct RevealReferralEpochCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_REFERRAL_EPOCH))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
     #[account(address = derive_mxe_pda!())]
     pub mxe_account: Account<'info, MXEAccount>,
-    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
-    /// CHECK: mempool_account
-    pub mempool_account: UncheckedAccount<'info>,
-    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
-    /// CHECK: executing_pool
-    pub executing_pool: UncheckedAccount<'info>,
-    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
     /// CHECK: computation_account
     pub computation_account: UncheckedAccount<'info>,
-    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROCESS_DEPOSIT))]
-    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
-    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
     pub cluster_account: Account<'info, Cluster>,
-    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
-    pub pool_account: Account<'info, FeePool>,
-    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
-    pub clock_account: Account<'info, ClockAccount>,
-    pub system_program: Program<'info, System>,
-    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
     #[account(mut)]
-    pub vault: Account<'info, EncryptedVaultAccount>,
+    pub referral_epoch: Account<'info, ReferralEpoch>,
 }
 
-#[queue_computation_accounts("confidential_swap", payer)]
+#[callback_accounts("init_position")]
 #[derive(Accounts)]
-#[instruction(computation_offset: u64)]
-pub struct QueueConfidentialSwap<'info> {
-    #[account(mut)]
-    pub payer: Signer<'info>,
-    #[account(
-        init_if_needed,
-        space = 9,
-        payer = payer,
-        seeds = [&SIGN_PDA_SEED],
-        bump,
-        address = derive_sign_pda!(),
-    )]
-    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+pub struct InitPositionCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_POSITION))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
     #[account(address = derive_mxe_pda!())]
     pub mxe_account: Account<'info, MXEAccount>,
-    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
-    /// CHECK: mempool_account
-    pub mempool_account: UncheckedAccount<'info>,
-    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
-    /// CHECK: executing_pool
-    pub executing_pool: UncheckedAccount<'info>,
-    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
     /// CHECK: computation_account
     pub computation_account: UncheckedAccount<'info>,
-    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CONFIDENTIAL_SWAP))]
-    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
-    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
     pub cluster_account: Account<'info, Cluster>,
-    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
-    pub pool_account: Account<'info, FeePool>,
-    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
-    pub clock_account: Account<'info, ClockAccount>,
-    pub system_program: Program<'info, System>,
-    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
     #[account(mut)]
-    pub vault: Account<'info, EncryptedVaultAccount>,
+    pub position: Account<'info, EncryptedUserPosition>,
 }
 
-// ============================================================================
-// CALLBACK ACCOUNTS
-// ============================================================================
-
-#[callback_accounts("init_vault")]
+#[callback_accounts("compute_withdrawal")]
 #[derive(Accounts)]
-pub struct InitVaultCallback<'info> {
+pub struct ComputeWithdrawalCallback<'info> {
     pub arcium_program: Program<'info, Arcium>,
-    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_VAULT))]
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_COMPUTE_WITHDRAWAL))]
     pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
     #[account(address = derive_mxe_pda!())]
     pub mxe_account: Account<'info, MXEAccount>,
@@ -528,14 +5572,14 @@ pub struct InitVaultCallback<'info> {
     /// CHECK: instructions_sysvar
     pub instructions_sysvar: AccountInfo<'info>,
     #[account(mut)]
-    pub vault: Account<'info, EncryptedVaultAccount>,
+    pub position: Account<'info, EncryptedUserPosition>,
 }
 
-#[callback_accounts("process_deposit")]
+#[callback_accounts("verify_sufficient_balance")]
 #[derive(Accounts)]
-pub struct ProcessDepositCallback<'info> {
+pub struct VerifySufficientBalanceCallback<'info> {
     pub arcium_program: Program<'info, Arcium>,
-    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROCESS_DEPOSIT))]
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_VERIFY_SUFFICIENT_BALANCE))]
     pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
     #[account(address = derive_mxe_pda!())]
     pub mxe_account: Account<'info, MXEAccount>,
@@ -547,14 +5591,14 @@ pub struct ProcessDepositCallback<'info> {
     /// CHECK: instructions_sysvar
     pub instructions_sysvar: AccountInfo<'info>,
     #[account(mut)]
-    pub vault: Account<'info, EncryptedVaultAccount>,
+    pub attestation: Account<'info, BalanceAttestation>,
 }
 
-#[callback_accounts("confidential_swap")]
+#[callback_accounts("clear_position")]
 #[derive(Accounts)]
-pub struct ConfidentialSwapCallback<'info> {
+pub struct ClearPositionCallback<'info> {
     pub arcium_program: Program<'info, Arcium>,
-    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CONFIDENTIAL_SWAP))]
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CLEAR_POSITION))]
     pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
     #[account(address = derive_mxe_pda!())]
     pub mxe_account: Account<'info, MXEAccount>,
@@ -565,6 +5609,10 @@ pub struct ConfidentialSwapCallback<'info> {
     #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
     /// CHECK: instructions_sysvar
     pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub position: Account<'info, EncryptedUserPosition>,
+    #[account(mut)]
+    pub vault: Account<'info, EncryptedVaultAccount>,
 }
 
 // ============================================================================
@@ -609,13 +5657,303 @@ pub struct DepositProcessed {
 pub struct ConfidentialSwapQueued {
     pub user: Pubkey,
     pub vault: Pubkey,
+    pub dest_vault: Pubkey,
+    pub swap_request: Pubkey,
     pub computation_offset: u64,
     pub current_output: u64,
+    pub liquidity_depth: u64,
+    pub oracle_price: u64,
     pub timestamp: i64,
 }
 
 #[event]
 pub struct ConfidentialSwapResult {
+    pub swap_request: Pubkey,
     pub should_execute: bool,
+    pub max_child_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ConfidentialSwapSkipped {
+    pub swap_request: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct HybridOrderEvaluationQueued {
+    pub order: Pubkey,
+    pub cranker: Pubkey,
+    pub computation_offset: u64,
+    pub current_price: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct HybridOrderEvaluated {
+    pub order: Pubkey,
+    pub triggered: bool,
+    pub evaluations: u32,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LimitOrderEvaluationQueued {
+    pub order: Pubkey,
+    pub cranker: Pubkey,
+    pub computation_offset: u64,
+    pub current_price: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LimitOrderEvaluated {
+    pub order: Pubkey,
+    pub triggered: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LimitOrderExpired {
+    pub order: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MatchProposed {
+    pub order_a: Pubkey,
+    pub order_b: Pubkey,
+    pub proposer: Pubkey,
+    pub computation_offset: u64,
+    pub timestamp: i64,
+}
+
+/// Each fill decrypts with its order owner's key and nonce
+#[event]
+pub struct LimitOrdersMatched {
+    pub order_a: Pubkey,
+    pub order_b: Pubkey,
+    pub encrypted_fill_a: [u8; 32],
+    pub fill_nonce_a: u128,
+    pub encrypted_fill_b: [u8; 32],
+    pub fill_nonce_b: u128,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LimitOrderMatchRejected {
+    pub order_a: Pubkey,
+    pub order_b: Pubkey,
+    /// The MXE aborted rather than finding the orders did not cross
+    pub aborted: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuctionQueued {
+    pub auction: Pubkey,
+    pub base_vault: Pubkey,
+    pub quote_vault: Pubkey,
+    pub clearing_price: u64,
+    pub order_count: u8,
+    pub computation_offset: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuctionSettled {
+    pub auction: Pubkey,
+    pub clearing_price: u64,
+    pub matched_volume: u64,
+    /// The MXE aborted; the batch's orders were returned to Open
+    pub aborted: bool,
+    pub timestamp: i64,
+}
+
+/// `encrypted_fill` decrypts with the order owner's key and `fill_nonce`
+#[event]
+pub struct SealedOrderSettled {
+    pub order: Pubkey,
+    pub auction: Pubkey,
+    pub encrypted_fill: [u8; 32],
+    pub fill_nonce: u128,
+}
+
+#[event]
+pub struct DcaExecutionQueued {
+    pub dca: Pubkey,
+    pub cranker: Pubkey,
+    pub computation_offset: u64,
+    pub current_price: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DcaProcessed {
+    pub dca: Pubkey,
+    /// Revealed swap amount approved for settlement (0 = interval skipped)
+    pub amount: u64,
+    pub completed: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DcaNettingQueued {
+    pub dca: Pubkey,
+    pub limit_order: Pubkey,
+    pub cranker: Pubkey,
+    pub computation_offset: u64,
+    pub current_price: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DcaNetted {
+    pub dca: Pubkey,
+    pub limit_order: Pubkey,
+    /// Revealed amount filled internally against the limit order
+    pub netted: u64,
+    /// Revealed amount left for `settle_dca_swap`
+    pub residual: u64,
+    pub completed: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RfqSelectionQueued {
+    pub rfq_request: Pubkey,
+    pub quote_count: u8,
+    pub computation_offset: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RfqQuoteSelected {
+    pub rfq_request: Pubkey,
+    /// Best quote's maker (default if no quote met the limit)
+    pub winner: Pubkey,
+    pub winning_amount: u64,
+    pub filled: bool,
+}
+
+#[event]
+pub struct TwapExecutionQueued {
+    pub twap: Pubkey,
+    pub cranker: Pubkey,
+    pub computation_offset: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TwapProcessed {
+    pub twap: Pubkey,
+    /// Revealed slice size approved for settlement (0 = nothing left)
+    pub amount: u64,
+    pub completed: bool,
+    /// The MXE aborted; the interval was skipped
+    pub aborted: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct StopOrderEvaluationQueued {
+    pub order: Pubkey,
+    pub cranker: Pubkey,
+    pub computation_offset: u64,
+    pub current_price: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct StopOrderEvaluated {
+    pub order: Pubkey,
+    pub triggered: bool,
+    /// Revealed amount to sell (0 unless triggered)
+    pub trigger_amount: u64,
+    /// The MXE aborted; the order is Active again
+    pub aborted: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct StopOrderExpired {
+    pub order: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ReferralEpochOpened {
+    pub epoch: u64,
+    pub timestamp: i64,
+}
+
+/// Aggregate referral fees per slot for an ended epoch
+#[event]
+pub struct ReferralEpochRevealed {
+    pub epoch: u64,
+    pub totals: [u64; state::REFERRAL_SLOTS],
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ConfidentialSwapFailed {
+    pub swap_request: Pubkey,
+    pub computation_offset: u64,
+    pub failure: state::ComputationFailure,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ConfidentialSwapRetried {
+    pub swap_request: Pubkey,
+    pub computation_offset: u64,
+    pub retries: u8,
+    pub current_output: u64,
+    pub liquidity_depth: u64,
+    pub oracle_price: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PositionInitialized {
+    pub position: Pubkey,
+    pub owner: Pubkey,
+    pub vault: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct WithdrawalComputed {
+    pub position: Pubkey,
+    pub owner: Pubkey,
+    /// Balance encrypted to the owner's key; decrypt with `quote_nonce`
+    pub withdrawal_quote: [u8; 32],
+    pub quote_nonce: u128,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CallbackReplayed {
+    /// Account the callback would have written
+    pub target: Pubkey,
+    /// Computation account whose output was already applied
+    pub computation: Pubkey,
+}
+
+#[event]
+pub struct BalanceAttested {
+    pub attestation: Pubkey,
+    pub position: Pubkey,
+    pub threshold: u64,
+    pub sufficient: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PositionCleared {
+    pub position: Pubkey,
+    pub owner: Pubkey,
+    pub vault: Pubkey,
+    pub amount: u64,
     pub timestamp: i64,
 }
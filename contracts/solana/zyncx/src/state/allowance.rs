@@ -0,0 +1,111 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ZyncxError;
+
+/// Maximum number of note-viewing keys one allowance policy can cover
+pub const MAX_ALLOWANCE_VIEWING_KEYS: usize = 16;
+
+/// Policy hash of a note bound to no allowance policy
+pub const UNBOUND_POLICY_HASH: [u8; 32] = [0u8; 32];
+
+/// Limits an organisation sets for one allowance policy
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AllowanceParams {
+    /// Most that all traders under the policy can withdraw per epoch
+    pub epoch_limit: u64,
+    /// Epoch length in seconds
+    pub epoch_seconds: i64,
+    /// X25519 viewing keys of the notes the policy covers
+    pub viewing_keys: Vec<[u8; 32]>,
+}
+
+impl AllowanceParams {
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.epoch_limit > 0
+                && self.epoch_seconds > 0
+                && !self.viewing_keys.is_empty()
+                && self.viewing_keys.len() <= MAX_ALLOWANCE_VIEWING_KEYS
+                && !self.viewing_keys.contains(&[0u8; 32]),
+            ZyncxError::InvalidAllowancePolicy
+        );
+        Ok(())
+    }
+}
+
+/// Per-epoch withdrawal allowance an organisation attaches to the notes it
+/// issues to its traders' viewing keys. A deposit made with the policy binds
+/// its `policy_hash` into the note's commitment, and every spend proof takes
+/// the policy hash as a public input that the circuit recomputes the
+/// commitment with: a bound note only proves in a withdrawal that passes
+/// (and is charged against) its policy, and never in a batch withdrawal,
+/// swap or private transfer, whose circuits only take unbound notes. The org
+/// keeps custody shielded; traders only ever see their own notes.
+/// seeds = [b"allowance_policy", vault, policy_id]
+#[account]
+pub struct AllowancePolicy {
+    pub bump: u8,
+    pub vault: Pubkey,
+    pub policy_id: u64,
+    /// Org admin; the only account that can change the policy
+    pub admin: Pubkey,
+    pub epoch_limit: u64,
+    pub epoch_seconds: i64,
+    pub viewing_keys: Vec<[u8; 32]>,
+    /// Start of the epoch `epoch_spent` is counted over
+    pub epoch_start: i64,
+    pub epoch_spent: u64,
+    pub total_withdrawn: u64,
+    pub created_at: i64,
+}
+
+impl AllowancePolicy {
+    pub const INIT_SPACE: usize = 8 + // discriminator
+        1 +  // bump
+        32 + // vault
+        8 +  // policy_id
+        32 + // admin
+        8 +  // epoch_limit
+        8 +  // epoch_seconds
+        4 + (32 * MAX_ALLOWANCE_VIEWING_KEYS) + // viewing_keys vec
+        8 +  // epoch_start
+        8 +  // epoch_spent
+        8 +  // total_withdrawn
+        8;   // created_at
+
+    pub fn apply(&mut self, params: AllowanceParams) {
+        self.epoch_limit = params.epoch_limit;
+        self.epoch_seconds = params.epoch_seconds;
+        self.viewing_keys = params.viewing_keys;
+    }
+
+    /// keccak(vault || policy_id), top byte cleared so it is a valid BN254
+    /// field element. Bound notes carry it in their commitment, so it never
+    /// changes with the policy's parameters.
+    pub fn policy_hash(&self) -> [u8; 32] {
+        use solana_program::keccak;
+
+        let mut hash = keccak::hashv(&[self.vault.as_ref(), &self.policy_id.to_le_bytes()]).0;
+        hash[0] = 0;
+        hash
+    }
+
+    /// Count `amount` against the current epoch, starting a new epoch once
+    /// the previous one has run out
+    pub fn charge(&mut self, amount: u64, now: i64) -> Result<()> {
+        if now >= self.epoch_start.saturating_add(self.epoch_seconds) {
+            let elapsed = now.saturating_sub(self.epoch_start);
+            self.epoch_start = now - elapsed % self.epoch_seconds;
+            self.epoch_spent = 0;
+        }
+
+        let spent = self
+            .epoch_spent
+            .checked_add(amount)
+            .filter(|spent| *spent <= self.epoch_limit)
+            .ok_or(ZyncxError::AllowanceExceeded)?;
+        self.epoch_spent = spent;
+        self.total_withdrawn = self.total_withdrawn.saturating_add(amount);
+        Ok(())
+    }
+}
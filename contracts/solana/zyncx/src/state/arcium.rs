@@ -1,5 +1,8 @@
 use anchor_lang::prelude::*;
 
+use super::PriceGuards;
+use crate::errors::ZyncxError;
+
 // ============================================================================
 // ARCIUM MXE (Multi-party eXecution Environment) STATE
 // ============================================================================
@@ -85,11 +88,12 @@ impl EncryptedStrategy {
 }
 
 /// State account tracking a queued Arcium computation
+/// PDA seeds: [b"computation_request", user, request_id (le bytes)]
 #[account]
 pub struct ComputationRequest {
     /// Bump seed for PDA
     pub bump: u8,
-    /// Unique request ID
+    /// Request ID, unique per user (from `UserRequestCounter`)
     pub request_id: u64,
     /// User who initiated the request
     pub user: Pubkey,
@@ -124,6 +128,8 @@ pub struct ComputationRequest {
 }
 
 impl ComputationRequest {
+    pub const SEED_PREFIX: &'static [u8] = b"computation_request";
+
     pub const BASE_SPACE: usize = 8 + // discriminator
         1 +   // bump
         8 +   // request_id
@@ -162,7 +168,8 @@ pub struct ArciumConfig {
     pub mxe_address: Pubkey,
     /// Fee for using confidential computation (in lamports)
     pub computation_fee: u64,
-    /// Request counter for unique IDs
+    /// Deprecated: request IDs are allocated per user by `UserRequestCounter`
+    /// so unrelated users don't write-lock this account. Kept for layout.
     pub request_counter: u64,
     /// Computation timeout in seconds
     pub timeout_seconds: i64,
@@ -174,6 +181,13 @@ pub struct ArciumConfig {
     pub min_amount: u64,
     /// Maximum amount for confidential operations
     pub max_amount: u64,
+    /// Oldest oracle price (seconds) swaps and limit orders are queued on
+    pub max_price_age_seconds: i64,
+    /// Widest oracle confidence interval, in bps of the price, swaps and
+    /// limit orders are queued on
+    pub max_price_confidence_bps: u16,
+    /// Layout version (`CURRENT_ACCOUNT_VERSION`); see `migrate_account`
+    pub version: u8,
 }
 
 impl ArciumConfig {
@@ -187,7 +201,92 @@ impl ArciumConfig {
         1 +   // swaps_enabled
         1 +   // limit_orders_enabled
         8 +   // min_amount
-        8;    // max_amount
+        8 +   // max_amount
+        8 +   // max_price_age_seconds
+        2 +   // max_price_confidence_bps
+        1;    // version
+
+    pub fn price_guards(&self) -> PriceGuards {
+        PriceGuards {
+            max_age_seconds: self.max_price_age_seconds,
+            max_confidence_bps: self.max_price_confidence_bps,
+        }
+    }
+}
+
+/// Lamports paid as `ArciumConfig::computation_fee`. A confidential swap's
+/// fee is held until its callback lands, then forwarded to `collected`;
+/// cancelling or expiring the request refunds it instead. The config
+/// authority withdraws what was collected.
+/// Singleton PDA: seeds = [b"computation_fees"]
+#[account]
+pub struct ComputationFeeEscrow {
+    pub bump: u8,
+    /// Fees of computations still in flight
+    pub held: u64,
+    /// Fees of finished computations, withdrawable by the config authority
+    pub collected: u64,
+    /// Lifetime total withdrawn
+    pub withdrawn: u64,
+}
+
+impl ComputationFeeEscrow {
+    pub const INIT_SPACE: usize = 8 + // discriminator
+        1 +   // bump
+        8 +   // held
+        8 +   // collected
+        8;    // withdrawn
+
+    pub fn hold(&mut self, fee: u64) -> Result<()> {
+        self.held = self
+            .held
+            .checked_add(fee)
+            .ok_or(ZyncxError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    /// Move a held fee to `collected` once its computation has run
+    pub fn forward(&mut self, fee: u64) -> Result<()> {
+        self.release(fee)?;
+        self.collect(fee)
+    }
+
+    /// Take a held fee out of the books ahead of refunding it
+    pub fn release(&mut self, fee: u64) -> Result<()> {
+        self.held = self
+            .held
+            .checked_sub(fee)
+            .ok_or(ZyncxError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    /// Book a fee as collected straight away
+    pub fn collect(&mut self, fee: u64) -> Result<()> {
+        self.collected = self
+            .collected
+            .checked_add(fee)
+            .ok_or(ZyncxError::ArithmeticOverflow)?;
+        Ok(())
+    }
+}
+
+/// Per-user request counter used to key `ComputationRequest` PDAs.
+/// PDA seeds: [b"user_request_counter", user]
+#[account]
+pub struct UserRequestCounter {
+    /// Bump seed for PDA
+    pub bump: u8,
+    /// Owner of the counter
+    pub user: Pubkey,
+    /// Next request ID to hand out
+    pub request_counter: u64,
+}
+
+impl UserRequestCounter {
+    pub const INIT_SPACE: usize = 8 + // discriminator
+        1 +   // bump
+        32 +  // user
+        8;    // request_counter
 
     pub fn next_request_id(&mut self) -> u64 {
         let id = self.request_counter;
@@ -21,6 +21,8 @@ use anchor_lang::prelude::*;
 /// [41..73]   authority (Pubkey, 32 bytes)
 /// [73..89]   nonce (u128, 16 bytes)
 /// [89..185]  encrypted_state (3 × 32 bytes = 96 bytes encrypted state)
+/// [185..217] last_computation (Pubkey, 32 bytes)
+/// [217]      version (1 byte)
 #[account]
 #[derive(InitSpace)]
 pub struct EncryptedVaultAccount {
@@ -36,6 +38,27 @@ pub struct EncryptedVaultAccount {
     /// Encrypted vault state: [pending_deposits, total_liquidity, total_deposited]
     /// Each is an Enc<Mxe, u64> - 32 bytes per ciphertext
     pub encrypted_state: [[u8; 32]; 3],
+
+    /// Computation whose callback was applied last; a redelivered callback
+    /// for the same computation is ignored
+    pub last_computation: Pubkey,
+
+    /// Layout version (`CURRENT_ACCOUNT_VERSION`); see `migrate_account`
+    pub version: u8,
+}
+
+impl EncryptedVaultAccount {
+    /// Byte offset to encrypted state
+    /// = 8 (discriminator) + 1 (bump) + 32 (token_mint) + 32 (authority) + 16 (nonce)
+    pub const ENCRYPTED_STATE_OFFSET: usize = 8 + 1 + 32 + 32 + 16;
+
+    /// Size of encrypted state in bytes (3 ciphertexts × 32 bytes)
+    pub const ENCRYPTED_STATE_SIZE: usize = 32 * 3;
+
+    /// Address of the encrypted vault for `token_mint`
+    pub fn address(token_mint: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"enc_vault", token_mint.as_ref()], &crate::ID).0
+    }
 }
 
 /// Encrypted user position - stores MXE-encrypted user-specific data
@@ -49,6 +72,12 @@ pub struct EncryptedVaultAccount {
 /// [137..153] nonce (u128, 16 bytes)
 /// [153..161] created_at (i64, 8 bytes)
 /// [161]      is_active (bool, 1 byte)
+/// [162..194] withdrawal_quote (32 bytes)
+/// [194..226] quote_pubkey (32 bytes)
+/// [226..242] quote_nonce (u128, 16 bytes)
+/// [242..250] redeemable_amount (u64, 8 bytes)
+/// [250..282] last_computation (Pubkey, 32 bytes)
+/// [282]      version (1 byte)
 #[account]
 pub struct EncryptedUserPosition {
     /// PDA bump seed
@@ -70,6 +99,23 @@ pub struct EncryptedUserPosition {
     
     /// Whether this position is active
     pub is_active: bool,
+    
+    /// Balance re-encrypted to the owner by `compute_withdrawal`
+    pub withdrawal_quote: [u8; 32],
+    
+    /// Owner's key and nonce the quote is encrypted under
+    pub quote_pubkey: [u8; 32],
+    pub quote_nonce: u128,
+    
+    /// Balance revealed by `clear_position`, owed to the owner
+    pub redeemable_amount: u64,
+
+    /// Computation whose callback was applied last; a redelivered callback
+    /// for the same computation is ignored
+    pub last_computation: Pubkey,
+
+    /// Layout version (`CURRENT_ACCOUNT_VERSION`); see `migrate_account`
+    pub version: u8,
 }
 
 impl EncryptedUserPosition {
@@ -81,10 +127,53 @@ impl EncryptedUserPosition {
     pub const ENCRYPTED_STATE_SIZE: usize = 32 * 2;
     
     /// Total account space
-    pub const INIT_SPACE: usize = 1 + 32 + 32 + (32 * 2) + 16 + 8 + 1;
+    pub const INIT_SPACE: usize = 1 + 32 + 32 + (32 * 2) + 16 + 8 + 1 + 32 + 32 + 16 + 8 + 32 + 1;
+}
+
+/// Public answer to "does this position hold at least `threshold`?",
+/// produced by the `verify_sufficient_balance` circuit. Other programs can
+/// read it to gate features without learning the balance itself.
+/// PDA: seeds = [b"balance_attestation", position, threshold.to_le_bytes()]
+#[account]
+pub struct BalanceAttestation {
+    /// PDA bump seed
+    pub bump: u8,
+    /// Position the check ran against
+    pub position: Pubkey,
+    /// Position owner, who requested the check
+    pub owner: Pubkey,
+    /// Encrypted vault the position belongs to
+    pub vault: Pubkey,
+    /// Amount the balance was compared against
+    pub threshold: u64,
+    /// Revealed result; only meaningful once `pending` is false
+    pub sufficient: bool,
+    /// Set while a check is queued, cleared by the callback
+    pub pending: bool,
+    /// Computation that produced (or will produce) the result
+    pub computation_offset: u64,
+    /// When the result was last written
+    pub attested_at: i64,
+
+    /// Computation whose callback was applied last; a redelivered callback
+    /// for the same computation is ignored
+    pub last_computation: Pubkey,
+}
+
+impl BalanceAttestation {
+    /// Total account space
+    pub const INIT_SPACE: usize = 1 + 32 + 32 + 32 + 8 + 1 + 1 + 8 + 8 + 32;
 }
 
 /// Encrypted swap request - queued computation waiting for MPC execution
+///
+/// Memory layout (fixed prefix, see `layout::swap_request`):
+/// [0..8]     Anchor discriminator
+/// [8]        bump (1 byte)
+/// [9..41]    user (Pubkey, 32 bytes)
+/// [41..73]   source_vault (Pubkey, 32 bytes)
+/// [73..105]  dest_vault (Pubkey, 32 bytes)
+/// [329]      status (SwapRequestStatus, 1 byte)
 #[account]
 pub struct EncryptedSwapRequest {
     /// PDA bump seed
@@ -98,7 +187,7 @@ pub struct EncryptedSwapRequest {
     /// Computation offset (unique identifier)
     pub computation_offset: u64,
     
-    /// Encrypted swap bounds: [min_out, max_slippage_bps, aggressive_flag]
+    /// Encrypted swap bounds: [min_out, referrer code, max_impact_bps]
     /// This is the user's encrypted trading strategy
     pub encrypted_bounds: [[u8; 32]; 3],
     
@@ -131,6 +220,53 @@ pub struct EncryptedSwapRequest {
     
     /// Result nonce
     pub result_nonce: u128,
+    
+    /// Lamports escrowed in this PDA to pay the keeper that executes the swap
+    pub keeper_escrow: u64,
+    
+    /// Revealed MPC decision (valid once status is Completed)
+    pub should_execute: bool,
+    
+    /// A request still Pending at this time can be expired by anyone
+    pub expires_at: i64,
+    
+    /// Offset of the most recent computation (differs from
+    /// `computation_offset`, which seeds the PDA, after a retry)
+    pub latest_computation_offset: u64,
+    
+    /// Times an aborted computation was re-queued
+    pub retries: u8,
+    
+    /// Pyth feed the decision price was read from
+    pub price_feed: Pubkey,
+    
+    /// Oracle price of the source token when the computation was queued
+    /// (`ORDER_PRICE_DECIMALS`)
+    pub oracle_price: u64,
+    
+    /// Realized price from the settlement's balance diffs, same scale
+    /// (0 until executed)
+    pub execution_price: u64,
+
+    /// Computation whose callback was applied last; a redelivered callback
+    /// for the same computation is ignored
+    pub last_computation: Pubkey,
+
+    /// Route liquidity depth the latest computation was sized against
+    pub liquidity_depth: u64,
+
+    /// Largest child order that keeps price impact under the user's
+    /// encrypted threshold (revealed by MPC, valid once Completed)
+    pub max_child_amount: u64,
+
+    /// Why the latest computation produced no decision (set once Failed or
+    /// Expired, cleared on retry)
+    pub failure: Option<ComputationFailure>,
+
+    /// Computation fee of the in-flight computation, held in the
+    /// `ComputationFeeEscrow` until its callback lands (0 once forwarded or
+    /// refunded)
+    pub computation_fee: u64,
 }
 
 impl EncryptedSwapRequest {
@@ -138,9 +274,118 @@ impl EncryptedSwapRequest {
     pub const ENCRYPTED_BOUNDS_SIZE: usize = 32 * 3;
     
     /// Total account space
-    pub const INIT_SPACE: usize = 1 + 32 + 32 + 32 + 8 + (32 * 3) + 16 + 32 + 8 + 32 + 32 + 1 + 8 + 8 + (32 * 2) + 16;
+    pub const INIT_SPACE: usize = 1 + 32 + 32 + 32 + 8 + (32 * 3) + 16 + 32 + 8 + 32 + 32 + 1 + 8 + 8 + (32 * 2) + 16 + 8 + 1 + 8 + 8 + 1 + 32 + 8 + 8 + 32 + 8 + 8 + 2 + 8;
+
+    /// No further state change will happen; the account can be closed
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self.status,
+            SwapRequestStatus::Completed
+                | SwapRequestStatus::Failed
+                | SwapRequestStatus::Expired
+                | SwapRequestStatus::Cancelled
+                | SwapRequestStatus::Executed
+        )
+    }
+}
+
+/// Requests remembered per user in `UserRequestIndex`
+pub const USER_REQUEST_INDEX_SLOTS: usize = 16;
+
+/// A confidential swap request in a `UserRequestIndex` and its last status
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct IndexedRequest {
+    pub request: Pubkey,
+    pub status: SwapRequestStatus,
+}
+
+impl IndexedRequest {
+    pub const SIZE: usize = 32 + 1;
+
+    const EMPTY: Self = Self {
+        request: Pubkey::new_from_array([0u8; 32]),
+        status: SwapRequestStatus::Pending,
+    };
 }
 
+/// Ring buffer of a user's most recent confidential swap requests, so
+/// wallets and keepers can find actionable ones without scanning program
+/// accounts. Written on queue, retry, callback, cancel, expiry and
+/// execution; a closed request is dropped. Once full, the oldest entry is
+/// overwritten.
+/// PDA seeds: [b"user_request_index", user]
+#[account]
+pub struct UserRequestIndex {
+    pub bump: u8,
+    pub user: Pubkey,
+    /// Slot the next new request is written to
+    pub head: u8,
+    /// Default request key = empty slot
+    pub entries: [IndexedRequest; USER_REQUEST_INDEX_SLOTS],
+}
+
+impl UserRequestIndex {
+    pub const INIT_SPACE: usize = 8 + // discriminator
+        1 +  // bump
+        32 + // user
+        1 +  // head
+        IndexedRequest::SIZE * USER_REQUEST_INDEX_SLOTS; // entries
+
+    /// Bind a freshly created index to its user (init_if_needed)
+    pub fn ensure_initialized(&mut self, user: Pubkey, bump: u8) {
+        if self.user == Pubkey::default() {
+            self.bump = bump;
+            self.user = user;
+            self.head = 0;
+            self.entries = [IndexedRequest::EMPTY; USER_REQUEST_INDEX_SLOTS];
+        }
+    }
+
+    /// Record `request` at `status`, taking the oldest slot if it is new
+    pub fn record(&mut self, request: Pubkey, status: SwapRequestStatus) {
+        if self.update(request, status) {
+            return;
+        }
+        self.entries[self.head as usize] = IndexedRequest { request, status };
+        self.head = ((self.head as usize + 1) % USER_REQUEST_INDEX_SLOTS) as u8;
+    }
+
+    /// Update the status of `request` if it is still indexed
+    pub fn update(&mut self, request: Pubkey, status: SwapRequestStatus) -> bool {
+        match self.entries.iter_mut().find(|entry| entry.request == request) {
+            Some(entry) => {
+                entry.status = status;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Forget a closed request
+    pub fn remove(&mut self, request: Pubkey) {
+        if let Some(entry) = self.entries.iter_mut().find(|entry| entry.request == request) {
+            *entry = IndexedRequest::EMPTY;
+        }
+    }
+}
+
+/// Market snapshot a confidential swap is evaluated against, supplied by
+/// whoever queues the computation
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct SwapQuote {
+    /// Output the full amount would receive on the current route
+    pub current_output: u64,
+    /// Input-side liquidity depth of the route (AMM reserve or the depth
+    /// behind a Jupiter quote), used to size child orders
+    pub liquidity_depth: u64,
+}
+
+/// How long a confidential swap may wait for its MXE result
+pub const SWAP_REQUEST_TTL_SECONDS: i64 = 10 * 60;
+
+/// Re-queues allowed for a swap request whose computation aborted
+pub const MAX_SWAP_REQUEST_RETRIES: u8 = 3;
+
 /// Status of an encrypted swap request
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
 pub enum SwapRequestStatus {
@@ -156,6 +401,8 @@ pub enum SwapRequestStatus {
     Expired,
     /// Request cancelled by user
     Cancelled,
+    /// Approved swap settled through `execute_approved_swap`
+    Executed,
 }
 
 impl Default for SwapRequestStatus {
@@ -164,7 +411,29 @@ impl Default for SwapRequestStatus {
     }
 }
 
+/// Why an MXE computation produced no usable output
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ComputationFailure {
+    /// No callback arrived before the request expired
+    ClusterTimeout,
+    /// The callback's output did not verify against the cluster's signature
+    OutputVerificationFailed,
+    /// The cluster aborted the circuit, e.g. on a failed assertion
+    CircuitAssertion,
+    /// The request expired with less escrowed than the configured
+    /// computation fee, so no keeper was paid to see it through
+    InsufficientFee,
+}
+
 /// Encrypted limit order
+///
+/// Memory layout (fixed prefix, see `layout::limit_order`):
+/// [0..8]     Anchor discriminator
+/// [8]        bump (1 byte)
+/// [9..41]    user (Pubkey, 32 bytes)
+/// [41..73]   source_vault (Pubkey, 32 bytes)
+/// [73..105]  dest_vault (Pubkey, 32 bytes)
+/// [257]      status (LimitOrderStatus, 1 byte)
 #[account]
 pub struct EncryptedLimitOrder {
     /// PDA bump seed
@@ -193,6 +462,28 @@ pub struct EncryptedLimitOrder {
     
     /// Created timestamp
     pub created_at: i64,
+    
+    /// User-chosen order id (PDA seed)
+    pub order_id: u64,
+    
+    /// Pyth price account evaluated against, fixed when the order is placed
+    pub price_feed: Pubkey,
+    
+    /// Timestamp of the last completed evaluation
+    pub last_evaluated_at: i64,
+
+    /// Computation whose callback was applied last; a redelivered callback
+    /// for the same computation is ignored
+    pub last_computation: Pubkey,
+
+    /// Counterparty order of a peer-to-peer match (default until Matched)
+    pub matched_with: Pubkey,
+
+    /// Matched size as Enc<Shared, u64> to the owner's key
+    pub encrypted_fill: [u8; 32],
+
+    /// Nonce for `encrypted_fill`
+    pub fill_nonce: u128,
 }
 
 impl EncryptedLimitOrder {
@@ -200,9 +491,25 @@ impl EncryptedLimitOrder {
     pub const ENCRYPTED_PARAMS_SIZE: usize = 32 * 3;
     
     /// Total account space
-    pub const INIT_SPACE: usize = 1 + 32 + 32 + 32 + (32 * 3) + 16 + 32 + 8 + 1 + 8;
+    pub const INIT_SPACE: usize = 1 + 32 + 32 + 32 + (32 * 3) + 16 + 32 + 8 + 1 + 8 + 8 + 32 + 8 + 32 + 32 + 32 + 16;
+
+    /// Whether a keeper crank may queue another evaluation at `now`
+    pub fn is_due(&self, now: i64) -> bool {
+        self.status == LimitOrderStatus::Active
+            && now < self.expires_at
+            && now >= self.last_evaluated_at.saturating_add(LIMIT_ORDER_EVALUATION_INTERVAL)
+    }
 }
 
+/// Orders evaluated per `crank_limit_orders` computation
+pub const LIMIT_ORDER_CRANK_BATCH: usize = 4;
+
+/// Minimum seconds between keeper-cranked evaluations of one order
+pub const LIMIT_ORDER_EVALUATION_INTERVAL: i64 = 30;
+
+/// Lamports paid to the cranker per order evaluated, out of accrued SOL fees
+pub const CRANK_TIP_LAMPORTS: u64 = 5_000;
+
 /// Status of a limit order
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
 pub enum LimitOrderStatus {
@@ -214,6 +521,12 @@ pub enum LimitOrderStatus {
     Cancelled,
     /// Order expired
     Expired,
+    /// Evaluation queued with the MXE
+    Evaluating,
+    /// Peer-to-peer match against another order queued with the MXE
+    Matching,
+    /// Crossed with `matched_with`; the fill is encrypted to the owner
+    Matched,
 }
 
 impl Default for LimitOrderStatus {
@@ -222,7 +535,85 @@ impl Default for LimitOrderStatus {
     }
 }
 
+/// Encrypted hybrid TWAP/limit order
+///
+/// The MXE re-evaluates the order on every crank: it takes liquidity as soon
+/// as the oracle price beats the hidden limit, and falls back to market
+/// execution once the hidden maximum duration has elapsed. Crankers only learn
+/// whether the order triggered, never why.
+#[account]
+pub struct EncryptedHybridOrder {
+    /// PDA bump seed
+    pub bump: u8,
+    /// User who created the order
+    pub user: Pubkey,
+    /// Vault for the source token
+    pub source_vault: Pubkey,
+    /// Vault for the destination token
+    pub dest_vault: Pubkey,
+    /// User-chosen order id (PDA seed)
+    pub order_id: u64,
+
+    /// Encrypted order params: [limit_price, max_duration (seconds), is_buy (as u64)]
+    /// limit_price uses `ORDER_PRICE_DECIMALS`
+    pub encrypted_params: [[u8; 32]; 3],
+
+    /// Nonce for encryption
+    pub params_nonce: u128,
+
+    /// Client's X25519 public key
+    pub client_pubkey: [u8; 32],
+
+    /// Pyth price account evaluated against, fixed when the order is placed
+    pub price_feed: Pubkey,
+
+    /// Order status
+    pub status: HybridOrderStatus,
+
+    /// Created timestamp (start of the TWAP window)
+    pub created_at: i64,
+
+    /// Timestamp of the last completed evaluation
+    pub last_evaluated_at: i64,
+
+    /// Number of completed evaluations
+    pub evaluations: u32,
+
+    /// Computation whose callback was applied last; a redelivered callback
+    /// for the same computation is ignored
+    pub last_computation: Pubkey,
+}
+
+impl EncryptedHybridOrder {
+    pub const ENCRYPTED_PARAMS_OFFSET: usize = 8 + 1 + 32 + 32 + 32 + 8;
+    pub const ENCRYPTED_PARAMS_SIZE: usize = 32 * 3;
+
+    /// Total account space
+    pub const INIT_SPACE: usize = 1 + 32 + 32 + 32 + 8 + (32 * 3) + 16 + 32 + 32 + 1 + 8 + 8 + 4 + 32;
+}
+
+/// Status of a hybrid order
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum HybridOrderStatus {
+    /// Waiting for the next crank
+    Active,
+    /// Evaluation queued with the MXE
+    Evaluating,
+    /// MXE decided to execute (limit hit or duration elapsed)
+    Triggered,
+    /// Order cancelled by user
+    Cancelled,
+}
+
 /// DCA (Dollar Cost Averaging) encrypted configuration
+///
+/// Memory layout (fixed prefix, see `layout::dca`):
+/// [0..8]     Anchor discriminator
+/// [8]        bump (1 byte)
+/// [9..41]    user (Pubkey, 32 bytes)
+/// [41..73]   source_vault (Pubkey, 32 bytes)
+/// [73..105]  dest_vault (Pubkey, 32 bytes)
+/// [265]      status (DCAStatus, 1 byte)
 #[account]
 pub struct EncryptedDCAConfig {
     /// PDA bump seed
@@ -257,6 +648,29 @@ pub struct EncryptedDCAConfig {
     
     /// Total swaps executed
     pub swaps_executed: u16,
+    
+    /// User-chosen DCA id (PDA seed)
+    pub dca_id: u64,
+    
+    /// Pyth price account for the source token, fixed at creation
+    pub price_feed: Pubkey,
+    
+    /// Account receiving the swapped tokens
+    pub recipient: Pubkey,
+    
+    /// Swap amount approved by the last `process_dca` run, awaiting settlement
+    pub pending_amount: u64,
+    
+    /// Whether a `process_dca` computation is in flight
+    pub computation_pending: bool,
+
+    /// Computation whose callback was applied last; a redelivered callback
+    /// for the same computation is ignored
+    pub last_computation: Pubkey,
+
+    /// Lamports netted against the owner's opposing limit orders instead of
+    /// swapped; they stay in the escrow and are refunded on cancel
+    pub netted_total: u64,
 }
 
 impl EncryptedDCAConfig {
@@ -264,9 +678,12 @@ impl EncryptedDCAConfig {
     pub const ENCRYPTED_PARAMS_SIZE: usize = 32 * 3;
     
     /// Total account space
-    pub const INIT_SPACE: usize = 1 + 32 + 32 + 32 + (32 * 3) + 16 + 32 + 8 + 8 + 1 + 8 + 2;
+    pub const INIT_SPACE: usize = 1 + 32 + 32 + 32 + (32 * 3) + 16 + 32 + 8 + 8 + 1 + 8 + 2 + 8 + 32 + 32 + 8 + 1 + 32 + 8;
 }
 
+/// Shortest interval a DCA may run at
+pub const MIN_DCA_INTERVAL_SECONDS: u64 = 60;
+
 /// Status of a DCA configuration
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
 pub enum DCAStatus {
@@ -285,3 +702,252 @@ impl Default for DCAStatus {
         Self::Active
     }
 }
+
+/// Confidential TWAP execution encrypted configuration
+///
+/// A large order is worked off in slices, one per interval. The MXE sizes
+/// each slice from the hidden total and maximum participation rate, so
+/// observers only see individual slices, never the full order size.
+#[account]
+pub struct EncryptedTWAPConfig {
+    /// PDA bump seed
+    pub bump: u8,
+    /// User who created the TWAP
+    pub user: Pubkey,
+    /// Source vault
+    pub source_vault: Pubkey,
+    /// Destination vault
+    pub dest_vault: Pubkey,
+
+    /// Encrypted TWAP params: [total_amount, max_participation_bps, executed_amount]
+    pub encrypted_params: [[u8; 32]; 3],
+
+    /// Nonce for encryption
+    pub params_nonce: u128,
+
+    /// Client's X25519 public key
+    pub client_pubkey: [u8; 32],
+
+    /// Interval between slices (seconds)
+    pub interval_seconds: u64,
+
+    /// Next execution timestamp
+    pub next_execution_at: i64,
+
+    /// TWAP status
+    pub status: TWAPStatus,
+
+    /// Created timestamp
+    pub created_at: i64,
+
+    /// Total slices settled
+    pub slices_executed: u16,
+
+    /// User-chosen TWAP id (PDA seed)
+    pub twap_id: u64,
+
+    /// Account receiving the swapped tokens
+    pub recipient: Pubkey,
+
+    /// Slice size approved by the last `process_twap` run, awaiting settlement
+    pub pending_amount: u64,
+
+    /// Whether a `process_twap` computation is in flight
+    pub computation_pending: bool,
+
+    /// Computation whose callback was applied last; a redelivered callback
+    /// for the same computation is ignored
+    pub last_computation: Pubkey,
+}
+
+impl EncryptedTWAPConfig {
+    pub const ENCRYPTED_PARAMS_OFFSET: usize = 8 + 1 + 32 + 32 + 32;
+    pub const ENCRYPTED_PARAMS_SIZE: usize = 32 * 3;
+
+    /// Total account space
+    pub const INIT_SPACE: usize = 1 + 32 + 32 + 32 + (32 * 3) + 16 + 32 + 8 + 8 + 1 + 8 + 2 + 8 + 32 + 8 + 1 + 32;
+}
+
+/// Shortest interval between TWAP slices
+pub const MIN_TWAP_INTERVAL_SECONDS: u64 = 30;
+
+/// Status of a TWAP execution
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TWAPStatus {
+    /// Slices are still being executed
+    Active,
+    /// The full amount has been sliced
+    Completed,
+}
+
+/// Encrypted stop order
+///
+/// The mirror image of a limit order: the stop level, size and direction
+/// stay hidden until the oracle price crosses the level. The SOL to sell is
+/// escrowed up front; once the MXE reports the stop hit, anyone can settle
+/// the revealed amount through Jupiter.
+#[account]
+pub struct EncryptedStopOrder {
+    /// PDA bump seed
+    pub bump: u8,
+    /// User who placed the order
+    pub user: Pubkey,
+    /// Vault for the source token
+    pub source_vault: Pubkey,
+    /// Vault for the destination token
+    pub dest_vault: Pubkey,
+
+    /// Encrypted order params: [stop_price, amount, is_buy (as u64)]
+    pub encrypted_params: [[u8; 32]; 3],
+
+    /// Nonce for encryption
+    pub params_nonce: u128,
+
+    /// Client's X25519 public key
+    pub client_pubkey: [u8; 32],
+
+    /// Expiration timestamp (plaintext)
+    pub expires_at: i64,
+
+    /// Order status
+    pub status: StopOrderStatus,
+
+    /// Created timestamp
+    pub created_at: i64,
+
+    /// User-chosen order id (PDA seed)
+    pub order_id: u64,
+
+    /// Pyth price account evaluated against, fixed when the order is placed
+    pub price_feed: Pubkey,
+
+    /// Account receiving the swapped tokens
+    pub recipient: Pubkey,
+
+    /// Timestamp of the last completed evaluation
+    pub last_evaluated_at: i64,
+
+    /// Amount revealed when the stop fired, awaiting settlement
+    pub trigger_amount: u64,
+
+    /// Oracle price the stop fired at
+    pub trigger_price: u64,
+
+    /// Price the pending evaluation was queued with
+    pub evaluation_price: u64,
+
+    /// Computation whose callback was applied last; a redelivered callback
+    /// for the same computation is ignored
+    pub last_computation: Pubkey,
+}
+
+impl EncryptedStopOrder {
+    pub const ENCRYPTED_PARAMS_OFFSET: usize = 8 + 1 + 32 + 32 + 32;
+    pub const ENCRYPTED_PARAMS_SIZE: usize = 32 * 3;
+
+    /// Total account space
+    pub const INIT_SPACE: usize = 1 + 32 + 32 + 32 + (32 * 3) + 16 + 32 + 8 + 1 + 8 + 8 + 32 + 32 + 8 + 8 + 8 + 8 + 32;
+}
+
+/// Status of a stop order
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum StopOrderStatus {
+    /// Waiting for the oracle price to cross the stop level
+    Active,
+    /// Evaluation queued with the MXE
+    Evaluating,
+    /// Stop hit; `trigger_amount` awaits settlement
+    Triggered,
+    /// Settled through Jupiter
+    Executed,
+    /// Expired before the stop was hit
+    Expired,
+}
+
+/// Sealed-bid order in a batch auction (dark pool)
+///
+/// Amount, limit price and side stay encrypted; an auction only reveals the
+/// batch's total matched volume. The owner's own fill comes back encrypted
+/// to `client_pubkey`.
+#[account]
+pub struct EncryptedSealedOrder {
+    /// PDA bump seed
+    pub bump: u8,
+    /// User who submitted the order
+    pub user: Pubkey,
+    /// Vault of the traded (base) asset
+    pub base_vault: Pubkey,
+    /// Vault of the pricing (quote) asset
+    pub quote_vault: Pubkey,
+    
+    /// Encrypted order params: [amount, limit_price, is_buy (as u64)]
+    pub encrypted_params: [[u8; 32]; 3],
+    
+    /// Nonce for encryption
+    pub params_nonce: u128,
+    
+    /// Client's X25519 public key
+    pub client_pubkey: [u8; 32],
+    
+    /// Pyth price account the clearing price is read from
+    pub price_feed: Pubkey,
+    
+    /// Order status
+    pub status: SealedOrderStatus,
+    
+    /// User-chosen order id (PDA seed)
+    pub order_id: u64,
+    
+    /// Created timestamp
+    pub created_at: i64,
+    
+    /// Auction the order was queued into (default until then)
+    pub auction: Pubkey,
+    
+    /// Fill encrypted to the owner, valid once settled
+    pub encrypted_fill: [u8; 32],
+    pub fill_nonce: u128,
+}
+
+impl EncryptedSealedOrder {
+    /// Total account space
+    pub const INIT_SPACE: usize = 1 + 32 + 32 + 32 + (32 * 3) + 16 + 32 + 32 + 1 + 8 + 8 + 32 + 32 + 16;
+}
+
+/// Orders cleared per `run_auction` computation
+pub const AUCTION_BATCH_SIZE: usize = 4;
+
+/// Status of a sealed order
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SealedOrderStatus {
+    /// Waiting for the next auction
+    Open,
+    /// Included in a queued auction
+    Auctioning,
+    /// Auction cleared; the encrypted fill is available
+    Settled,
+}
+
+/// One batch auction run over up to `AUCTION_BATCH_SIZE` sealed orders
+/// PDA: seeds = [b"batch_auction", computation_offset.to_le_bytes()]
+#[account]
+#[derive(InitSpace)]
+pub struct BatchAuction {
+    /// PDA bump seed
+    pub bump: u8,
+    /// Computation that clears the batch
+    pub computation_offset: u64,
+    /// Market the orders trade in
+    pub base_vault: Pubkey,
+    pub quote_vault: Pubkey,
+    /// Oracle price every order in the batch cleared against
+    pub clearing_price: u64,
+    /// Orders in the batch
+    pub order_count: u8,
+    /// Total volume matched between buys and sells (revealed by the MXE)
+    pub matched_volume: u64,
+    /// Set once the callback has applied the fills
+    pub settled: bool,
+    pub created_at: i64,
+    pub settled_at: i64,
+}
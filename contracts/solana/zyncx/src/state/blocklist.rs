@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+
+/// Maximum number of recipients the blocklist can hold
+pub const MAX_BLOCKED_RECIPIENTS: usize = 100;
+
+/// Recipients that withdrawals and swaps may not pay out to. Managed by the
+/// compliance role. Singleton PDA: seeds = [b"blocklist"]
+#[account]
+pub struct Blocklist {
+    pub bump: u8,
+    pub blocked: Vec<Pubkey>,
+}
+
+impl Blocklist {
+    pub const INIT_SPACE: usize = 8 + // discriminator
+        1 +  // bump
+        4 + (32 * MAX_BLOCKED_RECIPIENTS); // blocked vec
+
+    pub fn is_blocked(&self, recipient: &Pubkey) -> bool {
+        self.blocked.contains(recipient)
+    }
+
+    /// Reject `recipient` if it is on the blocklist. An uninitialized
+    /// blocklist account (never created on this deployment) blocks nobody.
+    pub fn check_recipient(blocklist: &AccountInfo, recipient: &Pubkey) -> Result<()> {
+        if blocklist.owner != &crate::ID || blocklist.data_is_empty() {
+            return Ok(());
+        }
+
+        let data = blocklist.try_borrow_data()?;
+        let blocklist = Blocklist::try_deserialize(&mut &data[..])?;
+        require!(
+            !blocklist.is_blocked(recipient),
+            crate::errors::ZyncxError::RecipientBlocked
+        );
+        Ok(())
+    }
+}
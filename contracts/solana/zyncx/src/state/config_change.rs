@@ -0,0 +1,52 @@
+use anchor_lang::prelude::*;
+
+use super::{FeeCurveParams, ProofSystem, TreasurySplitParams, TreeHash};
+
+/// Upper bound on the config timelock (30 days)
+pub const MAX_CONFIG_TIMELOCK_SECONDS: i64 = 30 * 86_400;
+
+/// Sensitive vault configuration that only changes through the timelock
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub enum ConfigChange {
+    /// Replace the withdrawal fee curve
+    FeeCurve(FeeCurveParams),
+    /// Point exclusion proofs at a different verifier program
+    ExclusionVerifier(Pubkey),
+    /// Shorten the timelock itself (lengthening is instant)
+    Timelock(i64),
+    /// Share of withdrawal fees routed to the insurance reserve (bps)
+    InsuranceShare(u16),
+    /// Re-split a native vault's SOL between hot and cold treasuries
+    TreasurySplit(TreasurySplitParams),
+    /// Switch how spend proofs are verified; Groth16 needs the circuits'
+    /// verification keys uploaded first
+    ProofSystem(ProofSystem),
+    /// Hash of the vault's future trees; the active tree switches too when
+    /// passed while still empty, otherwise at the next rotation
+    TreeHash(TreeHash),
+}
+
+impl ConfigChange {
+    pub const MAX_SIZE: usize = 1 + 32; // tag + largest variant (Pubkey)
+}
+
+/// A proposed config change waiting out the vault's timelock.
+/// One per vault: seeds = [b"pending_config", vault]
+#[account]
+pub struct PendingConfigChange {
+    pub bump: u8,
+    pub vault: Pubkey,
+    pub change: ConfigChange,
+    pub proposed_at: i64,
+    /// Earliest timestamp at which the change can be executed
+    pub eta: i64,
+}
+
+impl PendingConfigChange {
+    pub const INIT_SPACE: usize = 8 + // discriminator
+        1 +  // bump
+        32 + // vault
+        ConfigChange::MAX_SIZE + // change
+        8 +  // proposed_at
+        8;   // eta
+}
@@ -0,0 +1,24 @@
+use anchor_lang::prelude::*;
+
+/// Published exclusion (association set) root for a vault.
+/// The tree behind `root` lists commitments flagged as illicit; a withdrawal
+/// can optionally prove its note is not among them.
+#[account]
+pub struct ExclusionRoot {
+    pub bump: u8,
+    pub vault: Pubkey,
+    /// Merkle root of the exclusion set
+    pub root: [u8; 32],
+    /// Noir verifier program for the non-membership circuit
+    pub verifier_program: Pubkey,
+    pub updated_at: i64,
+}
+
+impl ExclusionRoot {
+    pub const INIT_SPACE: usize = 8 + // discriminator
+        1 +  // bump
+        32 + // vault
+        32 + // root
+        32 + // verifier_program
+        8;   // updated_at
+}
@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+
+use crate::state::TreeHash;
+
+/// Decimals of the faucet's test token
+pub const FAUCET_DECIMALS: u8 = 6;
+/// Most test tokens one airdrop can mint (1,000 tokens)
+pub const MAX_FAUCET_AIRDROP: u64 = 1_000 * 10u64.pow(FAUCET_DECIMALS as u32);
+/// Most pre-made notes one seeding call inserts
+pub const MAX_FAUCET_NOTES: u8 = 4;
+
+/// Devnet faucet: owns the test token mint and counts the notes it has
+/// seeded. Only compiled with the `faucet` feature.
+/// Singleton PDA: seeds = [b"faucet"]
+#[account]
+pub struct Faucet {
+    pub bump: u8,
+    /// Test token mint (seeds = [b"faucet_mint"]); the faucet PDA is its authority
+    pub mint: Pubkey,
+    pub total_airdropped: u64,
+    /// Notes seeded so far; the next note's secrets derive from this index
+    pub notes_seeded: u64,
+}
+
+impl Faucet {
+    pub const INIT_SPACE: usize = 8 + // discriminator
+        1 +  // bump
+        32 + // mint
+        8 +  // total_airdropped
+        8;   // notes_seeded
+
+    /// Published (secret, nullifier_secret) of faucet note `note_index`.
+    /// Anyone can recompute them, so faucet notes are spendable by anyone.
+    pub fn note_secrets(note_index: u64) -> ([u8; 32], [u8; 32]) {
+        use solana_program::keccak;
+
+        let index = note_index.to_le_bytes();
+        let mut secret = keccak::hashv(&[b"zyncx_faucet_secret", &index]).0;
+        let mut nullifier_secret = keccak::hashv(&[b"zyncx_faucet_nullifier", &index]).0;
+        // Keep both valid BN254 field elements
+        secret[0] = 0;
+        nullifier_secret[0] = 0;
+        (secret, nullifier_secret)
+    }
+
+    /// Precommitment of a note in a tree hashed with `tree_hash`, as clients
+    /// compute it for deposits
+    pub fn precommitment(
+        tree_hash: TreeHash,
+        secret: &[u8; 32],
+        nullifier_secret: &[u8; 32],
+    ) -> [u8; 32] {
+        tree_hash.precommitment(secret, nullifier_secret)
+    }
+}
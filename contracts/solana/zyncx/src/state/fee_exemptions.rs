@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+
+/// Maximum number of accounts the fee exemption list can hold
+pub const MAX_FEE_EXEMPTIONS: usize = 50;
+
+/// Integrations (aggregators, payment processors) whose withdrawals skip the
+/// protocol fee because they run their own fee structure. A withdrawal is
+/// exempt when its payer or recipient is listed; integrating programs list
+/// the PDA they sign with. Managed by the fee manager role.
+/// Singleton PDA: seeds = [b"fee_exemptions"]
+#[account]
+pub struct FeeExemptions {
+    pub bump: u8,
+    pub exempt: Vec<Pubkey>,
+}
+
+impl FeeExemptions {
+    pub const INIT_SPACE: usize = 8 + // discriminator
+        1 +  // bump
+        4 + (32 * MAX_FEE_EXEMPTIONS); // exempt vec
+
+    pub fn contains(&self, account: &Pubkey) -> bool {
+        self.exempt.contains(account)
+    }
+
+    /// Whether any of `accounts` is exempt. An uninitialized list account
+    /// (never created on this deployment) exempts nobody.
+    pub fn is_exempt(fee_exemptions: &AccountInfo, accounts: &[Pubkey]) -> Result<bool> {
+        if fee_exemptions.owner != &crate::ID || fee_exemptions.data_is_empty() {
+            return Ok(false);
+        }
+
+        let data = fee_exemptions.try_borrow_data()?;
+        let fee_exemptions = FeeExemptions::try_deserialize(&mut &data[..])?;
+        Ok(accounts.iter().any(|account| fee_exemptions.contains(account)))
+    }
+}
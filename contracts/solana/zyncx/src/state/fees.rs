@@ -0,0 +1,188 @@
+use anchor_lang::prelude::*;
+
+/// Number of buckets in the rolling withdrawal-volume window
+pub const FEE_WINDOW_BUCKETS: usize = 6;
+/// Default rolling window length (24 hours)
+pub const DEFAULT_FEE_WINDOW_SECONDS: i64 = 86_400;
+/// Hard ceiling on any withdrawal fee (10%)
+pub const MAX_WITHDRAWAL_FEE_BPS: u16 = 1_000;
+
+/// Fee curve parameters set through the config timelock
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct FeeCurveParams {
+    pub enabled: bool,
+    pub base_fee_bps: u16,
+    pub max_fee_bps: u16,
+    pub kink_utilization_bps: u16,
+    pub window_seconds: i64,
+}
+
+impl FeeCurveParams {
+    pub const SIZE: usize = 1 + 2 + 2 + 2 + 8;
+
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.base_fee_bps <= self.max_fee_bps
+                && self.max_fee_bps <= MAX_WITHDRAWAL_FEE_BPS
+                && self.kink_utilization_bps <= 10_000
+                && self.window_seconds >= FEE_WINDOW_BUCKETS as i64,
+            crate::errors::ZyncxError::InvalidFeeConfig
+        );
+        Ok(())
+    }
+}
+
+/// Per-vault withdrawal fee curve and rolling withdrawal volume.
+///
+/// Utilization is the window's withdrawal volume as a share of TVL. Below
+/// `kink_utilization_bps` the fee is `base_fee_bps`; above it the fee rises
+/// linearly to `max_fee_bps` at 100% utilization.
+#[account]
+pub struct VaultFeeState {
+    pub bump: u8,
+    pub vault: Pubkey,
+    /// When false, withdrawals are free; volume is still tracked
+    pub dynamic_fees_enabled: bool,
+    pub base_fee_bps: u16,
+    pub max_fee_bps: u16,
+    pub kink_utilization_bps: u16,
+    pub window_seconds: i64,
+    /// Withdrawal volume per bucket; `bucket_volumes[i]` covers bucket
+    /// numbers congruent to `i` modulo FEE_WINDOW_BUCKETS
+    pub bucket_volumes: [u64; FEE_WINDOW_BUCKETS],
+    /// Most recent bucket number written
+    pub last_bucket: i64,
+    /// Fees retained in the vault since creation
+    pub accrued_fees: u64,
+    /// Share of each withdrawal fee set aside for insurance (0 = no fund)
+    pub insurance_share_bps: u16,
+    /// Fees ring-fenced for insurance claims; not drawable by `draw_fees`
+    pub insurance_reserve: u64,
+    /// Total paid out by executed insurance claims
+    pub insurance_paid: u64,
+}
+
+impl VaultFeeState {
+    pub const INIT_SPACE: usize = 8 + // discriminator
+        1 +  // bump
+        32 + // vault
+        1 +  // dynamic_fees_enabled
+        2 +  // base_fee_bps
+        2 +  // max_fee_bps
+        2 +  // kink_utilization_bps
+        8 +  // window_seconds
+        8 * FEE_WINDOW_BUCKETS + // bucket_volumes
+        8 +  // last_bucket
+        8 +  // accrued_fees
+        2 +  // insurance_share_bps
+        8 +  // insurance_reserve
+        8;   // insurance_paid
+
+    /// Bind a freshly created fee account to its vault (init_if_needed)
+    pub fn ensure_initialized(&mut self, vault: Pubkey, bump: u8) {
+        if self.vault == Pubkey::default() {
+            self.bump = bump;
+            self.vault = vault;
+            self.window_seconds = DEFAULT_FEE_WINDOW_SECONDS;
+        }
+    }
+
+    pub fn apply_curve(&mut self, params: &FeeCurveParams) {
+        // Changing the bucket width invalidates the recorded volume
+        if self.window_seconds != params.window_seconds {
+            self.bucket_volumes = [0u64; FEE_WINDOW_BUCKETS];
+        }
+
+        self.dynamic_fees_enabled = params.enabled;
+        self.base_fee_bps = params.base_fee_bps;
+        self.max_fee_bps = params.max_fee_bps;
+        self.kink_utilization_bps = params.kink_utilization_bps;
+        self.window_seconds = params.window_seconds;
+    }
+
+    fn bucket_seconds(&self) -> i64 {
+        (self.window_seconds / FEE_WINDOW_BUCKETS as i64).max(1)
+    }
+
+    /// Advance the window to `now`, clearing buckets that fell out of it
+    fn roll(&mut self, now: i64) {
+        let bucket = now / self.bucket_seconds();
+        let elapsed = bucket.saturating_sub(self.last_bucket);
+
+        if elapsed >= FEE_WINDOW_BUCKETS as i64 {
+            self.bucket_volumes = [0u64; FEE_WINDOW_BUCKETS];
+        } else {
+            for step in 1..=elapsed {
+                let index = (self.last_bucket + step).rem_euclid(FEE_WINDOW_BUCKETS as i64);
+                self.bucket_volumes[index as usize] = 0;
+            }
+        }
+
+        if elapsed > 0 {
+            self.last_bucket = bucket;
+        }
+    }
+
+    pub fn window_volume(&self) -> u64 {
+        self.bucket_volumes.iter().fold(0u64, |acc, v| acc.saturating_add(*v))
+    }
+
+    /// Fee for withdrawing `amount` out of a pool holding `tvl`
+    pub fn fee_for(&self, amount: u64, tvl: u64) -> u64 {
+        if !self.dynamic_fees_enabled || tvl == 0 {
+            return 0;
+        }
+
+        let volume = self.window_volume().saturating_add(amount) as u128;
+        let utilization_bps = (volume * 10_000 / tvl as u128).min(10_000) as u64;
+        let kink = self.kink_utilization_bps as u64;
+
+        let fee_bps = if utilization_bps <= kink || kink >= 10_000 {
+            self.base_fee_bps as u64
+        } else {
+            let slope = (self.max_fee_bps - self.base_fee_bps) as u64;
+            self.base_fee_bps as u64 + slope * (utilization_bps - kink) / (10_000 - kink)
+        };
+
+        ((amount as u128) * (fee_bps as u128) / 10_000) as u64
+    }
+
+    /// Record a withdrawal and return the fee charged on it. Exempt
+    /// withdrawals still count toward the window volume.
+    pub fn apply_withdrawal(&mut self, amount: u64, tvl: u64, now: i64, exempt: bool) -> u64 {
+        self.roll(now);
+        let fee = if exempt { 0 } else { self.fee_for(amount, tvl) };
+
+        let index = self.last_bucket.rem_euclid(FEE_WINDOW_BUCKETS as i64) as usize;
+        self.bucket_volumes[index] = self.bucket_volumes[index].saturating_add(amount);
+
+        let insured = ((fee as u128) * (self.insurance_share_bps as u128) / 10_000) as u64;
+        self.insurance_reserve = self.insurance_reserve.saturating_add(insured);
+        self.accrued_fees = self.accrued_fees.saturating_add(fee - insured);
+
+        fee
+    }
+
+    /// Retain the protocol's part of a swap fee
+    pub fn record_swap_fee(&mut self, fee: u64) {
+        self.accrued_fees = self.accrued_fees.saturating_add(fee);
+    }
+
+    /// Spend up to `amount` of the accrued fees, returning what was drawn
+    pub fn draw_fees(&mut self, amount: u64) -> u64 {
+        let drawn = amount.min(self.accrued_fees);
+        self.accrued_fees -= drawn;
+        drawn
+    }
+
+    /// Pay an approved insurance claim out of the reserve
+    pub fn pay_insurance(&mut self, amount: u64) -> Result<()> {
+        require!(
+            self.insurance_reserve >= amount,
+            crate::errors::ZyncxError::InsufficientFunds
+        );
+        self.insurance_reserve -= amount;
+        self.insurance_paid = self.insurance_paid.saturating_add(amount);
+        Ok(())
+    }
+}
@@ -0,0 +1,66 @@
+use anchor_lang::prelude::*;
+
+/// Protocol-wide counters for indexers and dashboards, maintained by vault
+/// initialization, pause changes, deposits, withdrawals and swaps.
+///
+/// Amounts are raw base units summed across every vault asset; per-asset
+/// totals live on each `VaultState`.
+#[account]
+pub struct GlobalStats {
+    pub bump: u8,
+    pub total_deposited: u128,
+    pub total_withdrawn: u128,
+    pub total_swap_volume: u128,
+    pub deposit_count: u64,
+    pub withdrawal_count: u64,
+    pub swap_count: u64,
+    /// Initialized vaults that are not paused
+    pub active_vaults: u32,
+    pub updated_at: i64,
+}
+
+impl GlobalStats {
+    pub const INIT_SPACE: usize = 8 + // discriminator
+        1 +  // bump
+        16 + // total_deposited
+        16 + // total_withdrawn
+        16 + // total_swap_volume
+        8 +  // deposit_count
+        8 +  // withdrawal_count
+        8 +  // swap_count
+        4 +  // active_vaults
+        8;   // updated_at
+
+    /// Record the PDA bump on a freshly created account (init_if_needed)
+    pub fn ensure_initialized(&mut self, bump: u8) {
+        self.bump = bump;
+    }
+
+    pub fn record_vault_activated(&mut self, now: i64) {
+        self.active_vaults = self.active_vaults.saturating_add(1);
+        self.updated_at = now;
+    }
+
+    pub fn record_vault_deactivated(&mut self, now: i64) {
+        self.active_vaults = self.active_vaults.saturating_sub(1);
+        self.updated_at = now;
+    }
+
+    pub fn record_deposit(&mut self, amount: u64, now: i64) {
+        self.total_deposited = self.total_deposited.saturating_add(amount as u128);
+        self.deposit_count += 1;
+        self.updated_at = now;
+    }
+
+    pub fn record_withdrawal(&mut self, amount: u64, now: i64) {
+        self.total_withdrawn = self.total_withdrawn.saturating_add(amount as u128);
+        self.withdrawal_count += 1;
+        self.updated_at = now;
+    }
+
+    pub fn record_swap(&mut self, amount_in: u64, now: i64) {
+        self.total_swap_volume = self.total_swap_volume.saturating_add(amount_in as u128);
+        self.swap_count += 1;
+        self.updated_at = now;
+    }
+}
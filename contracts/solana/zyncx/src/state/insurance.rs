@@ -0,0 +1,115 @@
+use anchor_lang::prelude::*;
+
+use super::Role;
+use crate::errors::ZyncxError;
+
+/// Most payouts a single claim can make
+pub const MAX_CLAIM_PAYOUTS: usize = 4;
+/// Distinct governance roles that must approve (or reject) a claim
+pub const INSURANCE_CLAIM_QUORUM: u32 = 2;
+/// Hard ceiling on the share of withdrawal fees routed to insurance (50%)
+pub const MAX_INSURANCE_SHARE_BPS: u16 = 5_000;
+
+/// One note minted by an executed claim
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ClaimPayout {
+    pub amount: u64,
+    /// The new note's commitment is hash(amount, precommitment)
+    pub precommitment: [u8; 32],
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum InsuranceClaimStatus {
+    /// Collecting role votes
+    Voting,
+    /// Paid out of the insurance reserve
+    Executed,
+    /// Rejected by a quorum of roles
+    Rejected,
+}
+
+/// A request to compensate users out of a vault's insurance reserve, e.g.
+/// for funds lost to a stuck MPC computation or a verified accounting bug.
+/// Payouts are fresh commitments in the vault's tree, withdrawn like any
+/// other note. Kept after execution as the on-chain record of the payout.
+/// seeds = [b"insurance_claim", vault, claim_id]
+#[account]
+pub struct InsuranceClaim {
+    pub bump: u8,
+    pub vault: Pubkey,
+    pub claim_id: u64,
+    pub proposer: Pubkey,
+    /// Hash of the off-chain incident report backing the claim
+    pub evidence_hash: [u8; 32],
+    pub payouts: Vec<ClaimPayout>,
+    /// Sum of the payout amounts
+    pub total: u64,
+    /// Bitmask over `Role` of the roles that approved
+    pub approvals: u8,
+    /// Bitmask over `Role` of the roles that rejected
+    pub rejections: u8,
+    pub status: InsuranceClaimStatus,
+    pub proposed_at: i64,
+    pub resolved_at: i64,
+}
+
+impl InsuranceClaim {
+    pub const INIT_SPACE: usize = 8 + // discriminator
+        1 +  // bump
+        32 + // vault
+        8 +  // claim_id
+        32 + // proposer
+        32 + // evidence_hash
+        4 + (8 + 32) * MAX_CLAIM_PAYOUTS + // payouts
+        8 +  // total
+        1 +  // approvals
+        1 +  // rejections
+        1 +  // status
+        8 +  // proposed_at
+        8;   // resolved_at
+
+    /// Validate a claim's payouts and return their sum
+    pub fn payout_total(payouts: &[ClaimPayout]) -> Result<u64> {
+        require!(
+            !payouts.is_empty() && payouts.len() <= MAX_CLAIM_PAYOUTS,
+            ZyncxError::InvalidInsuranceClaim
+        );
+        payouts.iter().try_fold(0u64, |total, payout| {
+            require!(payout.amount > 0, ZyncxError::InvalidInsuranceClaim);
+            total
+                .checked_add(payout.amount)
+                .ok_or(error!(ZyncxError::ArithmeticOverflow))
+        })
+    }
+
+    /// Record `role`'s vote; each role votes once per claim. Moves the claim
+    /// to Rejected once a quorum of roles rejected it.
+    pub fn record_vote(&mut self, role: Role, approve: bool) -> Result<()> {
+        require!(
+            self.status == InsuranceClaimStatus::Voting,
+            ZyncxError::InsuranceClaimClosed
+        );
+        let bit = 1u8 << role as u8;
+        require!(
+            (self.approvals | self.rejections) & bit == 0,
+            ZyncxError::AlreadyVoted
+        );
+
+        if approve {
+            self.approvals |= bit;
+        } else {
+            self.rejections |= bit;
+            if self.rejections.count_ones() >= INSURANCE_CLAIM_QUORUM {
+                self.status = InsuranceClaimStatus::Rejected;
+            }
+        }
+        Ok(())
+    }
+
+    /// Approved by a quorum and by more roles than rejected it
+    pub fn is_approved(&self) -> bool {
+        self.status == InsuranceClaimStatus::Voting
+            && self.approvals.count_ones() >= INSURANCE_CLAIM_QUORUM
+            && self.approvals.count_ones() > self.rejections.count_ones()
+    }
+}
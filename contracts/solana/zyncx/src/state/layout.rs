@@ -0,0 +1,140 @@
+//! Fixed byte offsets of the fields indexers filter order accounts on.
+//!
+//! Every offset includes the 8-byte Anchor discriminator, so it can be used
+//! directly as a `getProgramAccounts` memcmp offset. "All active limit orders
+//! selling from vault X" is a discriminator filter at 0, `SOURCE_VAULT` = X
+//! and `STATUS` = `LimitOrderStatus::Active as u8`.
+//!
+//! The fields these offsets cover sit in each account's fixed-size prefix;
+//! new fields must be appended after them.
+
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+
+use super::{
+    DCAStatus, EncryptedDCAConfig, EncryptedLimitOrder, EncryptedSwapRequest, LimitOrderStatus,
+    SwapRequestStatus,
+};
+
+/// Offset of `user` in every order account (after discriminator and bump)
+const USER_OFFSET: usize = 8 + 1;
+/// Offset of `source_vault` in every order account
+const SOURCE_VAULT_OFFSET: usize = USER_OFFSET + 32;
+/// Offset of `dest_vault` in every order account
+const DEST_VAULT_OFFSET: usize = SOURCE_VAULT_OFFSET + 32;
+
+/// `EncryptedSwapRequest` offsets and getters
+pub mod swap_request {
+    use super::*;
+
+    pub const USER: usize = USER_OFFSET;
+    pub const SOURCE_VAULT: usize = SOURCE_VAULT_OFFSET;
+    pub const DEST_VAULT: usize = DEST_VAULT_OFFSET;
+    /// After encrypted_bounds, bounds_nonce, client_pubkey, amount,
+    /// nullifier and new_commitment
+    pub const STATUS: usize = EncryptedSwapRequest::ENCRYPTED_BOUNDS_OFFSET
+        + EncryptedSwapRequest::ENCRYPTED_BOUNDS_SIZE
+        + 16
+        + 32
+        + 8
+        + 32
+        + 32;
+
+    pub fn user(data: &[u8]) -> Option<Pubkey> {
+        pubkey_at::<EncryptedSwapRequest>(data, USER)
+    }
+
+    pub fn source_vault(data: &[u8]) -> Option<Pubkey> {
+        pubkey_at::<EncryptedSwapRequest>(data, SOURCE_VAULT)
+    }
+
+    pub fn dest_vault(data: &[u8]) -> Option<Pubkey> {
+        pubkey_at::<EncryptedSwapRequest>(data, DEST_VAULT)
+    }
+
+    pub fn status(data: &[u8]) -> Option<SwapRequestStatus> {
+        status_at::<EncryptedSwapRequest, _>(data, STATUS)
+    }
+}
+
+/// `EncryptedLimitOrder` offsets and getters
+pub mod limit_order {
+    use super::*;
+
+    pub const USER: usize = USER_OFFSET;
+    pub const SOURCE_VAULT: usize = SOURCE_VAULT_OFFSET;
+    pub const DEST_VAULT: usize = DEST_VAULT_OFFSET;
+    /// After encrypted_params, params_nonce, client_pubkey and expires_at
+    pub const STATUS: usize = EncryptedLimitOrder::ENCRYPTED_PARAMS_OFFSET
+        + EncryptedLimitOrder::ENCRYPTED_PARAMS_SIZE
+        + 16
+        + 32
+        + 8;
+
+    pub fn user(data: &[u8]) -> Option<Pubkey> {
+        pubkey_at::<EncryptedLimitOrder>(data, USER)
+    }
+
+    pub fn source_vault(data: &[u8]) -> Option<Pubkey> {
+        pubkey_at::<EncryptedLimitOrder>(data, SOURCE_VAULT)
+    }
+
+    pub fn dest_vault(data: &[u8]) -> Option<Pubkey> {
+        pubkey_at::<EncryptedLimitOrder>(data, DEST_VAULT)
+    }
+
+    pub fn status(data: &[u8]) -> Option<LimitOrderStatus> {
+        status_at::<EncryptedLimitOrder, _>(data, STATUS)
+    }
+}
+
+/// `EncryptedDCAConfig` offsets and getters
+pub mod dca {
+    use super::*;
+
+    pub const USER: usize = USER_OFFSET;
+    pub const SOURCE_VAULT: usize = SOURCE_VAULT_OFFSET;
+    pub const DEST_VAULT: usize = DEST_VAULT_OFFSET;
+    /// After encrypted_params, params_nonce, client_pubkey, interval_seconds
+    /// and next_execution_at
+    pub const STATUS: usize = EncryptedDCAConfig::ENCRYPTED_PARAMS_OFFSET
+        + EncryptedDCAConfig::ENCRYPTED_PARAMS_SIZE
+        + 16
+        + 32
+        + 8
+        + 8;
+
+    pub fn user(data: &[u8]) -> Option<Pubkey> {
+        pubkey_at::<EncryptedDCAConfig>(data, USER)
+    }
+
+    pub fn source_vault(data: &[u8]) -> Option<Pubkey> {
+        pubkey_at::<EncryptedDCAConfig>(data, SOURCE_VAULT)
+    }
+
+    pub fn dest_vault(data: &[u8]) -> Option<Pubkey> {
+        pubkey_at::<EncryptedDCAConfig>(data, DEST_VAULT)
+    }
+
+    pub fn status(data: &[u8]) -> Option<DCAStatus> {
+        status_at::<EncryptedDCAConfig, _>(data, STATUS)
+    }
+}
+
+/// Pubkey at `offset` of a `T` account's raw data, `None` for data of
+/// another account type or too short
+fn pubkey_at<T: Discriminator>(data: &[u8], offset: usize) -> Option<Pubkey> {
+    if !data.starts_with(T::DISCRIMINATOR) {
+        return None;
+    }
+    let bytes: [u8; 32] = data.get(offset..offset + 32)?.try_into().ok()?;
+    Some(Pubkey::new_from_array(bytes))
+}
+
+/// Status byte at `offset` of a `T` account's raw data
+fn status_at<T: Discriminator, S: AnchorDeserialize>(data: &[u8], offset: usize) -> Option<S> {
+    if !data.starts_with(T::DISCRIMINATOR) {
+        return None;
+    }
+    S::try_from_slice(data.get(offset..offset + 1)?).ok()
+}
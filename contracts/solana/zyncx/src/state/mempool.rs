@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+
+/// Computation types that can carry a depth limit
+pub const MAX_MEMPOOL_LIMITS: usize = 24;
+
+/// Byte offset of the queued-computation count (u32, little endian) in the
+/// Arcium cluster mempool account: the 8-byte discriminator is followed by
+/// the computation heap's length. Tied to the arcium-anchor version the
+/// program is built against.
+pub const MEMPOOL_DEPTH_OFFSET: usize = 8;
+
+/// Deepest mempool one computation type is still queued into
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct MempoolDepthLimit {
+    /// Computation definition offset of the circuit (`comp_def_offset`)
+    pub comp_def_offset: u32,
+    pub max_depth: u32,
+    /// Fail the queue instruction when over the limit; otherwise only the
+    /// depth event flags it
+    pub reject: bool,
+}
+
+/// Per-computation-type mempool backpressure, managed by the admin role.
+/// Queue instructions read the cluster mempool depth before queueing and
+/// fail fast instead of letting the computation time out.
+/// Singleton PDA: seeds = [b"mempool_limits"]
+#[account]
+pub struct MempoolLimits {
+    pub bump: u8,
+    pub limits: Vec<MempoolDepthLimit>,
+}
+
+impl MempoolLimits {
+    pub const INIT_SPACE: usize = 8 + // discriminator
+        1 +  // bump
+        4 + ((4 + 4 + 1) * MAX_MEMPOOL_LIMITS); // limits vec
+
+    pub fn limit_for(&self, comp_def_offset: u32) -> Option<&MempoolDepthLimit> {
+        self.limits
+            .iter()
+            .find(|l| l.comp_def_offset == comp_def_offset)
+    }
+
+    /// Limit configured for `comp_def_offset`. An uninitialized limits
+    /// account (never created on this deployment) limits nothing.
+    pub fn load_limit(
+        mempool_limits: &AccountInfo,
+        comp_def_offset: u32,
+    ) -> Result<Option<MempoolDepthLimit>> {
+        if mempool_limits.owner != &crate::ID || mempool_limits.data_is_empty() {
+            return Ok(None);
+        }
+
+        let data = mempool_limits.try_borrow_data()?;
+        let limits = MempoolLimits::try_deserialize(&mut &data[..])?;
+        Ok(limits.limit_for(comp_def_offset).copied())
+    }
+}
+
+/// Number of computations waiting in the cluster mempool
+pub fn mempool_depth(mempool: &AccountInfo) -> Result<u32> {
+    let data = mempool.try_borrow_data()?;
+    let bytes = data
+        .get(MEMPOOL_DEPTH_OFFSET..MEMPOOL_DEPTH_OFFSET + 4)
+        .ok_or(crate::errors::ZyncxError::InvalidMempoolAccount)?;
+    let mut depth = [0u8; 4];
+    depth.copy_from_slice(bytes);
+    Ok(u32::from_le_bytes(depth))
+}
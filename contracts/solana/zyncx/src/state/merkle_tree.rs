@@ -1,10 +1,126 @@
 use anchor_lang::prelude::*;
+use zyncx_core::hash::{
+    self as tree_hash, is_field_element, HashBackend, POSEIDON_TREE_DEPTH, POSEIDON_ZEROS,
+};
+
+use crate::state::{u64_public_input, UNBOUND_POLICY_HASH};
 // Note: light_poseidon and ark_bn254 removed due to zeroize version conflict with solana-program
 // Using keccak-based hashing for demo - production would use groth16-solana compatible implementation
 
 pub const MAX_DEPTH: u32 = 20;
 pub const ROOT_HISTORY_SIZE: usize = 30;
-pub const MAX_LEAVES: usize = 100;
+/// Levels kept in the frontier: one per tree level plus the root's
+pub const FRONTIER_SIZE: usize = MAX_DEPTH as usize + 1;
+/// Leaves a tree of `MAX_DEPTH` holds, and the default `max_leaves`. Leaves
+/// are not stored, so capacity no longer costs account space.
+pub const MAX_TREE_LEAVES: usize = 1 << MAX_DEPTH;
+/// Fill level (basis points of capacity) at which a tree may be rotated
+pub const DEFAULT_ROTATION_THRESHOLD_BPS: u16 = 9_000;
+
+/// Outcome of a leaf insertion. Tree-mutating instructions return this as
+/// return data so CPI callers can chain on the new root without parsing logs.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TreeInsertion {
+    pub leaf_index: u64,
+    pub new_root: [u8; 32],
+    pub tree_epoch: u64,
+    /// Slot the leaf was inserted at; a private input to note-age proofs
+    pub slot: u64,
+}
+
+/// Return data of a deposit. `auth_path` holds the sibling hashes from the
+/// new leaf up to `insertion.new_root` (bottom first) when the depositor asks
+/// for it, so the note is spendable without an indexer; otherwise it is
+/// empty. At most `MAX_DEPTH` siblings, well within the return data limit.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct DepositReceipt {
+    pub insertion: TreeInsertion,
+    pub auth_path: Vec<[u8; 32]>,
+}
+
+/// Two-to-one hash of a merkle tree, fixed when the tree is created
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum TreeHash {
+    /// Keccak-256 syscall; cheapest on-chain, costly to prove in a circuit
+    #[default]
+    Keccak,
+    /// Poseidon over BN254 (circom parameters) via the `sol_poseidon`
+    /// syscall, as Noir's `poseidon::bn254::hash_2` computes it. The tree is
+    /// the circuits' own: `MAX_DEPTH` levels over `hash_1([0])` empty leaves,
+    /// so its roots are the ones proofs recompute. Commitments must be field
+    /// elements.
+    Poseidon,
+}
+
+impl TreeHash {
+    fn backend(self) -> &'static dyn HashBackend {
+        match self {
+            TreeHash::Keccak => &SyscallKeccak,
+            TreeHash::Poseidon => &SyscallPoseidon,
+        }
+    }
+
+    /// Root of an empty tree using this hash
+    pub fn empty_root(self) -> [u8; 32] {
+        tree_hash::empty_root(self.backend())
+    }
+
+    /// Whether `leaf` can be inserted into a tree using this hash
+    pub fn accepts(self, leaf: &[u8; 32]) -> bool {
+        self == TreeHash::Keccak || is_field_element(leaf)
+    }
+
+    /// Precommitment of a note's secrets: keccak(secret || nullifier_secret),
+    /// or Poseidon(secret, nullifier_secret) over field-element secrets
+    pub fn precommitment(self, secret: &[u8; 32], nullifier_secret: &[u8; 32]) -> [u8; 32] {
+        match self {
+            TreeHash::Keccak => solana_program::keccak::hashv(&[secret, nullifier_secret]).0,
+            TreeHash::Poseidon => SyscallPoseidon.hash_pair(secret, nullifier_secret),
+        }
+    }
+
+    /// Leaf of a note of `amount` under `precommitment`. Keccak trees keep
+    /// `poseidon_hash_commitment`; Poseidon trees hash the amount (as a
+    /// public input word) with the precommitment, so the leaf is a field
+    /// element the circuit can recompute.
+    pub fn commitment(self, amount: u64, precommitment: [u8; 32]) -> Result<[u8; 32]> {
+        self.bound_commitment(amount, precommitment, &UNBOUND_POLICY_HASH)
+    }
+
+    /// Leaf of a note bound to the allowance policy hashing to `policy_hash`:
+    /// the policy hash is appended to `commitment`'s inputs (keccak over
+    /// `amount_le || precommitment || policy_hash`, or a three-input
+    /// Poseidon). `UNBOUND_POLICY_HASH` gives `commitment` itself.
+    pub fn bound_commitment(
+        self,
+        amount: u64,
+        precommitment: [u8; 32],
+        policy_hash: &[u8; 32],
+    ) -> Result<[u8; 32]> {
+        let bound = *policy_hash != UNBOUND_POLICY_HASH;
+        match self {
+            TreeHash::Keccak if bound => Ok(solana_program::keccak::hashv(&[
+                &amount.to_le_bytes(),
+                &precommitment,
+                policy_hash,
+            ])
+            .0),
+            TreeHash::Keccak => poseidon_hash_commitment(amount, precommitment),
+            TreeHash::Poseidon => {
+                require!(
+                    is_field_element(&precommitment),
+                    crate::errors::ZyncxError::CommitmentNotInField
+                );
+                let amount = u64_public_input(amount);
+                Ok(if bound {
+                    SyscallPoseidon::hash(&[&amount, &precommitment, policy_hash])
+                } else {
+                    SyscallPoseidon.hash_pair(&amount, &precommitment)
+                })
+            }
+        }
+    }
+}
 
 #[account]
 pub struct MerkleTreeState {
@@ -14,11 +130,19 @@ pub struct MerkleTreeState {
     pub current_root_index: u8,
     pub root: [u8; 32],
     pub roots: [[u8; 32]; ROOT_HISTORY_SIZE],
-    pub leaves: Vec<[u8; 32]>,
+    pub vault: Pubkey,
+    pub epoch: u64,
+    pub max_leaves: u32,
+    pub tree_hash: TreeHash,
+    /// Latest node at each level still waiting for its right sibling (see
+    /// `zyncx_core::hash::append_leaf`). Leaves themselves are only logged,
+    /// in `LeafInserted`.
+    pub frontier: [[u8; 32]; FRONTIER_SIZE],
+    /// Layout version (`CURRENT_ACCOUNT_VERSION`); see `migrate_account`
+    pub version: u8,
 }
 
 impl MerkleTreeState {
-    // ~4KB which is under Solana's 10KB limit
     pub const INIT_SPACE: usize = 8 + // discriminator
         1 +  // bump
         1 +  // depth (u8)
@@ -26,12 +150,42 @@ impl MerkleTreeState {
         1 +  // current_root_index (u8)
         32 + // root
         (32 * ROOT_HISTORY_SIZE) + // roots history (fixed array)
-        4 + (32 * MAX_LEAVES); // leaves vec (initial capacity)
+        32 + // vault
+        8 +  // epoch
+        4 +  // max_leaves
+        1 +  // tree_hash
+        (32 * FRONTIER_SIZE) + // frontier
+        1;   // version
 
     pub fn get_root(&self) -> [u8; 32] {
         self.root
     }
 
+    /// `(leaf_index, new_root)` for the event of an instruction that may
+    /// have inserted a change note: the insertion's, or `(0, root)` if it
+    /// inserted nothing
+    pub fn event_position(&self, insertion: Option<&TreeInsertion>) -> (u64, [u8; 32]) {
+        match insertion {
+            Some(insertion) => (insertion.leaf_index, insertion.new_root),
+            None => (0, self.root),
+        }
+    }
+
+    /// Leaf this tree inserts for a note of `amount` under `precommitment`
+    pub fn commitment(&self, amount: u64, precommitment: [u8; 32]) -> Result<[u8; 32]> {
+        self.tree_hash.commitment(amount, precommitment)
+    }
+
+    /// `commitment` bound to an allowance policy (see `TreeHash::bound_commitment`)
+    pub fn bound_commitment(
+        &self,
+        amount: u64,
+        precommitment: [u8; 32],
+        policy_hash: &[u8; 32],
+    ) -> Result<[u8; 32]> {
+        self.tree_hash.bound_commitment(amount, precommitment, policy_hash)
+    }
+
     pub fn get_depth(&self) -> u8 {
         self.depth
     }
@@ -40,26 +194,77 @@ impl MerkleTreeState {
         self.size
     }
 
-    pub fn insert(&mut self, leaf: [u8; 32]) -> Result<[u8; 32]> {
-        require!((self.depth as u32) < MAX_DEPTH, crate::errors::ZyncxError::MaxDepthReached);
-        require!(self.leaves.len() < MAX_LEAVES, crate::errors::ZyncxError::MaxDepthReached);
+    /// Whether the tree has reached `threshold_bps` of its leaf capacity
+    pub fn is_near_capacity(&self, threshold_bps: u16) -> bool {
+        self.size * 10_000 >= (self.max_leaves as u64) * (threshold_bps as u64)
+    }
 
-        self.leaves.push(leaf);
-        self.size += 1;
+    pub fn insert(&mut self, leaf: [u8; 32]) -> Result<TreeInsertion> {
+        require!(
+            self.size < self.max_leaves as u64,
+            crate::errors::ZyncxError::MaxDepthReached
+        );
+        require!(
+            self.tree_hash.accepts(&leaf),
+            crate::errors::ZyncxError::CommitmentNotInField
+        );
 
-        let new_root = self.compute_root()?;
+        let slot = Clock::get()?.slot;
+        let leaf_index = self.size;
+        let backend = self.tree_hash.backend();
+        let node = tree_hash::leaf_node(backend, &leaf, slot);
+        let new_root = tree_hash::append_leaf(backend, &mut self.frontier, leaf_index, node);
+        self.size += 1;
         self.root = new_root;
-
-        self.current_root_index = (self.current_root_index + 1) % (ROOT_HISTORY_SIZE as u8);
-        self.roots[self.current_root_index as usize] = new_root;
+        self.push_root(new_root);
 
         self.update_depth();
 
-        Ok(new_root)
+        emit!(LeafInserted {
+            vault: self.vault,
+            tree_epoch: self.epoch,
+            leaf_index,
+            commitment: leaf,
+            slot,
+            new_root,
+        });
+
+        Ok(TreeInsertion {
+            leaf_index,
+            new_root,
+            tree_epoch: self.epoch,
+            slot,
+        })
     }
 
-    pub fn has(&self, leaf: &[u8; 32]) -> bool {
-        self.leaves.contains(leaf)
+    /// Insert `leaves` in slice order. The tree is append-only, so the i-th
+    /// commitment always receives leaf index `size + i` (as of the call) and
+    /// the i-th returned insertion describes it; every intermediate root
+    /// enters the root history. Capacity is checked for the whole batch up
+    /// front, so a batch lands entirely or not at all.
+    pub fn insert_many(&mut self, leaves: &[[u8; 32]]) -> Result<Vec<TreeInsertion>> {
+        require!(
+            self.size + leaves.len() as u64 <= self.max_leaves as u64,
+            crate::errors::ZyncxError::MaxDepthReached
+        );
+        require!(
+            leaves.iter().all(|leaf| self.tree_hash.accepts(leaf)),
+            crate::errors::ZyncxError::CommitmentNotInField
+        );
+
+        leaves.iter().map(|leaf| self.insert(*leaf)).collect()
+    }
+
+    /// Record `root` in the history ring buffer. A root equal to the latest
+    /// entry is skipped so repeated roots don't evict older valid ones and
+    /// shrink the proof window.
+    fn push_root(&mut self, root: [u8; 32]) {
+        if self.roots[self.current_root_index as usize] == root {
+            return;
+        }
+
+        self.current_root_index = (self.current_root_index + 1) % (ROOT_HISTORY_SIZE as u8);
+        self.roots[self.current_root_index as usize] = root;
     }
 
     pub fn root_exists(&self, root: &[u8; 32]) -> bool {
@@ -77,48 +282,94 @@ impl MerkleTreeState {
         false
     }
 
-    fn compute_root(&self) -> Result<[u8; 32]> {
-        if self.leaves.is_empty() {
-            return Ok([0u8; 32]);
-        }
+    /// Sibling hashes from the latest leaf up to the current root, bottom
+    /// first. An unpaired node and a lone leaf have the empty subtree of
+    /// their level as sibling (zero in Keccak trees). Only the
+    /// latest leaf's path is in the frontier; clients rebuild older paths
+    /// from the `LeafInserted` log.
+    pub fn auth_path(&self, leaf_index: u64) -> Result<Vec<[u8; 32]>> {
+        require!(
+            leaf_index + 1 == self.size,
+            crate::errors::ZyncxError::InvalidMerkleProof
+        );
+        Ok(tree_hash::frontier_auth_path(
+            self.tree_hash.backend(),
+            &self.frontier,
+            leaf_index,
+        ))
+    }
 
-        // For single leaf, hash it with zero
-        if self.leaves.len() == 1 {
-            return simple_hash(&self.leaves[0], &[0u8; 32]);
-        }
+    /// Whether `leaf` (a tree node, see `leaf_node`) hashes up `path` to a
+    /// root in the history. `indices[i]` is 1 when the node at level `i` is
+    /// the right child, as in the circuits' `path_indices`.
+    pub fn verify_inclusion(
+        &self,
+        leaf: &[u8; 32],
+        path: &[[u8; 32]],
+        indices: &[u8],
+    ) -> Result<bool> {
+        require!(
+            !path.is_empty()
+                && path.len() <= MAX_DEPTH as usize
+                && path.len() == indices.len()
+                && indices.iter().all(|index| *index <= 1),
+            crate::errors::ZyncxError::InvalidMerkleProof
+        );
 
-        // Use iterative approach with minimal stack usage
-        let mut current_level: Vec<[u8; 32]> = self.leaves.clone();
-
-        while current_level.len() > 1 {
-            let mut next_level = Vec::with_capacity((current_level.len() + 1) / 2);
-            
-            let mut i = 0;
-            while i < current_level.len() {
-                let left = &current_level[i];
-                let right = if i + 1 < current_level.len() {
-                    &current_level[i + 1]
-                } else {
-                    &[0u8; 32]
-                };
-                let hash = simple_hash(left, right)?;
-                next_level.push(hash);
-                i += 2;
-            }
-            
-            current_level = next_level;
-        }
-
-        Ok(current_level[0])
+        let is_right: Vec<bool> = indices.iter().map(|index| *index == 1).collect();
+        let root = tree_hash::root_from_path(self.tree_hash.backend(), leaf, path, &is_right);
+        Ok(self.root_exists(&root))
     }
 
     fn update_depth(&mut self) {
-        let size = self.size;
-        if size == 0 {
-            self.depth = 0;
+        self.depth = if self.size == 0 {
+            0
         } else {
-            self.depth = (64 - (size - 1).leading_zeros()) as u8;
-        }
+            tree_hash::backend_height(self.tree_hash.backend(), self.size) as u8
+        };
+    }
+}
+
+/// Tree hashing through the keccak syscall. Trees are built by
+/// `zyncx_core::hash`, which clients run with their own backends; its golden
+/// vectors keep every backend on the roots computed here.
+pub struct SyscallKeccak;
+
+impl HashBackend for SyscallKeccak {
+    fn hash_pair(&self, left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+        solana_program::keccak::hashv(&[left, right]).0
+    }
+}
+
+/// Tree hashing through the `sol_poseidon` syscall. Tree inputs are field
+/// elements (`TreeHash::accepts` guards the leaves), so the syscall cannot
+/// reject them. Like `zyncx_core::hash::Poseidon`, trees have the circuits'
+/// fixed depth and empty subtrees.
+pub struct SyscallPoseidon;
+
+impl SyscallPoseidon {
+    /// Poseidon over `inputs.len()` field elements, as Noir's `hash_N`
+    pub fn hash(inputs: &[&[u8; 32]]) -> [u8; 32] {
+        use solana_poseidon::{hashv, Endianness, Parameters};
+
+        let inputs: Vec<&[u8]> = inputs.iter().map(|input| input.as_slice()).collect();
+        hashv(Parameters::Bn254X5, Endianness::BigEndian, &inputs)
+            .map(|hash| hash.to_bytes())
+            .expect("poseidon inputs are field elements")
+    }
+}
+
+impl HashBackend for SyscallPoseidon {
+    fn hash_pair(&self, left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+        Self::hash(&[left, right])
+    }
+
+    fn depth(&self) -> Option<usize> {
+        Some(POSEIDON_TREE_DEPTH)
+    }
+
+    fn zero_node(&self, level: usize) -> [u8; 32] {
+        POSEIDON_ZEROS[level]
     }
 }
 
@@ -126,13 +377,13 @@ impl MerkleTreeState {
 /// This is used internally for merkle tree computation to avoid stack overflow
 #[inline(never)]
 pub fn simple_hash(left: &[u8; 32], right: &[u8; 32]) -> Result<[u8; 32]> {
-    use solana_program::keccak;
-    
-    let mut combined = [0u8; 64];
-    combined[..32].copy_from_slice(left);
-    combined[32..].copy_from_slice(right);
-    
-    Ok(keccak::hash(&combined).0)
+    Ok(SyscallKeccak.hash_pair(left, right))
+}
+
+/// Tree node for a leaf: hash(commitment, insertion slot as 32-byte big endian)
+#[inline(never)]
+pub fn leaf_node(commitment: &[u8; 32], slot: u64) -> Result<[u8; 32]> {
+    Ok(tree_hash::leaf_node(&SyscallKeccak, commitment, slot))
 }
 
 /// Hash two values (using keccak for demo - production would use Poseidon)
@@ -165,3 +416,17 @@ pub fn poseidon_hash_commitment_zk(amount: u64, precommitment: [u8; 32]) -> Resu
     // Production would use Poseidon for ZK circuit compatibility
     poseidon_hash_commitment(amount, precommitment)
 }
+
+/// A leaf appended to a tree. Leaves are not kept on-chain, so this log is
+/// the canonical leaf set: replaying a vault's events per `tree_epoch` in
+/// `leaf_index` order rebuilds each tree and every auth path.
+#[event]
+pub struct LeafInserted {
+    pub vault: Pubkey,
+    pub tree_epoch: u64,
+    pub leaf_index: u64,
+    pub commitment: [u8; 32],
+    /// Hashed into the leaf node with `commitment`
+    pub slot: u64,
+    pub new_root: [u8; 32],
+}
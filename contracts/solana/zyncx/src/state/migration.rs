@@ -0,0 +1,99 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{ArciumConfig, EncryptedUserPosition, EncryptedVaultAccount, MerkleTreeState, VaultState};
+
+// ============================================================================
+// ACCOUNT SCHEMA VERSIONS
+// ============================================================================
+// Long-lived accounts end with a `version` byte. A layout change appends its
+// fields after it and bumps `CURRENT_ACCOUNT_VERSION`; `migrate_account`
+// grows older accounts to the new size (the tail reads as zero), lets
+// `upgrade_from` fill in what zero does not cover, and stamps the version.
+// Accounts created before versioning have no version byte and read as 0.
+// ============================================================================
+
+/// Layout version of accounts created by this build
+pub const CURRENT_ACCOUNT_VERSION: u8 = 1;
+
+/// Account types `migrate_account` upgrades
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MigratableAccount {
+    Vault,
+    MerkleTree,
+    EncryptedVault,
+    EncryptedPosition,
+    ArciumConfig,
+}
+
+/// An account with a `version` byte that `migrate_account` can upgrade
+pub trait Versioned: AccountSerialize + AccountDeserialize + Discriminator {
+    /// Size of the current layout, discriminator included
+    const SPACE: usize;
+
+    fn version(&self) -> u8;
+
+    fn set_version(&mut self, version: u8);
+
+    /// Fill fields added after layout `from` that must not stay zero
+    fn upgrade_from(&mut self, _from: u8) {}
+}
+
+impl Versioned for VaultState {
+    const SPACE: usize = VaultState::INIT_SPACE;
+
+    fn version(&self) -> u8 {
+        self.version
+    }
+
+    fn set_version(&mut self, version: u8) {
+        self.version = version;
+    }
+}
+
+impl Versioned for MerkleTreeState {
+    const SPACE: usize = MerkleTreeState::INIT_SPACE;
+
+    fn version(&self) -> u8 {
+        self.version
+    }
+
+    fn set_version(&mut self, version: u8) {
+        self.version = version;
+    }
+}
+
+impl Versioned for EncryptedVaultAccount {
+    const SPACE: usize = 8 + EncryptedVaultAccount::INIT_SPACE;
+
+    fn version(&self) -> u8 {
+        self.version
+    }
+
+    fn set_version(&mut self, version: u8) {
+        self.version = version;
+    }
+}
+
+impl Versioned for EncryptedUserPosition {
+    const SPACE: usize = 8 + EncryptedUserPosition::INIT_SPACE;
+
+    fn version(&self) -> u8 {
+        self.version
+    }
+
+    fn set_version(&mut self, version: u8) {
+        self.version = version;
+    }
+}
+
+impl Versioned for ArciumConfig {
+    const SPACE: usize = ArciumConfig::INIT_SPACE;
+
+    fn version(&self) -> u8 {
+        self.version
+    }
+
+    fn set_version(&mut self, version: u8) {
+        self.version = version;
+    }
+}
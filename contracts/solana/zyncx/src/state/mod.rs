@@ -5,6 +5,33 @@ pub mod verifier;
 pub mod arcium;
 pub mod arcium_mxe;
 pub mod pyth;
+pub mod switchboard;
+pub mod oracle;
+pub mod program_info;
+pub mod viewing_key;
+pub mod vault_stats;
+pub mod global_stats;
+pub mod exclusion;
+pub mod blocklist;
+pub mod fees;
+pub mod config_change;
+pub mod roles;
+pub mod fee_exemptions;
+pub mod referral;
+pub mod proof_failures;
+pub mod insurance;
+pub mod treasury;
+pub mod allowance;
+pub mod note_backup;
+pub mod rfq;
+pub mod mempool;
+pub mod stealth;
+pub mod protocol_config;
+pub mod relayer;
+pub mod layout;
+pub mod migration;
+#[cfg(feature = "faucet")]
+pub mod faucet;
 
 pub use merkle_tree::*;
 pub use vault::*;
@@ -13,3 +40,29 @@ pub use verifier::*;
 pub use arcium::*;
 pub use arcium_mxe::*;
 pub use pyth::*;
+pub use switchboard::*;
+pub use oracle::*;
+pub use program_info::*;
+pub use viewing_key::*;
+pub use vault_stats::*;
+pub use global_stats::*;
+pub use exclusion::*;
+pub use blocklist::*;
+pub use fees::*;
+pub use config_change::*;
+pub use roles::*;
+pub use fee_exemptions::*;
+pub use referral::*;
+pub use proof_failures::*;
+pub use insurance::*;
+pub use treasury::*;
+pub use allowance::*;
+pub use note_backup::*;
+pub use rfq::*;
+pub use mempool::*;
+pub use stealth::*;
+pub use protocol_config::*;
+pub use relayer::*;
+pub use migration::*;
+#[cfg(feature = "faucet")]
+pub use faucet::*;
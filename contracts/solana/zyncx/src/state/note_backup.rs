@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+
+use crate::instructions::deposit::MAX_ENCRYPTED_NOTE_LEN;
+
+/// Copy of a deposit's note ciphertext (encrypted to the depositor's wallet
+/// key) kept in account data, so a wallet that lost its local state can
+/// recover its notes by listing its backups instead of replaying logs.
+/// seeds = [b"note_backup", owner, precommitment]
+#[account]
+pub struct NoteBackup {
+    pub bump: u8,
+    pub owner: Pubkey,
+    pub vault: Pubkey,
+    pub commitment: [u8; 32],
+    pub leaf_index: u64,
+    pub tree_epoch: u64,
+    pub ciphertext: Vec<u8>,
+    pub created_at: i64,
+}
+
+impl NoteBackup {
+    pub const INIT_SPACE: usize = 8 + // discriminator
+        1 +  // bump
+        32 + // owner
+        32 + // vault
+        32 + // commitment
+        8 +  // leaf_index
+        8 +  // tree_epoch
+        4 + MAX_ENCRYPTED_NOTE_LEN + // ciphertext
+        8;   // created_at
+}
@@ -0,0 +1,89 @@
+use anchor_lang::prelude::*;
+
+use super::{load_pyth_price, load_switchboard_price, PriceData};
+use crate::dex::{NATIVE_SOL_MINT, WSOL_MINT};
+
+/// Maximum number of tokens the price feed registry can hold
+pub const MAX_REGISTERED_PRICE_FEEDS: usize = 32;
+
+/// Where a token's price is read from
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OracleSource {
+    /// Pyth `PriceUpdateV2` account carrying this feed ID
+    Pyth { feed_id: [u8; 32] },
+    /// Switchboard On-Demand pull feed at this address
+    Switchboard { feed: Pubkey },
+}
+
+impl OracleSource {
+    pub fn load(&self, price_feed: &AccountInfo) -> Result<PriceData> {
+        match self {
+            OracleSource::Pyth { feed_id } => load_pyth_price(price_feed, feed_id),
+            OracleSource::Switchboard { feed } => load_switchboard_price(price_feed, feed),
+        }
+    }
+}
+
+/// Secondary oracle for a token, read when its Pyth price is stale so a
+/// single oracle outage does not halt confidential execution. Set by the
+/// admin role.
+/// seeds = [b"oracle_fallback", token_mint]
+#[account]
+pub struct OracleFallback {
+    pub bump: u8,
+    pub token_mint: Pubkey,
+    pub source: OracleSource,
+    pub updated_at: i64,
+}
+
+impl OracleFallback {
+    pub const INIT_SPACE: usize = 8 + // discriminator
+        1 +  // bump
+        32 + // token_mint
+        1 + 32 + // source
+        8;   // updated_at
+}
+
+/// Pyth feed registered for a token
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RegisteredPriceFeed {
+    pub token_mint: Pubkey,
+    /// Pyth feed ID pricing the token in USD
+    pub feed_id: [u8; 32],
+    /// Oldest publish time (seconds) accepted for this feed; can only
+    /// tighten the configured `PriceGuards`
+    pub max_age_seconds: i64,
+}
+
+/// Pyth feed of every token the confidential order types can price. Lets
+/// vaults for new tokens get oracle-backed features without a program
+/// upgrade. Set by the admin role.
+/// Singleton PDA: seeds = [b"price_feed_registry"]
+#[account]
+pub struct PriceFeedRegistry {
+    pub bump: u8,
+    pub feeds: Vec<RegisteredPriceFeed>,
+}
+
+impl PriceFeedRegistry {
+    pub const INIT_SPACE: usize = 8 + // discriminator
+        1 +  // bump
+        4 + ((32 + 32 + 8) * MAX_REGISTERED_PRICE_FEEDS); // feeds vec
+
+    /// Feed registered for `mint`. Native SOL is priced by the wrapped SOL feed.
+    pub fn feed_for(&self, mint: &Pubkey) -> Option<&RegisteredPriceFeed> {
+        let mint = if *mint == NATIVE_SOL_MINT { &WSOL_MINT } else { mint };
+        self.feeds.iter().find(|feed| feed.token_mint == *mint)
+    }
+}
+
+/// The fallback oracle and its price account, when a caller passed both
+pub fn fallback_oracle<'a, 'info>(
+    oracle_fallback: &'a Option<Box<Account<'info, OracleFallback>>>,
+    fallback_price_feed: &'a Option<UncheckedAccount<'info>>,
+) -> Option<(&'a OracleFallback, &'a AccountInfo<'info>)> {
+    match (oracle_fallback, fallback_price_feed) {
+        (Some(oracle_fallback), Some(price_feed)) => Some((oracle_fallback, price_feed)),
+        _ => None,
+    }
+}
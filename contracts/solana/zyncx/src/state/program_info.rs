@@ -0,0 +1,92 @@
+use anchor_lang::prelude::*;
+
+// ============================================================================
+// PROGRAM INFO
+// ============================================================================
+// Version and capability metadata of the deployed binary. SDKs read this PDA
+// to gate behavior on what the program supports instead of probing with
+// instructions that may fail.
+// ============================================================================
+
+/// Capability bits reported in `ProgramInfo::features`
+pub mod feature_flags {
+    /// ZK-verified withdrawals (Noir verifier CPI)
+    pub const ZK_WITHDRAWALS: u64 = 1 << 0;
+    /// Shielded swaps routed through Jupiter
+    pub const JUPITER_SWAPS: u64 = 1 << 1;
+    /// Encrypted vault state maintained by Arcium MXE
+    pub const ARCIUM_ENCRYPTED_VAULTS: u64 = 1 << 2;
+    /// Confidential swap evaluation in Arcium MXE
+    pub const CONFIDENTIAL_SWAPS: u64 = 1 << 3;
+    /// Devnet test-token faucet (`faucet` feature)
+    pub const DEVNET_FAUCET: u64 = 1 << 4;
+}
+
+/// Features compiled into this build
+pub const ENABLED_FEATURES: u64 = feature_flags::ZK_WITHDRAWALS
+    | feature_flags::JUPITER_SWAPS
+    | feature_flags::ARCIUM_ENCRYPTED_VAULTS
+    | feature_flags::CONFIDENTIAL_SWAPS
+    | if cfg!(feature = "faucet") { feature_flags::DEVNET_FAUCET } else { 0 };
+
+/// Git commit of the source tree, injected by build.rs (empty if unknown)
+pub const GIT_HASH: &str = env!("ZYNCX_GIT_HASH");
+
+pub const GIT_HASH_LEN: usize = 40;
+
+#[account]
+pub struct ProgramInfo {
+    pub bump: u8,
+    pub version_major: u16,
+    pub version_minor: u16,
+    pub version_patch: u16,
+    /// Hex-encoded commit hash (ASCII, zero-padded)
+    pub git_hash: [u8; GIT_HASH_LEN],
+    pub features: u64,
+    pub updated_at: i64,
+    /// solana-verify executable hash attested by the upgrade authority
+    pub build_hash: [u8; 32],
+    /// ProgramData deploy slot the attestation refers to. An attestation is
+    /// stale once the program is upgraded past this slot.
+    pub attested_slot: u64,
+    pub attested_at: i64,
+}
+
+impl ProgramInfo {
+    pub const INIT_SPACE: usize = 8 + // discriminator
+        1 +  // bump
+        2 +  // version_major
+        2 +  // version_minor
+        2 +  // version_patch
+        GIT_HASH_LEN + // git_hash
+        8 +  // features
+        8 +  // updated_at
+        32 + // build_hash
+        8 +  // attested_slot
+        8;   // attested_at
+
+    pub fn has_feature(&self, feature: u64) -> bool {
+        self.features & feature == feature
+    }
+
+    /// Whether the attested build hash describes the binary deployed at
+    /// `deploy_slot` (the current `ProgramData::slot`)
+    pub fn is_attested_for(&self, deploy_slot: u64) -> bool {
+        self.attested_at != 0 && self.attested_slot == deploy_slot
+    }
+
+    /// Overwrite the metadata with the values compiled into this binary
+    pub fn sync_from_build(&mut self, now: i64) {
+        self.version_major = env!("CARGO_PKG_VERSION_MAJOR").parse().unwrap_or(0);
+        self.version_minor = env!("CARGO_PKG_VERSION_MINOR").parse().unwrap_or(0);
+        self.version_patch = env!("CARGO_PKG_VERSION_PATCH").parse().unwrap_or(0);
+
+        self.git_hash = [0u8; GIT_HASH_LEN];
+        let hash = GIT_HASH.as_bytes();
+        let len = hash.len().min(GIT_HASH_LEN);
+        self.git_hash[..len].copy_from_slice(&hash[..len]);
+
+        self.features = ENABLED_FEATURES;
+        self.updated_at = now;
+    }
+}
@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+
+use crate::state::DEPOSIT_EPOCH_SECONDS;
+
+/// Failures within one epoch that raise a `ProofFailureSpike`
+pub const PROOF_FAILURE_SPIKE_THRESHOLD: u32 = 20;
+
+/// Per-vault count of rejected ZK proofs, bucketed by `DEPOSIT_EPOCH_SECONDS`.
+/// A failed proof reverts its own transaction, so failures are reported
+/// afterwards by the operator's relayer or watchtower.
+/// PDA: seeds = [b"proof_failures", vault]
+#[account]
+pub struct ProofFailureStats {
+    pub bump: u8,
+    pub vault: Pubkey,
+    /// Epoch `epoch_failures` belongs to
+    pub epoch: i64,
+    pub epoch_failures: u32,
+    pub total_failures: u64,
+    pub last_failure_at: i64,
+}
+
+impl ProofFailureStats {
+    pub const INIT_SPACE: usize = 8 + // discriminator
+        1 +  // bump
+        32 + // vault
+        8 +  // epoch
+        4 +  // epoch_failures
+        8 +  // total_failures
+        8;   // last_failure_at
+
+    /// Bind a freshly created account to its vault (init_if_needed)
+    pub fn ensure_initialized(&mut self, vault: Pubkey, bump: u8) {
+        if self.vault == Pubkey::default() {
+            self.bump = bump;
+            self.vault = vault;
+        }
+    }
+
+    /// Add `count` failures at `now`. Returns true when this report pushes
+    /// the epoch's count across the spike threshold, so the alert fires
+    /// once per epoch.
+    pub fn record(&mut self, count: u32, now: i64) -> bool {
+        let epoch = now / DEPOSIT_EPOCH_SECONDS;
+        if epoch != self.epoch {
+            self.epoch = epoch;
+            self.epoch_failures = 0;
+        }
+
+        let before = self.epoch_failures;
+        self.epoch_failures = before.saturating_add(count);
+        self.total_failures = self.total_failures.saturating_add(count as u64);
+        self.last_failure_at = now;
+
+        before < PROOF_FAILURE_SPIKE_THRESHOLD
+            && self.epoch_failures >= PROOF_FAILURE_SPIKE_THRESHOLD
+    }
+}
@@ -0,0 +1,215 @@
+use anchor_lang::prelude::*;
+
+use super::ProofCircuit;
+use crate::errors::ZyncxError;
+
+/// Parameter changes that can wait in the queue at once
+pub const MAX_PENDING_PARAM_CHANGES: usize = 4;
+
+/// Hard ceiling on the protocol fee of a public swap (10%)
+pub const MAX_SWAP_FEE_BPS: u16 = 1_000;
+
+/// A protocol parameter change users get to exit ahead of. Tightening the
+/// operation ceiling or changing the computation fee only goes through the
+/// queue, so it is announced a full timelock before it applies.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParamChange {
+    /// New `max_operation_value` (0 = uncapped)
+    OperationValueLimit(u64),
+    /// New `ArciumConfig::computation_fee`, in lamports
+    ComputationFee(u64),
+    /// New `swap_fee_bps`
+    SwapFee(u16),
+    /// New `referral_share_bps`
+    ReferralShare(u16),
+}
+
+impl ParamChange {
+    pub const MAX_SIZE: usize = 1 + 8; // tag + largest variant (u64)
+
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            ParamChange::SwapFee(bps) => {
+                require!(*bps <= MAX_SWAP_FEE_BPS, ZyncxError::InvalidFeeConfig)
+            }
+            ParamChange::ReferralShare(bps) => {
+                require!(*bps <= 10_000, ZyncxError::InvalidFeeConfig)
+            }
+            ParamChange::OperationValueLimit(_) | ParamChange::ComputationFee(_) => {}
+        }
+        Ok(())
+    }
+}
+
+/// A queued parameter change and when it becomes executable
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PendingParamChange {
+    /// Identifies the change to execute or cancel
+    pub id: u64,
+    pub change: ParamChange,
+    pub proposed_at: i64,
+    /// Earliest timestamp the change can be executed at
+    pub eta: i64,
+}
+
+impl PendingParamChange {
+    pub const SIZE: usize = 8 + ParamChange::MAX_SIZE + 8 + 8;
+}
+
+/// Protocol-wide settings shared by every vault.
+/// Singleton: seeds = [b"protocol_config"]
+///
+/// Pins the Noir verifier program of each circuit. Proof-verifying
+/// instructions only accept the configured program, and changing one waits
+/// out `timelock_seconds` so users can exit before a new verifier is live.
+///
+/// Also caps the value of a single withdrawal or swap. Operations above
+/// `max_operation_value` must be co-signed by `risk_signer`.
+///
+/// Public swaps pay `swap_fee_bps` of their input as a protocol fee, of which
+/// `referral_share_bps` is credited to the swap's referrer.
+///
+/// Fee and limit changes that cost users wait in `pending_params` for the
+/// same timelock, giving them a window to withdraw under the old terms.
+#[account]
+pub struct ProtocolConfig {
+    pub bump: u8,
+    /// Verifier of the mixer circuit (withdrawals, swaps, proof checks)
+    pub mixer_verifier: Pubkey,
+    /// Verifier of the transfer circuit (shielded transfers)
+    pub transfer_verifier: Pubkey,
+    /// Verifier of the batch withdrawal circuit
+    pub batch_withdraw_verifier: Pubkey,
+    /// Delay between proposing and executing a verifier change
+    pub timelock_seconds: i64,
+    /// Circuit of the proposed verifier change
+    pub pending_circuit: ProofCircuit,
+    /// Proposed verifier program (default when nothing is pending)
+    pub pending_verifier: Pubkey,
+    /// Earliest timestamp the proposed change can be executed at
+    pub pending_eta: i64,
+    /// Largest withdrawal or swap, in raw base units of the vault asset,
+    /// accepted without a risk signer co-signature (0 = uncapped)
+    pub max_operation_value: u64,
+    /// Governance-designated key whose signature lifts the ceiling for one
+    /// operation (default = no bypass)
+    pub risk_signer: Pubkey,
+    /// Id the next queued parameter change gets
+    pub next_param_change_id: u64,
+    /// Announced parameter changes waiting out the timelock
+    pub pending_params: Vec<PendingParamChange>,
+    /// Protocol fee of `swap_native` / `swap_token`, in bps of `amount_in`
+    pub swap_fee_bps: u16,
+    /// Share of the swap fee credited to the referrer, in bps of the fee
+    pub referral_share_bps: u16,
+}
+
+impl ProtocolConfig {
+    pub const INIT_SPACE: usize = 8 + // discriminator
+        1 +  // bump
+        32 + // mixer_verifier
+        32 + // transfer_verifier
+        32 + // batch_withdraw_verifier
+        8 +  // timelock_seconds
+        1 +  // pending_circuit
+        32 + // pending_verifier
+        8 +  // pending_eta
+        8 +  // max_operation_value
+        32 + // risk_signer
+        8 +  // next_param_change_id
+        4 + (PendingParamChange::SIZE * MAX_PENDING_PARAM_CHANGES) + // pending_params vec
+        2 +  // swap_fee_bps
+        2;   // referral_share_bps
+
+    pub fn verifier(&self, circuit: ProofCircuit) -> Pubkey {
+        match circuit {
+            ProofCircuit::Mixer => self.mixer_verifier,
+            ProofCircuit::Transfer => self.transfer_verifier,
+            ProofCircuit::BatchWithdraw => self.batch_withdraw_verifier,
+        }
+    }
+
+    pub fn set_verifier(&mut self, circuit: ProofCircuit, verifier: Pubkey) {
+        match circuit {
+            ProofCircuit::Mixer => self.mixer_verifier = verifier,
+            ProofCircuit::Transfer => self.transfer_verifier = verifier,
+            ProofCircuit::BatchWithdraw => self.batch_withdraw_verifier = verifier,
+        }
+    }
+
+    pub fn has_pending_change(&self) -> bool {
+        self.pending_verifier != Pubkey::default()
+    }
+
+    pub fn clear_pending_change(&mut self) {
+        self.pending_verifier = Pubkey::default();
+        self.pending_eta = 0;
+    }
+
+    /// Enforce the per-operation value ceiling. `risk_signer` is the
+    /// co-signer passed with the operation, if any.
+    pub fn check_operation_value(&self, amount: u64, risk_signer: Option<Pubkey>) -> Result<()> {
+        if self.max_operation_value == 0 || amount <= self.max_operation_value {
+            return Ok(());
+        }
+        require!(
+            self.risk_signer != Pubkey::default() && risk_signer == Some(self.risk_signer),
+            crate::errors::ZyncxError::OperationValueTooLarge
+        );
+        Ok(())
+    }
+
+    /// Announce `change`, executable once the timelock has elapsed
+    pub fn queue_param_change(
+        &mut self,
+        change: ParamChange,
+        now: i64,
+    ) -> Result<PendingParamChange> {
+        require!(
+            self.pending_params.len() < MAX_PENDING_PARAM_CHANGES,
+            ZyncxError::ParamChangeQueueFull
+        );
+        let eta = now
+            .checked_add(self.timelock_seconds)
+            .ok_or(ZyncxError::ArithmeticOverflow)?;
+
+        let pending = PendingParamChange {
+            id: self.next_param_change_id,
+            change,
+            proposed_at: now,
+            eta,
+        };
+        self.next_param_change_id += 1;
+        self.pending_params.push(pending);
+        Ok(pending)
+    }
+
+    /// Remove the queued change `id` from the queue
+    pub fn take_param_change(&mut self, id: u64) -> Result<PendingParamChange> {
+        let index = self
+            .pending_params
+            .iter()
+            .position(|pending| pending.id == id)
+            .ok_or(ZyncxError::ParamChangeNotFound)?;
+        Ok(self.pending_params.remove(index))
+    }
+
+    /// Protocol fee on a swap of `amount_in`, and the referrer's part of it
+    /// when the swap names one
+    pub fn swap_fee(&self, amount_in: u64, referred: bool) -> (u64, u64) {
+        let fee = (amount_in as u128 * self.swap_fee_bps as u128 / 10_000) as u64;
+        let reward = if referred {
+            (fee as u128 * self.referral_share_bps as u128 / 10_000) as u64
+        } else {
+            0
+        };
+        (fee, reward)
+    }
+
+    /// Whether moving the ceiling to `max_operation_value` tightens it, which
+    /// users must be able to exit ahead of
+    pub fn tightens_operation_value(&self, max_operation_value: u64) -> bool {
+        max_operation_value != 0
+            && (self.max_operation_value == 0 || max_operation_value < self.max_operation_value)
+    }
+}
@@ -1,38 +1,24 @@
 use anchor_lang::prelude::*;
+use pyth_solana_receiver_sdk::price_update::{PriceUpdateV2, VerificationLevel};
+
+use super::{OracleFallback, OracleSource, PriceFeedRegistry};
 
 // ============================================================================
 // PYTH PRICE FEED INTEGRATION
 // ============================================================================
 // Pyth Network provides real-time price feeds that Arcium can use for
 // confidential price comparisons without revealing user's trading bounds.
+//
+// Prices come from the pull oracle: `PriceUpdateV2` accounts owned by the
+// Pyth receiver program, each carrying one feed's latest verified update.
+// Orders pin the account at placement (normally the feed's sponsored price
+// account, whose address never changes); every read checks the owner, that
+// the update was fully verified and that it is the feed for the token being
+// priced.
 // ============================================================================
 
-/// Pyth Oracle Program ID (Devnet)
-/// Address: gSbePebfvPy7tRqimPoVecS2UsBvYv46ynrzWocc92s
-pub const PYTH_PROGRAM_ID: Pubkey = Pubkey::new_from_array([
-    0x0b, 0x58, 0x7b, 0x8d, 0x5c, 0x3c, 0x89, 0x7a,
-    0x4e, 0x2a, 0x1f, 0x6b, 0x8d, 0x9c, 0x7e, 0x3f,
-    0x5a, 0x4b, 0x2c, 0x8d, 0x9e, 0x1f, 0x6a, 0x7b,
-    0x3c, 0x5d, 0x8e, 0x9f, 0x2a, 0x4b, 0x6c, 0x7d,
-]);
-
-/// SOL/USD Price Feed Account (Devnet)
-/// Address: J83w4HKfqxwcq3BEMMkPFSppX3gqekLyLJBexebFVkix
-pub const SOL_USD_PRICE_FEED: [u8; 32] = [
-    0xfe, 0x65, 0x0f, 0x04, 0x93, 0xa1, 0x66, 0xc8,
-    0x39, 0x10, 0x5b, 0xc8, 0x5c, 0x98, 0x7e, 0x74,
-    0x87, 0x4e, 0x5c, 0x76, 0x76, 0x2d, 0x3e, 0x3f,
-    0x62, 0x47, 0x5a, 0x7d, 0x63, 0x1a, 0x00, 0x3e,
-];
-
-/// USDC/USD Price Feed Account (Devnet)  
-/// Address: 5SSkXsEKQepHHAewytPVwdej4epN1nxgLVM84L4KXgy7
-pub const USDC_USD_PRICE_FEED: [u8; 32] = [
-    0x41, 0xf3, 0x62, 0x5f, 0x12, 0x30, 0x83, 0x5c,
-    0x38, 0x78, 0x6b, 0x8d, 0x4f, 0x85, 0x6d, 0x39,
-    0x7a, 0x97, 0x36, 0x25, 0x66, 0xf7, 0x3b, 0x67,
-    0x7a, 0xa1, 0xeb, 0x7f, 0xd5, 0x8c, 0x75, 0x7a,
-];
+/// Pyth Solana receiver program, owner of every `PriceUpdateV2` account
+pub const PYTH_RECEIVER_PROGRAM_ID: Pubkey = pyth_solana_receiver_sdk::ID;
 
 /// Price data from Pyth oracle
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
@@ -76,9 +62,45 @@ impl PriceData {
         let now = Clock::get().map(|c| c.unix_timestamp).unwrap_or(0);
         now - self.publish_time > max_age_seconds
     }
+
+    /// Check the confidence interval is within `max_confidence_bps` of the price
+    pub fn is_confident(&self, max_confidence_bps: u16) -> bool {
+        (self.confidence as u128) * 10_000
+            <= (self.price.unsigned_abs() as u128) * (max_confidence_bps as u128)
+    }
 }
 
-/// Cached price feed account for quick lookups
+/// Freshness and precision an oracle price needs before the MXE acts on it
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PriceGuards {
+    /// Oldest acceptable publish time, in seconds before now
+    pub max_age_seconds: i64,
+    /// Widest acceptable confidence interval, in bps of the price
+    pub max_confidence_bps: u16,
+}
+
+impl PriceGuards {
+    /// Used wherever no `ArciumConfig` applies
+    pub const DEFAULT: Self = Self {
+        max_age_seconds: ORDER_MAX_PRICE_AGE,
+        max_confidence_bps: ORDER_MAX_PRICE_CONFIDENCE_BPS,
+    };
+
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.max_age_seconds > 0
+                && self.max_age_seconds <= MAX_PRICE_AGE_LIMIT
+                && self.max_confidence_bps > 0
+                && self.max_confidence_bps <= 10_000,
+            crate::errors::ZyncxError::InvalidPriceGuards
+        );
+        Ok(())
+    }
+}
+
+/// Cached price feed account for quick lookups, refreshed by the
+/// permissionless `update_cached_price` crank
+/// seeds = [b"cached_price", token_mint]
 #[account]
 pub struct CachedPriceFeed {
     /// Bump seed for PDA
@@ -120,45 +142,123 @@ pub struct PriceComparisonParams {
     pub operator: u8,
 }
 
-/// Parse Pyth price from account data
-pub fn parse_pyth_price(data: &[u8]) -> Result<PriceData> {
-    // Pyth price account structure (simplified)
-    // Full structure: https://github.com/pyth-network/pyth-sdk-solana
-    
-    if data.len() < 48 {
-        return Err(crate::errors::ZyncxError::InvalidPriceFeed.into());
-    }
+/// Read the latest price of `feed_id` from a Pyth `PriceUpdateV2` account.
+/// Rejects accounts not owned by the receiver program, updates that were
+/// only partially verified and updates for any other feed.
+pub fn load_pyth_price(price_feed: &AccountInfo, feed_id: &[u8; 32]) -> Result<PriceData> {
+    require_keys_eq!(
+        *price_feed.owner,
+        PYTH_RECEIVER_PROGRAM_ID,
+        crate::errors::ZyncxError::InvalidPriceFeed
+    );
 
-    // Skip magic number and version (8 bytes)
-    // Price is at offset 208 in the full structure
-    // For simplified parsing, we use a subset
-    
-    let price = i64::from_le_bytes(data[32..40].try_into().unwrap());
-    let confidence = u64::from_le_bytes(data[40..48].try_into().unwrap());
-    let exponent = i32::from_le_bytes(data[20..24].try_into().unwrap());
-    let publish_time = i64::from_le_bytes(data[24..32].try_into().unwrap());
+    let data = price_feed.try_borrow_data()?;
+    let update = PriceUpdateV2::try_deserialize(&mut &data[..])
+        .map_err(|_| error!(crate::errors::ZyncxError::InvalidPriceFeed))?;
+    require!(
+        update.verification_level == VerificationLevel::Full,
+        crate::errors::ZyncxError::InvalidPriceFeed
+    );
+
+    let price = update
+        .get_price_unchecked(feed_id)
+        .map_err(|_| error!(crate::errors::ZyncxError::InvalidPriceFeed))?;
 
     Ok(PriceData {
-        price,
-        confidence,
-        exponent,
-        publish_time,
+        price: price.price,
+        confidence: price.conf,
+        exponent: price.exponent,
+        publish_time: price.publish_time,
     })
 }
 
-/// Common token price feed mappings
-pub mod price_feeds {
-    use super::*;
+/// Fixed-point decimals of oracle prices handed to the order circuits;
+/// encrypted limit prices must use the same scale
+pub const ORDER_PRICE_DECIMALS: u8 = 6;
+
+/// Oldest oracle price (seconds) an order may be evaluated against
+pub const ORDER_MAX_PRICE_AGE: i64 = 60;
+
+/// Widest oracle confidence interval (bps of the price) an order may be
+/// evaluated against
+pub const ORDER_MAX_PRICE_CONFIDENCE_BPS: u16 = 200;
 
-    pub fn get_feed_for_token(mint: &Pubkey) -> Option<[u8; 32]> {
-        // Native SOL (represented as zero pubkey in our system)
-        if *mint == Pubkey::default() {
-            return Some(SOL_USD_PRICE_FEED);
+/// Upper bound on a configured price age (1 hour)
+pub const MAX_PRICE_AGE_LIMIT: i64 = 3_600;
+
+/// Read a fresh, tight price for `asset_mint` from its Pyth price update
+/// account, scaled to `ORDER_PRICE_DECIMALS`. The feed ID and age limit come
+/// from the token's `PriceFeedRegistry` entry. When the Pyth price is stale
+/// and the token's fallback oracle was passed, the fallback is read instead
+/// and held to the same guards.
+pub fn load_order_price(
+    price_feed: &AccountInfo,
+    registry: &PriceFeedRegistry,
+    fallback: Option<(&OracleFallback, &AccountInfo)>,
+    asset_mint: &Pubkey,
+    guards: &PriceGuards,
+) -> Result<u64> {
+    let registered = registry
+        .feed_for(asset_mint)
+        .ok_or(crate::errors::ZyncxError::PriceFeedNotRegistered)?;
+    let guards = PriceGuards {
+        max_age_seconds: guards.max_age_seconds.min(registered.max_age_seconds),
+        ..*guards
+    };
+    let mut price_data = OracleSource::Pyth {
+        feed_id: registered.feed_id,
+    }
+    .load(price_feed)?;
+
+    if price_data.is_stale(guards.max_age_seconds) {
+        if let Some((oracle_fallback, fallback_feed)) = fallback {
+            require_keys_eq!(
+                oracle_fallback.token_mint,
+                *asset_mint,
+                crate::errors::ZyncxError::InvalidPriceFeed
+            );
+            price_data = oracle_fallback.source.load(fallback_feed)?;
+            msg!("Pyth price stale; priced {} from fallback oracle", asset_mint);
         }
-        
-        // Add more token mappings as needed
-        // USDC, USDT, etc.
-        
-        None
     }
+
+    require!(
+        !price_data.is_stale(guards.max_age_seconds),
+        crate::errors::ZyncxError::StalePriceFeed
+    );
+    require!(
+        price_data.is_confident(guards.max_confidence_bps),
+        crate::errors::ZyncxError::InvalidPriceFeed
+    );
+    price_data
+        .get_price_with_decimals(ORDER_PRICE_DECIMALS)
+        .ok_or(crate::errors::ZyncxError::InvalidPriceFeed.into())
+}
+
+/// Realized price of a swap from its balance diffs: output tokens per input
+/// token, scaled to `ORDER_PRICE_DECIMALS` so it compares directly with
+/// `load_order_price` for the input token
+pub fn execution_price(
+    amount_in: u64,
+    in_decimals: u8,
+    amount_out: u64,
+    out_decimals: u8,
+) -> Option<u64> {
+    let scale = 10u128.checked_pow(in_decimals as u32 + ORDER_PRICE_DECIMALS as u32)?;
+    let numerator = (amount_out as u128).checked_mul(scale)?;
+    let denominator = (amount_in as u128).checked_mul(10u128.checked_pow(out_decimals as u32)?)?;
+    if denominator == 0 {
+        return None;
+    }
+    u64::try_from(numerator / denominator).ok()
+}
+
+/// Shortfall of `execution_price` against `oracle_price` in basis points;
+/// negative when the swap filled better than the oracle
+pub fn slippage_bps(oracle_price: u64, execution_price: u64) -> i64 {
+    if oracle_price == 0 {
+        return 0;
+    }
+    let shortfall = oracle_price as i128 - execution_price as i128;
+    (shortfall * 10_000 / oracle_price as i128) as i64
 }
@@ -0,0 +1,107 @@
+use anchor_lang::prelude::*;
+
+/// Number of referrers the MXE accumulates fees for. Must match
+/// REFERRAL_SLOTS in the Arcis circuits.
+pub const REFERRAL_SLOTS: usize = 4;
+
+/// Length of a referral accrual epoch
+pub const REFERRAL_EPOCH_SECONDS: i64 = 7 * 86_400;
+
+/// Referral epoch index containing `timestamp`
+pub fn referral_epoch_at(timestamp: i64) -> u64 {
+    (timestamp.max(0) / REFERRAL_EPOCH_SECONDS) as u64
+}
+
+/// Referrers eligible for confidential swap attribution. A swapper picks a
+/// referrer by encrypting its code (slot + 1, 0 = none) into the swap
+/// params, so the referral is never visible on-chain.
+/// Managed by the fee manager role.
+/// Singleton PDA: seeds = [b"referral_registry"]
+#[account]
+pub struct ReferralRegistry {
+    pub bump: u8,
+    /// Payout wallet per slot (default = unassigned)
+    pub referrers: [Pubkey; REFERRAL_SLOTS],
+    /// Lamports accrued to the referrer for every executed referred swap
+    pub fee_per_referral: u64,
+}
+
+impl ReferralRegistry {
+    pub const INIT_SPACE: usize = 8 + // discriminator
+        1 +  // bump
+        (32 * REFERRAL_SLOTS) + // referrers
+        8;   // fee_per_referral
+}
+
+/// One epoch of referral accrual. Totals stay Enc<Mxe> while the epoch is
+/// live and are only revealed, in aggregate, once it has ended.
+/// PDA: seeds = [b"referral_epoch", epoch.to_le_bytes()]
+#[account]
+pub struct ReferralEpoch {
+    pub bump: u8,
+    pub epoch: u64,
+    /// Nonce for MXE re-encryption (updated by every callback)
+    pub nonce: u128,
+    /// Enc<Mxe, ReferralTotals> - lamports accrued per slot
+    pub encrypted_totals: [[u8; 32]; REFERRAL_SLOTS],
+    /// Set once the MXE has produced the zeroed totals
+    pub initialized: bool,
+    pub revealed: bool,
+    /// Plaintext totals, valid once `revealed`
+    pub totals: [u64; REFERRAL_SLOTS],
+    pub claimed: [bool; REFERRAL_SLOTS],
+    /// Computation whose callback was applied last; a redelivered callback
+    /// for the same computation is ignored
+    pub last_computation: Pubkey,
+}
+
+impl ReferralEpoch {
+    /// = 8 (discriminator) + 1 (bump) + 8 (epoch) + 16 (nonce)
+    pub const ENCRYPTED_TOTALS_OFFSET: usize = 8 + 1 + 8 + 16;
+    pub const ENCRYPTED_TOTALS_SIZE: usize = 32 * REFERRAL_SLOTS;
+
+    pub const INIT_SPACE: usize = 8 + // discriminator
+        1 +  // bump
+        8 +  // epoch
+        16 + // nonce
+        (32 * REFERRAL_SLOTS) + // encrypted_totals
+        1 +  // initialized
+        1 +  // revealed
+        (8 * REFERRAL_SLOTS) + // totals
+        REFERRAL_SLOTS + // claimed
+        32; // last_computation
+}
+
+/// Swap referral rewards of one integrator in one vault, in the vault's
+/// asset. Public swaps naming `referrer` credit `referral_share_bps` of their
+/// protocol fee here; the referrer claims it out of the vault.
+/// PDA: seeds = [b"swap_referral", referrer, vault]
+#[account]
+pub struct ReferralAccount {
+    pub bump: u8,
+    pub referrer: Pubkey,
+    pub vault: Pubkey,
+    /// Rewards not yet claimed
+    pub accrued: u64,
+    /// Lifetime total claimed
+    pub claimed: u64,
+}
+
+impl ReferralAccount {
+    pub const INIT_SPACE: usize = 8 + // discriminator
+        1 +  // bump
+        32 + // referrer
+        32 + // vault
+        8 +  // accrued
+        8;   // claimed
+
+    /// Bind a freshly created account to its referrer and vault
+    /// (init_if_needed)
+    pub fn ensure_initialized(&mut self, referrer: Pubkey, vault: Pubkey, bump: u8) {
+        if self.referrer == Pubkey::default() {
+            self.bump = bump;
+            self.referrer = referrer;
+            self.vault = vault;
+        }
+    }
+}
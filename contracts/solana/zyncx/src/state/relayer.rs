@@ -0,0 +1,86 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ZyncxError;
+use crate::state::WithdrawExtData;
+
+/// Vaults a single relayer registration can list
+pub const MAX_RELAYER_VAULTS: usize = 8;
+
+/// A relayer's entry in the on-chain relayer registry. Wallets enumerate
+/// these to discover relayers, and a withdrawal can pin the posted fee
+/// schedule in its ext data so the relayer cannot charge more than it
+/// advertised when the user signed.
+/// seeds = [b"relayer", relayer]
+#[account]
+pub struct RelayerRegistration {
+    pub bump: u8,
+    /// Key the relayer pays withdrawals from and collects tips with
+    pub relayer: Pubkey,
+    /// keccak of the relayer's endpoint URL
+    pub endpoint_hash: [u8; 32],
+    /// Vaults the relayer submits withdrawals for
+    pub vaults: [Pubkey; MAX_RELAYER_VAULTS],
+    pub vault_count: u8,
+    /// Flat part of the tip, in the vault's asset
+    pub base_fee: u64,
+    /// Proportional part of the tip, in basis points of the withdrawn amount
+    pub fee_bps: u16,
+    pub updated_at: i64,
+}
+
+impl RelayerRegistration {
+    pub const INIT_SPACE: usize = 8 + // discriminator
+        1 +  // bump
+        32 + // relayer
+        32 + // endpoint_hash
+        (32 * MAX_RELAYER_VAULTS) + // vaults
+        1 +  // vault_count
+        8 +  // base_fee
+        2 +  // fee_bps
+        8;   // updated_at
+
+    /// keccak(base_fee || fee_bps), what `WithdrawExtData::fee_schedule_hash`
+    /// pins
+    pub fn fee_schedule_hash(&self) -> [u8; 32] {
+        solana_program::keccak::hashv(&[&self.base_fee.to_le_bytes(), &self.fee_bps.to_le_bytes()])
+            .0
+    }
+
+    /// Largest tip the posted schedule allows on `amount`
+    pub fn max_tip(&self, amount: u64) -> u64 {
+        let proportional = amount as u128 * self.fee_bps as u128 / 10_000;
+        self.base_fee.saturating_add(proportional as u64)
+    }
+
+    pub fn serves(&self, vault: &Pubkey) -> bool {
+        self.vaults[..self.vault_count as usize].contains(vault)
+    }
+
+    /// Enforce the schedule a withdrawal pins, if any: the payer's
+    /// registration must be passed, still post that schedule, list the
+    /// vault and allow the tip
+    pub fn check_ext_data(
+        registration: &Option<Box<Account<RelayerRegistration>>>,
+        ext_data: &WithdrawExtData,
+        vault: &Pubkey,
+        amount: u64,
+    ) -> Result<()> {
+        if ext_data.fee_schedule_hash == [0u8; 32] {
+            return Ok(());
+        }
+
+        let registration = registration
+            .as_ref()
+            .ok_or(ZyncxError::RelayerScheduleMismatch)?;
+        require!(
+            registration.fee_schedule_hash() == ext_data.fee_schedule_hash,
+            ZyncxError::RelayerScheduleMismatch
+        );
+        require!(registration.serves(vault), ZyncxError::RelayerVaultNotSupported);
+        require!(
+            ext_data.relayer_tip <= registration.max_tip(amount),
+            ZyncxError::RelayerFeeAboveSchedule
+        );
+        Ok(())
+    }
+}
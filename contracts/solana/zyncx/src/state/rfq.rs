@@ -0,0 +1,140 @@
+use anchor_lang::prelude::*;
+
+/// Quotes one RFQ can collect (slots of the `select_rfq_quote` circuit)
+pub const RFQ_MAX_QUOTES: usize = 4;
+/// Longest quote window a request can ask for (1 hour)
+pub const MAX_RFQ_QUOTE_WINDOW: i64 = 3_600;
+
+/// Whether `size` is a valid RFQ size bucket: 1, 2 or 5 times a power of
+/// ten base units. Requests only reveal which bucket they trade, not a size
+/// that could fingerprint the requester.
+pub fn is_rfq_size_bucket(size: u64) -> bool {
+    (0..20)
+        .filter_map(|exponent| 10u64.checked_pow(exponent))
+        .any(|power| [1, 2, 5].iter().any(|m| power.checked_mul(*m) == Some(size)))
+}
+
+/// Market maker allowed to quote RFQs, registered by the admin role. Quotes
+/// settle out of the maker's inventory escrows
+/// (seeds = [b"mm_inventory", maker, mint]), which this PDA is the token
+/// authority of.
+/// seeds = [b"market_maker", maker]
+#[account]
+pub struct MarketMaker {
+    pub bump: u8,
+    pub maker: Pubkey,
+    /// Cleared by the admin to stop new quotes; open quotes still settle
+    pub active: bool,
+    /// Quotes not yet closed; inventory can only be withdrawn at zero
+    pub open_quotes: u32,
+    pub registered_at: i64,
+}
+
+impl MarketMaker {
+    pub const INIT_SPACE: usize = 8 + // discriminator
+        1 +  // bump
+        32 + // maker
+        1 +  // active
+        4 +  // open_quotes
+        8;   // registered_at
+}
+
+/// Lifecycle of an RFQ
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RfqStatus {
+    /// Collecting quotes until `quote_deadline`
+    Open,
+    /// Best-quote selection queued in the MXE
+    Selecting,
+    /// A quote met the requester's limit; waiting for settlement
+    Selected,
+    /// Winner's inventory paid out into the destination vault
+    Filled,
+    /// No quote met the limit; the requester can cancel
+    NoFill,
+    /// Escrow refunded to the requester
+    Cancelled,
+}
+
+/// Request for quote: trades `size` of the source vault's asset into a
+/// shielded note of the destination vault's asset. The requester's minimum
+/// output stays encrypted; makers' quotes are compared inside the MXE and
+/// only the winning amount is revealed. `size` is escrowed at creation
+/// (seeds = [b"rfq_escrow", rfq_request], authority = this PDA).
+/// seeds = [b"rfq_request", user, rfq_id]
+#[account]
+pub struct RfqRequest {
+    pub bump: u8,
+    pub user: Pubkey,
+    pub rfq_id: u64,
+    pub source_vault: Pubkey,
+    pub dest_vault: Pubkey,
+    /// Bucketed input amount (see `is_rfq_size_bucket`)
+    pub size: u64,
+    /// Minimum output encrypted to the requester (Enc<Shared, u64>)
+    pub encrypted_min_out: [u8; 32],
+    pub params_nonce: u128,
+    pub client_pubkey: [u8; 32],
+    /// Precommitment of the output note inserted on settlement
+    pub precommitment: [u8; 32],
+    /// Quotes are accepted until this time
+    pub quote_deadline: i64,
+    pub status: RfqStatus,
+    /// Makers that quoted, in circuit slot order
+    pub quotes: Vec<Pubkey>,
+    /// Maker of the best quote, valid once selected
+    pub winner: Pubkey,
+    /// Output the winner pays (revealed by the MXE)
+    pub winning_amount: u64,
+    pub created_at: i64,
+    /// Computation whose callback was applied last; a redelivered callback
+    /// for the same computation is ignored
+    pub last_computation: Pubkey,
+}
+
+impl RfqRequest {
+    pub const INIT_SPACE: usize = 8 + // discriminator
+        1 +  // bump
+        32 + // user
+        8 +  // rfq_id
+        32 + // source_vault
+        32 + // dest_vault
+        8 +  // size
+        32 + // encrypted_min_out
+        16 + // params_nonce
+        32 + // client_pubkey
+        32 + // precommitment
+        8 +  // quote_deadline
+        1 +  // status
+        4 + (32 * RFQ_MAX_QUOTES) + // quotes vec
+        32 + // winner
+        8 +  // winning_amount
+        8 +  // created_at
+        32;  // last_computation
+}
+
+/// A market maker's encrypted output amount for one RFQ
+/// seeds = [b"rfq_quote", rfq_request, maker]
+#[account]
+pub struct RfqQuote {
+    pub bump: u8,
+    pub rfq_request: Pubkey,
+    pub maker: Pubkey,
+    /// Output offered for the request's size, encrypted by the maker
+    /// (Enc<Shared, u64>)
+    pub encrypted_amount: [u8; 32],
+    pub quote_nonce: u128,
+    pub maker_pubkey: [u8; 32],
+    pub submitted_at: i64,
+}
+
+impl RfqQuote {
+    pub const INIT_SPACE: usize = 8 + // discriminator
+        1 +  // bump
+        32 + // rfq_request
+        32 + // maker
+        32 + // encrypted_amount
+        16 + // quote_nonce
+        32 + // maker_pubkey
+        8;   // submitted_at
+}
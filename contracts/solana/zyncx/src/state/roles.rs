@@ -0,0 +1,83 @@
+use anchor_lang::prelude::*;
+
+/// Number of protocol roles
+pub const ROLE_COUNT: usize = 4;
+/// Delay between proposing and executing a role reassignment (2 days).
+/// Roles vote on cold spends and insurance claims, so a new holder must be
+/// visible long enough for the others to react before it can vote.
+pub const ROLE_CHANGE_TIMELOCK_SECONDS: i64 = 2 * 86_400;
+
+/// Protocol roles. Each can be a plain key or a multisig vault (e.g. Squads).
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Role {
+    /// Creates vaults and reassigns roles
+    Admin,
+    /// Pauses and unpauses vaults
+    Pauser,
+    /// Changes fee parameters
+    FeeManager,
+    /// Manages the recipient blocklist
+    Compliance,
+}
+
+/// Singleton role registry: seeds = [b"roles"]
+#[account]
+pub struct Roles {
+    pub bump: u8,
+    pub admin: Pubkey,
+    pub pauser: Pubkey,
+    pub fee_manager: Pubkey,
+    pub compliance: Pubkey,
+}
+
+impl Roles {
+    pub const INIT_SPACE: usize = 8 + // discriminator
+        1 +  // bump
+        32 + // admin
+        32 + // pauser
+        32 + // fee_manager
+        32;  // compliance
+
+    pub fn holder(&self, role: Role) -> Pubkey {
+        match role {
+            Role::Admin => self.admin,
+            Role::Pauser => self.pauser,
+            Role::FeeManager => self.fee_manager,
+            Role::Compliance => self.compliance,
+        }
+    }
+
+    pub fn set_holder(&mut self, role: Role, holder: Pubkey) {
+        match role {
+            Role::Admin => self.admin = holder,
+            Role::Pauser => self.pauser = holder,
+            Role::FeeManager => self.fee_manager = holder,
+            Role::Compliance => self.compliance = holder,
+        }
+    }
+
+    pub fn has_role(&self, role: Role, signer: &Pubkey) -> bool {
+        self.holder(role) == *signer
+    }
+}
+
+/// A proposed role reassignment waiting out `ROLE_CHANGE_TIMELOCK_SECONDS`.
+/// One at a time: seeds = [b"pending_role"]
+#[account]
+pub struct PendingRoleChange {
+    pub bump: u8,
+    pub role: Role,
+    pub holder: Pubkey,
+    pub proposed_at: i64,
+    /// Earliest timestamp at which the change can be executed
+    pub eta: i64,
+}
+
+impl PendingRoleChange {
+    pub const INIT_SPACE: usize = 8 + // discriminator
+        1 +  // bump
+        1 +  // role
+        32 + // holder
+        8 +  // proposed_at
+        8;   // eta
+}
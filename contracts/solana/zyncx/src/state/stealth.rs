@@ -0,0 +1,26 @@
+use anchor_lang::prelude::*;
+
+/// A recipient's published stealth meta-address. Senders combine it with a
+/// fresh ephemeral key to derive a one-time address (withdrawals) or note
+/// secrets (shielded transfers) only the recipient can find, and emit the
+/// ephemeral pubkey alongside the payment so the recipient can scan for it.
+/// seeds = [b"stealth_meta", owner]
+#[account]
+pub struct StealthMetaAddress {
+    pub bump: u8,
+    pub owner: Pubkey,
+    /// Key one-time addresses and notes are derived from
+    pub spend_pubkey: [u8; 32],
+    /// Key the recipient scans ephemeral pubkeys with
+    pub view_pubkey: [u8; 32],
+    pub updated_at: i64,
+}
+
+impl StealthMetaAddress {
+    pub const INIT_SPACE: usize = 8 + // discriminator
+        1 +  // bump
+        32 + // owner
+        32 + // spend_pubkey
+        32 + // view_pubkey
+        8;   // updated_at
+}
@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+use switchboard_on_demand::on_demand::accounts::pull_feed::PullFeedAccountData;
+
+use super::PriceData;
+
+// ============================================================================
+// SWITCHBOARD PRICE FEED INTEGRATION
+// ============================================================================
+// Secondary oracle for tokens whose Pyth feed can go stale. Prices come from
+// Switchboard On-Demand pull feeds: each feed account holds the median of
+// its oracles' latest submissions as an 18-decimal fixed-point value. The
+// admin pins the feed account per token in its `OracleFallback`, so a read
+// only has to check the address and the owning program.
+// ============================================================================
+
+/// Switchboard On-Demand program (mainnet)
+pub const SWITCHBOARD_ON_DEMAND_PROGRAM_ID: Pubkey = switchboard_on_demand::ON_DEMAND_MAINNET_PID;
+
+/// Switchboard On-Demand program (devnet)
+pub const SWITCHBOARD_ON_DEMAND_DEVNET_PROGRAM_ID: Pubkey = switchboard_on_demand::ON_DEMAND_DEVNET_PID;
+
+/// Decimals of Switchboard feed values
+pub const SWITCHBOARD_DECIMALS: i32 = 18;
+
+/// Decimals `PriceData` keeps of a Switchboard value, so it fits in an i64
+const SWITCHBOARD_PRICE_DECIMALS: i32 = 8;
+
+/// Read the latest result of the Switchboard pull feed at `feed`.
+/// Rejects any other account and accounts not owned by the On-Demand program.
+pub fn load_switchboard_price(price_feed: &AccountInfo, feed: &Pubkey) -> Result<PriceData> {
+    require_keys_eq!(
+        price_feed.key(),
+        *feed,
+        crate::errors::ZyncxError::InvalidPriceFeed
+    );
+    require!(
+        *price_feed.owner == SWITCHBOARD_ON_DEMAND_PROGRAM_ID
+            || *price_feed.owner == SWITCHBOARD_ON_DEMAND_DEVNET_PROGRAM_ID,
+        crate::errors::ZyncxError::InvalidPriceFeed
+    );
+
+    let data = price_feed.try_borrow_data()?;
+    let pull_feed = PullFeedAccountData::parse(data)
+        .map_err(|_| error!(crate::errors::ZyncxError::InvalidPriceFeed))?;
+
+    let scale = 10i128.pow((SWITCHBOARD_DECIMALS - SWITCHBOARD_PRICE_DECIMALS) as u32);
+    let price = i64::try_from(pull_feed.result.value / scale)
+        .map_err(|_| error!(crate::errors::ZyncxError::InvalidPriceFeed))?;
+    let confidence = u64::try_from(pull_feed.result.std_dev.unsigned_abs() / scale as u128)
+        .map_err(|_| error!(crate::errors::ZyncxError::InvalidPriceFeed))?;
+
+    Ok(PriceData {
+        price,
+        confidence,
+        exponent: -SWITCHBOARD_PRICE_DECIMALS,
+        publish_time: pull_feed.last_update_timestamp,
+    })
+}
@@ -0,0 +1,205 @@
+use anchor_lang::prelude::*;
+
+use super::{Role, Roles, ROLE_COUNT};
+use crate::errors::ZyncxError;
+
+/// Distinct signers, each voting for a role it holds, that must approve (or
+/// reject) a cold spend
+pub const COLD_SPEND_QUORUM: u32 = 2;
+/// Minimum gap between two refills of the hot treasury (1 hour)
+pub const HOT_REFILL_COOLDOWN_SECONDS: i64 = 3_600;
+
+/// How a native vault's SOL is split between the hot treasury that pays
+/// withdrawals and the cold treasury that only moves under governance
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TreasurySplitParams {
+    /// Share of the vault's SOL the hot treasury is swept down to (bps)
+    pub hot_target_bps: u16,
+    /// Hot share below which the hot treasury may be refilled (bps)
+    pub refill_threshold_bps: u16,
+}
+
+impl TreasurySplitParams {
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.hot_target_bps > 0
+                && self.hot_target_bps <= 10_000
+                && self.refill_threshold_bps <= self.hot_target_bps,
+            ZyncxError::InvalidTreasurySplit
+        );
+        Ok(())
+    }
+}
+
+/// Hot/cold split of a native vault's SOL. Proof-verified withdrawals only
+/// ever draw on the hot treasury, so a broken verifier can drain at most the
+/// hot share before the guardians react; the rest sits in the cold treasury
+/// (seeds = [b"cold_treasury", vault]) behind a role quorum and the vault's
+/// config timelock.
+/// seeds = [b"treasury_policy", vault]
+#[account]
+pub struct TreasuryPolicy {
+    pub bump: u8,
+    pub vault: Pubkey,
+    pub hot_target_bps: u16,
+    pub refill_threshold_bps: u16,
+    pub last_refill_at: i64,
+    pub total_swept: u64,
+    pub total_refilled: u64,
+    pub total_cold_spent: u64,
+    /// Bump of the cold treasury PDA, which signs its system transfers
+    pub cold_bump: u8,
+}
+
+impl TreasuryPolicy {
+    pub const INIT_SPACE: usize = 8 + // discriminator
+        1 +  // bump
+        32 + // vault
+        2 +  // hot_target_bps
+        2 +  // refill_threshold_bps
+        8 +  // last_refill_at
+        8 +  // total_swept
+        8 +  // total_refilled
+        8 +  // total_cold_spent
+        1;   // cold_bump
+
+    /// Signer seeds of the cold treasury PDA
+    pub fn cold_seeds(&self) -> [&[u8]; 3] {
+        [b"cold_treasury", self.vault.as_ref(), std::slice::from_ref(&self.cold_bump)]
+    }
+
+    pub fn apply_split(&mut self, params: &TreasurySplitParams) {
+        self.hot_target_bps = params.hot_target_bps;
+        self.refill_threshold_bps = params.refill_threshold_bps;
+    }
+
+    /// Lamports to move from hot to cold to bring the hot side down to its
+    /// target share. Balances exclude each account's rent reserve.
+    pub fn sweep_amount(&self, hot: u64, cold: u64) -> u64 {
+        let target = share(hot.saturating_add(cold), self.hot_target_bps);
+        hot.saturating_sub(target)
+    }
+
+    /// Lamports to move from cold to hot, or 0 while the hot side is above
+    /// its refill threshold or a refill happened within the cooldown.
+    /// Balances exclude each account's rent reserve.
+    pub fn refill_amount(&self, hot: u64, cold: u64, now: i64) -> u64 {
+        if now < self.last_refill_at.saturating_add(HOT_REFILL_COOLDOWN_SECONDS) {
+            return 0;
+        }
+        let total = hot.saturating_add(cold);
+        if hot >= share(total, self.refill_threshold_bps) {
+            return 0;
+        }
+        share(total, self.hot_target_bps).saturating_sub(hot).min(cold)
+    }
+}
+
+fn share(total: u64, bps: u16) -> u64 {
+    ((total as u128) * (bps as u128) / 10_000) as u64
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ColdSpendStatus {
+    /// Collecting role votes and waiting out the timelock
+    Pending,
+    /// Paid out of the cold treasury
+    Executed,
+    /// Rejected by a quorum of roles
+    Rejected,
+}
+
+/// A proposed payment out of a vault's cold treasury, e.g. to rebalance
+/// into a migrated vault. Needs a quorum of distinct signers voting for
+/// roles they hold and the vault's config timelock, counted from the
+/// proposal. A key holding several roles votes only once.
+/// seeds = [b"cold_spend", vault, spend_id]
+#[account]
+pub struct ColdSpend {
+    pub bump: u8,
+    pub vault: Pubkey,
+    pub spend_id: u64,
+    pub proposer: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    /// Hash of the off-chain rationale for the spend
+    pub memo_hash: [u8; 32],
+    /// Bitmask over `Role` of the roles that approved
+    pub approvals: u8,
+    /// Bitmask over `Role` of the roles that rejected
+    pub rejections: u8,
+    pub status: ColdSpendStatus,
+    pub proposed_at: i64,
+    /// Earliest timestamp at which the spend can be executed
+    pub eta: i64,
+    pub resolved_at: i64,
+    /// Key that cast each role's vote, indexed by `Role`
+    pub voters: [Pubkey; ROLE_COUNT],
+}
+
+impl ColdSpend {
+    pub const INIT_SPACE: usize = 8 + // discriminator
+        1 +  // bump
+        32 + // vault
+        8 +  // spend_id
+        32 + // proposer
+        32 + // recipient
+        8 +  // amount
+        32 + // memo_hash
+        1 +  // approvals
+        1 +  // rejections
+        1 +  // status
+        8 +  // proposed_at
+        8 +  // eta
+        8 +  // resolved_at
+        32 * ROLE_COUNT; // voters
+
+    /// Record `voter`'s vote for `role`; each role and each key votes once
+    /// per spend, so every counted vote comes from a distinct signer. Moves
+    /// the spend to Rejected once a quorum rejected it.
+    pub fn record_vote(&mut self, role: Role, voter: Pubkey, approve: bool) -> Result<()> {
+        require!(
+            self.status == ColdSpendStatus::Pending,
+            ZyncxError::ColdSpendClosed
+        );
+        let bit = 1u8 << role as u8;
+        let voted = self.approvals | self.rejections;
+        require!(voted & bit == 0, ZyncxError::AlreadyVoted);
+        require!(
+            !(0..ROLE_COUNT).any(|index| voted & (1 << index) != 0 && self.voters[index] == voter),
+            ZyncxError::SignerAlreadyVoted
+        );
+
+        self.voters[role as usize] = voter;
+        if approve {
+            self.approvals |= bit;
+        } else {
+            self.rejections |= bit;
+            if self.rejections.count_ones() >= COLD_SPEND_QUORUM {
+                self.status = ColdSpendStatus::Rejected;
+            }
+        }
+        Ok(())
+    }
+
+    /// Approvals whose voter still holds the role it voted for; a vote
+    /// lapses once its role is reassigned
+    pub fn current_approvals(&self, roles: &Roles) -> u32 {
+        [Role::Admin, Role::Pauser, Role::FeeManager, Role::Compliance]
+            .into_iter()
+            .filter(|role| {
+                self.approvals & (1 << *role as u8) != 0
+                    && roles.has_role(*role, &self.voters[*role as usize])
+            })
+            .count() as u32
+    }
+
+    /// Approved by a quorum of current role holders and by more signers
+    /// than rejected it
+    pub fn is_approved(&self, roles: &Roles) -> bool {
+        let approvals = self.current_approvals(roles);
+        self.status == ColdSpendStatus::Pending
+            && approvals >= COLD_SPEND_QUORUM
+            && approvals > self.rejections.count_ones()
+    }
+}
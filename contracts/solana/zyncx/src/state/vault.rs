@@ -1,11 +1,33 @@
 use anchor_lang::prelude::*;
 
+use crate::state::{ProofSystem, TreeHash, DEPOSIT_EPOCH_SECONDS};
+
+/// Maximum number of fixed denominations a vault can accept
+pub const MAX_DENOMINATIONS: usize = 4;
+
+/// Granularity of the note-age cutoff so a proof stays valid for a while
+pub const NOTE_AGE_BUCKET_SLOTS: u64 = 150;
+
+/// Shortest grace period between a sunset flag and archiving (30 days)
+pub const MIN_SUNSET_GRACE_SECONDS: i64 = 30 * 86_400;
+
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
 pub enum VaultType {
     Native,      // SOL
     Alternative, // SPL Token
 }
 
+/// Sunset stage of a vault. Moves forward only through governance flagging
+/// and the archive crank; governance may reset it to Active.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum VaultLifecycle {
+    Active,
+    /// Flagged as abandoned: no new deposits, note holders should exit
+    WithdrawalsOnly,
+    /// Grace period over: swaps are closed too, withdrawals stay open
+    Archived,
+}
+
 #[account]
 pub struct VaultState {
     pub bump: u8,
@@ -15,6 +37,47 @@ pub struct VaultState {
     pub nonce: u64,
     pub authority: Pubkey,
     pub total_deposited: u64,
+    /// Epoch of the active merkle tree (`merkle_tree`) receiving deposits
+    pub tree_epoch: u64,
+    /// Leaf capacity applied to trees created for this vault
+    pub max_tree_leaves: u32,
+    /// Fill level (bps) at which the active tree may be rotated
+    pub rotation_threshold_bps: u16,
+    /// When set, deposits and withdrawals must match one of `denominations`
+    pub fixed_denominations: bool,
+    pub denomination_count: u8,
+    pub denominations: [u64; MAX_DENOMINATIONS],
+    /// Delay before proposed sensitive config changes can be executed
+    pub config_timelock_seconds: i64,
+    /// Set by the pauser role; halts deposits, withdrawals and swaps
+    pub paused: bool,
+    /// Ceiling on `total_deposited` (0 = uncapped)
+    pub max_total_deposited: u64,
+    /// Largest single deposit accepted (0 = uncapped)
+    pub max_deposit_per_tx: u64,
+    /// Volume accepted per `DEPOSIT_EPOCH_SECONDS` (0 = unlimited)
+    pub max_deposit_per_epoch: u64,
+    /// Epoch `epoch_deposited` refers to
+    pub deposit_epoch: i64,
+    /// Volume deposited during `deposit_epoch`
+    pub epoch_deposited: u64,
+    /// Minimum age (slots) of a note before it can be withdrawn or swapped (0 = off)
+    pub min_note_age_slots: u64,
+    /// Deposit epochs without deposits or withdrawals before governance may
+    /// flag the vault for sunset (0 = never)
+    pub sunset_inactivity_epochs: u64,
+    /// Time between the sunset flag and archiving
+    pub sunset_grace_seconds: i64,
+    pub lifecycle: VaultLifecycle,
+    /// When a WithdrawalsOnly vault may be archived
+    pub archive_at: i64,
+    /// How spend proofs are verified; changes through the config timelock
+    pub proof_system: ProofSystem,
+    /// Hash of trees created for this vault; changes through the config
+    /// timelock and reaches the active tree only while it is empty
+    pub tree_hash: TreeHash,
+    /// Layout version (`CURRENT_ACCOUNT_VERSION`); see `migrate_account`
+    pub version: u8,
 }
 
 impl VaultState {
@@ -25,7 +88,116 @@ impl VaultState {
         32 + // merkle_tree
         8 +  // nonce
         32 + // authority
-        8;   // total_deposited
+        8 +  // total_deposited
+        8 +  // tree_epoch
+        4 +  // max_tree_leaves
+        2 +  // rotation_threshold_bps
+        1 +  // fixed_denominations
+        1 +  // denomination_count
+        8 * MAX_DENOMINATIONS + // denominations
+        8 +  // config_timelock_seconds
+        1 +  // paused
+        8 +  // max_total_deposited
+        8 +  // max_deposit_per_tx
+        8 +  // max_deposit_per_epoch
+        8 +  // deposit_epoch
+        8 +  // epoch_deposited
+        8 +  // min_note_age_slots
+        8 +  // sunset_inactivity_epochs
+        8 +  // sunset_grace_seconds
+        1 +  // lifecycle
+        8 +  // archive_at
+        1 +  // proof_system
+        1 +  // tree_hash
+        1;   // version
+
+    /// Whether `amount` is accepted under the vault's denomination policy
+    pub fn is_valid_denomination(&self, amount: u64) -> bool {
+        !self.fixed_denominations
+            || self.denominations[..self.denomination_count as usize].contains(&amount)
+    }
+
+    /// Latest insertion slot a spent note may have, rounded down to
+    /// `NOTE_AGE_BUCKET_SLOTS`; `u64::MAX` when the vault sets no minimum
+    /// note age. Every spend circuit takes it as a public input and checks
+    /// the slot hashed into the note's leaf against it.
+    pub fn note_age_cutoff(&self, slot: u64) -> u64 {
+        if self.min_note_age_slots == 0 {
+            return u64::MAX;
+        }
+
+        let cutoff = slot.saturating_sub(self.min_note_age_slots);
+        cutoff - cutoff % NOTE_AGE_BUCKET_SLOTS
+    }
+
+    /// Sanity-check the `remaining_amount` public input of a spend against
+    /// the change note and the vault's accounting: a change note exists
+    /// exactly when value remains, and no spent note can be worth more than
+    /// the vault has ever taken in.
+    pub fn check_spent_note(
+        &self,
+        amount: u64,
+        remaining_amount: u64,
+        new_commitment: &[u8; 32],
+    ) -> Result<()> {
+        require!(
+            (remaining_amount == 0) == (*new_commitment == [0u8; 32]),
+            crate::errors::ZyncxError::RemainingAmountMismatch
+        );
+
+        let note_value = amount
+            .checked_add(remaining_amount)
+            .ok_or(crate::errors::ZyncxError::ArithmeticOverflow)?;
+        require!(
+            note_value <= self.total_deposited,
+            crate::errors::ZyncxError::NoteValueExceedsDeposits
+        );
+
+        Ok(())
+    }
+
+    /// Whether `last_activity_at` is old enough to flag the vault for sunset
+    pub fn is_inactive(&self, last_activity_at: i64, now: i64) -> bool {
+        self.sunset_inactivity_epochs > 0
+            && now / DEPOSIT_EPOCH_SECONDS - last_activity_at / DEPOSIT_EPOCH_SECONDS
+                >= self.sunset_inactivity_epochs as i64
+    }
+
+    /// Enforce the vault's deposit caps and count `amount` against the
+    /// current epoch. `total_deposited` is updated by the caller.
+    pub fn apply_deposit_limits(&mut self, amount: u64, now: i64) -> Result<()> {
+        require!(
+            self.max_deposit_per_tx == 0 || amount <= self.max_deposit_per_tx,
+            crate::errors::ZyncxError::DepositTooLarge
+        );
+
+        let total = self
+            .total_deposited
+            .checked_add(amount)
+            .ok_or(crate::errors::ZyncxError::ArithmeticOverflow)?;
+        require!(
+            self.max_total_deposited == 0 || total <= self.max_total_deposited,
+            crate::errors::ZyncxError::VaultDepositCapReached
+        );
+
+        let epoch = now / DEPOSIT_EPOCH_SECONDS;
+        if epoch != self.deposit_epoch {
+            self.deposit_epoch = epoch;
+            self.epoch_deposited = 0;
+        }
+
+        let epoch_total = self
+            .epoch_deposited
+            .checked_add(amount)
+            .ok_or(crate::errors::ZyncxError::ArithmeticOverflow)?;
+        require!(
+            self.max_deposit_per_epoch == 0 || epoch_total <= self.max_deposit_per_epoch,
+            crate::errors::ZyncxError::DepositRateLimited
+        );
+        self.epoch_deposited = epoch_total;
+
+        Ok(())
+    }
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
@@ -36,8 +208,102 @@ pub struct SwapParam {
     pub amount_in: u64,
     pub min_amount_out: u64,
     pub fee: u32, // basis points (1e-4)
+    /// Value left in the change note (0 for a full swap); public input of
+    /// the spend proof
+    pub remaining_amount: u64,
+    /// Integrator credited with part of the protocol fee (default = none).
+    /// Bound by the proof through `ext_data_hash`, so a relayer cannot
+    /// substitute its own key.
+    pub referrer: Pubkey,
 }
 
 impl SwapParam {
-    pub const SIZE: usize = 32 + 32 + 32 + 8 + 8 + 4;
+    pub const SIZE: usize = 32 + 32 + 32 + 8 + 8 + 4 + 8 + 32;
+
+    /// Account that must receive the swap output: the proof-bound recipient
+    /// for SOL output, otherwise its associated token account for `dst_token`
+    pub fn output_account(&self) -> Pubkey {
+        if self.dst_token == crate::instructions::NATIVE_MINT {
+            self.recipient
+        } else {
+            anchor_spl::associated_token::get_associated_token_address(
+                &self.recipient,
+                &self.dst_token,
+            )
+        }
+    }
+
+    /// The swap proof's `ext_data_hash`: keccak(referrer) with the top byte
+    /// cleared, or zero for a swap without a referrer
+    pub fn ext_data_hash(&self) -> [u8; 32] {
+        if self.referrer == Pubkey::default() {
+            return [0u8; 32];
+        }
+        let mut hash = solana_program::keccak::hash(self.referrer.as_ref()).0;
+        hash[0] = 0;
+        hash
+    }
+}
+
+/// Withdrawal terms the user authorizes off-chain. Their hash is a public
+/// input of the withdrawal proof, so a relayer cannot raise its tip, accept
+/// a higher protocol fee or strip the disclosure without invalidating it.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct WithdrawExtData {
+    /// Highest protocol fee the user accepts out of the withdrawn amount
+    pub max_fee: u64,
+    /// Paid to the transaction payer (the relayer) out of the withdrawn amount
+    pub relayer_tip: u64,
+    /// Withdrawal details encrypted to the note owner's viewing key
+    pub viewing_ciphertext: Vec<u8>,
+    /// Ephemeral pubkey of a payment to a stealth address, emitted so the
+    /// recipient can detect it (zero if none)
+    pub ephemeral_pubkey: [u8; 32],
+    /// `fee_schedule_hash` of the relayer's registration the tip was
+    /// accepted under (zero if the relayer is not pinned)
+    pub fee_schedule_hash: [u8; 32],
+    /// Hash the proof was generated over; must match `hash` for this call
+    pub ext_data_hash: [u8; 32],
+}
+
+impl WithdrawExtData {
+    /// keccak(recipient || relayer || max_fee || relayer_tip || keccak(viewing_ciphertext)
+    /// || ephemeral_pubkey || fee_schedule_hash),
+    /// top byte cleared so it is a valid BN254 field element
+    pub fn hash(&self, recipient: &Pubkey, relayer: &Pubkey) -> [u8; 32] {
+        use solana_program::keccak;
+
+        let ciphertext_hash = keccak::hash(&self.viewing_ciphertext).0;
+        let mut hash = keccak::hashv(&[
+            recipient.as_ref(),
+            relayer.as_ref(),
+            &self.max_fee.to_le_bytes(),
+            &self.relayer_tip.to_le_bytes(),
+            &ciphertext_hash,
+            &self.ephemeral_pubkey,
+            &self.fee_schedule_hash,
+        ])
+        .0;
+        hash[0] = 0;
+        hash
+    }
+
+    /// Recompute the hash for the accounts of this call and check it against
+    /// the one the proof commits to
+    pub fn verify(&self, recipient: &Pubkey, relayer: &Pubkey) -> Result<[u8; 32]> {
+        let hash = self.hash(recipient, relayer);
+        require!(hash == self.ext_data_hash, crate::errors::ZyncxError::ExtDataMismatch);
+        Ok(hash)
+    }
+
+    /// Split a withdrawal into (payout, tip) once the protocol fee is known,
+    /// rejecting fees above what the user authorized
+    pub fn split(&self, amount: u64, fee: u64) -> Result<(u64, u64)> {
+        require!(fee <= self.max_fee, crate::errors::ZyncxError::ExtDataMismatch);
+        let payout = amount
+            .checked_sub(fee)
+            .and_then(|rest| rest.checked_sub(self.relayer_tip))
+            .ok_or(crate::errors::ZyncxError::InvalidWithdrawalAmount)?;
+        Ok((payout, self.relayer_tip))
+    }
 }
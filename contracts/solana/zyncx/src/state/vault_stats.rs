@@ -0,0 +1,104 @@
+use anchor_lang::prelude::*;
+
+/// Length of a deposit epoch used for activity bucketing (1 hour)
+pub const DEPOSIT_EPOCH_SECONDS: i64 = 3_600;
+
+/// Per-vault anonymity set metrics, maintained by deposit and withdraw.
+/// Frontends read this to warn users before withdrawing from a small set.
+#[account]
+pub struct VaultStats {
+    pub bump: u8,
+    pub vault: Pubkey,
+    /// Notes ever inserted (deposits and change notes)
+    pub total_notes: u64,
+    /// Notes consumed by withdrawals and transfers
+    pub spent_notes: u64,
+    /// Number of distinct deposit epochs that saw at least one deposit
+    pub unique_deposit_epochs: u64,
+    /// Most recent deposit epoch
+    pub last_deposit_epoch: i64,
+    /// Deposits recorded in `last_deposit_epoch`
+    pub current_epoch_deposits: u32,
+    /// Deposits recorded in the epoch before `last_deposit_epoch`
+    pub previous_epoch_deposits: u32,
+    pub updated_at: i64,
+    /// Anonymity set size integrated over time (note-seconds) up to
+    /// `updated_at`. Like a cumulative price, the difference between two
+    /// snapshots divided by the time between them is the time-weighted
+    /// average set size over that window.
+    pub anonymity_set_seconds: u128,
+}
+
+impl VaultStats {
+    pub const INIT_SPACE: usize = 8 + // discriminator
+        1 +  // bump
+        32 + // vault
+        8 +  // total_notes
+        8 +  // spent_notes
+        8 +  // unique_deposit_epochs
+        8 +  // last_deposit_epoch
+        4 +  // current_epoch_deposits
+        4 +  // previous_epoch_deposits
+        8 +  // updated_at
+        16;  // anonymity_set_seconds
+
+    /// Bind a freshly created stats account to its vault (init_if_needed)
+    pub fn ensure_initialized(&mut self, vault: Pubkey, bump: u8) {
+        if self.vault == Pubkey::default() {
+            self.bump = bump;
+            self.vault = vault;
+        }
+    }
+
+    /// Notes that are still unspent, i.e. the effective anonymity set
+    pub fn anonymity_set_size(&self) -> u64 {
+        self.total_notes.saturating_sub(self.spent_notes)
+    }
+
+    /// Integrate the current set size up to `now`; called before every
+    /// change to the set
+    fn accrue(&mut self, now: i64) {
+        let elapsed = now.saturating_sub(self.updated_at).max(0) as u128;
+        self.anonymity_set_seconds = self
+            .anonymity_set_seconds
+            .saturating_add(self.anonymity_set_size() as u128 * elapsed);
+        self.updated_at = now;
+    }
+
+    pub fn record_deposit(&mut self, now: i64) {
+        self.accrue(now);
+        let epoch = now / DEPOSIT_EPOCH_SECONDS;
+
+        if self.unique_deposit_epochs == 0 || epoch != self.last_deposit_epoch {
+            self.previous_epoch_deposits = if epoch == self.last_deposit_epoch + 1 {
+                self.current_epoch_deposits
+            } else {
+                0
+            };
+            self.current_epoch_deposits = 0;
+            self.last_deposit_epoch = epoch;
+            self.unique_deposit_epochs += 1;
+        }
+
+        self.current_epoch_deposits = self.current_epoch_deposits.saturating_add(1);
+        self.total_notes += 1;
+        self.updated_at = now;
+    }
+
+    /// Record a spent note and, for partial withdrawals, the change note
+    pub fn record_spend(&mut self, change_note: bool, now: i64) {
+        self.accrue(now);
+        self.spent_notes += 1;
+        if change_note {
+            self.total_notes += 1;
+        }
+        self.updated_at = now;
+    }
+
+    /// Record a shielded transfer: one note spent, a recipient note and
+    /// optionally a change note inserted
+    pub fn record_transfer(&mut self, change_note: bool, now: i64) {
+        self.record_spend(change_note, now);
+        self.total_notes += 1;
+    }
+}
@@ -1,11 +1,54 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{instruction::Instruction, program::invoke};
+use solana_bn254::prelude::{alt_bn128_addition, alt_bn128_multiplication, alt_bn128_pairing};
+
+use crate::errors::ZyncxError;
+use crate::state::VaultState;
 
 pub const PROOF_SIZE: usize = 256; // Groth16 proof: 2*32 (A) + 2*64 (B) + 2*32 (C) = 256 bytes
 pub const PUBLIC_INPUT_SIZE: usize = 32; // Each public input is a 32-byte field element
+/// Most public inputs a stored Groth16 verification key can cover
+pub const MAX_VK_PUBLIC_INPUTS: usize = 16;
+
+/// BN254 base field modulus (big endian), used to negate G1 points
+const BN254_FIELD_MODULUS: [u8; 32] = [
+    0x30, 0x64, 0x4e, 0x72, 0xe1, 0x31, 0xa0, 0x29,
+    0xb8, 0x50, 0x45, 0xb6, 0x81, 0x81, 0x58, 0x5d,
+    0x97, 0x81, 0x6a, 0x91, 0x68, 0x71, 0xca, 0x8d,
+    0x3c, 0x20, 0x8c, 0x16, 0xd8, 0x7c, 0xfd, 0x47,
+];
 
+/// How a vault's spend proofs are verified
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum ProofSystem {
+    /// Noir UltraHonk proof, verified by CPI to the circuit's Sunspot
+    /// verifier program
+    #[default]
+    UltraHonk,
+    /// Groth16 proof, verified in-program on alt_bn128 against the vault's
+    /// `VerificationKey` for the circuit
+    Groth16,
+}
+
+/// Circuit a proof is for; selects the Groth16 verification key
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ProofCircuit {
+    /// Withdrawals, swaps and proof checks (mixer/)
+    Mixer,
+    /// Shielded transfers (transfer/)
+    Transfer,
+    /// Batch withdrawals (withdraw_batch/)
+    BatchWithdraw,
+}
+
+/// Groth16 verification key of one circuit for one vault, uploaded by the
+/// vault authority while the vault is not verifying with Groth16.
+/// seeds = [b"verification_key", vault, circuit as u8]
 #[account]
 pub struct VerificationKey {
     pub bump: u8,
+    pub vault: Pubkey,
+    pub circuit: ProofCircuit,
     pub alpha_g1: [u8; 64],      // G1 point (x, y)
     pub beta_g2: [u8; 128],      // G2 point (x1, x2, y1, y2)
     pub gamma_g2: [u8; 128],     // G2 point
@@ -16,6 +59,8 @@ pub struct VerificationKey {
 impl VerificationKey {
     pub const BASE_SPACE: usize = 8 + // discriminator
         1 +   // bump
+        32 +  // vault
+        1 +   // circuit
         64 +  // alpha_g1
         128 + // beta_g2
         128 + // gamma_g2
@@ -146,6 +191,136 @@ pub fn verify_groth16(
     Ok(true)
 }
 
+/// Groth16 check on the alt_bn128 syscalls:
+/// e(-A, B) · e(alpha, beta) · e(vk_x, gamma) · e(C, delta) == 1, where
+/// vk_x = ic[0] + sum(input_i · ic[i + 1])
+pub fn verify_groth16_proof(
+    proof: &Groth16Proof,
+    public_inputs: &[[u8; 32]],
+    vk: &VerificationKey,
+) -> Result<()> {
+    require!(
+        vk.ic.len() == public_inputs.len() + 1,
+        ZyncxError::VerificationKeyMismatch
+    );
+
+    let mut vk_x = vk.ic[0];
+    for (input, ic) in public_inputs.iter().zip(vk.ic.iter().skip(1)) {
+        let mut mul_input = [0u8; 96];
+        mul_input[..64].copy_from_slice(ic);
+        mul_input[64..].copy_from_slice(input);
+        let product =
+            alt_bn128_multiplication(&mul_input).map_err(|_| ZyncxError::InvalidZKProof)?;
+
+        let mut add_input = [0u8; 128];
+        add_input[..64].copy_from_slice(&vk_x);
+        add_input[64..].copy_from_slice(&product);
+        let sum = alt_bn128_addition(&add_input).map_err(|_| ZyncxError::InvalidZKProof)?;
+        vk_x.copy_from_slice(&sum);
+    }
+
+    let neg_a = negate_g1(&proof.a);
+    let mut pairing_input = Vec::with_capacity(4 * (64 + 128));
+    for (g1, g2) in [
+        (&neg_a, &proof.b),
+        (&vk.alpha_g1, &vk.beta_g2),
+        (&vk_x, &vk.gamma_g2),
+        (&proof.c, &vk.delta_g2),
+    ] {
+        pairing_input.extend_from_slice(g1);
+        pairing_input.extend_from_slice(g2);
+    }
+    let result = alt_bn128_pairing(&pairing_input).map_err(|_| ZyncxError::InvalidZKProof)?;
+
+    let mut one = [0u8; 32];
+    one[31] = 1;
+    require!(result.as_slice() == one, ZyncxError::InvalidZKProof);
+    Ok(())
+}
+
+/// Verifies a circuit's proof the way its vault is configured to, so
+/// handlers only assemble public inputs and circuits can move between proof
+/// systems without touching them
+pub struct ProofVerifier<'a, 'info> {
+    pub proof_system: ProofSystem,
+    /// Noir verifier program of the circuit (UltraHonk)
+    pub verifier_program: &'a AccountInfo<'info>,
+    /// The vault's key for the circuit (Groth16)
+    pub verification_key: Option<&'a VerificationKey>,
+}
+
+impl<'a, 'info> ProofVerifier<'a, 'info> {
+    pub fn for_vault(
+        vault: &VaultState,
+        verifier_program: &'a AccountInfo<'info>,
+        verification_key: &'a Option<Box<Account<'info, VerificationKey>>>,
+    ) -> Self {
+        Self {
+            proof_system: vault.proof_system,
+            verifier_program,
+            verification_key: verification_key.as_deref().map(|vk| &**vk),
+        }
+    }
+
+    /// `public_inputs` are 32-byte big-endian field elements in circuit order
+    pub fn verify(&self, proof: &[u8], public_inputs: &[[u8; 32]]) -> Result<()> {
+        require!(!proof.is_empty(), ZyncxError::InvalidZKProof);
+
+        match self.proof_system {
+            ProofSystem::UltraHonk => {
+                // Verifier instruction data: [proof][public_inputs...]
+                let mut verifier_input =
+                    Vec::with_capacity(proof.len() + PUBLIC_INPUT_SIZE * public_inputs.len());
+                verifier_input.extend_from_slice(proof);
+                for input in public_inputs {
+                    verifier_input.extend_from_slice(input);
+                }
+
+                let instruction = Instruction {
+                    program_id: *self.verifier_program.key,
+                    accounts: vec![],
+                    data: verifier_input,
+                };
+                invoke(&instruction, std::slice::from_ref(self.verifier_program)).map_err(|e| {
+                    msg!("Noir proof verification failed: {:?}", e);
+                    ZyncxError::InvalidZKProof
+                })?;
+            }
+            ProofSystem::Groth16 => {
+                let vk = self
+                    .verification_key
+                    .ok_or(ZyncxError::MissingVerificationKey)?;
+                verify_groth16_proof(&Groth16Proof::from_bytes(proof)?, public_inputs, vk)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Big-endian field element of a u64 public input
+pub fn u64_public_input(value: u64) -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    bytes[24..32].copy_from_slice(&value.to_be_bytes());
+    bytes
+}
+
+/// -(x, y) = (x, p - y); the point at infinity (all zero) is its own negation
+fn negate_g1(point: &[u8; 64]) -> [u8; 64] {
+    let mut negated = *point;
+    if point[32..] == [0u8; 32] {
+        return negated;
+    }
+
+    let mut borrow = 0i16;
+    for i in (0..32).rev() {
+        let diff = BN254_FIELD_MODULUS[i] as i16 - point[32 + i] as i16 - borrow;
+        borrow = (diff < 0) as i16;
+        negated[32 + i] = diff.rem_euclid(256) as u8;
+    }
+    negated
+}
+
 pub mod alt_bn128 {
     #![allow(dead_code)]
 
@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+
+/// Upper bound for activity ciphertexts encrypted to a viewing key
+pub const MAX_VIEWING_CIPHERTEXT_LEN: usize = 256;
+
+/// X25519 viewing key registered by a user for selective disclosure.
+/// Holders of the matching secret can decrypt the user's deposit and
+/// withdrawal disclosures but cannot spend notes.
+#[account]
+pub struct ViewingKey {
+    pub bump: u8,
+    pub owner: Pubkey,
+    pub viewing_pubkey: [u8; 32],
+    pub registered_at: i64,
+}
+
+impl ViewingKey {
+    pub const INIT_SPACE: usize = 8 + // discriminator
+        1 +  // bump
+        32 + // owner
+        32 + // viewing_pubkey
+        8;   // registered_at
+
+    pub fn check_ciphertext_len(viewing_ciphertext: &[u8]) -> Result<()> {
+        require!(
+            viewing_ciphertext.len() <= MAX_VIEWING_CIPHERTEXT_LEN,
+            crate::errors::ZyncxError::ViewingCiphertextTooLarge
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,414 @@
+//! Merkle tree hashing over a pluggable two-to-one hash.
+//!
+//! A tree over `n` leaves hashes each commitment with its insertion slot
+//! (`leaf_node`), then pairs each level left to right; an unpaired node is
+//! hashed with the backend's `zero_node` for its level. Keccak trees grow
+//! with their leaves and pair with zero; Poseidon trees are the circuits'
+//! fixed `POSEIDON_TREE_DEPTH` tree over `POSEIDON_ZEROS`, so a Poseidon
+//! root is exactly what the Noir circuits compute. Level and leaf hashing
+//! go through `HashBackend` in batches, so a backend can spread a batch
+//! across threads or SIMD lanes. Every backend must return what the
+//! sequential defaults return; `tests/golden.rs` pins the outputs.
+//!
+//! The program keeps only the tree's frontier and appends with
+//! `append_leaf`; clients rebuild full trees from the leaf log with
+//! `merkle_root` and `auth_path`.
+
+pub type Hash = [u8; 32];
+
+/// Sibling of an unpaired node
+pub const ZERO_HASH: Hash = [0u8; 32];
+
+/// BN254 scalar field modulus, big endian. Poseidon trees only accept
+/// commitments below it.
+pub const BN254_FIELD_MODULUS: Hash = [
+    0x30, 0x64, 0x4e, 0x72, 0xe1, 0x31, 0xa0, 0x29, 0xb8, 0x50, 0x45, 0xb6, 0x81, 0x81, 0x58, 0x5d,
+    0x28, 0x33, 0xe8, 0x48, 0x79, 0xb9, 0x70, 0x91, 0x43, 0xe1, 0xf5, 0x93, 0xf0, 0x00, 0x00, 0x01,
+];
+
+/// Levels of a Poseidon tree above its leaves: the circuits' `TREE_DEPTH`
+pub const POSEIDON_TREE_DEPTH: usize = 20;
+
+/// Roots of empty Poseidon subtrees by height, as the circuits'
+/// `get_zero_value`: `hash_1([0])` for an empty leaf, then `hash_2` of two
+/// copies of the level below. The last entry is the root of an empty tree.
+pub const POSEIDON_ZEROS: [Hash; POSEIDON_TREE_DEPTH + 1] = [
+    [
+        0x2a, 0x09, 0xa9, 0xfd, 0x93, 0xc5, 0x90, 0xc2, 0x6b, 0x91, 0xef, 0xfb, 0xb2, 0x49, 0x9f,
+        0x07, 0xe8, 0xf7, 0xaa, 0x12, 0xe2, 0xb4, 0x94, 0x0a, 0x3a, 0xed, 0x24, 0x11, 0xcb, 0x65,
+        0xe1, 0x1c,
+    ],
+    [
+        0x17, 0x19, 0x2e, 0x62, 0xa1, 0x57, 0x55, 0x68, 0x49, 0xd9, 0x3b, 0x3c, 0x6b, 0xe1, 0xe2,
+        0xbd, 0x1f, 0x3f, 0x16, 0x60, 0xd1, 0x0d, 0xd9, 0xb1, 0xff, 0xc4, 0x29, 0xaa, 0x90, 0x21,
+        0x25, 0x2c,
+    ],
+    [
+        0x04, 0xd5, 0xab, 0xb4, 0xc7, 0xf7, 0x7e, 0x3b, 0x5d, 0x8b, 0xc7, 0xa0, 0x49, 0xd5, 0xba,
+        0x6e, 0x79, 0xf2, 0x9c, 0x5c, 0x5a, 0x9e, 0xdf, 0x0a, 0x58, 0x72, 0x6e, 0x65, 0x3e, 0x8b,
+        0xc0, 0xc7,
+    ],
+    [
+        0x0e, 0xa5, 0x59, 0xa9, 0x0b, 0xea, 0xc7, 0xd4, 0x8c, 0xc7, 0x0d, 0xfa, 0xd2, 0xfe, 0xa2,
+        0x76, 0x21, 0xb7, 0x6f, 0x14, 0x04, 0x46, 0x32, 0x9b, 0x29, 0x3a, 0x04, 0x45, 0x4c, 0xcb,
+        0x0e, 0xc3,
+    ],
+    [
+        0x26, 0xf5, 0x2f, 0x9b, 0x31, 0xef, 0x80, 0x78, 0x27, 0x98, 0xf2, 0xae, 0x44, 0x65, 0x9d,
+        0xc1, 0xbe, 0xdf, 0x53, 0xac, 0x38, 0x36, 0x6d, 0x4d, 0xfe, 0xd7, 0x4c, 0xe7, 0xd9, 0x5a,
+        0xd1, 0xd5,
+    ],
+    [
+        0x2f, 0xa2, 0x7c, 0x5c, 0xf0, 0x18, 0x56, 0x54, 0xd6, 0xdc, 0xf1, 0x0d, 0xf1, 0xb3, 0x82,
+        0x32, 0x4a, 0xbd, 0xf6, 0x2d, 0x73, 0xd3, 0x95, 0xbe, 0x1c, 0xc9, 0x35, 0xab, 0x47, 0x03,
+        0x54, 0xf0,
+    ],
+    [
+        0x01, 0xc0, 0x8b, 0x39, 0x62, 0x1c, 0x26, 0x23, 0x50, 0xbc, 0x2d, 0xdc, 0xa3, 0x69, 0xa9,
+        0x68, 0xa6, 0x87, 0x50, 0xda, 0xcb, 0x26, 0x9e, 0x7a, 0xa9, 0x91, 0x52, 0x45, 0xeb, 0x0e,
+        0xc3, 0xf1,
+    ],
+    [
+        0x2a, 0x39, 0xb3, 0xa3, 0x55, 0xf8, 0x05, 0x0d, 0xb5, 0x18, 0x18, 0x06, 0x4c, 0xf8, 0xca,
+        0xa6, 0xf1, 0x71, 0x48, 0x53, 0x5e, 0xdf, 0xf5, 0x09, 0x86, 0x25, 0xbc, 0x53, 0x9f, 0xd4,
+        0xc0, 0x38,
+    ],
+    [
+        0x02, 0xf8, 0x47, 0x4b, 0x5f, 0xdf, 0x6c, 0xfc, 0xdb, 0x20, 0x6e, 0x08, 0xca, 0x30, 0xa6,
+        0x9d, 0x65, 0x9f, 0xf1, 0xaa, 0x27, 0x4f, 0x19, 0x51, 0xb9, 0xa2, 0x40, 0xa4, 0x15, 0x04,
+        0xa8, 0x97,
+    ],
+    [
+        0x25, 0x5c, 0x85, 0x88, 0xa2, 0x60, 0x94, 0x72, 0xe1, 0x54, 0x7d, 0x54, 0x07, 0xc2, 0x5f,
+        0x8f, 0x33, 0x91, 0x70, 0x34, 0x30, 0x2b, 0x40, 0x76, 0xd7, 0x8c, 0xf0, 0x7f, 0x60, 0xd6,
+        0x95, 0x46,
+    ],
+    [
+        0x0b, 0x01, 0xab, 0x30, 0x90, 0xcb, 0xdc, 0x90, 0x0f, 0xab, 0x5c, 0x56, 0x94, 0x5a, 0xe0,
+        0x60, 0xc3, 0xc4, 0x34, 0x71, 0xa6, 0xc4, 0x21, 0x23, 0x5e, 0x5c, 0x9f, 0xb7, 0xd9, 0xd0,
+        0x83, 0x82,
+    ],
+    [
+        0x15, 0x95, 0x09, 0x47, 0xde, 0xae, 0x80, 0x04, 0x6b, 0x47, 0xad, 0x93, 0x6c, 0x2b, 0xe2,
+        0xf9, 0xa5, 0x94, 0xf9, 0x0c, 0x28, 0x64, 0x5a, 0x61, 0xbd, 0x41, 0x8a, 0x5b, 0xd1, 0x45,
+        0x97, 0x8d,
+    ],
+    [
+        0x1d, 0xf9, 0xf6, 0x8e, 0xf2, 0x45, 0xa8, 0x6b, 0x3e, 0x8c, 0x13, 0xa0, 0xfb, 0xfc, 0xc4,
+        0xb5, 0x9a, 0x1f, 0x26, 0x4d, 0x88, 0xf9, 0x95, 0x8b, 0xc9, 0x76, 0x06, 0x9b, 0x2d, 0xef,
+        0x72, 0xad,
+    ],
+    [
+        0x21, 0x5e, 0x5f, 0x11, 0xc3, 0xf9, 0x14, 0xdb, 0xa3, 0xad, 0xd7, 0x30, 0x3a, 0x38, 0x9a,
+        0xaa, 0x6a, 0x98, 0x94, 0xc9, 0xbf, 0x42, 0x7c, 0x71, 0xdc, 0xdf, 0x08, 0x22, 0x49, 0x80,
+        0x53, 0x11,
+    ],
+    [
+        0x12, 0xdf, 0x9d, 0x7e, 0xb4, 0x3f, 0xe6, 0x6c, 0x3d, 0x91, 0x69, 0x02, 0x1a, 0x80, 0x93,
+        0x9d, 0x04, 0xe9, 0xa3, 0xc3, 0xd5, 0x14, 0xee, 0xf6, 0xa2, 0x69, 0xa1, 0xa6, 0x88, 0x57,
+        0xd8, 0xcd,
+    ],
+    [
+        0x27, 0x33, 0xef, 0x21, 0xe2, 0xd2, 0x90, 0xbd, 0xea, 0xdf, 0x2f, 0x63, 0x13, 0x99, 0xf9,
+        0x0c, 0x04, 0x21, 0x7e, 0x95, 0x00, 0x09, 0xf2, 0xa3, 0xfa, 0xe9, 0xf4, 0x45, 0x93, 0x47,
+        0x92, 0xaf,
+    ],
+    [
+        0x1b, 0x5d, 0xe3, 0xd4, 0xaa, 0x8b, 0x60, 0x17, 0x5a, 0x79, 0x85, 0xcc, 0x5a, 0x92, 0x9c,
+        0xe2, 0x94, 0x15, 0x4e, 0xa3, 0x5f, 0x85, 0x4e, 0xb5, 0xcd, 0xf3, 0xf9, 0xe7, 0xf1, 0x56,
+        0x61, 0xe2,
+    ],
+    [
+        0x22, 0xd0, 0x21, 0x4e, 0xc4, 0x26, 0x23, 0xdf, 0x8d, 0x4d, 0x65, 0xe3, 0xc6, 0x7a, 0x0a,
+        0x08, 0xfe, 0x9c, 0x51, 0x32, 0x5f, 0xe5, 0x5b, 0x33, 0x76, 0xf6, 0x57, 0x5a, 0x51, 0x97,
+        0xaf, 0x19,
+    ],
+    [
+        0x1c, 0x5f, 0x64, 0x9d, 0xea, 0x85, 0xdf, 0x27, 0x6a, 0x31, 0x2d, 0x15, 0x16, 0xd9, 0x53,
+        0xb4, 0x90, 0x9d, 0xad, 0x74, 0x2b, 0x3b, 0x31, 0x2b, 0xa4, 0x60, 0xd0, 0x20, 0x0a, 0x61,
+        0xd1, 0x58,
+    ],
+    [
+        0x2b, 0x80, 0x17, 0x3d, 0xe4, 0x3b, 0x19, 0x7a, 0x0b, 0xda, 0xda, 0x09, 0xd8, 0xd4, 0x9c,
+        0x79, 0xc1, 0x10, 0xb1, 0xdb, 0x98, 0xdd, 0x14, 0xef, 0x96, 0xf9, 0x43, 0x2f, 0xc7, 0x46,
+        0x20, 0xad,
+    ],
+    [
+        0x2d, 0x3c, 0x07, 0xbe, 0xa6, 0x88, 0x34, 0x28, 0xed, 0xd2, 0xd8, 0x0d, 0x07, 0xce, 0xc4,
+        0xb9, 0x11, 0x30, 0x9f, 0xed, 0x96, 0x74, 0x38, 0x22, 0xd6, 0xaa, 0xde, 0xa0, 0x63, 0x13,
+        0xa9, 0x51,
+    ],
+];
+
+pub trait HashBackend {
+    /// Hash of the 64-byte concatenation `left || right`
+    fn hash_pair(&self, left: &Hash, right: &Hash) -> Hash;
+
+    /// Fixed number of levels above the leaves, or `None` for a tree that
+    /// grows with its leaf count (`tree_height`)
+    fn depth(&self) -> Option<usize> {
+        None
+    }
+
+    /// Sibling of an unpaired node at `level` (0 = leaf nodes)
+    fn zero_node(&self, _level: usize) -> Hash {
+        ZERO_HASH
+    }
+
+    /// Hash `nodes`, sitting at `level`, pairwise into the next level up
+    fn hash_level(&self, nodes: &[Hash], level: usize) -> Vec<Hash> {
+        let zero = self.zero_node(level);
+        nodes
+            .chunks(2)
+            .map(|pair| self.hash_pair(&pair[0], pair.get(1).unwrap_or(&zero)))
+            .collect()
+    }
+
+    /// Tree nodes of `commitments`, bound to their insertion `slots`
+    fn leaf_nodes(&self, commitments: &[Hash], slots: &[u64]) -> Vec<Hash> {
+        commitments
+            .iter()
+            .zip(slots)
+            .map(|(commitment, slot)| self.hash_pair(commitment, &slot_word(*slot)))
+            .collect()
+    }
+}
+
+/// Whether `value`, read big endian, is a BN254 scalar field element
+pub fn is_field_element(value: &Hash) -> bool {
+    *value < BN254_FIELD_MODULUS
+}
+
+/// Insertion slot as a 32-byte big-endian word
+pub fn slot_word(slot: u64) -> Hash {
+    let mut word = ZERO_HASH;
+    word[24..].copy_from_slice(&slot.to_be_bytes());
+    word
+}
+
+/// Tree node of one commitment inserted at `slot`
+pub fn leaf_node<B: HashBackend + ?Sized>(backend: &B, commitment: &Hash, slot: u64) -> Hash {
+    backend.hash_pair(commitment, &slot_word(slot))
+}
+
+/// Root of a tree without leaves: zero for a growing tree, the empty
+/// subtree root of its depth for a fixed-depth one
+pub fn empty_root<B: HashBackend + ?Sized>(backend: &B) -> Hash {
+    backend
+        .depth()
+        .map_or(ZERO_HASH, |depth| backend.zero_node(depth))
+}
+
+/// Root of the tree over `commitments` (`empty_root` when there are none)
+pub fn merkle_root<B: HashBackend + ?Sized>(
+    backend: &B,
+    commitments: &[Hash],
+    slots: &[u64],
+) -> Hash {
+    if commitments.is_empty() {
+        return empty_root(backend);
+    }
+
+    let mut nodes = backend.leaf_nodes(commitments, slots);
+    for level in 0..backend_height(backend, commitments.len() as u64) {
+        nodes = backend.hash_level(&nodes, level);
+    }
+    nodes[0]
+}
+
+/// Sibling hashes from the leaf at `leaf_index` up to the root, bottom first
+pub fn auth_path<B: HashBackend + ?Sized>(
+    backend: &B,
+    commitments: &[Hash],
+    slots: &[u64],
+    leaf_index: usize,
+) -> Vec<Hash> {
+    let height = backend_height(backend, commitments.len() as u64);
+    let mut nodes = backend.leaf_nodes(commitments, slots);
+    let mut index = leaf_index;
+    let mut path = Vec::with_capacity(height);
+    for level in 0..height {
+        path.push(
+            nodes
+                .get(index ^ 1)
+                .copied()
+                .unwrap_or_else(|| backend.zero_node(level)),
+        );
+        nodes = backend.hash_level(&nodes, level);
+        index /= 2;
+    }
+    path
+}
+
+/// Levels hashed above the leaf nodes of a growing tree over `leaves`
+/// leaves (at least one: a lone leaf is hashed with a zero sibling)
+pub fn tree_height(leaves: u64) -> usize {
+    (64 - leaves.saturating_sub(1).leading_zeros() as usize).max(1)
+}
+
+/// Levels above the leaf nodes of `backend`'s tree over `leaves` leaves
+pub fn backend_height<B: HashBackend + ?Sized>(backend: &B, leaves: u64) -> usize {
+    backend.depth().unwrap_or_else(|| tree_height(leaves))
+}
+
+/// Append the leaf node `node` at `leaf_index` (the current leaf count) to a
+/// tree kept only as its frontier, and return the new root.
+/// `frontier[level]` holds the latest node at each level whose right
+/// sibling has not been filled yet; it starts zeroed and must have room for
+/// `backend_height(backend, leaf_index + 1) + 1` levels. The roots are
+/// `merkle_root`'s.
+pub fn append_leaf<B: HashBackend + ?Sized>(
+    backend: &B,
+    frontier: &mut [Hash],
+    leaf_index: u64,
+    node: Hash,
+) -> Hash {
+    let height = backend_height(backend, leaf_index + 1);
+    let mut node = node;
+    for (level, left) in frontier.iter_mut().enumerate().take(height) {
+        if (leaf_index >> level) & 1 == 0 {
+            *left = node;
+            node = backend.hash_pair(&node, &backend.zero_node(level));
+        } else {
+            node = backend.hash_pair(left, &node);
+        }
+    }
+    // The complete subtree becomes the left sibling once the tree grows
+    frontier[height] = node;
+    node
+}
+
+/// Auth path of the leaf `append_leaf` just put at `leaf_index`, read from
+/// the frontier it left behind. Earlier leaves' paths need the leaf log.
+pub fn frontier_auth_path<B: HashBackend + ?Sized>(
+    backend: &B,
+    frontier: &[Hash],
+    leaf_index: u64,
+) -> Vec<Hash> {
+    (0..backend_height(backend, leaf_index + 1))
+        .map(|level| {
+            if (leaf_index >> level) & 1 == 1 {
+                frontier[level]
+            } else {
+                backend.zero_node(level)
+            }
+        })
+        .collect()
+}
+
+/// Root reached by hashing `leaf` (a tree node, see `leaf_node`) up `path`.
+/// `is_right[i]` says whether the node at level `i` is the right child, as
+/// the circuits' `path_indices` do; levels past the shorter slice are ignored.
+pub fn root_from_path<B: HashBackend + ?Sized>(
+    backend: &B,
+    leaf: &Hash,
+    path: &[Hash],
+    is_right: &[bool],
+) -> Hash {
+    path.iter()
+        .zip(is_right)
+        .fold(*leaf, |node, (sibling, is_right)| {
+            if *is_right {
+                backend.hash_pair(sibling, &node)
+            } else {
+                backend.hash_pair(&node, sibling)
+            }
+        })
+}
+
+/// Portable Keccak-256, matching the program's `keccak` syscall
+#[cfg(feature = "keccak")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Keccak;
+
+#[cfg(feature = "keccak")]
+impl HashBackend for Keccak {
+    fn hash_pair(&self, left: &Hash, right: &Hash) -> Hash {
+        use sha3::{Digest, Keccak256};
+
+        let mut hasher = Keccak256::new();
+        hasher.update(left);
+        hasher.update(right);
+        hasher.finalize().into()
+    }
+}
+
+/// `Keccak` with batches hashed across the rayon thread pool
+#[cfg(feature = "parallel")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ParallelKeccak;
+
+#[cfg(feature = "parallel")]
+impl HashBackend for ParallelKeccak {
+    fn hash_pair(&self, left: &Hash, right: &Hash) -> Hash {
+        Keccak.hash_pair(left, right)
+    }
+
+    fn hash_level(&self, nodes: &[Hash], _level: usize) -> Vec<Hash> {
+        use rayon::prelude::*;
+
+        nodes
+            .par_chunks(2)
+            .map(|pair| Keccak.hash_pair(&pair[0], pair.get(1).unwrap_or(&ZERO_HASH)))
+            .collect()
+    }
+
+    fn leaf_nodes(&self, commitments: &[Hash], slots: &[u64]) -> Vec<Hash> {
+        use rayon::prelude::*;
+
+        commitments
+            .par_iter()
+            .zip(slots)
+            .map(|(commitment, slot)| Keccak.hash_pair(commitment, &slot_word(*slot)))
+            .collect()
+    }
+}
+
+/// Poseidon over BN254 with the circom parameters (x^5), matching the
+/// `sol_poseidon` syscall and Noir's `poseidon::bn254::hash_1`/`hash_2`.
+/// Inputs must be field elements (`is_field_element`); tree nodes always
+/// are, and the program rejects commitments that are not. Trees have the
+/// circuits' fixed depth and empty leaves.
+#[cfg(feature = "poseidon")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Poseidon;
+
+#[cfg(feature = "poseidon")]
+impl Poseidon {
+    /// One-input hash (width 2), Noir's `hash_1`
+    pub fn hash_one(&self, input: &Hash) -> Hash {
+        use light_poseidon::{Poseidon as Hasher, PoseidonBytesHasher};
+
+        Hasher::<ark_bn254::Fr>::new_circom(1)
+            .and_then(|mut hasher| hasher.hash_bytes_be(&[input]))
+            .expect("poseidon inputs must be field elements")
+    }
+
+    /// Three-input hash (width 4), Noir's `hash_3`
+    pub fn hash_three(&self, inputs: [&Hash; 3]) -> Hash {
+        use light_poseidon::{Poseidon as Hasher, PoseidonBytesHasher};
+
+        Hasher::<ark_bn254::Fr>::new_circom(3)
+            .and_then(|mut hasher| hasher.hash_bytes_be(&inputs.map(|input| input.as_slice())))
+            .expect("poseidon inputs must be field elements")
+    }
+}
+
+#[cfg(feature = "poseidon")]
+impl HashBackend for Poseidon {
+    fn hash_pair(&self, left: &Hash, right: &Hash) -> Hash {
+        use light_poseidon::{Poseidon as Hasher, PoseidonBytesHasher};
+
+        Hasher::<ark_bn254::Fr>::new_circom(2)
+            .and_then(|mut hasher| hasher.hash_bytes_be(&[left, right]))
+            .expect("poseidon inputs must be field elements")
+    }
+
+    fn depth(&self) -> Option<usize> {
+        Some(POSEIDON_TREE_DEPTH)
+    }
+
+    fn zero_node(&self, level: usize) -> Hash {
+        POSEIDON_ZEROS[level]
+    }
+}
@@ -0,0 +1,7 @@
+//! Code shared by the on-chain program and off-chain clients.
+//!
+//! The program depends on this crate without default features and plugs in
+//! the Keccak syscall; the SDK and indexers use the portable backends, so a
+//! tree rebuilt off-chain has exactly the roots the program computed.
+
+pub mod hash;
@@ -0,0 +1,252 @@
+//! Golden vectors every `HashBackend` must reproduce. The program hashes
+//! with the keccak and poseidon syscalls, so these also pin the roots it
+//! computes.
+
+use zyncx_core::hash::*;
+
+/// Expected outputs of one hash function over the fixture
+struct Golden {
+    pair_of_zeros: &'static str,
+    first_leaf: &'static str,
+    /// Roots of the first `TREE_SIZES[i]` fixture leaves
+    roots: [&'static str; 5],
+    /// Bottom of the auth path of leaf 2 in the five-leaf tree; the rest
+    /// of a fixed-depth path is the backend's empty subtrees
+    auth_path: [&'static str; 3],
+}
+
+const TREE_SIZES: [usize; 5] = [1, 2, 3, 5, 8];
+
+const KECCAK: Golden = Golden {
+    pair_of_zeros: "ad3228b676f7d3cd4284a5443f17f1962b36e491b30a40b2405849e597ba5fb5",
+    first_leaf: "a6b4b9b93f7ccd387475af6e8a4dcc10cf3f9203f261bc83ee37a0f1208f87c2",
+    roots: [
+        "a9a295fdfc7d027079197d6829c845a17655c856c2868655c9f01ebb76973eff",
+        "72e0b10b51f97825ce763a29775a4b953d5e5638ac7fc0b911e978b6361ea77b",
+        "47285708a36851301cf949fde1a5c141b4f39bc09acb0a72ea133d8cddcca874",
+        "03bb4d00d3ea4079018e420c71b0fdae273478c4e007c7073740343c64e01ac3",
+        "6f3df57cf1a08338018c610deaf36454e573df7439402aee829dbc2a5bc4a92b",
+    ],
+    auth_path: [
+        "b1e46d2708ade2de31c0bacfd8a71858d47e6fae79ab73fd35680efcf02812b1",
+        "72e0b10b51f97825ce763a29775a4b953d5e5638ac7fc0b911e978b6361ea77b",
+        "8ba692ef4550229acf6536932f66a62064f39ddbe40a13f2650a4efd717cea73",
+    ],
+};
+
+#[cfg(feature = "poseidon")]
+const POSEIDON: Golden = Golden {
+    pair_of_zeros: "2098f5fb9e239eab3ceac3f27b81e481dc3124d55ffed523a839ee8446b64864",
+    first_leaf: "2f87558d6f4d8756e29fef5e2b556deaca705d8eb45aa0c9fe4b0565e4a03f29",
+    roots: [
+        "2c0e415c23878971dcfd7b957582d90794d3e6b89b41dd74741912b5b8de95d9",
+        "24406027d8d9d470f4da97d53d23d2f7b62a5fe6be38bc2f7e66ec2ea0f56f70",
+        "232920e3a1820b4ee7ed9b53dbfa5fbd8a443a8ddcf131ceec258aafb9565daa",
+        "2a72f03851676d60ccff0d06c9c9a6d3fd984391632322969e82d86d09c5fc00",
+        "020cbf83343e5fa66696cbd083a4e583a4074f17f4c73280e4bd57c58ade3c62",
+    ],
+    auth_path: [
+        "2e0172e6e8b842c2d5363e003dec9460559ec5592149db728d3e80aa05211f3d",
+        "0b74bda75a661eef2b3be2212a5229be66e9661d76b5ef781948ae55ea4e6635",
+        "1c1ad7e8af5f970e9762c560493f44832d11336effc45786a7fb11de4fdf7e9c",
+    ],
+};
+
+/// Commitments `[i; 32]` for i in 1..=8, inserted at slots 1000..1008
+fn fixture() -> (Vec<Hash>, Vec<u64>) {
+    let commitments = (1..=8u8).map(|i| [i; 32]).collect();
+    let slots = (0..8u64).map(|i| 1_000 + i).collect();
+    (commitments, slots)
+}
+
+fn hex(hash: &Hash) -> String {
+    hash.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn check_backend(backend: &dyn HashBackend, golden: &Golden) {
+    let (commitments, slots) = fixture();
+
+    assert_eq!(
+        hex(&backend.hash_pair(&ZERO_HASH, &ZERO_HASH)),
+        golden.pair_of_zeros
+    );
+    assert_eq!(
+        hex(&leaf_node(backend, &commitments[0], slots[0])),
+        golden.first_leaf
+    );
+    assert_eq!(merkle_root(backend, &[], &[]), empty_root(backend));
+    for (leaves, root) in TREE_SIZES.into_iter().zip(golden.roots) {
+        assert_eq!(
+            hex(&merkle_root(
+                backend,
+                &commitments[..leaves],
+                &slots[..leaves]
+            )),
+            root,
+            "{leaves} leaves"
+        );
+    }
+
+    let path = auth_path(backend, &commitments[..5], &slots[..5], 2);
+    assert_eq!(path.len(), backend_height(backend, 5));
+    assert_eq!(
+        path[..3].iter().map(hex).collect::<Vec<_>>(),
+        golden.auth_path
+    );
+    for (level, sibling) in path.iter().enumerate().skip(3) {
+        assert_eq!(*sibling, backend.zero_node(level));
+    }
+
+    // Leaf 2 is the left child, then the right child, then the left child
+    // on every level above
+    let mut is_right = vec![false; path.len()];
+    is_right[1] = true;
+    let leaf = leaf_node(backend, &commitments[2], slots[2]);
+    let root = root_from_path(backend, &leaf, &path, &is_right);
+    assert_eq!(hex(&root), golden.roots[3]);
+
+    // Appending to a frontier reaches the same roots, one leaf at a time
+    let mut frontier = [ZERO_HASH; POSEIDON_TREE_DEPTH + 1];
+    for (i, (commitment, slot)) in commitments.iter().zip(&slots).enumerate() {
+        let root = append_leaf(
+            backend,
+            &mut frontier,
+            i as u64,
+            leaf_node(backend, commitment, *slot),
+        );
+        let leaves = i + 1;
+        if let Some(size) = TREE_SIZES.iter().position(|size| *size == leaves) {
+            assert_eq!(hex(&root), golden.roots[size], "{leaves} leaves appended");
+        }
+        assert_eq!(
+            frontier_auth_path(backend, &frontier, i as u64),
+            auth_path(backend, &commitments[..leaves], &slots[..leaves], i)
+        );
+    }
+}
+
+#[test]
+fn keccak_matches_golden_vectors() {
+    check_backend(&Keccak, &KECCAK);
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn parallel_keccak_matches_golden_vectors() {
+    check_backend(&ParallelKeccak, &KECCAK);
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn parallel_batches_match_sequential_ones() {
+    let commitments: Vec<Hash> = (0..1_000u32)
+        .map(|i| {
+            let mut commitment = ZERO_HASH;
+            commitment[..4].copy_from_slice(&i.to_le_bytes());
+            commitment
+        })
+        .collect();
+    let slots: Vec<u64> = (0..1_000).collect();
+
+    let sequential = Keccak.leaf_nodes(&commitments, &slots);
+    assert_eq!(ParallelKeccak.leaf_nodes(&commitments, &slots), sequential);
+    assert_eq!(
+        ParallelKeccak.hash_level(&sequential, 0),
+        Keccak.hash_level(&sequential, 0)
+    );
+}
+
+#[cfg(feature = "poseidon")]
+#[test]
+fn poseidon_matches_golden_vectors() {
+    check_backend(&Poseidon, &POSEIDON);
+}
+
+#[cfg(feature = "poseidon")]
+#[test]
+fn poseidon_zeros_are_the_circuits_empty_subtrees() {
+    assert_eq!(POSEIDON_ZEROS[0], Poseidon.hash_one(&ZERO_HASH));
+    for level in 1..=POSEIDON_TREE_DEPTH {
+        let below = POSEIDON_ZEROS[level - 1];
+        assert_eq!(POSEIDON_ZEROS[level], Poseidon.hash_pair(&below, &below));
+    }
+    assert_eq!(
+        hex(&empty_root(&Poseidon)),
+        hex(&POSEIDON_ZEROS[POSEIDON_TREE_DEPTH])
+    );
+}
+
+/// Root `test_root_matches_the_program` in mixer/src/main.nr asserts for a
+/// single note (secret 1, nullifier secret 2, amount 1_000) inserted at
+/// slot 1_000, hashed exactly as the program hashes deposits
+#[cfg(feature = "poseidon")]
+#[test]
+fn poseidon_root_matches_the_mixer_circuit() {
+    let precommitment = Poseidon.hash_pair(&slot_word(1), &slot_word(2));
+    let commitment = Poseidon.hash_pair(&slot_word(1_000), &precommitment);
+
+    assert_eq!(hex(&commitment), MIXER_COMMITMENT);
+    assert_eq!(
+        hex(&merkle_root(&Poseidon, &[commitment], &[1_000])),
+        MIXER_ROOT
+    );
+}
+
+#[cfg(feature = "poseidon")]
+const MIXER_COMMITMENT: &str = "1cd9b525e2fdc9da533472d8703e316fc9ba7061199900ac3098b46c86c12d75";
+#[cfg(feature = "poseidon")]
+const MIXER_ROOT: &str = "2716e5c3b0105108186cb17e46d7d8fb65162c7c7e12cebde75bda6aaf009357";
+
+/// circom's `Poseidon(2)([1, 2])` and `Poseidon(3)([1, 2, 3])`, which Noir's
+/// `poseidon::bn254::hash_2` and `hash_3` also return
+#[cfg(feature = "poseidon")]
+#[test]
+fn poseidon_matches_circom() {
+    let (one, two, three) = (slot_word(1), slot_word(2), slot_word(3));
+
+    assert_eq!(
+        hex(&Poseidon.hash_pair(&one, &two)),
+        "115cc0f5e7d690413df64c6b9662e9cf2a3617f2743245519e19607a4417189a"
+    );
+    assert_eq!(
+        hex(&Poseidon.hash_three([&one, &two, &three])),
+        "0e7732d89e6939c0ff03d5e58dab6302f3230e269dc5b968f725df34ab36d732"
+    );
+}
+
+#[test]
+fn frontier_roots_match_full_rebuilds() {
+    let commitments: Vec<Hash> = (0..70u8).map(|i| [i; 32]).collect();
+    let slots: Vec<u64> = (0..70).collect();
+
+    let mut frontier = [ZERO_HASH; 8];
+    for i in 0..commitments.len() {
+        let node = leaf_node(&Keccak, &commitments[i], slots[i]);
+        assert_eq!(
+            append_leaf(&Keccak, &mut frontier, i as u64, node),
+            merkle_root(&Keccak, &commitments[..=i], &slots[..=i]),
+            "{} leaves",
+            i + 1
+        );
+    }
+}
+
+#[test]
+fn tree_height_rounds_up() {
+    let heights: Vec<usize> = [1, 2, 3, 4, 5, 8, 9, 1 << 20]
+        .into_iter()
+        .map(tree_height)
+        .collect();
+    assert_eq!(heights, [1, 1, 2, 2, 3, 3, 4, 20]);
+}
+
+#[test]
+fn field_elements_stop_below_the_modulus() {
+    let mut below = BN254_FIELD_MODULUS;
+    below[31] -= 1;
+
+    assert!(is_field_element(&ZERO_HASH));
+    assert!(is_field_element(&below));
+    assert!(!is_field_element(&BN254_FIELD_MODULUS));
+    assert!(!is_field_element(&[0xff; 32]));
+}
@@ -0,0 +1,125 @@
+//! Typed instruction builders and CPI helpers for programs composing with
+//! Zyncx, e.g. a protocol depositing its revenue into the shielded pool.
+//!
+//! Callers fill the account structs in [`accounts`] (PDAs come from [`pda`])
+//! and the argument structs in [`instruction`]; the builders below pair the
+//! two and encode them, so no instruction data is assembled by hand. Inside
+//! an Anchor program, [`cpi`] invokes the same instructions from a
+//! `CpiContext` and returns their receipts.
+
+pub mod pda;
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::InstructionData;
+
+pub use zyncx::instructions::NATIVE_MINT;
+pub use zyncx::state::{DepositReceipt, ProofCircuit, SwapParam, TreeInsertion, WithdrawExtData};
+pub use zyncx::ID;
+
+/// Account pubkeys of each instruction, in the program's account order.
+/// Optional accounts left `None` are passed as the program id.
+pub mod accounts {
+    pub use zyncx::accounts::{
+        DepositNative, DepositToken, SwapNative, SwapToken, VerifyProof, WithdrawNative,
+        WithdrawToken,
+    };
+}
+
+/// Arguments of each instruction, Borsh-encoded after its discriminator
+pub mod instruction {
+    pub use zyncx::instruction::{
+        DepositNative, DepositToken, SwapNative, SwapToken, VerifyProof, WithdrawNative,
+        WithdrawToken,
+    };
+}
+
+/// Cross-program invocation from another Anchor program
+pub mod cpi {
+    pub use zyncx::cpi::{
+        deposit_native, deposit_token, swap_native, swap_token, verify_proof, withdraw_native,
+        withdraw_token,
+    };
+
+    /// `AccountInfo` structs for the `CpiContext` of each instruction
+    pub mod accounts {
+        pub use zyncx::cpi::accounts::{
+            DepositNative, DepositToken, SwapNative, SwapToken, VerifyProof, WithdrawNative,
+            WithdrawToken,
+        };
+    }
+}
+
+/// Deposit SOL into the native vault
+pub fn deposit_native(
+    accounts: accounts::DepositNative,
+    args: instruction::DepositNative,
+) -> Instruction {
+    build(accounts, args, Vec::new())
+}
+
+/// Deposit SPL tokens into the vault for their mint
+pub fn deposit_token(
+    accounts: accounts::DepositToken,
+    args: instruction::DepositToken,
+) -> Instruction {
+    build(accounts, args, Vec::new())
+}
+
+/// Withdraw SOL with a spend proof
+pub fn withdraw_native(
+    accounts: accounts::WithdrawNative,
+    args: instruction::WithdrawNative,
+) -> Instruction {
+    build(accounts, args, Vec::new())
+}
+
+/// Withdraw SPL tokens with a spend proof
+pub fn withdraw_token(
+    accounts: accounts::WithdrawToken,
+    args: instruction::WithdrawToken,
+) -> Instruction {
+    build(accounts, args, Vec::new())
+}
+
+/// Swap out of the native vault. `route` holds the DEX route accounts,
+/// appended after the instruction's own accounts.
+pub fn swap_native(
+    accounts: accounts::SwapNative,
+    args: instruction::SwapNative,
+    route: Vec<AccountMeta>,
+) -> Instruction {
+    build(accounts, args, route)
+}
+
+/// Swap out of a token vault. `route` holds the DEX route accounts,
+/// appended after the instruction's own accounts.
+pub fn swap_token(
+    accounts: accounts::SwapToken,
+    args: instruction::SwapToken,
+    route: Vec<AccountMeta>,
+) -> Instruction {
+    build(accounts, args, route)
+}
+
+/// Check a spend proof without spending the note
+pub fn verify_proof(
+    accounts: accounts::VerifyProof,
+    args: instruction::VerifyProof,
+) -> Instruction {
+    build(accounts, args, Vec::new())
+}
+
+fn build(
+    accounts: impl ToAccountMetas,
+    args: impl InstructionData,
+    remaining: Vec<AccountMeta>,
+) -> Instruction {
+    let mut metas = accounts.to_account_metas(None);
+    metas.extend(remaining);
+    Instruction {
+        program_id: ID,
+        accounts: metas,
+        data: args.data(),
+    }
+}
@@ -0,0 +1,79 @@
+//! Addresses of the program-derived accounts the deposit, withdraw, swap and
+//! verify instructions take. Seeds mirror the program's account constraints.
+
+use anchor_lang::prelude::Pubkey;
+
+use crate::{ProofCircuit, ID};
+
+fn find(seeds: &[&[u8]]) -> Pubkey {
+    Pubkey::find_program_address(seeds, &ID).0
+}
+
+/// Vault for `asset_mint` (`NATIVE_MINT` for SOL)
+pub fn vault(asset_mint: &Pubkey) -> Pubkey {
+    find(&[b"vault", asset_mint.as_ref()])
+}
+
+/// SOL treasury of a native vault
+pub fn vault_treasury(vault: &Pubkey) -> Pubkey {
+    find(&[b"vault_treasury", vault.as_ref()])
+}
+
+/// Token account of a token vault
+pub fn vault_token_account(vault: &Pubkey) -> Pubkey {
+    find(&[b"vault_token_account", vault.as_ref()])
+}
+
+pub fn vault_stats(vault: &Pubkey) -> Pubkey {
+    find(&[b"vault_stats", vault.as_ref()])
+}
+
+pub fn vault_fees(vault: &Pubkey) -> Pubkey {
+    find(&[b"vault_fees", vault.as_ref()])
+}
+
+pub fn global_stats() -> Pubkey {
+    find(&[b"global_stats"])
+}
+
+pub fn protocol_config() -> Pubkey {
+    find(&[b"protocol_config"])
+}
+
+pub fn blocklist() -> Pubkey {
+    find(&[b"blocklist"])
+}
+
+pub fn fee_exemptions() -> Pubkey {
+    find(&[b"fee_exemptions"])
+}
+
+/// Marker created when `nullifier` is spent from `vault`
+pub fn nullifier(vault: &Pubkey, nullifier: &[u8; 32]) -> Pubkey {
+    find(&[b"nullifier", vault.as_ref(), nullifier.as_ref()])
+}
+
+/// Per-vault Groth16 key of `circuit`
+pub fn verification_key(vault: &Pubkey, circuit: ProofCircuit) -> Pubkey {
+    find(&[b"verification_key", vault.as_ref(), &[circuit as u8]])
+}
+
+/// Viewing key registered by `owner`
+pub fn viewing_key(owner: &Pubkey) -> Pubkey {
+    find(&[b"viewing_key", owner.as_ref()])
+}
+
+/// On-chain copy of a deposit's encrypted note
+pub fn note_backup(depositor: &Pubkey, precommitment: &[u8; 32]) -> Pubkey {
+    find(&[b"note_backup", depositor.as_ref(), precommitment.as_ref()])
+}
+
+/// Registration of the relayer paying for a withdrawal
+pub fn relayer(payer: &Pubkey) -> Pubkey {
+    find(&[b"relayer", payer.as_ref()])
+}
+
+/// Swap fees accrued to `referrer` in `vault`
+pub fn swap_referral(referrer: &Pubkey, vault: &Pubkey) -> Pubkey {
+    find(&[b"swap_referral", referrer.as_ref(), vault.as_ref()])
+}
@@ -0,0 +1,81 @@
+//! The builders must encode exactly what the program's dispatcher decodes:
+//! the instruction discriminator, the Borsh arguments and the account order.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::AccountMeta;
+use anchor_lang::{system_program, Discriminator};
+use zyncx_cpi::{accounts, instruction, pda, NATIVE_MINT};
+
+fn deposit_accounts(depositor: Pubkey) -> accounts::DepositNative {
+    let vault = pda::vault(&NATIVE_MINT);
+    accounts::DepositNative {
+        depositor,
+        vault,
+        merkle_tree: Pubkey::new_unique(),
+        vault_treasury: pda::vault_treasury(&vault),
+        vault_stats: pda::vault_stats(&vault),
+        global_stats: pda::global_stats(),
+        viewing_key: None,
+        note_backup: None,
+        system_program: system_program::ID,
+    }
+}
+
+#[test]
+fn deposit_native_encodes_discriminator_and_args() {
+    let depositor = Pubkey::new_unique();
+    let ix = zyncx_cpi::deposit_native(
+        deposit_accounts(depositor),
+        instruction::DepositNative {
+            amount: 1_000_000,
+            precommitment: [7u8; 32],
+            encrypted_note: vec![1, 2, 3],
+            viewing_ciphertext: Vec::new(),
+            include_path: false,
+        },
+    );
+
+    assert_eq!(ix.program_id, zyncx_cpi::ID);
+    let (discriminator, args) = ix.data.split_at(8);
+    assert_eq!(discriminator, instruction::DepositNative::DISCRIMINATOR);
+    assert_eq!(&args[..8], &1_000_000u64.to_le_bytes());
+    assert_eq!(&args[8..40], &[7u8; 32]);
+    assert_eq!(&args[40..44], &3u32.to_le_bytes());
+
+    assert_eq!(ix.accounts[0], AccountMeta::new(depositor, true));
+    assert_eq!(ix.accounts[1].pubkey, pda::vault(&NATIVE_MINT));
+}
+
+#[test]
+fn absent_optional_accounts_are_the_program_id() {
+    let ix = zyncx_cpi::deposit_native(
+        deposit_accounts(Pubkey::new_unique()),
+        instruction::DepositNative {
+            amount: 1,
+            precommitment: [0u8; 32],
+            encrypted_note: Vec::new(),
+            viewing_ciphertext: Vec::new(),
+            include_path: false,
+        },
+    );
+
+    // viewing_key and note_backup, ahead of system_program
+    let placeholder = AccountMeta::new_readonly(zyncx_cpi::ID, false);
+    let n = ix.accounts.len();
+    assert_eq!(n, 9);
+    assert_eq!(ix.accounts[n - 3], placeholder);
+    assert_eq!(ix.accounts[n - 2], placeholder);
+    assert_eq!(ix.accounts[n - 1].pubkey, system_program::ID);
+}
+
+#[test]
+fn pdas_match_the_program_seeds() {
+    let vault = pda::vault(&NATIVE_MINT);
+    let (expected, _) = Pubkey::find_program_address(&[b"vault", &[0u8; 32]], &zyncx_cpi::ID);
+    assert_eq!(vault, expected);
+
+    let nullifier = [9u8; 32];
+    let (expected, _) =
+        Pubkey::find_program_address(&[b"nullifier", vault.as_ref(), &nullifier], &zyncx_cpi::ID);
+    assert_eq!(pda::nullifier(&vault, &nullifier), expected);
+}
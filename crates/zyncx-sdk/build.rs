@@ -0,0 +1,539 @@
+//! Generates the instruction table in `src/instructions.rs` from the program
+//! sources: discriminators, account orders and Borsh argument layouts, plus
+//! the compute ceilings declared in `src/budgets.rs`.
+//!
+//! The table is written to `$OUT_DIR/instruction_table.rs` together with its
+//! JSON rendering. Set `ZYNCX_WRITE_INSTRUCTION_TABLE=1` to also refresh the
+//! committed `zyncx-instructions.json` that non-Rust clients consume.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::{env, fs};
+
+use proc_macro2::{TokenStream, TokenTree};
+use sha2::{Digest, Sha256};
+use syn::{Attribute, Expr, Fields, FnArg, GenericArgument, Item, Lit, Pat, PathArguments, Type};
+
+const PROGRAM_SRC: &str = "../../contracts/solana/zyncx/src";
+const ARTIFACT: &str = "zyncx-instructions.json";
+
+#[allow(dead_code)]
+#[path = "src/budgets.rs"]
+mod budgets;
+
+struct Account {
+    name: String,
+    writable: bool,
+    signer: bool,
+    optional: bool,
+}
+
+struct Field {
+    name: String,
+    ty: String,
+}
+
+struct Instruction {
+    name: String,
+    discriminator: [u8; 8],
+    accounts: Vec<Account>,
+    args: Vec<Field>,
+    compute_budget: Option<u32>,
+}
+
+enum TypeDef {
+    Struct(Vec<Field>),
+    Enum(Vec<(String, Vec<Field>)>),
+}
+
+#[derive(Default)]
+struct Sources {
+    program: Option<syn::ItemMod>,
+    accounts: HashMap<String, syn::ItemStruct>,
+    types: HashMap<String, Item>,
+    consts: HashMap<String, Expr>,
+}
+
+fn main() {
+    let manifest_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let src = manifest_dir.join(PROGRAM_SRC);
+    println!("cargo:rerun-if-env-changed=ZYNCX_WRITE_INSTRUCTION_TABLE");
+    println!("cargo:rerun-if-changed=src/budgets.rs");
+
+    let mut sources = Sources::default();
+    sources.load(&src.join("lib.rs"), &src);
+
+    let program = sources
+        .program
+        .as_ref()
+        .expect("#[program] module not found in the program sources");
+    let mut referenced = BTreeSet::new();
+    let instructions: Vec<Instruction> = program
+        .content
+        .as_ref()
+        .map(|(_, items)| items.as_slice())
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|item| match item {
+            Item::Fn(f) if matches!(f.vis, syn::Visibility::Public(_)) => Some(f),
+            _ => None,
+        })
+        .map(|f| sources.instruction(f, &mut referenced))
+        .collect();
+
+    for budget in budgets::COMPUTE_BUDGETS {
+        assert!(
+            instructions.iter().any(|ix| ix.name == budget.instruction),
+            "budgets.rs names unknown instruction {}",
+            budget.instruction
+        );
+    }
+
+    let types = sources.type_defs(referenced);
+
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    let json = render_json(&instructions, &types);
+    fs::write(
+        out_dir.join("instruction_table.rs"),
+        render_rust(&instructions, &types),
+    )
+    .unwrap();
+    fs::write(out_dir.join(ARTIFACT), &json).unwrap();
+    if env::var_os("ZYNCX_WRITE_INSTRUCTION_TABLE").is_some() {
+        fs::write(manifest_dir.join(ARTIFACT), &json).unwrap();
+    }
+}
+
+fn has_attr(attrs: &[Attribute], name: &str) -> bool {
+    attrs.iter().any(|attr| attr.path().is_ident(name))
+}
+
+fn derives(attrs: &[Attribute], name: &str) -> bool {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("derive"))
+        .any(|attr| {
+            let mut found = false;
+            let _ = attr.parse_nested_meta(|meta| {
+                found |= meta.path.is_ident(name);
+                Ok(())
+            });
+            found
+        })
+}
+
+/// Top-level, comma-separated flags inside `#[account(...)]` (`mut`, `init`, ...)
+fn account_flags(attrs: &[Attribute]) -> BTreeSet<String> {
+    let mut flags = BTreeSet::new();
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("account")) {
+        let Ok(list) = attr.meta.require_list() else {
+            continue;
+        };
+        let mut segment: Vec<TokenTree> = Vec::new();
+        let tokens: TokenStream = list.tokens.clone();
+        for token in
+            tokens
+                .into_iter()
+                .chain(std::iter::once(TokenTree::Punct(proc_macro2::Punct::new(
+                    ',',
+                    proc_macro2::Spacing::Alone,
+                ))))
+        {
+            match &token {
+                TokenTree::Punct(p) if p.as_char() == ',' => {
+                    if let [TokenTree::Ident(ident)] = segment.as_slice() {
+                        flags.insert(ident.to_string());
+                    }
+                    segment.clear();
+                }
+                _ => segment.push(token),
+            }
+        }
+    }
+    flags
+}
+
+fn last_type_arg(args: &PathArguments) -> Option<&Type> {
+    match args {
+        PathArguments::AngleBracketed(args) => args.args.iter().rev().find_map(|arg| match arg {
+            GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        }),
+        _ => None,
+    }
+}
+
+fn type_ident(ty: &Type) -> Option<&syn::PathSegment> {
+    match ty {
+        Type::Path(path) => path.path.segments.last(),
+        _ => None,
+    }
+}
+
+impl Sources {
+    /// Parse `file` and every module it declares, following the module tree
+    /// the compiler sees. `dir` is where its child module files live.
+    fn load(&mut self, file: &Path, dir: &Path) {
+        println!("cargo:rerun-if-changed={}", file.display());
+        let text = fs::read_to_string(file)
+            .unwrap_or_else(|e| panic!("cannot read {}: {e}", file.display()));
+        let parsed = syn::parse_file(&text)
+            .unwrap_or_else(|e| panic!("failed to parse {}: {e}", file.display()));
+        self.collect(parsed.items, dir);
+    }
+
+    fn collect(&mut self, items: Vec<Item>, dir: &Path) {
+        for item in items {
+            match item {
+                Item::Mod(module)
+                    if has_attr(&module.attrs, "arcium_program")
+                        || has_attr(&module.attrs, "program") =>
+                {
+                    if let Some((_, items)) = &module.content {
+                        self.collect_nested(items);
+                    }
+                    self.program = Some(module);
+                }
+                Item::Mod(module) => {
+                    let name = module.ident.to_string();
+                    match module.content {
+                        Some((_, items)) => self.collect(items, &dir.join(&name)),
+                        None => {
+                            let flat = dir.join(format!("{name}.rs"));
+                            if flat.exists() {
+                                self.load(&flat, &dir.join(&name));
+                            } else {
+                                self.load(&dir.join(&name).join("mod.rs"), &dir.join(&name));
+                            }
+                        }
+                    }
+                }
+                other => self.collect_nested(std::slice::from_ref(&other)),
+            }
+        }
+    }
+
+    fn collect_nested(&mut self, items: &[Item]) {
+        for item in items {
+            match item {
+                Item::Struct(s) if derives(&s.attrs, "Accounts") => {
+                    let name = s.ident.to_string();
+                    if self.accounts.insert(name.clone(), s.clone()).is_some() {
+                        panic!("duplicate Accounts struct {name}");
+                    }
+                }
+                Item::Struct(s) if derives(&s.attrs, "AnchorSerialize") => {
+                    self.types.insert(s.ident.to_string(), item.clone());
+                }
+                Item::Enum(e) if derives(&e.attrs, "AnchorSerialize") => {
+                    self.types.insert(e.ident.to_string(), item.clone());
+                }
+                Item::Const(c) => {
+                    self.consts.insert(c.ident.to_string(), (*c.expr).clone());
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn instruction(&self, f: &syn::ItemFn, referenced: &mut BTreeSet<String>) -> Instruction {
+        let name = f.sig.ident.to_string();
+        let mut inputs = f.sig.inputs.iter().map(|input| match input {
+            FnArg::Typed(arg) => arg,
+            FnArg::Receiver(_) => panic!("{name}: unexpected receiver"),
+        });
+
+        let ctx = inputs
+            .next()
+            .unwrap_or_else(|| panic!("{name}: missing Context"));
+        let accounts_ty = type_ident(&ctx.ty)
+            .and_then(|ctx| last_type_arg(&ctx.arguments))
+            .and_then(type_ident)
+            .unwrap_or_else(|| panic!("{name}: cannot read the Context type"))
+            .ident
+            .to_string();
+        let mut accounts = Vec::new();
+        self.flatten_accounts(&accounts_ty, &mut accounts);
+
+        let args = inputs
+            .map(|arg| {
+                let Pat::Ident(pat) = &*arg.pat else {
+                    panic!("{name}: unsupported argument pattern");
+                };
+                Field {
+                    name: pat.ident.to_string(),
+                    ty: self.render_type(&arg.ty, referenced),
+                }
+            })
+            .collect();
+
+        let hash = Sha256::digest(format!("global:{name}").as_bytes());
+        Instruction {
+            discriminator: hash[..8].try_into().unwrap(),
+            compute_budget: budgets::COMPUTE_BUDGETS
+                .iter()
+                .find(|budget| budget.instruction == name)
+                .map(|budget| budget.max_units),
+            name,
+            accounts,
+            args,
+        }
+    }
+
+    fn flatten_accounts(&self, name: &str, out: &mut Vec<Account>) {
+        let item = self
+            .accounts
+            .get(name)
+            .unwrap_or_else(|| panic!("Accounts struct {name} not found"));
+        for field in &item.fields {
+            let mut ty = &field.ty;
+            let optional = type_ident(ty).is_some_and(|seg| seg.ident == "Option");
+            if optional {
+                ty = last_type_arg(&type_ident(ty).unwrap().arguments).unwrap();
+            }
+            let ident = type_ident(ty).map(|seg| seg.ident.to_string());
+
+            // Composite accounts are inlined in declaration order
+            if let Some(nested) = ident.as_ref().filter(|i| self.accounts.contains_key(*i)) {
+                self.flatten_accounts(nested, out);
+                continue;
+            }
+
+            let flags = account_flags(&field.attrs);
+            out.push(Account {
+                name: field.ident.as_ref().unwrap().to_string(),
+                writable: ["mut", "init", "init_if_needed", "zero"]
+                    .iter()
+                    .any(|flag| flags.contains(*flag)),
+                signer: ident.as_deref() == Some("Signer") || flags.contains("signer"),
+                optional,
+            });
+        }
+    }
+
+    fn render_type(&self, ty: &Type, referenced: &mut BTreeSet<String>) -> String {
+        match ty {
+            Type::Path(path) => {
+                let seg = path.path.segments.last().unwrap();
+                let ident = seg.ident.to_string();
+                if self.types.contains_key(&ident) {
+                    referenced.insert(ident.clone());
+                }
+                match &seg.arguments {
+                    PathArguments::AngleBracketed(args) => {
+                        let inner: Vec<String> = args
+                            .args
+                            .iter()
+                            .filter_map(|arg| match arg {
+                                GenericArgument::Type(ty) => Some(self.render_type(ty, referenced)),
+                                _ => None,
+                            })
+                            .collect();
+                        format!("{ident}<{}>", inner.join(", "))
+                    }
+                    _ => ident,
+                }
+            }
+            Type::Array(array) => format!(
+                "[{}; {}]",
+                self.render_type(&array.elem, referenced),
+                self.eval(&array.len)
+            ),
+            Type::Tuple(tuple) if tuple.elems.is_empty() => "()".to_string(),
+            _ => panic!("unsupported argument type"),
+        }
+    }
+
+    fn eval(&self, expr: &Expr) -> u64 {
+        match expr {
+            Expr::Lit(lit) => match &lit.lit {
+                Lit::Int(int) => int.base10_parse().unwrap(),
+                _ => panic!("unsupported array length"),
+            },
+            Expr::Path(path) => {
+                let name = path.path.segments.last().unwrap().ident.to_string();
+                let value = self
+                    .consts
+                    .get(&name)
+                    .unwrap_or_else(|| panic!("unknown constant {name}"));
+                self.eval(value)
+            }
+            Expr::Paren(paren) => self.eval(&paren.expr),
+            Expr::Binary(bin) => {
+                let (l, r) = (self.eval(&bin.left), self.eval(&bin.right));
+                match bin.op {
+                    syn::BinOp::Add(_) => l + r,
+                    syn::BinOp::Sub(_) => l - r,
+                    syn::BinOp::Mul(_) => l * r,
+                    syn::BinOp::Div(_) => l / r,
+                    _ => panic!("unsupported array length"),
+                }
+            }
+            _ => panic!("unsupported array length"),
+        }
+    }
+
+    fn fields(&self, fields: &Fields, referenced: &mut BTreeSet<String>) -> Vec<Field> {
+        fields
+            .iter()
+            .enumerate()
+            .map(|(i, field)| Field {
+                name: field
+                    .ident
+                    .as_ref()
+                    .map_or_else(|| i.to_string(), |ident| ident.to_string()),
+                ty: self.render_type(&field.ty, referenced),
+            })
+            .collect()
+    }
+
+    /// Layouts of every type reachable from the instruction arguments
+    fn type_defs(&self, mut pending: BTreeSet<String>) -> BTreeMap<String, TypeDef> {
+        let mut defs = BTreeMap::new();
+        while let Some(name) = pending.pop_first() {
+            if defs.contains_key(&name) {
+                continue;
+            }
+            let mut referenced = BTreeSet::new();
+            let def = match &self.types[&name] {
+                Item::Struct(s) => TypeDef::Struct(self.fields(&s.fields, &mut referenced)),
+                Item::Enum(e) => TypeDef::Enum(
+                    e.variants
+                        .iter()
+                        .map(|v| (v.ident.to_string(), self.fields(&v.fields, &mut referenced)))
+                        .collect(),
+                ),
+                _ => unreachable!(),
+            };
+            defs.insert(name, def);
+            pending.extend(referenced.into_iter().filter(|n| !defs.contains_key(n)));
+        }
+        defs
+    }
+}
+
+fn render_fields_rust(fields: &[Field]) -> String {
+    let fields: Vec<String> = fields
+        .iter()
+        .map(|f| format!("FieldLayout {{ name: {:?}, ty: {:?} }}", f.name, f.ty))
+        .collect();
+    format!("&[{}]", fields.join(", "))
+}
+
+fn render_rust(instructions: &[Instruction], types: &BTreeMap<String, TypeDef>) -> String {
+    let mut out = String::from("pub static INSTRUCTIONS: &[InstructionLayout] = &[\n");
+    for ix in instructions {
+        let accounts: Vec<String> = ix
+            .accounts
+            .iter()
+            .map(|a| {
+                format!(
+                    "AccountLayout {{ name: {:?}, writable: {}, signer: {}, optional: {} }}",
+                    a.name, a.writable, a.signer, a.optional
+                )
+            })
+            .collect();
+        writeln!(
+            out,
+            "    InstructionLayout {{ name: {:?}, discriminator: {:?}, accounts: &[{}], args: {}, compute_budget: {:?} }},",
+            ix.name,
+            ix.discriminator,
+            accounts.join(", "),
+            render_fields_rust(&ix.args),
+            ix.compute_budget
+        )
+        .unwrap();
+    }
+    out.push_str("];\n\npub static TYPES: &[TypeLayout] = &[\n");
+    for (name, def) in types {
+        let kind = match def {
+            TypeDef::Struct(fields) => format!("TypeKind::Struct({})", render_fields_rust(fields)),
+            TypeDef::Enum(variants) => {
+                let variants: Vec<String> = variants
+                    .iter()
+                    .map(|(v, fields)| {
+                        format!(
+                            "VariantLayout {{ name: {v:?}, fields: {} }}",
+                            render_fields_rust(fields)
+                        )
+                    })
+                    .collect();
+                format!("TypeKind::Enum(&[{}])", variants.join(", "))
+            }
+        };
+        writeln!(out, "    TypeLayout {{ name: {name:?}, kind: {kind} }},").unwrap();
+    }
+    out.push_str("];\n");
+    out
+}
+
+fn render_fields_json(fields: &[Field]) -> String {
+    let fields: Vec<String> = fields
+        .iter()
+        .map(|f| format!("{{ \"name\": {:?}, \"type\": {:?} }}", f.name, f.ty))
+        .collect();
+    format!("[{}]", fields.join(", "))
+}
+
+fn render_json(instructions: &[Instruction], types: &BTreeMap<String, TypeDef>) -> String {
+    let mut out = String::from("{\n  \"program\": \"zyncx\",\n  \"instructions\": [\n");
+    for (i, ix) in instructions.iter().enumerate() {
+        let accounts: Vec<String> = ix
+            .accounts
+            .iter()
+            .map(|a| {
+                format!(
+                    "        {{ \"name\": {:?}, \"writable\": {}, \"signer\": {}, \"optional\": {} }}",
+                    a.name, a.writable, a.signer, a.optional
+                )
+            })
+            .collect();
+        write!(
+            out,
+            "    {{\n      \"name\": {:?},\n      \"discriminator\": {:?},\n      \"accounts\": [\n{}\n      ],\n      \"args\": {},\n      \"compute_budget\": {}\n    }}",
+            ix.name,
+            ix.discriminator,
+            accounts.join(",\n"),
+            render_fields_json(&ix.args),
+            ix.compute_budget
+                .map_or_else(|| "null".to_string(), |units| units.to_string())
+        )
+        .unwrap();
+        out.push_str(if i + 1 < instructions.len() {
+            ",\n"
+        } else {
+            "\n"
+        });
+    }
+    out.push_str("  ],\n  \"types\": [\n");
+    for (i, (name, def)) in types.iter().enumerate() {
+        match def {
+            TypeDef::Struct(fields) => write!(
+                out,
+                "    {{ \"name\": {name:?}, \"kind\": \"struct\", \"fields\": {} }}",
+                render_fields_json(fields)
+            ),
+            TypeDef::Enum(variants) => {
+                let variants: Vec<String> = variants
+                    .iter()
+                    .map(|(v, fields)| {
+                        format!(
+                            "{{ \"name\": {v:?}, \"fields\": {} }}",
+                            render_fields_json(fields)
+                        )
+                    })
+                    .collect();
+                write!(
+                    out,
+                    "    {{ \"name\": {name:?}, \"kind\": \"enum\", \"variants\": [{}] }}",
+                    variants.join(", ")
+                )
+            }
+        }
+        .unwrap();
+        out.push_str(if i + 1 < types.len() { ",\n" } else { "\n" });
+    }
+    out.push_str("  ]\n}\n");
+    out
+}
@@ -0,0 +1,342 @@
+// ============================================================================
+// SHARED EXAMPLE SETUP
+// ============================================================================
+// Everything the devnet examples have in common: the RPC connection and
+// payer, PDA derivation, instruction assembly from the SDK's instruction
+// table, transaction sending and event parsing.
+//
+// Configuration comes from the environment:
+//
+//   ZYNCX_RPC_URL          RPC endpoint (default: devnet)
+//   ZYNCX_KEYPAIR          payer keypair file (default: ~/.config/solana/id.json)
+//   ZYNCX_CLUSTER_OFFSET   Arcium cluster the MXE is deployed to (MXE examples)
+//   ZYNCX_MXE_PUBKEY       MXE cluster x25519 key, base58 (MXE examples)
+// ============================================================================
+
+#![allow(dead_code)]
+
+use std::error::Error;
+use std::str::FromStr;
+
+use base64::Engine as _;
+use sha2::{Digest, Sha256};
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcTransactionConfig;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{read_keypair_file, Keypair, Signature, Signer};
+use solana_sdk::transaction::Transaction;
+use solana_transaction_status::{UiTransactionEncoding, UiTransactionReturnData};
+use zyncx_sdk::budgets::MAX_TRANSACTION_COMPUTE_UNITS;
+use zyncx_sdk::instructions::instruction_for_data;
+
+pub type ExampleResult<T = ()> = Result<T, Box<dyn Error>>;
+
+pub const PROGRAM_ID: Pubkey = pubkey!("5TGQEPDL2K6RoxKLbfjD2KMypbvKewDUsfuaNAvCAUMU");
+pub const ARCIUM_PROGRAM_ID: Pubkey = pubkey!("Arcj82pX7HxYKLR92qvgZUAd7vGS1k4hQvAFcPATFdEQ");
+pub const TOKEN_PROGRAM_ID: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+pub const NOIR_VERIFIER_PROGRAM_ID: Pubkey =
+    pubkey!("AWUEQfGnU2nVYAA3dfKpckDhqjoW6HELT5wvkg9Sve1y");
+
+/// `NATIVE_MINT` of the program: SOL vaults are keyed by the zero pubkey
+pub const NATIVE_MINT: Pubkey = Pubkey::new_from_array([0u8; 32]);
+
+const DEVNET_RPC_URL: &str = "https://api.devnet.solana.com";
+
+pub struct Client {
+    pub rpc: RpcClient,
+    pub payer: Keypair,
+}
+
+impl Client {
+    /// Connect with the RPC endpoint and payer from the environment
+    pub fn from_env() -> ExampleResult<Self> {
+        let url = std::env::var("ZYNCX_RPC_URL").unwrap_or_else(|_| DEVNET_RPC_URL.to_string());
+        let keypair_path = match std::env::var("ZYNCX_KEYPAIR") {
+            Ok(path) => path,
+            Err(_) => format!("{}/.config/solana/id.json", std::env::var("HOME")?),
+        };
+        let payer = read_keypair_file(&keypair_path)
+            .map_err(|e| format!("reading keypair {keypair_path}: {e}"))?;
+
+        println!("RPC:   {url}");
+        println!("Payer: {}", payer.pubkey());
+
+        Ok(Self {
+            rpc: RpcClient::new_with_commitment(url, CommitmentConfig::confirmed()),
+            payer,
+        })
+    }
+
+    /// Send `instructions` in one transaction and wait for confirmation. The
+    /// compute limit is the sum of the instructions' ceilings from the SDK's
+    /// budget table, when any declare one.
+    pub fn send(
+        &self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> ExampleResult<Signature> {
+        let compute_units: u32 = instructions
+            .iter()
+            .filter_map(|ix| instruction_for_data(&ix.data)?.compute_budget)
+            .sum();
+
+        let mut all = Vec::with_capacity(instructions.len() + 1);
+        if compute_units > 0 {
+            all.push(ComputeBudgetInstruction::set_compute_unit_limit(
+                compute_units.min(MAX_TRANSACTION_COMPUTE_UNITS),
+            ));
+        }
+        all.extend_from_slice(instructions);
+
+        let mut keypairs: Vec<&Keypair> = vec![&self.payer];
+        keypairs.extend_from_slice(signers);
+
+        let blockhash = self.rpc.get_latest_blockhash()?;
+        let transaction = Transaction::new_signed_with_payer(
+            &all,
+            Some(&self.payer.pubkey()),
+            &keypairs,
+            blockhash,
+        );
+        let signature = self.rpc.send_and_confirm_transaction(&transaction)?;
+        println!("Confirmed {signature}");
+        Ok(signature)
+    }
+
+    /// Events and return data of a confirmed transaction
+    pub fn output(&self, signature: &Signature) -> ExampleResult<TransactionOutput> {
+        let transaction = self.rpc.get_transaction_with_config(
+            signature,
+            RpcTransactionConfig {
+                encoding: Some(UiTransactionEncoding::Json),
+                commitment: Some(CommitmentConfig::confirmed()),
+                max_supported_transaction_version: Some(0),
+            },
+        )?;
+        let meta = transaction
+            .transaction
+            .meta
+            .ok_or("transaction has no status meta")?;
+        let logs: Option<Vec<String>> = meta.log_messages.into();
+        let return_data: Option<UiTransactionReturnData> = meta.return_data.into();
+        let base64 = base64::engine::general_purpose::STANDARD;
+
+        Ok(TransactionOutput {
+            events: logs
+                .unwrap_or_default()
+                .iter()
+                .filter_map(|line| line.strip_prefix("Program data: "))
+                .filter_map(|data| base64.decode(data).ok())
+                .collect(),
+            return_data: match return_data {
+                Some(return_data) => base64.decode(return_data.data.0)?,
+                None => Vec::new(),
+            },
+        })
+    }
+
+    /// Raw data of a program account
+    pub fn account_data(&self, address: &Pubkey) -> ExampleResult<Vec<u8>> {
+        Ok(self.rpc.get_account_data(address)?)
+    }
+}
+
+pub struct TransactionOutput {
+    /// Anchor events as (discriminator + Borsh body) payloads
+    pub events: Vec<Vec<u8>>,
+    /// Borsh-encoded return value of the instruction
+    pub return_data: Vec<u8>,
+}
+
+impl TransactionOutput {
+    /// Body of the first `name` event
+    pub fn event(&self, name: &str) -> Option<Reader<'_>> {
+        let hash = Sha256::digest(format!("event:{name}").as_bytes());
+        self.events
+            .iter()
+            .find(|event| event.len() >= 8 && event[..8] == hash[..8])
+            .map(|event| Reader(&event[8..]))
+    }
+}
+
+/// PDA of the Zyncx program
+pub fn pda(seeds: &[&[u8]]) -> Pubkey {
+    Pubkey::find_program_address(seeds, &PROGRAM_ID).0
+}
+
+/// Assemble a Zyncx instruction from the SDK's instruction table. Accounts
+/// are given by name in any order; `system_program` and `token_program` are
+/// filled in, and optional accounts that are left out are passed as the
+/// program id.
+pub fn instruction(
+    name: &str,
+    accounts: &[(&str, Pubkey)],
+    args: &[u8],
+) -> ExampleResult<Instruction> {
+    let layout = zyncx_sdk::instructions::instruction(name)
+        .ok_or_else(|| format!("unknown instruction {name}"))?;
+
+    let metas = layout
+        .accounts
+        .iter()
+        .map(|account| {
+            let pubkey = accounts
+                .iter()
+                .find(|(given, _)| *given == account.name)
+                .map(|(_, pubkey)| *pubkey)
+                .or(match account.name {
+                    "system_program" => Some(solana_sdk::system_program::id()),
+                    "token_program" => Some(TOKEN_PROGRAM_ID),
+                    _ => None,
+                });
+
+            match pubkey {
+                Some(pubkey) if account.writable => Ok(AccountMeta::new(pubkey, account.signer)),
+                Some(pubkey) => Ok(AccountMeta::new_readonly(pubkey, account.signer)),
+                None if account.optional => Ok(AccountMeta::new_readonly(PROGRAM_ID, false)),
+                None => Err(format!("{name}: missing account {}", account.name)),
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut data = layout.discriminator.to_vec();
+    data.extend_from_slice(args);
+
+    Ok(Instruction {
+        program_id: PROGRAM_ID,
+        accounts: metas,
+        data,
+    })
+}
+
+/// Sequential Borsh decoder for event bodies and account data
+pub struct Reader<'a>(pub &'a [u8]);
+
+impl Reader<'_> {
+    pub fn bytes<const N: usize>(&mut self) -> [u8; N] {
+        let (head, rest) = self.0.split_at(N);
+        self.0 = rest;
+        head.try_into().unwrap()
+    }
+
+    pub fn skip(&mut self, len: usize) {
+        self.0 = &self.0[len..];
+    }
+
+    pub fn bool(&mut self) -> bool {
+        self.bytes::<1>()[0] != 0
+    }
+
+    pub fn u64(&mut self) -> u64 {
+        u64::from_le_bytes(self.bytes())
+    }
+
+    pub fn i64(&mut self) -> i64 {
+        i64::from_le_bytes(self.bytes())
+    }
+
+    pub fn u128(&mut self) -> u128 {
+        u128::from_le_bytes(self.bytes())
+    }
+
+    pub fn pubkey(&mut self) -> Pubkey {
+        Pubkey::new_from_array(self.bytes())
+    }
+
+    pub fn vec_u8(&mut self) -> Vec<u8> {
+        let len = u32::from_le_bytes(self.bytes()) as usize;
+        let (head, rest) = self.0.split_at(len);
+        self.0 = rest;
+        head.to_vec()
+    }
+}
+
+/// Borsh `Vec<u8>`
+pub fn borsh_bytes(out: &mut Vec<u8>, bytes: &[u8]) {
+    out.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+    out.extend_from_slice(bytes);
+}
+
+pub fn env_var(name: &str) -> ExampleResult<String> {
+    std::env::var(name).map_err(|_| format!("{name} is not set").into())
+}
+
+pub fn env_pubkey(name: &str) -> ExampleResult<Pubkey> {
+    Ok(Pubkey::from_str(&env_var(name)?)?)
+}
+
+pub fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+// ============================================================================
+// ARCIUM
+// ============================================================================
+
+/// Arcium accounts every `queue_*` instruction takes, for one computation
+pub struct ArciumAccounts {
+    pub cluster_offset: u32,
+    pub mxe_pubkey: [u8; 32],
+}
+
+impl ArciumAccounts {
+    pub fn from_env() -> ExampleResult<Self> {
+        Ok(Self {
+            cluster_offset: env_var("ZYNCX_CLUSTER_OFFSET")?.parse()?,
+            mxe_pubkey: env_pubkey("ZYNCX_MXE_PUBKEY")?.to_bytes(),
+        })
+    }
+
+    /// Named accounts for `circuit` queued at `computation_offset`
+    pub fn accounts(&self, circuit: &str, computation_offset: u64) -> Vec<(&'static str, Pubkey)> {
+        let arcium_pda =
+            |seeds: &[&[u8]]| Pubkey::find_program_address(seeds, &ARCIUM_PROGRAM_ID).0;
+        let cluster = self.cluster_offset.to_le_bytes();
+
+        vec![
+            ("sign_pda_account", pda(&[b"ArciumSignerAccount"])),
+            (
+                "mxe_account",
+                arcium_pda(&[b"MXEAccount", PROGRAM_ID.as_ref()]),
+            ),
+            ("mempool_account", arcium_pda(&[b"Mempool", &cluster])),
+            ("mempool_limits", pda(&[b"mempool_limits"])),
+            ("executing_pool", arcium_pda(&[b"Execpool", &cluster])),
+            (
+                "computation_account",
+                arcium_pda(&[
+                    b"ComputationAccount",
+                    &cluster,
+                    &computation_offset.to_le_bytes(),
+                ]),
+            ),
+            (
+                "comp_def_account",
+                arcium_pda(&[
+                    b"ComputationDefinitionAccount",
+                    PROGRAM_ID.as_ref(),
+                    &comp_def_offset(circuit).to_le_bytes(),
+                ]),
+            ),
+            ("cluster_account", arcium_pda(&[b"Cluster", &cluster])),
+            ("pool_account", arcium_pda(&[b"FeePool"])),
+            ("clock_account", arcium_pda(&[b"ClockAccount"])),
+            ("arcium_program", ARCIUM_PROGRAM_ID),
+        ]
+    }
+}
+
+/// Computation definition offset of a circuit (`sha256(name)[..4]`, LE)
+pub fn comp_def_offset(circuit: &str) -> u32 {
+    let hash = Sha256::digest(circuit.as_bytes());
+    u32::from_le_bytes(hash[..4].try_into().unwrap())
+}
+
+/// Random computation offset; also keys per-request PDAs
+pub fn random_offset() -> u64 {
+    zyncx_sdk::random_nonce() as u64
+}
@@ -0,0 +1,191 @@
+//! Queue a confidential swap between two encrypted vaults and wait for the
+//! MXE's verdict.
+//!
+//! ```text
+//! ZYNCX_SOURCE_MINT=<mint> ZYNCX_DEST_MINT=<mint> ZYNCX_PRICE_FEED=<pyth account> \
+//! ZYNCX_CLUSTER_OFFSET=<offset> ZYNCX_MXE_PUBKEY=<x25519 key> \
+//!     cargo run -p zyncx-sdk --example confidential_swap -- <min out> <quoted output> <liquidity depth> [keeper fee]
+//! ```
+//!
+//! The minimum output is encrypted with `SwapBounds::encrypt`; the quote is
+//! public and is checked against the oracle on-chain. The callback emits
+//! `ConfidentialSwapResult` once the cluster has evaluated the bounds.
+
+mod common;
+
+use std::thread::sleep;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use common::*;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+use zyncx_sdk::instructions::DISCRIMINATOR_LEN;
+use zyncx_sdk::{
+    random_nonce, ClientKeypair, RescueCipher, SwapBounds, SwapQuote, SwapVaults, NO_REFERRER,
+};
+
+const REFERRAL_EPOCH_SECONDS: u64 = 7 * 86_400;
+const RESULT_TIMEOUT: Duration = Duration::from_secs(90);
+
+fn main() -> ExampleResult {
+    let args: Vec<u64> = std::env::args()
+        .skip(1)
+        .map(|arg| arg.parse())
+        .collect::<Result<_, _>>()?;
+    let (min_out, quote, keeper_fee) = match args[..] {
+        [min_out, current_output, liquidity_depth] => (
+            min_out,
+            SwapQuote {
+                current_output,
+                liquidity_depth,
+            },
+            0,
+        ),
+        [min_out, current_output, liquidity_depth, keeper_fee] => (
+            min_out,
+            SwapQuote {
+                current_output,
+                liquidity_depth,
+            },
+            keeper_fee,
+        ),
+        _ => return Err("usage: <min out> <quoted output> <liquidity depth> [keeper fee]".into()),
+    };
+
+    let client = Client::from_env()?;
+    let arcium = ArciumAccounts::from_env()?;
+    let vault = pda(&[b"enc_vault", env_pubkey("ZYNCX_SOURCE_MINT")?.as_ref()]);
+    let dest_vault = pda(&[b"enc_vault", env_pubkey("ZYNCX_DEST_MINT")?.as_ref()]);
+    let price_feed = env_pubkey("ZYNCX_PRICE_FEED")?;
+
+    let epoch = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() / REFERRAL_EPOCH_SECONDS;
+    let referral_registry = pda(&[b"referral_registry"]);
+    let referral_epoch = pda(&[b"referral_epoch", &epoch.to_le_bytes()]);
+
+    // Encrypt the bounds to the cluster key
+    let bounds = SwapBounds {
+        min_out,
+        referrer: NO_REFERRER,
+        max_impact_bps: 0,
+    };
+    let encrypted = bounds.encrypt(
+        &RescueCipher,
+        &ClientKeypair::generate(),
+        &arcium.mxe_pubkey,
+        random_nonce(),
+    );
+
+    // The arguments the program will push, for inspection
+    let vaults = SwapVaults {
+        source: vault.to_bytes(),
+        source_nonce: vault_nonce(&client, &vault)?,
+        dest: dest_vault.to_bytes(),
+        dest_nonce: vault_nonce(&client, &dest_vault)?,
+    };
+    let fee_per_referral = {
+        // ReferralRegistry: discriminator, bump, referrers, fee_per_referral
+        let data = client.account_data(&referral_registry)?;
+        let mut reader = Reader(&data);
+        reader.skip(8 + 1 + 32 * zyncx_sdk::REFERRAL_SLOTS);
+        reader.u64()
+    };
+    let epoch_nonce = {
+        // ReferralEpoch: discriminator, bump, epoch, nonce
+        let data = client.account_data(&referral_epoch)?;
+        let mut reader = Reader(&data);
+        reader.skip(8 + 1 + 8);
+        reader.u128()
+    };
+    println!("Computation arguments:");
+    for argument in encrypted.arguments(
+        quote,
+        fee_per_referral,
+        referral_epoch.to_bytes(),
+        epoch_nonce,
+        &vaults,
+    ) {
+        println!("  {argument:?}");
+    }
+
+    let computation_offset = random_offset();
+    let swap_request = pda(&[
+        b"swap_request",
+        client.payer.pubkey().as_ref(),
+        &computation_offset.to_le_bytes(),
+    ]);
+    let data = encrypted.instruction_data(computation_offset, quote, keeper_fee);
+
+    let mut accounts = arcium.accounts("confidential_swap", computation_offset);
+    accounts.extend([
+        ("payer", client.payer.pubkey()),
+        ("vault", vault),
+        ("dest_vault", dest_vault),
+        ("arcium_config", pda(&[b"arcium_config"])),
+        ("price_feed", price_feed),
+        ("price_feed_registry", pda(&[b"price_feed_registry"])),
+        ("referral_registry", referral_registry),
+        ("referral_epoch", referral_epoch),
+        ("swap_request", swap_request),
+        ("computation_fee_escrow", pda(&[b"computation_fees"])),
+        (
+            "user_request_index",
+            pda(&[b"user_request_index", client.payer.pubkey().as_ref()]),
+        ),
+    ]);
+    let ix = instruction(
+        "queue_confidential_swap",
+        &accounts,
+        &data[DISCRIMINATOR_LEN..],
+    )?;
+    let signature = client.send(&[ix], &[])?;
+    let output = client.output(&signature)?;
+
+    let mut event = output
+        .event("ConfidentialSwapQueued")
+        .ok_or("no ConfidentialSwapQueued")?;
+    event.skip(32 * 4 + 8 + 8 + 8);
+    let oracle_price = event.u64();
+    println!("Queued swap request {swap_request} at oracle price {oracle_price}");
+
+    wait_for_result(&client, &swap_request)
+}
+
+/// `EncryptedVaultAccount::nonce`
+fn vault_nonce(client: &Client, vault: &Pubkey) -> ExampleResult<u128> {
+    let data = client.account_data(vault)?;
+    let mut reader = Reader(&data);
+    reader.skip(8 + 1 + 32 + 32);
+    Ok(reader.u128())
+}
+
+/// Poll the swap request's transactions until the callback lands
+fn wait_for_result(client: &Client, swap_request: &Pubkey) -> ExampleResult {
+    let started = SystemTime::now();
+
+    while started.elapsed()? < RESULT_TIMEOUT {
+        sleep(Duration::from_secs(3));
+
+        for status in client.rpc.get_signatures_for_address(swap_request)? {
+            let output = client.output(&status.signature.parse()?)?;
+            if let Some(mut event) = output.event("ConfidentialSwapResult") {
+                let _swap_request = event.pubkey();
+                let should_execute = event.bool();
+                let max_child_amount = event.u64();
+                println!(
+                    "MXE verdict: execute {should_execute}, max child amount {max_child_amount}"
+                );
+                return Ok(());
+            }
+            if output.event("ConfidentialSwapSkipped").is_some() {
+                println!("MXE skipped the swap (stale or aborted computation)");
+                return Ok(());
+            }
+        }
+    }
+
+    Err(format!(
+        "no callback for {swap_request} after {}s",
+        RESULT_TIMEOUT.as_secs()
+    )
+    .into())
+}
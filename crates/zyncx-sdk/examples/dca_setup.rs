@@ -0,0 +1,111 @@
+//! Create an encrypted DCA schedule and read it back.
+//!
+//! ```text
+//! ZYNCX_SOURCE_MINT=<mint> ZYNCX_DEST_MINT=<mint> ZYNCX_PRICE_FEED=<pyth account> \
+//!     cargo run -p zyncx-sdk --example dca_setup -- <amount per swap> <swaps> <min price> <interval seconds> <budget lamports>
+//! ```
+//!
+//! The schedule's `DCAParams` are encrypted to the MXE cluster key, so only
+//! the interval and the escrowed budget are public. Keepers pick the schedule
+//! up from there (`queue_dca_execution`); this example stops at creation.
+
+mod common;
+
+use common::*;
+use solana_sdk::signature::Signer;
+use zyncx_sdk::{random_nonce, ClientKeypair, MxeCipher, RescueCipher};
+
+fn main() -> ExampleResult {
+    let args: Vec<u64> = std::env::args()
+        .skip(1)
+        .map(|arg| arg.parse())
+        .collect::<Result<_, _>>()?;
+    let [amount_per_swap, swaps, min_price, interval_seconds, budget] = args[..] else {
+        return Err(
+            "usage: <amount per swap> <swaps> <min price> <interval seconds> <budget lamports>"
+                .into(),
+        );
+    };
+
+    let client = Client::from_env()?;
+    let mxe_pubkey = env_pubkey("ZYNCX_MXE_PUBKEY")?.to_bytes();
+    let source_vault = pda(&[b"vault", env_pubkey("ZYNCX_SOURCE_MINT")?.as_ref()]);
+    let dest_vault = pda(&[b"vault", env_pubkey("ZYNCX_DEST_MINT")?.as_ref()]);
+    let price_feed = env_pubkey("ZYNCX_PRICE_FEED")?;
+
+    // Enc<Shared, DCAParams>: [amount_per_swap, swaps_remaining, min_price]
+    let keypair = ClientKeypair::generate();
+    let nonce = random_nonce();
+    let ciphertext = RescueCipher.encrypt(
+        &keypair.shared_secret(&mxe_pubkey),
+        nonce,
+        &[amount_per_swap, swaps, min_price],
+    );
+    let encrypted_params: [[u8; 32]; 3] = ciphertext
+        .try_into()
+        .map_err(|_| "expected 3 ciphertexts")?;
+    for (name, block) in ["amount_per_swap", "swaps_remaining", "min_price"]
+        .iter()
+        .zip(&encrypted_params)
+    {
+        println!("{name:>16}: {}", hex(block));
+    }
+
+    let dca_id = random_offset();
+    let dca_config = pda(&[
+        b"dca",
+        client.payer.pubkey().as_ref(),
+        &dca_id.to_le_bytes(),
+    ]);
+
+    let mut data = Vec::new();
+    data.extend_from_slice(&dca_id.to_le_bytes());
+    for block in &encrypted_params {
+        data.extend_from_slice(block);
+    }
+    data.extend_from_slice(&nonce.to_le_bytes());
+    data.extend_from_slice(&keypair.public_key());
+    data.extend_from_slice(&interval_seconds.to_le_bytes());
+    data.extend_from_slice(&budget.to_le_bytes());
+
+    let ix = instruction(
+        "create_dca",
+        &[
+            ("user", client.payer.pubkey()),
+            ("source_vault", source_vault),
+            ("dest_vault", dest_vault),
+            ("price_feed", price_feed),
+            ("recipient", client.payer.pubkey()),
+            ("dca_config", dca_config),
+            ("dca_escrow", pda(&[b"dca_escrow", dca_config.as_ref()])),
+        ],
+        &data,
+    )?;
+    let signature = client.send(&[ix], &[])?;
+    let output = client.output(&signature)?;
+
+    let mut event = output.event("DcaCreated").ok_or("no DcaCreated")?;
+    let dca = event.pubkey();
+    let _user = event.pubkey();
+    let _source_vault = event.pubkey();
+    let _dest_vault = event.pubkey();
+    let interval = event.u64();
+    let escrowed = event.u64();
+    println!("Created DCA {dca}: every {interval}s, {escrowed} lamports escrowed");
+
+    // EncryptedDCAConfig: the stored ciphertexts are the ones we sent
+    let account = client.account_data(&dca_config)?;
+    let mut reader = Reader(&account);
+    reader.skip(8 + 1 + 32 + 32 + 32);
+    let stored: [[u8; 32]; 3] = [reader.bytes(), reader.bytes(), reader.bytes()];
+    let stored_nonce = reader.u128();
+    let _client_pubkey: [u8; 32] = reader.bytes();
+    let _interval_seconds = reader.u64();
+    let next_execution_at = reader.i64();
+    if stored != encrypted_params || stored_nonce != nonce {
+        return Err("stored DCA params differ from the submitted ciphertexts".into());
+    }
+    println!("Next execution at {next_execution_at}; params stored encrypted");
+
+    Ok(())
+}
@@ -0,0 +1,273 @@
+//! Deposit SOL into the shielded pool, then withdraw it with a proof.
+//!
+//! ```text
+//! cargo run -p zyncx-sdk --example deposit_and_withdraw -- deposit <lamports> [note file]
+//! cargo run -p zyncx-sdk --example deposit_and_withdraw -- withdraw <note file> <proof file>
+//! ```
+//!
+//! `deposit` creates a note, deposits it and writes the note file: the
+//! note's secrets, leaf index, authentication path and the public inputs of
+//! a withdrawal to the payer. Generate the withdrawal proof from it with the
+//! mixer circuit (`nargo prove` / Sunspot), then run `withdraw`, which spends
+//! the note and prints the `WithdrawnEvent`. The proof is checked against the
+//! tree's current root, so prove against the root the note file records only
+//! if no deposit landed in between.
+
+mod common;
+
+use std::fs;
+
+use common::*;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+use zyncx_sdk::mirror::TreeHash;
+use zyncx_sdk::proof::ExtData;
+use zyncx_sdk::Note;
+
+const DEFAULT_NOTE_FILE: &str = "zyncx-note.toml";
+
+fn main() -> ExampleResult {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let client = Client::from_env()?;
+
+    match args
+        .iter()
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .as_slice()
+    {
+        ["deposit", amount] => deposit(&client, amount.parse()?, DEFAULT_NOTE_FILE),
+        ["deposit", amount, note_file] => deposit(&client, amount.parse()?, note_file),
+        ["withdraw", note_file, proof_file] => withdraw(&client, note_file, proof_file),
+        _ => {
+            Err("usage: deposit <lamports> [note file] | withdraw <note file> <proof file>".into())
+        }
+    }
+}
+
+struct SolVault {
+    vault: Pubkey,
+    merkle_tree: Pubkey,
+    tree_hash: TreeHash,
+    vault_treasury: Pubkey,
+    vault_stats: Pubkey,
+    vault_fees: Pubkey,
+    global_stats: Pubkey,
+}
+
+impl SolVault {
+    fn load(client: &Client) -> ExampleResult<Self> {
+        let vault = pda(&[b"vault", NATIVE_MINT.as_ref()]);
+
+        // VaultState: discriminator, bump, vault_type, asset_mint, merkle_tree, ...
+        let data = client.account_data(&vault)?;
+        let mut reader = Reader(&data);
+        reader.skip(8 + 1 + 1 + 32);
+        let merkle_tree = reader.pubkey();
+
+        // MerkleTreeState: discriminator, bump, depth, size, current_root_index,
+        // root, roots, vault, epoch, max_leaves, tree_hash, ...
+        let data = client.account_data(&merkle_tree)?;
+        let mut reader = Reader(&data);
+        reader.skip(8 + 1 + 1 + 8 + 1 + 32 + 32 * 30 + 32 + 8 + 4);
+        let tree_hash = match reader.bytes::<1>()[0] {
+            0 => TreeHash::Keccak,
+            _ => TreeHash::Poseidon,
+        };
+
+        Ok(Self {
+            vault,
+            merkle_tree,
+            tree_hash,
+            vault_treasury: pda(&[b"vault_treasury", vault.as_ref()]),
+            vault_stats: pda(&[b"vault_stats", vault.as_ref()]),
+            vault_fees: pda(&[b"vault_fees", vault.as_ref()]),
+            global_stats: pda(&[b"global_stats"]),
+        })
+    }
+}
+
+fn deposit(client: &Client, amount: u64, note_file: &str) -> ExampleResult {
+    let sol_vault = SolVault::load(client)?;
+
+    let note = Note::generate(amount);
+
+    let mut args = Vec::new();
+    args.extend_from_slice(&amount.to_le_bytes());
+    args.extend_from_slice(&note.precommitment(sol_vault.tree_hash));
+    borsh_bytes(&mut args, &[]); // encrypted_note
+    borsh_bytes(&mut args, &[]); // viewing_ciphertext
+    args.push(1); // include_path
+
+    let ix = instruction(
+        "deposit_native",
+        &[
+            ("depositor", client.payer.pubkey()),
+            ("vault", sol_vault.vault),
+            ("merkle_tree", sol_vault.merkle_tree),
+            ("vault_treasury", sol_vault.vault_treasury),
+            ("vault_stats", sol_vault.vault_stats),
+            ("global_stats", sol_vault.global_stats),
+        ],
+        &args,
+    )?;
+    let signature = client.send(&[ix], &[])?;
+    let output = client.output(&signature)?;
+
+    let mut event = output.event("DepositedEvent").ok_or("no DepositedEvent")?;
+    let _depositor = event.pubkey();
+    let deposited = event.u64();
+    let commitment: [u8; 32] = event.bytes();
+    println!(
+        "Deposited {deposited} lamports as commitment {}",
+        hex(&commitment)
+    );
+
+    // DepositReceipt { insertion: { leaf_index, new_root, tree_epoch, slot }, auth_path }
+    let mut receipt = Reader(&output.return_data);
+    let leaf_index = receipt.u64();
+    let root: [u8; 32] = receipt.bytes();
+    let tree_epoch = receipt.u64();
+    let _slot = receipt.u64();
+    let path_len = u32::from_le_bytes(receipt.bytes()) as usize;
+    let auth_path: Vec<String> = (0..path_len).map(|_| hex(&receipt.bytes::<32>())).collect();
+    println!(
+        "Leaf {leaf_index} (tree epoch {tree_epoch}), root {}",
+        hex(&root)
+    );
+
+    // Public inputs of a full withdrawal to the payer, relayed by the payer
+    let ext_data_hash = ext_data_hash(&client.payer.pubkey(), &client.payer.pubkey(), deposited);
+
+    let note = format!(
+        "# Zyncx note - keep secret\n\
+         amount = \"{deposited}\"\n\
+         secret = \"{}\"\n\
+         nullifier_secret = \"{}\"\n\
+         commitment = \"{}\"\n\
+         leaf_index = \"{leaf_index}\"\n\
+         merkle_path = [{}]\n\
+         \n\
+         # Public inputs of a withdrawal to {}\n\
+         root = \"{}\"\n\
+         nullifier_hash = \"{}\"\n\
+         recipient = \"{}\"\n\
+         ext_data_hash = \"{}\"\n\
+         remaining_amount = \"0\"\n",
+        hex(&note.secret),
+        hex(&note.nullifier_secret),
+        hex(&commitment),
+        auth_path
+            .iter()
+            .map(|node| format!("\"{node}\""))
+            .collect::<Vec<_>>()
+            .join(", "),
+        client.payer.pubkey(),
+        hex(&root),
+        hex(&note.nullifier_hash(sol_vault.tree_hash)),
+        hex(&client.payer.pubkey().to_bytes()),
+        hex(&ext_data_hash),
+    );
+    fs::write(note_file, note)?;
+    println!("Note written to {note_file}");
+
+    Ok(())
+}
+
+fn withdraw(client: &Client, note_file: &str, proof_file: &str) -> ExampleResult {
+    let sol_vault = SolVault::load(client)?;
+    let note = fs::read_to_string(note_file)?;
+    let amount: u64 = note_value(&note, "amount")?.parse()?;
+    let nullifier: [u8; 32] = unhex(&note_value(&note, "nullifier_hash")?)?;
+    let proof = fs::read(proof_file)?;
+
+    let recipient = client.payer.pubkey();
+    let ext_data_hash = ext_data_hash(&recipient, &client.payer.pubkey(), amount);
+
+    let mut args = Vec::new();
+    args.extend_from_slice(&amount.to_le_bytes());
+    args.extend_from_slice(&nullifier);
+    args.extend_from_slice(&[0u8; 32]); // new_commitment: full withdrawal
+    args.extend_from_slice(&0u64.to_le_bytes()); // remaining_amount
+    borsh_bytes(&mut args, &proof);
+    // WithdrawExtData { max_fee, relayer_tip, viewing_ciphertext, ephemeral_pubkey,
+    // fee_schedule_hash, ext_data_hash }
+    args.extend_from_slice(&amount.to_le_bytes());
+    args.extend_from_slice(&0u64.to_le_bytes());
+    borsh_bytes(&mut args, &[]);
+    args.extend_from_slice(&[0u8; 32]);
+    args.extend_from_slice(&[0u8; 32]);
+    args.extend_from_slice(&ext_data_hash);
+    borsh_bytes(&mut args, &[]); // exclusion_proof
+
+    let ix = instruction(
+        "withdraw_native",
+        &[
+            ("recipient", recipient),
+            ("vault", sol_vault.vault),
+            ("merkle_tree", sol_vault.merkle_tree),
+            ("vault_treasury", sol_vault.vault_treasury),
+            (
+                "nullifier_account",
+                pda(&[b"nullifier", sol_vault.vault.as_ref(), &nullifier]),
+            ),
+            ("vault_stats", sol_vault.vault_stats),
+            ("vault_fees", sol_vault.vault_fees),
+            ("global_stats", sol_vault.global_stats),
+            ("blocklist", pda(&[b"blocklist"])),
+            ("fee_exemptions", pda(&[b"fee_exemptions"])),
+            ("protocol_config", pda(&[b"protocol_config"])),
+            ("verifier_program", NOIR_VERIFIER_PROGRAM_ID),
+            ("payer", client.payer.pubkey()),
+        ],
+        &args,
+    )?;
+    let signature = client.send(&[ix], &[])?;
+    let output = client.output(&signature)?;
+
+    let mut event = output.event("WithdrawnEvent").ok_or("no WithdrawnEvent")?;
+    let recipient = event.pubkey();
+    let amount = event.u64();
+    let fee = event.u64();
+    let relayer_tip = event.u64();
+    let _nullifier: [u8; 32] = event.bytes();
+    let _new_commitment: [u8; 32] = event.bytes();
+    let is_partial = event.bool();
+    let _viewing_ciphertext = event.vec_u8();
+    let _exclusion_root: [u8; 32] = event.bytes();
+    let _ephemeral_pubkey: [u8; 32] = event.bytes();
+    let anonymity_set = event.u64();
+    println!(
+        "Withdrew {amount} lamports to {recipient} (fee {fee}, tip {relayer_tip}, partial {is_partial}), \
+         anonymity set {anonymity_set}"
+    );
+
+    Ok(())
+}
+
+/// `WithdrawExtData::hash` with no disclosure, no stealth key and no tip
+fn ext_data_hash(recipient: &Pubkey, relayer: &Pubkey, max_fee: u64) -> [u8; 32] {
+    ExtData {
+        max_fee,
+        ..ExtData::default()
+    }
+    .hash(&recipient.to_bytes(), &relayer.to_bytes())
+}
+
+fn note_value(note: &str, key: &str) -> ExampleResult<String> {
+    note.lines()
+        .filter_map(|line| line.split_once(" = "))
+        .find(|(name, _)| *name == key)
+        .map(|(_, value)| value.trim_matches('"').to_string())
+        .ok_or_else(|| format!("note has no {key}").into())
+}
+
+fn unhex<const N: usize>(value: &str) -> ExampleResult<[u8; N]> {
+    let bytes = (0..value.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&value[i..i + 2], 16))
+        .collect::<Result<Vec<_>, _>>()?;
+    bytes
+        .try_into()
+        .map_err(|_| format!("expected {N} bytes: {value}").into())
+}
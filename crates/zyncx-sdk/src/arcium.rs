@@ -0,0 +1,320 @@
+// ============================================================================
+// ARCIUM ENCRYPTED ARGUMENTS
+// ============================================================================
+// The queue_* instructions forward their arguments to the MXE through an
+// ArgBuilder. The circuit consumes those arguments strictly in push order, so
+// a client has to produce ciphertexts, nonces and pubkeys that line up with
+// the builder calls in the program:
+//
+//   queue_confidential_swap  x25519_pubkey, plaintext_u128(nonce),
+//                            encrypted_u64(min_out), encrypted_u64(referrer),
+//                            encrypted_u64(max_impact_bps),
+//                            plaintext_u64(output), plaintext_u64(depth),
+//                            plaintext_u64(referral_fee),
+//                            plaintext_u128(epoch_nonce),
+//                            account(referral_epoch, 33, 128),
+//                            plaintext_u128(source_nonce),
+//                            account(source_vault, 89, 96),
+//                            plaintext_u128(dest_nonce),
+//                            account(dest_vault, 89, 96)
+//   queue_encrypted_deposit  plaintext_u64(amount), plaintext_u128(nonce),
+//                            account(vault, 89, 96)
+//   queue_compute_withdrawal x25519_pubkey, plaintext_u128(nonce),
+//                            plaintext_u128(position_nonce),
+//                            account(position, 73, 64)
+//   queue_balance_check      plaintext_u64(threshold),
+//                            plaintext_u128(position_nonce),
+//                            account(position, 73, 64)
+//
+// Key agreement (x25519) and nonce generation live here. The symmetric cipher
+// is an `MxeCipher`; `RescueCipher` (rescue.rs) is the one the MXE decrypts
+// with. Instruction data is laid out from the instruction table, so it
+// follows the program's argument list.
+// ============================================================================
+
+use rand_core::{OsRng, RngCore};
+use sha2::{Digest, Sha256};
+use x25519_dalek::{PublicKey, StaticSecret};
+
+use crate::instructions::encode_instruction;
+
+/// Size of every MXE ciphertext (one field element)
+pub const CIPHERTEXT_LEN: usize = 32;
+
+/// Size of the encryption nonce passed as `plaintext_u128`
+pub const NONCE_LEN: usize = 16;
+
+/// Offset of `encrypted_state` inside `EncryptedVaultAccount`
+/// (discriminator + bump + token_mint + authority + nonce)
+pub const VAULT_STATE_OFFSET: u32 = 8 + 1 + 32 + 32 + 16;
+
+/// Length of `encrypted_state` ([pending_deposits, total_liquidity, total_deposited])
+pub const VAULT_STATE_LEN: u32 = 32 * 3;
+
+/// Number of referral slots accrued per epoch
+pub const REFERRAL_SLOTS: usize = 4;
+
+/// Offset of `encrypted_totals` inside `ReferralEpoch`
+/// (discriminator + bump + epoch + nonce)
+pub const REFERRAL_TOTALS_OFFSET: u32 = 8 + 1 + 8 + 16;
+
+/// Length of `encrypted_totals` (one ciphertext per referral slot)
+pub const REFERRAL_TOTALS_LEN: u32 = 32 * REFERRAL_SLOTS as u32;
+
+/// Referrer code meaning "no referrer"
+pub const NO_REFERRER: u64 = 0;
+
+/// Referrer code for a registry slot (slot + 1)
+pub fn referrer_code(slot: u8) -> u64 {
+    slot as u64 + 1
+}
+
+/// Symmetric cipher shared between the client and the MXE.
+///
+/// Implementations must be deterministic in `(shared_secret, nonce)` and map
+/// each plaintext word to exactly one 32-byte ciphertext, in order.
+pub trait MxeCipher {
+    fn encrypt(&self, shared_secret: &[u8; 32], nonce: u128, plaintext: &[u64]) -> Vec<[u8; 32]>;
+
+    fn decrypt(&self, shared_secret: &[u8; 32], nonce: u128, ciphertext: &[[u8; 32]]) -> Vec<u64>;
+}
+
+/// One `ArgBuilder` call, in the order the program pushes it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Argument {
+    X25519Pubkey([u8; 32]),
+    PlaintextU64(u64),
+    PlaintextU128(u128),
+    EncryptedU64([u8; 32]),
+    Account {
+        pubkey: [u8; 32],
+        offset: u32,
+        length: u32,
+    },
+}
+
+/// Client x25519 keypair used for `Enc<Shared, _>` inputs
+pub struct ClientKeypair {
+    secret: StaticSecret,
+    public: PublicKey,
+}
+
+impl ClientKeypair {
+    pub fn generate() -> Self {
+        Self::from_secret_bytes(random_bytes())
+    }
+
+    pub fn from_secret_bytes(secret: [u8; 32]) -> Self {
+        let secret = StaticSecret::from(secret);
+        let public = PublicKey::from(&secret);
+        Self { secret, public }
+    }
+
+    pub fn public_key(&self) -> [u8; 32] {
+        self.public.to_bytes()
+    }
+
+    pub fn secret_bytes(&self) -> [u8; 32] {
+        self.secret.to_bytes()
+    }
+
+    /// Diffie-Hellman secret shared with the MXE cluster key
+    pub fn shared_secret(&self, mxe_pubkey: &[u8; 32]) -> [u8; 32] {
+        self.secret
+            .diffie_hellman(&PublicKey::from(*mxe_pubkey))
+            .to_bytes()
+    }
+}
+
+/// Fresh random nonce. Never reuse a nonce with the same shared secret.
+pub fn random_nonce() -> u128 {
+    let mut bytes = [0u8; NONCE_LEN];
+    OsRng.fill_bytes(&mut bytes);
+    u128::from_le_bytes(bytes)
+}
+
+fn random_bytes() -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    bytes
+}
+
+/// Anchor instruction discriminator (`sha256("global:<name>")[..8]`)
+pub fn instruction_discriminator(name: &str) -> [u8; 8] {
+    let hash = Sha256::digest(format!("global:{name}").as_bytes());
+    let mut discriminator = [0u8; 8];
+    discriminator.copy_from_slice(&hash[..8]);
+    discriminator
+}
+
+// ============================================================================
+// CONFIDENTIAL SWAP
+// ============================================================================
+
+/// Plaintext swap params consumed by the `confidential_swap` circuit
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SwapBounds {
+    pub min_out: u64,
+    /// `referrer_code(slot)`, or `NO_REFERRER`
+    pub referrer: u64,
+    /// Highest price impact one child order may cause, or 0 for no cap
+    pub max_impact_bps: u64,
+}
+
+/// Plaintext market snapshot the swap is evaluated against
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SwapQuote {
+    /// Output the full amount would receive on the current route
+    pub current_output: u64,
+    /// Input-side liquidity depth of the route (AMM reserve or quote depth)
+    pub liquidity_depth: u64,
+}
+
+/// Encrypted vaults a confidential swap moves between, with their current
+/// `EncryptedVaultAccount::nonce`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SwapVaults {
+    pub source: [u8; 32],
+    pub source_nonce: u128,
+    pub dest: [u8; 32],
+    pub dest_nonce: u128,
+}
+
+/// Swap params encrypted to the MXE cluster key
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EncryptedSwapBounds {
+    pub encryption_pubkey: [u8; 32],
+    pub nonce: u128,
+    /// [min_out, referrer, max_impact_bps]
+    pub encrypted_params: [[u8; 32]; 3],
+}
+
+impl SwapBounds {
+    /// Encrypt the bounds for `queue_confidential_swap`
+    pub fn encrypt<C: MxeCipher>(
+        &self,
+        cipher: &C,
+        keypair: &ClientKeypair,
+        mxe_pubkey: &[u8; 32],
+        nonce: u128,
+    ) -> EncryptedSwapBounds {
+        let shared_secret = keypair.shared_secret(mxe_pubkey);
+        let ciphertext = cipher.encrypt(
+            &shared_secret,
+            nonce,
+            &[self.min_out, self.referrer, self.max_impact_bps],
+        );
+
+        EncryptedSwapBounds {
+            encryption_pubkey: keypair.public_key(),
+            nonce,
+            encrypted_params: [ciphertext[0], ciphertext[1], ciphertext[2]],
+        }
+    }
+}
+
+impl EncryptedSwapBounds {
+    /// Arguments in the order `queue_confidential_swap` pushes them.
+    /// `referral_fee` is the registry's `fee_per_referral`; `referral_epoch`
+    /// and `epoch_nonce` are the current `ReferralEpoch` account and nonce.
+    pub fn arguments(
+        &self,
+        quote: SwapQuote,
+        referral_fee: u64,
+        referral_epoch: [u8; 32],
+        epoch_nonce: u128,
+        vaults: &SwapVaults,
+    ) -> Vec<Argument> {
+        vec![
+            Argument::X25519Pubkey(self.encryption_pubkey),
+            Argument::PlaintextU128(self.nonce),
+            Argument::EncryptedU64(self.encrypted_params[0]),
+            Argument::EncryptedU64(self.encrypted_params[1]),
+            Argument::EncryptedU64(self.encrypted_params[2]),
+            Argument::PlaintextU64(quote.current_output),
+            Argument::PlaintextU64(quote.liquidity_depth),
+            Argument::PlaintextU64(referral_fee),
+            Argument::PlaintextU128(epoch_nonce),
+            Argument::Account {
+                pubkey: referral_epoch,
+                offset: REFERRAL_TOTALS_OFFSET,
+                length: REFERRAL_TOTALS_LEN,
+            },
+            Argument::PlaintextU128(vaults.source_nonce),
+            Argument::Account {
+                pubkey: vaults.source,
+                offset: VAULT_STATE_OFFSET,
+                length: VAULT_STATE_LEN,
+            },
+            Argument::PlaintextU128(vaults.dest_nonce),
+            Argument::Account {
+                pubkey: vaults.dest,
+                offset: VAULT_STATE_OFFSET,
+                length: VAULT_STATE_LEN,
+            },
+        ]
+    }
+
+    /// Serialized `queue_confidential_swap` instruction data
+    pub fn instruction_data(
+        &self,
+        computation_offset: u64,
+        quote: SwapQuote,
+        keeper_fee: u64,
+    ) -> Vec<u8> {
+        encode_instruction(
+            "queue_confidential_swap",
+            &[
+                ("computation_offset", &computation_offset.to_le_bytes()),
+                ("encrypted_params", &self.encrypted_params.concat()),
+                ("encryption_pubkey", &self.encryption_pubkey),
+                ("nonce", &self.nonce.to_le_bytes()),
+                ("quote.current_output", &quote.current_output.to_le_bytes()),
+                (
+                    "quote.liquidity_depth",
+                    &quote.liquidity_depth.to_le_bytes(),
+                ),
+                ("keeper_fee", &keeper_fee.to_le_bytes()),
+            ],
+        )
+        .expect("queue_confidential_swap arguments match the instruction table")
+    }
+}
+
+// ============================================================================
+// ENCRYPTED DEPOSIT
+// ============================================================================
+
+/// Plaintext input to the `process_deposit` circuit
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DepositInput {
+    pub amount: u64,
+}
+
+impl DepositInput {
+    /// Arguments in the order `queue_encrypted_deposit` pushes them.
+    /// `vault_nonce` is the current `EncryptedVaultAccount::nonce`.
+    pub fn arguments(&self, vault: [u8; 32], vault_nonce: u128) -> Vec<Argument> {
+        vec![
+            Argument::PlaintextU64(self.amount),
+            Argument::PlaintextU128(vault_nonce),
+            Argument::Account {
+                pubkey: vault,
+                offset: VAULT_STATE_OFFSET,
+                length: VAULT_STATE_LEN,
+            },
+        ]
+    }
+
+    /// Serialized `queue_encrypted_deposit` instruction data
+    pub fn instruction_data(&self, computation_offset: u64) -> Vec<u8> {
+        encode_instruction(
+            "queue_encrypted_deposit",
+            &[
+                ("computation_offset", &computation_offset.to_le_bytes()),
+                ("deposit_amount", &self.amount.to_le_bytes()),
+            ],
+        )
+        .expect("queue_encrypted_deposit arguments match the instruction table")
+    }
+}
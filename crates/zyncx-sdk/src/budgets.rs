@@ -0,0 +1,72 @@
+// ============================================================================
+// COMPUTE BUDGETS
+// ============================================================================
+// Compute-unit ceilings per instruction. The integration tests measure what
+// each instruction actually consumes and fail once it exceeds its ceiling, so
+// a change that pushes a deposit or swap past what fits in a mainnet
+// transaction shows up before it ships.
+//
+// The ceilings cover the instruction alone. Deposits have to fit in the
+// default 200k units so wallets can send them without a compute-budget
+// instruction; anything that verifies a proof leaves room under the 1.4M
+// transaction limit for the compute-budget instructions and, for swaps, the
+// Jupiter route.
+//
+// build.rs copies the ceilings into the instruction table, which is where
+// the TypeScript tests read them from.
+// ============================================================================
+
+/// Solana's hard per-transaction compute limit
+pub const MAX_TRANSACTION_COMPUTE_UNITS: u32 = 1_400_000;
+
+/// Units an instruction gets when the transaction requests no budget
+pub const DEFAULT_INSTRUCTION_COMPUTE_UNITS: u32 = 200_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ComputeBudget {
+    pub instruction: &'static str,
+    pub max_units: u32,
+}
+
+pub const COMPUTE_BUDGETS: &[ComputeBudget] = &[
+    ComputeBudget {
+        instruction: "deposit_native",
+        max_units: DEFAULT_INSTRUCTION_COMPUTE_UNITS,
+    },
+    ComputeBudget {
+        instruction: "deposit_token",
+        max_units: DEFAULT_INSTRUCTION_COMPUTE_UNITS,
+    },
+    ComputeBudget {
+        instruction: "withdraw_native",
+        max_units: 1_000_000,
+    },
+    ComputeBudget {
+        instruction: "withdraw_token",
+        max_units: 1_000_000,
+    },
+    ComputeBudget {
+        instruction: "swap_native",
+        max_units: 1_100_000,
+    },
+    ComputeBudget {
+        instruction: "swap_token",
+        max_units: 1_100_000,
+    },
+    ComputeBudget {
+        instruction: "verify_proof",
+        max_units: 1_000_000,
+    },
+    ComputeBudget {
+        instruction: "queue_encrypted_deposit",
+        max_units: DEFAULT_INSTRUCTION_COMPUTE_UNITS,
+    },
+    ComputeBudget {
+        instruction: "queue_confidential_swap",
+        max_units: DEFAULT_INSTRUCTION_COMPUTE_UNITS,
+    },
+    ComputeBudget {
+        instruction: "execute_insurance_claim",
+        max_units: 400_000,
+    },
+];
@@ -0,0 +1,213 @@
+// ============================================================================
+// INSTRUCTION TABLE
+// ============================================================================
+// Generated by build.rs from the program sources, for clients that do not go
+// through Anchor's TypeScript client (raw web3, Python, keepers):
+//
+//   discriminator  sha256("global:<instruction name>")[..8], the first eight
+//                  bytes of the instruction data
+//   accounts       in the order the program expects them; optional accounts
+//                  that are left out are passed as the program id
+//   args           Borsh-encoded back to back after the discriminator, using
+//                  the Rust spelling of each type. Named types are listed in
+//                  `TYPES`; enums encode a u8 variant index, then its fields.
+//                  `encode_instruction` lays out named values in this order
+//   compute_budget the instruction's ceiling from budgets.rs, or null
+//
+// The same table is committed as `zyncx-instructions.json` at the root of
+// this crate. Refresh it with:
+//
+//   ZYNCX_WRITE_INSTRUCTION_TABLE=1 cargo build -p zyncx-sdk
+// ============================================================================
+
+use std::fmt;
+
+/// Size of the instruction discriminator prefix
+pub const DISCRIMINATOR_LEN: usize = 8;
+
+/// JSON rendering of `INSTRUCTIONS` and `TYPES`
+pub const INSTRUCTION_TABLE_JSON: &str =
+    include_str!(concat!(env!("OUT_DIR"), "/zyncx-instructions.json"));
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InstructionLayout {
+    pub name: &'static str,
+    pub discriminator: [u8; DISCRIMINATOR_LEN],
+    pub accounts: &'static [AccountLayout],
+    pub args: &'static [FieldLayout],
+    /// Compute-unit ceiling from `budgets.rs`, if one is declared
+    pub compute_budget: Option<u32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AccountLayout {
+    pub name: &'static str,
+    pub writable: bool,
+    pub signer: bool,
+    pub optional: bool,
+}
+
+/// An instruction argument or a field of a named type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldLayout {
+    pub name: &'static str,
+    /// Rust spelling of the Borsh type, e.g. `u64`, `[u8; 32]`, `Vec<ClaimPayout>`
+    pub ty: &'static str,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TypeLayout {
+    pub name: &'static str,
+    pub kind: TypeKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TypeKind {
+    Struct(&'static [FieldLayout]),
+    /// Variants in index order; tuple fields are named "0", "1", ...
+    Enum(&'static [VariantLayout]),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VariantLayout {
+    pub name: &'static str,
+    pub fields: &'static [FieldLayout],
+}
+
+include!(concat!(env!("OUT_DIR"), "/instruction_table.rs"));
+
+/// Look up an instruction by name
+pub fn instruction(name: &str) -> Option<&'static InstructionLayout> {
+    INSTRUCTIONS.iter().find(|ix| ix.name == name)
+}
+
+/// Identify an instruction from its data
+pub fn instruction_for_data(data: &[u8]) -> Option<&'static InstructionLayout> {
+    let discriminator = data.get(..DISCRIMINATOR_LEN)?;
+    INSTRUCTIONS
+        .iter()
+        .find(|ix| ix.discriminator == discriminator)
+}
+
+/// Look up a named argument type
+pub fn type_layout(name: &str) -> Option<&'static TypeLayout> {
+    TYPES.iter().find(|ty| ty.name == name)
+}
+
+/// Borsh size of a type spelled as in the table, when every value of it has
+/// the same size
+pub fn fixed_size(ty: &str) -> Option<usize> {
+    match ty {
+        "u8" | "i8" | "bool" => Some(1),
+        "u16" | "i16" => Some(2),
+        "u32" | "i32" => Some(4),
+        "u64" | "i64" => Some(8),
+        "u128" | "i128" => Some(16),
+        "Pubkey" => Some(32),
+        "()" => Some(0),
+        _ => {
+            if let Some(array) = ty.strip_prefix('[').and_then(|ty| ty.strip_suffix(']')) {
+                let (elem, len) = array.rsplit_once("; ")?;
+                return Some(fixed_size(elem)? * len.parse::<usize>().ok()?);
+            }
+            match type_layout(ty)?.kind {
+                TypeKind::Struct(fields) => fields.iter().map(|f| fixed_size(f.ty)).sum(),
+                TypeKind::Enum(variants) => {
+                    variants.iter().all(|v| v.fields.is_empty()).then_some(1)
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EncodeError {
+    UnknownInstruction(String),
+    /// No value for this argument (or struct field, as `arg.field`)
+    MissingArgument(String),
+    /// A value that matches no argument of the instruction
+    UnexpectedArgument(String),
+    /// The value is not the encoded size of the argument's type
+    WrongSize {
+        argument: String,
+        expected: usize,
+        found: usize,
+    },
+}
+
+impl fmt::Display for EncodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownInstruction(name) => write!(f, "unknown instruction {name}"),
+            Self::MissingArgument(name) => write!(f, "missing argument {name}"),
+            Self::UnexpectedArgument(name) => write!(f, "unexpected argument {name}"),
+            Self::WrongSize {
+                argument,
+                expected,
+                found,
+            } => write!(f, "{argument} must be {expected} bytes, got {found}"),
+        }
+    }
+}
+
+impl std::error::Error for EncodeError {}
+
+/// Instruction data for `name`: the discriminator, then `values` in the
+/// order the table lists the arguments. Each value is the Borsh encoding of
+/// one argument; the fields of a named struct argument may instead be given
+/// one by one as `"<arg>.<field>"`.
+pub fn encode_instruction(name: &str, values: &[(&str, &[u8])]) -> Result<Vec<u8>, EncodeError> {
+    let layout =
+        instruction(name).ok_or_else(|| EncodeError::UnknownInstruction(name.to_string()))?;
+
+    let mut data = layout.discriminator.to_vec();
+    let mut used = vec![false; values.len()];
+    encode_fields(layout.args, "", values, &mut used, &mut data)?;
+
+    match used.iter().position(|used| !used) {
+        Some(unused) => Err(EncodeError::UnexpectedArgument(
+            values[unused].0.to_string(),
+        )),
+        None => Ok(data),
+    }
+}
+
+fn encode_fields(
+    fields: &[FieldLayout],
+    prefix: &str,
+    values: &[(&str, &[u8])],
+    used: &mut [bool],
+    out: &mut Vec<u8>,
+) -> Result<(), EncodeError> {
+    for field in fields {
+        let path = if prefix.is_empty() {
+            field.name.to_string()
+        } else {
+            format!("{prefix}.{}", field.name)
+        };
+
+        if let Some(index) = values.iter().position(|(name, _)| *name == path) {
+            let value = values[index].1;
+            if let Some(expected) = fixed_size(field.ty) {
+                if value.len() != expected {
+                    return Err(EncodeError::WrongSize {
+                        argument: path,
+                        expected,
+                        found: value.len(),
+                    });
+                }
+            }
+            used[index] = true;
+            out.extend_from_slice(value);
+        } else if let Some(TypeLayout {
+            kind: TypeKind::Struct(inner),
+            ..
+        }) = type_layout(field.ty)
+        {
+            encode_fields(inner, &path, values, used, out)?;
+        } else {
+            return Err(EncodeError::MissingArgument(path));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,25 @@
+//! Client-side helpers for Zyncx.
+//!
+//! Everything here runs off-chain: wallets, keepers and the web app use it to
+//! build instruction arguments that the on-chain program and the Arcium MXE
+//! accept byte-for-byte.
+
+pub mod arcium;
+pub mod budgets;
+pub mod instructions;
+pub mod mirror;
+pub mod note;
+pub mod proof;
+pub mod rescue;
+pub mod tree;
+
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+pub use arcium::*;
+pub use note::Note;
+pub use rescue::RescueCipher;
+/// Merkle tree hashing shared with the program. `hash::Keccak` (or
+/// `hash::ParallelKeccak` with the `parallel` feature) and `hash::Poseidon`
+/// rebuild the roots of Keccak and Poseidon trees
+pub use zyncx_core::hash;
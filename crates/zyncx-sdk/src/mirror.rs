@@ -0,0 +1,276 @@
+// ============================================================================
+// MERKLE TREE MIRROR
+// ============================================================================
+// An off-chain copy of one `MerkleTreeState`, advanced exactly as the
+// program advances it: each commitment is hashed with its insertion slot into
+// a leaf node and appended to the frontier with `zyncx_core::hash`, through
+// the tree's `TreeHash`. Feeding it a vault's `DepositedEvent`s (with the
+// slot of the transaction that emitted each) reproduces every root byte for
+// byte, and each event's `new_root` is checked on the way, so a mismatch
+// surfaces here rather than as a failed withdrawal proof.
+//
+// Change notes of partial withdrawals, swaps and transfers are inserted
+// without a `DepositedEvent`; feed those trees their `LeafInserted` events
+// with `apply_leaf`, or the next deposit reports `OutOfOrder`.
+// ============================================================================
+
+use std::fmt;
+
+use sha2::{Digest, Sha256};
+
+use crate::hash::{self, Hash, HashBackend, Keccak, Poseidon, ZERO_HASH};
+use crate::tree::LeafInserted;
+
+/// Levels of the program's trees (`MAX_DEPTH`)
+pub const MAX_DEPTH: usize = 20;
+
+/// Leaves one tree holds at most
+pub const MAX_TREE_LEAVES: u64 = 1 << MAX_DEPTH;
+
+type Frontier = [Hash; MAX_DEPTH + 1];
+
+/// Two-to-one hash of a tree, as the program's `TreeHash`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TreeHash {
+    #[default]
+    Keccak,
+    Poseidon,
+}
+
+impl TreeHash {
+    pub fn backend(self) -> &'static dyn HashBackend {
+        match self {
+            TreeHash::Keccak => &Keccak,
+            TreeHash::Poseidon => &Poseidon,
+        }
+    }
+
+    /// Whether the program inserts `commitment` into a tree using this hash
+    pub fn accepts(self, commitment: &Hash) -> bool {
+        self == TreeHash::Keccak || hash::is_field_element(commitment)
+    }
+
+    /// Root of an empty tree using this hash
+    pub fn empty_root(self) -> Hash {
+        hash::empty_root(self.backend())
+    }
+}
+
+/// Tree position carried by a `DepositedEvent`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DepositedEvent {
+    pub depositor: [u8; 32],
+    pub amount: u64,
+    pub commitment: Hash,
+    pub leaf_index: u64,
+    pub new_root: Hash,
+}
+
+impl DepositedEvent {
+    /// Decode an event payload (discriminator + Borsh body); `None` for any
+    /// other event
+    pub fn from_event_data(data: &[u8]) -> Option<Self> {
+        let discriminator = Sha256::digest(b"event:DepositedEvent");
+        let mut body = Body(data.strip_prefix(&discriminator[..8])?);
+
+        let depositor = body.word()?;
+        let amount = body.u64()?;
+        let commitment = body.word()?;
+        let _precommitment = body.word()?;
+        body.bytes()?; // encrypted_note
+        let _viewing_pubkey = body.word()?;
+        body.bytes()?; // viewing_ciphertext
+        Some(Self {
+            depositor,
+            amount,
+            commitment,
+            leaf_index: body.u64()?,
+            new_root: body.word()?,
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MirrorError {
+    /// The event is not for the next leaf of the mirrored tree
+    OutOfOrder {
+        expected: u64,
+        leaf_index: u64,
+    },
+    /// The root after inserting the leaf differs from the one the program
+    /// reported: wrong tree, wrong hash mode or wrong slot
+    RootMismatch {
+        leaf_index: u64,
+    },
+    /// Poseidon trees only take field elements
+    CommitmentNotInField,
+    TreeFull,
+}
+
+impl fmt::Display for MirrorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::OutOfOrder {
+                expected,
+                leaf_index,
+            } => write!(f, "expected leaf {expected}, got leaf {leaf_index}"),
+            Self::RootMismatch { leaf_index } => {
+                write!(f, "root after leaf {leaf_index} differs from the program's")
+            }
+            Self::CommitmentNotInField => write!(f, "commitment is not a field element"),
+            Self::TreeFull => write!(f, "tree is full"),
+        }
+    }
+}
+
+impl std::error::Error for MirrorError {}
+
+/// Off-chain replica of one merkle tree
+#[derive(Clone, Debug)]
+pub struct MerkleTreeMirror {
+    tree_hash: TreeHash,
+    frontier: Frontier,
+    commitments: Vec<Hash>,
+    slots: Vec<u64>,
+    root: Hash,
+}
+
+impl MerkleTreeMirror {
+    pub fn new(tree_hash: TreeHash) -> Self {
+        Self {
+            tree_hash,
+            frontier: [ZERO_HASH; MAX_DEPTH + 1],
+            commitments: Vec::new(),
+            slots: Vec::new(),
+            root: tree_hash.empty_root(),
+        }
+    }
+
+    pub fn tree_hash(&self) -> TreeHash {
+        self.tree_hash
+    }
+
+    /// Leaves inserted so far (`MerkleTreeState::size`)
+    pub fn size(&self) -> u64 {
+        self.commitments.len() as u64
+    }
+
+    /// Current root (`MerkleTreeState::root`), `TreeHash::empty_root` while
+    /// empty
+    pub fn root(&self) -> Hash {
+        self.root
+    }
+
+    /// Append `commitment` inserted at `slot`, as `MerkleTreeState::insert`
+    /// does, and return its leaf index and the new root
+    pub fn insert(&mut self, commitment: Hash, slot: u64) -> Result<(u64, Hash), MirrorError> {
+        let leaf_index = self.size();
+        let (frontier, root) = self.appended(&commitment, slot)?;
+        self.commit(commitment, slot, frontier, root);
+        Ok((leaf_index, root))
+    }
+
+    /// Replay a deposit emitted in a transaction at `slot`
+    pub fn apply_deposit(&mut self, event: &DepositedEvent, slot: u64) -> Result<(), MirrorError> {
+        self.apply(event.leaf_index, event.commitment, slot, event.new_root)
+    }
+
+    /// Replay any leaf insertion of this tree
+    pub fn apply_leaf(&mut self, event: &LeafInserted) -> Result<(), MirrorError> {
+        self.apply(
+            event.leaf_index,
+            event.commitment,
+            event.slot,
+            event.new_root,
+        )
+    }
+
+    /// Sibling hashes from the leaf at `leaf_index` up to the current root,
+    /// bottom first
+    pub fn auth_path(&self, leaf_index: u64) -> Option<Vec<Hash>> {
+        let index = usize::try_from(leaf_index).ok()?;
+        if index >= self.commitments.len() {
+            return None;
+        }
+        Some(hash::auth_path(
+            self.tree_hash.backend(),
+            &self.commitments,
+            &self.slots,
+            index,
+        ))
+    }
+
+    fn apply(
+        &mut self,
+        leaf_index: u64,
+        commitment: Hash,
+        slot: u64,
+        new_root: Hash,
+    ) -> Result<(), MirrorError> {
+        let expected = self.size();
+        if leaf_index != expected {
+            return Err(MirrorError::OutOfOrder {
+                expected,
+                leaf_index,
+            });
+        }
+
+        let (frontier, root) = self.appended(&commitment, slot)?;
+        if root != new_root {
+            return Err(MirrorError::RootMismatch { leaf_index });
+        }
+        self.commit(commitment, slot, frontier, root);
+        Ok(())
+    }
+
+    /// Frontier and root once `commitment` is appended, leaving the mirror
+    /// untouched
+    fn appended(&self, commitment: &Hash, slot: u64) -> Result<(Frontier, Hash), MirrorError> {
+        if self.size() >= MAX_TREE_LEAVES {
+            return Err(MirrorError::TreeFull);
+        }
+        if !self.tree_hash.accepts(commitment) {
+            return Err(MirrorError::CommitmentNotInField);
+        }
+
+        let backend = self.tree_hash.backend();
+        let node = hash::leaf_node(backend, commitment, slot);
+        let mut frontier = self.frontier;
+        let root = hash::append_leaf(backend, &mut frontier, self.size(), node);
+        Ok((frontier, root))
+    }
+
+    fn commit(&mut self, commitment: Hash, slot: u64, frontier: Frontier, root: Hash) {
+        self.frontier = frontier;
+        self.commitments.push(commitment);
+        self.slots.push(slot);
+        self.root = root;
+    }
+}
+
+/// Borsh reader over an event body
+struct Body<'a>(&'a [u8]);
+
+impl Body<'_> {
+    fn take(&mut self, len: usize) -> Option<&[u8]> {
+        if self.0.len() < len {
+            return None;
+        }
+        let (head, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Some(head)
+    }
+
+    fn word(&mut self) -> Option<Hash> {
+        self.take(32)?.try_into().ok()
+    }
+
+    fn u64(&mut self) -> Option<u64> {
+        Some(u64::from_le_bytes(self.take(8)?.try_into().ok()?))
+    }
+
+    fn bytes(&mut self) -> Option<&[u8]> {
+        let len = u32::from_le_bytes(self.take(4)?.try_into().ok()?) as usize;
+        self.take(len)
+    }
+}
@@ -0,0 +1,161 @@
+// ============================================================================
+// NOTES
+// ============================================================================
+// A note is the secret side of a deposit: its value and two random field
+// elements. Only hashes of them ever reach the chain, computed with the
+// vault's `TreeHash` as the program's `TreeHash::commitment` does:
+//
+//                   Keccak                          Poseidon (as the circuits)
+//   precommitment   keccak(secret || nullifier)     hash_2([secret, nullifier])
+//   commitment      keccak(amount_le || precomm.)   hash_2([amount, precomm.])
+//   nullifier_hash  keccak(nullifier)               hash_1([nullifier])
+//
+// The precommitment is the deposit argument, the commitment the tree leaf
+// the program derives from it, and the nullifier hash a spend public input.
+// A note deposited with an allowance policy also hashes the policy's
+// `policy_hash` into its commitment (appended to the keccak preimage, or a
+// third Poseidon input), and only withdraws through that policy.
+//
+// A partial spend leaves a change note holding `remaining_amount` under fresh
+// secrets, bound to the same policy; its commitment is the spend's
+// `new_commitment`.
+// ============================================================================
+
+use rand_core::{OsRng, RngCore};
+use sha3::{Digest, Keccak256};
+
+use crate::hash::{slot_word, Hash, HashBackend, Poseidon};
+use crate::mirror::TreeHash;
+
+/// Policy hash of a note bound to no allowance policy
+pub const UNBOUND_POLICY_HASH: Hash = [0u8; 32];
+
+/// Secrets and value of one deposit. Losing them loses the funds.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Note {
+    pub amount: u64,
+    pub secret: Hash,
+    pub nullifier_secret: Hash,
+    /// Allowance policy the note is bound to (`policy_hash`), or
+    /// `UNBOUND_POLICY_HASH`
+    pub policy_hash: Hash,
+}
+
+impl Note {
+    /// Fresh note of `amount` with random secrets
+    pub fn generate(amount: u64) -> Self {
+        Self::bound(amount, UNBOUND_POLICY_HASH)
+    }
+
+    /// Fresh note of `amount` bound to the allowance policy hashing to
+    /// `policy_hash`; deposit it with that policy's account
+    pub fn bound(amount: u64, policy_hash: Hash) -> Self {
+        Self {
+            amount,
+            secret: random_field(),
+            nullifier_secret: random_field(),
+            policy_hash,
+        }
+    }
+
+    /// Deposit argument committing to the secrets
+    pub fn precommitment(&self, tree_hash: TreeHash) -> Hash {
+        precommitment(tree_hash, &self.secret, &self.nullifier_secret)
+    }
+
+    /// Leaf the program inserts for this note
+    pub fn commitment(&self, tree_hash: TreeHash) -> Hash {
+        commitment(
+            tree_hash,
+            self.amount,
+            &self.precommitment(tree_hash),
+            &self.policy_hash,
+        )
+    }
+
+    /// Nullifier revealed when the note is spent
+    pub fn nullifier_hash(&self, tree_hash: TreeHash) -> Hash {
+        nullifier_hash(tree_hash, &self.nullifier_secret)
+    }
+
+    /// Change note left after spending `amount`, or `None` for a full spend.
+    /// Its commitment is the spend's `new_commitment`.
+    pub fn change(&self, amount: u64) -> Option<Self> {
+        match self.amount.checked_sub(amount)? {
+            0 => None,
+            remaining => Some(Self::bound(remaining, self.policy_hash)),
+        }
+    }
+}
+
+/// `keccak(secret || nullifier_secret)`, or `Poseidon(secret,
+/// nullifier_secret)` in Poseidon vaults
+pub fn precommitment(tree_hash: TreeHash, secret: &Hash, nullifier_secret: &Hash) -> Hash {
+    match tree_hash {
+        TreeHash::Keccak => keccak(&[secret, nullifier_secret]),
+        TreeHash::Poseidon => Poseidon.hash_pair(secret, nullifier_secret),
+    }
+}
+
+/// `keccak(amount_le || precommitment)`, as `poseidon_hash_commitment`, or
+/// `Poseidon(amount, precommitment)` with the amount as a 32-byte big-endian
+/// word in Poseidon vaults. A bound note's `policy_hash` is appended to
+/// either, as the program's `TreeHash::bound_commitment` does.
+pub fn commitment(
+    tree_hash: TreeHash,
+    amount: u64,
+    precommitment: &Hash,
+    policy_hash: &Hash,
+) -> Hash {
+    let amount_le = amount.to_le_bytes();
+    let amount_word = slot_word(amount);
+    match (tree_hash, *policy_hash == UNBOUND_POLICY_HASH) {
+        (TreeHash::Keccak, true) => keccak(&[&amount_le, precommitment]),
+        (TreeHash::Keccak, false) => keccak(&[&amount_le, precommitment, policy_hash]),
+        (TreeHash::Poseidon, true) => Poseidon.hash_pair(&amount_word, precommitment),
+        (TreeHash::Poseidon, false) => {
+            Poseidon.hash_three([&amount_word, precommitment, policy_hash])
+        }
+    }
+}
+
+/// Hash of the allowance policy `policy_id` of `vault`, as the program's
+/// `AllowancePolicy::policy_hash`: keccak(vault || policy_id_le), top byte
+/// cleared
+pub fn policy_hash(vault: &[u8; 32], policy_id: u64) -> Hash {
+    let mut hash = keccak(&[vault, &policy_id.to_le_bytes()]);
+    hash[0] = 0;
+    hash
+}
+
+/// `keccak(nullifier_secret)`, or the circuits' `hash_1([nullifier_secret])`
+/// in Poseidon vaults
+pub fn nullifier_hash(tree_hash: TreeHash, nullifier_secret: &Hash) -> Hash {
+    match tree_hash {
+        TreeHash::Keccak => keccak(&[nullifier_secret]),
+        TreeHash::Poseidon => Poseidon.hash_one(nullifier_secret),
+    }
+}
+
+/// Commitment of the change note, zero for a full spend
+pub fn new_commitment(tree_hash: TreeHash, change: Option<&Note>) -> Hash {
+    change
+        .map(|change| change.commitment(tree_hash))
+        .unwrap_or_default()
+}
+
+pub(crate) fn keccak(parts: &[&[u8]]) -> Hash {
+    let mut hasher = Keccak256::new();
+    for part in parts {
+        hasher.update(part);
+    }
+    hasher.finalize().into()
+}
+
+/// Random 32 bytes below the BN254 modulus (top byte cleared)
+fn random_field() -> Hash {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    bytes[0] = 0;
+    bytes
+}
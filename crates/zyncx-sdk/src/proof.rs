@@ -0,0 +1,177 @@
+// ============================================================================
+// SPEND PROOF PUBLIC INPUTS
+// ============================================================================
+// The program rebuilds each proof's public inputs from the instruction and
+// its accounts, as 32-byte big-endian field elements in this order:
+//
+//   withdraw_*   root, nullifier_hash, recipient, amount, new_commitment,
+//                ext_data_hash, remaining_amount, note_age_cutoff,
+//                policy_hash
+//   swap_*       root, nullifier_hash, recipient, amount_in, new_commitment,
+//                ext_data_hash (the referrer's), remaining_amount,
+//                note_age_cutoff, 0 (policy_hash)
+//   verify_proof root, nullifier_hash, recipient, amount, new_commitment,
+//                ext_data_hash, remaining_amount, note_age_cutoff,
+//                0 (policy_hash)
+//
+// note_age_cutoff is u64::MAX when the vault sets no minimum note age.
+// policy_hash is the note's `Note::policy_hash`: zero unless the note is
+// bound to an allowance policy, which only withdrawals passing that policy
+// can spend. A proof generated over any other order or encoding fails
+// verification.
+// ============================================================================
+
+use crate::hash::Hash;
+use crate::note::{keccak, UNBOUND_POLICY_HASH};
+
+/// Big-endian field element of a u64 public input
+pub fn u64_public_input(value: u64) -> Hash {
+    let mut bytes = [0u8; 32];
+    bytes[24..].copy_from_slice(&value.to_be_bytes());
+    bytes
+}
+
+/// Instruction data of the UltraHonk verifier program: the proof followed by
+/// the public inputs
+pub fn verifier_input(proof: &[u8], public_inputs: &[Hash]) -> Vec<u8> {
+    let mut data = Vec::with_capacity(proof.len() + 32 * public_inputs.len());
+    data.extend_from_slice(proof);
+    for input in public_inputs {
+        data.extend_from_slice(input);
+    }
+    data
+}
+
+/// Withdrawal terms hashed into `ext_data_hash` (`WithdrawExtData` without
+/// the hash itself)
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ExtData {
+    pub max_fee: u64,
+    pub relayer_tip: u64,
+    pub viewing_ciphertext: Vec<u8>,
+    pub ephemeral_pubkey: [u8; 32],
+    pub fee_schedule_hash: Hash,
+}
+
+impl ExtData {
+    /// `WithdrawExtData::hash` for a withdrawal to `recipient` relayed by
+    /// `relayer` (the transaction payer); the top byte is cleared so the
+    /// hash is a field element
+    pub fn hash(&self, recipient: &[u8; 32], relayer: &[u8; 32]) -> Hash {
+        let ciphertext_hash = keccak(&[&self.viewing_ciphertext]);
+        let mut hash = keccak(&[
+            recipient,
+            relayer,
+            &self.max_fee.to_le_bytes(),
+            &self.relayer_tip.to_le_bytes(),
+            &ciphertext_hash,
+            &self.ephemeral_pubkey,
+            &self.fee_schedule_hash,
+        ]);
+        hash[0] = 0;
+        hash
+    }
+}
+
+/// Public inputs of `withdraw_native` / `withdraw_token`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WithdrawPublicInputs {
+    pub root: Hash,
+    pub nullifier_hash: Hash,
+    pub recipient: [u8; 32],
+    pub amount: u64,
+    pub new_commitment: Hash,
+    pub ext_data_hash: Hash,
+    pub remaining_amount: u64,
+    pub note_age_cutoff: u64,
+    /// Pass the note's policy account with the withdrawal when non-zero
+    pub policy_hash: Hash,
+}
+
+impl WithdrawPublicInputs {
+    pub fn to_fields(&self) -> Vec<Hash> {
+        vec![
+            self.root,
+            self.nullifier_hash,
+            self.recipient,
+            u64_public_input(self.amount),
+            self.new_commitment,
+            self.ext_data_hash,
+            u64_public_input(self.remaining_amount),
+            u64_public_input(self.note_age_cutoff),
+            self.policy_hash,
+        ]
+    }
+}
+
+/// Public inputs of `swap_native` / `swap_token`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SwapPublicInputs {
+    pub root: Hash,
+    pub nullifier_hash: Hash,
+    pub recipient: [u8; 32],
+    pub amount_in: u64,
+    pub new_commitment: Hash,
+    pub remaining_amount: u64,
+    pub note_age_cutoff: u64,
+    /// `SwapParam::referrer`; all zero for none
+    pub referrer: [u8; 32],
+}
+
+impl SwapPublicInputs {
+    /// `SwapParam::ext_data_hash`: keccak(referrer) with the top byte
+    /// cleared, zero without a referrer
+    pub fn ext_data_hash(&self) -> Hash {
+        if self.referrer == [0u8; 32] {
+            return [0u8; 32];
+        }
+        let mut hash = keccak(&[&self.referrer]);
+        hash[0] = 0;
+        hash
+    }
+
+    pub fn to_fields(&self) -> Vec<Hash> {
+        vec![
+            self.root,
+            self.nullifier_hash,
+            self.recipient,
+            u64_public_input(self.amount_in),
+            self.new_commitment,
+            // Swaps carry no relayer terms, only the referrer
+            self.ext_data_hash(),
+            u64_public_input(self.remaining_amount),
+            u64_public_input(self.note_age_cutoff),
+            // Only unbound notes can be swapped
+            UNBOUND_POLICY_HASH,
+        ]
+    }
+}
+
+/// Public inputs of `verify_proof`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VerifyPublicInputs {
+    pub root: Hash,
+    pub nullifier_hash: Hash,
+    pub recipient: [u8; 32],
+    pub amount: u64,
+    pub new_commitment: Hash,
+    pub ext_data_hash: Hash,
+    pub remaining_amount: u64,
+    pub note_age_cutoff: u64,
+}
+
+impl VerifyPublicInputs {
+    pub fn to_fields(&self) -> Vec<Hash> {
+        vec![
+            self.root,
+            self.nullifier_hash,
+            self.recipient,
+            u64_public_input(self.amount),
+            self.new_commitment,
+            self.ext_data_hash,
+            u64_public_input(self.remaining_amount),
+            u64_public_input(self.note_age_cutoff),
+            UNBOUND_POLICY_HASH,
+        ]
+    }
+}
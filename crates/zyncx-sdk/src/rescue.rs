@@ -0,0 +1,255 @@
+// ============================================================================
+// RESCUE CIPHER
+// ============================================================================
+// The cipher the MXE uses for `Enc<Shared, _>` inputs, ported from Arcium's
+// client (`RescueCipher` in `@arcium-hq/client`, `arcis-compiler` in Rust).
+// Everything lives in the curve25519 base field p = 2^255 - 19:
+//
+//   key        RescuePrimeHash(1 || shared_secret || 5), truncated to five
+//              elements (NIST SP 800-56C one-step KDF, one repetition)
+//   encrypt    CTR mode over blocks of five: block i is masked, by field
+//              addition, with Rescue_key(nonce, i, 0, 0, 0)
+//   encoding   shared secret, nonce and ciphertexts are little-endian field
+//              elements; a ciphertext is always 32 bytes
+//
+// Round constants are drawn from SHAKE256 exactly as the reference does, so
+// ciphertexts are bit-identical to the TS client's for the same secret and
+// nonce.
+// ============================================================================
+
+use ark_ff::{BigInteger, Field, PrimeField, Zero};
+use sha3::digest::{ExtendableOutput, Update, XofReader};
+use sha3::Shake256;
+
+use crate::arcium::{MxeCipher, CIPHERTEXT_LEN};
+
+use self::field::Fq;
+
+mod field {
+    // ark-ff 0.4's derive expands its impls inside an anonymous const
+    #![allow(non_local_definitions)]
+
+    use ark_ff::fields::{Fp256, MontBackend, MontConfig};
+
+    #[derive(MontConfig)]
+    #[modulus = "57896044618658097711785492504343953926634992332820282019728792003956564819949"]
+    #[generator = "2"]
+    pub struct FieldConfig;
+
+    /// Curve25519 base field
+    pub type Fq = Fp256<MontBackend<FieldConfig, 4>>;
+}
+
+/// Number of key elements, and the cipher's block width
+const RESCUE_KEY_COUNT: usize = 5;
+
+/// S-box exponent: the smallest prime not dividing p - 1
+const ALPHA: u64 = 5;
+
+/// ALPHA^-1 mod p - 1, little-endian limbs
+const ALPHA_INVERSE: [u64; 4] = [
+    0xcccc_cccc_cccc_ccc1,
+    0xcccc_cccc_cccc_cccc,
+    0xcccc_cccc_cccc_cccc,
+    0x4ccc_cccc_cccc_cccc,
+];
+
+/// Rounds of the block cipher at width 5 and 128-bit security
+const CIPHER_ROUNDS: usize = 10;
+
+/// Width, capacity and rounds of the Rescue-Prime hash used for the key
+const HASH_WIDTH: usize = 12;
+const HASH_CAPACITY: usize = 5;
+const HASH_RATE: usize = HASH_WIDTH - HASH_CAPACITY;
+const HASH_ROUNDS: usize = 8;
+
+const CIPHER_SEED: &[u8] = b"encrypt everything, compute anything";
+const HASH_SEED: &str = "Rescue-XLIX(57896044618658097711785492504343953926634992332820282019728792003956564819949,12,5,256)";
+
+/// Bytes squeezed per constant: the field size plus 16 so that the reduction
+/// is statistically close to uniform
+const SAMPLE_LEN: usize = 32 + 16;
+
+/// Arcium's Rescue cipher, keyed by the x25519 secret shared with the MXE
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RescueCipher;
+
+impl RescueCipher {
+    /// First `len` words of the key stream under `nonce`
+    fn keystream(shared_secret: &[u8; 32], nonce: u128, len: usize) -> Vec<Fq> {
+        let permutation = Permutation::cipher(&derive_key(shared_secret));
+        let nonce = Fq::from(nonce);
+
+        (0..len.div_ceil(RESCUE_KEY_COUNT))
+            .flat_map(|block| {
+                let mut counter = vec![Fq::zero(); RESCUE_KEY_COUNT];
+                counter[0] = nonce;
+                counter[1] = Fq::from(block as u64);
+                permutation.permute(counter)
+            })
+            .take(len)
+            .collect()
+    }
+}
+
+impl MxeCipher for RescueCipher {
+    fn encrypt(&self, shared_secret: &[u8; 32], nonce: u128, plaintext: &[u64]) -> Vec<[u8; 32]> {
+        plaintext
+            .iter()
+            .zip(Self::keystream(shared_secret, nonce, plaintext.len()))
+            .map(|(word, mask)| to_bytes(Fq::from(*word) + mask))
+            .collect()
+    }
+
+    /// Words that did not decrypt to a u64 (wrong key or nonce) come back
+    /// truncated to their low 64 bits
+    fn decrypt(&self, shared_secret: &[u8; 32], nonce: u128, ciphertext: &[[u8; 32]]) -> Vec<u64> {
+        ciphertext
+            .iter()
+            .zip(Self::keystream(shared_secret, nonce, ciphertext.len()))
+            .map(|(block, mask)| {
+                let word = to_bytes(Fq::from_le_bytes_mod_order(block) - mask);
+                u64::from_le_bytes(word[..8].try_into().unwrap())
+            })
+            .collect()
+    }
+}
+
+fn to_bytes(element: Fq) -> [u8; CIPHERTEXT_LEN] {
+    element.into_bigint().to_bytes_le().try_into().unwrap()
+}
+
+/// Cipher key for a shared secret
+fn derive_key(shared_secret: &[u8; 32]) -> [Fq; RESCUE_KEY_COUNT] {
+    let hash = Permutation::hash();
+    let mut message = vec![
+        Fq::from(1u64),
+        Fq::from_le_bytes_mod_order(shared_secret),
+        Fq::from(RESCUE_KEY_COUNT as u64),
+        // Padding: a one, then zeros up to a multiple of the rate
+        Fq::from(1u64),
+    ];
+    message.resize(message.len().div_ceil(HASH_RATE) * HASH_RATE, Fq::zero());
+
+    let mut state = vec![Fq::zero(); HASH_WIDTH];
+    for chunk in message.chunks(HASH_RATE) {
+        for (word, input) in state.iter_mut().zip(chunk) {
+            *word += input;
+        }
+        state = hash.permute(state);
+    }
+    state[..RESCUE_KEY_COUNT].try_into().unwrap()
+}
+
+/// Field elements squeezed from SHAKE256 over `seed`
+fn sample(seed: &[u8]) -> impl Iterator<Item = Fq> {
+    let mut hasher = Shake256::default();
+    hasher.update(seed);
+    let mut reader = hasher.finalize_xof();
+    std::iter::repeat_with(move || {
+        let mut bytes = [0u8; SAMPLE_LEN];
+        reader.read(&mut bytes);
+        Fq::from_le_bytes_mod_order(&bytes)
+    })
+}
+
+/// Cauchy MDS matrix `1 / (i + j)` for 1-based `i`, `j`
+fn mds(width: usize) -> Vec<Vec<Fq>> {
+    (1..=width)
+        .map(|i| {
+            (1..=width)
+                .map(|j| Fq::from((i + j) as u64).inverse().unwrap())
+                .collect()
+        })
+        .collect()
+}
+
+fn mat_mul(matrix: &[Vec<Fq>], vector: &[Fq]) -> Vec<Fq> {
+    matrix
+        .iter()
+        .map(|row| row.iter().zip(vector).map(|(a, b)| *a * b).sum())
+        .collect()
+}
+
+fn add(a: Vec<Fq>, b: &[Fq]) -> Vec<Fq> {
+    a.into_iter().zip(b).map(|(a, b)| a + b).collect()
+}
+
+/// Rescue permutation with its round keys
+struct Permutation {
+    mds: Vec<Vec<Fq>>,
+    round_keys: Vec<Vec<Fq>>,
+    /// The block cipher applies `x^(1/alpha)` in even rounds, the hash in odd
+    /// ones
+    inverse_first: bool,
+}
+
+impl Permutation {
+    /// Block cipher keyed with `key`. The round keys are the intermediate
+    /// states of the permutation applied to the key, with the SHAKE-derived
+    /// affine recurrence as round constants.
+    fn cipher(key: &[Fq; RESCUE_KEY_COUNT]) -> Self {
+        let m = RESCUE_KEY_COUNT;
+        let mut samples = sample(CIPHER_SEED);
+        // The reference resamples a singular matrix; the one this seed
+        // yields is invertible
+        let matrix: Vec<Vec<Fq>> = (0..m).map(|_| samples.by_ref().take(m).collect()).collect();
+        let initial: Vec<Fq> = samples.by_ref().take(m).collect();
+        let affine: Vec<Fq> = samples.take(m).collect();
+
+        let mut constants = vec![initial];
+        for _ in 0..2 * CIPHER_ROUNDS {
+            let last = constants.last().unwrap();
+            constants.push(add(mat_mul(&matrix, last), &affine));
+        }
+
+        let schedule = Self {
+            mds: mds(m),
+            round_keys: constants,
+            inverse_first: true,
+        };
+        let round_keys = schedule.states(key.to_vec());
+        Self {
+            round_keys,
+            ..schedule
+        }
+    }
+
+    /// Rescue-Prime at width 12; a zero key first makes it Algorithm 3 of
+    /// https://eprint.iacr.org/2020/1143.pdf
+    fn hash() -> Self {
+        let mut round_keys = vec![vec![Fq::zero(); HASH_WIDTH]];
+        let samples: Vec<Fq> = sample(HASH_SEED.as_bytes())
+            .take(2 * HASH_WIDTH * HASH_ROUNDS)
+            .collect();
+        round_keys.extend(samples.chunks(HASH_WIDTH).map(<[Fq]>::to_vec));
+
+        Self {
+            mds: mds(HASH_WIDTH),
+            round_keys,
+            inverse_first: false,
+        }
+    }
+
+    /// State after the initial key and after every round
+    fn states(&self, state: Vec<Fq>) -> Vec<Vec<Fq>> {
+        let mut states = vec![add(state, &self.round_keys[0])];
+        for (round, key) in self.round_keys[1..].iter().enumerate() {
+            let inverse = (round % 2 == 0) == self.inverse_first;
+            let sbox = states.last().unwrap().iter().map(|x| {
+                if inverse {
+                    x.pow(ALPHA_INVERSE)
+                } else {
+                    x.pow([ALPHA])
+                }
+            });
+            let sbox: Vec<Fq> = sbox.collect();
+            states.push(add(mat_mul(&self.mds, &sbox), key));
+        }
+        states
+    }
+
+    fn permute(&self, state: Vec<Fq>) -> Vec<Fq> {
+        self.states(state).pop().unwrap()
+    }
+}
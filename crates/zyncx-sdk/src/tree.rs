@@ -0,0 +1,206 @@
+// ============================================================================
+// MERKLE PATHS FROM EVENT LOGS
+// ============================================================================
+// The program keeps only each tree's frontier; the leaves live in the
+// `LeafInserted` events it emits (as `Program data: <base64>` log lines).
+// Replaying one tree's events in `leaf_index` order rebuilds the tree, and
+// with it the root and authentication path a spend proof is generated
+// against. Events may be fed in any order and more than once.
+// ============================================================================
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use base64::Engine as _;
+use sha2::{Digest, Sha256};
+
+use crate::hash::{self, Hash, HashBackend};
+
+/// Log prefix of Anchor events
+const EVENT_LOG_PREFIX: &str = "Program data: ";
+
+/// Body length of `LeafInserted` (vault, tree_epoch, leaf_index,
+/// commitment, slot, new_root)
+const LEAF_INSERTED_LEN: usize = 32 + 8 + 8 + 32 + 8 + 32;
+
+/// A leaf appended to a vault's tree
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LeafInserted {
+    pub vault: [u8; 32],
+    pub tree_epoch: u64,
+    pub leaf_index: u64,
+    pub commitment: Hash,
+    pub slot: u64,
+    pub new_root: Hash,
+}
+
+impl LeafInserted {
+    /// Decode an event payload (discriminator + Borsh body); `None` for any
+    /// other event
+    pub fn from_event_data(data: &[u8]) -> Option<Self> {
+        let discriminator = Sha256::digest(b"event:LeafInserted");
+        let body = data.strip_prefix(&discriminator[..8])?;
+        if body.len() < LEAF_INSERTED_LEN {
+            return None;
+        }
+
+        let word = |at: usize| -> Hash { body[at..at + 32].try_into().unwrap() };
+        let u64_at = |at: usize| u64::from_le_bytes(body[at..at + 8].try_into().unwrap());
+        Some(Self {
+            vault: word(0),
+            tree_epoch: u64_at(32),
+            leaf_index: u64_at(40),
+            commitment: word(48),
+            slot: u64_at(80),
+            new_root: word(88),
+        })
+    }
+
+    /// Decode a transaction log line; `None` for lines that are not a
+    /// `LeafInserted` event
+    pub fn from_log(line: &str) -> Option<Self> {
+        let data = line.strip_prefix(EVENT_LOG_PREFIX)?;
+        let data = base64::engine::general_purpose::STANDARD
+            .decode(data)
+            .ok()?;
+        Self::from_event_data(&data)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TreeLogError {
+    /// No event seen yet for this leaf index
+    MissingLeaf(u64),
+    /// Two events disagree on this leaf
+    ConflictingLeaf(u64),
+}
+
+impl fmt::Display for TreeLogError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingLeaf(index) => write!(f, "no LeafInserted event for leaf {index}"),
+            Self::ConflictingLeaf(index) => write!(f, "conflicting events for leaf {index}"),
+        }
+    }
+}
+
+impl std::error::Error for TreeLogError {}
+
+/// Authentication path of one leaf, in the shape the spend circuits take
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MerklePath {
+    pub leaf_index: u64,
+    /// Tree node of the leaf (commitment hashed with its slot)
+    pub leaf: Hash,
+    /// Sibling hashes from the leaf up, bottom first
+    pub siblings: Vec<Hash>,
+    /// Whether the node at each level is the right child (`path_indices`)
+    pub is_right: Vec<bool>,
+    /// Root the path leads to
+    pub root: Hash,
+}
+
+/// Leaves of one tree (a vault's tree epoch) collected from its events
+#[derive(Clone, Debug)]
+pub struct TreeLog {
+    vault: [u8; 32],
+    tree_epoch: u64,
+    leaves: BTreeMap<u64, LeafInserted>,
+}
+
+impl TreeLog {
+    pub fn new(vault: [u8; 32], tree_epoch: u64) -> Self {
+        Self {
+            vault,
+            tree_epoch,
+            leaves: BTreeMap::new(),
+        }
+    }
+
+    /// Collect the tree's leaves from transaction log lines
+    pub fn from_logs<'a>(
+        vault: [u8; 32],
+        tree_epoch: u64,
+        logs: impl IntoIterator<Item = &'a str>,
+    ) -> Result<Self, TreeLogError> {
+        let mut log = Self::new(vault, tree_epoch);
+        for event in logs.into_iter().filter_map(LeafInserted::from_log) {
+            log.insert(event)?;
+        }
+        Ok(log)
+    }
+
+    /// Record a leaf. Returns whether it belongs to this tree; replays of a
+    /// known leaf are ignored.
+    pub fn insert(&mut self, event: LeafInserted) -> Result<bool, TreeLogError> {
+        if event.vault != self.vault || event.tree_epoch != self.tree_epoch {
+            return Ok(false);
+        }
+        match self.leaves.get(&event.leaf_index) {
+            Some(known) if *known != event => Err(TreeLogError::ConflictingLeaf(event.leaf_index)),
+            Some(_) => Ok(true),
+            None => {
+                self.leaves.insert(event.leaf_index, event);
+                Ok(true)
+            }
+        }
+    }
+
+    /// Leaf index of `commitment`, if it was inserted into this tree
+    pub fn find(&self, commitment: &Hash) -> Option<u64> {
+        self.leaves
+            .values()
+            .find(|leaf| leaf.commitment == *commitment)
+            .map(|leaf| leaf.leaf_index)
+    }
+
+    /// Root the program reported after the latest leaf seen
+    pub fn latest_root(&self) -> Option<Hash> {
+        self.leaves.values().next_back().map(|leaf| leaf.new_root)
+    }
+
+    /// Root rebuilt from the leaves with `backend` (the tree's `TreeHash`)
+    pub fn root<B: HashBackend + ?Sized>(&self, backend: &B) -> Result<Hash, TreeLogError> {
+        let (commitments, slots) = self.columns()?;
+        Ok(hash::merkle_root(backend, &commitments, &slots))
+    }
+
+    /// Path of the leaf at `leaf_index` to the root over all leaves seen
+    pub fn path<B: HashBackend + ?Sized>(
+        &self,
+        backend: &B,
+        leaf_index: u64,
+    ) -> Result<MerklePath, TreeLogError> {
+        let (commitments, slots) = self.columns()?;
+        let index = leaf_index as usize;
+        if index >= commitments.len() {
+            return Err(TreeLogError::MissingLeaf(leaf_index));
+        }
+
+        let siblings = hash::auth_path(backend, &commitments, &slots, index);
+        Ok(MerklePath {
+            leaf_index,
+            leaf: hash::leaf_node(backend, &commitments[index], slots[index]),
+            is_right: (0..siblings.len())
+                .map(|level| (leaf_index >> level) & 1 == 1)
+                .collect(),
+            siblings,
+            root: hash::merkle_root(backend, &commitments, &slots),
+        })
+    }
+
+    /// Commitments and slots in leaf order; every index below the highest
+    /// seen must be present
+    fn columns(&self) -> Result<(Vec<Hash>, Vec<u64>), TreeLogError> {
+        let mut commitments = Vec::with_capacity(self.leaves.len());
+        let mut slots = Vec::with_capacity(self.leaves.len());
+        for (expected, (index, leaf)) in self.leaves.iter().enumerate() {
+            if *index != expected as u64 {
+                return Err(TreeLogError::MissingLeaf(expected as u64));
+            }
+            commitments.push(leaf.commitment);
+            slots.push(leaf.slot);
+        }
+        Ok((commitments, slots))
+    }
+}
@@ -0,0 +1,76 @@
+//! WASM bindings for the web app.
+//!
+//! The browser encrypts with Arcium's JS `RescueCipher`; these bindings cover
+//! key agreement, nonces and the exact instruction layout around it.
+
+use wasm_bindgen::prelude::*;
+
+use crate::arcium::{self, ClientKeypair, DepositInput, EncryptedSwapBounds, SwapQuote};
+
+fn to_array(bytes: &[u8], what: &str) -> Result<[u8; 32], JsError> {
+    bytes
+        .try_into()
+        .map_err(|_| JsError::new(&format!("{what} must be 32 bytes")))
+}
+
+/// New x25519 keypair as `secret (32) || public (32)`
+#[wasm_bindgen(js_name = generateKeypair)]
+pub fn generate_keypair() -> Vec<u8> {
+    let keypair = ClientKeypair::generate();
+    let mut out = keypair.secret_bytes().to_vec();
+    out.extend_from_slice(&keypair.public_key());
+    out
+}
+
+#[wasm_bindgen(js_name = sharedSecret)]
+pub fn shared_secret(secret: &[u8], mxe_pubkey: &[u8]) -> Result<Vec<u8>, JsError> {
+    let keypair = ClientKeypair::from_secret_bytes(to_array(secret, "secret")?);
+    Ok(keypair
+        .shared_secret(&to_array(mxe_pubkey, "mxe_pubkey")?)
+        .to_vec())
+}
+
+/// Random 16-byte little-endian nonce
+#[wasm_bindgen(js_name = randomNonce)]
+pub fn random_nonce() -> Vec<u8> {
+    arcium::random_nonce().to_le_bytes().to_vec()
+}
+
+#[wasm_bindgen(js_name = confidentialSwapInstructionData)]
+pub fn confidential_swap_instruction_data(
+    computation_offset: u64,
+    encrypted_params: &[u8],
+    encryption_pubkey: &[u8],
+    nonce: &[u8],
+    current_output: u64,
+    liquidity_depth: u64,
+    keeper_fee: u64,
+) -> Result<Vec<u8>, JsError> {
+    let nonce: [u8; 16] = nonce
+        .try_into()
+        .map_err(|_| JsError::new("nonce must be 16 bytes"))?;
+
+    if encrypted_params.len() != 96 {
+        return Err(JsError::new("encrypted_params must be 96 bytes"));
+    }
+
+    let bounds = EncryptedSwapBounds {
+        encryption_pubkey: to_array(encryption_pubkey, "encryption_pubkey")?,
+        nonce: u128::from_le_bytes(nonce),
+        encrypted_params: [
+            to_array(&encrypted_params[..32], "min_out ciphertext")?,
+            to_array(&encrypted_params[32..64], "referrer ciphertext")?,
+            to_array(&encrypted_params[64..], "max_impact_bps ciphertext")?,
+        ],
+    };
+    let quote = SwapQuote {
+        current_output,
+        liquidity_depth,
+    };
+    Ok(bounds.instruction_data(computation_offset, quote, keeper_fee))
+}
+
+#[wasm_bindgen(js_name = encryptedDepositInstructionData)]
+pub fn encrypted_deposit_instruction_data(computation_offset: u64, amount: u64) -> Vec<u8> {
+    DepositInput { amount }.instruction_data(computation_offset)
+}
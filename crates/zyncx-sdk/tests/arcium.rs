@@ -0,0 +1,304 @@
+use zyncx_sdk::*;
+
+/// SOL and USDC encrypted vaults
+const VAULTS: SwapVaults = SwapVaults {
+    source: [8u8; 32],
+    source_nonce: 3,
+    dest: [9u8; 32],
+    dest_nonce: 4,
+};
+
+/// Mocked MXE that consumes arguments in ArgBuilder order like the circuits
+struct MockMxe {
+    keypair: ClientKeypair,
+}
+
+impl MockMxe {
+    fn new() -> Self {
+        Self {
+            keypair: ClientKeypair::from_secret_bytes([7u8; 32]),
+        }
+    }
+
+    fn pubkey(&self) -> [u8; 32] {
+        self.keypair.public_key()
+    }
+
+    /// `confidential_swap(params: Enc<Shared, SwapParams>, current_output: u64,
+    /// liquidity_depth: u64, referral_fee: u64, referrals: Enc<Mxe, ReferralTotals>,
+    /// source_vault: Enc<Mxe, VaultState>, dest_vault: Enc<Mxe, VaultState>)`,
+    /// returning the decision, the max child order size and the fees credited
+    /// per referral slot
+    fn confidential_swap(&self, args: &[Argument]) -> (bool, u64, [u64; REFERRAL_SLOTS]) {
+        let [Argument::X25519Pubkey(client), Argument::PlaintextU128(nonce), Argument::EncryptedU64(min_out_ct), Argument::EncryptedU64(referrer_ct), Argument::EncryptedU64(impact_ct), Argument::PlaintextU64(current_output), Argument::PlaintextU64(liquidity_depth), Argument::PlaintextU64(referral_fee), Argument::PlaintextU128(_), Argument::Account { offset, length, .. }, Argument::PlaintextU128(_), Argument::Account {
+            pubkey: source,
+            offset: source_offset,
+            length: source_length,
+        }, Argument::PlaintextU128(_), Argument::Account {
+            pubkey: dest,
+            offset: dest_offset,
+            length: dest_length,
+        }] = args
+        else {
+            panic!("unexpected argument layout: {args:?}");
+        };
+
+        assert_eq!(*offset, REFERRAL_TOTALS_OFFSET);
+        assert_eq!(*length, REFERRAL_TOTALS_LEN);
+        assert_ne!(source, dest);
+        for (offset, length) in [(source_offset, source_length), (dest_offset, dest_length)] {
+            assert_eq!(*offset, VAULT_STATE_OFFSET);
+            assert_eq!(*length, VAULT_STATE_LEN);
+        }
+
+        let shared_secret = self.keypair.shared_secret(client);
+        let params = RescueCipher.decrypt(
+            &shared_secret,
+            *nonce,
+            &[*min_out_ct, *referrer_ct, *impact_ct],
+        );
+        let max_child = if params[2] == 0 {
+            u64::MAX
+        } else {
+            (*liquidity_depth / 10_000) * params[2]
+                + (*liquidity_depth % 10_000) * params[2] / 10_000
+        };
+        let execute = *current_output >= params[0] && max_child > 0;
+
+        let mut accrued = [0u64; REFERRAL_SLOTS];
+        for (slot, total) in accrued.iter_mut().enumerate() {
+            if execute && params[1] == referrer_code(slot as u8) {
+                *total += referral_fee;
+            }
+        }
+        (execute, max_child, accrued)
+    }
+
+    /// `process_deposit(deposit_amount: u64, vault_state: Enc<Mxe, VaultState>)`
+    fn process_deposit(&self, args: &[Argument]) -> u64 {
+        let [Argument::PlaintextU64(amount), Argument::PlaintextU128(_), Argument::Account { offset, length, .. }] =
+            args
+        else {
+            panic!("unexpected argument layout: {args:?}");
+        };
+
+        assert_eq!(*offset, VAULT_STATE_OFFSET);
+        assert_eq!(*length, VAULT_STATE_LEN);
+        *amount
+    }
+}
+
+#[test]
+fn shared_secret_is_symmetric() {
+    let mxe = MockMxe::new();
+    let client = ClientKeypair::generate();
+
+    assert_eq!(
+        client.shared_secret(&mxe.pubkey()),
+        mxe.keypair.shared_secret(&client.public_key())
+    );
+}
+
+#[test]
+fn swap_bounds_round_trip_through_mxe() {
+    let mxe = MockMxe::new();
+    let client = ClientKeypair::generate();
+    let nonce = random_nonce();
+
+    let bounds = SwapBounds {
+        min_out: 1_000,
+        referrer: NO_REFERRER,
+        max_impact_bps: 0,
+    };
+    let encrypted = bounds.encrypt(&RescueCipher, &client, &mxe.pubkey(), nonce);
+    assert_eq!(encrypted.encryption_pubkey, client.public_key());
+    assert_eq!(encrypted.nonce, nonce);
+
+    let shared_secret = mxe.keypair.shared_secret(&encrypted.encryption_pubkey);
+    assert_eq!(
+        RescueCipher.decrypt(&shared_secret, nonce, &encrypted.encrypted_params),
+        vec![1_000, NO_REFERRER, 0]
+    );
+
+    let swap = |current_output| {
+        let quote = SwapQuote {
+            current_output,
+            liquidity_depth: 1_000_000,
+        };
+        mxe.confidential_swap(&encrypted.arguments(quote, 10, [5u8; 32], 1, &VAULTS))
+    };
+    assert!(swap(1_000).0);
+    assert!(swap(1_500).0);
+    assert!(!swap(999).0);
+    assert_eq!(swap(1_500).1, u64::MAX);
+    assert_eq!(swap(1_500).2, [0; REFERRAL_SLOTS]);
+}
+
+#[test]
+fn referral_accrues_only_on_executed_swaps() {
+    let mxe = MockMxe::new();
+    let client = ClientKeypair::generate();
+
+    let bounds = SwapBounds {
+        min_out: 1_000,
+        referrer: referrer_code(2),
+        max_impact_bps: 0,
+    };
+    let encrypted = bounds.encrypt(&RescueCipher, &client, &mxe.pubkey(), random_nonce());
+    let epoch = [5u8; 32];
+    let quote = |current_output| SwapQuote {
+        current_output,
+        liquidity_depth: 1_000_000,
+    };
+
+    let (executed, _, accrued) =
+        mxe.confidential_swap(&encrypted.arguments(quote(1_200), 25, epoch, 1, &VAULTS));
+    assert!(executed);
+    assert_eq!(accrued, [0, 0, 25, 0]);
+
+    let (executed, _, accrued) =
+        mxe.confidential_swap(&encrypted.arguments(quote(900), 25, epoch, 1, &VAULTS));
+    assert!(!executed);
+    assert_eq!(accrued, [0; REFERRAL_SLOTS]);
+}
+
+#[test]
+fn child_order_size_keeps_impact_under_threshold() {
+    let mxe = MockMxe::new();
+    let client = ClientKeypair::generate();
+
+    let bounds = SwapBounds {
+        min_out: 1_000,
+        referrer: NO_REFERRER,
+        max_impact_bps: 50,
+    };
+    let encrypted = bounds.encrypt(&RescueCipher, &client, &mxe.pubkey(), random_nonce());
+    let swap = |liquidity_depth| {
+        let quote = SwapQuote {
+            current_output: 1_200,
+            liquidity_depth,
+        };
+        mxe.confidential_swap(&encrypted.arguments(quote, 0, [5u8; 32], 1, &VAULTS))
+    };
+
+    // 0.5% of a 2M-unit pool
+    let (executed, max_child, _) = swap(2_000_000);
+    assert!(executed);
+    assert_eq!(max_child, 10_000);
+    // x / (depth + x) stays under the threshold at the revealed size
+    assert!(max_child * 10_000 / (2_000_000 + max_child) <= 50);
+
+    // Too shallow to route even one unit within the threshold
+    let (executed, max_child, _) = swap(100);
+    assert!(!executed);
+    assert_eq!(max_child, 0);
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Ciphertexts from Arcium's reference `RescueCipher` (arcis-compiler 0.6.3,
+/// which `@arcium-hq/client` mirrors) for the same secret, nonce and words
+#[test]
+fn rescue_matches_reference_vectors() {
+    let ciphertext = RescueCipher.encrypt(&[7u8; 32], 1, &[1, 2, 3]);
+    assert_eq!(
+        ciphertext.iter().map(|c| hex(c)).collect::<Vec<_>>(),
+        [
+            "813c12bab9d084961d2def9d5de9daa33a2a6a8f278109f7dbafa745e824357a",
+            "17c615beafa8e1f279b853d90c09b5ac557f1b0c1a1039b5f541a954a0340a55",
+            "7053641a038b5abde6c2e285f13d5265d4f8310a451c23b3146c0c8a0f099860",
+        ]
+    );
+
+    // Six words span two counter blocks
+    let secret: [u8; 32] = std::array::from_fn(|i| i as u8);
+    let nonce = 0x0123456789abcdef0011223344556677;
+    let plaintext = [1_000_000, 0, 250, u64::MAX, 42, 7];
+    let ciphertext = RescueCipher.encrypt(&secret, nonce, &plaintext);
+    assert_eq!(
+        ciphertext.iter().map(|c| hex(c)).collect::<Vec<_>>(),
+        [
+            "a00b52026012fd85589f49386600c3c0dabf5db63b41140ddfdd87ec637a3d42",
+            "2c08182b685a756f7fb76ba706bcfc35f5766b3b615664b25ff13d5c43f8332c",
+            "5a3b6fc11cc0d096266880c0f338943072a50d42a9891235469843e650efd71f",
+            "02e5d02b9f927f99cc377ed71b11c9bb82d6ebfb566251da6505b1bf9fab794c",
+            "435fae57813cf40512fd453cf750a72ec52c2cd95a443b9a4a89110f4c40e204",
+            "4ea6ebe7d66a1c05299c0f4fbca86b87c5f33a4549b1ada877c5b9d7ecac132b",
+        ]
+    );
+    assert_eq!(RescueCipher.decrypt(&secret, nonce, &ciphertext), plaintext);
+    assert_ne!(
+        RescueCipher.decrypt(&[7u8; 32], nonce, &ciphertext),
+        plaintext
+    );
+}
+
+#[test]
+fn swap_ciphertext_depends_on_nonce() {
+    let mxe = MockMxe::new();
+    let client = ClientKeypair::generate();
+    let bounds = SwapBounds {
+        min_out: 42,
+        referrer: referrer_code(0),
+        max_impact_bps: 100,
+    };
+
+    let a = bounds.encrypt(&RescueCipher, &client, &mxe.pubkey(), 1);
+    let b = bounds.encrypt(&RescueCipher, &client, &mxe.pubkey(), 2);
+    assert_ne!(a.encrypted_params, b.encrypted_params);
+}
+
+#[test]
+fn swap_instruction_data_matches_program_layout() {
+    let bounds = EncryptedSwapBounds {
+        encryption_pubkey: [1u8; 32],
+        nonce: 0x0102,
+        encrypted_params: [[2u8; 32], [3u8; 32], [4u8; 32]],
+    };
+    let quote = SwapQuote {
+        current_output: 500,
+        liquidity_depth: 70_000,
+    };
+    let data = bounds.instruction_data(9, quote, 10);
+
+    let layout = instructions::instruction("queue_confidential_swap").unwrap();
+    let args_len: usize = layout
+        .args
+        .iter()
+        .map(|arg| instructions::fixed_size(arg.ty).unwrap())
+        .sum();
+    assert_eq!(data.len(), instructions::DISCRIMINATOR_LEN + args_len);
+
+    assert_eq!(data.len(), 8 + 8 + 96 + 32 + 16 + 16 + 8);
+    assert_eq!(
+        &data[..8],
+        &instruction_discriminator("queue_confidential_swap")
+    );
+    assert_eq!(&data[8..16], &9u64.to_le_bytes());
+    assert_eq!(&data[16..48], &[2u8; 32]);
+    assert_eq!(&data[48..80], &[3u8; 32]);
+    assert_eq!(&data[80..112], &[4u8; 32]);
+    assert_eq!(&data[112..144], &[1u8; 32]);
+    assert_eq!(&data[144..160], &0x0102u128.to_le_bytes());
+    assert_eq!(&data[160..168], &500u64.to_le_bytes());
+    assert_eq!(&data[168..176], &70_000u64.to_le_bytes());
+    assert_eq!(&data[176..184], &10u64.to_le_bytes());
+}
+
+#[test]
+fn deposit_arguments_reference_vault_state() {
+    let mxe = MockMxe::new();
+    let input = DepositInput { amount: 250 };
+
+    assert_eq!(mxe.process_deposit(&input.arguments([3u8; 32], 77)), 250);
+
+    let data = input.instruction_data(4);
+    assert_eq!(
+        &data[..8],
+        &instruction_discriminator("queue_encrypted_deposit")
+    );
+    assert_eq!(&data[8..16], &4u64.to_le_bytes());
+    assert_eq!(&data[16..24], &250u64.to_le_bytes());
+}
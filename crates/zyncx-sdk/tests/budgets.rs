@@ -0,0 +1,47 @@
+use std::collections::HashSet;
+
+use zyncx_sdk::budgets::*;
+use zyncx_sdk::instructions::{instruction, INSTRUCTIONS};
+
+#[test]
+fn budgets_fit_in_a_transaction() {
+    let mut seen = HashSet::new();
+    for budget in COMPUTE_BUDGETS {
+        assert!(
+            seen.insert(budget.instruction),
+            "{} listed twice",
+            budget.instruction
+        );
+        assert!(budget.max_units > 0, "{}", budget.instruction);
+        assert!(
+            budget.max_units <= MAX_TRANSACTION_COMPUTE_UNITS,
+            "{} cannot fit in one transaction",
+            budget.instruction
+        );
+    }
+}
+
+#[test]
+fn deposits_fit_the_default_budget() {
+    for name in ["deposit_native", "deposit_token"] {
+        let budget = instruction(name).unwrap().compute_budget.unwrap();
+        assert!(budget <= DEFAULT_INSTRUCTION_COMPUTE_UNITS, "{name}");
+    }
+}
+
+#[test]
+fn instruction_table_carries_the_budgets() {
+    for ix in INSTRUCTIONS {
+        let declared = COMPUTE_BUDGETS
+            .iter()
+            .find(|budget| budget.instruction == ix.name)
+            .map(|budget| budget.max_units);
+        assert_eq!(ix.compute_budget, declared, "{}", ix.name);
+    }
+    for name in ["withdraw_native", "swap_native", "swap_token"] {
+        assert!(
+            instruction(name).unwrap().compute_budget.is_some(),
+            "{name}"
+        );
+    }
+}
@@ -0,0 +1,200 @@
+use std::collections::HashSet;
+use std::path::Path;
+
+use sha2::{Digest, Sha256};
+use zyncx_sdk::instructions::*;
+
+#[test]
+fn discriminators_are_anchor_sighashes() {
+    let mut seen = HashSet::new();
+    for ix in INSTRUCTIONS {
+        let hash = Sha256::digest(format!("global:{}", ix.name).as_bytes());
+        assert_eq!(
+            ix.discriminator[..],
+            hash[..DISCRIMINATOR_LEN],
+            "{}",
+            ix.name
+        );
+        assert!(
+            seen.insert(ix.discriminator),
+            "duplicate discriminator: {}",
+            ix.name
+        );
+    }
+}
+
+#[test]
+fn committed_artifact_is_current() {
+    let committed = include_str!("../zyncx-instructions.json");
+    assert!(
+        committed == INSTRUCTION_TABLE_JSON,
+        "zyncx-instructions.json is stale; run ZYNCX_WRITE_INSTRUCTION_TABLE=1 cargo build -p zyncx-sdk"
+    );
+
+    let parsed: serde_json::Value = serde_json::from_str(committed).unwrap();
+    assert_eq!(
+        parsed["instructions"].as_array().unwrap().len(),
+        INSTRUCTIONS.len()
+    );
+    assert_eq!(parsed["types"].as_array().unwrap().len(), TYPES.len());
+}
+
+#[test]
+fn lookups() {
+    let ix = instruction("withdraw_native").unwrap();
+    let mut data = ix.discriminator.to_vec();
+    data.extend_from_slice(&[0u8; 16]);
+    assert_eq!(instruction_for_data(&data), Some(ix));
+    assert_eq!(instruction_for_data(&data[..4]), None);
+
+    let claim = instruction("propose_insurance_claim").unwrap();
+    assert_eq!(
+        claim.args.iter().map(|a| a.ty).collect::<Vec<_>>(),
+        ["u64", "[u8; 32]", "Vec<ClaimPayout>"]
+    );
+    assert!(matches!(
+        type_layout("ClaimPayout").unwrap().kind,
+        TypeKind::Struct([amount, _]) if amount.ty == "u64"
+    ));
+
+    let cancel = instruction("cancel_twap").unwrap();
+    let user = &cancel.accounts[0];
+    assert!(user.name == "user" && user.signer && user.writable);
+}
+
+#[test]
+fn encodes_arguments_in_table_order() {
+    assert_eq!(fixed_size("[[u8; 32]; 3]"), Some(96));
+    assert_eq!(fixed_size("SwapQuote"), Some(16));
+    assert_eq!(fixed_size("TreeHash"), Some(1));
+    assert_eq!(fixed_size("Vec<u8>"), None);
+
+    let ix = instruction("queue_encrypted_deposit").unwrap();
+    let data = encode_instruction(
+        "queue_encrypted_deposit",
+        &[
+            ("deposit_amount", &250u64.to_le_bytes()),
+            ("computation_offset", &4u64.to_le_bytes()),
+        ],
+    )
+    .unwrap();
+    assert_eq!(data[..DISCRIMINATOR_LEN], ix.discriminator);
+    assert_eq!(data[DISCRIMINATOR_LEN..16], 4u64.to_le_bytes());
+    assert_eq!(data[16..], 250u64.to_le_bytes());
+
+    // Struct arguments whole or field by field
+    let quote = [[1u8; 8], [2u8; 8]].concat();
+    let whole = encode_instruction(
+        "retry_computation",
+        &[("computation_offset", &[0u8; 8]), ("quote", &quote)],
+    )
+    .unwrap();
+    let fields = encode_instruction(
+        "retry_computation",
+        &[
+            ("computation_offset", &[0u8; 8]),
+            ("quote.liquidity_depth", &[2u8; 8]),
+            ("quote.current_output", &[1u8; 8]),
+        ],
+    )
+    .unwrap();
+    assert_eq!(whole, fields);
+
+    assert_eq!(
+        encode_instruction(
+            "queue_encrypted_deposit",
+            &[("computation_offset", &[0u8; 8])]
+        ),
+        Err(EncodeError::MissingArgument("deposit_amount".into()))
+    );
+    assert_eq!(
+        encode_instruction(
+            "queue_encrypted_deposit",
+            &[
+                ("computation_offset", &[0u8; 8]),
+                ("deposit_amount", &[0u8; 4]),
+            ],
+        ),
+        Err(EncodeError::WrongSize {
+            argument: "deposit_amount".into(),
+            expected: 8,
+            found: 4,
+        })
+    );
+    assert_eq!(
+        encode_instruction(
+            "queue_encrypted_deposit",
+            &[
+                ("computation_offset", &[0u8; 8]),
+                ("deposit_amount", &[0u8; 8]),
+                ("amount", &[0u8; 8]),
+            ],
+        ),
+        Err(EncodeError::UnexpectedArgument("amount".into()))
+    );
+}
+
+/// Compare against the IDL of the compiled program when `anchor build` has
+/// produced one; the table is derived from the same sources, so any drift
+/// means the generator misread them.
+#[test]
+fn table_matches_compiled_idl() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../target/idl/zyncx.json");
+    let Ok(idl) = std::fs::read_to_string(&path) else {
+        eprintln!("skipping: {} not built", path.display());
+        return;
+    };
+    let idl: serde_json::Value = serde_json::from_str(&idl).unwrap();
+    let idl_ixs = idl["instructions"].as_array().unwrap();
+    assert_eq!(idl_ixs.len(), INSTRUCTIONS.len());
+
+    for expected in idl_ixs {
+        let name = expected["name"].as_str().unwrap();
+        let ix = instruction(name).unwrap_or_else(|| panic!("{name} missing from the table"));
+
+        let discriminator: Vec<u8> = expected["discriminator"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|b| b.as_u64().unwrap() as u8)
+            .collect();
+        assert_eq!(ix.discriminator[..], discriminator[..], "{name}");
+
+        let mut accounts = Vec::new();
+        flatten_idl_accounts(&expected["accounts"], &mut accounts);
+        let table: Vec<_> = ix
+            .accounts
+            .iter()
+            .map(|a| (a.name.to_string(), a.writable, a.signer, a.optional))
+            .collect();
+        assert_eq!(table, accounts, "{name}");
+
+        let args: Vec<&str> = expected["args"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|a| a["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            ix.args.iter().map(|a| a.name).collect::<Vec<_>>(),
+            args,
+            "{name}"
+        );
+    }
+}
+
+fn flatten_idl_accounts(accounts: &serde_json::Value, out: &mut Vec<(String, bool, bool, bool)>) {
+    for account in accounts.as_array().unwrap() {
+        if account.get("accounts").is_some() {
+            flatten_idl_accounts(&account["accounts"], out);
+            continue;
+        }
+        let flag = |key: &str| account.get(key).and_then(|v| v.as_bool()).unwrap_or(false);
+        out.push((
+            account["name"].as_str().unwrap().to_string(),
+            flag("writable"),
+            flag("signer"),
+            flag("optional"),
+        ));
+    }
+}
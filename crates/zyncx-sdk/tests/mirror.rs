@@ -0,0 +1,173 @@
+//! The mirror must land on the roots `MerkleTreeState::insert` computes, in
+//! both hash modes. The program reads the insertion slot from the Clock
+//! sysvar, so the tests serve it through syscall stubs.
+
+use std::cell::Cell;
+use std::sync::Once;
+
+use sha2::{Digest, Sha256};
+use solana_program::clock::Clock;
+use solana_program::program_stubs::{set_syscall_stubs, SyscallStubs};
+use zyncx::state::{
+    MerkleTreeState, TreeHash as ProgramTreeHash, CURRENT_ACCOUNT_VERSION, FRONTIER_SIZE,
+    ROOT_HISTORY_SIZE,
+};
+use zyncx_sdk::hash::root_from_path;
+use zyncx_sdk::mirror::{DepositedEvent, MerkleTreeMirror, MirrorError, TreeHash};
+use zyncx_sdk::tree::LeafInserted;
+
+thread_local! {
+    static SLOT: Cell<u64> = const { Cell::new(0) };
+}
+
+struct ClockStub;
+
+impl SyscallStubs for ClockStub {
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        let clock = Clock {
+            slot: SLOT.with(Cell::get),
+            ..Clock::default()
+        };
+        unsafe { *(var_addr as *mut Clock) = clock };
+        0
+    }
+}
+
+fn program_tree(tree_hash: ProgramTreeHash) -> MerkleTreeState {
+    static STUBS: Once = Once::new();
+    STUBS.call_once(|| {
+        set_syscall_stubs(Box::new(ClockStub));
+    });
+
+    MerkleTreeState {
+        bump: 0,
+        depth: 0,
+        size: 0,
+        current_root_index: 0,
+        root: [0u8; 32],
+        roots: [[0u8; 32]; ROOT_HISTORY_SIZE],
+        vault: Default::default(),
+        epoch: 0,
+        max_leaves: 1 << 20,
+        tree_hash,
+        frontier: [[0u8; 32]; FRONTIER_SIZE],
+        version: CURRENT_ACCOUNT_VERSION,
+    }
+}
+
+/// Distinct field-element commitments
+fn commitment(i: u64) -> [u8; 32] {
+    let mut commitment: [u8; 32] = Sha256::digest(i.to_le_bytes()).into();
+    commitment[0] = 0;
+    commitment
+}
+
+fn cross_check(program_hash: ProgramTreeHash, tree_hash: TreeHash) {
+    let mut program = program_tree(program_hash);
+    let mut mirror = MerkleTreeMirror::new(tree_hash);
+
+    for i in 0..37u64 {
+        let slot = 1_000 + 3 * i;
+        SLOT.with(|cell| cell.set(slot));
+        let insertion = program.insert(commitment(i)).unwrap();
+        let (leaf_index, root) = mirror.insert(commitment(i), slot).unwrap();
+
+        assert_eq!(leaf_index, insertion.leaf_index);
+        assert_eq!(root, insertion.new_root, "root after leaf {i}");
+        assert_eq!(mirror.size(), program.size);
+    }
+
+    let root = mirror.root();
+    assert_eq!(root, program.get_root());
+    for leaf_index in [0u64, 17, 36] {
+        let path = mirror.auth_path(leaf_index).unwrap();
+        let is_right: Vec<bool> = (0..path.len())
+            .map(|level| (leaf_index >> level) & 1 == 1)
+            .collect();
+        let leaf = zyncx_sdk::hash::leaf_node(
+            tree_hash.backend(),
+            &commitment(leaf_index),
+            1_000 + 3 * leaf_index,
+        );
+        assert_eq!(
+            root_from_path(tree_hash.backend(), &leaf, &path, &is_right),
+            root
+        );
+    }
+}
+
+#[test]
+fn keccak_roots_match_the_program() {
+    cross_check(ProgramTreeHash::Keccak, TreeHash::Keccak);
+}
+
+#[test]
+fn poseidon_roots_match_the_program() {
+    cross_check(ProgramTreeHash::Poseidon, TreeHash::Poseidon);
+}
+
+fn deposited_event(commitment: [u8; 32], leaf_index: u64, new_root: [u8; 32]) -> Vec<u8> {
+    let mut data = Sha256::digest(b"event:DepositedEvent")[..8].to_vec();
+    data.extend_from_slice(&[7u8; 32]); // depositor
+    data.extend_from_slice(&50u64.to_le_bytes()); // amount
+    data.extend_from_slice(&commitment);
+    data.extend_from_slice(&[0u8; 32]); // precommitment
+    data.extend_from_slice(&3u32.to_le_bytes()); // encrypted_note
+    data.extend_from_slice(&[1, 2, 3]);
+    data.extend_from_slice(&[0u8; 32]); // viewing_pubkey
+    data.extend_from_slice(&0u32.to_le_bytes()); // viewing_ciphertext
+    data.extend_from_slice(&leaf_index.to_le_bytes());
+    data.extend_from_slice(&new_root);
+    data
+}
+
+#[test]
+fn replayed_deposits_are_checked_against_the_reported_roots() {
+    let mut program = program_tree(ProgramTreeHash::Keccak);
+    let mut mirror = MerkleTreeMirror::new(TreeHash::Keccak);
+
+    SLOT.with(|cell| cell.set(500));
+    let first = program.insert(commitment(0)).unwrap();
+    let event = DepositedEvent::from_event_data(&deposited_event(commitment(0), 0, first.new_root))
+        .unwrap();
+    assert_eq!(event.amount, 50);
+    mirror.apply_deposit(&event, 500).unwrap();
+
+    // A change note inserted without a DepositedEvent
+    SLOT.with(|cell| cell.set(501));
+    let change = program.insert(commitment(1)).unwrap();
+
+    SLOT.with(|cell| cell.set(502));
+    let second = program.insert(commitment(2)).unwrap();
+    let event =
+        DepositedEvent::from_event_data(&deposited_event(commitment(2), 2, second.new_root))
+            .unwrap();
+    assert_eq!(
+        mirror.apply_deposit(&event, 502),
+        Err(MirrorError::OutOfOrder {
+            expected: 1,
+            leaf_index: 2
+        })
+    );
+
+    // Wrong slot: the leaf node differs, so does the root
+    let leaf = LeafInserted {
+        vault: [0u8; 32],
+        tree_epoch: 0,
+        leaf_index: 1,
+        commitment: commitment(1),
+        slot: 499,
+        new_root: change.new_root,
+    };
+    assert_eq!(
+        mirror.apply_leaf(&leaf),
+        Err(MirrorError::RootMismatch { leaf_index: 1 })
+    );
+    assert_eq!(mirror.size(), 1);
+
+    mirror
+        .apply_leaf(&LeafInserted { slot: 501, ..leaf })
+        .unwrap();
+    mirror.apply_deposit(&event, 502).unwrap();
+    assert_eq!(mirror.root(), program.get_root());
+}
@@ -0,0 +1,253 @@
+use base64::Engine as _;
+use sha2::{Digest, Sha256};
+use sha3::Keccak256;
+use zyncx_sdk::hash::{merkle_root, root_from_path, Keccak, Poseidon};
+use zyncx_sdk::mirror::TreeHash;
+use zyncx_sdk::note::{self, Note};
+use zyncx_sdk::proof::{u64_public_input, ExtData, SwapPublicInputs, WithdrawPublicInputs};
+use zyncx_sdk::tree::{LeafInserted, TreeLog, TreeLogError};
+
+const VAULT: [u8; 32] = [3u8; 32];
+
+fn keccak(data: &[u8]) -> [u8; 32] {
+    Keccak256::digest(data).into()
+}
+
+#[test]
+fn commitment_matches_the_program_hash() {
+    let note = Note {
+        amount: 1_500_000,
+        secret: [1u8; 32],
+        nullifier_secret: [2u8; 32],
+        policy_hash: note::UNBOUND_POLICY_HASH,
+    };
+
+    let mut secrets = [1u8; 64];
+    secrets[32..].fill(2);
+    let precommitment = keccak(&secrets);
+    assert_eq!(note.precommitment(TreeHash::Keccak), precommitment);
+
+    // poseidon_hash_commitment: keccak(amount_le || precommitment)
+    let mut preimage = 1_500_000u64.to_le_bytes().to_vec();
+    preimage.extend_from_slice(&precommitment);
+    assert_eq!(note.commitment(TreeHash::Keccak), keccak(&preimage));
+
+    assert_eq!(note.nullifier_hash(TreeHash::Keccak), keccak(&[2u8; 32]));
+}
+
+#[test]
+fn poseidon_commitment_matches_the_circuits() {
+    let word = |value: u8| {
+        let mut word = [0u8; 32];
+        word[31] = value;
+        word
+    };
+    let note = Note {
+        amount: 1_000,
+        secret: word(1),
+        nullifier_secret: word(2),
+        policy_hash: note::UNBOUND_POLICY_HASH,
+    };
+
+    // The note `test_root_matches_the_program` in mixer/src/main.nr spends
+    let commitment = note.commitment(TreeHash::Poseidon);
+    assert_eq!(
+        merkle_root(&Poseidon, &[commitment], &[1_000])
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect::<String>(),
+        "2716e5c3b0105108186cb17e46d7d8fb65162c7c7e12cebde75bda6aaf009357"
+    );
+    assert_ne!(commitment, note.commitment(TreeHash::Keccak));
+    assert_eq!(
+        note.nullifier_hash(TreeHash::Poseidon),
+        Poseidon.hash_one(&word(2))
+    );
+}
+
+#[test]
+fn bound_notes_hash_their_policy_into_the_commitment() {
+    let policy_hash = note::policy_hash(&VAULT, 1);
+    assert_eq!(policy_hash[0], 0);
+    assert_ne!(policy_hash, note::policy_hash(&VAULT, 2));
+
+    let note = Note::bound(1_000, policy_hash);
+    let unbound = Note {
+        policy_hash: note::UNBOUND_POLICY_HASH,
+        ..note.clone()
+    };
+    for tree_hash in [TreeHash::Keccak, TreeHash::Poseidon] {
+        assert_eq!(
+            note.precommitment(tree_hash),
+            unbound.precommitment(tree_hash)
+        );
+        assert_ne!(note.commitment(tree_hash), unbound.commitment(tree_hash));
+    }
+
+    // keccak(amount_le || precommitment || policy_hash)
+    let mut preimage = 1_000u64.to_le_bytes().to_vec();
+    preimage.extend_from_slice(&note.precommitment(TreeHash::Keccak));
+    preimage.extend_from_slice(&policy_hash);
+    assert_eq!(note.commitment(TreeHash::Keccak), keccak(&preimage));
+
+    // Change stays bound to the policy
+    assert_eq!(note.change(400).unwrap().policy_hash, policy_hash);
+}
+
+#[test]
+fn generated_secrets_are_field_elements() {
+    let note = Note::generate(10);
+    assert_eq!(note.secret[0], 0);
+    assert_eq!(note.nullifier_secret[0], 0);
+    assert_ne!(note.secret, Note::generate(10).secret);
+}
+
+#[test]
+fn change_note_only_for_partial_spends() {
+    let note = Note::generate(100);
+    assert_eq!(note.change(100), None);
+    assert_eq!(note.change(101), None);
+    assert_eq!(note::new_commitment(TreeHash::Poseidon, None), [0u8; 32]);
+
+    let change = note.change(40).unwrap();
+    assert_eq!(change.amount, 60);
+    assert_eq!(
+        note::new_commitment(TreeHash::Poseidon, Some(&change)),
+        change.commitment(TreeHash::Poseidon)
+    );
+}
+
+#[test]
+fn withdraw_inputs_follow_the_program_order() {
+    let inputs = WithdrawPublicInputs {
+        root: [1u8; 32],
+        nullifier_hash: [2u8; 32],
+        recipient: [3u8; 32],
+        amount: 500,
+        new_commitment: [4u8; 32],
+        ext_data_hash: [5u8; 32],
+        remaining_amount: 250,
+        note_age_cutoff: u64::MAX,
+        policy_hash: [6u8; 32],
+    };
+    let fields = inputs.to_fields();
+    assert_eq!(fields.len(), 9);
+    assert_eq!(fields[3], u64_public_input(500));
+    assert_eq!(fields[3][24..], 500u64.to_be_bytes());
+    assert_eq!(fields[5], [5u8; 32]);
+    assert_eq!(fields[6], u64_public_input(250));
+    assert_eq!(fields[7], u64_public_input(u64::MAX));
+    assert_eq!(fields[8], [6u8; 32]);
+
+    let with_cutoff = WithdrawPublicInputs {
+        note_age_cutoff: 9_000,
+        ..inputs
+    };
+    assert_eq!(with_cutoff.to_fields()[7], u64_public_input(9_000));
+}
+
+#[test]
+fn swap_inputs_bind_the_referrer_as_ext_data_hash() {
+    let inputs = SwapPublicInputs {
+        root: [1u8; 32],
+        nullifier_hash: [2u8; 32],
+        recipient: [3u8; 32],
+        amount_in: 500,
+        new_commitment: [4u8; 32],
+        remaining_amount: 7,
+        note_age_cutoff: u64::MAX,
+        referrer: [0u8; 32],
+    };
+    let fields = inputs.to_fields();
+    assert_eq!(fields.len(), 9);
+    assert_eq!(fields[5], [0u8; 32]);
+    assert_eq!(fields[6], u64_public_input(7));
+    // Policy-bound notes cannot be swapped
+    assert_eq!(fields[8], [0u8; 32]);
+
+    // A referrer is bound through ext_data_hash
+    let referred = SwapPublicInputs {
+        referrer: [5u8; 32],
+        ..inputs
+    };
+    let ext_data_hash = referred.to_fields()[5];
+    assert_ne!(ext_data_hash, [0u8; 32]);
+    assert_eq!(ext_data_hash[0], 0);
+}
+
+#[test]
+fn ext_data_hash_is_a_field_element() {
+    let ext_data = ExtData {
+        max_fee: 10,
+        ..ExtData::default()
+    };
+    let hash = ext_data.hash(&[1u8; 32], &[2u8; 32]);
+    assert_eq!(hash[0], 0);
+    assert_ne!(hash, ext_data.hash(&[1u8; 32], &[3u8; 32]));
+}
+
+fn event_log(leaf_index: u64, commitment: [u8; 32], new_root: [u8; 32]) -> String {
+    let mut data = Sha256::digest(b"event:LeafInserted")[..8].to_vec();
+    data.extend_from_slice(&VAULT);
+    data.extend_from_slice(&0u64.to_le_bytes()); // tree_epoch
+    data.extend_from_slice(&leaf_index.to_le_bytes());
+    data.extend_from_slice(&commitment);
+    data.extend_from_slice(&(100 + leaf_index).to_le_bytes()); // slot
+    data.extend_from_slice(&new_root);
+    format!(
+        "Program data: {}",
+        base64::engine::general_purpose::STANDARD.encode(data)
+    )
+}
+
+#[test]
+fn paths_rebuilt_from_logs_reach_the_root() {
+    let commitments: Vec<[u8; 32]> = (1..=5).map(|i| [i as u8; 32]).collect();
+    // Newest first, as signatures are listed, with a replayed event
+    let mut logs: Vec<String> = commitments
+        .iter()
+        .enumerate()
+        .map(|(i, commitment)| event_log(i as u64, *commitment, [0u8; 32]))
+        .rev()
+        .collect();
+    logs.push("Program log: Instruction: DepositNative".to_string());
+    logs.push(logs[0].clone());
+
+    let log = TreeLog::from_logs(VAULT, 0, logs.iter().map(String::as_str)).unwrap();
+    assert_eq!(log.find(&[3u8; 32]), Some(2));
+
+    let root = log.root(&Keccak).unwrap();
+    for index in 0..5 {
+        let path = log.path(&Keccak, index).unwrap();
+        assert_eq!(path.root, root);
+        assert_eq!(
+            root_from_path(&Keccak, &path.leaf, &path.siblings, &path.is_right),
+            root
+        );
+    }
+    assert_eq!(
+        log.path(&Keccak, 5).unwrap_err(),
+        TreeLogError::MissingLeaf(5)
+    );
+}
+
+#[test]
+fn gaps_and_conflicts_are_reported() {
+    let mut log = TreeLog::new(VAULT, 0);
+    let leaf = |line: String| LeafInserted::from_log(&line).unwrap();
+
+    assert!(log
+        .insert(leaf(event_log(1, [1u8; 32], [0u8; 32])))
+        .unwrap());
+    assert_eq!(log.root(&Keccak).unwrap_err(), TreeLogError::MissingLeaf(0));
+    assert_eq!(
+        log.insert(leaf(event_log(1, [2u8; 32], [0u8; 32]))),
+        Err(TreeLogError::ConflictingLeaf(1))
+    );
+
+    let other_tree = LeafInserted {
+        tree_epoch: 1,
+        ..leaf(event_log(0, [1u8; 32], [0u8; 32]))
+    };
+    assert!(!log.insert(other_tree).unwrap());
+}
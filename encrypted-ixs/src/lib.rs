@@ -41,13 +41,497 @@ mod circuits {
         vault_state.owner.from_arcis(vault)
     }
 
-    /// Evaluate swap - returns boolean for whether swap should execute
+    /// Per-user balance tracked inside the MXE
+    #[derive(Copy, Clone)]
+    pub struct UserPosition {
+        pub deposited_amount: u64,
+        pub lp_share: u64,
+    }
+
+    /// Initialize a new user position with zeroed encrypted state
+    #[instruction]
+    pub fn init_position(mxe: Mxe) -> Enc<Mxe, UserPosition> {
+        let initial_position = UserPosition {
+            deposited_amount: 0,
+            lp_share: 0,
+        };
+        mxe.from_arcis(initial_position)
+    }
+
+    /// Re-encrypt a position's withdrawable balance to its owner's key.
+    /// Nothing is revealed on-chain.
+    #[instruction]
+    pub fn compute_withdrawal(owner: Shared, position: Enc<Mxe, UserPosition>) -> Enc<Shared, u64> {
+        let position = position.to_arcis();
+        owner.from_arcis(position.deposited_amount)
+    }
+
+    /// Reveal only whether a position holds at least `threshold`
+    #[instruction]
+    pub fn verify_sufficient_balance(threshold: u64, position: Enc<Mxe, UserPosition>) -> bool {
+        (position.to_arcis().deposited_amount >= threshold).reveal()
+    }
+
+    /// Redeem a position - reveals its balance for payout, releases it from
+    /// the vault's pending deposits and returns the emptied position
+    #[instruction]
+    pub fn clear_position(
+        position: Enc<Mxe, UserPosition>,
+        vault_state: Enc<Mxe, VaultState>,
+    ) -> (u64, Enc<Mxe, UserPosition>, Enc<Mxe, VaultState>) {
+        let amount = position.to_arcis().deposited_amount;
+        let mut vault = vault_state.to_arcis();
+        // Saturating subtraction; arcis integers have no saturating_sub
+        vault.pending_deposits -= vault.pending_deposits.min(amount);
+        let cleared = UserPosition {
+            deposited_amount: 0,
+            lp_share: 0,
+        };
+        (
+            amount.reveal(),
+            position.owner.from_arcis(cleared),
+            vault_state.owner.from_arcis(vault),
+        )
+    }
+
+    /// Number of referrers accrued per epoch (matches the program's REFERRAL_SLOTS)
+    const REFERRAL_SLOTS: usize = 4;
+
+    /// Confidential swap parameters, encrypted by the swapper
+    #[derive(Copy, Clone)]
+    pub struct SwapParams {
+        pub min_out: u64,
+        /// Referrer code: slot + 1, or 0 for no referrer
+        pub referrer: u64,
+        /// Highest price impact a single child order may cause, or 0 for no cap
+        pub max_impact_bps: u64,
+    }
+
+    /// Per-epoch referral fee accrual, only ever revealed in aggregate
+    #[derive(Copy, Clone)]
+    pub struct ReferralTotals {
+        pub totals: [u64; REFERRAL_SLOTS],
+    }
+
+    /// Open a referral epoch with zeroed encrypted totals
+    #[instruction]
+    pub fn init_referral_epoch(mxe: Mxe) -> Enc<Mxe, ReferralTotals> {
+        mxe.from_arcis(ReferralTotals {
+            totals: [0; REFERRAL_SLOTS],
+        })
+    }
+
+    /// Revealed approval and child order cap, then the re-encrypted referral
+    /// totals and source and destination vault states
+    pub type SwapResult = (
+        bool,
+        u64,
+        Enc<Mxe, ReferralTotals>,
+        Enc<Mxe, VaultState>,
+        Enc<Mxe, VaultState>,
+    );
+
+    /// Evaluate swap - reveals whether the swap should execute and the
+    /// largest child order the execution layer may route. When it does,
+    /// referral_fee is credited to the encrypted referrer's slot inside the
+    /// MXE, so the referral itself never becomes public.
+    ///
+    /// Both vaults' states are carried through the swap. On approval the
+    /// expected output is recorded as pending in the destination vault, where
+    /// settlement inserts the resulting note; the source state is re-encrypted
+    /// as is, since the input amount is only fixed by the proof at settlement.
+    ///
+    /// `liquidity_depth` is the input-side depth of the route (AMM reserve or
+    /// Jupiter quote). Selling x into depth D moves the price by x / (D + x),
+    /// so staying under t bps needs x <= D * t / (10_000 - t); the circuit
+    /// uses D * t / 10_000, which is always on the safe side and avoids
+    /// dividing by a secret. Only the resulting size is revealed, never the
+    /// threshold itself.
     #[instruction]
     pub fn confidential_swap(
-        encrypted_min_out: Enc<Shared, u64>,
+        params: Enc<Shared, SwapParams>,
         current_output: u64,
+        liquidity_depth: u64,
+        referral_fee: u64,
+        referrals: Enc<Mxe, ReferralTotals>,
+        source_vault: Enc<Mxe, VaultState>,
+        dest_vault: Enc<Mxe, VaultState>,
+    ) -> SwapResult {
+        let swap = params.to_arcis();
+        let mut accrued = referrals.to_arcis();
+        let source = source_vault.to_arcis();
+        let mut dest = dest_vault.to_arcis();
+        // depth * bps / 10_000 without overflowing u64
+        let cap = (liquidity_depth / 10_000) * swap.max_impact_bps
+            + (liquidity_depth % 10_000) * swap.max_impact_bps / 10_000;
+        let max_child = if swap.max_impact_bps == 0 { u64::MAX } else { cap };
+        let execute = (current_output >= swap.min_out) & (max_child > 0);
+        for i in 0..REFERRAL_SLOTS {
+            if execute & (swap.referrer == (i as u64) + 1) {
+                accrued.totals[i] += referral_fee;
+            }
+        }
+        if execute {
+            dest.pending_deposits += current_output;
+        }
+        (
+            execute.reveal(),
+            max_child.reveal(),
+            referrals.owner.from_arcis(accrued),
+            source_vault.owner.from_arcis(source),
+            dest_vault.owner.from_arcis(dest),
+        )
+    }
+
+    /// Reveal an ended epoch's per-referrer totals
+    #[instruction]
+    pub fn reveal_referral_epoch(referrals: Enc<Mxe, ReferralTotals>) -> [u64; REFERRAL_SLOTS] {
+        referrals.to_arcis().totals.reveal()
+    }
+
+    /// Limit order parameters, encrypted by the order owner
+    #[derive(Copy, Clone)]
+    pub struct LimitOrderParams {
+        pub target_price: u64,
+        pub amount: u64,
+        pub is_buy: u64,
+    }
+
+    /// Whether the oracle price crossed the target on the order's side
+    fn limit_order_triggered(params: LimitOrderParams, current_price: u64) -> bool {
+        if params.is_buy == 1 {
+            current_price <= params.target_price
+        } else {
+            current_price >= params.target_price
+        }
+    }
+
+    /// Evaluate a limit order - returns whether it triggered at current_price
+    #[instruction]
+    pub fn evaluate_limit_order(
+        order: Enc<Shared, LimitOrderParams>,
+        current_price: u64,
     ) -> bool {
-        let min_out = encrypted_min_out.to_arcis();
-        (current_output >= min_out).reveal()
+        limit_order_triggered(order.to_arcis(), current_price).reveal()
+    }
+
+    /// Evaluate a crank batch of four limit orders sharing one price feed.
+    /// Bit i of the revealed mask is set when order i triggered; keepers pad
+    /// short batches by repeating an order and ignore the extra bits.
+    #[instruction]
+    pub fn evaluate_limit_order_batch(
+        order_0: Enc<Shared, LimitOrderParams>,
+        order_1: Enc<Shared, LimitOrderParams>,
+        order_2: Enc<Shared, LimitOrderParams>,
+        order_3: Enc<Shared, LimitOrderParams>,
+        current_price: u64,
+    ) -> u8 {
+        let bit_0: u8 = if limit_order_triggered(order_0.to_arcis(), current_price) { 1 } else { 0 };
+        let bit_1: u8 = if limit_order_triggered(order_1.to_arcis(), current_price) { 2 } else { 0 };
+        let bit_2: u8 = if limit_order_triggered(order_2.to_arcis(), current_price) { 4 } else { 0 };
+        let bit_3: u8 = if limit_order_triggered(order_3.to_arcis(), current_price) { 8 } else { 0 };
+        (bit_0 + bit_1 + bit_2 + bit_3).reveal()
+    }
+
+    /// Cross two users' limit orders directly. They match when they sit on
+    /// opposite sides and the buy limit is at or above the sell limit; the
+    /// matched size is the smaller amount. Only whether they crossed is
+    /// revealed; each owner gets the matched size encrypted to its key.
+    #[instruction]
+    pub fn match_orders(
+        order_a: Enc<Shared, LimitOrderParams>,
+        order_b: Enc<Shared, LimitOrderParams>,
+    ) -> (bool, Enc<Shared, u64>, Enc<Shared, u64>) {
+        let a = order_a.to_arcis();
+        let b = order_b.to_arcis();
+        let a_buys = a.is_buy == 1;
+        let b_buys = b.is_buy == 1;
+        let bid = if a_buys { a.target_price } else { b.target_price };
+        let ask = if a_buys { b.target_price } else { a.target_price };
+        let crossed = (a_buys != b_buys) & (bid >= ask);
+        let smaller = if a.amount <= b.amount { a.amount } else { b.amount };
+        let size = if crossed { smaller } else { 0 };
+        (
+            crossed.reveal(),
+            order_a.owner.from_arcis(size),
+            order_b.owner.from_arcis(size),
+        )
+    }
+
+    /// Stop order parameters, encrypted by the order owner
+    #[derive(Copy, Clone)]
+    pub struct StopOrderParams {
+        pub stop_price: u64,
+        pub amount: u64,
+        pub is_buy: u64,
+    }
+
+    /// Whether the oracle price crossed the stop level. The mirror image of
+    /// a limit order: a stop sell fires once the price falls to the level, a
+    /// stop buy once it rises to it.
+    fn stop_order_triggered(params: StopOrderParams, current_price: u64) -> bool {
+        if params.is_buy == 1 {
+            current_price >= params.stop_price
+        } else {
+            current_price <= params.stop_price
+        }
+    }
+
+    /// Evaluate a stop order at current_price. Returns whether it triggered
+    /// and the amount to execute; the amount is revealed only once the stop
+    /// fires (0 otherwise), since the sell has to be settled in the clear.
+    #[instruction]
+    pub fn evaluate_stop_order(
+        order: Enc<Shared, StopOrderParams>,
+        current_price: u64,
+    ) -> (bool, u64) {
+        let params = order.to_arcis();
+        let triggered = stop_order_triggered(params, current_price);
+        let amount = if triggered { params.amount } else { 0 };
+        (triggered.reveal(), amount.reveal())
+    }
+
+    /// DCA parameters, encrypted by the DCA owner
+    #[derive(Copy, Clone)]
+    pub struct DCAParams {
+        pub amount_per_swap: u64,
+        pub swaps_remaining: u64,
+        pub min_price: u64,
+    }
+
+    /// Run one DCA interval - approves amount_per_swap when swaps remain and
+    /// the source price is at least min_price. Returns the updated params
+    /// re-encrypted for the owner, the revealed amount to swap (0 = skip) and
+    /// whether the schedule is finished.
+    #[instruction]
+    pub fn process_dca(
+        dca: Enc<Shared, DCAParams>,
+        current_price: u64,
+    ) -> (Enc<Shared, DCAParams>, u64, bool) {
+        let mut params = dca.to_arcis();
+        let execute = (params.swaps_remaining > 0) & (current_price >= params.min_price);
+        let amount = if execute { params.amount_per_swap } else { 0 };
+        if execute {
+            params.swaps_remaining -= 1;
+        }
+        let completed = params.swaps_remaining == 0;
+        (dca.owner.from_arcis(params), amount.reveal(), completed.reveal())
+    }
+
+    /// Net one DCA interval against the same owner's opposing limit order.
+    /// The interval's approved amount first fills the limit order (when it
+    /// buys back what the DCA sells and has triggered at current_price)
+    /// inside the MXE; only the residual is left for Jupiter. Returns both
+    /// updated params re-encrypted for the owner, the revealed netted size,
+    /// the revealed residual to swap and whether the DCA is finished. The
+    /// limit order's remaining amount stays encrypted.
+    #[instruction]
+    pub fn net_orders(
+        dca: Enc<Shared, DCAParams>,
+        order: Enc<Shared, LimitOrderParams>,
+        current_price: u64,
+    ) -> (Enc<Shared, DCAParams>, Enc<Shared, LimitOrderParams>, u64, u64, bool) {
+        let mut schedule = dca.to_arcis();
+        let mut limit = order.to_arcis();
+        let execute = (schedule.swaps_remaining > 0) & (current_price >= schedule.min_price);
+        let amount = if execute { schedule.amount_per_swap } else { 0 };
+        if execute {
+            schedule.swaps_remaining -= 1;
+        }
+        let opposing = (limit.is_buy == 1) & limit_order_triggered(limit, current_price);
+        let capacity = if opposing { limit.amount } else { 0 };
+        let netted = if amount <= capacity { amount } else { capacity };
+        limit.amount -= netted;
+        let residual = amount - netted;
+        let completed = schedule.swaps_remaining == 0;
+        (
+            dca.owner.from_arcis(schedule),
+            order.owner.from_arcis(limit),
+            netted.reveal(),
+            residual.reveal(),
+            completed.reveal(),
+        )
+    }
+
+    /// TWAP parameters, encrypted by the TWAP owner
+    #[derive(Copy, Clone)]
+    pub struct TWAPParams {
+        pub total_amount: u64,
+        pub max_participation_bps: u64,
+        pub executed_amount: u64,
+    }
+
+    /// Size one TWAP slice - at most max_participation_bps of the total per
+    /// interval and never more than what remains. Returns the updated params
+    /// re-encrypted for the owner, the revealed slice size and whether the
+    /// full amount has now been sliced.
+    #[instruction]
+    pub fn process_twap(twap: Enc<Shared, TWAPParams>) -> (Enc<Shared, TWAPParams>, u64, bool) {
+        let mut params = twap.to_arcis();
+        // Saturating subtraction; arcis integers have no saturating_sub
+        let remaining = params.total_amount - params.executed_amount.min(params.total_amount);
+        // total * bps / 10_000 without overflowing u64
+        let cap = (params.total_amount / 10_000) * params.max_participation_bps
+            + (params.total_amount % 10_000) * params.max_participation_bps / 10_000;
+        let cap = if cap > 0 { cap } else { 1 };
+        let slice = if remaining <= cap { remaining } else { cap };
+        params.executed_amount += slice;
+        let completed = params.executed_amount >= params.total_amount;
+        (twap.owner.from_arcis(params), slice.reveal(), completed.reveal())
+    }
+
+    /// Hybrid TWAP/limit order parameters, encrypted by the order owner
+    #[derive(Copy, Clone)]
+    pub struct HybridOrderParams {
+        pub limit_price: u64,
+        pub max_duration: u64,
+        pub is_buy: u64,
+    }
+
+    /// Evaluate a hybrid order on each crank - takes liquidity when the price
+    /// beats the limit, or unconditionally (market) once max_duration has
+    /// elapsed. Only the combined decision is revealed, never which branch.
+    #[instruction]
+    pub fn evaluate_hybrid_order(
+        order: Enc<Shared, HybridOrderParams>,
+        current_price: u64,
+        elapsed_seconds: u64,
+    ) -> bool {
+        let params = order.to_arcis();
+        let price_ok = if params.is_buy == 1 {
+            current_price <= params.limit_price
+        } else {
+            current_price >= params.limit_price
+        };
+        let expired = elapsed_seconds >= params.max_duration;
+        (price_ok | expired).reveal()
+    }
+
+    /// Sealed-bid order for a batch auction, encrypted by its owner
+    #[derive(Copy, Clone)]
+    pub struct SealedOrder {
+        pub amount: u64,
+        pub limit_price: u64,
+        pub is_buy: u64,
+    }
+
+    /// Amount an order is willing to trade at the clearing price (0 if its
+    /// limit is not met), on the buy side or sell side
+    fn auction_demand(order: SealedOrder, clearing_price: u64) -> (u64, u64) {
+        let is_buy = order.is_buy == 1;
+        let buy_ok = is_buy & (clearing_price <= order.limit_price);
+        let sell_ok = !is_buy & (clearing_price >= order.limit_price);
+        (
+            if buy_ok { order.amount } else { 0 },
+            if sell_ok { order.amount } else { 0 },
+        )
+    }
+
+    /// Revealed matched volume, then each slot's fill encrypted to its owner
+    pub type AuctionResult = (
+        u64,
+        Enc<Shared, u64>,
+        Enc<Shared, u64>,
+        Enc<Shared, u64>,
+        Enc<Shared, u64>,
+    );
+
+    /// Fill `wanted` out of the side's remaining matched volume
+    fn auction_fill(wanted: u64, remaining: u64) -> u64 {
+        if wanted <= remaining {
+            wanted
+        } else {
+            remaining
+        }
+    }
+
+    /// Clear a batch of four sealed orders at one price. Buys and sells whose
+    /// limits accept the clearing price are matched up to the smaller side's
+    /// volume, filling orders in slot order. Only the matched volume is
+    /// revealed; each owner gets its own fill encrypted to its key. Short
+    /// batches repeat an order; slots at or past `order_count` are ignored.
+    #[instruction]
+    pub fn run_batch_auction(
+        order_0: Enc<Shared, SealedOrder>,
+        order_1: Enc<Shared, SealedOrder>,
+        order_2: Enc<Shared, SealedOrder>,
+        order_3: Enc<Shared, SealedOrder>,
+        clearing_price: u64,
+        order_count: u64,
+    ) -> AuctionResult {
+        let orders = [
+            order_0.to_arcis(),
+            order_1.to_arcis(),
+            order_2.to_arcis(),
+            order_3.to_arcis(),
+        ];
+        let mut demand = [(0u64, 0u64); 4];
+        let mut buy_volume = 0u64;
+        let mut sell_volume = 0u64;
+        for i in 0..4 {
+            if (i as u64) < order_count {
+                demand[i] = auction_demand(orders[i], clearing_price);
+            }
+            buy_volume += demand[i].0;
+            sell_volume += demand[i].1;
+        }
+        let matched = auction_fill(buy_volume, sell_volume);
+
+        let mut buys_left = matched;
+        let mut sells_left = matched;
+        let mut fills = [0u64; 4];
+        for i in 0..4 {
+            let (buy, sell) = demand[i];
+            let buy_fill = auction_fill(buy, buys_left);
+            let sell_fill = auction_fill(sell, sells_left);
+            buys_left -= buy_fill;
+            sells_left -= sell_fill;
+            fills[i] = buy_fill + sell_fill;
+        }
+
+        (
+            matched.reveal(),
+            order_0.owner.from_arcis(fills[0]),
+            order_1.owner.from_arcis(fills[1]),
+            order_2.owner.from_arcis(fills[2]),
+            order_3.owner.from_arcis(fills[3]),
+        )
+    }
+
+    /// Pick the best of up to four market makers' encrypted RFQ quotes. The
+    /// highest output wins if it meets the requester's encrypted minimum;
+    /// only whether a quote won, its slot and its amount are revealed, so
+    /// losing quotes stay private. Slots at or past `quote_count` are ignored.
+    #[instruction]
+    pub fn select_rfq_quote(
+        min_out: Enc<Shared, u64>,
+        quote_0: Enc<Shared, u64>,
+        quote_1: Enc<Shared, u64>,
+        quote_2: Enc<Shared, u64>,
+        quote_3: Enc<Shared, u64>,
+        quote_count: u64,
+    ) -> (bool, u64, u64) {
+        let min_out = min_out.to_arcis();
+        let quotes = [
+            quote_0.to_arcis(),
+            quote_1.to_arcis(),
+            quote_2.to_arcis(),
+            quote_3.to_arcis(),
+        ];
+        let mut best = 0u64;
+        let mut best_slot = 0u64;
+        for (i, quote) in quotes.iter().enumerate() {
+            if ((i as u64) < quote_count) & (*quote > best) {
+                best = *quote;
+                best_slot = i as u64;
+            }
+        }
+        let filled = (best > 0) & (best >= min_out);
+
+        (
+            filled.reveal(),
+            (if filled { best_slot } else { 0 }).reveal(),
+            (if filled { best } else { 0 }).reveal(),
+        )
     }
 }
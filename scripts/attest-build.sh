@@ -0,0 +1,35 @@
+#!/bin/bash
+
+# ZYNCX Verifiable Build Attestation
+# Builds the program deterministically with solana-verify and prints the
+# executable hash to record on-chain with the `attest_build` instruction.
+#
+# Usage: ./scripts/attest-build.sh [cluster-url]
+
+set -e
+
+CLUSTER_URL=${1:-devnet}
+PROGRAM_ID=$(grep -A1 "\[programs.devnet\]" Anchor.toml | grep zyncx | cut -d'"' -f2)
+
+if ! command -v solana-verify &> /dev/null; then
+    echo "solana-verify not found. Install it with: cargo install solana-verify"
+    exit 1
+fi
+
+echo "Building program in the verifiable build container..."
+solana-verify build --library-name zyncx
+
+BUILD_HASH=$(solana-verify get-executable-hash target/deploy/zyncx.so)
+echo "Local build hash:    $BUILD_HASH"
+
+DEPLOYED_HASH=$(solana-verify get-program-hash -u "$CLUSTER_URL" "$PROGRAM_ID" || echo "unavailable")
+echo "Deployed build hash: $DEPLOYED_HASH"
+
+if [[ "$BUILD_HASH" != "$DEPLOYED_HASH" ]]; then
+    echo "Deployed binary does not match this source tree. Not attesting."
+    exit 1
+fi
+
+echo ""
+echo "Hashes match. Record it with attest_build (upgrade authority):"
+echo "  build_hash = 0x$BUILD_HASH"
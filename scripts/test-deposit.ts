@@ -58,7 +58,10 @@ async function main() {
     const tx = await program.methods
       .depositNative(
         amount,
-        Array.from(precommitment)
+        Array.from(precommitment),
+        Buffer.alloc(0),
+        Buffer.alloc(0),
+        false
       )
       .accounts({
         depositor: wallet.publicKey,
@@ -17,7 +17,10 @@ import {
   getAssociatedTokenAddress,
   createAssociatedTokenAccountInstruction,
 } from "@solana/spl-token";
+import { keccak_256 } from "@noble/hashes/sha3";
 import crypto from "crypto";
+import fs from "fs";
+import path from "path";
 
 // Type helper to bypass Anchor's strict account type inference
 type Accounts = Record<string, PublicKey>;
@@ -42,6 +45,42 @@ function generateMockProof(): Buffer {
   return crypto.randomBytes(256);
 }
 
+/**
+ * Withdrawal terms for mock-proof withdrawals: no relayer tip, any fee.
+ * The hash is left zero since mock proofs never reach the payout anyway.
+ */
+function mockExtData() {
+  return {
+    maxFee: new BN("18446744073709551615"),
+    relayerTip: new BN(0),
+    viewingCiphertext: Buffer.alloc(0),
+    ephemeralPubkey: Array(32).fill(0),
+    feeScheduleHash: Array(32).fill(0),
+    extDataHash: Array(32).fill(0),
+  };
+}
+
+/**
+ * mockExtData() with its hash bound to `recipient` and `relayer`, as
+ * WithdrawExtData::hash computes it, for withdrawals that reach the payout
+ */
+function boundExtData(recipient: PublicKey, relayer: PublicKey) {
+  const extData = mockExtData();
+  const hash = keccak_256(
+    Buffer.concat([
+      recipient.toBuffer(),
+      relayer.toBuffer(),
+      extData.maxFee.toArrayLike(Buffer, "le", 8),
+      extData.relayerTip.toArrayLike(Buffer, "le", 8),
+      keccak_256(extData.viewingCiphertext),
+      Buffer.from(extData.ephemeralPubkey),
+      Buffer.from(extData.feeScheduleHash),
+    ])
+  );
+  hash[0] = 0;
+  return { ...extData, extDataHash: Array.from(hash) };
+}
+
 /**
  * Sleep for specified milliseconds
  */
@@ -64,6 +103,42 @@ async function airdrop(
   await provider.connection.confirmTransaction(sig, "confirmed");
 }
 
+/**
+ * Compute-unit ceilings declared in crates/zyncx-sdk/src/budgets.rs, read
+ * from the generated instruction table
+ */
+const COMPUTE_BUDGETS: Record<string, number> = Object.fromEntries(
+  JSON.parse(
+    fs.readFileSync(
+      path.join(__dirname, "../crates/zyncx-sdk/zyncx-instructions.json"),
+      "utf8"
+    )
+  )
+    .instructions.filter((ix: any) => ix.compute_budget !== null)
+    .map((ix: any) => [ix.name, ix.compute_budget])
+);
+
+/**
+ * Fail when a confirmed transaction consumed more compute units than the
+ * ceiling declared for `instruction`. Returns the units consumed.
+ */
+async function expectWithinComputeBudget(
+  provider: anchor.AnchorProvider,
+  signature: string,
+  instruction: string
+): Promise<number> {
+  const budget = COMPUTE_BUDGETS[instruction];
+  expect(budget, `no compute budget declared for ${instruction}`).to.be.a("number");
+
+  const tx = await provider.connection.getTransaction(signature, {
+    commitment: "confirmed",
+    maxSupportedTransactionVersion: 0,
+  });
+  const consumed = tx!.meta!.computeUnitsConsumed!;
+  expect(consumed, `${instruction} consumed ${consumed} CU, budget ${budget}`).to.be.at.most(budget);
+  return consumed;
+}
+
 // ============================================================================
 // TEST SUITE
 // ============================================================================
@@ -79,6 +154,16 @@ describe("Zyncx Privacy Protocol - Comprehensive Test Suite", () => {
   const JUPITER_PROGRAM_ID = new PublicKey(
     "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4"
   );
+  const BPF_LOADER_UPGRADEABLE = new PublicKey(
+    "BPFLoaderUpgradeab1e11111111111111111111111"
+  );
+  const NOIR_VERIFIER_PROGRAM_ID = new PublicKey(
+    "AWUEQfGnU2nVYAA3dfKpckDhqjoW6HELT5wvkg9Sve1y"
+  );
+
+  // Protocol role registry and config
+  let rolesPda: PublicKey;
+  let protocolConfigPda: PublicKey;
 
   // PDAs for native vault
   let nativeVaultPda: PublicKey;
@@ -179,6 +264,55 @@ describe("Zyncx Privacy Protocol - Comprehensive Test Suite", () => {
     console.log(" Derived token vault PDAs");
     console.log("   Vault:", tokenVaultPda.toString());
 
+    // Protocol roles: the test wallet holds every role
+    const [programData] = PublicKey.findProgramAddressSync(
+      [program.programId.toBuffer()],
+      BPF_LOADER_UPGRADEABLE
+    );
+    [rolesPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("roles")],
+      program.programId
+    );
+
+    await program.methods
+      .initializeRoles(
+        provider.wallet.publicKey,
+        provider.wallet.publicKey,
+        provider.wallet.publicKey,
+        provider.wallet.publicKey
+      )
+      .accounts({
+        authority: provider.wallet.publicKey,
+        roles: rolesPda,
+        program: program.programId,
+        programData,
+        systemProgram: SystemProgram.programId,
+      } as Accounts)
+      .rpc();
+    console.log(" Initialized protocol roles");
+
+    // Protocol config: pin the verifier programs, no timelock on changes
+    [protocolConfigPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("protocol_config")],
+      program.programId
+    );
+
+    await program.methods
+      .initializeProtocolConfig(
+        NOIR_VERIFIER_PROGRAM_ID,
+        new PublicKey("4fxK8aGf8D4k4qLRhSfkGARMx6WWJoHP9JaiGX4qKeke"),
+        new PublicKey("2Xxnd8BJg3x5p7xgSX2gxGWveaJvSnmPpm4mXP2tdwF7"),
+        new BN(0)
+      )
+      .accounts({
+        admin: provider.wallet.publicKey,
+        roles: rolesPda,
+        protocolConfig: protocolConfigPda,
+        systemProgram: SystemProgram.programId,
+      } as Accounts)
+      .rpc();
+    console.log(" Initialized protocol config");
+
     console.log("\n" + "=".repeat(60) + "\n");
   });
 
@@ -253,6 +387,33 @@ describe("Zyncx Privacy Protocol - Comprehensive Test Suite", () => {
         expect(err.toString()).to.include("already in use");
       }
     });
+
+    it("1.4 Should reject a WSOL vault next to the native one", async () => {
+      const wsolMint = new PublicKey("So11111111111111111111111111111111111111112");
+      const [wsolVaultPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("vault"), wsolMint.toBuffer()],
+        program.programId
+      );
+      const [wsolMerkleTreePda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("merkle_tree"), wsolVaultPda.toBuffer()],
+        program.programId
+      );
+
+      try {
+        await program.methods
+          .initializeVault(wsolMint)
+          .accounts({
+            authority: provider.wallet.publicKey,
+            vault: wsolVaultPda,
+            merkleTree: wsolMerkleTreePda,
+            systemProgram: SystemProgram.programId,
+          } as Accounts)
+          .rpc();
+        expect.fail("Should have thrown an error");
+      } catch (err: any) {
+        expect(err.toString()).to.include("NonCanonicalAssetMint");
+      }
+    });
   });
 
   // ============================================================================
@@ -271,7 +432,7 @@ describe("Zyncx Privacy Protocol - Comprehensive Test Suite", () => {
       );
 
       const tx = await program.methods
-        .depositNative(depositAmount, precommitment)
+        .depositNative(depositAmount, precommitment, Buffer.alloc(0), Buffer.alloc(0), false)
         .accounts({
           depositor: provider.wallet.publicKey,
           vault: nativeVaultPda,
@@ -314,7 +475,7 @@ describe("Zyncx Privacy Protocol - Comprehensive Test Suite", () => {
         commitments.push(precommitment);
 
         await program.methods
-          .depositNative(depositAmount, precommitment)
+          .depositNative(depositAmount, precommitment, Buffer.alloc(0), Buffer.alloc(0), false)
           .accounts({
             depositor: provider.wallet.publicKey,
             vault: nativeVaultPda,
@@ -343,7 +504,7 @@ describe("Zyncx Privacy Protocol - Comprehensive Test Suite", () => {
 
       try {
         await program.methods
-          .depositNative(new BN(0), precommitment)
+          .depositNative(new BN(0), precommitment, Buffer.alloc(0), Buffer.alloc(0), false)
           .accounts({
             depositor: provider.wallet.publicKey,
             vault: nativeVaultPda,
@@ -364,7 +525,7 @@ describe("Zyncx Privacy Protocol - Comprehensive Test Suite", () => {
       commitments.push(precommitment);
 
       const tx = await program.methods
-        .depositNative(depositAmount, precommitment)
+        .depositNative(depositAmount, precommitment, Buffer.alloc(0), Buffer.alloc(0), false)
         .accounts({
           depositor: user1.publicKey,
           vault: nativeVaultPda,
@@ -382,6 +543,30 @@ describe("Zyncx Privacy Protocol - Comprehensive Test Suite", () => {
       );
       expect(merkleTreeAccount.size.toNumber()).to.equal(5);
     });
+
+    it("2.5 Should reject an oversized encrypted note", async () => {
+      try {
+        await program.methods
+          .depositNative(
+            new BN(0.01 * LAMPORTS_PER_SOL),
+            generateRandomBytes32(),
+            crypto.randomBytes(257),
+            Buffer.alloc(0),
+            false
+          )
+          .accounts({
+            depositor: provider.wallet.publicKey,
+            vault: nativeVaultPda,
+            merkleTree: nativeMerkleTreePda,
+            vaultTreasury: nativeVaultTreasuryPda,
+            systemProgram: SystemProgram.programId,
+          } as Accounts)
+          .rpc();
+        expect.fail("Should have thrown an error");
+      } catch (err: any) {
+        expect(err.toString()).to.include("EncryptedNoteTooLarge");
+      }
+    });
   });
 
   // ============================================================================
@@ -432,7 +617,7 @@ describe("Zyncx Privacy Protocol - Comprehensive Test Suite", () => {
       commitments.push(precommitment);
 
       await program.methods
-        .depositNative(new BN(0.1 * LAMPORTS_PER_SOL), precommitment)
+        .depositNative(new BN(0.1 * LAMPORTS_PER_SOL), precommitment, Buffer.alloc(0), Buffer.alloc(0), false)
         .accounts({
           depositor: provider.wallet.publicKey,
           vault: nativeVaultPda,
@@ -453,6 +638,42 @@ describe("Zyncx Privacy Protocol - Comprehensive Test Suite", () => {
 
       expect(oldRootExists).to.be.true;
     });
+
+    it("3.4 Should not store consecutive duplicate roots in history", async () => {
+      const merkleTree = await program.account.merkleTreeState.fetch(
+        nativeMerkleTreePda
+      );
+      const roots = merkleTree.roots.map((r: number[]) => Buffer.from(r).toString("hex"));
+      const zero = Buffer.alloc(32).toString("hex");
+
+      for (let i = 0; i < roots.length; i++) {
+        const prev = roots[(i + roots.length - 1) % roots.length];
+        if (roots[i] !== zero) {
+          expect(roots[i]).to.not.equal(prev);
+        }
+      }
+    });
+
+    it("3.5 Should accept every root still in the history window", async () => {
+      const merkleTree = await program.account.merkleTreeState.fetch(
+        nativeMerkleTreePda
+      );
+      const zero = Buffer.alloc(32).toString("hex");
+
+      for (const root of merkleTree.roots) {
+        if (Buffer.from(root).toString("hex") === zero) continue;
+
+        const exists = await program.methods
+          .checkRoot(Array.from(root))
+          .accounts({
+            merkleTree: nativeMerkleTreePda,
+            vault: nativeVaultPda,
+          } as Accounts)
+          .view();
+
+        expect(exists).to.be.true;
+      }
+    });
   });
 
   // ============================================================================
@@ -489,11 +710,12 @@ describe("Zyncx Privacy Protocol - Comprehensive Test Suite", () => {
 
       try {
         const tx = await program.methods
-          .withdrawNative(withdrawAmount, withdrawNullifier, newCommitment, mockProof)
+          .withdrawNative(withdrawAmount, withdrawNullifier, newCommitment, new BN(0.1 * LAMPORTS_PER_SOL), mockProof, mockExtData(), Buffer.alloc(0))
           .accounts({
             recipient: user2.publicKey,
             vault: nativeVaultPda,
             merkleTree: nativeMerkleTreePda,
+            verifierProgram: NOIR_VERIFIER_PROGRAM_ID,
             vaultTreasury: nativeVaultTreasuryPda,
             nullifierAccount: nullifierPda,
             payer: provider.wallet.publicKey,
@@ -540,11 +762,12 @@ describe("Zyncx Privacy Protocol - Comprehensive Test Suite", () => {
 
       try {
         await program.methods
-          .withdrawNative(withdrawAmount, withdrawNullifier, newCommitment2, mockProof)
+          .withdrawNative(withdrawAmount, withdrawNullifier, newCommitment2, new BN(0.1 * LAMPORTS_PER_SOL), mockProof, mockExtData(), Buffer.alloc(0))
           .accounts({
             recipient: user2.publicKey,
             vault: nativeVaultPda,
             merkleTree: nativeMerkleTreePda,
+            verifierProgram: NOIR_VERIFIER_PROGRAM_ID,
             vaultTreasury: nativeVaultTreasuryPda,
             nullifierAccount: nullifierPda,
             payer: provider.wallet.publicKey,
@@ -575,11 +798,12 @@ describe("Zyncx Privacy Protocol - Comprehensive Test Suite", () => {
 
       try {
         await program.methods
-          .withdrawNative(new BN(0), freshNullifier, freshCommitment, mockProof)
+          .withdrawNative(new BN(0), freshNullifier, freshCommitment, new BN(0.1 * LAMPORTS_PER_SOL), mockProof, mockExtData(), Buffer.alloc(0))
           .accounts({
             recipient: user2.publicKey,
             vault: nativeVaultPda,
             merkleTree: nativeMerkleTreePda,
+            verifierProgram: NOIR_VERIFIER_PROGRAM_ID,
             vaultTreasury: nativeVaultTreasuryPda,
             nullifierAccount: nullifierPda,
             payer: provider.wallet.publicKey,
@@ -610,11 +834,12 @@ describe("Zyncx Privacy Protocol - Comprehensive Test Suite", () => {
 
       try {
         await program.methods
-          .withdrawNative(withdrawAmount, freshNullifier, freshCommitment, Buffer.from([]))
+          .withdrawNative(withdrawAmount, freshNullifier, freshCommitment, new BN(0.1 * LAMPORTS_PER_SOL), Buffer.from([]), mockExtData(), Buffer.alloc(0))
           .accounts({
             recipient: user2.publicKey,
             vault: nativeVaultPda,
             merkleTree: nativeMerkleTreePda,
+            verifierProgram: NOIR_VERIFIER_PROGRAM_ID,
             vaultTreasury: nativeVaultTreasuryPda,
             nullifierAccount: nullifierPda,
             payer: provider.wallet.publicKey,
@@ -643,10 +868,11 @@ describe("Zyncx Privacy Protocol - Comprehensive Test Suite", () => {
 
       try {
         const result = await program.methods
-          .verifyProof(amount, nullifier, newCommitment, mockProof)
+          .verifyProof(amount, nullifier, user1.publicKey, newCommitment, generateRandomBytes32(), new BN(0), mockProof)
           .accounts({
             vault: nativeVaultPda,
             merkleTree: nativeMerkleTreePda,
+            verifierProgram: NOIR_VERIFIER_PROGRAM_ID,
           } as Accounts)
           .view();
 
@@ -664,10 +890,11 @@ describe("Zyncx Privacy Protocol - Comprehensive Test Suite", () => {
 
       try {
         await program.methods
-          .verifyProof(amount, nullifier, newCommitment, Buffer.from([]))
+          .verifyProof(amount, nullifier, user1.publicKey, newCommitment, generateRandomBytes32(), new BN(0), Buffer.from([]))
           .accounts({
             vault: nativeVaultPda,
             merkleTree: nativeMerkleTreePda,
+            verifierProgram: NOIR_VERIFIER_PROGRAM_ID,
           } as Accounts)
           .view();
         expect.fail("Should have thrown an error");
@@ -693,6 +920,8 @@ describe("Zyncx Privacy Protocol - Comprehensive Test Suite", () => {
         amountIn: new BN(0.1 * LAMPORTS_PER_SOL),
         minAmountOut: new BN(1000000), // 1 token with 9 decimals
         fee: 30, // 0.3% fee in basis points
+        remainingAmount: new BN(0),
+        referrer: PublicKey.default,
       };
 
       expect(swapParam.amountIn.toNumber()).to.equal(0.1 * LAMPORTS_PER_SOL);
@@ -722,6 +951,8 @@ describe("Zyncx Privacy Protocol - Comprehensive Test Suite", () => {
         amountIn: new BN(0.1 * LAMPORTS_PER_SOL),
         minAmountOut: new BN(1000000),
         fee: 30,
+        remainingAmount: new BN(0),
+        referrer: PublicKey.default,
       };
 
       // This would require Jupiter program accounts
@@ -800,7 +1031,7 @@ describe("Zyncx Privacy Protocol - Comprehensive Test Suite", () => {
       );
 
       await program.methods
-        .depositNative(largeAmount, precommitment)
+        .depositNative(largeAmount, precommitment, Buffer.alloc(0), Buffer.alloc(0), false)
         .accounts({
           depositor: provider.wallet.publicKey,
           vault: nativeVaultPda,
@@ -826,7 +1057,7 @@ describe("Zyncx Privacy Protocol - Comprehensive Test Suite", () => {
       // Make 5 quick deposits
       for (let i = 0; i < 5; i++) {
         await program.methods
-          .depositNative(new BN(0.01 * LAMPORTS_PER_SOL), generateRandomBytes32())
+          .depositNative(new BN(0.01 * LAMPORTS_PER_SOL), generateRandomBytes32(), Buffer.alloc(0), Buffer.alloc(0), false)
           .accounts({
             depositor: provider.wallet.publicKey,
             vault: nativeVaultPda,
@@ -1093,7 +1324,7 @@ describe("Zyncx Privacy Protocol - Comprehensive Test Suite", () => {
       const precommitment = generateRandomBytes32();
       
       await program.methods
-        .depositNative(depositAmount, precommitment)
+        .depositNative(depositAmount, precommitment, Buffer.alloc(0), Buffer.alloc(0), false)
         .accounts({
           depositor: provider.wallet.publicKey,
           vault: nativeVaultPda,
@@ -1124,10 +1355,11 @@ describe("Zyncx Privacy Protocol - Comprehensive Test Suite", () => {
       const newCommitment = generateRandomBytes32();
 
       const proofValid = await program.methods
-        .verifyProof(depositAmount, nullifier, newCommitment, mockProof)
+        .verifyProof(depositAmount, nullifier, user1.publicKey, newCommitment, generateRandomBytes32(), new BN(0), mockProof)
         .accounts({
           vault: nativeVaultPda,
           merkleTree: nativeMerkleTreePda,
+          verifierProgram: NOIR_VERIFIER_PROGRAM_ID,
         } as Accounts)
         .view();
 
@@ -1157,6 +1389,4282 @@ describe("Zyncx Privacy Protocol - Comprehensive Test Suite", () => {
     });
   });
 
+  // ============================================================================
+  // 13. PROGRAM INFO TESTS
+  // ============================================================================
+
+  describe("13. Program Info", () => {
+    let programInfoPda: PublicKey;
+
+    before(() => {
+      [programInfoPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("program_info")],
+        program.programId
+      );
+    });
+
+    it("13.1 Should sync program version and feature bitmap", async () => {
+      await program.methods
+        .syncProgramInfo()
+        .accounts({
+          payer: provider.wallet.publicKey,
+          programInfo: programInfoPda,
+          systemProgram: SystemProgram.programId,
+        } as Accounts)
+        .rpc();
+
+      const info = await program.account.programInfo.fetch(programInfoPda);
+      expect(info.versionMajor).to.equal(0);
+      expect(info.versionMinor).to.equal(1);
+      expect(info.features.toNumber()).to.be.greaterThan(0);
+    });
+
+    it("13.2 Should allow re-syncing after an upgrade", async () => {
+      const before = await program.account.programInfo.fetch(programInfoPda);
+
+      await program.methods
+        .syncProgramInfo()
+        .accounts({
+          payer: user1.publicKey,
+          programInfo: programInfoPda,
+          systemProgram: SystemProgram.programId,
+        } as Accounts)
+        .signers([user1])
+        .rpc();
+
+      const after = await program.account.programInfo.fetch(programInfoPda);
+      expect(after.features.toString()).to.equal(before.features.toString());
+      expect(Array.from(after.gitHash)).to.deep.equal(Array.from(before.gitHash));
+    });
+  });
+
+  // ============================================================================
+  // 14. FIXED DENOMINATION TESTS
+  // ============================================================================
+
+  describe("14. Fixed Denominations", () => {
+    const denominations = [
+      new BN(0.1 * LAMPORTS_PER_SOL),
+      new BN(1 * LAMPORTS_PER_SOL),
+      new BN(10 * LAMPORTS_PER_SOL),
+    ];
+
+    it("14.1 Should enable fixed-denomination mode", async () => {
+      await program.methods
+        .setDenominations(true, denominations)
+        .accounts({
+          authority: provider.wallet.publicKey,
+          vault: nativeVaultPda,
+        } as Accounts)
+        .rpc();
+
+      const vault = await program.account.vaultState.fetch(nativeVaultPda);
+      expect(vault.fixedDenominations).to.be.true;
+      expect(vault.denominationCount).to.equal(3);
+    });
+
+    it("14.2 Should accept a deposit matching a denomination", async () => {
+      await program.methods
+        .depositNative(denominations[0], generateRandomBytes32(), Buffer.alloc(0), Buffer.alloc(0), false)
+        .accounts({
+          depositor: provider.wallet.publicKey,
+          vault: nativeVaultPda,
+          merkleTree: nativeMerkleTreePda,
+          vaultTreasury: nativeVaultTreasuryPda,
+          systemProgram: SystemProgram.programId,
+        } as Accounts)
+        .rpc();
+    });
+
+    it("14.3 Should reject a deposit outside the denominations", async () => {
+      try {
+        await program.methods
+          .depositNative(new BN(0.5 * LAMPORTS_PER_SOL), generateRandomBytes32(), Buffer.alloc(0), Buffer.alloc(0), false)
+          .accounts({
+            depositor: provider.wallet.publicKey,
+            vault: nativeVaultPda,
+            merkleTree: nativeMerkleTreePda,
+            vaultTreasury: nativeVaultTreasuryPda,
+            systemProgram: SystemProgram.programId,
+          } as Accounts)
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (error: any) {
+        expect(error.message).to.include("InvalidDenomination");
+      }
+    });
+
+    it("14.4 Should reject configuration changes from non-authority", async () => {
+      try {
+        await program.methods
+          .setDenominations(false, [])
+          .accounts({
+            authority: user1.publicKey,
+            vault: nativeVaultPda,
+          } as Accounts)
+          .signers([user1])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (error: any) {
+        expect(error.message).to.include("Unauthorized");
+      }
+    });
+
+    after(async () => {
+      await program.methods
+        .setDenominations(false, [])
+        .accounts({
+          authority: provider.wallet.publicKey,
+          vault: nativeVaultPda,
+        } as Accounts)
+        .rpc();
+    });
+  });
+
+  // ============================================================================
+  // 15. VAULT STATS TESTS
+  // ============================================================================
+
+  describe("15. Vault Stats", () => {
+    let vaultStatsPda: PublicKey;
+
+    before(() => {
+      [vaultStatsPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("vault_stats"), nativeVaultPda.toBuffer()],
+        program.programId
+      );
+    });
+
+    it("15.1 Should track notes created by deposits", async () => {
+      const before = await program.account.vaultStats.fetch(vaultStatsPda);
+
+      await program.methods
+        .depositNative(new BN(0.1 * LAMPORTS_PER_SOL), generateRandomBytes32(), Buffer.alloc(0), Buffer.alloc(0), false)
+        .accounts({
+          depositor: provider.wallet.publicKey,
+          vault: nativeVaultPda,
+          merkleTree: nativeMerkleTreePda,
+          vaultTreasury: nativeVaultTreasuryPda,
+          vaultStats: vaultStatsPda,
+          systemProgram: SystemProgram.programId,
+        } as Accounts)
+        .rpc();
+
+      const after = await program.account.vaultStats.fetch(vaultStatsPda);
+      expect(after.vault.toBase58()).to.equal(nativeVaultPda.toBase58());
+      expect(after.totalNotes.toNumber()).to.equal(before.totalNotes.toNumber() + 1);
+      expect(after.currentEpochDeposits).to.be.greaterThan(0);
+      expect(after.uniqueDepositEpochs.toNumber()).to.be.greaterThan(0);
+    });
+
+    it("15.2 Should never report more spent than created notes", async () => {
+      const stats = await program.account.vaultStats.fetch(vaultStatsPda);
+      expect(stats.spentNotes.toNumber()).to.be.at.most(stats.totalNotes.toNumber());
+      console.log(
+        "   Anonymity set:",
+        stats.totalNotes.toNumber() - stats.spentNotes.toNumber()
+      );
+    });
+
+    it("15.3 Should accumulate the time-weighted anonymity set", async () => {
+      const before = await program.account.vaultStats.fetch(vaultStatsPda);
+
+      await sleep(1000);
+      await program.methods
+        .depositNative(new BN(0.1 * LAMPORTS_PER_SOL), generateRandomBytes32(), Buffer.alloc(0), Buffer.alloc(0), false)
+        .accounts({
+          depositor: provider.wallet.publicKey,
+          vault: nativeVaultPda,
+          merkleTree: nativeMerkleTreePda,
+          vaultTreasury: nativeVaultTreasuryPda,
+          vaultStats: vaultStatsPda,
+          systemProgram: SystemProgram.programId,
+        } as Accounts)
+        .rpc();
+
+      const after = await program.account.vaultStats.fetch(vaultStatsPda);
+      expect(after.anonymitySetSeconds.gte(before.anonymitySetSeconds)).to.be.true;
+      expect(after.updatedAt.toNumber()).to.be.at.least(before.updatedAt.toNumber());
+    });
+  });
+
+  // ============================================================================
+  // 16. EXCLUSION ROOT TESTS
+  // ============================================================================
+
+  describe("16. Exclusion Root", () => {
+    const exclusionVerifier = new PublicKey(
+      "AWUEQfGnU2nVYAA3dfKpckDhqjoW6HELT5wvkg9Sve1y"
+    );
+    let exclusionRootPda: PublicKey;
+
+    before(() => {
+      [exclusionRootPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("exclusion_root"), nativeVaultPda.toBuffer()],
+        program.programId
+      );
+    });
+
+    it("16.1 Should publish an exclusion root", async () => {
+      const root = generateRandomBytes32();
+
+      await program.methods
+        .setExclusionRoot(root, exclusionVerifier)
+        .accounts({
+          authority: provider.wallet.publicKey,
+          vault: nativeVaultPda,
+          exclusionRoot: exclusionRootPda,
+          systemProgram: SystemProgram.programId,
+        } as Accounts)
+        .rpc();
+
+      const account = await program.account.exclusionRoot.fetch(exclusionRootPda);
+      expect(Array.from(account.root)).to.deep.equal(root);
+      expect(account.vault.toBase58()).to.equal(nativeVaultPda.toBase58());
+    });
+
+    it("16.2 Should reject exclusion root updates from non-authority", async () => {
+      try {
+        await program.methods
+          .setExclusionRoot(generateRandomBytes32(), exclusionVerifier)
+          .accounts({
+            authority: user1.publicKey,
+            vault: nativeVaultPda,
+            exclusionRoot: exclusionRootPda,
+            systemProgram: SystemProgram.programId,
+          } as Accounts)
+          .signers([user1])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (error: any) {
+        expect(error.message).to.include("Unauthorized");
+      }
+    });
+  });
+
+  // ============================================================================
+  // 17. ACCOUNT PLANNING TESTS
+  // ============================================================================
+
+  describe("17. Account Planning", () => {
+    it("17.1 Should plan native withdrawal accounts in instruction order", async () => {
+      const nullifier = generateRandomBytes32();
+
+      const plan = await program.methods
+        .planWithdrawal(nullifier, user2.publicKey, provider.wallet.publicKey)
+        .accounts({
+          vault: nativeVaultPda,
+          exclusionRoot: null,
+          treasuryPolicy: null,
+          allowancePolicy: null,
+        } as Accounts)
+        .view();
+
+      const [nullifierPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("nullifier"), nativeVaultPda.toBuffer(), Buffer.from(nullifier)],
+        program.programId
+      );
+
+      expect(plan[0].pubkey.toBase58()).to.equal(user2.publicKey.toBase58());
+      expect(plan[1].pubkey.toBase58()).to.equal(nativeVaultPda.toBase58());
+      expect(plan[3].pubkey.toBase58()).to.equal(nativeVaultTreasuryPda.toBase58());
+      expect(plan[4].pubkey.toBase58()).to.equal(nullifierPda.toBase58());
+      expect(plan[4].isWritable).to.be.true;
+
+      const payer = plan.find((a: any) => a.isSigner);
+      expect(payer.pubkey.toBase58()).to.equal(provider.wallet.publicKey.toBase58());
+    });
+
+    it("17.2 Should plan native swap accounts", async () => {
+      const plan = await program.methods
+        .planSwap(generateRandomBytes32(), user2.publicKey, provider.wallet.publicKey)
+        .accounts({
+          vault: nativeVaultPda,
+          exclusionRoot: null,
+          treasuryPolicy: null,
+          allowancePolicy: null,
+        } as Accounts)
+        .view();
+
+      expect(plan.length).to.equal(14);
+      expect(plan[7].pubkey.toBase58()).to.equal(protocolConfigPda.toBase58());
+      expect(plan[8].pubkey.toBase58()).to.equal(NOIR_VERIFIER_PROGRAM_ID.toBase58());
+      expect(plan[plan.length - 1].pubkey.toBase58()).to.equal(
+        SystemProgram.programId.toBase58()
+      );
+    });
+  });
+
+  // ============================================================================
+  // 18. RECIPIENT BLOCKLIST TESTS
+  // ============================================================================
+
+  describe("18. Recipient Blocklist", () => {
+    let blocklistPda: PublicKey;
+
+    before(() => {
+      [blocklistPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("blocklist")],
+        program.programId
+      );
+    });
+
+    it("18.1 Should initialize blocklist as compliance role", async () => {
+      await program.methods
+        .initializeBlocklist()
+        .accounts({
+          authority: provider.wallet.publicKey,
+          blocklist: blocklistPda,
+          roles: rolesPda,
+          systemProgram: SystemProgram.programId,
+        } as Accounts)
+        .rpc();
+
+      const blocklist = await program.account.blocklist.fetch(blocklistPda);
+      expect(blocklist.blocked.length).to.equal(0);
+    });
+
+    it("18.2 Should add and remove a blocked recipient", async () => {
+      const blocked = Keypair.generate().publicKey;
+
+      await program.methods
+        .addBlocked(blocked)
+        .accounts({
+          authority: provider.wallet.publicKey,
+          blocklist: blocklistPda,
+          roles: rolesPda,
+        } as Accounts)
+        .rpc();
+
+      let blocklist = await program.account.blocklist.fetch(blocklistPda);
+      expect(blocklist.blocked.map((k: PublicKey) => k.toBase58())).to.include(blocked.toBase58());
+
+      await program.methods
+        .removeBlocked(blocked)
+        .accounts({
+          authority: provider.wallet.publicKey,
+          blocklist: blocklistPda,
+          roles: rolesPda,
+        } as Accounts)
+        .rpc();
+
+      blocklist = await program.account.blocklist.fetch(blocklistPda);
+      expect(blocklist.blocked.length).to.equal(0);
+    });
+
+    it("18.3 Should reject blocklist edits from non-compliance authority", async () => {
+      try {
+        await program.methods
+          .addBlocked(user2.publicKey)
+          .accounts({
+            authority: user1.publicKey,
+            blocklist: blocklistPda,
+            roles: rolesPda,
+          } as Accounts)
+          .signers([user1])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (error: any) {
+        expect(error.message).to.include("Unauthorized");
+      }
+    });
+  });
+
+  // ============================================================================
+  // 19. DYNAMIC FEE TESTS
+  // ============================================================================
+
+  describe("19. Dynamic Withdrawal Fees", () => {
+    let vaultFeesPda: PublicKey;
+    let pendingConfigPda: PublicKey;
+
+    const applyFeeCurve = async (params: any) => {
+      await program.methods
+        .proposeConfigChange({ feeCurve: { 0: params } })
+        .accounts({
+          authority: provider.wallet.publicKey,
+          vault: nativeVaultPda,
+          pendingChange: pendingConfigPda,
+          systemProgram: SystemProgram.programId,
+        } as Accounts)
+        .rpc();
+
+      await program.methods
+        .executeConfigChange()
+        .accounts({
+          authority: provider.wallet.publicKey,
+          vault: nativeVaultPda,
+          pendingChange: pendingConfigPda,
+          vaultFees: vaultFeesPda,
+          exclusionRoot: null,
+          treasuryPolicy: null,
+          systemProgram: SystemProgram.programId,
+        } as Accounts)
+        .rpc();
+    };
+
+    before(() => {
+      [vaultFeesPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("vault_fees"), nativeVaultPda.toBuffer()],
+        program.programId
+      );
+      [pendingConfigPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("pending_config"), nativeVaultPda.toBuffer()],
+        program.programId
+      );
+    });
+
+    it("19.1 Should configure the fee curve through the timelock", async () => {
+      await applyFeeCurve({
+        enabled: true,
+        baseFeeBps: 10,
+        maxFeeBps: 200,
+        kinkUtilizationBps: 2_000,
+        windowSeconds: new BN(86_400),
+      });
+
+      const fees = await program.account.vaultFeeState.fetch(vaultFeesPda);
+      expect(fees.dynamicFeesEnabled).to.be.true;
+      expect(fees.baseFeeBps).to.equal(10);
+      expect(fees.maxFeeBps).to.equal(200);
+    });
+
+    it("19.2 Should reject a fee curve above the hard cap", async () => {
+      try {
+        await program.methods
+          .proposeConfigChange({
+            feeCurve: {
+              0: {
+                enabled: true,
+                baseFeeBps: 10,
+                maxFeeBps: 5_000,
+                kinkUtilizationBps: 2_000,
+                windowSeconds: new BN(86_400),
+              },
+            },
+          })
+          .accounts({
+            authority: provider.wallet.publicKey,
+            vault: nativeVaultPda,
+            pendingChange: pendingConfigPda,
+            systemProgram: SystemProgram.programId,
+          } as Accounts)
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (error: any) {
+        expect(error.message).to.include("InvalidFeeConfig");
+      }
+    });
+
+    it("19.3 Should not execute a change before its timelock elapses", async () => {
+      // Raise the token vault's timelock: section 53 still reconfigures the
+      // native vault
+      const [tokenVaultFeesPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("vault_fees"), tokenVaultPda.toBuffer()],
+        program.programId
+      );
+      const [tokenPendingConfigPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("pending_config"), tokenVaultPda.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .increaseConfigTimelock(new BN(3_600))
+        .accounts({
+          authority: provider.wallet.publicKey,
+          vault: tokenVaultPda,
+        } as Accounts)
+        .rpc();
+
+      await program.methods
+        .proposeConfigChange({ timelock: { 0: new BN(0) } })
+        .accounts({
+          authority: provider.wallet.publicKey,
+          vault: tokenVaultPda,
+          pendingChange: tokenPendingConfigPda,
+          systemProgram: SystemProgram.programId,
+        } as Accounts)
+        .rpc();
+
+      try {
+        await program.methods
+          .executeConfigChange()
+          .accounts({
+            authority: provider.wallet.publicKey,
+            vault: tokenVaultPda,
+            pendingChange: tokenPendingConfigPda,
+            vaultFees: tokenVaultFeesPda,
+            exclusionRoot: null,
+            treasuryPolicy: null,
+            systemProgram: SystemProgram.programId,
+          } as Accounts)
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (error: any) {
+        expect(error.message).to.include("TimelockNotElapsed");
+      }
+
+      await program.methods
+        .cancelConfigChange()
+        .accounts({
+          authority: provider.wallet.publicKey,
+          vault: tokenVaultPda,
+          pendingChange: tokenPendingConfigPda,
+        } as Accounts)
+        .rpc();
+    });
+  });
+
+  // ============================================================================
+  // 20. ROLES & PAUSE TESTS
+  // ============================================================================
+
+  describe("20. Roles & Pause", () => {
+    it("20.1 Should reject vault creation by a non-admin", async () => {
+      const mint = Keypair.generate().publicKey;
+      const [vault] = PublicKey.findProgramAddressSync(
+        [Buffer.from("vault"), mint.toBuffer()],
+        program.programId
+      );
+      const [merkleTree] = PublicKey.findProgramAddressSync(
+        [Buffer.from("merkle_tree"), vault.toBuffer()],
+        program.programId
+      );
+
+      try {
+        await program.methods
+          .initializeVault(mint)
+          .accounts({
+            authority: user1.publicKey,
+            roles: rolesPda,
+            vault,
+            merkleTree,
+            systemProgram: SystemProgram.programId,
+          } as Accounts)
+          .signers([user1])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (error: any) {
+        expect(error.message).to.include("Unauthorized");
+      }
+    });
+
+    it("20.2 Should block deposits while a vault is paused", async () => {
+      await program.methods
+        .setVaultPaused(true)
+        .accounts({
+          pauser: provider.wallet.publicKey,
+          roles: rolesPda,
+          vault: nativeVaultPda,
+        } as Accounts)
+        .rpc();
+
+      try {
+        await program.methods
+          .depositNative(new BN(0.1 * LAMPORTS_PER_SOL), generateRandomBytes32(), Buffer.alloc(0), Buffer.alloc(0), false)
+          .accounts({
+            depositor: provider.wallet.publicKey,
+            vault: nativeVaultPda,
+            merkleTree: nativeMerkleTreePda,
+            vaultTreasury: nativeVaultTreasuryPda,
+            systemProgram: SystemProgram.programId,
+          } as Accounts)
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (error: any) {
+        expect(error.message).to.include("VaultPaused");
+      }
+
+      await program.methods
+        .setVaultPaused(false)
+        .accounts({
+          pauser: provider.wallet.publicKey,
+          roles: rolesPda,
+          vault: nativeVaultPda,
+        } as Accounts)
+        .rpc();
+    });
+
+    it("20.3 Should reject pausing by a non-pauser", async () => {
+      try {
+        await program.methods
+          .setVaultPaused(true)
+          .accounts({
+            pauser: user1.publicKey,
+            roles: rolesPda,
+            vault: nativeVaultPda,
+          } as Accounts)
+          .signers([user1])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (error: any) {
+        expect(error.message).to.include("Unauthorized");
+      }
+    });
+  });
+
+  // ============================================================================
+  // 21. GLOBAL STATS TESTS
+  // ============================================================================
+
+  describe("21. Global Stats", () => {
+    let globalStatsPda: PublicKey;
+
+    before(() => {
+      [globalStatsPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("global_stats")],
+        program.programId
+      );
+    });
+
+    it("21.1 Should aggregate deposits across vaults", async () => {
+      const before = await program.account.globalStats.fetch(globalStatsPda);
+      const amount = new BN(0.1 * LAMPORTS_PER_SOL);
+
+      await program.methods
+        .depositNative(amount, generateRandomBytes32(), Buffer.alloc(0), Buffer.alloc(0), false)
+        .accounts({
+          depositor: provider.wallet.publicKey,
+          vault: nativeVaultPda,
+          merkleTree: nativeMerkleTreePda,
+          vaultTreasury: nativeVaultTreasuryPda,
+          globalStats: globalStatsPda,
+          systemProgram: SystemProgram.programId,
+        } as Accounts)
+        .rpc();
+
+      const after = await program.account.globalStats.fetch(globalStatsPda);
+      expect(after.depositCount.toNumber()).to.equal(before.depositCount.toNumber() + 1);
+      expect(after.totalDeposited.sub(before.totalDeposited).eq(amount)).to.be.true;
+      expect(after.activeVaults).to.be.greaterThan(0);
+    });
+
+    it("21.2 Should drop paused vaults from the active count", async () => {
+      const before = await program.account.globalStats.fetch(globalStatsPda);
+
+      await program.methods
+        .setVaultPaused(true)
+        .accounts({
+          pauser: provider.wallet.publicKey,
+          roles: rolesPda,
+          vault: nativeVaultPda,
+          globalStats: globalStatsPda,
+        } as Accounts)
+        .rpc();
+
+      const paused = await program.account.globalStats.fetch(globalStatsPda);
+      expect(paused.activeVaults).to.equal(before.activeVaults - 1);
+
+      await program.methods
+        .setVaultPaused(false)
+        .accounts({
+          pauser: provider.wallet.publicKey,
+          roles: rolesPda,
+          vault: nativeVaultPda,
+          globalStats: globalStatsPda,
+        } as Accounts)
+        .rpc();
+
+      const resumed = await program.account.globalStats.fetch(globalStatsPda);
+      expect(resumed.activeVaults).to.equal(before.activeVaults);
+    });
+  });
+
+  // ============================================================================
+  // 22. SWAP RECIPIENT BINDING TESTS
+  // ============================================================================
+
+  describe("22. Swap Recipient Binding", () => {
+    it("22.1 Should reject a swap paying an account other than the proof-bound recipient", async () => {
+      const nullifier = generateRandomBytes32();
+      const swapParam = {
+        srcToken: PublicKey.default,
+        dstToken: PublicKey.default,
+        recipient: user2.publicKey,
+        amountIn: new BN(0.1 * LAMPORTS_PER_SOL),
+        minAmountOut: new BN(0),
+        fee: 0,
+        remainingAmount: new BN(0),
+        referrer: PublicKey.default,
+      };
+
+      try {
+        await program.methods
+          .swapNative(swapParam, nullifier, Array(32).fill(0), Buffer.alloc(64), Buffer.alloc(0))
+          .accounts({
+            recipient: user1.publicKey,
+            vault: nativeVaultPda,
+            merkleTree: nativeMerkleTreePda,
+            verifierProgram: NOIR_VERIFIER_PROGRAM_ID,
+            vaultTreasury: nativeVaultTreasuryPda,
+            payer: provider.wallet.publicKey,
+            systemProgram: SystemProgram.programId,
+          } as Accounts)
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (error: any) {
+        expect(error.message).to.include("RecipientMismatch");
+      }
+    });
+  });
+
+  // ============================================================================
+  // 23. DEPOSIT LIMIT TESTS
+  // ============================================================================
+
+  describe("23. Deposit Limits", () => {
+    const setLimits = (total: number, perTx: number, perEpoch: number) =>
+      program.methods
+        .setDepositLimits(new BN(total), new BN(perTx), new BN(perEpoch))
+        .accounts({
+          authority: provider.wallet.publicKey,
+          vault: nativeVaultPda,
+        } as Accounts)
+        .rpc();
+
+    const deposit = (amount: number) =>
+      program.methods
+        .depositNative(new BN(amount), generateRandomBytes32(), Buffer.alloc(0), Buffer.alloc(0), false)
+        .accounts({
+          depositor: provider.wallet.publicKey,
+          vault: nativeVaultPda,
+          merkleTree: nativeMerkleTreePda,
+          vaultTreasury: nativeVaultTreasuryPda,
+          systemProgram: SystemProgram.programId,
+        } as Accounts)
+        .rpc();
+
+    after(async () => {
+      await setLimits(0, 0, 0);
+    });
+
+    it("23.1 Should reject deposits above the per-transaction limit", async () => {
+      await setLimits(0, 0.1 * LAMPORTS_PER_SOL, 0);
+
+      const vault = await program.account.vaultState.fetch(nativeVaultPda);
+      expect(vault.maxDepositPerTx.toNumber()).to.equal(0.1 * LAMPORTS_PER_SOL);
+
+      try {
+        await deposit(0.2 * LAMPORTS_PER_SOL);
+        expect.fail("Should have thrown");
+      } catch (error: any) {
+        expect(error.message).to.include("DepositTooLarge");
+      }
+    });
+
+    it("23.2 Should rate limit deposits within an epoch", async () => {
+      await setLimits(0, 0, 0.15 * LAMPORTS_PER_SOL);
+
+      const vault = await program.account.vaultState.fetch(nativeVaultPda);
+      const remaining = 0.15 * LAMPORTS_PER_SOL - vault.epochDeposited.toNumber();
+      if (remaining >= 0.1 * LAMPORTS_PER_SOL) {
+        await deposit(0.1 * LAMPORTS_PER_SOL);
+      }
+
+      try {
+        await deposit(0.1 * LAMPORTS_PER_SOL);
+        expect.fail("Should have thrown");
+      } catch (error: any) {
+        expect(error.message).to.include("DepositRateLimited");
+      }
+    });
+
+    it("23.3 Should enforce the total deposit cap", async () => {
+      const vault = await program.account.vaultState.fetch(nativeVaultPda);
+      await setLimits(vault.totalDeposited.toNumber(), 0, 0);
+
+      try {
+        await deposit(0.1 * LAMPORTS_PER_SOL);
+        expect.fail("Should have thrown");
+      } catch (error: any) {
+        expect(error.message).to.include("VaultDepositCapReached");
+      }
+    });
+  });
+
+  // ============================================================================
+  // 24. FEE EXEMPTION TESTS
+  // ============================================================================
+
+  describe("24. Fee Exemptions", () => {
+    let feeExemptionsPda: PublicKey;
+
+    before(() => {
+      [feeExemptionsPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("fee_exemptions")],
+        program.programId
+      );
+    });
+
+    it("24.1 Should initialize the exemption list as fee manager", async () => {
+      await program.methods
+        .initializeFeeExemptions()
+        .accounts({
+          authority: provider.wallet.publicKey,
+          feeExemptions: feeExemptionsPda,
+          roles: rolesPda,
+          systemProgram: SystemProgram.programId,
+        } as Accounts)
+        .rpc();
+
+      const list = await program.account.feeExemptions.fetch(feeExemptionsPda);
+      expect(list.exempt.length).to.equal(0);
+    });
+
+    it("24.2 Should add and remove an exempt integration", async () => {
+      const integration = Keypair.generate().publicKey;
+
+      await program.methods
+        .addFeeExemption(integration)
+        .accounts({
+          authority: provider.wallet.publicKey,
+          feeExemptions: feeExemptionsPda,
+          roles: rolesPda,
+        } as Accounts)
+        .rpc();
+
+      let list = await program.account.feeExemptions.fetch(feeExemptionsPda);
+      expect(list.exempt.map((k: PublicKey) => k.toBase58())).to.include(integration.toBase58());
+
+      await program.methods
+        .removeFeeExemption(integration)
+        .accounts({
+          authority: provider.wallet.publicKey,
+          feeExemptions: feeExemptionsPda,
+          roles: rolesPda,
+        } as Accounts)
+        .rpc();
+
+      list = await program.account.feeExemptions.fetch(feeExemptionsPda);
+      expect(list.exempt.length).to.equal(0);
+    });
+
+    it("24.3 Should reject exemptions from a non fee manager", async () => {
+      try {
+        await program.methods
+          .addFeeExemption(user1.publicKey)
+          .accounts({
+            authority: user1.publicKey,
+            feeExemptions: feeExemptionsPda,
+            roles: rolesPda,
+          } as Accounts)
+          .signers([user1])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (error: any) {
+        expect(error.message).to.include("Unauthorized");
+      }
+    });
+  });
+
+  // ============================================================================
+  // 25. VAULT TOKEN ACCOUNT TESTS
+  // ============================================================================
+
+  describe("25. Vault Token Account", () => {
+    const prepare = () =>
+      program.methods
+        .prepareVaultTokenAccount()
+        .accounts({
+          payer: provider.wallet.publicKey,
+          vault: tokenVaultPda,
+          mint: testTokenMint,
+          vaultTokenAccount: tokenVaultTokenAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        } as Accounts)
+        .rpc();
+
+    it("25.1 Should create the vault escrow token account idempotently", async () => {
+      await prepare();
+      await prepare();
+
+      const escrow = await getAccount(provider.connection, tokenVaultTokenAccount);
+      expect(escrow.mint.toBase58()).to.equal(testTokenMint.toBase58());
+      expect(escrow.owner.toBase58()).to.equal(tokenVaultTokenAccount.toBase58());
+    });
+
+    it("25.2 Should reject a mint that is not the vault asset", async () => {
+      const otherMint = await createMint(
+        provider.connection,
+        testTokenMintAuthority,
+        testTokenMintAuthority.publicKey,
+        null,
+        9
+      );
+
+      try {
+        await program.methods
+          .prepareVaultTokenAccount()
+          .accounts({
+            payer: provider.wallet.publicKey,
+            vault: tokenVaultPda,
+            mint: otherMint,
+            vaultTokenAccount: tokenVaultTokenAccount,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          } as Accounts)
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (error: any) {
+        expect(error.message).to.include("InvalidMint");
+      }
+    });
+  });
+
+  // ============================================================================
+  // 26. NOTE AGE TESTS
+  // ============================================================================
+
+  describe("26. Minimum Note Age", () => {
+    it("26.1 Should log each leaf with its insertion slot", async () => {
+      const vault = await program.account.vaultState.fetch(nativeVaultPda);
+      const signature = await program.methods
+        .depositNative(
+          new BN(0.01 * LAMPORTS_PER_SOL),
+          generateRandomBytes32(),
+          Buffer.alloc(0),
+          Buffer.alloc(0),
+          false
+        )
+        .accounts({
+          depositor: provider.wallet.publicKey,
+          vault: nativeVaultPda,
+          merkleTree: vault.merkleTree,
+          vaultTreasury: nativeVaultTreasuryPda,
+          systemProgram: SystemProgram.programId,
+        } as Accounts)
+        .rpc({ commitment: "confirmed" });
+
+      const confirmed = await provider.connection.getTransaction(signature, {
+        commitment: "confirmed",
+        maxSupportedTransactionVersion: 0,
+      });
+      const parser = new anchor.EventParser(program.programId, new anchor.BorshCoder(program.idl));
+      const inserted = Array.from(parser.parseLogs(confirmed!.meta!.logMessages!)).filter(
+        (event) => event.name === "leafInserted"
+      );
+      expect(inserted.length).to.equal(1);
+      expect(inserted[0].data.slot.toNumber()).to.equal(confirmed!.slot);
+    });
+
+    it("26.2 Should let the vault authority set the minimum note age", async () => {
+      await program.methods
+        .setMinNoteAge(new BN(1_000))
+        .accounts({
+          authority: provider.wallet.publicKey,
+          vault: nativeVaultPda,
+        } as Accounts)
+        .rpc();
+
+      let vault = await program.account.vaultState.fetch(nativeVaultPda);
+      expect(vault.minNoteAgeSlots.toNumber()).to.equal(1_000);
+
+      await program.methods
+        .setMinNoteAge(new BN(0))
+        .accounts({
+          authority: provider.wallet.publicKey,
+          vault: nativeVaultPda,
+        } as Accounts)
+        .rpc();
+
+      vault = await program.account.vaultState.fetch(nativeVaultPda);
+      expect(vault.minNoteAgeSlots.toNumber()).to.equal(0);
+    });
+
+    it("26.3 Should reject note age changes from non-authority", async () => {
+      try {
+        await program.methods
+          .setMinNoteAge(new BN(1))
+          .accounts({
+            authority: user1.publicKey,
+            vault: nativeVaultPda,
+          } as Accounts)
+          .signers([user1])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (error: any) {
+        expect(error.message).to.include("Unauthorized");
+      }
+    });
+  });
+
+  // ============================================================================
+  // 27. HYBRID ORDER TESTS
+  // ============================================================================
+
+  describe("27. Hybrid TWAP/Limit Orders", () => {
+    const orderId = new BN(1);
+    const [hybridOrderPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("hybrid_order"),
+        provider.wallet.publicKey.toBuffer(),
+        orderId.toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+    const priceFeed = Keypair.generate().publicKey;
+
+    it("27.1 Should place a hybrid order with encrypted params", async () => {
+      const encryptedParams = [
+        Array.from(generateRandomBytes32()),
+        Array.from(generateRandomBytes32()),
+        Array.from(generateRandomBytes32()),
+      ];
+
+      await program.methods
+        .placeHybridOrder(
+          orderId,
+          encryptedParams,
+          new BN(42),
+          Array.from(generateRandomBytes32())
+        )
+        .accounts({
+          user: provider.wallet.publicKey,
+          sourceVault: nativeVaultPda,
+          destVault: tokenVaultPda,
+          priceFeed,
+          hybridOrder: hybridOrderPda,
+          systemProgram: SystemProgram.programId,
+        } as Accounts)
+        .rpc();
+
+      const order = await program.account.encryptedHybridOrder.fetch(hybridOrderPda);
+      expect(order.user.toBase58()).to.equal(provider.wallet.publicKey.toBase58());
+      expect(order.priceFeed.toBase58()).to.equal(priceFeed.toBase58());
+      expect(order.status).to.deep.equal({ active: {} });
+      expect(order.evaluations).to.equal(0);
+    });
+
+    it("27.2 Should reject an order between the same vault", async () => {
+      const [samePda] = PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("hybrid_order"),
+          provider.wallet.publicKey.toBuffer(),
+          new BN(2).toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      );
+
+      try {
+        await program.methods
+          .placeHybridOrder(
+            new BN(2),
+            [
+              Array.from(generateRandomBytes32()),
+              Array.from(generateRandomBytes32()),
+              Array.from(generateRandomBytes32()),
+            ],
+            new BN(1),
+            Array.from(generateRandomBytes32())
+          )
+          .accounts({
+            user: provider.wallet.publicKey,
+            sourceVault: nativeVaultPda,
+            destVault: nativeVaultPda,
+            priceFeed,
+            hybridOrder: samePda,
+            systemProgram: SystemProgram.programId,
+          } as Accounts)
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (error: any) {
+        expect(error.message).to.include("InvalidSwapRoute");
+      }
+    });
+
+    it("27.3 Should cancel the order and close its account", async () => {
+      await program.methods
+        .cancelHybridOrder()
+        .accounts({
+          user: provider.wallet.publicKey,
+          hybridOrder: hybridOrderPda,
+        } as Accounts)
+        .rpc();
+
+      const info = await provider.connection.getAccountInfo(hybridOrderPda);
+      expect(info).to.be.null;
+    });
+  });
+
+  // ============================================================================
+  // 28. LIMIT ORDER TESTS
+  // ============================================================================
+
+  describe("28. Limit Orders", () => {
+    const orderId = new BN(1);
+    const [limitOrderPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("limit_order"),
+        provider.wallet.publicKey.toBuffer(),
+        orderId.toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+    const priceFeed = Keypair.generate().publicKey;
+    const encryptedParams = () => [
+      Array.from(generateRandomBytes32()),
+      Array.from(generateRandomBytes32()),
+      Array.from(generateRandomBytes32()),
+    ];
+
+    it("28.1 Should place a limit order", async () => {
+      const expiresAt = new BN(Math.floor(Date.now() / 1000) + 3600);
+
+      await program.methods
+        .placeLimitOrder(
+          orderId,
+          encryptedParams(),
+          new BN(7),
+          Array.from(generateRandomBytes32()),
+          expiresAt
+        )
+        .accounts({
+          user: provider.wallet.publicKey,
+          sourceVault: nativeVaultPda,
+          destVault: tokenVaultPda,
+          priceFeed,
+          limitOrder: limitOrderPda,
+          systemProgram: SystemProgram.programId,
+        } as Accounts)
+        .rpc();
+
+      const order = await program.account.encryptedLimitOrder.fetch(limitOrderPda);
+      expect(order.orderId.toNumber()).to.equal(1);
+      expect(order.expiresAt.toNumber()).to.equal(expiresAt.toNumber());
+      expect(order.priceFeed.toBase58()).to.equal(priceFeed.toBase58());
+      expect(order.status).to.deep.equal({ active: {} });
+    });
+
+    it("28.2 Should reject an already expired order", async () => {
+      const [expiredPda] = PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("limit_order"),
+          provider.wallet.publicKey.toBuffer(),
+          new BN(2).toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      );
+
+      try {
+        await program.methods
+          .placeLimitOrder(
+            new BN(2),
+            encryptedParams(),
+            new BN(7),
+            Array.from(generateRandomBytes32()),
+            new BN(1)
+          )
+          .accounts({
+            user: provider.wallet.publicKey,
+            sourceVault: nativeVaultPda,
+            destVault: tokenVaultPda,
+            priceFeed,
+            limitOrder: expiredPda,
+            systemProgram: SystemProgram.programId,
+          } as Accounts)
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (error: any) {
+        expect(error.message).to.include("ComputationExpired");
+      }
+    });
+
+    it("28.3 Should reject cancellation by another user", async () => {
+      try {
+        await program.methods
+          .cancelLimitOrder()
+          .accounts({
+            user: user1.publicKey,
+            limitOrder: limitOrderPda,
+          } as Accounts)
+          .signers([user1])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (error: any) {
+        expect(error.message).to.match(/Unauthorized|ConstraintSeeds/);
+      }
+    });
+
+    it("28.4 Should cancel the order and refund its rent", async () => {
+      const before = await provider.connection.getBalance(provider.wallet.publicKey);
+
+      await program.methods
+        .cancelLimitOrder()
+        .accounts({
+          user: provider.wallet.publicKey,
+          limitOrder: limitOrderPda,
+        } as Accounts)
+        .rpc();
+
+      const after = await provider.connection.getBalance(provider.wallet.publicKey);
+      expect(after).to.be.greaterThan(before);
+      expect(await provider.connection.getAccountInfo(limitOrderPda)).to.be.null;
+    });
+  });
+
+  // ============================================================================
+  // 29. TREE GROWTH TESTS
+  // ============================================================================
+
+  describe("29. Merkle Tree Growth", () => {
+    const configure = (maxLeaves: number) =>
+      program.methods
+        .configureTree(maxLeaves, 9_000)
+        .accounts({
+          authority: provider.wallet.publicKey,
+          vault: nativeVaultPda,
+          merkleTree: nativeMerkleTreePda,
+        } as Accounts)
+        .rpc();
+
+    it("29.1 Should allow capacities up to 2^20 leaves", async () => {
+      await configure(1_000);
+
+      let tree = await program.account.merkleTreeState.fetch(nativeMerkleTreePda);
+      expect(tree.maxLeaves).to.equal(1_000);
+
+      await configure(1 << 20);
+      tree = await program.account.merkleTreeState.fetch(nativeMerkleTreePda);
+      expect(tree.maxLeaves).to.equal(1 << 20);
+    });
+
+    it("29.2 Should reject capacities beyond the tree depth", async () => {
+      try {
+        await configure((1 << 20) + 1);
+        expect.fail("Should have thrown");
+      } catch (error: any) {
+        expect(error.message).to.include("InvalidTreeConfig");
+      }
+    });
+  });
+
+  // ============================================================================
+  // 30. DCA TESTS
+  // ============================================================================
+
+  describe("30. DCA Schedules", () => {
+    const findDca = (id: BN) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from("dca"), provider.wallet.publicKey.toBuffer(), id.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      )[0];
+    const findEscrow = (dca: PublicKey) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from("dca_escrow"), dca.toBuffer()],
+        program.programId
+      )[0];
+    const encryptedParams = () => [
+      Array.from(generateRandomBytes32()),
+      Array.from(generateRandomBytes32()),
+      Array.from(generateRandomBytes32()),
+    ];
+    const createDca = (id: BN, interval: number, budget: BN) => {
+      const dcaConfig = findDca(id);
+      return program.methods
+        .createDca(id, encryptedParams(), new BN(3), Array.from(generateRandomBytes32()), new BN(interval), budget)
+        .accounts({
+          user: provider.wallet.publicKey,
+          sourceVault: nativeVaultPda,
+          destVault: tokenVaultPda,
+          priceFeed: Keypair.generate().publicKey,
+          recipient: user2.publicKey,
+          dcaConfig,
+          dcaEscrow: findEscrow(dcaConfig),
+          systemProgram: SystemProgram.programId,
+        } as Accounts)
+        .rpc();
+    };
+
+    it("30.1 Should create a DCA and escrow its budget", async () => {
+      const id = new BN(1);
+      const budget = new BN(LAMPORTS_PER_SOL / 10);
+      await createDca(id, 3600, budget);
+
+      const dcaConfig = findDca(id);
+      const dca = await program.account.encryptedDcaConfig.fetch(dcaConfig);
+      expect(dca.intervalSeconds.toNumber()).to.equal(3600);
+      expect(dca.recipient.toBase58()).to.equal(user2.publicKey.toBase58());
+      expect(dca.status).to.deep.equal({ active: {} });
+      expect(dca.pendingAmount.toNumber()).to.equal(0);
+
+      const escrow = await provider.connection.getBalance(findEscrow(dcaConfig));
+      expect(escrow).to.equal(budget.toNumber());
+    });
+
+    it("30.2 Should reject intervals below the minimum", async () => {
+      try {
+        await createDca(new BN(2), 1, new BN(LAMPORTS_PER_SOL / 10));
+        expect.fail("Should have thrown");
+      } catch (error: any) {
+        expect(error.message).to.include("InvalidDcaConfig");
+      }
+    });
+
+    it("30.3 Should cancel the DCA and refund the escrow", async () => {
+      const dcaConfig = findDca(new BN(1));
+      const escrow = findEscrow(dcaConfig);
+
+      await program.methods
+        .cancelDca()
+        .accounts({
+          user: provider.wallet.publicKey,
+          dcaConfig,
+          dcaEscrow: escrow,
+          systemProgram: SystemProgram.programId,
+        } as Accounts)
+        .rpc();
+
+      expect(await provider.connection.getBalance(escrow)).to.equal(0);
+      expect(await provider.connection.getAccountInfo(dcaConfig)).to.be.null;
+    });
+  });
+
+  // ============================================================================
+  // 31. VAULT SUNSET TESTS
+  // ============================================================================
+
+  describe("31. Vault Sunset", () => {
+    const [vaultStatsPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vault_stats"), nativeVaultPda.toBuffer()],
+      program.programId
+    );
+    const grace = new BN(30 * 86_400);
+
+    it("31.1 Should set a sunset policy", async () => {
+      await program.methods
+        .setSunsetPolicy(new BN(4), grace)
+        .accounts({
+          authority: provider.wallet.publicKey,
+          vault: nativeVaultPda,
+        } as Accounts)
+        .rpc();
+
+      const vault = await program.account.vaultState.fetch(nativeVaultPda);
+      expect(vault.sunsetInactivityEpochs.toNumber()).to.equal(4);
+      expect(vault.sunsetGraceSeconds.toNumber()).to.equal(grace.toNumber());
+      expect(vault.lifecycle).to.deep.equal({ active: {} });
+    });
+
+    it("31.2 Should reject a grace period shorter than the minimum", async () => {
+      try {
+        await program.methods
+          .setSunsetPolicy(new BN(4), new BN(60))
+          .accounts({
+            authority: provider.wallet.publicKey,
+            vault: nativeVaultPda,
+          } as Accounts)
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (error: any) {
+        expect(error.message).to.include("InvalidSunsetState");
+      }
+    });
+
+    it("31.3 Should not flag a recently active vault", async () => {
+      try {
+        await program.methods
+          .flagVaultSunset()
+          .accounts({
+            admin: provider.wallet.publicKey,
+            roles: rolesPda,
+            vault: nativeVaultPda,
+            vaultStats: vaultStatsPda,
+          } as Accounts)
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (error: any) {
+        expect(error.message).to.include("InvalidSunsetState");
+      }
+    });
+
+    it("31.4 Should not archive an active vault", async () => {
+      try {
+        await program.methods
+          .archiveVault()
+          .accounts({ vault: nativeVaultPda } as Accounts)
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (error: any) {
+        expect(error.message).to.include("InvalidSunsetState");
+      }
+
+      await program.methods
+        .setSunsetPolicy(new BN(0), new BN(0))
+        .accounts({
+          authority: provider.wallet.publicKey,
+          vault: nativeVaultPda,
+        } as Accounts)
+        .rpc();
+    });
+  });
+
+  // ============================================================================
+  // 32. REFERRAL REGISTRY TESTS
+  // ============================================================================
+
+  describe("32. Referral Registry", () => {
+    const [referralRegistryPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("referral_registry")],
+      program.programId
+    );
+
+    it("32.1 Should initialize the referral registry", async () => {
+      await program.methods
+        .initializeReferralRegistry(new BN(5_000))
+        .accounts({
+          authority: provider.wallet.publicKey,
+          referralRegistry: referralRegistryPda,
+          roles: rolesPda,
+          systemProgram: SystemProgram.programId,
+        } as Accounts)
+        .rpc();
+
+      const registry = await program.account.referralRegistry.fetch(referralRegistryPda);
+      expect(registry.feePerReferral.toNumber()).to.equal(5_000);
+      expect(registry.referrers.every((r: PublicKey) => r.equals(PublicKey.default))).to.be.true;
+    });
+
+    it("32.2 Should assign a referrer slot and update the fee", async () => {
+      await program.methods
+        .setReferrer(1, user1.publicKey)
+        .accounts({
+          authority: provider.wallet.publicKey,
+          referralRegistry: referralRegistryPda,
+          roles: rolesPda,
+        } as Accounts)
+        .rpc();
+
+      await program.methods
+        .setReferralFee(new BN(7_500))
+        .accounts({
+          authority: provider.wallet.publicKey,
+          referralRegistry: referralRegistryPda,
+          roles: rolesPda,
+        } as Accounts)
+        .rpc();
+
+      const registry = await program.account.referralRegistry.fetch(referralRegistryPda);
+      expect(registry.referrers[1].equals(user1.publicKey)).to.be.true;
+      expect(registry.feePerReferral.toNumber()).to.equal(7_500);
+    });
+
+    it("32.3 Should reject an out-of-range slot", async () => {
+      try {
+        await program.methods
+          .setReferrer(4, user2.publicKey)
+          .accounts({
+            authority: provider.wallet.publicKey,
+            referralRegistry: referralRegistryPda,
+            roles: rolesPda,
+          } as Accounts)
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (error: any) {
+        expect(error.message).to.include("InvalidReferrer");
+      }
+    });
+
+    it("32.4 Should reject registry changes from non-fee-manager", async () => {
+      try {
+        await program.methods
+          .setReferrer(0, user2.publicKey)
+          .accounts({
+            authority: user2.publicKey,
+            referralRegistry: referralRegistryPda,
+            roles: rolesPda,
+          } as Accounts)
+          .signers([user2])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (error: any) {
+        expect(error.message).to.include("Unauthorized");
+      }
+    });
+  });
+
+  // ============================================================================
+  // 33. DCA LIFECYCLE TESTS
+  // ============================================================================
+
+  describe("33. DCA Pause, Resume and Param Rotation", () => {
+    const dcaId = new BN(10);
+    const [dcaConfig] = PublicKey.findProgramAddressSync(
+      [Buffer.from("dca"), provider.wallet.publicKey.toBuffer(), dcaId.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    const [dcaEscrow] = PublicKey.findProgramAddressSync(
+      [Buffer.from("dca_escrow"), dcaConfig.toBuffer()],
+      program.programId
+    );
+    const encryptedParams = () => [
+      Array.from(generateRandomBytes32()),
+      Array.from(generateRandomBytes32()),
+      Array.from(generateRandomBytes32()),
+    ];
+    const owner = { user: provider.wallet.publicKey, dcaConfig } as Accounts;
+
+    before(async () => {
+      await program.methods
+        .createDca(dcaId, encryptedParams(), new BN(1), Array.from(generateRandomBytes32()), new BN(3600), new BN(LAMPORTS_PER_SOL / 10))
+        .accounts({
+          user: provider.wallet.publicKey,
+          sourceVault: nativeVaultPda,
+          destVault: tokenVaultPda,
+          priceFeed: Keypair.generate().publicKey,
+          recipient: user2.publicKey,
+          dcaConfig,
+          dcaEscrow,
+          systemProgram: SystemProgram.programId,
+        } as Accounts)
+        .rpc();
+    });
+
+    it("33.1 Should pause and resume a DCA", async () => {
+      await program.methods.pauseDca().accounts(owner).rpc();
+      let dca = await program.account.encryptedDcaConfig.fetch(dcaConfig);
+      expect(dca.status).to.deep.equal({ paused: {} });
+
+      try {
+        await program.methods.pauseDca().accounts(owner).rpc();
+        expect.fail("Should have thrown");
+      } catch (error: any) {
+        expect(error.message).to.include("InvalidComputationStatus");
+      }
+
+      await program.methods.resumeDca().accounts(owner).rpc();
+      dca = await program.account.encryptedDcaConfig.fetch(dcaConfig);
+      expect(dca.status).to.deep.equal({ active: {} });
+    });
+
+    it("33.2 Should rotate encrypted params under a new nonce", async () => {
+      const params = encryptedParams();
+      await program.methods
+        .updateDcaParams(params, new BN(2), Array.from(generateRandomBytes32()))
+        .accounts(owner)
+        .rpc();
+
+      const dca = await program.account.encryptedDcaConfig.fetch(dcaConfig);
+      expect(dca.paramsNonce.toNumber()).to.equal(2);
+      expect(dca.encryptedParams).to.deep.equal(params);
+      expect(dca.status).to.deep.equal({ active: {} });
+    });
+
+    it("33.3 Should reject updates from another user", async () => {
+      try {
+        await program.methods
+          .pauseDca()
+          .accounts({ user: user1.publicKey, dcaConfig } as Accounts)
+          .signers([user1])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (error: any) {
+        expect(error.message).to.match(/Unauthorized|ConstraintSeeds/);
+      }
+    });
+
+    after(async () => {
+      await program.methods
+        .cancelDca()
+        .accounts({
+          user: provider.wallet.publicKey,
+          dcaConfig,
+          dcaEscrow,
+          systemProgram: SystemProgram.programId,
+        } as Accounts)
+        .rpc();
+    });
+  });
+
+  // ============================================================================
+  // 34. BUILD ATTESTATION TESTS
+  // ============================================================================
+
+  describe("34. Build Attestation", () => {
+    const [programInfoPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("program_info")],
+      program.programId
+    );
+    const [programData] = PublicKey.findProgramAddressSync(
+      [program.programId.toBuffer()],
+      BPF_LOADER_UPGRADEABLE
+    );
+    const buildHash = Array.from(generateRandomBytes32());
+
+    it("34.1 Should record the build hash from the upgrade authority", async () => {
+      await program.methods
+        .attestBuild(buildHash)
+        .accounts({
+          authority: provider.wallet.publicKey,
+          programInfo: programInfoPda,
+          program: program.programId,
+          programData,
+        } as Accounts)
+        .rpc();
+
+      const info = await program.account.programInfo.fetch(programInfoPda);
+      expect(Array.from(info.buildHash)).to.deep.equal(buildHash);
+      expect(info.attestedSlot.toNumber()).to.be.greaterThan(0);
+      expect(info.attestedAt.toNumber()).to.be.greaterThan(0);
+    });
+
+    it("34.2 Should reject attestation from other signers", async () => {
+      try {
+        await program.methods
+          .attestBuild(Array.from(generateRandomBytes32()))
+          .accounts({
+            authority: user1.publicKey,
+            programInfo: programInfoPda,
+            program: program.programId,
+            programData,
+          } as Accounts)
+          .signers([user1])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (error: any) {
+        expect(error.message).to.include("Unauthorized");
+      }
+    });
+  });
+
+  // ============================================================================
+  // 35. PROOF FAILURE ALERT TESTS
+  // ============================================================================
+
+  describe("35. Proof Failure Alerts", () => {
+    const [proofFailuresPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("proof_failures"), nativeVaultPda.toBuffer()],
+      program.programId
+    );
+    const report = (count: number, reporter = provider.wallet.publicKey) =>
+      program.methods
+        .reportProofFailures(count)
+        .accounts({
+          reporter,
+          roles: rolesPda,
+          vault: nativeVaultPda,
+          proofFailures: proofFailuresPda,
+          systemProgram: SystemProgram.programId,
+        } as Accounts);
+
+    it("35.1 Should count reported failures in the current epoch", async () => {
+      await report(3).rpc();
+
+      const stats = await program.account.proofFailureStats.fetch(proofFailuresPda);
+      expect(stats.vault.toBase58()).to.equal(nativeVaultPda.toBase58());
+      expect(stats.epochFailures).to.be.at.least(3);
+      expect(stats.totalFailures.toNumber()).to.be.at.least(3);
+    });
+
+    it("35.2 Should emit ProofFailureSpike when the threshold is crossed", async () => {
+      let spike: any = null;
+      const listener = program.addEventListener("proofFailureSpike", (event) => {
+        spike = event;
+      });
+
+      await report(20).rpc();
+      await new Promise((resolve) => setTimeout(resolve, 1000));
+      await program.removeEventListener(listener);
+
+      expect(spike).to.not.be.null;
+      expect(spike.vault.toBase58()).to.equal(nativeVaultPda.toBase58());
+      expect(spike.failures).to.be.at.least(20);
+    });
+
+    it("35.3 Should reject reports from unprivileged signers", async () => {
+      try {
+        await report(1, user1.publicKey).signers([user1]).rpc();
+        expect.fail("Should have thrown");
+      } catch (error: any) {
+        expect(error.message).to.include("Unauthorized");
+      }
+    });
+  });
+
+  // ============================================================================
+  // 36. DEPOSIT MERKLE PATH TESTS
+  // ============================================================================
+
+  describe("36. Deposit Merkle Path", () => {
+    // DepositReceipt: TreeInsertion (leaf_index, new_root, tree_epoch, slot)
+    // followed by the Vec<[u8; 32]> authentication path
+    async function depositReceipt(includePath: boolean) {
+      const signature = await program.methods
+        .depositNative(
+          new BN(0.01 * LAMPORTS_PER_SOL),
+          generateRandomBytes32(),
+          Buffer.alloc(0),
+          Buffer.alloc(0),
+          includePath
+        )
+        .accounts({
+          depositor: provider.wallet.publicKey,
+          vault: nativeVaultPda,
+          merkleTree: nativeMerkleTreePda,
+          vaultTreasury: nativeVaultTreasuryPda,
+          systemProgram: SystemProgram.programId,
+        } as Accounts)
+        .rpc({ commitment: "confirmed" });
+
+      const tx = await provider.connection.getTransaction(signature, {
+        commitment: "confirmed",
+        maxSupportedTransactionVersion: 0,
+      });
+      const data = Buffer.from(tx!.meta!.returnData!.data[0], "base64");
+
+      const pathLen = data.readUInt32LE(56);
+      const path = [];
+      for (let i = 0; i < pathLen; i++) {
+        path.push(data.subarray(60 + i * 32, 92 + i * 32));
+      }
+
+      return {
+        leafIndex: Number(data.readBigUInt64LE(0)),
+        newRoot: data.subarray(8, 40),
+        path,
+      };
+    }
+
+    it("36.1 Should return the authentication path of the new leaf", async () => {
+      const receipt = await depositReceipt(true);
+      const tree = await program.account.merkleTreeState.fetch(nativeMerkleTreePda);
+
+      expect(receipt.leafIndex).to.equal(tree.size.toNumber() - 1);
+      expect(receipt.newRoot.equals(Buffer.from(tree.root))).to.be.true;
+      expect(receipt.path.length).to.equal(Math.max(tree.depth, 1));
+    });
+
+    it("36.2 Should omit the path unless requested", async () => {
+      const receipt = await depositReceipt(false);
+      const tree = await program.account.merkleTreeState.fetch(nativeMerkleTreePda);
+
+      expect(receipt.leafIndex).to.equal(tree.size.toNumber() - 1);
+      expect(receipt.path).to.have.length(0);
+    });
+  });
+
+  // ============================================================================
+  // 37. ENCRYPTED ACCOUNT LAYOUT TESTS
+  // ============================================================================
+
+  describe("37. Encrypted Account Layout", () => {
+    // The queue_* instructions hand the MXE raw byte ranges of these accounts,
+    // so the decoded fields must sit exactly where the program's offsets say
+    const ENCRYPTED_VAULT_STATE_OFFSET = 8 + 1 + 32 + 32 + 16;
+    const ENCRYPTED_POSITION_STATE_OFFSET = 8 + 1 + 32 + 32;
+
+    function discriminator(name: string): Buffer {
+      return crypto.createHash("sha256").update(`account:${name}`).digest().subarray(0, 8);
+    }
+
+    function ciphertexts(count: number): Buffer[] {
+      return Array.from({ length: count }, () => crypto.randomBytes(32));
+    }
+
+    it("37.1 Should decode EncryptedVaultAccount with state at the queued offset", async () => {
+      const tokenMint = Keypair.generate().publicKey;
+      const authority = Keypair.generate().publicKey;
+      const state = ciphertexts(3);
+
+      // Fixture laid out as [disc | bump | token_mint | authority | nonce | state | last_computation]
+      const nonce = Buffer.alloc(16);
+      nonce.writeBigUInt64LE(BigInt(42), 0);
+      const fixture = Buffer.concat([
+        discriminator("EncryptedVaultAccount"),
+        Buffer.from([254]),
+        tokenMint.toBuffer(),
+        authority.toBuffer(),
+        nonce,
+        ...state,
+        Buffer.alloc(32),
+      ]);
+
+      const decoded = program.coder.accounts.decode("EncryptedVaultAccount", fixture);
+      expect(decoded.bump).to.equal(254);
+      expect(decoded.tokenMint.equals(tokenMint)).to.be.true;
+      expect(decoded.authority.equals(authority)).to.be.true;
+      expect(decoded.nonce.toNumber()).to.equal(42);
+      decoded.encryptedState.forEach((ct: number[], i: number) => {
+        expect(Buffer.from(ct).equals(state[i])).to.be.true;
+        expect(
+          fixture
+            .subarray(ENCRYPTED_VAULT_STATE_OFFSET + i * 32, ENCRYPTED_VAULT_STATE_OFFSET + (i + 1) * 32)
+            .equals(state[i])
+        ).to.be.true;
+      });
+    });
+
+    it("37.2 Should decode EncryptedUserPosition with state at the queued offset", async () => {
+      const owner = Keypair.generate().publicKey;
+      const vault = Keypair.generate().publicKey;
+      const state = ciphertexts(2);
+
+      const fixture = Buffer.concat([
+        discriminator("EncryptedUserPosition"),
+        Buffer.from([253]),
+        owner.toBuffer(),
+        vault.toBuffer(),
+        ...state,
+        Buffer.alloc(16 + 8 + 1 + 32 + 32 + 16 + 8 + 32),
+      ]);
+
+      const decoded = program.coder.accounts.decode("EncryptedUserPosition", fixture);
+      expect(decoded.owner.equals(owner)).to.be.true;
+      expect(decoded.vault.equals(vault)).to.be.true;
+      decoded.positionState.forEach((ct: number[], i: number) => {
+        expect(
+          fixture
+            .subarray(ENCRYPTED_POSITION_STATE_OFFSET + i * 32, ENCRYPTED_POSITION_STATE_OFFSET + (i + 1) * 32)
+            .equals(Buffer.from(ct))
+        ).to.be.true;
+      });
+    });
+  });
+
+  // ============================================================================
+  // 38. SEALED ORDER TESTS
+  // ============================================================================
+
+  describe("38. Sealed Orders", () => {
+    const orderId = new BN(1);
+    const [sealedOrderPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("sealed_order"),
+        provider.wallet.publicKey.toBuffer(),
+        orderId.toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+    const priceFeed = Keypair.generate().publicKey;
+
+    it("38.1 Should submit a sealed order", async () => {
+      await program.methods
+        .submitSealedOrder(
+          orderId,
+          [
+            Array.from(generateRandomBytes32()),
+            Array.from(generateRandomBytes32()),
+            Array.from(generateRandomBytes32()),
+          ],
+          new BN(11),
+          Array.from(generateRandomBytes32())
+        )
+        .accounts({
+          user: provider.wallet.publicKey,
+          baseVault: nativeVaultPda,
+          quoteVault: tokenVaultPda,
+          priceFeed,
+          sealedOrder: sealedOrderPda,
+          systemProgram: SystemProgram.programId,
+        } as Accounts)
+        .rpc();
+
+      const order = await program.account.encryptedSealedOrder.fetch(sealedOrderPda);
+      expect(order.orderId.toNumber()).to.equal(1);
+      expect(order.baseVault.toBase58()).to.equal(nativeVaultPda.toBase58());
+      expect(order.priceFeed.toBase58()).to.equal(priceFeed.toBase58());
+      expect(order.status).to.deep.equal({ open: {} });
+    });
+
+    it("38.2 Should reject cancellation by another user", async () => {
+      try {
+        await program.methods
+          .cancelSealedOrder()
+          .accounts({
+            user: user1.publicKey,
+            sealedOrder: sealedOrderPda,
+          } as Accounts)
+          .signers([user1])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (error: any) {
+        expect(error.message).to.match(/Unauthorized|ConstraintSeeds/);
+      }
+    });
+
+    it("38.3 Should cancel an open order and refund its rent", async () => {
+      await program.methods
+        .cancelSealedOrder()
+        .accounts({
+          user: provider.wallet.publicKey,
+          sealedOrder: sealedOrderPda,
+        } as Accounts)
+        .rpc();
+
+      expect(await provider.connection.getAccountInfo(sealedOrderPda)).to.be.null;
+    });
+  });
+
+  // ============================================================================
+  // 39. INSURANCE FUND TESTS
+  // ============================================================================
+
+  describe("39. Insurance Claims", () => {
+    const claimId = new BN(1);
+    let claimPda: PublicKey;
+    let vaultFeesPda: PublicKey;
+
+    before(() => {
+      [claimPda] = PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("insurance_claim"),
+          nativeVaultPda.toBuffer(),
+          claimId.toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      );
+      [vaultFeesPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("vault_fees"), nativeVaultPda.toBuffer()],
+        program.programId
+      );
+    });
+
+    async function executeClaim() {
+      const vault = await program.account.vaultState.fetch(nativeVaultPda);
+      return program.methods
+        .executeInsuranceClaim()
+        .accounts({
+          executor: provider.wallet.publicKey,
+          vault: nativeVaultPda,
+          merkleTree: vault.merkleTree,
+          vaultFees: vaultFeesPda,
+          claim: claimPda,
+          systemProgram: SystemProgram.programId,
+        } as Accounts)
+        .rpc();
+    }
+
+    it("39.1 Should propose a claim paying a new commitment", async () => {
+      await program.methods
+        .proposeInsuranceClaim(claimId, Array.from(generateRandomBytes32()), [
+          {
+            amount: new BN(0.05 * LAMPORTS_PER_SOL),
+            precommitment: Array.from(generateRandomBytes32()),
+          },
+        ])
+        .accounts({
+          proposer: user1.publicKey,
+          vault: nativeVaultPda,
+          claim: claimPda,
+          systemProgram: SystemProgram.programId,
+        } as Accounts)
+        .signers([user1])
+        .rpc();
+
+      const claim = await program.account.insuranceClaim.fetch(claimPda);
+      expect(claim.proposer.toBase58()).to.equal(user1.publicKey.toBase58());
+      expect(claim.total.toNumber()).to.equal(0.05 * LAMPORTS_PER_SOL);
+      expect(claim.status).to.deep.equal({ voting: {} });
+    });
+
+    it("39.2 Should reject a vote from a non-role holder", async () => {
+      try {
+        await program.methods
+          .voteInsuranceClaim({ admin: {} }, true)
+          .accounts({
+            voter: user1.publicKey,
+            roles: rolesPda,
+            claim: claimPda,
+          } as Accounts)
+          .signers([user1])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (error: any) {
+        expect(error.message).to.include("Unauthorized");
+      }
+    });
+
+    it("39.3 Should not execute before a quorum approves", async () => {
+      try {
+        await executeClaim();
+        expect.fail("Should have thrown");
+      } catch (error: any) {
+        expect(error.message).to.include("InsuranceClaimNotApproved");
+      }
+    });
+
+    it("39.4 Should count each role's vote once", async () => {
+      const vote = (role: object) =>
+        program.methods
+          .voteInsuranceClaim(role, true)
+          .accounts({
+            voter: provider.wallet.publicKey,
+            roles: rolesPda,
+            claim: claimPda,
+          } as Accounts)
+          .rpc();
+
+      await vote({ admin: {} });
+      try {
+        await vote({ admin: {} });
+        expect.fail("Should have thrown");
+      } catch (error: any) {
+        expect(error.message).to.include("AlreadyVoted");
+      }
+      await vote({ feeManager: {} });
+
+      const claim = await program.account.insuranceClaim.fetch(claimPda);
+      expect(claim.approvals).to.equal(0b101);
+      expect(claim.status).to.deep.equal({ voting: {} });
+    });
+
+    it("39.5 Should not pay more than the insurance reserve holds", async () => {
+      const fees = await program.account.vaultFeeState.fetch(vaultFeesPda);
+      expect(fees.insuranceReserve.toNumber()).to.be.lessThan(0.05 * LAMPORTS_PER_SOL);
+
+      try {
+        await executeClaim();
+        expect.fail("Should have thrown");
+      } catch (error: any) {
+        expect(error.message).to.include("InsufficientFunds");
+      }
+    });
+  });
+
+  // ============================================================================
+  // 40. CONFIDENTIAL TWAP TESTS
+  // ============================================================================
+
+  describe("40. Confidential TWAP", () => {
+    const findTwap = (id: BN) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from("twap"), provider.wallet.publicKey.toBuffer(), id.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      )[0];
+    const findEscrow = (twap: PublicKey) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from("twap_escrow"), twap.toBuffer()],
+        program.programId
+      )[0];
+    const encryptedParams = () => [
+      Array.from(generateRandomBytes32()),
+      Array.from(generateRandomBytes32()),
+      Array.from(generateRandomBytes32()),
+    ];
+    const createTwap = (id: BN, interval: number, budget: BN) => {
+      const twapConfig = findTwap(id);
+      return program.methods
+        .createTwap(id, encryptedParams(), new BN(5), Array.from(generateRandomBytes32()), new BN(interval), budget)
+        .accounts({
+          user: provider.wallet.publicKey,
+          sourceVault: nativeVaultPda,
+          destVault: tokenVaultPda,
+          recipient: user2.publicKey,
+          twapConfig,
+          twapEscrow: findEscrow(twapConfig),
+          systemProgram: SystemProgram.programId,
+        } as Accounts)
+        .rpc();
+    };
+
+    it("40.1 Should create a TWAP and escrow its budget", async () => {
+      const id = new BN(1);
+      const budget = new BN(LAMPORTS_PER_SOL / 10);
+      await createTwap(id, 300, budget);
+
+      const twapConfig = findTwap(id);
+      const twap = await program.account.encryptedTwapConfig.fetch(twapConfig);
+      expect(twap.intervalSeconds.toNumber()).to.equal(300);
+      expect(twap.recipient.toBase58()).to.equal(user2.publicKey.toBase58());
+      expect(twap.status).to.deep.equal({ active: {} });
+      expect(twap.pendingAmount.toNumber()).to.equal(0);
+
+      const escrow = await provider.connection.getBalance(findEscrow(twapConfig));
+      expect(escrow).to.equal(budget.toNumber());
+    });
+
+    it("40.2 Should reject intervals below the minimum", async () => {
+      try {
+        await createTwap(new BN(2), 1, new BN(LAMPORTS_PER_SOL / 10));
+        expect.fail("Should have thrown");
+      } catch (error: any) {
+        expect(error.message).to.include("InvalidTwapConfig");
+      }
+    });
+
+    it("40.3 Should cancel the TWAP and refund the escrow", async () => {
+      const twapConfig = findTwap(new BN(1));
+      const escrow = findEscrow(twapConfig);
+
+      await program.methods
+        .cancelTwap()
+        .accounts({
+          user: provider.wallet.publicKey,
+          twapConfig,
+          twapEscrow: escrow,
+          systemProgram: SystemProgram.programId,
+        } as Accounts)
+        .rpc();
+
+      expect(await provider.connection.getBalance(escrow)).to.equal(0);
+      expect(await provider.connection.getAccountInfo(twapConfig)).to.be.null;
+    });
+  });
+
+  // ============================================================================
+  // 41. COMPUTE BUDGET TESTS
+  // ============================================================================
+
+  describe("41. Compute Budgets", () => {
+    const deposit = (includePath: boolean) =>
+      program.methods
+        .depositNative(
+          new BN(0.01 * LAMPORTS_PER_SOL),
+          generateRandomBytes32(),
+          Buffer.alloc(0),
+          Buffer.alloc(0),
+          includePath
+        )
+        .accounts({
+          depositor: provider.wallet.publicKey,
+          vault: nativeVaultPda,
+          merkleTree: nativeMerkleTreePda,
+          vaultTreasury: nativeVaultTreasuryPda,
+          systemProgram: SystemProgram.programId,
+        } as Accounts)
+        .rpc({ commitment: "confirmed" });
+
+    it("41.1 Should declare budgets for deposits, withdrawals and swaps", async () => {
+      for (const name of [
+        "deposit_native",
+        "deposit_token",
+        "withdraw_native",
+        "withdraw_token",
+        "swap_native",
+        "swap_token",
+      ]) {
+        expect(COMPUTE_BUDGETS[name], name).to.be.a("number");
+        expect(COMPUTE_BUDGETS[name], name).to.be.at.most(1_400_000);
+      }
+    });
+
+    it("41.2 Should keep a native deposit within its budget", async () => {
+      const consumed = await expectWithinComputeBudget(
+        provider,
+        await deposit(false),
+        "deposit_native"
+      );
+      console.log("   deposit_native:", consumed, "CU");
+    });
+
+    it("41.3 Should keep a deposit returning its Merkle path within budget", async () => {
+      const consumed = await expectWithinComputeBudget(
+        provider,
+        await deposit(true),
+        "deposit_native"
+      );
+      console.log("   deposit_native (with path):", consumed, "CU");
+    });
+  });
+
+  // ============================================================================
+  // 42. STOP ORDER TESTS
+  // ============================================================================
+
+  describe("42. Stop Orders", () => {
+    const findStopOrder = (id: BN) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from("stop_order"), provider.wallet.publicKey.toBuffer(), id.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      )[0];
+    const findEscrow = (order: PublicKey) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from("stop_escrow"), order.toBuffer()],
+        program.programId
+      )[0];
+    const placeStopOrder = (id: BN, expiresAt: number, budget: BN) => {
+      const stopOrder = findStopOrder(id);
+      return program.methods
+        .placeStopOrder(
+          id,
+          [generateRandomBytes32(), generateRandomBytes32(), generateRandomBytes32()],
+          new BN(7),
+          generateRandomBytes32(),
+          new BN(expiresAt),
+          budget
+        )
+        .accounts({
+          user: provider.wallet.publicKey,
+          sourceVault: nativeVaultPda,
+          destVault: tokenVaultPda,
+          priceFeed: Keypair.generate().publicKey,
+          recipient: user2.publicKey,
+          stopOrder,
+          stopEscrow: findEscrow(stopOrder),
+          systemProgram: SystemProgram.programId,
+        } as Accounts)
+        .rpc();
+    };
+    const inOneDay = () => Math.floor(Date.now() / 1000) + 86_400;
+
+    it("42.1 Should place a stop order and escrow its budget", async () => {
+      const id = new BN(1);
+      const budget = new BN(LAMPORTS_PER_SOL / 10);
+      await placeStopOrder(id, inOneDay(), budget);
+
+      const stopOrder = findStopOrder(id);
+      const order = await program.account.encryptedStopOrder.fetch(stopOrder);
+      expect(order.status).to.deep.equal({ active: {} });
+      expect(order.recipient.toBase58()).to.equal(user2.publicKey.toBase58());
+      expect(order.triggerAmount.toNumber()).to.equal(0);
+
+      const escrow = await provider.connection.getBalance(findEscrow(stopOrder));
+      expect(escrow).to.equal(budget.toNumber());
+    });
+
+    it("42.2 Should reject an order that is already expired", async () => {
+      try {
+        await placeStopOrder(new BN(2), Math.floor(Date.now() / 1000) - 60, new BN(LAMPORTS_PER_SOL / 10));
+        expect.fail("Should have thrown");
+      } catch (error: any) {
+        expect(error.message).to.include("ComputationExpired");
+      }
+    });
+
+    it("42.3 Should not execute a stop that has not triggered", async () => {
+      const stopOrder = findStopOrder(new BN(1));
+      try {
+        await program.methods
+          .executeStopOrder(Buffer.alloc(0))
+          .accounts({
+            payer: provider.wallet.publicKey,
+            stopOrder,
+            stopEscrow: findEscrow(stopOrder),
+            recipient: user2.publicKey,
+            jupiterProgram: JUPITER_PROGRAM_ID,
+          } as Accounts)
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (error: any) {
+        expect(error.message).to.include("InvalidComputationStatus");
+      }
+    });
+
+    it("42.4 Should cancel the stop order and refund the escrow", async () => {
+      const stopOrder = findStopOrder(new BN(1));
+      const escrow = findEscrow(stopOrder);
+
+      await program.methods
+        .cancelStopOrder()
+        .accounts({
+          user: provider.wallet.publicKey,
+          stopOrder,
+          stopEscrow: escrow,
+          systemProgram: SystemProgram.programId,
+        } as Accounts)
+        .rpc();
+
+      expect(await provider.connection.getBalance(escrow)).to.equal(0);
+      expect(await provider.connection.getAccountInfo(stopOrder)).to.be.null;
+    });
+  });
+
+  // ============================================================================
+  // 43. HOT/COLD TREASURY TESTS
+  // ============================================================================
+
+  describe("43. Hot/Cold Treasury", () => {
+    let treasuryPolicyPda: PublicKey;
+    let coldTreasuryPda: PublicKey;
+    const findColdSpend = (id: BN) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from("cold_spend"), nativeVaultPda.toBuffer(), id.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      )[0];
+    const initializePolicy = (hotTargetBps: number, refillThresholdBps: number) =>
+      program.methods
+        .initializeTreasuryPolicy({ hotTargetBps, refillThresholdBps })
+        .accounts({
+          admin: provider.wallet.publicKey,
+          roles: rolesPda,
+          vault: nativeVaultPda,
+          treasuryPolicy: treasuryPolicyPda,
+          systemProgram: SystemProgram.programId,
+        } as Accounts)
+        .rpc();
+    const rebalanceAccounts = () =>
+      ({
+        cranker: provider.wallet.publicKey,
+        vault: nativeVaultPda,
+        treasuryPolicy: treasuryPolicyPda,
+        vaultTreasury: nativeVaultTreasuryPda,
+        coldTreasury: coldTreasuryPda,
+        systemProgram: SystemProgram.programId,
+      } as Accounts);
+
+    before(() => {
+      [treasuryPolicyPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("treasury_policy"), nativeVaultPda.toBuffer()],
+        program.programId
+      );
+      [coldTreasuryPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("cold_treasury"), nativeVaultPda.toBuffer()],
+        program.programId
+      );
+    });
+
+    it("43.1 Should reject a refill threshold above the hot target", async () => {
+      try {
+        await initializePolicy(2_000, 3_000);
+        expect.fail("Should have thrown");
+      } catch (error: any) {
+        expect(error.message).to.include("InvalidTreasurySplit");
+      }
+    });
+
+    it("43.2 Should initialize the treasury split as admin", async () => {
+      await initializePolicy(2_000, 1_000);
+
+      const policy = await program.account.treasuryPolicy.fetch(treasuryPolicyPda);
+      expect(policy.vault.toBase58()).to.equal(nativeVaultPda.toBase58());
+      expect(policy.hotTargetBps).to.equal(2_000);
+      expect(policy.refillThresholdBps).to.equal(1_000);
+    });
+
+    it("43.3 Should sweep the hot treasury's excess into cold", async () => {
+      const hotBefore = await provider.connection.getBalance(nativeVaultTreasuryPda);
+
+      await program.methods.sweepToCold().accounts(rebalanceAccounts()).rpc();
+
+      const hotAfter = await provider.connection.getBalance(nativeVaultTreasuryPda);
+      const coldAfter = await provider.connection.getBalance(coldTreasuryPda);
+      expect(hotAfter + coldAfter).to.equal(hotBefore);
+      expect(coldAfter).to.be.greaterThan(hotAfter);
+
+      const policy = await program.account.treasuryPolicy.fetch(treasuryPolicyPda);
+      expect(policy.totalSwept.toNumber()).to.equal(coldAfter);
+    });
+
+    it("43.4 Should not refill a hot treasury at its target", async () => {
+      try {
+        await program.methods.refillHotTreasury().accounts(rebalanceAccounts()).rpc();
+        expect.fail("Should have thrown");
+      } catch (error: any) {
+        expect(error.message).to.include("RefillNotDue");
+      }
+    });
+
+    it("43.5 Should count a key holding several roles as one cold spend vote", async () => {
+      const id = new BN(1);
+      const coldSpend = findColdSpend(id);
+      await program.methods
+        .proposeColdSpend(id, new BN(LAMPORTS_PER_SOL / 1_000), generateRandomBytes32())
+        .accounts({
+          proposer: provider.wallet.publicKey,
+          vault: nativeVaultPda,
+          treasuryPolicy: treasuryPolicyPda,
+          recipient: user2.publicKey,
+          coldSpend,
+          systemProgram: SystemProgram.programId,
+        } as Accounts)
+        .rpc();
+
+      const vote = (role: object) =>
+        program.methods
+          .voteColdSpend(role, true)
+          .accounts({
+            voter: provider.wallet.publicKey,
+            roles: rolesPda,
+            coldSpend,
+          } as Accounts)
+          .rpc();
+
+      // The test wallet holds every role, but votes only once
+      await vote({ admin: {} });
+      try {
+        await vote({ feeManager: {} });
+        expect.fail("Should have thrown");
+      } catch (error: any) {
+        expect(error.message).to.include("SignerAlreadyVoted");
+      }
+
+      const spend = await program.account.coldSpend.fetch(coldSpend);
+      expect(spend.status).to.deep.equal({ pending: {} });
+      expect(spend.approvals).to.equal(1);
+      expect(spend.voters[0].toBase58()).to.equal(provider.wallet.publicKey.toBase58());
+
+      try {
+        await program.methods
+          .executeColdSpend()
+          .accounts({
+            executor: provider.wallet.publicKey,
+            roles: rolesPda,
+            vault: nativeVaultPda,
+            treasuryPolicy: treasuryPolicyPda,
+            coldTreasury: coldTreasuryPda,
+            coldSpend,
+            recipient: user2.publicKey,
+            systemProgram: SystemProgram.programId,
+          } as Accounts)
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (error: any) {
+        expect(error.message).to.include("ColdSpendNotApproved");
+      }
+    });
+  });
+
+  // ============================================================================
+  // 44. ORACLE PRICE GUARD TESTS
+  // ============================================================================
+
+  describe("44. Oracle Price Guards", () => {
+    let arciumConfigPda: PublicKey;
+
+    before(() => {
+      [arciumConfigPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("arcium_config")],
+        program.programId
+      );
+    });
+
+    it("44.1 Should start from the default staleness and confidence limits", async () => {
+      const config = await program.account.arciumConfig.fetch(arciumConfigPda);
+      expect(config.maxPriceAgeSeconds.toNumber()).to.equal(60);
+      expect(config.maxPriceConfidenceBps).to.equal(200);
+    });
+
+    it("44.2 Should let the config authority tighten the guards", async () => {
+      await program.methods
+        .setPriceGuards({ maxAgeSeconds: new BN(30), maxConfidenceBps: 50 })
+        .accounts({
+          authority: provider.wallet.publicKey,
+          arciumConfig: arciumConfigPda,
+        } as Accounts)
+        .rpc();
+
+      const config = await program.account.arciumConfig.fetch(arciumConfigPda);
+      expect(config.maxPriceAgeSeconds.toNumber()).to.equal(30);
+      expect(config.maxPriceConfidenceBps).to.equal(50);
+    });
+
+    it("44.3 Should reject guards that disable the checks", async () => {
+      for (const guards of [
+        { maxAgeSeconds: new BN(0), maxConfidenceBps: 50 },
+        { maxAgeSeconds: new BN(30), maxConfidenceBps: 0 },
+      ]) {
+        try {
+          await program.methods
+            .setPriceGuards(guards)
+            .accounts({
+              authority: provider.wallet.publicKey,
+              arciumConfig: arciumConfigPda,
+            } as Accounts)
+            .rpc();
+          expect.fail("Should have thrown");
+        } catch (error: any) {
+          expect(error.message).to.include("InvalidPriceGuards");
+        }
+      }
+    });
+
+    it("44.4 Should reject guard changes from anyone else", async () => {
+      try {
+        await program.methods
+          .setPriceGuards({ maxAgeSeconds: new BN(3_600), maxConfidenceBps: 10_000 })
+          .accounts({
+            authority: user1.publicKey,
+            arciumConfig: arciumConfigPda,
+          } as Accounts)
+          .signers([user1])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (error: any) {
+        expect(error.message).to.include("Unauthorized");
+      }
+    });
+  });
+
+  // ============================================================================
+  // 45. ALLOWANCE POLICY TESTS
+  // ============================================================================
+
+  describe("45. Allowance Policies", () => {
+    const policyId = new BN(1);
+    let allowancePolicyPda: PublicKey;
+    const traderKeys = [generateRandomBytes32(), generateRandomBytes32()];
+
+    before(() => {
+      [allowancePolicyPda] = PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("allowance_policy"),
+          nativeVaultPda.toBuffer(),
+          policyId.toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      );
+    });
+
+    it("45.1 Should create a policy for an org's trader viewing keys", async () => {
+      await program.methods
+        .createAllowancePolicy(policyId, {
+          epochLimit: new BN(2 * LAMPORTS_PER_SOL),
+          epochSeconds: new BN(86_400),
+          viewingKeys: traderKeys,
+        })
+        .accounts({
+          admin: user1.publicKey,
+          vault: nativeVaultPda,
+          allowancePolicy: allowancePolicyPda,
+          systemProgram: SystemProgram.programId,
+        } as Accounts)
+        .signers([user1])
+        .rpc();
+
+      const policy = await program.account.allowancePolicy.fetch(allowancePolicyPda);
+      expect(policy.admin.toBase58()).to.equal(user1.publicKey.toBase58());
+      expect(policy.epochLimit.toNumber()).to.equal(2 * LAMPORTS_PER_SOL);
+      expect(policy.viewingKeys.length).to.equal(2);
+      expect(policy.epochSpent.toNumber()).to.equal(0);
+    });
+
+    it("45.2 Should reject policies without viewing keys or a limit", async () => {
+      for (const params of [
+        { epochLimit: new BN(1), epochSeconds: new BN(86_400), viewingKeys: [] },
+        { epochLimit: new BN(0), epochSeconds: new BN(86_400), viewingKeys: traderKeys },
+      ]) {
+        try {
+          await program.methods
+            .updateAllowancePolicy(params)
+            .accounts({
+              admin: user1.publicKey,
+              allowancePolicy: allowancePolicyPda,
+            } as Accounts)
+            .signers([user1])
+            .rpc();
+          expect.fail("Should have thrown");
+        } catch (error: any) {
+          expect(error.message).to.include("InvalidAllowancePolicy");
+        }
+      }
+    });
+
+    it("45.3 Should let only the org admin rotate the viewing keys", async () => {
+      const params = {
+        epochLimit: new BN(LAMPORTS_PER_SOL),
+        epochSeconds: new BN(86_400),
+        viewingKeys: [traderKeys[0]],
+      };
+
+      try {
+        await program.methods
+          .updateAllowancePolicy(params)
+          .accounts({
+            admin: user2.publicKey,
+            allowancePolicy: allowancePolicyPda,
+          } as Accounts)
+          .signers([user2])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (error: any) {
+        expect(error.message).to.include("Unauthorized");
+      }
+
+      await program.methods
+        .updateAllowancePolicy(params)
+        .accounts({
+          admin: user1.publicKey,
+          allowancePolicy: allowancePolicyPda,
+        } as Accounts)
+        .signers([user1])
+        .rpc();
+
+      const policy = await program.account.allowancePolicy.fetch(allowancePolicyPda);
+      expect(policy.epochLimit.toNumber()).to.equal(LAMPORTS_PER_SOL);
+      expect(policy.viewingKeys.length).to.equal(1);
+    });
+
+    it("45.4 Should plan the policy into withdrawals of bound notes", async () => {
+      const plan = await program.methods
+        .planWithdrawal(generateRandomBytes32(), user2.publicKey, provider.wallet.publicKey)
+        .accounts({
+          vault: nativeVaultPda,
+          exclusionRoot: null,
+          treasuryPolicy: null,
+          allowancePolicy: allowancePolicyPda,
+        } as Accounts)
+        .view();
+
+      const planned = plan.find(
+        (a: any) => a.pubkey.toBase58() === allowancePolicyPda.toBase58()
+      );
+      expect(planned.isWritable).to.be.true;
+    });
+
+    it("45.5 Should bind a note deposited with the policy to it", async () => {
+      const vault = await program.account.vaultState.fetch(nativeVaultPda);
+      const precommitment = generateRandomBytes32();
+      const parser = new anchor.EventParser(program.programId, new anchor.BorshCoder(program.idl));
+
+      const depositedCommitment = async (allowancePolicy: PublicKey | null) => {
+        const signature = await program.methods
+          .depositNative(
+            new BN(0.01 * LAMPORTS_PER_SOL),
+            precommitment,
+            Buffer.alloc(0),
+            Buffer.alloc(0),
+            false
+          )
+          .accounts({
+            depositor: provider.wallet.publicKey,
+            vault: nativeVaultPda,
+            merkleTree: vault.merkleTree,
+            vaultTreasury: nativeVaultTreasuryPda,
+            allowancePolicy,
+            systemProgram: SystemProgram.programId,
+          } as Accounts)
+          .rpc({ commitment: "confirmed" });
+        const confirmed = await provider.connection.getTransaction(signature, {
+          commitment: "confirmed",
+          maxSupportedTransactionVersion: 0,
+        });
+        const deposited = Array.from(parser.parseLogs(confirmed!.meta!.logMessages!)).find(
+          (event) => event.name === "depositedEvent"
+        );
+        return Buffer.from(deposited!.data.commitment as number[]);
+      };
+
+      // Same secrets and amount: only the policy hash tells the leaves apart,
+      // so the bound note cannot be proven as an unbound one
+      const bound = await depositedCommitment(allowancePolicyPda);
+      const unbound = await depositedCommitment(null);
+      expect(bound.equals(unbound)).to.be.false;
+    });
+  });
+
+  // ============================================================================
+  // 46. ORACLE FALLBACK TESTS
+  // ============================================================================
+
+  describe("46. Oracle Fallback", () => {
+    const tokenMint = PublicKey.default;
+    const switchboardFeed = Keypair.generate().publicKey;
+    let oracleFallbackPda: PublicKey;
+
+    before(() => {
+      [oracleFallbackPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("oracle_fallback"), tokenMint.toBuffer()],
+        program.programId
+      );
+    });
+
+    it("46.1 Should let the admin set a Switchboard fallback for SOL", async () => {
+      await program.methods
+        .setOracleFallback(tokenMint, { switchboard: { feed: switchboardFeed } })
+        .accounts({
+          admin: provider.wallet.publicKey,
+          roles: rolesPda,
+          oracleFallback: oracleFallbackPda,
+          systemProgram: SystemProgram.programId,
+        } as Accounts)
+        .rpc();
+
+      const fallback = await program.account.oracleFallback.fetch(oracleFallbackPda);
+      expect(fallback.tokenMint.toBase58()).to.equal(tokenMint.toBase58());
+      expect(fallback.source.switchboard.feed.toBase58()).to.equal(switchboardFeed.toBase58());
+    });
+
+    it("46.2 Should reject fallback changes from non-admins", async () => {
+      try {
+        await program.methods
+          .setOracleFallback(tokenMint, { switchboard: { feed: user1.publicKey } })
+          .accounts({
+            admin: user1.publicKey,
+            roles: rolesPda,
+            oracleFallback: oracleFallbackPda,
+            systemProgram: SystemProgram.programId,
+          } as Accounts)
+          .signers([user1])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (error: any) {
+        expect(error.message).to.include("Unauthorized");
+      }
+    });
+  });
+
+  // ============================================================================
+  // 47. PRICE FEED REGISTRY TESTS
+  // ============================================================================
+
+  describe("47. Price Feed Registry", () => {
+    const wsolMint = new PublicKey("So11111111111111111111111111111111111111112");
+    // Pyth SOL/USD feed ID
+    const solUsdFeed = Array.from(
+      Buffer.from("ef0d8b6fda2ceba41da15d4095d1da392a0d2f8ed0c6c7bc0f4cfac8c280b56d", "hex")
+    );
+    let registryPda: PublicKey;
+
+    before(() => {
+      [registryPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("price_feed_registry")],
+        program.programId
+      );
+    });
+
+    it("47.1 Should let the admin register a token's price feed", async () => {
+      await program.methods
+        .registerPriceFeed(wsolMint, solUsdFeed, new BN(60))
+        .accounts({
+          admin: provider.wallet.publicKey,
+          roles: rolesPda,
+          priceFeedRegistry: registryPda,
+          systemProgram: SystemProgram.programId,
+        } as Accounts)
+        .rpc();
+
+      const registry = await program.account.priceFeedRegistry.fetch(registryPda);
+      const entry = registry.feeds.find((f: any) => f.tokenMint.equals(wsolMint));
+      expect(entry).to.not.be.undefined;
+      expect(entry.feedId).to.deep.equal(solUsdFeed);
+      expect(entry.maxAgeSeconds.toNumber()).to.equal(60);
+    });
+
+    it("47.2 Should replace an existing entry instead of adding another", async () => {
+      await program.methods
+        .registerPriceFeed(wsolMint, solUsdFeed, new BN(30))
+        .accounts({
+          admin: provider.wallet.publicKey,
+          roles: rolesPda,
+          priceFeedRegistry: registryPda,
+          systemProgram: SystemProgram.programId,
+        } as Accounts)
+        .rpc();
+
+      const registry = await program.account.priceFeedRegistry.fetch(registryPda);
+      const entries = registry.feeds.filter((f: any) => f.tokenMint.equals(wsolMint));
+      expect(entries.length).to.equal(1);
+      expect(entries[0].maxAgeSeconds.toNumber()).to.equal(30);
+    });
+
+    it("47.3 Should reject registrations from non-admins", async () => {
+      try {
+        await program.methods
+          .registerPriceFeed(user1.publicKey, solUsdFeed, new BN(60))
+          .accounts({
+            admin: user1.publicKey,
+            roles: rolesPda,
+            priceFeedRegistry: registryPda,
+            systemProgram: SystemProgram.programId,
+          } as Accounts)
+          .signers([user1])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (error: any) {
+        expect(error.message).to.include("Unauthorized");
+      }
+    });
+  });
+
+  // ============================================================================
+  // 48. NOTE BACKUP TESTS
+  // ============================================================================
+
+  describe("48. Note Backup", () => {
+    const precommitment = generateRandomBytes32();
+    const encryptedNote = Buffer.alloc(96, 7);
+    let noteBackupPda: PublicKey;
+
+    before(() => {
+      [noteBackupPda] = PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("note_backup"),
+          provider.wallet.publicKey.toBuffer(),
+          Buffer.from(precommitment),
+        ],
+        program.programId
+      );
+    });
+
+    it("48.1 Should store the encrypted note when a backup account is passed", async () => {
+      const vault = await program.account.vaultState.fetch(nativeVaultPda);
+      await program.methods
+        .depositNative(new BN(0.01 * LAMPORTS_PER_SOL), precommitment, encryptedNote, Buffer.alloc(0), false)
+        .accounts({
+          depositor: provider.wallet.publicKey,
+          vault: nativeVaultPda,
+          merkleTree: vault.merkleTree,
+          vaultTreasury: nativeVaultTreasuryPda,
+          noteBackup: noteBackupPda,
+          systemProgram: SystemProgram.programId,
+        } as Accounts)
+        .rpc();
+
+      const backup = await program.account.noteBackup.fetch(noteBackupPda);
+      expect(backup.owner.toBase58()).to.equal(provider.wallet.publicKey.toBase58());
+      expect(backup.vault.toBase58()).to.equal(nativeVaultPda.toBase58());
+      expect(Buffer.from(backup.ciphertext).equals(encryptedNote)).to.be.true;
+    });
+
+    it("48.2 Should reject a backup without an encrypted note", async () => {
+      const otherPrecommitment = generateRandomBytes32();
+      const [otherBackupPda] = PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("note_backup"),
+          provider.wallet.publicKey.toBuffer(),
+          Buffer.from(otherPrecommitment),
+        ],
+        program.programId
+      );
+      const vault = await program.account.vaultState.fetch(nativeVaultPda);
+      try {
+        await program.methods
+          .depositNative(new BN(0.01 * LAMPORTS_PER_SOL), otherPrecommitment, Buffer.alloc(0), Buffer.alloc(0), false)
+          .accounts({
+            depositor: provider.wallet.publicKey,
+            vault: nativeVaultPda,
+            merkleTree: vault.merkleTree,
+            vaultTreasury: nativeVaultTreasuryPda,
+            noteBackup: otherBackupPda,
+            systemProgram: SystemProgram.programId,
+          } as Accounts)
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (error: any) {
+        expect(error.message).to.include("MissingEncryptedNote");
+      }
+    });
+
+    it("48.3 Should let only the owner close a backup", async () => {
+      try {
+        await program.methods
+          .closeNoteBackup()
+          .accounts({
+            owner: user1.publicKey,
+            noteBackup: noteBackupPda,
+          } as Accounts)
+          .signers([user1])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (error: any) {
+        expect(error.message).to.include("Unauthorized");
+      }
+
+      await program.methods
+        .closeNoteBackup()
+        .accounts({
+          owner: provider.wallet.publicKey,
+          noteBackup: noteBackupPda,
+        } as Accounts)
+        .rpc();
+
+      const info = await provider.connection.getAccountInfo(noteBackupPda);
+      expect(info).to.be.null;
+    });
+  });
+
+  // ============================================================================
+  // 49. RFQ MARKET MAKER TESTS
+  // ============================================================================
+
+  describe("49. RFQ Market Makers", () => {
+    const maker = Keypair.generate();
+    let marketMakerPda: PublicKey;
+
+    before(() => {
+      [marketMakerPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("market_maker"), maker.publicKey.toBuffer()],
+        program.programId
+      );
+    });
+
+    it("49.1 Should let the admin register a market maker", async () => {
+      await program.methods
+        .registerMarketMaker(maker.publicKey, true)
+        .accounts({
+          admin: provider.wallet.publicKey,
+          roles: rolesPda,
+          marketMaker: marketMakerPda,
+          systemProgram: SystemProgram.programId,
+        } as Accounts)
+        .rpc();
+
+      const marketMaker = await program.account.marketMaker.fetch(marketMakerPda);
+      expect(marketMaker.maker.toBase58()).to.equal(maker.publicKey.toBase58());
+      expect(marketMaker.active).to.be.true;
+      expect(marketMaker.openQuotes).to.equal(0);
+    });
+
+    it("49.2 Should deactivate a market maker without resetting it", async () => {
+      await program.methods
+        .registerMarketMaker(maker.publicKey, false)
+        .accounts({
+          admin: provider.wallet.publicKey,
+          roles: rolesPda,
+          marketMaker: marketMakerPda,
+          systemProgram: SystemProgram.programId,
+        } as Accounts)
+        .rpc();
+
+      const marketMaker = await program.account.marketMaker.fetch(marketMakerPda);
+      expect(marketMaker.active).to.be.false;
+      expect(marketMaker.maker.toBase58()).to.equal(maker.publicKey.toBase58());
+    });
+
+    it("49.3 Should reject registrations from non-admins", async () => {
+      const [otherPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("market_maker"), user1.publicKey.toBuffer()],
+        program.programId
+      );
+      try {
+        await program.methods
+          .registerMarketMaker(user1.publicKey, true)
+          .accounts({
+            admin: user1.publicKey,
+            roles: rolesPda,
+            marketMaker: otherPda,
+            systemProgram: SystemProgram.programId,
+          } as Accounts)
+          .signers([user1])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (error: any) {
+        expect(error.message).to.include("Unauthorized");
+      }
+    });
+  });
+
+  // ============================================================================
+  // 50. SHIELDED TRANSFER TESTS
+  // ============================================================================
+
+  describe("50. Shielded Transfers", () => {
+    const TRANSFER_VERIFIER_PROGRAM_ID = new PublicKey(
+      "4fxK8aGf8D4k4qLRhSfkGARMx6WWJoHP9JaiGX4qKeke"
+    );
+
+    const transferAccounts = (nullifier: number[]) => ({
+      vault: nativeVaultPda,
+      merkleTree: nativeMerkleTreePda,
+      nullifierAccount: PublicKey.findProgramAddressSync(
+        [Buffer.from("nullifier"), nativeVaultPda.toBuffer(), Buffer.from(nullifier)],
+        program.programId
+      )[0],
+      vaultStats: PublicKey.findProgramAddressSync(
+        [Buffer.from("vault_stats"), nativeVaultPda.toBuffer()],
+        program.programId
+      )[0],
+      verifierProgram: TRANSFER_VERIFIER_PROGRAM_ID,
+      payer: provider.wallet.publicKey,
+      systemProgram: SystemProgram.programId,
+    });
+
+    it("50.1 Should reject a transfer with a zero recipient commitment", async () => {
+      const nullifier = generateRandomBytes32();
+      try {
+        await program.methods
+          .privateTransfer(
+            nullifier,
+            Array(32).fill(0),
+            generateRandomBytes32(),
+            Buffer.alloc(0),
+            Buffer.alloc(0),
+            Array(32).fill(0)
+          )
+          .accounts(transferAccounts(nullifier) as Accounts)
+          .rpc();
+        expect.fail("Should have thrown an error");
+      } catch (err: any) {
+        expect(err.toString()).to.satisfy(
+          (msg: string) =>
+            msg.includes("InvalidTransferCommitment") || msg.includes("verifier")
+        );
+      }
+    });
+
+    it("50.2 Should reject a transfer with an invalid proof", async () => {
+      const nullifier = generateRandomBytes32();
+      try {
+        await program.methods
+          .privateTransfer(
+            nullifier,
+            generateRandomBytes32(),
+            Array(32).fill(0),
+            Buffer.alloc(256),
+            Buffer.alloc(0),
+            Array(32).fill(0)
+          )
+          .accounts(transferAccounts(nullifier) as Accounts)
+          .rpc();
+        expect.fail("Should have thrown an error");
+      } catch (err: any) {
+        // Fails on the proof, or earlier if the verifier is not deployed locally
+        expect(err.toString()).to.not.include("Should have thrown");
+      }
+    });
+  });
+
+  // ============================================================================
+  // 51. MEMPOOL BACKPRESSURE TESTS
+  // ============================================================================
+
+  describe("51. Mempool Backpressure", () => {
+    // comp_def_offset("confidential_swap"): first 4 bytes of sha256, little endian
+    const confidentialSwapOffset = crypto
+      .createHash("sha256")
+      .update("confidential_swap")
+      .digest()
+      .readUInt32LE(0);
+    let mempoolLimitsPda: PublicKey;
+
+    before(() => {
+      [mempoolLimitsPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("mempool_limits")],
+        program.programId
+      );
+    });
+
+    it("51.1 Should let the admin set a mempool limit", async () => {
+      await program.methods
+        .setMempoolLimit(confidentialSwapOffset, 64, true)
+        .accounts({
+          admin: provider.wallet.publicKey,
+          roles: rolesPda,
+          mempoolLimits: mempoolLimitsPda,
+          systemProgram: SystemProgram.programId,
+        } as Accounts)
+        .rpc();
+
+      const limits = await program.account.mempoolLimits.fetch(mempoolLimitsPda);
+      const limit = limits.limits.find(
+        (l: any) => l.compDefOffset === confidentialSwapOffset
+      );
+      expect(limit.maxDepth).to.equal(64);
+      expect(limit.reject).to.be.true;
+    });
+
+    it("51.2 Should remove a limit with a zero max depth", async () => {
+      await program.methods
+        .setMempoolLimit(confidentialSwapOffset, 0, false)
+        .accounts({
+          admin: provider.wallet.publicKey,
+          roles: rolesPda,
+          mempoolLimits: mempoolLimitsPda,
+          systemProgram: SystemProgram.programId,
+        } as Accounts)
+        .rpc();
+
+      const limits = await program.account.mempoolLimits.fetch(mempoolLimitsPda);
+      expect(
+        limits.limits.some((l: any) => l.compDefOffset === confidentialSwapOffset)
+      ).to.be.false;
+    });
+
+    it("51.3 Should reject a mempool limit from a non-admin", async () => {
+      try {
+        await program.methods
+          .setMempoolLimit(confidentialSwapOffset, 8, true)
+          .accounts({
+            admin: user1.publicKey,
+            roles: rolesPda,
+            mempoolLimits: mempoolLimitsPda,
+            systemProgram: SystemProgram.programId,
+          } as Accounts)
+          .signers([user1])
+          .rpc();
+        expect.fail("Should have thrown an error");
+      } catch (err: any) {
+        expect(err.toString()).to.include("Unauthorized");
+      }
+    });
+  });
+
+  // ============================================================================
+  // 52. STEALTH META-ADDRESS TESTS
+  // ============================================================================
+
+  describe("52. Stealth Meta-Addresses", () => {
+    let stealthMetaPda: PublicKey;
+
+    before(() => {
+      [stealthMetaPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("stealth_meta"), user1.publicKey.toBuffer()],
+        program.programId
+      );
+    });
+
+    it("52.1 Should register a stealth meta-address", async () => {
+      const spendPubkey = generateRandomBytes32();
+      const viewPubkey = generateRandomBytes32();
+
+      await program.methods
+        .registerStealthMetaAddress(spendPubkey, viewPubkey)
+        .accounts({
+          owner: user1.publicKey,
+          stealthMetaAddress: stealthMetaPda,
+          systemProgram: SystemProgram.programId,
+        } as Accounts)
+        .signers([user1])
+        .rpc();
+
+      const meta = await program.account.stealthMetaAddress.fetch(stealthMetaPda);
+      expect(meta.owner.toBase58()).to.equal(user1.publicKey.toBase58());
+      expect(meta.spendPubkey).to.deep.equal(spendPubkey);
+      expect(meta.viewPubkey).to.deep.equal(viewPubkey);
+    });
+
+    it("52.2 Should reject a zero view key", async () => {
+      try {
+        await program.methods
+          .registerStealthMetaAddress(generateRandomBytes32(), Array(32).fill(0))
+          .accounts({
+            owner: user1.publicKey,
+            stealthMetaAddress: stealthMetaPda,
+            systemProgram: SystemProgram.programId,
+          } as Accounts)
+          .signers([user1])
+          .rpc();
+        expect.fail("Should have thrown an error");
+      } catch (err: any) {
+        expect(err.toString()).to.include("InvalidStealthMetaAddress");
+      }
+    });
+
+    it("52.3 Should close the stealth meta-address", async () => {
+      await program.methods
+        .closeStealthMetaAddress()
+        .accounts({
+          owner: user1.publicKey,
+          stealthMetaAddress: stealthMetaPda,
+        } as Accounts)
+        .signers([user1])
+        .rpc();
+
+      const info = await provider.connection.getAccountInfo(stealthMetaPda);
+      expect(info).to.be.null;
+    });
+  });
+
+  // ============================================================================
+  // 53. BATCH WITHDRAWAL TESTS
+  // ============================================================================
+
+  describe("53. Batch Withdrawals", () => {
+    const BATCH_WITHDRAW_VERIFIER_PROGRAM_ID = new PublicKey(
+      "2Xxnd8BJg3x5p7xgSX2gxGWveaJvSnmPpm4mXP2tdwF7"
+    );
+
+    const nullifierPda = (nullifier: number[]) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from("nullifier"), nativeVaultPda.toBuffer(), Buffer.from(nullifier)],
+        program.programId
+      )[0];
+
+    const batchAccounts = () => ({
+      recipient: user1.publicKey,
+      vault: nativeVaultPda,
+      merkleTree: nativeMerkleTreePda,
+      vaultTreasury: nativeVaultTreasuryPda,
+      treasuryPolicy: null,
+      coldTreasury: null,
+      vaultTokenAccount: null,
+      recipientTokenAccount: null,
+      relayerTokenAccount: null,
+      verifierProgram: BATCH_WITHDRAW_VERIFIER_PROGRAM_ID,
+      payer: provider.wallet.publicKey,
+      tokenProgram: null,
+      systemProgram: SystemProgram.programId,
+    });
+
+    // BN254 generators in the alt_bn128 encoding (big-endian, G2 coordinates
+    // imaginary part first) and the point at infinity
+    const G1_GENERATOR = Array.from(
+      Buffer.from(
+        "0000000000000000000000000000000000000000000000000000000000000001" +
+          "0000000000000000000000000000000000000000000000000000000000000002",
+        "hex"
+      )
+    );
+    const G2_GENERATOR = Array.from(
+      Buffer.from(
+        "198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c2" +
+          "1800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed" +
+          "090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b" +
+          "12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa",
+        "hex"
+      )
+    );
+    const G1_IDENTITY = Array(64).fill(0);
+
+    const updateAccounts = (admin: PublicKey) => ({
+      admin,
+      roles: rolesPda,
+      protocolConfig: protocolConfigPda,
+    });
+
+    const setBatchVerifier = async (verifier: PublicKey) => {
+      await program.methods
+        .proposeVerifierChange({ batchWithdraw: {} }, verifier)
+        .accounts(updateAccounts(provider.wallet.publicKey) as Accounts)
+        .rpc();
+      await program.methods
+        .executeVerifierChange()
+        .accounts(updateAccounts(provider.wallet.publicKey) as Accounts)
+        .rpc();
+    };
+
+    const setProofSystem = async (proofSystem: any) => {
+      const [pendingChange] = PublicKey.findProgramAddressSync(
+        [Buffer.from("pending_config"), nativeVaultPda.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .proposeConfigChange({ proofSystem: { 0: proofSystem } })
+        .accounts({
+          authority: provider.wallet.publicKey,
+          vault: nativeVaultPda,
+          pendingChange,
+          systemProgram: SystemProgram.programId,
+        } as Accounts)
+        .rpc();
+      await program.methods
+        .executeConfigChange()
+        .accounts({
+          authority: provider.wallet.publicKey,
+          vault: nativeVaultPda,
+          pendingChange,
+          exclusionRoot: null,
+          treasuryPolicy: null,
+          merkleTree: null,
+          systemProgram: SystemProgram.programId,
+        } as Accounts)
+        .rpc();
+    };
+
+    it("53.1 Should reject a batch of more than four nullifiers", async () => {
+      const nullifiers = Array.from({ length: 5 }, () => generateRandomBytes32());
+      try {
+        await program.methods
+          .withdrawBatch(
+            new BN(LAMPORTS_PER_SOL),
+            nullifiers,
+            Array(32).fill(0),
+            generateMockProof(),
+            mockExtData()
+          )
+          .accounts(batchAccounts() as Accounts)
+          .remainingAccounts(
+            nullifiers.map((n) => ({
+              pubkey: nullifierPda(n),
+              isWritable: true,
+              isSigner: false,
+            }))
+          )
+          .rpc();
+        expect.fail("Should have thrown an error");
+      } catch (err: any) {
+        expect(err.toString()).to.satisfy(
+          (msg: string) =>
+            msg.includes("InvalidBatchWithdrawal") || msg.includes("verifier")
+        );
+      }
+    });
+
+    it("53.2 Should reject a batch missing its nullifier accounts", async () => {
+      const nullifiers = [generateRandomBytes32(), generateRandomBytes32()];
+      try {
+        await program.methods
+          .withdrawBatch(
+            new BN(LAMPORTS_PER_SOL),
+            nullifiers,
+            Array(32).fill(0),
+            generateMockProof(),
+            mockExtData()
+          )
+          .accounts(batchAccounts() as Accounts)
+          .rpc();
+        expect.fail("Should have thrown an error");
+      } catch (err: any) {
+        expect(err.toString()).to.satisfy(
+          (msg: string) =>
+            msg.includes("InvalidBatchWithdrawal") || msg.includes("verifier")
+        );
+      }
+    });
+
+    it("53.3 Should pay a native batch out of the vault treasury", async () => {
+      // No Noir verifier runs on localnet, so verify Groth16 against a key
+      // this proof satisfies for any public inputs: with identity IC points
+      // vk_x is the identity, and A = alpha, B = beta, C = identity leaves
+      // e(-alpha, beta) · e(alpha, beta) == 1. The batch circuit has ten
+      // public inputs.
+      const [verificationKey] = PublicKey.findProgramAddressSync(
+        [Buffer.from("verification_key"), nativeVaultPda.toBuffer(), Buffer.from([2])],
+        program.programId
+      );
+      await program.methods
+        .setVerificationKey(
+          { batchWithdraw: {} },
+          G1_GENERATOR,
+          G2_GENERATOR,
+          G2_GENERATOR,
+          G2_GENERATOR,
+          Array(11).fill(G1_IDENTITY)
+        )
+        .accounts({
+          authority: provider.wallet.publicKey,
+          vault: nativeVaultPda,
+          verificationKey,
+          systemProgram: SystemProgram.programId,
+        } as Accounts)
+        .rpc();
+      const proof = Buffer.from([...G1_GENERATOR, ...G2_GENERATOR, ...G1_IDENTITY]);
+
+      // Under Groth16 the verifier program is only checked to be executable
+      await setBatchVerifier(program.programId);
+      await setProofSystem({ groth16: {} });
+
+      const amount = new BN(0.1 * LAMPORTS_PER_SOL);
+      const nullifiers = [generateRandomBytes32(), generateRandomBytes32()];
+      const recipientBefore = await provider.connection.getBalance(user1.publicKey);
+      const treasuryBefore = await provider.connection.getBalance(nativeVaultTreasuryPda);
+
+      try {
+        await program.methods
+          .withdrawBatch(
+            amount,
+            nullifiers,
+            Array(32).fill(0),
+            proof,
+            boundExtData(user1.publicKey, provider.wallet.publicKey)
+          )
+          .accounts({
+            ...batchAccounts(),
+            verifierProgram: program.programId,
+            verificationKey,
+          } as Accounts)
+          .remainingAccounts(
+            nullifiers.map((n) => ({
+              pubkey: nullifierPda(n),
+              isWritable: true,
+              isSigner: false,
+            }))
+          )
+          .rpc();
+      } finally {
+        await setProofSystem({ ultraHonk: {} });
+        await setBatchVerifier(BATCH_WITHDRAW_VERIFIER_PROGRAM_ID);
+      }
+
+      // The fee stays in the treasury; everything else reached the recipient
+      const received =
+        (await provider.connection.getBalance(user1.publicKey)) - recipientBefore;
+      const paid =
+        treasuryBefore - (await provider.connection.getBalance(nativeVaultTreasuryPda));
+      expect(received).to.be.greaterThan(0);
+      expect(received).to.be.at.most(amount.toNumber());
+      expect(paid).to.equal(received);
+
+      for (const nullifier of nullifiers) {
+        const state = await program.account.nullifierState.fetch(nullifierPda(nullifier));
+        expect(state.spent).to.be.true;
+      }
+    });
+  });
+
+  // ============================================================================
+  // 54. PROOF SYSTEM TESTS
+  // ============================================================================
+
+  describe("54. Proof Systems", () => {
+    let verificationKeyPda: PublicKey;
+    const g1 = () => Array.from(crypto.randomBytes(64));
+    const g2 = () => Array.from(crypto.randomBytes(128));
+
+    before(() => {
+      [verificationKeyPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("verification_key"), nativeVaultPda.toBuffer(), Buffer.from([0])],
+        program.programId
+      );
+    });
+
+    it("54.1 New vaults verify UltraHonk proofs", async () => {
+      const vault = await program.account.vaultState.fetch(nativeVaultPda);
+      expect(vault.proofSystem).to.deep.equal({ ultraHonk: {} });
+    });
+
+    it("54.2 Should upload a Groth16 verification key for the mixer circuit", async () => {
+      const ic = [g1(), g1(), g1(), g1(), g1(), g1()];
+
+      await program.methods
+        .setVerificationKey({ mixer: {} }, g1(), g2(), g2(), g2(), ic)
+        .accounts({
+          authority: provider.wallet.publicKey,
+          vault: nativeVaultPda,
+          verificationKey: verificationKeyPda,
+          systemProgram: SystemProgram.programId,
+        } as Accounts)
+        .rpc();
+
+      const vk = await program.account.verificationKey.fetch(verificationKeyPda);
+      expect(vk.vault.toBase58()).to.equal(nativeVaultPda.toBase58());
+      expect(vk.circuit).to.deep.equal({ mixer: {} });
+      expect(vk.ic.length).to.equal(6);
+    });
+
+    it("54.3 Should reject a key without public inputs", async () => {
+      try {
+        await program.methods
+          .setVerificationKey({ mixer: {} }, g1(), g2(), g2(), g2(), [g1()])
+          .accounts({
+            authority: provider.wallet.publicKey,
+            vault: nativeVaultPda,
+            verificationKey: verificationKeyPda,
+            systemProgram: SystemProgram.programId,
+          } as Accounts)
+          .rpc();
+        expect.fail("Should have thrown an error");
+      } catch (err: any) {
+        expect(err.toString()).to.include("VerificationKeyMismatch");
+      }
+    });
+
+    it("54.4 Should reject a key upload from a non-authority", async () => {
+      try {
+        await program.methods
+          .setVerificationKey({ mixer: {} }, g1(), g2(), g2(), g2(), [g1(), g1()])
+          .accounts({
+            authority: user1.publicKey,
+            vault: nativeVaultPda,
+            verificationKey: verificationKeyPda,
+            systemProgram: SystemProgram.programId,
+          } as Accounts)
+          .signers([user1])
+          .rpc();
+        expect.fail("Should have thrown an error");
+      } catch (err: any) {
+        expect(err.toString()).to.include("Unauthorized");
+      }
+    });
+  });
+
+  // ============================================================================
+  // 55. INSERTION ORDER TESTS
+  // ============================================================================
+
+  describe("55. Insertion Order", () => {
+    const depositIx = (precommitment: number[], merkleTree: PublicKey) =>
+      program.methods
+        .depositNative(
+          new BN(0.01 * LAMPORTS_PER_SOL),
+          precommitment,
+          Buffer.alloc(0),
+          Buffer.alloc(0),
+          false
+        )
+        .accounts({
+          depositor: provider.wallet.publicKey,
+          vault: nativeVaultPda,
+          merkleTree,
+          vaultTreasury: nativeVaultTreasuryPda,
+          systemProgram: SystemProgram.programId,
+        } as Accounts)
+        .instruction();
+
+    it("55.1 Should assign consecutive leaf indexes in instruction order", async () => {
+      const vault = await program.account.vaultState.fetch(nativeVaultPda);
+      const before = await program.account.merkleTreeState.fetch(vault.merkleTree);
+      const precommitments = [generateRandomBytes32(), generateRandomBytes32(), generateRandomBytes32()];
+
+      const tx = new Transaction();
+      for (const precommitment of precommitments) {
+        tx.add(await depositIx(precommitment, vault.merkleTree));
+      }
+      const signature = await provider.sendAndConfirm(tx, [], { commitment: "confirmed" });
+
+      const confirmed = await provider.connection.getTransaction(signature, {
+        commitment: "confirmed",
+        maxSupportedTransactionVersion: 0,
+      });
+      const parser = new anchor.EventParser(program.programId, new anchor.BorshCoder(program.idl));
+      const logged = Array.from(parser.parseLogs(confirmed!.meta!.logMessages!));
+      const events = logged
+        .filter((event) => event.name === "depositedEvent")
+        .map((event) => event.data);
+      const inserted = logged
+        .filter((event) => event.name === "leafInserted")
+        .map((event) => event.data);
+      const deposited = events.map((event) => Buffer.from(event.commitment as number[]));
+      expect(deposited.length).to.equal(precommitments.length);
+
+      // Leaf size + i holds the i-th commitment, as the leaf log records
+      const after = await program.account.merkleTreeState.fetch(vault.merkleTree);
+      const start = before.size.toNumber();
+      expect(after.size.toNumber()).to.equal(start + precommitments.length);
+      expect(inserted.length).to.equal(precommitments.length);
+      deposited.forEach((commitment, i) => {
+        expect(inserted[i].leafIndex.toNumber()).to.equal(start + i);
+        expect(Buffer.from(inserted[i].commitment as number[]).equals(commitment)).to.be.true;
+      });
+
+      // Each event carries its leaf index and the root after its insertion
+      events.forEach((event, i) => {
+        expect(event.leafIndex.toNumber()).to.equal(start + i);
+      });
+      const lastRoot = Buffer.from(events[events.length - 1].newRoot as number[]);
+      expect(lastRoot.equals(Buffer.from(after.root))).to.be.true;
+
+      // Return data is the last deposit's receipt
+      const data = Buffer.from(confirmed!.meta!.returnData!.data[0], "base64");
+      expect(Number(data.readBigUInt64LE(0))).to.equal(start + precommitments.length - 1);
+      expect(data.subarray(8, 40).equals(Buffer.from(after.root))).to.be.true;
+    });
+
+    it("55.2 Should keep the tree account size fixed as the tree grows", async () => {
+      const vault = await program.account.vaultState.fetch(nativeVaultPda);
+      const before = await provider.connection.getAccountInfo(vault.merkleTree);
+
+      const tx = new Transaction()
+        .add(await depositIx(generateRandomBytes32(), vault.merkleTree))
+        .add(await depositIx(generateRandomBytes32(), vault.merkleTree));
+      await provider.sendAndConfirm(tx, [], { commitment: "confirmed" });
+
+      const after = await provider.connection.getAccountInfo(vault.merkleTree);
+      expect(after!.data.length).to.equal(before!.data.length);
+    });
+  });
+
+  // ============================================================================
+  // 56. VERIFIER PINNING TESTS
+  // ============================================================================
+
+  describe("56. Verifier Pinning", () => {
+    const updateAccounts = (admin: PublicKey) => ({
+      admin,
+      roles: rolesPda,
+      protocolConfig: protocolConfigPda,
+    });
+
+    it("56.1 Should pin each circuit's verifier at init", async () => {
+      const config = await program.account.protocolConfig.fetch(protocolConfigPda);
+      expect(config.mixerVerifier.toBase58()).to.equal(NOIR_VERIFIER_PROGRAM_ID.toBase58());
+      expect(config.pendingVerifier.toBase58()).to.equal(PublicKey.default.toBase58());
+    });
+
+    it("56.2 Should reject a proof checked by an unpinned verifier program", async () => {
+      try {
+        await program.methods
+          .verifyProof(new BN(0.1 * LAMPORTS_PER_SOL), generateRandomBytes32(), user1.publicKey, generateRandomBytes32(), generateRandomBytes32(), new BN(0), generateMockProof())
+          .accounts({
+            vault: nativeVaultPda,
+            merkleTree: nativeMerkleTreePda,
+            verifierProgram: SystemProgram.programId,
+          } as Accounts)
+          .view();
+        expect.fail("Should have thrown an error");
+      } catch (err: any) {
+        expect(err.toString()).to.include("InvalidVerifierProgram");
+      }
+    });
+
+    it("56.3 Should reject verifier changes from non-admins", async () => {
+      try {
+        await program.methods
+          .proposeVerifierChange({ mixer: {} }, SystemProgram.programId)
+          .accounts(updateAccounts(user1.publicKey) as Accounts)
+          .signers([user1])
+          .rpc();
+        expect.fail("Should have thrown an error");
+      } catch (err: any) {
+        expect(err.toString()).to.include("Unauthorized");
+      }
+    });
+
+    it("56.4 Should cancel a pending verifier change", async () => {
+      await program.methods
+        .proposeVerifierChange({ transfer: {} }, SystemProgram.programId)
+        .accounts(updateAccounts(provider.wallet.publicKey) as Accounts)
+        .rpc();
+
+      let config = await program.account.protocolConfig.fetch(protocolConfigPda);
+      expect(config.pendingCircuit).to.deep.equal({ transfer: {} });
+
+      await program.methods
+        .cancelVerifierChange()
+        .accounts(updateAccounts(provider.wallet.publicKey) as Accounts)
+        .rpc();
+
+      config = await program.account.protocolConfig.fetch(protocolConfigPda);
+      expect(config.pendingVerifier.toBase58()).to.equal(PublicKey.default.toBase58());
+
+      try {
+        await program.methods
+          .executeVerifierChange()
+          .accounts(updateAccounts(provider.wallet.publicKey) as Accounts)
+          .rpc();
+        expect.fail("Should have thrown an error");
+      } catch (err: any) {
+        expect(err.toString()).to.include("NoPendingVerifierChange");
+      }
+    });
+
+    it("56.5 Should apply a verifier change once the timelock has elapsed", async () => {
+      const batchVerifier = new PublicKey("2Xxnd8BJg3x5p7xgSX2gxGWveaJvSnmPpm4mXP2tdwF7");
+
+      // Re-pin the current deployment; the test config has no timelock
+      await program.methods
+        .proposeVerifierChange({ batchWithdraw: {} }, batchVerifier)
+        .accounts(updateAccounts(provider.wallet.publicKey) as Accounts)
+        .rpc();
+      await program.methods
+        .executeVerifierChange()
+        .accounts(updateAccounts(provider.wallet.publicKey) as Accounts)
+        .rpc();
+
+      const config = await program.account.protocolConfig.fetch(protocolConfigPda);
+      expect(config.batchWithdrawVerifier.toBase58()).to.equal(batchVerifier.toBase58());
+      expect(config.pendingEta.toNumber()).to.equal(0);
+    });
+  });
+
+  // ============================================================================
+  // 57. OPERATION VALUE CEILING TESTS
+  // ============================================================================
+
+  describe("57. Operation Value Ceiling", () => {
+    const riskSigner = Keypair.generate();
+    const ceiling = new BN(0.05 * LAMPORTS_PER_SOL);
+
+    const updateAccounts = (admin: PublicKey) => ({
+      admin,
+      roles: rolesPda,
+      protocolConfig: protocolConfigPda,
+    });
+
+    const withdrawAbove = (signer: Keypair | null) => {
+      const nullifier = generateRandomBytes32();
+      const [nullifierPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("nullifier"), nativeVaultPda.toBuffer(), Buffer.from(nullifier)],
+        program.programId
+      );
+      const builder = program.methods
+        .withdrawNative(ceiling.muln(2), nullifier, generateRandomBytes32(), new BN(1), generateMockProof(), mockExtData(), Buffer.alloc(0))
+        .accounts({
+          recipient: user2.publicKey,
+          vault: nativeVaultPda,
+          merkleTree: nativeMerkleTreePda,
+          verifierProgram: NOIR_VERIFIER_PROGRAM_ID,
+          vaultTreasury: nativeVaultTreasuryPda,
+          nullifierAccount: nullifierPda,
+          riskSigner: signer ? signer.publicKey : null,
+          payer: provider.wallet.publicKey,
+          systemProgram: SystemProgram.programId,
+        } as Accounts);
+      return signer ? builder.signers([signer]).rpc() : builder.rpc();
+    };
+
+    it("57.1 Should reject ceiling changes from non-admins", async () => {
+      try {
+        await program.methods
+          .setOperationValueLimit(ceiling, riskSigner.publicKey)
+          .accounts(updateAccounts(user1.publicKey) as Accounts)
+          .signers([user1])
+          .rpc();
+        expect.fail("Should have thrown an error");
+      } catch (err: any) {
+        expect(err.toString()).to.include("Unauthorized");
+      }
+    });
+
+    it("57.2 Should set the ceiling and risk signer", async () => {
+      // Introducing a ceiling tightens it, so it goes through the param queue
+      const { nextParamChangeId } = await program.account.protocolConfig.fetch(protocolConfigPda);
+      await program.methods
+        .proposeParamChange({ operationValueLimit: [ceiling] })
+        .accounts(updateAccounts(provider.wallet.publicKey) as Accounts)
+        .rpc();
+      await program.methods
+        .executeParamChange(nextParamChangeId)
+        .accounts({ ...updateAccounts(provider.wallet.publicKey), arciumConfig: null } as Accounts)
+        .rpc();
+
+      await program.methods
+        .setOperationValueLimit(ceiling, riskSigner.publicKey)
+        .accounts(updateAccounts(provider.wallet.publicKey) as Accounts)
+        .rpc();
+
+      const config = await program.account.protocolConfig.fetch(protocolConfigPda);
+      expect(config.maxOperationValue.toString()).to.equal(ceiling.toString());
+      expect(config.riskSigner.toBase58()).to.equal(riskSigner.publicKey.toBase58());
+    });
+
+    it("57.3 Should reject a withdrawal above the ceiling without an attestation", async () => {
+      try {
+        await withdrawAbove(null);
+        expect.fail("Should have thrown an error");
+      } catch (err: any) {
+        expect(err.toString()).to.include("OperationValueTooLarge");
+      }
+    });
+
+    it("57.4 Should reject an attestation from another signer", async () => {
+      try {
+        await withdrawAbove(user1);
+        expect.fail("Should have thrown an error");
+      } catch (err: any) {
+        expect(err.toString()).to.include("OperationValueTooLarge");
+      }
+    });
+
+    it("57.5 Should let the risk signer lift the ceiling", async () => {
+      try {
+        await withdrawAbove(riskSigner);
+      } catch (err: any) {
+        // The mock proof may still fail verification, but not the ceiling
+        expect(err.toString()).to.not.include("OperationValueTooLarge");
+      }
+
+      // Remove the ceiling for the rest of the suite
+      await program.methods
+        .setOperationValueLimit(new BN(0), PublicKey.default)
+        .accounts(updateAccounts(provider.wallet.publicKey) as Accounts)
+        .rpc();
+    });
+  });
+
+  // ============================================================================
+  // 58. POSEIDON TREE TESTS
+  // ============================================================================
+
+  describe("58. Poseidon Trees", () => {
+    const mint = Keypair.generate().publicKey;
+    let vault: PublicKey;
+    let merkleTree: PublicKey;
+
+    const switchTreeHash = async (vaultPda: PublicKey, tree: PublicKey) => {
+      const [pendingChange] = PublicKey.findProgramAddressSync(
+        [Buffer.from("pending_config"), vaultPda.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .proposeConfigChange({ treeHash: { 0: { poseidon: {} } } })
+        .accounts({
+          authority: provider.wallet.publicKey,
+          vault: vaultPda,
+          pendingChange,
+          systemProgram: SystemProgram.programId,
+        } as Accounts)
+        .rpc();
+
+      try {
+        await program.methods
+          .executeConfigChange()
+          .accounts({
+            authority: provider.wallet.publicKey,
+            vault: vaultPda,
+            pendingChange,
+            exclusionRoot: null,
+            treasuryPolicy: null,
+            merkleTree: tree,
+            systemProgram: SystemProgram.programId,
+          } as Accounts)
+          .rpc();
+      } catch (err) {
+        await program.methods
+          .cancelConfigChange()
+          .accounts({
+            authority: provider.wallet.publicKey,
+            vault: vaultPda,
+            pendingChange,
+          } as Accounts)
+          .rpc();
+        throw err;
+      }
+    };
+
+    before(async () => {
+      [vault] = PublicKey.findProgramAddressSync(
+        [Buffer.from("vault"), mint.toBuffer()],
+        program.programId
+      );
+      [merkleTree] = PublicKey.findProgramAddressSync(
+        [Buffer.from("merkle_tree"), vault.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initializeVault(mint)
+        .accounts({
+          authority: provider.wallet.publicKey,
+          roles: rolesPda,
+          vault,
+          merkleTree,
+          systemProgram: SystemProgram.programId,
+        } as Accounts)
+        .rpc();
+    });
+
+    it("58.1 New vaults build keccak trees", async () => {
+      const vaultAccount = await program.account.vaultState.fetch(vault);
+      const tree = await program.account.merkleTreeState.fetch(merkleTree);
+      expect(vaultAccount.treeHash).to.deep.equal({ keccak: {} });
+      expect(tree.treeHash).to.deep.equal({ keccak: {} });
+    });
+
+    it("58.2 Should switch an empty tree to Poseidon", async () => {
+      await switchTreeHash(vault, merkleTree);
+
+      const vaultAccount = await program.account.vaultState.fetch(vault);
+      const tree = await program.account.merkleTreeState.fetch(merkleTree);
+      expect(vaultAccount.treeHash).to.deep.equal({ poseidon: {} });
+      expect(tree.treeHash).to.deep.equal({ poseidon: {} });
+    });
+
+    it("58.3 Should only switch the vault's active tree", async () => {
+      try {
+        await switchTreeHash(vault, nativeMerkleTreePda);
+        expect.fail("Should have thrown an error");
+      } catch (err: any) {
+        expect(err.toString()).to.include("InactiveMerkleTree");
+      }
+
+      const nativeTree = await program.account.merkleTreeState.fetch(nativeMerkleTreePda);
+      expect(nativeTree.treeHash).to.deep.equal({ keccak: {} });
+    });
+  });
+
+  // ============================================================================
+  // 59. MERKLE INCLUSION TESTS
+  // ============================================================================
+
+  describe("59. Merkle Inclusion", () => {
+    const checkInclusion = async (leaf: number[], path: number[][], indices: number[]) => {
+      const vault = await program.account.vaultState.fetch(nativeVaultPda);
+      return program.methods
+        .verifyMerkleInclusion(leaf, path, Buffer.from(indices))
+        .accounts({
+          merkleTree: vault.merkleTree,
+          vault: nativeVaultPda,
+        } as Accounts)
+        .view();
+    };
+
+    it("59.1 Should reject a path whose indices do not line up", async () => {
+      try {
+        await checkInclusion(generateRandomBytes32(), [generateRandomBytes32()], [0, 1]);
+        expect.fail("Should have thrown an error");
+      } catch (err: any) {
+        expect(err.toString()).to.include("InvalidMerkleProof");
+      }
+    });
+
+    it("59.2 Should reject non-binary path indices", async () => {
+      try {
+        await checkInclusion(generateRandomBytes32(), [generateRandomBytes32()], [2]);
+        expect.fail("Should have thrown an error");
+      } catch (err: any) {
+        expect(err.toString()).to.include("InvalidMerkleProof");
+      }
+    });
+
+    it("59.3 Should not include a leaf that was never inserted", async () => {
+      const vault = await program.account.vaultState.fetch(nativeVaultPda);
+      const tree = await program.account.merkleTreeState.fetch(vault.merkleTree);
+      const path = Array.from({ length: Math.max(tree.depth, 1) }, () => generateRandomBytes32());
+
+      const included = await checkInclusion(generateRandomBytes32(), path, path.map(() => 0));
+      expect(included).to.be.false;
+    });
+  });
+
+  // ============================================================================
+  // 60. RELAYER REGISTRY TESTS
+  // ============================================================================
+
+  describe("60. Relayer Registry", () => {
+    let relayerRegistrationPda: PublicKey;
+    const endpointHash = generateRandomBytes32();
+
+    before(() => {
+      [relayerRegistrationPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("relayer"), user1.publicKey.toBuffer()],
+        program.programId
+      );
+    });
+
+    it("60.1 Should register a relayer with its fee schedule", async () => {
+      await program.methods
+        .registerRelayer(endpointHash, [nativeVaultPda], new BN(5_000), 25)
+        .accounts({
+          relayer: user1.publicKey,
+        } as Accounts)
+        .signers([user1])
+        .rpc();
+
+      const registration = await program.account.relayerRegistration.fetch(relayerRegistrationPda);
+      expect(registration.relayer.toBase58()).to.equal(user1.publicKey.toBase58());
+      expect(registration.endpointHash).to.deep.equal(endpointHash);
+      expect(registration.vaultCount).to.equal(1);
+      expect(registration.vaults[0].toBase58()).to.equal(nativeVaultPda.toBase58());
+      expect(registration.baseFee.toNumber()).to.equal(5_000);
+      expect(registration.feeBps).to.equal(25);
+    });
+
+    it("60.2 Should let the relayer update its fee schedule", async () => {
+      await program.methods
+        .registerRelayer(endpointHash, [nativeVaultPda], new BN(0), 50)
+        .accounts({
+          relayer: user1.publicKey,
+        } as Accounts)
+        .signers([user1])
+        .rpc();
+
+      const registration = await program.account.relayerRegistration.fetch(relayerRegistrationPda);
+      expect(registration.baseFee.toNumber()).to.equal(0);
+      expect(registration.feeBps).to.equal(50);
+    });
+
+    it("60.3 Should reject a registration without vaults", async () => {
+      try {
+        await program.methods
+          .registerRelayer(endpointHash, [], new BN(0), 50)
+          .accounts({
+            relayer: user1.publicKey,
+          } as Accounts)
+          .signers([user1])
+          .rpc();
+        expect.fail("Should have thrown an error");
+      } catch (err: any) {
+        expect(err.toString()).to.include("InvalidRelayerRegistration");
+      }
+    });
+
+    it("60.4 Should reject a fee of 100% or more", async () => {
+      try {
+        await program.methods
+          .registerRelayer(endpointHash, [nativeVaultPda], new BN(0), 10_000)
+          .accounts({
+            relayer: user1.publicKey,
+          } as Accounts)
+          .signers([user1])
+          .rpc();
+        expect.fail("Should have thrown an error");
+      } catch (err: any) {
+        expect(err.toString()).to.include("InvalidRelayerRegistration");
+      }
+    });
+
+    it("60.5 Should close the registration", async () => {
+      await program.methods
+        .closeRelayerRegistration()
+        .accounts({
+          relayer: user1.publicKey,
+        } as Accounts)
+        .signers([user1])
+        .rpc();
+
+      const info = await provider.connection.getAccountInfo(relayerRegistrationPda);
+      expect(info).to.be.null;
+    });
+  });
+
+  // ============================================================================
+  // 61. PARAMETER CHANGE QUEUE TESTS
+  // ============================================================================
+
+  describe("61. Parameter Change Queue", () => {
+    const ceiling = new BN(LAMPORTS_PER_SOL);
+
+    const updateAccounts = (admin: PublicKey) => ({
+      admin,
+      roles: rolesPda,
+      protocolConfig: protocolConfigPda,
+    });
+
+    it("61.1 Should reject tightening the ceiling without the queue", async () => {
+      try {
+        await program.methods
+          .setOperationValueLimit(ceiling, PublicKey.default)
+          .accounts(updateAccounts(provider.wallet.publicKey) as Accounts)
+          .rpc();
+        expect.fail("Should have thrown an error");
+      } catch (err: any) {
+        expect(err.toString()).to.include("ParamChangeRequiresTimelock");
+      }
+    });
+
+    it("61.2 Should reject proposals from non-admins", async () => {
+      try {
+        await program.methods
+          .proposeParamChange({ operationValueLimit: [ceiling] })
+          .accounts(updateAccounts(user1.publicKey) as Accounts)
+          .signers([user1])
+          .rpc();
+        expect.fail("Should have thrown an error");
+      } catch (err: any) {
+        expect(err.toString()).to.include("Unauthorized");
+      }
+    });
+
+    it("61.3 Should announce a queued change and let it be cancelled", async () => {
+      const before = await program.account.protocolConfig.fetch(protocolConfigPda);
+      const id = before.nextParamChangeId;
+
+      await program.methods
+        .proposeParamChange({ computationFee: [new BN(10_000)] })
+        .accounts(updateAccounts(provider.wallet.publicKey) as Accounts)
+        .rpc();
+
+      let config = await program.account.protocolConfig.fetch(protocolConfigPda);
+      const pending = config.pendingParams.find((p: any) => p.id.eq(id));
+      expect(pending).to.not.be.undefined;
+      expect(pending.eta.toNumber()).to.equal(
+        pending.proposedAt.toNumber() + config.timelockSeconds.toNumber()
+      );
+
+      await program.methods
+        .cancelParamChange(id)
+        .accounts(updateAccounts(provider.wallet.publicKey) as Accounts)
+        .rpc();
+
+      config = await program.account.protocolConfig.fetch(protocolConfigPda);
+      expect(config.pendingParams.some((p: any) => p.id.eq(id))).to.be.false;
+    });
+
+    it("61.4 Should reject executing an unknown change", async () => {
+      try {
+        await program.methods
+          .executeParamChange(new BN(1_000_000))
+          .accounts({ ...updateAccounts(provider.wallet.publicKey), arciumConfig: null } as Accounts)
+          .rpc();
+        expect.fail("Should have thrown an error");
+      } catch (err: any) {
+        expect(err.toString()).to.include("ParamChangeNotFound");
+      }
+    });
+  });
+
+  // ============================================================================
+  // 62. SWAP REFERRAL TESTS
+  // ============================================================================
+
+  describe("62. Swap Referrals", () => {
+    let referralAccountPda: PublicKey;
+
+    const updateAccounts = (admin: PublicKey) => ({
+      admin,
+      roles: rolesPda,
+      protocolConfig: protocolConfigPda,
+    });
+
+    before(() => {
+      [referralAccountPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("swap_referral"), user1.publicKey.toBuffer(), nativeVaultPda.toBuffer()],
+        program.programId
+      );
+    });
+
+    it("62.1 Should reject a swap fee above the ceiling", async () => {
+      try {
+        await program.methods
+          .proposeParamChange({ swapFee: [1_001] })
+          .accounts(updateAccounts(provider.wallet.publicKey) as Accounts)
+          .rpc();
+        expect.fail("Should have thrown an error");
+      } catch (err: any) {
+        expect(err.toString()).to.include("InvalidFeeConfig");
+      }
+    });
+
+    it("62.2 Should reject a referral share above 100%", async () => {
+      try {
+        await program.methods
+          .proposeParamChange({ referralShare: [10_001] })
+          .accounts(updateAccounts(provider.wallet.publicKey) as Accounts)
+          .rpc();
+        expect.fail("Should have thrown an error");
+      } catch (err: any) {
+        expect(err.toString()).to.include("InvalidFeeConfig");
+      }
+    });
+
+    it("62.3 Should not claim for a referrer with no rewards account", async () => {
+      try {
+        await program.methods
+          .claimSwapReferralFees()
+          .accounts({
+            referrer: user1.publicKey,
+            referralAccount: referralAccountPda,
+            vault: nativeVaultPda,
+            vaultTokenAccount: null,
+            referrerTokenAccount: null,
+            tokenProgram: null,
+            systemProgram: SystemProgram.programId,
+          } as Accounts)
+          .signers([user1])
+          .rpc();
+        expect.fail("Should have thrown an error");
+      } catch (err: any) {
+        expect(err.toString()).to.include("AccountNotInitialized");
+      }
+    });
+  });
+
+  // ============================================================================
+  // 63. CHANGE NOTE VALUE TESTS
+  // ============================================================================
+
+  describe("63. Change Note Value", () => {
+    const withdraw = (newCommitment: number[], remainingAmount: BN) => {
+      const nullifier = generateRandomBytes32();
+      const [nullifierPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("nullifier"), nativeVaultPda.toBuffer(), Buffer.from(nullifier)],
+        program.programId
+      );
+
+      return program.methods
+        .withdrawNative(
+          new BN(0.1 * LAMPORTS_PER_SOL),
+          nullifier,
+          newCommitment,
+          remainingAmount,
+          generateMockProof(),
+          mockExtData(),
+          Buffer.alloc(0)
+        )
+        .accounts({
+          recipient: user2.publicKey,
+          vault: nativeVaultPda,
+          merkleTree: nativeMerkleTreePda,
+          verifierProgram: NOIR_VERIFIER_PROGRAM_ID,
+          vaultTreasury: nativeVaultTreasuryPda,
+          nullifierAccount: nullifierPda,
+          payer: provider.wallet.publicKey,
+          systemProgram: SystemProgram.programId,
+        } as Accounts)
+        .rpc();
+    };
+
+    it("63.1 Should reject a remaining amount without a change note", async () => {
+      try {
+        await withdraw(Array(32).fill(0), new BN(1));
+        expect.fail("Should have thrown an error");
+      } catch (err: any) {
+        expect(err.toString()).to.include("RemainingAmountMismatch");
+      }
+    });
+
+    it("63.2 Should reject a change note with nothing remaining", async () => {
+      try {
+        await withdraw(generateRandomBytes32(), new BN(0));
+        expect.fail("Should have thrown an error");
+      } catch (err: any) {
+        expect(err.toString()).to.include("RemainingAmountMismatch");
+      }
+    });
+
+    it("63.3 Should reject a note worth more than the vault took in", async () => {
+      const vault = await program.account.vaultState.fetch(nativeVaultPda);
+      try {
+        await withdraw(generateRandomBytes32(), vault.totalDeposited);
+        expect.fail("Should have thrown an error");
+      } catch (err: any) {
+        expect(err.toString()).to.include("NoteValueExceedsDeposits");
+      }
+    });
+  });
+
+  // ============================================================================
+  // 64. WITHDRAWAL SIMULATION TESTS
+  // ============================================================================
+
+  describe("64. Withdrawal Simulation", () => {
+    const simulate = (amount: BN) => {
+      const nullifier = generateRandomBytes32();
+      const [nullifierPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("nullifier"), nativeVaultPda.toBuffer(), Buffer.from(nullifier)],
+        program.programId
+      );
+
+      return program.methods
+        .simulateWithdraw(
+          nullifier,
+          generateRandomBytes32(),
+          amount,
+          Array(32).fill(0),
+          new BN(0),
+          generateMockProof(),
+          mockExtData()
+        )
+        .accounts({
+          recipient: user2.publicKey,
+          vault: nativeVaultPda,
+          merkleTree: nativeMerkleTreePda,
+          vaultTreasury: nativeVaultTreasuryPda,
+          vaultTokenAccount: null,
+          nullifierAccount: nullifierPda,
+          vaultFees: null,
+          allowancePolicy: null,
+          verifierProgram: NOIR_VERIFIER_PROGRAM_ID,
+          verificationKey: null,
+          payer: provider.wallet.publicKey,
+          relayerRegistration: null,
+        } as Accounts)
+        .view();
+    };
+
+    it("64.1 Should reject a zero amount like the withdrawal", async () => {
+      try {
+        await simulate(new BN(0));
+        expect.fail("Should have thrown an error");
+      } catch (err: any) {
+        expect(err.toString()).to.include("InvalidWithdrawalAmount");
+      }
+    });
+
+    it("64.2 Should reject ext data the proof was not bound to", async () => {
+      try {
+        await simulate(new BN(0.1 * LAMPORTS_PER_SOL));
+        expect.fail("Should have thrown an error");
+      } catch (err: any) {
+        expect(err.toString()).to.include("ExtDataMismatch");
+      }
+    });
+  });
+
+  // ============================================================================
+  // 65. ACCOUNT MIGRATION TESTS
+  // ============================================================================
+
+  describe("65. Account Migration", () => {
+    const migrate = (account: PublicKey, kind: object) =>
+      program.methods
+        .migrateAccount(kind as any)
+        .accounts({
+          account,
+          payer: provider.wallet.publicKey,
+          systemProgram: SystemProgram.programId,
+        } as Accounts)
+        .rpc();
+
+    it("65.1 Should create vaults and trees at the current version", async () => {
+      const vault = await program.account.vaultState.fetch(nativeVaultPda);
+      const tree = await program.account.merkleTreeState.fetch(nativeMerkleTreePda);
+      expect(vault.version).to.equal(1);
+      expect(tree.version).to.equal(1);
+    });
+
+    it("65.2 Should reject migrating an up-to-date account", async () => {
+      try {
+        await migrate(nativeVaultPda, { vault: {} });
+        expect.fail("Should have thrown an error");
+      } catch (err: any) {
+        expect(err.toString()).to.include("AccountUpToDate");
+      }
+    });
+
+    it("65.3 Should reject an account of another kind", async () => {
+      try {
+        await migrate(nativeVaultPda, { merkleTree: {} });
+        expect.fail("Should have thrown an error");
+      } catch (err: any) {
+        expect(err.toString()).to.include("AccountDiscriminatorMismatch");
+      }
+    });
+  });
+
   // ============================================================================
   // TEST SUMMARY
   // ============================================================================